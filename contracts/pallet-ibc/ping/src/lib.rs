@@ -268,14 +268,14 @@ impl<T: Config + Send + Sync> Module for IbcModule<T> {
 		_output: &mut ModuleOutputBuilder,
 		packet: &mut Packet,
 		_relayer: &Signer,
-	) -> Result<Acknowledgement, Ics04Error> {
+	) -> Result<Option<Acknowledgement>, Ics04Error> {
 		let success = "ping-success".as_bytes().to_vec();
 		let data = String::from_utf8(packet.data.clone()).ok();
 		log::info!("Received Packet Sequence {:?}, Packet Data {:?}", packet.sequence, data);
 		let packet = packet.clone();
 		T::IbcHandler::handle_message(HandlerMessage::WriteAck { packet, ack: success.clone() })
 			.map_err(|e| Ics04Error::implementation_specific(format!("{e:?}")))?;
-		Ok(success.into())
+		Ok(Some(success.into()))
 	}
 
 	fn on_acknowledgement_packet(
@@ -217,15 +217,94 @@ pub struct QueryChannelResponse {
 	pub trie_key: Vec<u8>,
 }
 
+#[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
+pub struct QueryChannelUpgradeResponse {
+	/// Protobuf encoded `ibc::core::ics04_channel::upgrade::Upgrade`
+	pub upgrade: Vec<u8>,
+	pub height: u64,
+	pub trie_key: Vec<u8>,
+}
+
+#[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
+pub struct QueryChannelUpgradeErrorResponse {
+	/// Protobuf encoded `ibc::core::ics04_channel::upgrade::ErrorReceipt`
+	pub error_receipt: Vec<u8>,
+	pub height: u64,
+	pub trie_key: Vec<u8>,
+}
+
+/// Maximum number of items returned in a page of a paginated query.
+pub const MAX_PAGE_LIMIT: u64 = 1000;
+
+/// Pagination of the queries returning many items, mirroring the cosmos `PageRequest`.
+#[derive(
+	Clone,
+	Default,
+	RuntimeDebug,
+	codec::Encode,
+	codec::Decode,
+	PartialEq,
+	Eq,
+	Ord,
+	PartialOrd,
+	TypeInfo,
+)]
+pub struct PageRequest {
+	/// Storage key of the first item of the page, as returned in the `next_key` of the previous
+	/// page. Takes precedence over `offset` when not empty.
+	pub key: Vec<u8>,
+	/// Number of items to skip when `key` is empty
+	pub offset: u64,
+	/// Maximum number of items in the page, capped to [`MAX_PAGE_LIMIT`]. Zero means
+	/// [`MAX_PAGE_LIMIT`]
+	pub limit: u64,
+	/// Whether to count the total number of items, only when `key` is empty
+	pub count_total: bool,
+	/// Whether to return the items in descending key order
+	pub reverse: bool,
+}
+
+/// Pagination of the response of a paginated query, mirroring the cosmos `PageResponse`.
+#[derive(
+	Clone,
+	Default,
+	RuntimeDebug,
+	codec::Encode,
+	codec::Decode,
+	PartialEq,
+	Eq,
+	Ord,
+	PartialOrd,
+	TypeInfo,
+)]
+pub struct PageResponse {
+	/// Storage key of the first item of the next page, empty on the last page
+	pub next_key: Vec<u8>,
+	/// Total number of items, if counted
+	pub total: u64,
+}
+
+#[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
+pub struct QueryClientStatesResponse {
+	pub client_states: Vec<IdentifiedClientState>,
+	/// Set when the query was paginated
+	pub pagination: Option<PageResponse>,
+	pub height: u64,
+}
+
 #[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
 pub struct QueryChannelsResponse {
 	pub channels: Vec<IdentifiedChannel>,
+	/// Set when the query was paginated
+	pub pagination: Option<PageResponse>,
 	pub height: u64,
 }
 
 #[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
 pub struct QueryConnectionsResponse {
 	pub connections: Vec<IdentifiedConnection>,
+	/// Set when the query was paginated
+	pub pagination: Option<PageResponse>,
 	pub height: u64,
 }
 
@@ -254,6 +333,8 @@ pub struct PacketState {
 #[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
 pub struct QueryPacketCommitmentsResponse {
 	pub commitments: Vec<PacketState>,
+	/// Set when the query was paginated
+	pub pagination: Option<PageResponse>,
 	pub height: u64,
 }
 
@@ -267,6 +348,8 @@ pub struct QueryPacketAcknowledgementResponse {
 #[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
 pub struct QueryPacketAcknowledgementsResponse {
 	pub acks: Vec<PacketState>,
+	/// Set when the query was paginated
+	pub pagination: Option<PageResponse>,
 	pub height: u64,
 }
 
@@ -277,14 +360,30 @@ pub struct QueryPacketReceiptResponse {
 	pub trie_key: Vec<u8>,
 }
 
+/// Metadata of an IBC asset, displayed by wallets and explorers.
+#[derive(
+	Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo, RuntimeDebug,
+)]
+pub struct AssetMetadata {
+	/// Name of the asset, represented as utf8 string bytes
+	pub name: Vec<u8>,
+	/// Symbol of the asset, represented as utf8 string bytes
+	pub symbol: Vec<u8>,
+	/// Number of decimals of the amounts of the asset
+	pub decimals: u8,
+}
+
 #[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
 pub struct QueryDenomTraceResponse {
 	pub denom: Vec<u8>,
+	pub metadata: Option<AssetMetadata>,
 }
 
 #[derive(Clone, codec::Encode, codec::Decode, PartialEq, Eq, Ord, PartialOrd, TypeInfo)]
 pub struct QueryDenomTracesResponse {
 	pub denoms: Vec<Vec<u8>>,
+	/// Metadata of the assets of `denoms`, in the same order
+	pub metadata: Vec<Option<AssetMetadata>>,
 	pub next_key: Option<Vec<u8>>,
 	pub total: Option<u64>,
 }
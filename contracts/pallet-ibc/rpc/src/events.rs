@@ -1,9 +1,14 @@
 //! Relayer events.
-use ibc::{core::ics04_channel::packet::Packet, events::IbcEvent as RawIbcEvent};
+use ibc::{
+	core::ics04_channel::packet::Packet,
+	events::{IbcEvent as RawIbcEvent, IbcEventType},
+};
 use ibc_runtime_api::IbcRuntimeApi;
 use pallet_ibc::events::IbcEvent;
 use sc_client_api::HeaderBackend;
+use serde::{Deserialize, Serialize};
 use sp_api::{ApiRef, BlockT, ProvideRuntimeApi};
+use std::str::FromStr;
 
 /// Filter out none relayer events and modify
 /// Fetch actual packet and acknowledgements from off chain storage and modify packets
@@ -135,3 +140,170 @@ where
 		_ => Some(event),
 	}
 }
+
+/// Filter of the events streamed to a subscriber. Each non empty list only lets through the events
+/// carrying one of its identifiers, on either side of the connection, channel or packet.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventFilter {
+	/// Client identifiers
+	pub client_ids: Vec<String>,
+	/// Connection identifiers
+	pub connection_ids: Vec<String>,
+	/// Channel identifiers
+	pub channel_ids: Vec<String>,
+	/// Event types, e.g. `send_packet` or `update_client`
+	pub event_types: Vec<String>,
+}
+
+impl EventFilter {
+	/// Parses the event types of the filter, returning the first unknown one as an error.
+	pub fn event_types(&self) -> Result<Vec<IbcEventType>, String> {
+		self.event_types
+			.iter()
+			.map(|event_type| IbcEventType::from_str(event_type).map_err(|_| event_type.clone()))
+			.collect()
+	}
+
+	/// Returns true if the identifiers carried by the event pass the filter.
+	pub fn matches_ids(&self, ev: &IbcEvent) -> bool {
+		let (clients, connections, channels) = event_ids(ev);
+		matches_any(&self.client_ids, &clients) &&
+			matches_any(&self.connection_ids, &connections) &&
+			matches_any(&self.channel_ids, &channels)
+	}
+}
+
+fn matches_any(filter: &[String], ids: &[&[u8]]) -> bool {
+	filter.is_empty() || ids.iter().any(|id| filter.iter().any(|f| f.as_bytes() == *id))
+}
+
+type Ids<'a> = Vec<&'a [u8]>;
+
+/// Returns the client, connection and channel identifiers carried by the event.
+fn event_ids(ev: &IbcEvent) -> (Ids, Ids, Ids) {
+	match ev {
+		IbcEvent::CreateClient { client_id, .. } |
+		IbcEvent::UpdateClient { client_id, .. } |
+		IbcEvent::UpgradeClient { client_id, .. } |
+		IbcEvent::ClientMisbehaviour { client_id, .. } => (vec![client_id.as_slice()], vec![], vec![]),
+		IbcEvent::OpenInitConnection {
+			connection_id,
+			client_id,
+			counterparty_connection_id,
+			counterparty_client_id,
+			..
+		} |
+		IbcEvent::OpenTryConnection {
+			connection_id,
+			client_id,
+			counterparty_connection_id,
+			counterparty_client_id,
+			..
+		} |
+		IbcEvent::OpenAckConnection {
+			connection_id,
+			client_id,
+			counterparty_connection_id,
+			counterparty_client_id,
+			..
+		} |
+		IbcEvent::OpenConfirmConnection {
+			connection_id,
+			client_id,
+			counterparty_connection_id,
+			counterparty_client_id,
+			..
+		} => (
+			vec![client_id.as_slice(), counterparty_client_id.as_slice()],
+			connection_id
+				.iter()
+				.chain(counterparty_connection_id)
+				.map(Vec::as_slice)
+				.collect(),
+			vec![],
+		),
+		IbcEvent::OpenInitChannel {
+			channel_id, connection_id, counterparty_channel_id, ..
+		} |
+		IbcEvent::OpenTryChannel { channel_id, connection_id, counterparty_channel_id, .. } |
+		IbcEvent::OpenAckChannel { channel_id, connection_id, counterparty_channel_id, .. } |
+		IbcEvent::OpenConfirmChannel {
+			channel_id, connection_id, counterparty_channel_id, ..
+		} |
+		IbcEvent::CloseConfirmChannel {
+			channel_id,
+			connection_id,
+			counterparty_channel_id,
+			..
+		} => (
+			vec![],
+			vec![connection_id.as_slice()],
+			channel_id.iter().chain(counterparty_channel_id).map(Vec::as_slice).collect(),
+		),
+		IbcEvent::CloseInitChannel {
+			channel_id, connection_id, counterparty_channel_id, ..
+		} => (
+			vec![],
+			vec![connection_id.as_slice()],
+			core::iter::once(channel_id)
+				.chain(counterparty_channel_id)
+				.map(Vec::as_slice)
+				.collect(),
+		),
+		IbcEvent::UpgradeInitChannel { channel_id, counterparty_channel_id, .. } |
+		IbcEvent::UpgradeTryChannel { channel_id, counterparty_channel_id, .. } |
+		IbcEvent::UpgradeAckChannel { channel_id, counterparty_channel_id, .. } |
+		IbcEvent::UpgradeConfirmChannel { channel_id, counterparty_channel_id, .. } |
+		IbcEvent::UpgradeOpenChannel { channel_id, counterparty_channel_id, .. } |
+		IbcEvent::UpgradeTimeoutChannel { channel_id, counterparty_channel_id, .. } |
+		IbcEvent::UpgradeCancelChannel { channel_id, counterparty_channel_id, .. } |
+		IbcEvent::UpgradeErrorChannel { channel_id, counterparty_channel_id, .. } |
+		IbcEvent::FlushCompleteChannel { channel_id, counterparty_channel_id, .. } => (
+			vec![],
+			vec![],
+			core::iter::once(channel_id)
+				.chain(counterparty_channel_id)
+				.map(Vec::as_slice)
+				.collect(),
+		),
+		IbcEvent::SendPacket { channel_id, dest_channel, .. } |
+		IbcEvent::ReceivePacket { channel_id, dest_channel, .. } |
+		IbcEvent::WriteAcknowledgement { channel_id, dest_channel, .. } =>
+			(vec![], vec![], vec![channel_id.as_slice(), dest_channel.as_slice()]),
+		IbcEvent::AcknowledgePacket { channel_id, .. } |
+		IbcEvent::TimeoutPacket { channel_id, .. } |
+		IbcEvent::TimeoutOnClosePacket { channel_id, .. } =>
+			(vec![], vec![], vec![channel_id.as_slice()]),
+		IbcEvent::NewBlock { .. } |
+		IbcEvent::Empty |
+		IbcEvent::ChainError |
+		IbcEvent::AppModule { .. } |
+		IbcEvent::PushWasmCode { .. } => (vec![], vec![], vec![]),
+	}
+}
+
+/// Reads the relayer events deposited in the block that pass the filter.
+pub fn filtered_block_events<C, Block, AssetId>(
+	client: &C,
+	at: <Block as BlockT>::Hash,
+	filter: &EventFilter,
+	event_types: &[IbcEventType],
+) -> Result<Vec<RawIbcEvent>, String>
+where
+	C: Send + Sync + 'static + ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: IbcRuntimeApi<Block, AssetId>,
+	Block: BlockT,
+	AssetId: codec::Codec,
+{
+	let api = client.runtime_api();
+	let events = api.block_events(at, None).map_err(|e| e.to_string())?;
+	Ok(events
+		.into_iter()
+		.filter_map(|event| {
+			let event = event.ok().filter(|event| filter.matches_ids(event))?;
+			filter_map_pallet_event::<C, Block, AssetId>(at, &api, event)
+		})
+		.filter(|event| event_types.is_empty() || event_types.contains(&event.event_type()))
+		.collect())
+}
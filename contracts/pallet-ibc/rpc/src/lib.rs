@@ -3,6 +3,7 @@
 //! IBC RPC Implementation.
 
 use codec::Encode;
+use futures::{FutureExt, StreamExt};
 use ibc::{
 	core::{
 		ics03_connection::connection::ConnectionEnd,
@@ -13,18 +14,22 @@ use ibc::{
 };
 use ibc_primitives::PacketInfo as RawPacketInfo;
 use ibc_proto::{
-	cosmos::base::{query::v1beta1::PageResponse, v1beta1::Coin},
+	cosmos::base::{
+		query::v1beta1::{PageRequest, PageResponse},
+		v1beta1::Coin,
+	},
 	ibc::{
-		applications::transfer::v1::{QueryDenomTraceResponse, QueryDenomTracesResponse},
+		applications::transfer::v1::DenomTrace,
 		core::{
 			channel::v1::{
 				PacketState, QueryChannelResponse, QueryChannelsResponse,
 				QueryNextSequenceReceiveResponse, QueryPacketAcknowledgementResponse,
 				QueryPacketAcknowledgementsResponse, QueryPacketCommitmentResponse,
 				QueryPacketCommitmentsResponse, QueryPacketReceiptResponse,
+				QueryUpgradeErrorResponse, QueryUpgradeResponse,
 			},
 			client::v1::{
-				Height, IdentifiedClientState, QueryClientStateResponse,
+				Height, IdentifiedClientState, QueryClientStateResponse, QueryClientStatesResponse,
 				QueryConsensusStateResponse,
 			},
 			connection::v1::{
@@ -38,18 +43,19 @@ use jsonrpsee::{
 	core::{Error as RpcError, RpcResult as Result},
 	proc_macros::rpc,
 	tracing::log,
-	types::{error::CallError, ErrorObject},
+	types::{error::CallError, ErrorObject, SubscriptionResult},
+	SubscriptionSink,
 };
 use pallet_ibc::{
 	events::IbcEvent,
 	light_clients::{AnyClientState, AnyConsensusState},
 };
 use sc_chain_spec::Properties;
-use sc_client_api::{BlockBackend, ProofProvider};
+use sc_client_api::{BlockBackend, BlockchainEvents, ProofProvider};
 use serde::{Deserialize, Serialize};
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::HeaderBackend;
-use sp_core::{blake2_256, storage::ChildInfo};
+use sp_core::{blake2_256, storage::ChildInfo, traits::SpawnNamed};
 use sp_runtime::{
 	generic::{BlockId, SignedBlock},
 	traits::{Block as BlockT, Header as HeaderT},
@@ -57,7 +63,8 @@ use sp_runtime::{
 use std::{collections::HashMap, fmt::Display, str::FromStr, sync::Arc};
 use tendermint_proto::Protobuf;
 pub mod events;
-use events::filter_map_pallet_event;
+pub use events::EventFilter;
+use events::{filter_map_pallet_event, filtered_block_events};
 use ibc_proto::ibc::core::channel::v1::IdentifiedChannel;
 use pallet_ibc::errors::IbcError;
 
@@ -109,6 +116,49 @@ pub struct Proof {
 	pub height: ibc_proto::ibc::core::client::v1::Height,
 }
 
+/// Metadata of an IBC asset
+#[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub struct AssetMetadata {
+	/// Name of the asset
+	pub name: String,
+	/// Symbol of the asset
+	pub symbol: String,
+	/// Number of decimals of the amounts of the asset
+	pub decimals: u8,
+}
+
+impl From<ibc_primitives::AssetMetadata> for AssetMetadata {
+	fn from(metadata: ibc_primitives::AssetMetadata) -> Self {
+		Self {
+			name: String::from_utf8_lossy(&metadata.name).into_owned(),
+			symbol: String::from_utf8_lossy(&metadata.symbol).into_owned(),
+			decimals: metadata.decimals,
+		}
+	}
+}
+
+/// Denom trace of an IBC asset, the `QueryDenomTraceResponse` of ibc-go along with the metadata
+/// of the asset
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct QueryDenomTraceResponse {
+	/// Denom trace of the asset
+	pub denom_trace: Option<DenomTrace>,
+	/// Metadata of the asset, if registered
+	pub metadata: Option<AssetMetadata>,
+}
+
+/// Denom traces of IBC assets, the `QueryDenomTracesResponse` of ibc-go along with the metadata
+/// of the assets
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct QueryDenomTracesResponse {
+	/// Denom traces of the assets
+	pub denom_traces: Vec<DenomTrace>,
+	/// Metadata of the assets of `denom_traces`, in the same order
+	pub metadata: Vec<Option<AssetMetadata>>,
+	/// Pagination of the response
+	pub pagination: Option<PageResponse>,
+}
+
 /// Packet info
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug, PartialOrd, Ord)]
 pub struct PacketInfo {
@@ -160,6 +210,34 @@ impl TryFrom<RawPacketInfo> for PacketInfo {
 	}
 }
 
+/// Relayer events deposited in a finalized block
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FinalizedBlockEvents<BlockNumber, Hash> {
+	/// Block hash
+	pub block_hash: Hash,
+	/// Block number
+	pub block_number: BlockNumber,
+	/// Events that passed the filter of the subscription
+	pub events: Vec<RawIbcEvent>,
+}
+
+/// Outcome of a message dry-run
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimulatedMessage {
+	/// Events emitted by the message
+	pub events: Vec<RawIbcEvent>,
+	/// Error the message failed with
+	pub error: Option<String>,
+	/// Reference time of the weight charged for the message, from the benchmarks
+	pub benchmarked_ref_time: u64,
+	/// Proof size of the weight charged for the message, from the benchmarks
+	pub benchmarked_proof_size: u64,
+}
+
+/// Number of finalized blocks a subscription to the events may lag behind the chain before it
+/// gets closed.
+pub const MAX_LAGGING_BLOCKS: u32 = 256;
+
 /// IBC RPC methods.
 #[rpc(client, server)]
 pub trait IbcApi<BlockNumber, Hash, AssetId>
@@ -238,9 +316,9 @@ where
 	#[method(name = "ibc_queryUpgradedConnectionState")]
 	fn query_upgraded_cons_state(&self, height: u32) -> Result<QueryConsensusStateResponse>;
 
-	/// Query all client states
+	/// Query all client states, or a page of them
 	#[method(name = "ibc_queryClients")]
-	fn query_clients(&self) -> Result<Vec<IdentifiedClientState>>;
+	fn query_clients(&self, pagination: Option<PageRequest>) -> Result<QueryClientStatesResponse>;
 
 	/// Query a connection state
 	#[method(name = "ibc_queryConnection")]
@@ -250,9 +328,12 @@ where
 		connection_id: String,
 	) -> Result<QueryConnectionResponse>;
 
-	/// Query all connection states
+	/// Query all connection states, or a page of them
 	#[method(name = "ibc_queryConnections")]
-	fn query_connections(&self) -> Result<QueryConnectionsResponse>;
+	fn query_connections(
+		&self,
+		pagination: Option<PageRequest>,
+	) -> Result<QueryConnectionsResponse>;
 
 	/// Query all connection states for associated client
 	#[method(name = "ibc_queryConnectionUsingClient")]
@@ -280,6 +361,24 @@ where
 		port_id: String,
 	) -> Result<QueryChannelResponse>;
 
+	/// Query the pending upgrade of a channel
+	#[method(name = "ibc_queryChannelUpgrade")]
+	fn query_channel_upgrade(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<QueryUpgradeResponse>;
+
+	/// Query the last upgrade error receipt of a channel
+	#[method(name = "ibc_queryChannelUpgradeError")]
+	fn query_channel_upgrade_error(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<QueryUpgradeErrorResponse>;
+
 	/// Query client state for channel and port id
 	#[method(name = "ibc_queryChannelClient")]
 	fn query_channel_client(
@@ -297,26 +396,28 @@ where
 		connection_id: String,
 	) -> Result<QueryChannelsResponse>;
 
-	/// Query all channel states
+	/// Query all channel states, or a page of them
 	#[method(name = "ibc_queryChannels")]
-	fn query_channels(&self) -> Result<QueryChannelsResponse>;
+	fn query_channels(&self, pagination: Option<PageRequest>) -> Result<QueryChannelsResponse>;
 
-	/// Query packet commitments
+	/// Query packet commitments, or a page of them
 	#[method(name = "ibc_queryPacketCommitments")]
 	fn query_packet_commitments(
 		&self,
 		height: u32,
 		channel_id: String,
 		port_id: String,
+		pagination: Option<PageRequest>,
 	) -> Result<QueryPacketCommitmentsResponse>;
 
-	/// Query packet acknowledgements
+	/// Query packet acknowledgements, or a page of them
 	#[method(name = "ibc_queryPacketAcknowledgements")]
 	fn query_packet_acknowledgements(
 		&self,
 		height: u32,
 		channel_id: String,
 		port_id: String,
+		pagination: Option<PageRequest>,
 	) -> Result<QueryPacketAcknowledgementsResponse>;
 
 	/// Given a list of counterparty packet commitments, the querier checks if the packet
@@ -449,6 +550,49 @@ where
 		&self,
 		block_numbers: Vec<BlockNumberOrHash<Hash>>,
 	) -> Result<HashMap<String, Vec<RawIbcEvent>>>;
+
+	/// Dry-run the delivery of the messages, as done by the `deliver` extrinsic, on top of the
+	/// state of the given block or of the best block, without persisting anything. Returns the
+	/// outcome of every message, in order.
+	#[method(name = "ibc_simulateDeliver")]
+	fn simulate_deliver(
+		&self,
+		messages: Vec<ibc_proto::google::protobuf::Any>,
+		at: Option<Hash>,
+	) -> Result<Vec<SimulatedMessage>>;
+
+	/// Subscribe to the Ibc Events deposited in the finalized blocks that pass the filter.
+	/// Blocks without any of these events are skipped. The subscription is closed with an error
+	/// when the subscriber falls more than [`MAX_LAGGING_BLOCKS`] blocks behind the finalized
+	/// chain, it should then resume from the last block it received with `ibc_queryEvents`.
+	#[subscription(
+		name = "ibc_subscribeEvents" => "ibc_events",
+		unsubscribe = "ibc_unsubscribeEvents",
+		item = FinalizedBlockEvents<BlockNumber, Hash>
+	)]
+	fn subscribe_events(&self, filter: Option<EventFilter>);
+}
+
+fn into_page_request(page: PageRequest) -> ibc_primitives::PageRequest {
+	let PageRequest { key, offset, limit, count_total, reverse } = page;
+	ibc_primitives::PageRequest { key, offset, limit, count_total, reverse }
+}
+
+fn into_page_response(page: ibc_primitives::PageResponse) -> PageResponse {
+	let ibc_primitives::PageResponse { next_key, total } = page;
+	PageResponse { next_key, total }
+}
+
+fn ibc_error_message(error: IbcError) -> String {
+	let (kind, message) = match error {
+		IbcError::Ics02Client { message } => ("client", message),
+		IbcError::Ics03Connection { message } => ("connection", message),
+		IbcError::Ics04Channel { message } => ("channel", message),
+		IbcError::Ics20FungibleTokenTransfer { message } => ("transfer", message),
+		IbcError::UnknownMessageTypeUrl { message } => ("unknown message type", message),
+		IbcError::MalformedMessageBytes { message } => ("malformed message", message),
+	};
+	format!("{kind} error: {}", String::from_utf8_lossy(&message))
 }
 
 /// Converts a runtime trap into an RPC error.
@@ -465,13 +609,16 @@ pub struct IbcRpcHandler<C, B, AssetId> {
 	client: Arc<C>,
 	/// A copy of the chain properties.
 	pub chain_props: Properties,
+	/// Executor of the subscription tasks.
+	executor: Arc<dyn SpawnNamed>,
 	_marker: std::marker::PhantomData<(B, AssetId)>,
 }
 
 impl<C, B, AssetId> IbcRpcHandler<C, B, AssetId> {
-	/// Create new `IbcRpcHandler` with the given reference to the client.
-	pub fn new(client: Arc<C>, chain_props: Properties) -> Self {
-		Self { client, chain_props, _marker: Default::default() }
+	/// Create new `IbcRpcHandler` with the given reference to the client and the executor of the
+	/// subscription tasks.
+	pub fn new(client: Arc<C>, chain_props: Properties, executor: Arc<dyn SpawnNamed>) -> Self {
+		Self { client, chain_props, executor, _marker: Default::default() }
 	}
 }
 
@@ -486,7 +633,8 @@ where
 		+ ProvideRuntimeApi<Block>
 		+ HeaderBackend<Block>
 		+ ProofProvider<Block>
-		+ BlockBackend<Block>,
+		+ BlockBackend<Block>
+		+ BlockchainEvents<Block>,
 	C::Api: IbcRuntimeApi<Block, AssetId>,
 	AssetId: codec::Codec + Copy + Send + Sync + 'static,
 {
@@ -804,28 +952,31 @@ where
 		Err(runtime_error_into_rpc_error("Unimplemented"))
 	}
 
-	fn query_clients(&self) -> Result<Vec<IdentifiedClientState>> {
+	fn query_clients(&self, pagination: Option<PageRequest>) -> Result<QueryClientStatesResponse> {
 		let api = self.client.runtime_api();
 
-		let client_states: Option<Vec<(Vec<u8>, Vec<u8>)>> =
-			api.clients(self.client.info().best_hash).ok().flatten();
-		match client_states {
-			Some(client_states) => client_states
-				.into_iter()
-				.map(|(client_id, client_state)| {
-					let client_state = AnyClientState::decode_vec(&client_state).map_err(|_| {
-						runtime_error_into_rpc_error("Failed to decode client state")
-					})?;
-					Ok(IdentifiedClientState {
-						client_id: String::from_utf8(client_id).map_err(|_| {
-							runtime_error_into_rpc_error("Failed to decode client id")
-						})?,
-						client_state: Some(client_state.into()),
-					})
+		let result: ibc_primitives::QueryClientStatesResponse = api
+			.clients(self.client.info().best_hash, pagination.map(into_page_request))
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch client states"))?;
+		let client_states = result
+			.client_states
+			.into_iter()
+			.map(|identified_client| {
+				let client_state = AnyClientState::decode_vec(&identified_client.client_state)
+					.map_err(|_| runtime_error_into_rpc_error("Failed to decode client state"))?;
+				Ok(IdentifiedClientState {
+					client_id: String::from_utf8(identified_client.client_id)
+						.map_err(|_| runtime_error_into_rpc_error("Failed to decode client id"))?,
+					client_state: Some(client_state.into()),
 				})
-				.collect(),
-			_ => Err(runtime_error_into_rpc_error("Failed to fetch client states")),
-		}
+			})
+			.collect::<Result<Vec<_>>>()?;
+		Ok(QueryClientStatesResponse {
+			client_states,
+			pagination: result.pagination.map(into_page_response),
+		})
 	}
 
 	fn query_connection(
@@ -874,7 +1025,10 @@ where
 		})
 	}
 
-	fn query_connections(&self) -> Result<QueryConnectionsResponse> {
+	fn query_connections(
+		&self,
+		pagination: Option<PageRequest>,
+	) -> Result<QueryConnectionsResponse> {
 		let api = self.client.runtime_api();
 
 		let at = BlockId::Hash(self.client.info().best_hash);
@@ -884,7 +1038,7 @@ where
 			.map_err(|_| RpcError::Custom("Unknown block".into()))?
 			.ok_or_else(|| RpcError::Custom("Unknown block".into()))?;
 		let result: ibc_primitives::QueryConnectionsResponse = api
-			.connections(hash_at)
+			.connections(hash_at, pagination.map(into_page_request))
 			.ok()
 			.flatten()
 			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch connections"))?;
@@ -914,7 +1068,7 @@ where
 			.collect::<Result<Vec<_>>>()?;
 		Ok(QueryConnectionsResponse {
 			connections,
-			pagination: None,
+			pagination: result.pagination.map(into_page_response),
 			height: Some(ibc_proto::ibc::core::client::v1::Height {
 				revision_number: para_id.into(),
 				revision_height: result.height,
@@ -1063,6 +1217,105 @@ where
 		})
 	}
 
+	fn query_channel_upgrade(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<QueryUpgradeResponse> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		let hash_at = self
+			.client
+			.block_hash_from_id(&at)
+			.map_err(|_| RpcError::Custom("Unknown block".into()))?
+			.ok_or_else(|| RpcError::Custom("Unknown block".into()))?;
+		let para_id = api
+			.para_id(hash_at)
+			.map_err(|_| runtime_error_into_rpc_error("Error getting para id"))?;
+		let result: ibc_primitives::QueryChannelUpgradeResponse = api
+			.channel_upgrade(hash_at, channel_id.as_bytes().to_vec(), port_id.as_bytes().to_vec())
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch channel upgrade"))?;
+		let upgrade = ibc::core::ics04_channel::upgrade::Upgrade::decode_vec(&result.upgrade)
+			.map_err(|_| runtime_error_into_rpc_error("Failed to decode channel upgrade"))?;
+		let mut keys = vec![result.trie_key];
+		let child_trie_key = api
+			.child_trie_key(hash_at)
+			.map_err(|_| runtime_error_into_rpc_error("Failed to get child trie key"))?;
+		let child_info = ChildInfo::new_default(&child_trie_key);
+		let proof = self
+			.client
+			.read_child_proof(hash_at, &child_info, &mut keys.iter_mut().map(|nodes| &nodes[..]))
+			.map_err(runtime_error_into_rpc_error)?
+			.iter_nodes()
+			.collect::<Vec<_>>()
+			.encode();
+		Ok(QueryUpgradeResponse {
+			upgrade: Some(upgrade.into()),
+			proof,
+			proof_height: Some(ibc_proto::ibc::core::client::v1::Height {
+				revision_number: para_id.into(),
+				revision_height: result.height,
+			}),
+		})
+	}
+
+	fn query_channel_upgrade_error(
+		&self,
+		height: u32,
+		channel_id: String,
+		port_id: String,
+	) -> Result<QueryUpgradeErrorResponse> {
+		let api = self.client.runtime_api();
+
+		let at = BlockId::Number(height.into());
+		let hash_at = self
+			.client
+			.block_hash_from_id(&at)
+			.map_err(|_| RpcError::Custom("Unknown block".into()))?
+			.ok_or_else(|| RpcError::Custom("Unknown block".into()))?;
+		let para_id = api
+			.para_id(hash_at)
+			.map_err(|_| runtime_error_into_rpc_error("Error getting para id"))?;
+		let result: ibc_primitives::QueryChannelUpgradeErrorResponse = api
+			.channel_upgrade_error(
+				hash_at,
+				channel_id.as_bytes().to_vec(),
+				port_id.as_bytes().to_vec(),
+			)
+			.ok()
+			.flatten()
+			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch upgrade error receipt"))?;
+		let error_receipt =
+			ibc::core::ics04_channel::upgrade::ErrorReceipt::decode_vec(&result.error_receipt)
+				.map_err(|_| {
+					runtime_error_into_rpc_error("Failed to decode upgrade error receipt")
+				})?;
+		let mut keys = vec![result.trie_key];
+		let child_trie_key = api
+			.child_trie_key(hash_at)
+			.map_err(|_| runtime_error_into_rpc_error("Failed to get child trie key"))?;
+		let child_info = ChildInfo::new_default(&child_trie_key);
+		let proof = self
+			.client
+			.read_child_proof(hash_at, &child_info, &mut keys.iter_mut().map(|nodes| &nodes[..]))
+			.map_err(runtime_error_into_rpc_error)?
+			.iter_nodes()
+			.collect::<Vec<_>>()
+			.encode();
+		Ok(QueryUpgradeErrorResponse {
+			error_receipt: Some(error_receipt.into()),
+			proof,
+			proof_height: Some(ibc_proto::ibc::core::client::v1::Height {
+				revision_number: para_id.into(),
+				revision_height: result.height,
+			}),
+		})
+	}
+
 	fn query_channel_client(
 		&self,
 		height: u32,
@@ -1149,7 +1402,7 @@ where
 		})
 	}
 
-	fn query_channels(&self) -> Result<QueryChannelsResponse> {
+	fn query_channels(&self, pagination: Option<PageRequest>) -> Result<QueryChannelsResponse> {
 		let api = self.client.runtime_api();
 		let at = BlockId::Hash(self.client.info().best_hash);
 		let hash_at = self
@@ -1161,7 +1414,7 @@ where
 			.para_id(hash_at)
 			.map_err(|_| runtime_error_into_rpc_error("Error getting para id"))?;
 		let result: ibc_primitives::QueryChannelsResponse = api
-			.channels(hash_at)
+			.channels(hash_at, pagination.map(into_page_request))
 			.ok()
 			.flatten()
 			.ok_or_else(|| runtime_error_into_rpc_error("Failed to fetch channels"))?;
@@ -1191,7 +1444,7 @@ where
 
 		Ok(QueryChannelsResponse {
 			channels,
-			pagination: None,
+			pagination: result.pagination.map(into_page_response),
 			height: Some(ibc_proto::ibc::core::client::v1::Height {
 				revision_number: para_id.into(),
 				revision_height: result.height,
@@ -1204,6 +1457,7 @@ where
 		height: u32,
 		channel_id: String,
 		port_id: String,
+		pagination: Option<PageRequest>,
 	) -> Result<QueryPacketCommitmentsResponse> {
 		let api = self.client.runtime_api();
 
@@ -1221,6 +1475,7 @@ where
 				hash_at,
 				channel_id.as_bytes().to_vec(),
 				port_id.as_bytes().to_vec(),
+				pagination.map(into_page_request),
 			)
 			.ok()
 			.flatten()
@@ -1243,7 +1498,7 @@ where
 			.collect::<Result<Vec<_>>>()?;
 		Ok(QueryPacketCommitmentsResponse {
 			commitments,
-			pagination: None,
+			pagination: result.pagination.map(into_page_response),
 			height: Some(ibc_proto::ibc::core::client::v1::Height {
 				revision_number: para_id.into(),
 				revision_height: result.height,
@@ -1256,6 +1511,7 @@ where
 		height: u32,
 		channel_id: String,
 		port_id: String,
+		pagination: Option<PageRequest>,
 	) -> Result<QueryPacketAcknowledgementsResponse> {
 		let api = self.client.runtime_api();
 
@@ -1274,6 +1530,7 @@ where
 				hash_at,
 				channel_id.as_bytes().to_vec(),
 				port_id.as_bytes().to_vec(),
+				pagination.map(into_page_request),
 			)
 			.ok()
 			.flatten()
@@ -1296,7 +1553,7 @@ where
 			.collect::<Result<Vec<_>>>()?;
 		Ok(QueryPacketAcknowledgementsResponse {
 			acknowledgements,
-			pagination: None,
+			pagination: result.pagination.map(into_page_response),
 			height: Some(ibc_proto::ibc::core::client::v1::Height {
 				revision_number: para_id.into(),
 				revision_height: result.height,
@@ -1561,6 +1818,7 @@ where
 				)
 			})?;
 
+		let metadata = denom_trace.metadata;
 		let denom_str = String::from_utf8(denom_trace.denom).map_err(|_| {
 			runtime_error_into_rpc_error(
 				"[ibc_rpc]: Could not decode ibc denom into a valid string",
@@ -1579,7 +1837,10 @@ where
 				)
 			})?;
 
-		Ok(QueryDenomTraceResponse { denom_trace: Some(denom_trace) })
+		Ok(QueryDenomTraceResponse {
+			denom_trace: Some(denom_trace),
+			metadata: metadata.map(Into::into),
+		})
 	}
 
 	fn query_denom_traces(
@@ -1629,6 +1890,11 @@ where
 
 		Ok(QueryDenomTracesResponse {
 			denom_traces,
+			metadata: result
+				.metadata
+				.into_iter()
+				.map(|metadata| metadata.map(Into::into))
+				.collect(),
 			pagination: result.next_key.map(|key| PageResponse {
 				next_key: key.encode(),
 				total: result.total.unwrap_or_default(),
@@ -1766,6 +2032,110 @@ where
 		}
 		Ok(events)
 	}
+
+	fn simulate_deliver(
+		&self,
+		messages: Vec<ibc_proto::google::protobuf::Any>,
+		at: Option<Block::Hash>,
+	) -> Result<Vec<SimulatedMessage>> {
+		let api = self.client.runtime_api();
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		let simulated = api
+			.simulate_deliver(at, messages.into_iter().map(Into::into).collect())
+			.map_err(|_| runtime_error_into_rpc_error("[ibc_rpc]: failed to simulate deliver"))?;
+		Ok(simulated
+			.into_iter()
+			.map(|pallet_ibc::SimulatedMessage { result, benchmarked_weight }| {
+				let (events, error) = match result {
+					Ok(events) => (
+						events.into_iter().filter_map(|event| event.try_into().ok()).collect(),
+						None,
+					),
+					Err(e) => (vec![], Some(ibc_error_message(e))),
+				};
+				SimulatedMessage {
+					events,
+					error,
+					benchmarked_ref_time: benchmarked_weight.ref_time(),
+					benchmarked_proof_size: benchmarked_weight.proof_size(),
+				}
+			})
+			.collect())
+	}
+
+	fn subscribe_events(
+		&self,
+		mut sink: SubscriptionSink,
+		filter: Option<EventFilter>,
+	) -> SubscriptionResult {
+		let filter = filter.unwrap_or_default();
+		let event_types = match filter.event_types() {
+			Ok(event_types) => event_types,
+			Err(event_type) => {
+				let _ = sink.reject(RpcError::Custom(format!("Unknown event type: {event_type}")));
+				return Ok(())
+			},
+		};
+		let client = self.client.clone();
+		// Subscribe before accepting, so that no finalized block is missed in between
+		let mut notifications = client.finality_notification_stream();
+		sink.accept()?;
+
+		let fut = async move {
+			while let Some(notification) = notifications.next().await {
+				let number = *notification.header.number();
+				// The notifications are buffered while the events of the previous blocks are
+				// read and sent, so a subscriber that can't keep up would grow the buffer forever
+				let lag = client.info().finalized_number.saturating_sub(number);
+				if lag > MAX_LAGGING_BLOCKS.into() {
+					sink.close(RpcError::Custom(format!(
+						"Subscription lagging {lag} blocks behind the finalized chain"
+					)));
+					return
+				}
+				// Blocks finalized along with the notified one are reported in its tree route
+				let mut blocks = notification
+					.tree_route
+					.iter()
+					.filter_map(|hash| {
+						let number = client.number(*hash).ok().flatten()?;
+						Some((*hash, number))
+					})
+					.collect::<Vec<_>>();
+				blocks.push((notification.hash, number));
+				for (block_hash, block_number) in blocks {
+					let events = match filtered_block_events::<C, Block, AssetId>(
+						&client,
+						block_hash,
+						&filter,
+						&event_types,
+					) {
+						Ok(events) => events,
+						Err(e) => {
+							log::error!("[ibc_rpc]: failed to read block events: {e}");
+							sink.close(runtime_error_into_rpc_error(e));
+							return
+						},
+					};
+					if events.is_empty() {
+						continue
+					}
+					let item = FinalizedBlockEvents { block_hash, block_number, events };
+					match sink.send(&item) {
+						Ok(true) => {},
+						// The subscriber went away
+						Ok(false) => return,
+						Err(e) => {
+							log::error!("[ibc_rpc]: failed to serialize block events: {e}");
+							return
+						},
+					}
+				}
+			}
+		};
+		self.executor.spawn("ibc-rpc-subscription", Some("rpc"), fut.boxed());
+		Ok(())
+	}
 }
 
 impl<C, Block, AssetId> IbcRpcHandler<C, Block, AssetId>
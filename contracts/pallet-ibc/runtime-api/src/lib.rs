@@ -31,34 +31,42 @@ sp_api::decl_runtime_apis! {
 		/// Return the consensus state for the given client at a height
 		fn client_consensus_state(client_id: Vec<u8>, revision_number: u64, revision_height: u64, latest_cs: bool) -> Option<QueryConsensusStateResponse>;
 
-		/// Returns client states for all clients on chain
-		fn clients() -> Option<Vec<(Vec<u8>, Vec<u8>)>>;
+		/// Returns client states for all clients on chain, or a page of them
+		fn clients(pagination: Option<PageRequest>) -> Option<QueryClientStatesResponse>;
 
 		/// Query the given connection state with proof
 		fn connection(connection_id: Vec<u8>) -> Option<QueryConnectionResponse>;
 
-		/// Returns all connections registered on chain
-		fn connections() -> Option<QueryConnectionsResponse>;
+		/// Returns all connections registered on chain, or a page of them
+		fn connections(pagination: Option<PageRequest>) -> Option<QueryConnectionsResponse>;
 
 		/// Returns all connections associated with the given client
 		fn connection_using_client(client_id: Vec<u8>) -> Option<Vec<IdentifiedConnection>>;
 
 		fn channel(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryChannelResponse>;
 
+		/// Returns the pending upgrade of this channel
+		fn channel_upgrade(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryChannelUpgradeResponse>;
+
+		/// Returns the last upgrade error receipt of this channel
+		fn channel_upgrade_error(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryChannelUpgradeErrorResponse>;
+
 		/// Should return the client state for the client supporting this channel
 		fn channel_client(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<IdentifiedClientState>;
 
 		/// Returns all channels associated with this connection
 		fn connection_channels(connection_id: Vec<u8>) -> Option<QueryChannelsResponse>;
 
-		/// Returns all channels registered on chain
-		fn channels() -> Option<QueryChannelsResponse>;
+		/// Returns all channels registered on chain, or a page of them
+		fn channels(pagination: Option<PageRequest>) -> Option<QueryChannelsResponse>;
 
 		fn connection_handshake(client_id: Vec<u8>, connection_id: Vec<u8>) -> Option<ConnectionHandshake>;
 
-		fn packet_commitments(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryPacketCommitmentsResponse>;
+		/// Returns the packet commitments of this channel, or a page of them
+		fn packet_commitments(channel_id: Vec<u8>, port_id: Vec<u8>, pagination: Option<PageRequest>) -> Option<QueryPacketCommitmentsResponse>;
 
-		fn packet_acknowledgements(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<QueryPacketAcknowledgementsResponse>;
+		/// Returns the packet acknowledgements of this channel, or a page of them
+		fn packet_acknowledgements(channel_id: Vec<u8>, port_id: Vec<u8>, pagination: Option<PageRequest>) -> Option<QueryPacketAcknowledgementsResponse>;
 
 		fn unreceived_packets(channel_id: Vec<u8>, port_id: Vec<u8>, seqs: Vec<u64>) -> Option<Vec<u64>>;
 
@@ -78,5 +86,24 @@ sp_api::decl_runtime_apis! {
 		fn denom_traces(key: Option<AssetId>, offset: Option<u32>, limit: u64, count_total: bool) -> QueryDenomTracesResponse;
 
 		fn block_events(extrinsic_index: Option<u32>) -> Vec<Result<pallet_ibc::events::IbcEvent, pallet_ibc::errors::IbcError>>;
+
+		/// Returns the channels whose escrow account holds less of an asset than the transfers
+		/// escrowed, for off-chain auditing
+		fn escrow_violations() -> Vec<pallet_ibc::ics20::escrow::EscrowViolation>;
+
+		/// Dry-runs the delivery of the messages in a storage transaction that is rolled back,
+		/// returning the outcome and the benchmarked weight of every message
+		fn simulate_deliver(messages: Vec<pallet_ibc::Any>) -> Vec<pallet_ibc::SimulatedMessage>;
+	}
+
+	/// Rate limits of the ICS20 transfers, implemented by runtimes with the
+	/// `pallet_ibc::ics20_rate_limit` pallet
+	pub trait Ics20RateLimitApi {
+		/// Returns all the rate limits along with their usage in the current window
+		fn rate_limits() -> Vec<pallet_ibc::ics20_rate_limit::RateLimitUsage>;
+
+		/// Returns the rate limit of `denom` on the transfer port of the channel with sequence
+		/// `channel`, along with its usage in the current window
+		fn rate_limit(channel: u64, denom: Vec<u8>) -> Option<pallet_ibc::ics20_rate_limit::RateLimitUsage>;
 	}
 }
@@ -7,9 +7,10 @@ use sp_core::crypto::AccountId32;
 
 use crate::{
 	ics23::{
-		acknowledgements::Acknowledgements, channels::Channels, next_seq_ack::NextSequenceAck,
-		next_seq_recv::NextSequenceRecv, next_seq_send::NextSequenceSend,
-		packet_commitments::PacketCommitment, receipts::PacketReceipt,
+		acknowledgements::Acknowledgements, channel_upgrades::ChannelUpgrades, channels::Channels,
+		next_seq_ack::NextSequenceAck, next_seq_recv::NextSequenceRecv,
+		next_seq_send::NextSequenceSend, packet_commitments::PacketCommitment,
+		receipts::PacketReceipt,
 	},
 	impls::host_height,
 	routing::Context,
@@ -24,8 +25,12 @@ use ibc::{
 			error::Error as ICS04Error,
 			msgs::acknowledgement::Acknowledgement,
 			packet::{Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
+		},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::ChannelUpgradePath,
 		},
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
 	timestamp::Timestamp,
 	Height,
@@ -257,6 +262,51 @@ where
 		let expected = T::ExpectedBlockTime::get();
 		Duration::from_millis(expected)
 	}
+
+	fn channel_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, ICS04Error> {
+		let (port_id, channel_id) = port_channel_id.clone();
+		let data =
+			<ChannelUpgrades<T>>::get(ChannelUpgradePath::Upgrade(port_id.clone(), channel_id))
+				.ok_or_else(|| ICS04Error::upgrade_not_found(port_id, channel_id))?;
+		Upgrade::decode_vec(&data).map_err(|e| ICS04Error::implementation_specific(e.to_string()))
+	}
+
+	fn channel_counterparty_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, ICS04Error> {
+		let (port_id, channel_id) = port_channel_id.clone();
+		let data = <ChannelUpgrades<T>>::get(ChannelUpgradePath::CounterpartyUpgrade(
+			port_id.clone(),
+			channel_id,
+		))
+		.ok_or_else(|| ICS04Error::upgrade_not_found(port_id, channel_id))?;
+		Upgrade::decode_vec(&data).map_err(|e| ICS04Error::implementation_specific(e.to_string()))
+	}
+
+	fn channel_upgrade_error(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, ICS04Error> {
+		let (port_id, channel_id) = port_channel_id.clone();
+		let data = <ChannelUpgrades<T>>::get(ChannelUpgradePath::UpgradeError(
+			port_id.clone(),
+			channel_id,
+		))
+		.ok_or_else(|| ICS04Error::upgrade_not_found(port_id, channel_id))?;
+		ErrorReceipt::decode_vec(&data)
+			.map_err(|e| ICS04Error::implementation_specific(e.to_string()))
+	}
+
+	fn inflight_packets(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<Sequence>, ICS04Error> {
+		Ok(<PacketCommitment<T>>::sequences(port_channel_id.0.clone(), port_channel_id.1).collect())
+	}
 }
 
 impl<T: Config + Sync + Send> ChannelKeeper for Context<T>
@@ -465,6 +515,61 @@ where
 		Ok(())
 	}
 
+	fn store_channel_upgrade(
+		&mut self,
+		(port_id, channel_id): (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), ICS04Error> {
+		let data = upgrade
+			.encode_vec()
+			.map_err(|e| ICS04Error::implementation_specific(e.to_string()))?;
+		<ChannelUpgrades<T>>::insert(ChannelUpgradePath::Upgrade(port_id, channel_id), data);
+		Ok(())
+	}
+
+	fn delete_channel_upgrade(
+		&mut self,
+		(port_id, channel_id): (PortId, ChannelId),
+	) -> Result<(), ICS04Error> {
+		<ChannelUpgrades<T>>::remove(ChannelUpgradePath::Upgrade(port_id, channel_id));
+		Ok(())
+	}
+
+	fn store_counterparty_upgrade(
+		&mut self,
+		(port_id, channel_id): (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), ICS04Error> {
+		let data = upgrade
+			.encode_vec()
+			.map_err(|e| ICS04Error::implementation_specific(e.to_string()))?;
+		<ChannelUpgrades<T>>::insert(
+			ChannelUpgradePath::CounterpartyUpgrade(port_id, channel_id),
+			data,
+		);
+		Ok(())
+	}
+
+	fn delete_counterparty_upgrade(
+		&mut self,
+		(port_id, channel_id): (PortId, ChannelId),
+	) -> Result<(), ICS04Error> {
+		<ChannelUpgrades<T>>::remove(ChannelUpgradePath::CounterpartyUpgrade(port_id, channel_id));
+		Ok(())
+	}
+
+	fn store_upgrade_error_receipt(
+		&mut self,
+		(port_id, channel_id): (PortId, ChannelId),
+		receipt: ErrorReceipt,
+	) -> Result<(), ICS04Error> {
+		let data = receipt
+			.encode_vec()
+			.map_err(|e| ICS04Error::implementation_specific(e.to_string()))?;
+		<ChannelUpgrades<T>>::insert(ChannelUpgradePath::UpgradeError(port_id, channel_id), data);
+		Ok(())
+	}
+
 	/// Called upon channel identifier creation (Init or Try message processing).
 	/// Increases the counter which keeps track of how many channels have been created.
 	/// Should never fail.
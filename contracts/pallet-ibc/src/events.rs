@@ -157,6 +157,96 @@ pub enum IbcEvent {
 		counterparty_port_id: Vec<u8>,
 		counterparty_channel_id: Option<Vec<u8>>,
 	},
+	/// Channel upgrade init
+	UpgradeInitChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade try
+	UpgradeTryChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade ack
+	UpgradeAckChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade confirm
+	UpgradeConfirmChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade open
+	UpgradeOpenChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade timeout
+	UpgradeTimeoutChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade cancel
+	UpgradeCancelChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel upgrade error
+	UpgradeErrorChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
+	/// Channel flush complete
+	FlushCompleteChannel {
+		revision_height: u64,
+		revision_number: u64,
+		port_id: Vec<u8>,
+		channel_id: Vec<u8>,
+		counterparty_port_id: Vec<u8>,
+		counterparty_channel_id: Option<Vec<u8>>,
+		upgrade_sequence: u64,
+	},
 	/// Receive packet
 	ReceivePacket {
 		revision_height: u64,
@@ -432,6 +522,105 @@ impl From<RawIbcEvent> for IbcEvent {
 				port_id: ev.src_port_id().as_bytes().to_vec(),
 				sequence: ev.packet.sequence.into(),
 			},
+			RawIbcEvent::UpgradeInitChannel(ev) => IbcEvent::UpgradeInitChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
+			RawIbcEvent::UpgradeTryChannel(ev) => IbcEvent::UpgradeTryChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
+			RawIbcEvent::UpgradeAckChannel(ev) => IbcEvent::UpgradeAckChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
+			RawIbcEvent::UpgradeConfirmChannel(ev) => IbcEvent::UpgradeConfirmChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
+			RawIbcEvent::UpgradeOpenChannel(ev) => IbcEvent::UpgradeOpenChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
+			RawIbcEvent::UpgradeTimeoutChannel(ev) => IbcEvent::UpgradeTimeoutChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
+			RawIbcEvent::UpgradeCancelChannel(ev) => IbcEvent::UpgradeCancelChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
+			RawIbcEvent::UpgradeErrorChannel(ev) => IbcEvent::UpgradeErrorChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
+			RawIbcEvent::FlushCompleteChannel(ev) => IbcEvent::FlushCompleteChannel {
+				revision_height: ev.height().revision_height,
+				revision_number: ev.height().revision_number,
+				port_id: ev.port_id.as_bytes().to_vec(),
+				channel_id: ev.channel_id.to_string().as_bytes().to_vec(),
+				counterparty_port_id: ev.counterparty_port_id.as_bytes().to_vec(),
+				counterparty_channel_id: ev
+					.counterparty_channel_id
+					.map(|val| val.to_string().as_bytes().to_vec()),
+				upgrade_sequence: ev.upgrade_sequence.into(),
+			},
 			RawIbcEvent::Empty(_) => IbcEvent::Empty,
 			RawIbcEvent::ChainError(_) => IbcEvent::ChainError,
 			RawIbcEvent::AppModule(ev) => IbcEvent::AppModule {
@@ -1040,6 +1229,249 @@ impl TryFrom<IbcEvent> for RawIbcEvent {
 					timeout_timestamp: Timestamp::default(),
 				},
 			})),
+			IbcEvent::UpgradeInitChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeInitChannel(ChannelEvents::UpgradeInit {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
+			IbcEvent::UpgradeTryChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeTryChannel(ChannelEvents::UpgradeTry {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
+			IbcEvent::UpgradeAckChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeAckChannel(ChannelEvents::UpgradeAck {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
+			IbcEvent::UpgradeConfirmChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeConfirmChannel(ChannelEvents::UpgradeConfirm {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
+			IbcEvent::UpgradeOpenChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeOpenChannel(ChannelEvents::UpgradeOpen {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
+			IbcEvent::UpgradeTimeoutChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeTimeoutChannel(ChannelEvents::UpgradeTimeout {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
+			IbcEvent::UpgradeCancelChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeCancelChannel(ChannelEvents::UpgradeCancel {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
+			IbcEvent::UpgradeErrorChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::UpgradeErrorChannel(ChannelEvents::UpgradeError {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
+			IbcEvent::FlushCompleteChannel {
+				revision_height,
+				revision_number,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id,
+				upgrade_sequence,
+			} => Ok(RawIbcEvent::FlushCompleteChannel(ChannelEvents::FlushComplete {
+				height: Height::new(revision_number, revision_height),
+				port_id: PortId::from_str(&String::from_utf8(port_id).map_err(|_| ERROR_STR)?)
+					.map_err(|_| ERROR_STR)?,
+				channel_id: ChannelId::from_str(
+					&String::from_utf8(channel_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_port_id: PortId::from_str(
+					&String::from_utf8(counterparty_port_id).map_err(|_| ERROR_STR)?,
+				)
+				.map_err(|_| ERROR_STR)?,
+				counterparty_channel_id: counterparty_channel_id.and_then(|channel_id| {
+					String::from_utf8(channel_id)
+						.ok()
+						.and_then(|channel_id| ChannelId::from_str(&channel_id).ok())
+				}),
+				upgrade_sequence: upgrade_sequence.into(),
+			})),
 			IbcEvent::Empty => Ok(RawIbcEvent::Empty("Empty".to_string())),
 			IbcEvent::ChainError => Ok(RawIbcEvent::ChainError("Chain Error".to_string())),
 			IbcEvent::AppModule { kind, module_id } => Ok(RawIbcEvent::AppModule(ModuleEvent {
@@ -0,0 +1,199 @@
+use crate::{
+	Config, DenomToAssetId, EscrowedAmounts, Event, Pallet, PausedChannels, ReceivedVoucherAmounts,
+	TransferPause,
+};
+use alloc::{string::String, vec::Vec};
+use frame_support::traits::{fungibles::Inspect, Currency, Get};
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc_primitives::get_channel_escrow_address;
+use sp_runtime::traits::{IdentifyAccount, SaturatedConversion};
+
+/// A channel whose escrow account holds less of an asset than the transfer application escrowed,
+/// or whose vouchers of an asset were issued more than the transfer application received.
+#[derive(
+	frame_support::RuntimeDebug,
+	PartialEq,
+	Eq,
+	scale_info::TypeInfo,
+	codec::Encode,
+	codec::Decode,
+	Clone,
+)]
+pub enum EscrowViolation {
+	/// The escrow account of the channel holds less of an asset than was escrowed
+	Escrow {
+		/// Sequence of the channel on the transfer port
+		channel: u64,
+		/// Denom of the asset on this chain, represented as utf8 string bytes
+		denom: Vec<u8>,
+		/// Net amount escrowed by the transfers sent and received over the channel
+		escrowed: u128,
+		/// Balance of the escrow account of the channel
+		balance: u128,
+	},
+	/// More vouchers of an asset were issued than were received over the channel
+	Voucher {
+		/// Sequence of the channel on the transfer port
+		channel: u64,
+		/// Denom of the voucher on this chain, represented as utf8 string bytes
+		denom: Vec<u8>,
+		/// Net amount of vouchers minted by the transfers received and sent over the channel
+		received: u128,
+		/// Total issuance of the voucher
+		issuance: u128,
+	},
+}
+
+impl<T: Config> Pallet<T> {
+	/// Records `amount` of `denom` escrowed for a transfer over `channel`, i.e. sent by this chain
+	/// while being the source of the asset, or taken back from the receiver of a transfer.
+	pub(crate) fn record_escrow(channel: u64, denom: &str, amount: u128) {
+		EscrowedAmounts::<T>::mutate(channel, denom.as_bytes(), |escrowed| {
+			*escrowed = escrowed.saturating_add(amount)
+		});
+		Self::enforce_escrow_invariant(Self::check_escrow(channel, denom));
+	}
+
+	/// Records `amount` of `denom` released from the escrow of `channel`, i.e. received by this
+	/// chain while being the source of the asset, or refunded to the sender of a transfer.
+	pub(crate) fn record_unescrow(channel: u64, denom: &str, amount: u128) {
+		// amounts escrowed before the accounting was introduced aren't recorded
+		EscrowedAmounts::<T>::mutate(channel, denom.as_bytes(), |escrowed| {
+			*escrowed = escrowed.saturating_sub(amount)
+		});
+		Self::enforce_escrow_invariant(Self::check_escrow(channel, denom));
+	}
+
+	/// Records `amount` of vouchers of `denom` minted for a transfer received over `channel`, or
+	/// given back to the sender of a transfer of vouchers.
+	pub(crate) fn record_voucher_mint(channel: u64, denom: &str, amount: u128) {
+		Self::record_vouchers(channel, denom, |received| received.saturating_add(amount))
+	}
+
+	/// Records `amount` of vouchers of `denom` burnt for a transfer sent over `channel`, or taken
+	/// back from the receiver of a transfer.
+	pub(crate) fn record_voucher_burn(channel: u64, denom: &str, amount: u128) {
+		Self::record_vouchers(channel, denom, |received| received.saturating_sub(amount))
+	}
+
+	fn record_vouchers(channel: u64, denom: &str, f: impl FnOnce(u128) -> u128) {
+		ReceivedVoucherAmounts::<T>::mutate(channel, denom.as_bytes(), |received| {
+			*received = Some(match received {
+				Some(received) => f(*received),
+				// vouchers minted before the accounting was introduced aren't recorded, so it
+				// starts from the issuance, which the mint or burn is already part of
+				None => Self::voucher_issuance(denom),
+			})
+		});
+		Self::enforce_escrow_invariant(Self::check_vouchers(channel, denom));
+	}
+
+	/// Returns the balance of `denom` of the escrow account of `channel`.
+	pub fn escrow_balance(channel: u64, denom: &str) -> u128 {
+		let Some(escrow_account) =
+			get_channel_escrow_address(&PortId::transfer(), ChannelId::new(channel))
+				.ok()
+				.and_then(|address| T::AccountIdConversion::try_from(address).ok())
+				.map(IdentifyAccount::into_account)
+		else {
+			return 0
+		};
+		let Ok(asset_id) = T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(denom) else {
+			return 0
+		};
+		if asset_id == T::NativeAssetId::get() {
+			T::NativeCurrency::free_balance(&escrow_account).saturated_into()
+		} else {
+			T::Fungibles::balance(asset_id, &escrow_account).saturated_into()
+		}
+	}
+
+	/// Returns the total issuance of the vouchers of `denom`.
+	pub fn voucher_issuance(denom: &str) -> u128 {
+		match T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(denom) {
+			Ok(asset_id) if asset_id != T::NativeAssetId::get() =>
+				T::Fungibles::total_issuance(asset_id).saturated_into(),
+			_ => 0,
+		}
+	}
+
+	/// Returns the violation of the escrow invariant of `denom` on `channel`, if its escrow
+	/// account holds less than the amount escrowed. It may hold more since anyone can send tokens
+	/// to it.
+	pub fn check_escrow(channel: u64, denom: &str) -> Option<EscrowViolation> {
+		let escrowed = EscrowedAmounts::<T>::get(channel, denom.as_bytes());
+		let balance = Self::escrow_balance(channel, denom);
+		(balance < escrowed).then(|| EscrowViolation::Escrow {
+			channel,
+			denom: denom.as_bytes().to_vec(),
+			escrowed,
+			balance,
+		})
+	}
+
+	/// Returns the violation of the voucher invariant of `denom` on `channel`, if more of its
+	/// vouchers were issued than received over the channel. Fewer may be issued since holders can
+	/// lose them, i.e. when their accounts are reaped.
+	pub fn check_vouchers(channel: u64, denom: &str) -> Option<EscrowViolation> {
+		let received = ReceivedVoucherAmounts::<T>::get(channel, denom.as_bytes())?;
+		let issuance = Self::voucher_issuance(denom);
+		(issuance > received).then(|| EscrowViolation::Voucher {
+			channel,
+			denom: denom.as_bytes().to_vec(),
+			received,
+			issuance,
+		})
+	}
+
+	/// Returns the violations of the escrow and voucher invariants over every channel.
+	pub fn escrow_violations() -> Vec<EscrowViolation> {
+		let escrow = EscrowedAmounts::<T>::iter().filter_map(|(channel, denom, _)| {
+			Self::check_escrow(channel, &String::from_utf8_lossy(&denom))
+		});
+		let vouchers = ReceivedVoucherAmounts::<T>::iter().filter_map(|(channel, denom, _)| {
+			Self::check_vouchers(channel, &String::from_utf8_lossy(&denom))
+		});
+		escrow.chain(vouchers).collect()
+	}
+
+	/// Reports a violation of the escrow or voucher invariant, pausing the transfers over its
+	/// channel if [`Config::PauseOnEscrowViolation`] is enabled.
+	fn enforce_escrow_invariant(violation: Option<EscrowViolation>) {
+		let channel = match violation {
+			None => return,
+			Some(EscrowViolation::Escrow { channel, denom, escrowed, balance }) => {
+				log::error!(
+					target: "pallet_ibc",
+					"Escrow of {} on channel-{channel} holds {balance} but {escrowed} were escrowed",
+					String::from_utf8_lossy(&denom)
+				);
+				Self::deposit_event(Event::<T>::EscrowInvariantViolated {
+					channel,
+					denom,
+					escrowed,
+					balance,
+				});
+				channel
+			},
+			Some(EscrowViolation::Voucher { channel, denom, received, issuance }) => {
+				log::error!(
+					target: "pallet_ibc",
+					"{issuance} vouchers of {} were issued but {received} were received on channel-{channel}",
+					String::from_utf8_lossy(&denom)
+				);
+				Self::deposit_event(Event::<T>::VoucherInvariantViolated {
+					channel,
+					denom,
+					received,
+					issuance,
+				});
+				channel
+			},
+		};
+		if T::PauseOnEscrowViolation::get() {
+			let pause = TransferPause { send: true, receive: true };
+			PausedChannels::<T>::insert(channel, pause);
+			Self::deposit_event(Event::<T>::ChannelPauseSet { channel, pause });
+		}
+	}
+}
@@ -0,0 +1,289 @@
+use crate::{
+	ics20::{full_ibc_denom, HandleMemo, IbcModule, MemoExecution, MemoFailureReason},
+	ics27::{context::account_to_signer, MAX_CALL_DECODE_DEPTH},
+	routing::Context,
+	Config, Event, Pallet,
+};
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+use codec::DecodeLimit;
+use frame_support::traits::Contains;
+use frame_system::RawOrigin;
+use ibc::{
+	applications::transfer::{
+		context::BankKeeper, error::Error as Ics20Error, relay::decode_packet_data, PrefixedCoin,
+		PrefixedDenom,
+	},
+	core::ics04_channel::packet::Packet,
+	signer::Signer,
+};
+use sp_core::crypto::AccountId32;
+use sp_runtime::{
+	traits::{Dispatchable, IdentifyAccount},
+	DispatchError, DispatchResult,
+};
+use sp_std::{marker::PhantomData, str::FromStr};
+
+/// Prefix of the preimage of the intermediate accounts of the senders of hook memos.
+pub const INTERMEDIATE_ACCOUNT_PREFIX: &[u8] = b"ibc-hooks-intermediary";
+
+/// The smart contracts of the runtime hook memos call, usually backed by `pallet-contracts`.
+pub trait ContractHandler<AccountId> {
+	/// Calls `contract` with `msg` on behalf of `origin`, the tokens received with the memo
+	/// having been sent to `contract` beforehand.
+	fn execute(origin: &AccountId, contract: &AccountId, msg: Vec<u8>) -> DispatchResult;
+
+	/// Notifies `contract` of the acknowledgement of a packet it sent with an `ibc_callback`
+	/// memo, `ack` being `None` if the packet timed out.
+	fn ibc_callback(contract: &AccountId, packet: &Packet, ack: Option<&[u8]>) -> DispatchResult;
+}
+
+/// Runtimes without smart contracts reject the contract calls of hook memos.
+impl<AccountId> ContractHandler<AccountId> for () {
+	fn execute(_origin: &AccountId, _contract: &AccountId, _msg: Vec<u8>) -> DispatchResult {
+		Err(DispatchError::Other("Contracts are not supported"))
+	}
+
+	fn ibc_callback(
+		_contract: &AccountId,
+		_packet: &Packet,
+		_ack: Option<&[u8]>,
+	) -> DispatchResult {
+		Err(DispatchError::Other("Contracts are not supported"))
+	}
+}
+
+/// Memo of a transfer executed by [`IbcHooksHandler`].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Default)]
+pub struct HookMemo {
+	/// Contract called with the tokens received
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub wasm: Option<WasmHook>,
+	/// Hex encoded `RuntimeCall` dispatched by the intermediate account of the sender
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub call: Option<String>,
+	/// Contract notified of the acknowledgement of the packet it sends with this memo
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub ibc_callback: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct WasmHook {
+	pub contract: String,
+	pub msg: serde_json::Value,
+}
+
+impl HookMemo {
+	fn parse(memo: &str) -> Option<Self> {
+		if memo.is_empty() {
+			return None
+		}
+		serde_json::from_str(memo).ok()
+	}
+}
+
+/// Returns the account holding the tokens received from `sender` over `channel_id` while the
+/// call of its memo is executed. As no one else can dispatch calls with it, it acts on behalf
+/// of the sender.
+pub fn intermediate_account(channel_id: &str, sender: &str) -> AccountId32 {
+	let preimage =
+		[INTERMEDIATE_ACCOUNT_PREFIX, channel_id.as_bytes(), sender.as_bytes()].join(&b'/');
+	AccountId32::new(sp_io::hashing::blake2_256(&preimage))
+}
+
+/// An "ibc-hooks" memo handler: the tokens of transfers whose memo has a `wasm` or a `call`
+/// payload are credited to the intermediate account of the sender, which then calls the contract
+/// with them, or dispatches the call if it's allowed by [`Config::MemoAllowedCalls`]. Other memos
+/// are executed by the inner handler.
+///
+/// The packets sent by a contract with an `ibc_callback` memo naming it are notified to the
+/// contract once acknowledged or timed out.
+pub struct IbcHooksHandler<H, T> {
+	pub inner: H,
+	pub _phantom: PhantomData<T>,
+}
+
+impl<H: Default, T> Default for IbcHooksHandler<H, T> {
+	fn default() -> Self {
+		Self { inner: H::default(), _phantom: PhantomData }
+	}
+}
+
+impl<T, H: HandleMemo<T>> HandleMemo<T> for IbcHooksHandler<H, T>
+where
+	T: Config + Send + Sync,
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn execute_memo(&self, packet: &Packet) -> Result<MemoExecution, Ics20Error> {
+		let ctx = Context::<T>::default();
+		let packet_data = decode_packet_data(
+			&ctx,
+			&packet.destination_port,
+			packet.destination_channel,
+			&packet.data,
+		)
+		.map_err(|e| {
+			Ics20Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
+		})?;
+		let memo = match HookMemo::parse(&packet_data.memo) {
+			Some(memo) if memo.wasm.is_some() || memo.call.is_some() => memo,
+			_ => return self.inner.execute_memo(packet),
+		};
+		let receiver = <T as Config>::AccountIdConversion::try_from(packet_data.receiver.clone())
+			.map_err(|_| {
+			Ics20Error::implementation_specific("Failed to parse receiver account".to_string())
+		})?;
+
+		// hooks only execute single token transfers
+		let token = match packet_data.tokens.as_slice() {
+			[token] => PrefixedCoin {
+				denom: PrefixedDenom::from_str(&full_ibc_denom(packet, token.clone()))?,
+				amount: token.amount,
+			},
+			_ =>
+				return Err(Ics20Error::implementation_specific(
+					"memo of a multi-denom transfer can't be executed".to_string(),
+				)),
+		};
+
+		Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoStarted {
+			account_id: receiver.clone().into_account(),
+			memo: Some(packet_data.memo.clone()),
+		});
+
+		let intermediate = intermediate_account(
+			&packet.destination_channel.to_string(),
+			packet_data.sender.as_ref(),
+		);
+		let intermediate = account_to_signer(intermediate)
+			.ok()
+			.and_then(|signer| <T as Config>::AccountIdConversion::try_from(signer).ok())
+			.ok_or_else(|| {
+				Ics20Error::implementation_specific(
+					"Failed to derive intermediate account".to_string(),
+				)
+			})?;
+
+		// the call is executed along with the transfer of the tokens, or not at all
+		IbcModule::<T>::atomically(|| Self::execute_hook(&receiver, &intermediate, &token, &memo))
+			.map_err(|e| {
+				IbcModule::<T>::emit_memo_execution_failed_event(
+					receiver.clone().into_account(),
+					packet_data.memo.clone(),
+					MemoFailureReason::HookExecutionFailed,
+				);
+				e
+			})?;
+
+		Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoHookSuccess {
+			from: intermediate.into_account(),
+			contract: memo.wasm.and_then(|wasm| {
+				Self::parse_account(&wasm.contract).ok().map(IdentifyAccount::into_account)
+			}),
+		});
+		Ok(MemoExecution::Completed)
+	}
+
+	fn retry_forward(&self, packet: &Packet) -> Result<MemoExecution, Ics20Error> {
+		self.inner.retry_forward(packet)
+	}
+
+	fn on_acknowledgement_packet(&self, packet: &Packet, ack: &[u8]) -> Result<(), Ics20Error> {
+		self.inner.on_acknowledgement_packet(packet, ack)?;
+		Self::ibc_callback(packet, Some(ack))
+	}
+
+	fn on_timeout_packet(&self, packet: &Packet) -> Result<(), Ics20Error> {
+		self.inner.on_timeout_packet(packet)?;
+		Self::ibc_callback(packet, None)
+	}
+}
+
+impl<T, H> IbcHooksHandler<H, T>
+where
+	T: Config + Send + Sync,
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn parse_account(address: &str) -> Result<T::AccountIdConversion, Ics20Error> {
+		Signer::from_str(address)
+			.ok()
+			.and_then(|signer| <T as Config>::AccountIdConversion::try_from(signer).ok())
+			.ok_or_else(|| {
+				Ics20Error::implementation_specific(format!("Failed to parse account {address}"))
+			})
+	}
+
+	/// Sends the tokens received to the intermediate account of the sender, which then executes
+	/// the memo.
+	fn execute_hook(
+		receiver: &T::AccountIdConversion,
+		intermediate: &T::AccountIdConversion,
+		token: &PrefixedCoin,
+		memo: &HookMemo,
+	) -> Result<(), Ics20Error> {
+		let mut ctx = Context::<T>::default();
+		ctx.send_coins(receiver, intermediate, token)?;
+		let origin = intermediate.clone().into_account();
+
+		if let Some(wasm) = &memo.wasm {
+			let contract = Self::parse_account(&wasm.contract)?;
+			ctx.send_coins(intermediate, &contract, token)?;
+			let msg = serde_json::to_vec(&wasm.msg).map_err(|e| {
+				Ics20Error::implementation_specific(format!("Failed to encode contract msg {e:?}"))
+			})?;
+			return T::ContractHandler::execute(&origin, &contract.into_account(), msg).map_err(
+				|e| Ics20Error::implementation_specific(format!("Contract call failed: {e:?}")),
+			)
+		}
+
+		let call = memo.call.as_deref().unwrap_or_default();
+		let call = hex::decode(call.strip_prefix("0x").unwrap_or(call)).map_err(|_| {
+			Ics20Error::implementation_specific("Failed to decode hex call".to_string())
+		})?;
+		let call = <T as Config>::RuntimeCall::decode_with_depth_limit(
+			MAX_CALL_DECODE_DEPTH,
+			&mut call.as_slice(),
+		)
+		.map_err(|e| {
+			Ics20Error::implementation_specific(format!("Failed to decode call: {e:?}"))
+		})?;
+		if !T::MemoAllowedCalls::contains(&call) {
+			return Err(Ics20Error::implementation_specific("Call is not allowed".to_string()))
+		}
+		call.dispatch(RawOrigin::Signed(origin).into()).map_err(|e| {
+			Ics20Error::implementation_specific(format!("Call failed: {:?}", e.error))
+		})?;
+		Ok(())
+	}
+
+	/// Notifies the contract which sent `packet` with an `ibc_callback` memo naming it.
+	fn ibc_callback(packet: &Packet, ack: Option<&[u8]>) -> Result<(), Ics20Error> {
+		let ctx = Context::<T>::default();
+		let packet_data =
+			decode_packet_data(&ctx, &packet.source_port, packet.source_channel, &packet.data)?;
+		let Some(contract) = HookMemo::parse(&packet_data.memo).and_then(|memo| memo.ibc_callback)
+		else {
+			return Ok(())
+		};
+		let contract = Self::parse_account(&contract)?.into_account();
+		// contracts are only notified of the packets they sent
+		let sender = Self::parse_account(packet_data.sender.as_ref())?.into_account();
+		if sender != contract {
+			return Ok(())
+		}
+		T::ContractHandler::ibc_callback(&contract, packet, ack).map_err(|e| {
+			Ics20Error::implementation_specific(format!("Contract callback failed: {e:?}"))
+		})?;
+		Pallet::<T>::deposit_event(Event::<T>::IbcCallbackExecuted {
+			contract,
+			channel: packet.source_channel.sequence(),
+			sequence: packet.sequence.into(),
+		});
+		Ok(())
+	}
+}
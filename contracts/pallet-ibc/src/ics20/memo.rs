@@ -1,12 +1,22 @@
-use crate::{ics20::HandleMemo, Config};
+use crate::{
+	ics20::{full_ibc_denom, HandleMemo, Ics20RateLimiter, InFlightForward, MemoExecution},
+	routing::Context,
+	Config, Event, InFlightForwards, Pallet,
+};
 use alloc::{
 	format,
 	string::{String, ToString},
+	vec::Vec,
 };
 use core::{fmt::Debug, str::FromStr};
 use ibc::{
 	applications::transfer::{
-		acknowledgement::Acknowledgement as Ics20Acknowledgement, error::Error as Ics20Error,
+		acknowledgement::Acknowledgement as Ics20Acknowledgement,
+		context::{BankKeeper, Ics20Reader},
+		error::Error as Ics20Error,
+		is_receiver_chain_source,
+		relay::decode_packet_data,
+		PrefixedCoin, PrefixedDenom,
 	},
 	core::{
 		ics04_channel::{
@@ -17,10 +27,13 @@ use ibc::{
 			Version,
 		},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
-		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+		ics26_routing::context::{
+			Ics26Context, Module, ModuleCallbackContext, ModuleOutputBuilder,
+		},
 	},
 	signer::Signer,
 };
+use sp_core::{crypto::AccountId32, Get};
 
 /// This middleware should be used to wrap ics20 to execute memo
 /// We chose to use this as a middleware so that we can easily choose
@@ -53,6 +66,9 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 
 impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Debug> Module
 	for Memo<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
 {
 	fn on_chan_open_init(
 		&mut self,
@@ -152,14 +168,94 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 		self.inner.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)
 	}
 
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<Version, Error> {
+		self.inner.on_chan_upgrade_init(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		counterparty_version: &Version,
+	) -> Result<Version, Error> {
+		self.inner.on_chan_upgrade_try(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			counterparty_version,
+		)
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Error> {
+		self.inner
+			.on_chan_upgrade_ack(ctx, output, port_id, channel_id, counterparty_version)
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<(), Error> {
+		self.inner.on_chan_upgrade_open(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
 	fn on_recv_packet(
 		&self,
 		ctx: &dyn ModuleCallbackContext,
 		output: &mut ModuleOutputBuilder,
 		packet: &mut Packet,
 		relayer: &Signer,
-	) -> Result<Acknowledgement, Error> {
-		let ack = self.inner.on_recv_packet(ctx, output, packet, relayer)?;
+	) -> Result<Option<Acknowledgement>, Error> {
+		// the inner modules may change the packet data, e.g. to deduct the service charge
+		let received = packet.clone();
+		let Some(ack) = self.inner.on_recv_packet(ctx, output, packet, relayer)? else {
+			// the memo can only be executed once the transfer is known to have succeeded
+			return Ok(None)
+		};
 		let ics20_ack = Ics20Acknowledgement::from_str(&String::from_utf8_lossy(ack.as_ref()))
 			.map_err(|_| Error::invalid_acknowledgement())?;
 
@@ -167,22 +263,28 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 		// does not contain an error
 
 		if !ics20_ack.is_successful() {
-			return Ok(ack)
+			return Ok(Some(ack))
 		}
 
 		// We want the whole chain of calls to fail only if the ics20 transfer fails, because
 		// the other modules are not part of ics-20 standard
 		match Self::process_memo(packet) {
-			Ok(_) => Ok(ack),
+			Ok(MemoExecution::Completed) => Ok(Some(ack)),
+			// the packet is acknowledged once the packet forwarding its tokens is
+			Ok(MemoExecution::Forwarded { channel, sequence, retries }) => {
+				InFlightForwards::<T>::insert(
+					channel,
+					sequence,
+					InFlightForward::new(&received, packet.data.clone(), retries),
+				);
+				Ok(None)
+			},
 			Err(err) => {
 				log::error!(target: "pallet_ibc", "Error while handling memo: {:?}", err);
-				Ok(Acknowledgement::from_bytes(
-					Ics20Acknowledgement::from_error(Ics20Error::implementation_specific(
-						err.to_string(),
-					))
-					.to_string()
-					.into_bytes(),
-				))
+				// the sender is refunded by the error acknowledgement, so the receiver mustn't
+				// keep the tokens
+				Self::revert_receive(&received, &packet.data)?;
+				Ok(Some(Self::error_ack(err.to_string())))
 			},
 		}
 	}
@@ -195,8 +297,21 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 		acknowledgement: &GenericAcknowledgement,
 		relayer: &Signer,
 	) -> Result<(), Error> {
+		// on failure, the inner modules refund the forwarded tokens to the receiver of the packet
+		// they were received with
 		self.inner
-			.on_acknowledgement_packet(ctx, output, packet, acknowledgement, relayer)
+			.on_acknowledgement_packet(ctx, output, packet, acknowledgement, relayer)?;
+		if let Err(err) = <T as Config>::HandleMemo::default()
+			.on_acknowledgement_packet(packet, acknowledgement.as_ref())
+		{
+			log::error!(target: "pallet_ibc", "Error while handling memo acknowledgement: {:?}", err);
+		}
+		let Some(forward) = Self::take_in_flight_forward(packet) else { return Ok(()) };
+		let success =
+			Ics20Acknowledgement::from_str(&String::from_utf8_lossy(acknowledgement.as_ref()))
+				.map(|ack| ack.is_successful())
+				.unwrap_or(false);
+		Self::settle_forward(output, packet, forward, success)
 	}
 
 	fn on_timeout_packet(
@@ -206,15 +321,167 @@ impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Deb
 		packet: &mut Packet,
 		relayer: &Signer,
 	) -> Result<(), Error> {
-		self.inner.on_timeout_packet(ctx, output, packet, relayer)
+		self.inner.on_timeout_packet(ctx, output, packet, relayer)?;
+		if let Err(err) = <T as Config>::HandleMemo::default().on_timeout_packet(packet) {
+			log::error!(target: "pallet_ibc", "Error while handling memo timeout: {:?}", err);
+		}
+		let Some(forward) = Self::take_in_flight_forward(packet) else { return Ok(()) };
+		if forward.retries_remaining > 0 {
+			match Self::retry_forward(&forward) {
+				Ok((channel, sequence)) => {
+					let retries_remaining = forward.retries_remaining - 1;
+					InFlightForwards::<T>::insert(
+						channel,
+						sequence,
+						InFlightForward { retries_remaining, ..forward },
+					);
+					Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoForwardRetried {
+						channel,
+						sequence,
+						retries_remaining,
+					});
+					return Ok(())
+				},
+				Err(err) =>
+					log::error!(target: "pallet_ibc", "Error while retrying forward: {:?}", err),
+			}
+		}
+		Self::settle_forward(output, packet, forward, false)
 	}
 }
 
-impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Debug> Memo<T, S> {
-	fn process_memo(packet: &mut Packet) -> Result<(), Error> {
-		<T as Config>::HandleMemo::default().execute_memo(packet).map_err(|e| {
-			Error::implementation_specific(format!("Failed to execute memo {:?}", e))
-		})?;
+impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Debug> Memo<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn process_memo(packet: &mut Packet) -> Result<MemoExecution, Error> {
+		<T as Config>::HandleMemo::default()
+			.execute_memo(packet)
+			.map_err(|e| Error::implementation_specific(format!("Failed to execute memo {:?}", e)))
+	}
+
+	fn error_ack(err: String) -> Acknowledgement {
+		Acknowledgement::from_bytes(
+			Ics20Acknowledgement::from_error(Ics20Error::implementation_specific(err))
+				.to_string()
+				.into_bytes(),
+		)
+	}
+
+	/// Removes the forward of tokens sent with `packet`, if any.
+	fn take_in_flight_forward(packet: &Packet) -> Option<InFlightForward> {
+		if packet.source_port != PortId::transfer() {
+			return None
+		}
+		InFlightForwards::<T>::take(packet.source_channel.sequence(), u64::from(packet.sequence))
+	}
+
+	/// Sends the forward of tokens again, returning the channel and sequence of the new packet.
+	fn retry_forward(forward: &InFlightForward) -> Result<(u64, u64), Error> {
+		let mut packet = forward.packet()?;
+		packet.data = forward.data.clone();
+		match <T as Config>::HandleMemo::default().retry_forward(&packet) {
+			Ok(MemoExecution::Forwarded { channel, sequence, .. }) => Ok((channel, sequence)),
+			Ok(MemoExecution::Completed) =>
+				Err(Error::implementation_specific("Memo didn't forward tokens".to_string())),
+			Err(e) => Err(Error::implementation_specific(format!("Failed to retry forward {e:?}"))),
+		}
+	}
+
+	/// Acknowledges the packet whose tokens have been forwarded by `packet`, once `packet` is
+	/// acknowledged or timed out. If forwarding failed, the received packet is acknowledged with
+	/// an error, refunding its sender.
+	fn settle_forward(
+		output: &mut ModuleOutputBuilder,
+		packet: &Packet,
+		forward: InFlightForward,
+		success: bool,
+	) -> Result<(), Error> {
+		let received = forward.packet()?;
+		let ack = if success {
+			Acknowledgement::from_bytes(Ics20Acknowledgement::success().to_string().into_bytes())
+		} else {
+			Self::revert_receive(&received, &forward.data)?;
+			Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoForwardFailed {
+				channel: packet.source_channel.sequence(),
+				sequence: packet.sequence.into(),
+			});
+			Self::error_ack("Forwarded packet failed".to_string())
+		};
+		let ack_output = Context::<T>::default().write_acknowledgement(received, ack)?;
+		for event in ack_output.events {
+			output.emit(event);
+		}
+		Ok(())
+	}
+
+	/// Takes the tokens received with `received` back, as they are refunded to its sender by an
+	/// error acknowledgement. `data` is the packet data as seen by the memo handler: the tokens it
+	/// holds are taken from the receiver, the rest from the account the service charge is paid to.
+	fn revert_receive(received: &Packet, data: &[u8]) -> Result<(), Error> {
+		let mut ctx = Context::<T>::default();
+		let decode = |data: &[u8]| {
+			decode_packet_data(&ctx, &received.destination_port, received.destination_channel, data)
+				.map_err(|e| {
+					Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
+				})
+		};
+		let received_data = decode(&received.data)?;
+		let held_data = decode(data)?;
+		let receiver = <T as Config>::AccountIdConversion::try_from(held_data.receiver.clone())
+			.map_err(|_| {
+				Error::implementation_specific(format!(
+					"Failed to parse receiver account {:?}",
+					held_data.receiver
+				))
+			})?;
+		let fee_account = T::FeeAccount::get();
+		let mut refunded = Vec::with_capacity(received_data.tokens.len());
+		for (token, held) in received_data.tokens.iter().zip(&held_data.tokens) {
+			let denom = PrefixedDenom::from_str(&full_ibc_denom(received, token.clone())).map_err(
+				|_| Error::implementation_specific("Failed to parse token denom".to_string()),
+			)?;
+			refunded.push(PrefixedCoin { denom: denom.clone(), amount: token.amount });
+			let held_amount = held.amount.as_u256();
+			let charged_amount = token.amount.as_u256().saturating_sub(held_amount);
+			for (account, amount) in [(&receiver, held_amount), (&fee_account, charged_amount)] {
+				if amount.is_zero() {
+					continue
+				}
+				let coin = PrefixedCoin { denom: denom.clone(), amount: amount.into() };
+				// escrow the tokens unescrowed on receipt back, or burn the vouchers minted
+				if is_receiver_chain_source(
+					received.source_port.clone(),
+					received.source_channel,
+					&token.denom,
+				) {
+					ctx.get_channel_escrow_address(
+						&received.destination_port,
+						received.destination_channel,
+					)
+					.and_then(|escrow_address| ctx.send_coins(account, &escrow_address, &coin))
+					.map(|_| {
+						Pallet::<T>::record_escrow(
+							received.destination_channel.sequence(),
+							&denom.to_string(),
+							amount.low_u128(),
+						)
+					})
+				} else {
+					ctx.burn_coins(account, &coin).map(|_| {
+						Pallet::<T>::record_voucher_burn(
+							received.destination_channel.sequence(),
+							&denom.to_string(),
+							amount.low_u128(),
+						)
+					})
+				}
+				.map_err(|e| Error::implementation_specific(e.to_string()))?;
+			}
+		}
+		// the tokens didn't flow in after all
+		T::Ics20RateLimiter::on_receive_refunded(&received.destination_channel, &refunded);
 		Ok(())
 	}
 }
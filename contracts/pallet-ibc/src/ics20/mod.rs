@@ -1,4 +1,6 @@
 pub mod context;
+pub mod escrow;
+pub mod hooks;
 pub mod memo;
 
 use crate::{
@@ -8,28 +10,34 @@ use alloc::{
 	format,
 	str::FromStr,
 	string::{String, ToString},
+	vec::Vec,
 };
 
-use frame_support::weights::Weight;
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	weights::Weight,
+};
 pub use ibc::applications::transfer::{MODULE_ID_STR, PORT_ID_STR};
 use ibc::{
 	applications::transfer::{
 		acknowledgement::{Acknowledgement as Ics20Acknowledgement, ACK_ERR_STR},
 		context::{
 			on_chan_close_confirm, on_chan_close_init, on_chan_open_ack, on_chan_open_confirm,
-			on_chan_open_init, on_chan_open_try, BankKeeper,
+			on_chan_open_init, on_chan_open_try, on_chan_upgrade_ack, on_chan_upgrade_init,
+			on_chan_upgrade_try, BankKeeper,
 		},
 		is_receiver_chain_source, is_sender_chain_source,
-		packet::PacketData,
+		packet::PacketDataV2,
 		relay::{
-			on_ack_packet::process_ack_packet, on_recv_packet::process_recv_packet,
-			on_timeout_packet::process_timeout_packet,
+			decode_packet_data, on_ack_packet::process_ack_packet,
+			on_recv_packet::process_recv_packet, on_timeout_packet::process_timeout_packet,
 		},
 		PrefixedCoin, PrefixedDenom, TracePrefix,
 	},
 	core::{
 		ics04_channel::{
 			channel::{Counterparty, Order},
+			context::ChannelReader,
 			error::Error as Ics04Error,
 			msgs::acknowledgement::Acknowledgement,
 			packet::Packet,
@@ -40,9 +48,11 @@ use ibc::{
 	},
 	signer::Signer,
 };
-use ibc_primitives::{CallbackWeight, HandlerMessage, IbcHandler};
+use ibc_primitives::CallbackWeight;
+use ibc_proto::ibc::core::channel::v1::Packet as RawPacket;
+use prost::Message;
 use sp_core::crypto::AccountId32;
-use sp_runtime::traits::IdentifyAccount;
+use sp_runtime::{traits::IdentifyAccount, DispatchError};
 use sp_std::marker::PhantomData;
 
 pub type Ics20TransferMsg = ibc::applications::transfer::msgs::transfer::MsgTransfer<
@@ -200,20 +210,78 @@ where
 			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
 	}
 
+	fn on_chan_upgrade_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<Version, Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_upgrade_init(&mut ctx, output, port_id, channel_id, order, connection_hops, version)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		counterparty_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_upgrade_try(
+			&mut ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			counterparty_version,
+		)
+		.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_upgrade_ack(&mut ctx, output, port_id, channel_id, counterparty_version)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
 	fn on_recv_packet(
 		&self,
 		_ctx: &dyn ModuleCallbackContext,
 		output: &mut ModuleOutputBuilder,
 		packet: &mut Packet,
 		_relayer: &Signer,
-	) -> Result<Acknowledgement, Ics04Error> {
+	) -> Result<Option<Acknowledgement>, Ics04Error> {
 		let mut ctx = Context::<T>::default();
 
-		let result = serde_json::from_slice(packet.data.as_slice())
-			.map_err(|e| {
-				Ics04Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
-			})
-			.and_then(|packet_data: PacketData| {
+		let result = decode_packet_data(
+			&ctx,
+			&packet.destination_port,
+			packet.destination_channel,
+			&packet.data,
+		)
+		.map_err(|e| {
+			Ics04Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
+		})
+		.and_then(|packet_data| {
+			let mut denoms = Vec::with_capacity(packet_data.tokens.len());
+			for token in &packet_data.tokens {
 				// We need to reject transaction amounts that are larger than u128 since we expect
 				// the balance type of the runtime to be a u128; For a U256 to be converted to a
 				// u128 without truncating, the last two words should be zero
@@ -221,85 +289,116 @@ where
 				// in order to properly calculate rate limits, we need to adjust the full denom,
 				// which wan not included as part of the token in the packet data.
 
-				let mut token = packet_data.token.clone();
-				let denom = full_ibc_denom(packet, packet_data.token.clone());
-				token.denom = PrefixedDenom::from_str(&denom).map_err(|_| {
+				let denom = full_ibc_denom(packet, token.clone());
+				PrefixedDenom::from_str(&denom).map_err(|_| {
 					Ics04Error::implementation_specific("Failed to parse token denom".to_string())
 				})?;
 
-				let amount = packet_data.token.amount.as_u256();
+				let amount = token.amount.as_u256();
 				u128::try_from(amount)
 					.map_err(|e| Ics04Error::implementation_specific(format!("{e:?}")))?;
+				denoms.push(denom);
+			}
+			Self::atomically(|| {
+				// assets are looked up in the transaction since it may register them
+				for denom in &denoms {
+					let asset_id =
+						T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(denom).ok();
+					let channel = packet.destination_channel.sequence();
+					if Pallet::<T>::transfer_pause(channel, asset_id.as_ref()).receive {
+						return Err(Ics20Error::implementation_specific(format!(
+							"Transfers of {denom} over {} are paused",
+							packet.destination_channel
+						)))
+					}
+				}
 				process_recv_packet(&mut ctx, output, packet, packet_data.clone())
-					.map(|_| packet_data)
-					.map_err(|e| {
-						log::trace!(target: "pallet_ibc", "[on_recv_packet]: token: {}, error: {:?}", denom, e);
-						Ics04Error::implementation_specific(e.to_string())
-					})
-			});
+			})
+			.map(|_| packet_data)
+			.map_err(|e| {
+				log::trace!(target: "pallet_ibc", "[on_recv_packet]: tokens: {:?}, error: {:?}", denoms, e);
+				Ics04Error::implementation_specific(e.to_string())
+			})
+		});
 
 		let ack = match result {
 			Err(err) => {
 				log::trace!(target: "pallet_ibc", "Acknowledgement error: {:?}", err);
-				let ack = Ics20Acknowledgement::Error(format!("{ACK_ERR_STR}: {err:?}"))
+				Ics20Acknowledgement::Error(format!("{ACK_ERR_STR}: {err:?}"))
 					.to_string()
-					.into_bytes();
-				Pallet::<T>::handle_message(HandlerMessage::WriteAck {
-					packet: packet.clone(),
-					ack: ack.clone(),
-				})
-				.map_err(|e| {
-					Ics04Error::implementation_specific(format!("[on_recv_packet] {e:#?}"))
-				})?;
-				ack
+					.into_bytes()
 			},
 			Ok(packet_data) => {
-				let denom = full_ibc_denom(packet, packet_data.token.clone());
-				let prefixed_denom = PrefixedDenom::from_str(&denom).map_err(|_| {
-					Ics04Error::implementation_specific("Failed to parse token denom".to_string())
-				})?;
-				Pallet::<T>::deposit_event(Event::<T>::TokenReceived {
-					from: packet_data.sender,
-					to: packet_data.receiver,
-					ibc_denom: denom.as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom)
-						.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_receiver_source: is_receiver_chain_source(
+				for token in &packet_data.tokens {
+					let denom = full_ibc_denom(packet, token.clone());
+					let prefixed_denom = PrefixedDenom::from_str(&denom).map_err(|_| {
+						Ics04Error::implementation_specific(
+							"Failed to parse token denom".to_string(),
+						)
+					})?;
+					if is_receiver_chain_source(
 						packet.source_port.clone(),
 						packet.source_channel,
-						&prefixed_denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				});
-				let packet = packet.clone();
-				Pallet::<T>::handle_message(HandlerMessage::WriteAck {
-					packet,
-					ack: Ics20Acknowledgement::success().to_string().into_bytes(),
-				})
-				.map_err(|e| {
-					Ics04Error::implementation_specific(format!("[on_recv_packet] {e:#?}"))
-				})?;
+						&token.denom,
+					) {
+						Pallet::<T>::record_unescrow(
+							packet.destination_channel.sequence(),
+							&denom,
+							token.amount.as_u256().low_u128(),
+						);
+					} else {
+						Pallet::<T>::record_voucher_mint(
+							packet.destination_channel.sequence(),
+							&denom,
+							token.amount.as_u256().low_u128(),
+						);
+					}
+					let local_asset_id =
+						T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom).ok();
+					if let Some(asset_id) = local_asset_id {
+						Pallet::<T>::register_placeholder_metadata(&prefixed_denom, asset_id);
+					}
+					Pallet::<T>::deposit_event(Event::<T>::TokenReceived {
+						from: packet_data.sender.clone(),
+						to: packet_data.receiver.clone(),
+						ibc_denom: denom.as_bytes().to_vec(),
+						local_asset_id,
+						amount: token.amount.as_u256().as_u128().into(),
+						is_receiver_source: is_receiver_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel,
+							&prefixed_denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+					});
+				}
 				Ics20Acknowledgement::success().to_string().into_bytes()
 			},
 		};
-		Ok(Acknowledgement::from_bytes(ack))
+		Ok(Some(Acknowledgement::from_bytes(ack)))
 	}
 
 	fn on_acknowledgement_packet(
 		&mut self,
 		_ctx: &dyn ModuleCallbackContext,
-		_output: &mut ModuleOutputBuilder,
+		output: &mut ModuleOutputBuilder,
 		packet: &mut Packet,
 		acknowledgement: &Acknowledgement,
 		_relayer: &Signer,
 	) -> Result<(), Ics04Error> {
 		let mut ctx = Context::<T>::default();
-		let packet_data: PacketData =
-			serde_json::from_slice(packet.data.as_slice()).map_err(|e| {
-				Ics04Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
-			})?;
+		let packet_data =
+			decode_packet_data(&ctx, &packet.source_port, packet.source_channel, &packet.data)
+				.map_err(|e| {
+					Ics04Error::implementation_specific(format!(
+						"Failed to decode packet data {e:?}"
+					))
+				})?;
 		let ack = serde_json::from_slice::<Ics20Acknowledgement>(acknowledgement.as_ref())
 			.map_err(|e| {
 				Ics04Error::implementation_specific(format!(
@@ -307,7 +406,7 @@ where
 				))
 			})?;
 		let sequence: u64 = packet.sequence.into();
-		process_ack_packet(&mut ctx, packet, &packet_data, &ack)
+		Self::atomically(|| process_ack_packet(&mut ctx, output, packet, &packet_data, &ack))
 			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))?;
 		match ack.into_result() {
 			Ok(_) => {
@@ -315,23 +414,29 @@ where
 					SequenceFee::<T>::remove(sequence);
 					Pallet::<T>::deposit_event(Event::<T>::ChargingFeeConfirmed { sequence });
 				}
-				Pallet::<T>::deposit_event(Event::<T>::TokenTransferCompleted {
-					from: packet_data.sender,
-					to: packet_data.receiver,
-					ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-						&packet_data.token.denom.to_string(),
-					)
-					.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_sender_source: is_sender_chain_source(
-						packet.source_port.clone(),
-						packet.source_channel,
-						&packet_data.token.denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				})
+				for token in &packet_data.tokens {
+					Pallet::<T>::deposit_event(Event::<T>::TokenTransferCompleted {
+						from: packet_data.sender.clone(),
+						to: packet_data.receiver.clone(),
+						ibc_denom: token.denom.to_string().as_bytes().to_vec(),
+						local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+							&token.denom.to_string(),
+						)
+						.ok(),
+						amount: token.amount.as_u256().as_u128().into(),
+						is_sender_source: is_sender_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel,
+							&token.denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+					})
+				}
 			},
 			Err(e) => {
 				log::trace!(
@@ -339,27 +444,34 @@ where
 					"error: acknowledgement error: {e}",
 				);
 				Self::refund_fee(packet, &packet_data)?;
+				Self::record_refunded_escrow(packet, &packet_data);
 				Pallet::<T>::deposit_event(Event::<T>::ChargingFeeFailedAcknowledgement {
 					sequence,
 				});
 
-				Pallet::<T>::deposit_event(Event::<T>::TokenTransferFailed {
-					from: packet_data.sender,
-					to: packet_data.receiver,
-					ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
-					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-						&packet_data.token.denom.to_string(),
-					)
-					.ok(),
-					amount: packet_data.token.amount.as_u256().as_u128().into(),
-					is_sender_source: is_sender_chain_source(
-						packet.source_port.clone(),
-						packet.source_channel,
-						&packet_data.token.denom,
-					),
-					source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-					destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-				})
+				for token in &packet_data.tokens {
+					Pallet::<T>::deposit_event(Event::<T>::TokenTransferFailed {
+						from: packet_data.sender.clone(),
+						to: packet_data.receiver.clone(),
+						ibc_denom: token.denom.to_string().as_bytes().to_vec(),
+						local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+							&token.denom.to_string(),
+						)
+						.ok(),
+						amount: token.amount.as_u256().as_u128().into(),
+						is_sender_source: is_sender_chain_source(
+							packet.source_port.clone(),
+							packet.source_channel,
+							&token.denom,
+						),
+						source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+						destination_channel: packet
+							.destination_channel
+							.to_string()
+							.as_bytes()
+							.to_vec(),
+					})
+				}
 			},
 		}
 
@@ -369,36 +481,42 @@ where
 	fn on_timeout_packet(
 		&mut self,
 		_ctx: &dyn ModuleCallbackContext,
-		_output: &mut ModuleOutputBuilder,
+		output: &mut ModuleOutputBuilder,
 		packet: &mut Packet,
 		_relayer: &Signer,
 	) -> Result<(), Ics04Error> {
 		let mut ctx = Context::<T>::default();
-		let packet_data: PacketData = serde_json::from_slice(packet.data.as_slice())
-			.map_err(|e| Ics04Error::app_module(format!("Failed to decode packet data {e:?}")))?;
-		process_timeout_packet(&mut ctx, packet, &packet_data)
+		let packet_data =
+			decode_packet_data(&ctx, &packet.source_port, packet.source_channel, &packet.data)
+				.map_err(|e| {
+					Ics04Error::app_module(format!("Failed to decode packet data {e:?}"))
+				})?;
+		Self::atomically(|| process_timeout_packet(&mut ctx, output, packet, &packet_data))
 			.map_err(|e| Ics04Error::app_module(e.to_string()))?;
 		let sequence: u64 = packet.sequence.into();
 		Self::refund_fee(packet, &packet_data)?;
+		Self::record_refunded_escrow(packet, &packet_data);
 		Pallet::<T>::deposit_event(Event::<T>::ChargingFeeTimeout { sequence });
 
-		Pallet::<T>::deposit_event(Event::<T>::TokenTransferTimeout {
-			from: packet_data.sender,
-			to: packet_data.receiver,
-			ibc_denom: packet_data.token.denom.to_string().as_bytes().to_vec(),
-			local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
-				&packet_data.token.denom.to_string(),
-			)
-			.ok(),
-			amount: packet_data.token.amount.as_u256().as_u128().into(),
-			is_sender_source: is_sender_chain_source(
-				packet.source_port.clone(),
-				packet.source_channel,
-				&packet_data.token.denom,
-			),
-			source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
-			destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
-		});
+		for token in &packet_data.tokens {
+			Pallet::<T>::deposit_event(Event::<T>::TokenTransferTimeout {
+				from: packet_data.sender.clone(),
+				to: packet_data.receiver.clone(),
+				ibc_denom: token.denom.to_string().as_bytes().to_vec(),
+				local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+					&token.denom.to_string(),
+				)
+				.ok(),
+				amount: token.amount.as_u256().as_u128().into(),
+				is_sender_source: is_sender_chain_source(
+					packet.source_port.clone(),
+					packet.source_channel,
+					&token.denom,
+				),
+				source_channel: packet.source_channel.to_string().as_bytes().to_vec(),
+				destination_channel: packet.destination_channel.to_string().as_bytes().to_vec(),
+			});
+		}
 		Ok(())
 	}
 }
@@ -409,6 +527,16 @@ where
 	u32: From<<T as frame_system::Config>::BlockNumber>,
 	AccountId32: From<<T as frame_system::Config>::AccountId>,
 {
+	/// Runs `f` in a storage transaction, so that the changes made for the tokens of a
+	/// multi-denom transfer are reverted if any of them fails.
+	fn atomically<R>(f: impl FnOnce() -> Result<R, Ics20Error>) -> Result<R, Ics20Error> {
+		with_transaction::<_, DispatchError, _>(|| match f() {
+			Ok(result) => TransactionOutcome::Commit(Ok(Ok(result))),
+			Err(e) => TransactionOutcome::Rollback(Ok(Err(e))),
+		})
+		.map_err(|e| Ics20Error::implementation_specific(format!("{e:?}")))?
+	}
+
 	/// Refunds the fee from the FeeAccount to the sender of the packet.
 	///
 	/// This function is called on `on_timeout_packet` and `on_acknowledgement_packet` in case of
@@ -430,7 +558,7 @@ where
 	///
 	/// - The fee cannot be refunded to the sender's account. ctx.send_coins failed.
 	/// - The sender's account cannot be parsed from the packet data.
-	fn refund_fee(packet: &Packet, packet_data: &PacketData) -> Result<(), Ics04Error> {
+	fn refund_fee(packet: &Packet, packet_data: &PacketDataV2) -> Result<(), Ics04Error> {
 		use ibc::bigint::U256;
 		use sp_core::Get;
 		let sequence: u64 = packet.sequence.into();
@@ -442,7 +570,10 @@ where
 		let fee_account = T::FeeAccount::get();
 
 		let mut ctx = Context::<T>::default();
-		let mut fee_coin = packet_data.token.clone();
+		// the fee is only charged on single token transfers
+		let mut fee_coin = packet_data.tokens.first().cloned().ok_or_else(|| {
+			Ics04Error::implementation_specific("Packet data has no tokens".to_string())
+		})?;
 
 		fee_coin.amount = U256::from(fee).into();
 
@@ -462,6 +593,24 @@ where
 			})?;
 		Ok(())
 	}
+
+	/// Records the tokens of a packet sent by this chain refunded from the escrow of its channel,
+	/// or minted back as vouchers.
+	fn record_refunded_escrow(packet: &Packet, packet_data: &PacketDataV2) {
+		for token in &packet_data.tokens {
+			let (channel, denom) = (packet.source_channel.sequence(), token.denom.to_string());
+			let amount = token.amount.as_u256().low_u128();
+			if is_sender_chain_source(
+				packet.source_port.clone(),
+				packet.source_channel,
+				&token.denom,
+			) {
+				Pallet::<T>::record_unescrow(channel, &denom, amount);
+			} else {
+				Pallet::<T>::record_voucher_mint(channel, &denom, amount);
+			}
+		}
+	}
 }
 
 pub struct WeightHandler<T: Config>(PhantomData<T>);
@@ -530,16 +679,96 @@ pub fn full_ibc_denom(packet: &Packet, mut token: PrefixedCoin) -> String {
 
 use ibc::applications::transfer::error::Error as Ics20Error;
 
+/// Outcome of the execution of the memo of a received transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoExecution {
+	/// The memo has been executed, the received packet can be acknowledged.
+	Completed,
+	/// The tokens have been forwarded over IBC, the received packet is acknowledged once the
+	/// packet sent on the transfer port of `channel` with `sequence` is.
+	Forwarded {
+		channel: u64,
+		sequence: u64,
+		/// Number of times the forward is sent again if it times out
+		retries: u64,
+	},
+}
+
 pub trait HandleMemo<T: Config> {
-	fn execute_memo(&self, packet: &Packet) -> Result<(), Ics20Error>;
+	fn execute_memo(&self, packet: &Packet) -> Result<MemoExecution, Ics20Error>;
+
+	/// Forwards again the tokens of the received `packet`, after its previous forward timed out.
+	fn retry_forward(&self, _packet: &Packet) -> Result<MemoExecution, Ics20Error> {
+		Err(Ics20Error::implementation_specific("Forwards can't be retried".to_string()))
+	}
+
+	/// Called once a transfer sent by this chain is acknowledged.
+	fn on_acknowledgement_packet(&self, _packet: &Packet, _ack: &[u8]) -> Result<(), Ics20Error> {
+		Ok(())
+	}
+
+	/// Called once a transfer sent by this chain times out.
+	fn on_timeout_packet(&self, _packet: &Packet) -> Result<(), Ics20Error> {
+		Ok(())
+	}
 }
 
 impl<T: Config> HandleMemo<T> for () {
-	fn execute_memo(&self, _packet: &Packet) -> Result<(), Ics20Error> {
+	fn execute_memo(&self, _packet: &Packet) -> Result<MemoExecution, Ics20Error> {
+		Ok(MemoExecution::Completed)
+	}
+}
+
+/// Limits the tokens sent over the transfer port, see [`crate::ics20_rate_limit`].
+pub trait Ics20RateLimiter {
+	/// Called before `tokens` are sent over `channel_id`, the transfer fails if an error is
+	/// returned.
+	fn on_send(channel_id: &ChannelId, tokens: &[PrefixedCoin]) -> Result<(), Ics20Error>;
+
+	/// Called when `tokens` received over `channel_id` are taken back to refund their sender,
+	/// because the packet they were received with is acknowledged asynchronously with an error.
+	fn on_receive_refunded(_channel_id: &ChannelId, _tokens: &[PrefixedCoin]) {}
+}
+
+impl Ics20RateLimiter for () {
+	fn on_send(_channel_id: &ChannelId, _tokens: &[PrefixedCoin]) -> Result<(), Ics20Error> {
 		Ok(())
 	}
 }
 
+/// A received packet whose tokens have been forwarded by its memo, waiting for the forwarded
+/// packet to be acknowledged or to time out.
+#[derive(
+	frame_support::RuntimeDebug,
+	PartialEq,
+	Eq,
+	scale_info::TypeInfo,
+	codec::Encode,
+	codec::Decode,
+	Clone,
+)]
+pub struct InFlightForward {
+	/// Protobuf encoded packet received from the previous hop
+	pub packet: Vec<u8>,
+	/// Data of the packet as seen by the memo handler, i.e. net of the service charge
+	pub data: Vec<u8>,
+	/// Number of times the forward is sent again if it times out
+	pub retries_remaining: u64,
+}
+
+impl InFlightForward {
+	pub fn new(packet: &Packet, data: Vec<u8>, retries_remaining: u64) -> Self {
+		Self { packet: RawPacket::from(packet.clone()).encode_to_vec(), data, retries_remaining }
+	}
+
+	/// Returns the packet received from the previous hop.
+	pub fn packet(&self) -> Result<Packet, Ics04Error> {
+		RawPacket::decode(&*self.packet)
+			.map_err(|e| Ics04Error::implementation_specific(format!("{e:?}")))
+			.and_then(Packet::try_from)
+	}
+}
+
 pub trait SubstrateMultihopXcmHandler {
 	type AccountId;
 
@@ -682,13 +911,42 @@ where
 	AccountId32: From<<T as frame_system::Config>::AccountId>,
 	u128: From<T::AssetId>,
 {
-	fn execute_memo(&self, packet: &Packet) -> Result<(), Ics20Error> {
+	fn execute_memo(&self, packet: &Packet) -> Result<MemoExecution, Ics20Error> {
 		self.inner.execute_memo(packet)?;
+		self.forward(packet)
+	}
 
-		let packet_data: PacketData =
-			serde_json::from_slice(packet.data.as_slice()).map_err(|e| {
-				Ics20Error::implementation_specific(format!("Failed to decode packet data {:?}", e))
-			})?;
+	fn retry_forward(&self, packet: &Packet) -> Result<MemoExecution, Ics20Error> {
+		self.forward(packet)
+	}
+
+	fn on_acknowledgement_packet(&self, packet: &Packet, ack: &[u8]) -> Result<(), Ics20Error> {
+		self.inner.on_acknowledgement_packet(packet, ack)
+	}
+
+	fn on_timeout_packet(&self, packet: &Packet) -> Result<(), Ics20Error> {
+		self.inner.on_timeout_packet(packet)
+	}
+}
+
+impl<T, H> IbcMemoHandler<H, T>
+where
+	T: Config + Send + Sync + pallet_timestamp::Config,
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+	u128: From<T::AssetId>,
+{
+	/// Forwards the tokens of the received `packet` as its memo says.
+	fn forward(&self, packet: &Packet) -> Result<MemoExecution, Ics20Error> {
+		let packet_data = decode_packet_data(
+			&Context::<T>::default(),
+			&packet.destination_port,
+			packet.destination_channel,
+			&packet.data,
+		)
+		.map_err(|e| {
+			Ics20Error::implementation_specific(format!("Failed to decode packet data {:?}", e))
+		})?;
 		let receiver = <T as Config>::AccountIdConversion::try_from(packet_data.receiver.clone())
 			.map_err(|_| {
 				Ics20Error::implementation_specific(format!("Failed to parse receiver account"))
@@ -697,9 +955,18 @@ where
 
 		//Handle only memo with IBC forward.
 		if packet_data.memo.is_empty() {
-			return Ok(())
+			return Ok(MemoExecution::Completed)
 		}
 
+		// memos only forward single token transfers
+		let token = match packet_data.tokens.as_slice() {
+			[token] => token.clone(),
+			_ =>
+				return Err(Ics20Error::implementation_specific(
+					"memo of a multi-denom transfer can't be executed".to_string(),
+				)),
+		};
+
 		crate::Pallet::<T>::deposit_event(Event::<T>::ExecuteMemoStarted {
 			account_id: receiver.clone(),
 			memo: Some(packet_data.memo.clone()),
@@ -709,7 +976,7 @@ where
 			IbcModule::<T>::emit_memo_execution_failed_event(
 				receiver.clone(),
 				packet_data.memo.clone(),
-				0,
+				MemoFailureReason::InvalidMemo,
 			);
 			Ics20Error::implementation_specific(format!(
 				"Failed to parse memo : {:?} ",
@@ -720,16 +987,16 @@ where
 		let prefixed_coin = if is_receiver_chain_source(
 			packet.source_port.clone(),
 			packet.source_channel,
-			&packet_data.token.denom,
+			&token.denom,
 		) {
 			let prefix = TracePrefix::new(packet.source_port.clone(), packet.source_channel);
-			let mut c = packet_data.token.clone();
+			let mut c = token.clone();
 			c.denom.remove_trace_prefix(&prefix);
 			c
 		} else {
 			let prefix =
 				TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
-			let mut c = packet_data.token.clone();
+			let mut c = token.clone();
 			c.denom.add_trace_prefix(prefix);
 			c
 		};
@@ -741,17 +1008,21 @@ where
 			)
 			.map_err(|_| {
 				log::warn!(target: "pallet_ibc", "Asset does not exist for denom: {}", prefixed_coin.denom.to_string());
-				IbcModule::<T>::emit_memo_execution_failed_event(receiver.clone(), packet_data.memo.clone(), 1);
+				IbcModule::<T>::emit_memo_execution_failed_event(
+					receiver.clone(),
+					packet_data.memo.clone(),
+					MemoFailureReason::UnknownAsset,
+				);
 				Ics20Error::implementation_specific("asset does not exist".to_string())
 			})?;
 
-		let amount = packet_data.token.amount.as_u256().low_u128();
+		let amount = token.amount.as_u256().low_u128();
 
 		let memo_forward = memo.forward.get_memo().map_err(|_| {
 			IbcModule::<T>::emit_memo_execution_failed_event(
 				receiver.clone(),
 				packet_data.memo.clone(),
-				2,
+				MemoFailureReason::IncompleteForward,
 			);
 			Ics20Error::implementation_specific("Failed to get memo".to_string())
 		})?;
@@ -763,7 +1034,7 @@ where
 					IbcModule::<T>::emit_memo_execution_failed_event(
 						receiver.clone(),
 						packet_data.memo.clone(),
-						11,
+						MemoFailureReason::MissingXcmReceiverPrefix,
 					);
 					Ics20Error::implementation_specific("failed strip_prefix.".to_string())
 				})?;
@@ -772,7 +1043,7 @@ where
 					IbcModule::<T>::emit_memo_execution_failed_event(
 						receiver.clone(),
 						packet_data.memo.clone(),
-						12,
+						MemoFailureReason::InvalidXcmReceiverHex,
 					);
 					Ics20Error::implementation_specific("hex::decode".to_string())
 				})?;
@@ -781,7 +1052,7 @@ where
 					IbcModule::<T>::emit_memo_execution_failed_event(
 						receiver.clone(),
 						packet_data.memo.clone(),
-						13,
+						MemoFailureReason::InvalidXcmReceiverAccount,
 					);
 					Ics20Error::implementation_specific("T::AccountId::decode".to_string())
 				})?;
@@ -814,7 +1085,7 @@ where
 					asset_id: asset_id.clone(),
 				});
 
-				return Ok(())
+				return Ok(MemoExecution::Completed)
 			},
 		};
 
@@ -832,7 +1103,7 @@ where
 				IbcModule::<T>::emit_memo_execution_failed_event(
 					receiver.clone(),
 					packet_data.memo.clone(),
-					3,
+					MemoFailureReason::MissingChannelId,
 				);
 				Ics20Error::implementation_specific(format!(
 					"Failed to extract channel number from channel ID: {:?}",
@@ -844,7 +1115,7 @@ where
 				IbcModule::<T>::emit_memo_execution_failed_event(
 					receiver.clone(),
 					packet_data.memo.clone(),
-					4,
+					MemoFailureReason::InvalidChannelId,
 				);
 				Ics20Error::implementation_specific("Failed to parse channel ID".to_string())
 			})?;
@@ -864,7 +1135,7 @@ where
 				IbcModule::<T>::emit_memo_execution_failed_event(
 					receiver.clone(),
 					packet_data.memo.clone(),
-					5,
+					MemoFailureReason::InvalidNextMemo,
 				);
 				Ics20Error::implementation_specific("failed to try_from memo".to_string())
 			})?;
@@ -872,6 +1143,12 @@ where
 			next_memo = Some(memo_result);
 		}
 
+		// the sequence of the forwarded packet, to find the received packet back once it's
+		// acknowledged
+		let sequence = Context::<T>::default()
+			.get_next_sequence_send(&(PortId::transfer(), ChannelId::new(channel_id)))
+			.map_err(Ics20Error::ics04_channel)?;
+
 		crate::Pallet::<T>::transfer(
 			origin.into(),
 			params,
@@ -901,7 +1178,11 @@ where
 			channel: channel_id,
 			next_memo,
 		});
-		Ok(())
+		Ok(MemoExecution::Forwarded {
+			channel: channel_id,
+			sequence: sequence.into(),
+			retries: memo_forward.retries,
+		})
 	}
 }
 
@@ -912,13 +1193,49 @@ where
 	AccountId32: From<<T as frame_system::Config>::AccountId>,
 {
 	//function that takes account and memo and emit event that memo execution failed
-	fn emit_memo_execution_failed_event(account: T::AccountId, memo: String, reason: u8) {
+	fn emit_memo_execution_failed_event(
+		account: T::AccountId,
+		memo: String,
+		reason: MemoFailureReason,
+	) {
 		crate::Pallet::<T>::deposit_event(
-			Event::<T>::ExecuteMemoIbcTokenTransferFailedWithReason { from: account, memo, reason },
+			Event::<T>::ExecuteMemoIbcTokenTransferFailedWithReason {
+				from: account,
+				memo,
+				reason: reason as u8,
+			},
 		);
 	}
 }
 
+/// Reason of the failure of a memo execution, emitted as the `reason` of
+/// [`Event::ExecuteMemoIbcTokenTransferFailedWithReason`]. The codes are part of the events'
+/// encoding so they must not change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MemoFailureReason {
+	/// The memo isn't valid json of a known memo
+	InvalidMemo = 0,
+	/// No asset is registered for the denom of the received tokens
+	UnknownAsset = 1,
+	/// The IBC forward of the memo misses its port, channel, timeout or retries
+	IncompleteForward = 2,
+	/// The channel of an IBC forward has no channel number
+	MissingChannelId = 3,
+	/// The channel number of an IBC forward isn't a number
+	InvalidChannelId = 4,
+	/// The memo to send along with an IBC forward isn't a valid memo of this chain
+	InvalidNextMemo = 5,
+	/// The call or contract execution of an ibc-hooks memo failed
+	HookExecutionFailed = 6,
+	/// The receiver of an XCM forward isn't prefixed with `0x`
+	MissingXcmReceiverPrefix = 11,
+	/// The receiver of an XCM forward isn't hex encoded
+	InvalidXcmReceiverHex = 12,
+	/// The receiver of an XCM forward isn't an account of the destination
+	InvalidXcmReceiverAccount = 13,
+}
+
 pub trait ValidateMemo {
 	fn validate(&self) -> Result<(), String>;
 }
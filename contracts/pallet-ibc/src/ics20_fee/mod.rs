@@ -7,12 +7,13 @@ use core::{fmt::Debug, marker::PhantomData, str::FromStr};
 use ibc::{
 	applications::transfer::{
 		acknowledgement::Acknowledgement as Ics20Ack, context::BankKeeper,
-		is_receiver_chain_source, packet::PacketData, TracePrefix,
+		is_receiver_chain_source, packet::PacketData, relay::channel_app_version, TracePrefix,
 	},
 	bigint::U256,
 	core::{
 		ics04_channel::{
 			channel::{Counterparty, Order},
+			context::ChannelReader,
 			error::Error as Ics04Error,
 			msgs::acknowledgement::Acknowledgement,
 			packet::Packet,
@@ -289,22 +290,99 @@ where
 		self.inner.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)
 	}
 
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<Version, Ics04Error> {
+		self.inner.on_chan_upgrade_init(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		counterparty_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		self.inner.on_chan_upgrade_try(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			counterparty_version,
+		)
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Ics04Error> {
+		self.inner
+			.on_chan_upgrade_ack(ctx, output, port_id, channel_id, counterparty_version)
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_upgrade_open(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
 	fn on_recv_packet(
 		&self,
 		_ctx: &dyn ModuleCallbackContext,
 		output: &mut ModuleOutputBuilder,
 		packet: &mut Packet,
 		relayer: &Signer,
-	) -> Result<Acknowledgement, Ics04Error> {
+	) -> Result<Option<Acknowledgement>, Ics04Error> {
 		// Module ModuleCallbackContext does not have the ics20 context as part of its trait bounds
 		// so we define a new context
 		let mut ctx = Context::<T>::default();
 		// We want the whole chain of calls to fail only if the ics20 transfer fails, because
 		// the other modules are not part of ics-20 standard
 		let ack = self.inner.on_recv_packet(&ctx, output, packet, relayer)?;
-		let _ = Self::process_fee(&mut ctx, packet, &ack).map_err(|e| {
-			log::error!(target: "pallet_ibc", "Error processing fee: {:?}", e);
-		});
+		if let Some(ack) = &ack {
+			let _ = Self::process_fee(&mut ctx, packet, ack).map_err(|e| {
+				log::error!(target: "pallet_ibc", "Error processing fee: {:?}", e);
+			});
+		}
 		Ok(ack)
 	}
 
@@ -343,6 +421,13 @@ where
 		packet: &mut Packet,
 		ack: &Acknowledgement,
 	) -> Result<(), Ics04Error> {
+		// the service charge is only taken on single token (`ics20-1`) transfers
+		let channel_end =
+			ctx.channel_end(&(packet.destination_port.clone(), packet.destination_channel))?;
+		if channel_app_version(channel_end.version()) != Version::ics20() {
+			return Ok(())
+		}
+
 		let mut packet_data = serde_json::from_slice::<PacketData>(packet.data.as_slice())
 			.map_err(|e| {
 				Ics04Error::implementation_specific(format!("Failed to decode packet data {e:?}"))
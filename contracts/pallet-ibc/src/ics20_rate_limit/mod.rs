@@ -0,0 +1,670 @@
+use crate::{ics20::full_ibc_denom, routing::Context, DenomToAssetId};
+use alloc::{
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData, str::FromStr};
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::{fungibles::Inspect, Currency, Get, UnixTime},
+};
+use ibc::{
+	applications::transfer::{
+		acknowledgement::Acknowledgement as Ics20Ack, error::Error as Ics20Error,
+		relay::decode_packet_data, PrefixedCoin,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module as IbcModule, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use sp_core::crypto::AccountId32;
+use sp_runtime::{traits::SaturatedConversion, DispatchError};
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use alloc::vec::Vec;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::OriginFor;
+	use sp_runtime::Perbill;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + crate::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub (super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	/// Maximum net amount of an asset flowing through a channel during a window.
+	#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub enum Quota {
+		/// Percentage of the supply of the asset at the start of the window
+		Percent(Perbill),
+		/// Amount of the asset
+		Absolute(u128),
+	}
+
+	impl Quota {
+		/// Returns the amount allowed by the quota, given the supply of the asset.
+		pub fn amount(&self, supply: u128) -> u128 {
+			match self {
+				Quota::Percent(percent) => percent.mul_floor(supply),
+				Quota::Absolute(amount) => *amount,
+			}
+		}
+	}
+
+	/// Quotas of an asset on a channel. The flows are counted over windows of `period` seconds,
+	/// starting with the first transfer after the previous window elapsed.
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct RateLimit {
+		/// Quota of the tokens received net of the tokens sent, unlimited if `None`
+		pub max_inflow: Option<Quota>,
+		/// Quota of the tokens sent net of the tokens received, unlimited if `None`
+		pub max_outflow: Option<Quota>,
+		/// Duration of the windows, in seconds
+		pub period: u64,
+	}
+
+	/// Amounts of an asset which flowed through a channel during the current window.
+	#[derive(Clone, Default, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct Flow {
+		pub inflow: u128,
+		pub outflow: u128,
+		/// Supply of the asset when the window started
+		pub supply: u128,
+		/// Unix timestamp, in seconds, at which the window ends
+		pub period_end: u64,
+	}
+
+	#[derive(Clone, Copy, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub enum FlowDirection {
+		In,
+		Out,
+	}
+
+	/// Rate limit of an asset on a channel along with its current usage.
+	#[derive(Clone, PartialEq, Eq, Encode, Decode, RuntimeDebug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+	pub struct RateLimitUsage {
+		pub channel: u64,
+		pub denom: Vec<u8>,
+		pub rate_limit: RateLimit,
+		pub flow: Flow,
+	}
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (channel sequence, denom) => quotas of the asset on the transfer port of the channel
+	pub type RateLimits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		Vec<u8>,
+		RateLimit,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (channel sequence, denom) => amounts of the asset which flowed through the channel during
+	/// the current window
+	pub type Flows<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, u64, Blake2_128Concat, Vec<u8>, Flow, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub (super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		RateLimitSet {
+			channel: u64,
+			denom: Vec<u8>,
+			rate_limit: RateLimit,
+		},
+		RateLimitRemoved {
+			channel: u64,
+			denom: Vec<u8>,
+		},
+		FlowReset {
+			channel: u64,
+			denom: Vec<u8>,
+		},
+		/// A transfer has been rejected because it would exceed a quota
+		RateLimitExceeded {
+			channel: u64,
+			denom: Vec<u8>,
+			direction: FlowDirection,
+			amount: u128,
+			flow: Flow,
+		},
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The period of a rate limit must not be zero
+		InvalidPeriod,
+		/// The transfer would exceed the quota of the asset on the channel
+		RateLimitExceeded,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Sets the quotas of an asset, identified by its denom on this chain, on the transfer
+		/// port of a channel. The flows of the current window are reset.
+		#[pallet::call_index(0)]
+		#[pallet::weight(0)]
+		pub fn set_rate_limit(
+			origin: OriginFor<T>,
+			channel: u64,
+			denom: Vec<u8>,
+			rate_limit: RateLimit,
+		) -> DispatchResult {
+			<T as crate::Config>::AdminOrigin::ensure_origin(origin)?;
+			ensure!(rate_limit.period > 0, Error::<T>::InvalidPeriod);
+			RateLimits::<T>::insert(channel, &denom, rate_limit.clone());
+			Flows::<T>::remove(channel, &denom);
+			Self::deposit_event(Event::<T>::RateLimitSet { channel, denom, rate_limit });
+			Ok(())
+		}
+
+		#[pallet::call_index(1)]
+		#[pallet::weight(0)]
+		pub fn remove_rate_limit(
+			origin: OriginFor<T>,
+			channel: u64,
+			denom: Vec<u8>,
+		) -> DispatchResult {
+			<T as crate::Config>::AdminOrigin::ensure_origin(origin)?;
+			RateLimits::<T>::remove(channel, &denom);
+			Flows::<T>::remove(channel, &denom);
+			Self::deposit_event(Event::<T>::RateLimitRemoved { channel, denom });
+			Ok(())
+		}
+
+		#[pallet::call_index(2)]
+		#[pallet::weight(0)]
+		pub fn reset_flow(origin: OriginFor<T>, channel: u64, denom: Vec<u8>) -> DispatchResult {
+			<T as crate::Config>::AdminOrigin::ensure_origin(origin)?;
+			Flows::<T>::remove(channel, &denom);
+			Self::deposit_event(Event::<T>::FlowReset { channel, denom });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the rate limit of an asset on a channel along with its current usage.
+	pub fn rate_limit_usage(channel: u64, denom: Vec<u8>) -> Option<RateLimitUsage> {
+		let rate_limit = RateLimits::<T>::get(channel, &denom)?;
+		let flow = Flows::<T>::get(channel, &denom);
+		Some(RateLimitUsage { channel, denom, rate_limit, flow })
+	}
+
+	/// Returns all the rate limits along with their current usage.
+	pub fn rate_limits() -> Vec<RateLimitUsage> {
+		RateLimits::<T>::iter()
+			.map(|(channel, denom, rate_limit)| {
+				let flow = Flows::<T>::get(channel, &denom);
+				RateLimitUsage { channel, denom, rate_limit, flow }
+			})
+			.collect()
+	}
+
+	fn supply(denom: &str) -> u128 {
+		let Ok(asset_id) =
+			<T as crate::Config>::IbcDenomToAssetIdConversion::from_denom_to_asset_id(denom)
+		else {
+			return 0
+		};
+		if asset_id == <T as crate::Config>::NativeAssetId::get() {
+			<T as crate::Config>::NativeCurrency::total_issuance().saturated_into()
+		} else {
+			<T as crate::Config>::Fungibles::total_issuance(asset_id).saturated_into()
+		}
+	}
+
+	/// Records `amount` of `denom` flowing through `channel`, failing if the net flow would
+	/// exceed the quota of the asset in that direction.
+	pub fn record_flow(
+		channel: u64,
+		denom: &str,
+		amount: u128,
+		direction: FlowDirection,
+	) -> Result<(), Error<T>> {
+		Self::try_record_flow(channel, denom, amount, direction).map_err(|flow| {
+			Self::rate_limit_exceeded(channel, denom, amount, direction, flow);
+			Error::<T>::RateLimitExceeded
+		})
+	}
+
+	/// Undoes the flow of `amount` of `denom` through `channel`, for transfers which failed.
+	pub fn undo_flow(channel: u64, denom: &str, amount: u128, direction: FlowDirection) {
+		let key = denom.as_bytes().to_vec();
+		if !RateLimits::<T>::contains_key(channel, &key) {
+			return
+		}
+		Flows::<T>::mutate(channel, key, |flow| match direction {
+			FlowDirection::In => flow.inflow = flow.inflow.saturating_sub(amount),
+			FlowDirection::Out => flow.outflow = flow.outflow.saturating_sub(amount),
+		});
+	}
+
+	/// Records the flows of several tokens, or none of them if one exceeds its quota.
+	fn record_flows(
+		channel: u64,
+		flows: &[(String, u128)],
+		direction: FlowDirection,
+	) -> Result<(), Error<T>> {
+		let exceeded = with_transaction::<_, DispatchError, _>(|| {
+			for (denom, amount) in flows {
+				if let Err(flow) = Self::try_record_flow(channel, denom, *amount, direction) {
+					return TransactionOutcome::Rollback(Ok(Some((denom, *amount, flow))))
+				}
+			}
+			TransactionOutcome::Commit(Ok(None))
+		})
+		.map_err(|_| Error::<T>::RateLimitExceeded)?;
+		// the event is deposited once the flows recorded before have been rolled back
+		match exceeded {
+			Some((denom, amount, flow)) => {
+				Self::rate_limit_exceeded(channel, denom, amount, direction, flow);
+				Err(Error::<T>::RateLimitExceeded)
+			},
+			None => Ok(()),
+		}
+	}
+
+	/// Records the flow, returning the flow it would have resulted in if it exceeds the quota.
+	fn try_record_flow(
+		channel: u64,
+		denom: &str,
+		amount: u128,
+		direction: FlowDirection,
+	) -> Result<(), Flow> {
+		let key = denom.as_bytes().to_vec();
+		let Some(rate_limit) = RateLimits::<T>::get(channel, &key) else { return Ok(()) };
+		let now = <T as crate::Config>::TimeProvider::now().as_secs();
+		let mut flow = Flows::<T>::get(channel, &key);
+		if now >= flow.period_end {
+			flow = Flow {
+				inflow: 0,
+				outflow: 0,
+				supply: Self::supply(denom),
+				period_end: now.saturating_add(rate_limit.period),
+			};
+		}
+		let (net_flow, quota) = match direction {
+			FlowDirection::In => {
+				flow.inflow = flow.inflow.saturating_add(amount);
+				(flow.inflow.saturating_sub(flow.outflow), rate_limit.max_inflow)
+			},
+			FlowDirection::Out => {
+				flow.outflow = flow.outflow.saturating_add(amount);
+				(flow.outflow.saturating_sub(flow.inflow), rate_limit.max_outflow)
+			},
+		};
+		if matches!(quota, Some(quota) if net_flow > quota.amount(flow.supply)) {
+			return Err(flow)
+		}
+		Flows::<T>::insert(channel, key, flow);
+		Ok(())
+	}
+
+	fn rate_limit_exceeded(
+		channel: u64,
+		denom: &str,
+		amount: u128,
+		direction: FlowDirection,
+		flow: Flow,
+	) {
+		Self::deposit_event(Event::<T>::RateLimitExceeded {
+			channel,
+			denom: denom.as_bytes().to_vec(),
+			direction,
+			amount,
+			flow,
+		});
+	}
+}
+
+impl<T: Config> crate::ics20::Ics20RateLimiter for Pallet<T> {
+	fn on_send(channel_id: &ChannelId, tokens: &[PrefixedCoin]) -> Result<(), Ics20Error> {
+		let flows = tokens
+			.iter()
+			.map(|token| (token.denom.to_string(), token.amount.as_u256().low_u128()))
+			.collect::<Vec<_>>();
+		Self::record_flows(channel_id.sequence(), &flows, FlowDirection::Out).map_err(|_| {
+			Ics20Error::implementation_specific("Transfer exceeds the rate limit".to_string())
+		})
+	}
+
+	fn on_receive_refunded(channel_id: &ChannelId, tokens: &[PrefixedCoin]) {
+		for token in tokens {
+			Self::undo_flow(
+				channel_id.sequence(),
+				&token.denom.to_string(),
+				token.amount.as_u256().low_u128(),
+				FlowDirection::In,
+			);
+		}
+	}
+}
+
+/// Middleware wrapping the ICS20 application to enforce the rate limits of the assets received.
+/// The tokens sent are limited by the pallet used as [`crate::Config::Ics20RateLimiter`], and
+/// their flow is undone by this middleware if they're refunded. The inflow of tokens refunded by
+/// an asynchronous acknowledgement is undone by the pallet, once the tokens are taken back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ics20RateLimit<T: Config, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> {
+	inner: S,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> Default
+	for Ics20RateLimit<T, S>
+{
+	fn default() -> Self {
+		Self { inner: S::default(), _phantom: Default::default() }
+	}
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug>
+	Ics20RateLimit<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	/// Returns the denoms on this chain and the amounts of the tokens of `packet`, sent by this
+	/// chain if `sent`, received otherwise.
+	fn packet_flows(packet: &Packet, sent: bool) -> Option<(u64, Vec<(String, u128)>)> {
+		let ctx = Context::<T>::default();
+		let (port_id, channel_id) = if sent {
+			(&packet.source_port, packet.source_channel)
+		} else {
+			(&packet.destination_port, packet.destination_channel)
+		};
+		let packet_data = decode_packet_data(&ctx, port_id, channel_id, &packet.data).ok()?;
+		let flows = packet_data
+			.tokens
+			.into_iter()
+			.map(|token| {
+				let amount = token.amount.as_u256().low_u128();
+				let denom =
+					if sent { token.denom.to_string() } else { full_ibc_denom(packet, token) };
+				(denom, amount)
+			})
+			.collect();
+		Some((channel_id.sequence(), flows))
+	}
+
+	fn is_successful(ack: &[u8]) -> bool {
+		Ics20Ack::from_str(&String::from_utf8_lossy(ack))
+			.map(|ack| ack.is_successful())
+			.unwrap_or(false)
+	}
+}
+
+impl<T: Config + Send + Sync, S: IbcModule + Clone + Default + PartialEq + Eq + Debug> IbcModule
+	for Ics20RateLimit<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_open_init(
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		self.inner.on_chan_open_try(
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			counterparty_version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner
+			.on_chan_open_ack(ctx, output, port_id, channel_id, counterparty_version, relayer)
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_open_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_close_init(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<Version, Ics04Error> {
+		self.inner.on_chan_upgrade_init(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		counterparty_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		self.inner.on_chan_upgrade_try(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			counterparty_version,
+		)
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Ics04Error> {
+		self.inner
+			.on_chan_upgrade_ack(ctx, output, port_id, channel_id, counterparty_version)
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_upgrade_open(
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_recv_packet(
+		&self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<Option<Acknowledgement>, Ics04Error> {
+		// packets which can't be decoded are rejected by the inner module
+		let Some((channel, flows)) = Self::packet_flows(packet, false) else {
+			return self.inner.on_recv_packet(ctx, output, packet, relayer)
+		};
+		if let Err(e) = Pallet::<T>::record_flows(channel, &flows, FlowDirection::In) {
+			log::debug!(target: "pallet_ibc", "[on_recv_packet]: rate limit exceeded: {:?}", e);
+			return Ok(Some(Acknowledgement::from_bytes(
+				Ics20Ack::from_error(Ics20Error::implementation_specific(
+					"Transfer exceeds the rate limit".to_string(),
+				))
+				.to_string()
+				.into_bytes(),
+			)))
+		}
+		let ack = self.inner.on_recv_packet(ctx, output, packet, relayer)?;
+		if matches!(&ack, Some(ack) if !Self::is_successful(ack.as_ref())) {
+			for (denom, amount) in &flows {
+				Pallet::<T>::undo_flow(channel, denom, *amount, FlowDirection::In);
+			}
+		}
+		Ok(ack)
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &Acknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner
+			.on_acknowledgement_packet(ctx, output, packet, acknowledgement, relayer)?;
+		// the tokens have been refunded to the sender
+		if !Self::is_successful(acknowledgement.as_ref()) {
+			if let Some((channel, flows)) = Self::packet_flows(packet, true) {
+				for (denom, amount) in &flows {
+					Pallet::<T>::undo_flow(channel, denom, *amount, FlowDirection::Out);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_timeout_packet(ctx, output, packet, relayer)?;
+		if let Some((channel, flows)) = Self::packet_flows(packet, true) {
+			for (denom, amount) in &flows {
+				Pallet::<T>::undo_flow(channel, denom, *amount, FlowDirection::Out);
+			}
+		}
+		Ok(())
+	}
+}
@@ -5,6 +5,7 @@
 //! just to recalculate its root hash.
 
 pub mod acknowledgements;
+pub mod channel_upgrades;
 pub mod channels;
 pub mod client_states;
 pub mod clients;
@@ -14,4 +15,5 @@ pub mod next_seq_ack;
 pub mod next_seq_recv;
 pub mod next_seq_send;
 pub mod packet_commitments;
+pub mod pagination;
 pub mod receipts;
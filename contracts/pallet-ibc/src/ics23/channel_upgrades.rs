@@ -0,0 +1,29 @@
+use crate::{format, Config};
+use frame_support::storage::{child, child::ChildInfo};
+use ibc::core::ics24_host::path::ChannelUpgradePath;
+use ibc_primitives::apply_prefix;
+use sp_core::Get;
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// ChannelUpgradePath => Upgrade | ErrorReceipt
+/// trie key paths: "channelUpgrades/upgrades/ports/{port_id}/channels/{channel_id}",
+/// "channelUpgrades/upgradeError/ports/{port_id}/channels/{channel_id}" and
+/// "channelUpgrades/counterpartyUpgrade/ports/{port_id}/channels/{channel_id}"
+pub struct ChannelUpgrades<T>(PhantomData<T>);
+
+impl<T: Config> ChannelUpgrades<T> {
+	pub fn get(path: ChannelUpgradePath) -> Option<Vec<u8>> {
+		let upgrade_key = apply_prefix(T::PalletPrefix::get(), vec![format!("{path}")]);
+		child::get(&ChildInfo::new_default(T::PalletPrefix::get()), &upgrade_key)
+	}
+
+	pub fn insert(path: ChannelUpgradePath, value: Vec<u8>) {
+		let upgrade_key = apply_prefix(T::PalletPrefix::get(), vec![format!("{path}")]);
+		child::put(&ChildInfo::new_default(T::PalletPrefix::get()), &upgrade_key, &value)
+	}
+
+	pub fn remove(path: ChannelUpgradePath) {
+		let upgrade_key = apply_prefix(T::PalletPrefix::get(), vec![format!("{path}")]);
+		child::kill(&ChildInfo::new_default(T::PalletPrefix::get()), &upgrade_key)
+	}
+}
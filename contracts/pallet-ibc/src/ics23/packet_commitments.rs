@@ -53,6 +53,19 @@ impl<T: Config> PacketCommitment<T> {
 		child::exists(&ChildInfo::new_default(T::PalletPrefix::get()), &commitment_key)
 	}
 
+	/// Returns the sequences of the packet commitments stored for the given channel.
+	pub fn sequences(port_id: PortId, channel_id: ChannelId) -> impl Iterator<Item = Sequence> {
+		let prefix = format!("commitments/ports/{port_id}/channels/{channel_id}/sequences/");
+		let prefix_key = apply_prefix(T::PalletPrefix::get(), vec![prefix]);
+		ChildTriePrefixIterator::with_prefix(
+			&ChildInfo::new_default(T::PalletPrefix::get()),
+			&prefix_key,
+		)
+		.filter_map(|(remaining_key, _): (Vec<u8>, Vec<u8>)| {
+			String::from_utf8(remaining_key).ok()?.parse().ok()
+		})
+	}
+
 	// WARNING: too expensive to be called from an on-chain context, only here for rpc layer.
 	pub fn iter() -> impl Iterator<Item = ((PortId, ChannelId, Sequence), Vec<u8>)> {
 		let prefix = "commitments/ports/".to_string();
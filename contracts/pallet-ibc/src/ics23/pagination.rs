@@ -0,0 +1,92 @@
+use crate::{format, Config};
+use alloc::{boxed::Box, string::String};
+use frame_support::storage::{child, child::ChildInfo};
+use ibc_primitives::{apply_prefix, PageRequest, PageResponse, MAX_PAGE_LIMIT};
+use sp_core::Get;
+use sp_std::prelude::*;
+
+/// Keys of the child trie under a prefix in ascending order, starting from a cursor.
+struct PrefixKeys {
+	child_info: ChildInfo,
+	prefix: Vec<u8>,
+	next: Option<Vec<u8>>,
+}
+
+impl PrefixKeys {
+	fn new(child_info: ChildInfo, prefix: Vec<u8>, start: Vec<u8>) -> Self {
+		let next = if child::exists(&child_info, &start) {
+			Some(start)
+		} else {
+			sp_io::default_child_storage::next_key(child_info.storage_key(), &start)
+		}
+		.filter(|key| key.starts_with(&prefix));
+		Self { child_info, prefix, next }
+	}
+}
+
+impl Iterator for PrefixKeys {
+	type Item = Vec<u8>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		let key = self.next.take()?;
+		self.next = sp_io::default_child_storage::next_key(self.child_info.storage_key(), &key)
+			.filter(|next| next.starts_with(&self.prefix));
+		Some(key)
+	}
+}
+
+// WARNING: too expensive to be called from an on-chain context, only here for rpc layer.
+/// Returns a page of the items stored under the trie key path `prefix`, along with the pagination
+/// of the response. `decode` is given the trie key path and the value of every entry under the
+/// prefix, only the entries it decodes count as items of the page.
+///
+/// Iterating in ascending order only walks the entries of the page, starting from the key of the
+/// request, while iterating in descending order walks all the keys under the prefix.
+pub fn paginate<T: Config, I>(
+	prefix: &str,
+	page: PageRequest,
+	decode: impl Fn(&str, Vec<u8>) -> Option<I>,
+) -> (Vec<I>, PageResponse) {
+	let child_info = ChildInfo::new_default(T::PalletPrefix::get());
+	let base_len = T::PalletPrefix::get().len();
+	let prefix_key = apply_prefix(T::PalletPrefix::get(), vec![prefix.into()]);
+	let PageRequest { key, offset, limit, count_total, reverse } = page;
+	let limit = if limit == 0 { MAX_PAGE_LIMIT } else { limit.min(MAX_PAGE_LIMIT) };
+	let from_key = !key.is_empty();
+
+	let keys: Box<dyn Iterator<Item = Vec<u8>>> = if from_key && !key.starts_with(&prefix_key) {
+		Box::new(core::iter::empty())
+	} else if reverse {
+		let mut keys =
+			PrefixKeys::new(child_info.clone(), prefix_key.clone(), prefix_key).collect::<Vec<_>>();
+		if from_key {
+			keys.retain(|k| k <= &key);
+		}
+		Box::new(keys.into_iter().rev())
+	} else {
+		let start = if from_key { key } else { prefix_key.clone() };
+		Box::new(PrefixKeys::new(child_info.clone(), prefix_key, start))
+	};
+	let mut items = keys.filter_map(|key| {
+		let path = String::from_utf8(key.get(base_len..)?.to_vec()).ok()?;
+		let value = child::get_raw(&child_info, &key)?;
+		Some((key, decode(&path, value)?))
+	});
+
+	let skipped = if from_key { 0 } else { items.by_ref().take(offset as usize).count() as u64 };
+	let page = items.by_ref().take(limit as usize).map(|(_, item)| item).collect::<Vec<_>>();
+	let next = items.next().map(|(key, _)| key);
+	let total = if count_total && !from_key {
+		skipped + page.len() as u64 + next.iter().count() as u64 + items.count() as u64
+	} else {
+		0
+	};
+
+	(page, PageResponse { next_key: next.unwrap_or_default(), total })
+}
+
+/// Returns the trie key path prefix of the items of the given channel under `path`, e.g.
+/// `commitments`.
+pub fn channel_prefix(path: &str, port_id: &str, channel_id: &str) -> String {
+	format!("{path}/ports/{port_id}/channels/{channel_id}/sequences/")
+}
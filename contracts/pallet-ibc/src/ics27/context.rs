@@ -0,0 +1,176 @@
+use super::super::*;
+use crate::{ics27::decode_call, routing::Context};
+use frame_support::traits::{Contains, Get};
+use ibc::{
+	applications::interchain_accounts::{
+		context::{Ics27ControllerContext, Ics27HostContext},
+		error::Error as Ics27Error,
+	},
+	core::ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+	signer::Signer,
+};
+use sp_core::crypto::{AccountId32, ByteArray};
+use sp_runtime::traits::{Dispatchable, IdentifyAccount};
+
+/// Converts an account of this chain into a hex encoded signer, the representation used in the
+/// port identifiers of the interchain account owners.
+pub fn account_to_signer(account: AccountId32) -> Result<Signer, Ics27Error> {
+	let mut hex_string = hex::encode(account.to_raw_vec());
+	hex_string.insert_str(0, "0x");
+	Signer::from_str(&hex_string).map_err(Ics27Error::signer)
+}
+
+fn signer_to_account<T: Config>(
+	signer: &Signer,
+) -> Result<<T as frame_system::Config>::AccountId, Ics27Error> {
+	T::AccountIdConversion::try_from(signer.clone())
+		.map(IdentifyAccount::into_account)
+		.map_err(|_| {
+			Ics27Error::implementation_specific(format!("Invalid account address {signer}"))
+		})
+}
+
+impl<T: Config + Send + Sync> Ics27HostContext for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn is_host_enabled(&self) -> bool {
+		T::IsIcaHostEnabled::get()
+	}
+
+	fn is_message_allowed(&self, type_url: &str) -> bool {
+		// the calls are checked against `IcaAllowedCalls` once decoded
+		type_url == crate::ics27::RUNTIME_CALL_TYPE_URL
+	}
+
+	fn account_address(&self, address: Vec<u8>) -> Result<Signer, Ics27Error> {
+		let account = AccountId32::from_slice(&address).map_err(|_| {
+			Ics27Error::implementation_specific(format!(
+				"Derived address must be 32 bytes long, got {}",
+				address.len()
+			))
+		})?;
+		account_to_signer(account)
+	}
+
+	fn interchain_account(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<Signer> {
+		let account = IcaHostAccounts::<T>::get(
+			connection_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+		)?;
+		account_to_signer(account.into()).ok()
+	}
+
+	fn store_interchain_account(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		account: Signer,
+	) -> Result<(), Ics27Error> {
+		let account = signer_to_account::<T>(&account)?;
+		IcaHostAccounts::<T>::insert(
+			connection_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+			account,
+		);
+		Ok(())
+	}
+
+	fn active_channel(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<ChannelId> {
+		IcaHostActiveChannels::<T>::get(
+			connection_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+		)
+		.map(ChannelId::new)
+	}
+
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Ics27Error> {
+		IcaHostActiveChannels::<T>::insert(
+			connection_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+			channel_id.sequence(),
+		);
+		Ok(())
+	}
+
+	fn execute_message(
+		&mut self,
+		account: &Signer,
+		message: ibc_proto::google::protobuf::Any,
+	) -> Result<ibc_proto::google::protobuf::Any, Ics27Error> {
+		let call = decode_call::<T>(&message)?;
+		if !T::IcaAllowedCalls::contains(&call) {
+			return Err(Ics27Error::message_not_allowed(message.type_url))
+		}
+		let account = signer_to_account::<T>(account)?;
+		let post_info =
+			call.dispatch(frame_system::RawOrigin::Signed(account).into()).map_err(|e| {
+				log::trace!(target: "pallet_ibc", "[execute_message]: error: {:?}", e.error);
+				Ics27Error::implementation_specific(format!("Call failed: {:?}", e.error))
+			})?;
+		Ok(ibc_proto::google::protobuf::Any {
+			type_url: format!("{}Response", message.type_url),
+			value: post_info.encode(),
+		})
+	}
+}
+
+impl<T: Config + Send + Sync> Ics27ControllerContext for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn is_controller_enabled(&self) -> bool {
+		T::IsIcaControllerEnabled::get()
+	}
+
+	fn interchain_account(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<Signer> {
+		let address = IcaControllerAccounts::<T>::get(
+			connection_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+		)?;
+		String::from_utf8(address).ok()?.parse().ok()
+	}
+
+	fn store_interchain_account(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		account: Signer,
+	) -> Result<(), Ics27Error> {
+		IcaControllerAccounts::<T>::insert(
+			connection_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+			account.as_ref().as_bytes().to_vec(),
+		);
+		Ok(())
+	}
+
+	fn active_channel(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<ChannelId> {
+		IcaControllerActiveChannels::<T>::get(
+			connection_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+		)
+		.map(ChannelId::new)
+	}
+
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Ics27Error> {
+		IcaControllerActiveChannels::<T>::insert(
+			connection_id.as_bytes().to_vec(),
+			port_id.as_bytes().to_vec(),
+			channel_id.sequence(),
+		);
+		Ok(())
+	}
+}
@@ -0,0 +1,362 @@
+pub mod context;
+
+use crate::{routing::Context, Config, WeightInfo};
+use alloc::{format, string::ToString};
+use codec::DecodeLimit;
+use frame_support::{
+	dispatch::GetDispatchInfo,
+	storage::{with_transaction, TransactionOutcome},
+	weights::Weight,
+};
+pub use ibc::applications::interchain_accounts::{
+	CONTROLLER_MODULE_ID_STR, CONTROLLER_PORT_PREFIX, HOST_MODULE_ID_STR, HOST_PORT_ID_STR,
+};
+use ibc::{
+	applications::interchain_accounts::{
+		controller, error::Error as Ics27Error, host, packet::InterchainAccountPacketData,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use ibc_primitives::CallbackWeight;
+use sp_core::crypto::AccountId32;
+use sp_runtime::DispatchError;
+use sp_std::marker::PhantomData;
+
+/// Type url of the interchain account messages executed by this chain. The value of such a
+/// message is a SCALE encoded `RuntimeCall`.
+pub const RUNTIME_CALL_TYPE_URL: &str = "/pallet_ibc.RuntimeCall";
+
+/// Maximum depth of the runtime calls decoded from interchain account packets.
+pub const MAX_CALL_DECODE_DEPTH: u32 = 256;
+
+/// Decodes a runtime call sent to an interchain account hosted on this chain.
+pub fn decode_call<T: Config>(
+	message: &ibc_proto::google::protobuf::Any,
+) -> Result<<T as Config>::RuntimeCall, Ics27Error> {
+	if message.type_url != RUNTIME_CALL_TYPE_URL {
+		return Err(Ics27Error::message_not_allowed(message.type_url.clone()))
+	}
+	<T as Config>::RuntimeCall::decode_with_depth_limit(
+		MAX_CALL_DECODE_DEPTH,
+		&mut message.value.as_slice(),
+	)
+	.map_err(|e| Ics27Error::implementation_specific(format!("Failed to decode call: {e:?}")))
+}
+
+/// The ICS27 host application, bound to the [`HOST_PORT_ID_STR`] port. Executes the calls sent by
+/// the controller chains with the interchain accounts derived for them.
+#[derive(Clone, Eq, Debug, PartialEq)]
+pub struct IcaHostModule<T: Config>(PhantomData<T>);
+
+impl<T: Config> Default for IcaHostModule<T> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Module for IcaHostModule<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics27Error::handshake_not_by_controller().into())
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		_version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		host::on_chan_open_try(
+			&mut ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			counterparty_version,
+		)
+		.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics27Error::handshake_not_by_controller().into())
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		host::on_chan_open_confirm(&mut ctx, output, port_id, channel_id)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics27Error::cant_close_channel().into())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<Option<Acknowledgement>, Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		// the calls of a transaction are executed atomically, the state changes of the calls that
+		// succeeded are reverted if a later one fails
+		let ack = with_transaction::<_, DispatchError, _>(|| {
+			let ack = host::on_recv_packet(&mut ctx, output, packet, relayer);
+			if ack.is_successful() {
+				TransactionOutcome::Commit(Ok(ack))
+			} else {
+				TransactionOutcome::Rollback(Ok(ack))
+			}
+		})
+		.map_err(|e| Ics04Error::implementation_specific(format!("{e:?}")))?;
+		Ok(Some(Acknowledgement::from_bytes(ack.to_string().into_bytes())))
+	}
+}
+
+/// The ICS27 controller application, bound to the ports of the interchain account owners on this
+/// chain. Transactions are sent with the `send_ica_tx` extrinsic.
+#[derive(Clone, Eq, Debug, PartialEq)]
+pub struct IcaControllerModule<T: Config>(PhantomData<T>);
+
+impl<T: Config> Default for IcaControllerModule<T> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Module for IcaControllerModule<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		controller::on_chan_open_init(
+			&mut ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+		)
+		.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		_counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		Err(Ics27Error::handshake_not_by_controller().into())
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		controller::on_chan_open_ack(&mut ctx, output, port_id, channel_id, counterparty_version)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics27Error::handshake_not_by_controller().into())
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		Err(Ics27Error::cant_close_channel().into())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<Option<Acknowledgement>, Ics04Error> {
+		Err(Ics27Error::unexpected_packet().into())
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &Acknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		controller::on_acknowledgement_packet(&mut ctx, output, packet, acknowledgement, relayer)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		controller::on_timeout_packet(&mut ctx, output, packet, relayer)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+}
+
+/// Callback weights of the interchain accounts applications. The weight of a received packet
+/// includes the weight of the calls it dispatches.
+pub struct WeightHandler<T: Config>(PhantomData<T>);
+
+impl<T: Config> Default for WeightHandler<T> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config> CallbackWeight for WeightHandler<T> {
+	fn on_chan_open_init(&self) -> Weight {
+		<T as Config>::WeightInfo::on_chan_open_init()
+	}
+
+	fn on_chan_open_try(&self) -> Weight {
+		<T as Config>::WeightInfo::on_chan_open_try()
+	}
+
+	fn on_chan_open_ack(&self, _port_id: &PortId, _channel_id: &ChannelId) -> Weight {
+		<T as Config>::WeightInfo::on_chan_open_ack()
+	}
+
+	fn on_chan_open_confirm(&self, _port_id: &PortId, _channel_id: &ChannelId) -> Weight {
+		<T as Config>::WeightInfo::on_chan_open_confirm()
+	}
+
+	fn on_chan_close_init(&self, _port_id: &PortId, _channel_id: &ChannelId) -> Weight {
+		<T as Config>::WeightInfo::on_chan_close_init()
+	}
+
+	fn on_chan_close_confirm(&self, _port_id: &PortId, _channel_id: &ChannelId) -> Weight {
+		<T as Config>::WeightInfo::on_chan_close_confirm()
+	}
+
+	fn on_recv_packet(&self, packet: &Packet) -> Weight {
+		// packets that can't be decoded are rejected before any call is dispatched
+		let calls_weight = serde_json::from_slice::<InterchainAccountPacketData>(&packet.data)
+			.ok()
+			.and_then(|data| data.messages().ok())
+			.unwrap_or_default()
+			.iter()
+			.filter_map(|message| decode_call::<T>(message).ok())
+			.fold(Weight::zero(), |acc, call| acc.saturating_add(call.get_dispatch_info().weight));
+		<T as Config>::WeightInfo::on_recv_packet().saturating_add(calls_weight)
+	}
+
+	fn on_acknowledgement_packet(
+		&self,
+		_packet: &Packet,
+		_acknowledgement: &Acknowledgement,
+	) -> Weight {
+		<T as Config>::WeightInfo::on_acknowledgement_packet()
+	}
+
+	fn on_timeout_packet(&self, _packet: &Packet) -> Weight {
+		<T as Config>::WeightInfo::on_timeout_packet()
+	}
+}
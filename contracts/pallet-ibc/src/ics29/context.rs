@@ -0,0 +1,178 @@
+use super::super::*;
+use crate::{ics27::context::account_to_signer, ics29::fee_escrow_address, routing::Context};
+use ibc::{
+	applications::{
+		fee::{
+			context::Ics29Context,
+			error::Error as Ics29Error,
+			packet_fee::{PacketFee, PacketFees as IbcPacketFees},
+		},
+		transfer::{context::BankKeeper, PrefixedCoin},
+	},
+	core::{
+		ics04_channel::packet::Sequence,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	signer::Signer,
+};
+use sp_core::crypto::AccountId32;
+use sp_runtime::traits::IdentifyAccount;
+use tendermint_proto::Protobuf;
+
+fn to_account<T: Config>(signer: &Signer) -> Result<T::AccountIdConversion, Ics29Error> {
+	T::AccountIdConversion::try_from(signer.clone()).map_err(|_| {
+		Ics29Error::implementation_specific(format!("Invalid account address {signer}"))
+	})
+}
+
+impl<T: Config + Send + Sync> Ics29Context for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn is_fee_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+		FeeEnabledChannels::<T>::contains_key(port_id.as_bytes().to_vec(), channel_id.sequence())
+	}
+
+	fn set_fee_enabled(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Ics29Error> {
+		FeeEnabledChannels::<T>::insert(port_id.as_bytes().to_vec(), channel_id.sequence(), ());
+		Ok(())
+	}
+
+	fn payee(&self, channel_id: &ChannelId, relayer: &Signer) -> Option<Signer> {
+		let relayer = to_account::<T>(relayer).ok()?.into_account();
+		let payee = FeePayees::<T>::get(channel_id.sequence(), relayer)?;
+		account_to_signer(payee.into()).ok()
+	}
+
+	fn store_payee(
+		&mut self,
+		channel_id: ChannelId,
+		relayer: Signer,
+		payee: Signer,
+	) -> Result<(), Ics29Error> {
+		let relayer = to_account::<T>(&relayer)?.into_account();
+		let payee = to_account::<T>(&payee)?.into_account();
+		FeePayees::<T>::insert(channel_id.sequence(), relayer, payee);
+		Ok(())
+	}
+
+	fn counterparty_payee(&self, channel_id: &ChannelId, relayer: &Signer) -> Option<String> {
+		let relayer = to_account::<T>(relayer).ok()?.into_account();
+		let payee = FeeCounterpartyPayees::<T>::get(channel_id.sequence(), relayer)?;
+		String::from_utf8(payee).ok()
+	}
+
+	fn store_counterparty_payee(
+		&mut self,
+		channel_id: ChannelId,
+		relayer: Signer,
+		counterparty_payee: String,
+	) -> Result<(), Ics29Error> {
+		let relayer = to_account::<T>(&relayer)?.into_account();
+		FeeCounterpartyPayees::<T>::insert(
+			channel_id.sequence(),
+			relayer,
+			counterparty_payee.as_bytes().to_vec(),
+		);
+		Ok(())
+	}
+
+	fn forward_relayer(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Option<String> {
+		let key = (port_id.as_bytes().to_vec(), channel_id.sequence());
+		let forward_relayer = FeeForwardRelayers::<T>::get(key, u64::from(sequence))?;
+		String::from_utf8(forward_relayer).ok()
+	}
+
+	fn store_forward_relayer(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+		forward_relayer: Option<String>,
+	) -> Result<(), Ics29Error> {
+		let key = (port_id.as_bytes().to_vec(), channel_id.sequence());
+		match forward_relayer {
+			Some(forward_relayer) => FeeForwardRelayers::<T>::insert(
+				key,
+				u64::from(sequence),
+				forward_relayer.into_bytes(),
+			),
+			None => FeeForwardRelayers::<T>::remove(key, u64::from(sequence)),
+		}
+		Ok(())
+	}
+
+	fn packet_fees(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Vec<PacketFee> {
+		PacketFees::<T>::get(
+			(port_id.as_bytes().to_vec(), channel_id.sequence()),
+			u64::from(sequence),
+		)
+		.and_then(|fees| IbcPacketFees::decode_vec(&fees).ok())
+		.map(|fees| fees.packet_fees)
+		.unwrap_or_default()
+	}
+
+	fn store_packet_fees(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+		packet_fees: Vec<PacketFee>,
+	) -> Result<(), Ics29Error> {
+		let key = (port_id.as_bytes().to_vec(), channel_id.sequence());
+		if packet_fees.is_empty() {
+			PacketFees::<T>::remove(key, u64::from(sequence));
+		} else {
+			let fees = IbcPacketFees { packet_fees }.encode_vec().map_err(|e| {
+				Ics29Error::implementation_specific(format!("Failed to encode packet fees: {e}"))
+			})?;
+			PacketFees::<T>::insert(key, u64::from(sequence), fees);
+		}
+		Ok(())
+	}
+
+	fn incentivized_packets(&self, port_id: &PortId, channel_id: &ChannelId) -> Vec<Sequence> {
+		PacketFees::<T>::iter_key_prefix((port_id.as_bytes().to_vec(), channel_id.sequence()))
+			.map(Sequence::from)
+			.collect()
+	}
+
+	fn escrow_coins(&mut self, payer: &Signer, coins: &[PrefixedCoin]) -> Result<(), Ics29Error> {
+		let payer = to_account::<T>(payer)?;
+		let escrow = to_account::<T>(&fee_escrow_address())?;
+		for coin in coins {
+			self.send_coins(&payer, &escrow, coin)
+				.map_err(|e| Ics29Error::implementation_specific(e.to_string()))?;
+		}
+		Ok(())
+	}
+
+	fn distribute_coins(
+		&mut self,
+		receiver: &Signer,
+		coins: &[PrefixedCoin],
+	) -> Result<(), Ics29Error> {
+		let receiver = to_account::<T>(receiver)?;
+		let escrow = to_account::<T>(&fee_escrow_address())?;
+		for coin in coins {
+			self.send_coins(&escrow, &receiver, coin)
+				.map_err(|e| Ics29Error::implementation_specific(e.to_string()))?;
+		}
+		Ok(())
+	}
+}
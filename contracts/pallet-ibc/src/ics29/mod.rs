@@ -0,0 +1,334 @@
+pub mod context;
+
+use crate::{routing::Context, Config, DenomToAssetId, Error, PacketFeeParams};
+use alloc::format;
+use core::{fmt::Debug, str::FromStr};
+pub use ibc::applications::fee::{MODULE_ID_STR, VERSION};
+use ibc::{
+	applications::{
+		fee::{middleware, packet_fee::Fee},
+		transfer::{Amount, PrefixedCoin, PrefixedDenom},
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use sp_core::crypto::AccountId32;
+use sp_std::{marker::PhantomData, prelude::*};
+
+/// Returns the address of the account holding the escrowed ICS29 fees, derived like the ICS20
+/// channel escrow addresses.
+pub fn fee_escrow_address() -> Signer {
+	let hash = sp_io::hashing::sha2_256(MODULE_ID_STR.as_bytes());
+	let mut hex_string = hex::encode_upper(hash);
+	hex_string.insert_str(0, "0x");
+	hex_string.parse().expect("hex encoded hash is a valid signer")
+}
+
+/// Converts the fees paid in assets of this chain into ICS29 fees.
+pub(crate) fn fee_from_params<T: Config>(
+	params: PacketFeeParams<T::AssetId, T::Balance>,
+) -> Result<Fee, Error<T>> {
+	let coins = |assets: Vec<(T::AssetId, T::Balance)>| {
+		assets
+			.into_iter()
+			.map(|(asset_id, amount)| {
+				let denom = T::IbcDenomToAssetIdConversion::from_asset_id_to_denom(asset_id)
+					.ok_or(Error::<T>::InvalidAssetId)?;
+				Ok(PrefixedCoin {
+					denom: PrefixedDenom::from_str(&denom)
+						.map_err(|_| Error::<T>::PrefixedDenomParse)?,
+					amount: Amount::from_str(&format!("{amount:?}"))
+						.map_err(|_| Error::<T>::InvalidAmount)?,
+				})
+			})
+			.collect::<Result<Vec<_>, Error<T>>>()
+	};
+	Ok(Fee {
+		recv_fee: coins(params.recv_fee)?,
+		ack_fee: coins(params.ack_fee)?,
+		timeout_fee: coins(params.timeout_fee)?,
+	})
+}
+
+/// The ICS29 middleware, negotiating the fee version on the channels of the wrapped application
+/// and paying the relayers of the packets sent on the fee enabled channels.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeMiddleware<T: Config, S: Module + Clone + Default + PartialEq + Eq + Debug> {
+	inner: S,
+	_phantom: PhantomData<T>,
+}
+
+impl<T: Config, S: Module + Clone + Default + PartialEq + Eq + Debug> Default
+	for FeeMiddleware<T, S>
+{
+	fn default() -> Self {
+		Self { inner: S::default(), _phantom: Default::default() }
+	}
+}
+
+impl<T: Config + Send + Sync, S: Module + Clone + Default + PartialEq + Eq + Debug> Module
+	for FeeMiddleware<T, S>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		middleware::on_chan_open_init(
+			&mut Context::<T>::default(),
+			&mut self.inner,
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		middleware::on_chan_open_try(
+			&mut Context::<T>::default(),
+			&mut self.inner,
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			counterparty_version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		middleware::on_chan_open_ack(
+			&mut Context::<T>::default(),
+			&mut self.inner,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			counterparty_version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_open_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_close_init(ctx, output, port_id, channel_id, relayer)?;
+		middleware::on_chan_close(&mut Context::<T>::default(), output, port_id, channel_id)
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		self.inner.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)?;
+		middleware::on_chan_close(&mut Context::<T>::default(), output, port_id, channel_id)
+	}
+
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<Version, Ics04Error> {
+		middleware::on_chan_upgrade_init(
+			&mut self.inner,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		counterparty_version: &Version,
+	) -> Result<Version, Ics04Error> {
+		middleware::on_chan_upgrade_try(
+			&mut self.inner,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			counterparty_version,
+		)
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Ics04Error> {
+		middleware::on_chan_upgrade_ack(
+			&mut self.inner,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			counterparty_version,
+		)
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<(), Ics04Error> {
+		middleware::on_chan_upgrade_open(
+			&mut Context::<T>::default(),
+			&mut self.inner,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_recv_packet(
+		&self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<Option<Acknowledgement>, Ics04Error> {
+		middleware::on_recv_packet(
+			&mut Context::<T>::default(),
+			&self.inner,
+			ctx,
+			output,
+			packet,
+			relayer,
+		)
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &Acknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		middleware::on_acknowledgement_packet(
+			&mut Context::<T>::default(),
+			&mut self.inner,
+			ctx,
+			output,
+			packet,
+			acknowledgement,
+			relayer,
+		)
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		middleware::on_timeout_packet(
+			&mut Context::<T>::default(),
+			&mut self.inner,
+			ctx,
+			output,
+			packet,
+			relayer,
+		)
+	}
+}
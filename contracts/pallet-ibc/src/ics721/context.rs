@@ -0,0 +1,139 @@
+use super::super::*;
+use crate::{ics721::NftRegistry, routing::Context};
+use frame_support::traits::Get;
+use ibc::{
+	applications::nft_transfer::{
+		context::{Ics721Context, Ics721Keeper, Ics721Reader, NftKeeper},
+		error::Error as Ics721Error,
+		Class, Nft, PrefixedClassId, TokenId, PORT_ID_STR,
+	},
+	core::ics24_host::identifier::{ChannelId, PortId},
+};
+use ibc_primitives::get_channel_escrow_address;
+use sp_core::crypto::AccountId32;
+use sp_runtime::traits::IdentifyAccount;
+
+impl<T: Config + Send + Sync> Ics721Reader for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	type AccountId = T::AccountIdConversion;
+
+	fn get_port(&self) -> Result<PortId, Ics721Error> {
+		PortId::from_str(PORT_ID_STR)
+			.map_err(|e| Ics721Error::invalid_port_id(PORT_ID_STR.to_string(), e))
+	}
+
+	fn get_channel_escrow_address(
+		&self,
+		port_id: &PortId,
+		channel_id: ChannelId,
+	) -> Result<<Self as Ics721Reader>::AccountId, Ics721Error> {
+		get_channel_escrow_address(port_id, channel_id)
+			.map_err(|e| Ics721Error::implementation_specific(e.to_string()))?
+			.try_into()
+			.map_err(|_| {
+				log::trace!(target: "pallet_ibc", "Failed to get channel escrow address");
+				Ics721Error::parse_account_failure()
+			})
+	}
+
+	fn is_send_enabled(&self) -> bool {
+		T::IsSendEnabled::get()
+	}
+
+	fn is_receive_enabled(&self) -> bool {
+		T::IsReceiveEnabled::get()
+	}
+
+	fn get_class(&self, class_id: &PrefixedClassId) -> Option<Class> {
+		T::NftRegistry::class(class_id)
+	}
+
+	fn get_nft(&self, class_id: &PrefixedClassId, token_id: &TokenId) -> Option<Nft> {
+		T::NftRegistry::nft(class_id, token_id)
+	}
+}
+
+impl<T: Config + Send + Sync> Ics721Keeper for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	type AccountId = T::AccountIdConversion;
+}
+
+impl<T: Config + Send + Sync> Ics721Context for Context<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	type AccountId = T::AccountIdConversion;
+}
+
+impl<T> NftKeeper for Context<T>
+where
+	T: Config + Send + Sync,
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	type AccountId = T::AccountIdConversion;
+
+	fn create_class(&mut self, class: &Class) -> Result<(), Ics721Error> {
+		T::NftRegistry::create_class(class).map_err(|e| {
+			log::debug!(target: "pallet_ibc", "Failed to create class {}: {:?}", class.id, e);
+			Ics721Error::implementation_specific(format!("Failed to create class {}", class.id))
+		})
+	}
+
+	fn mint_nft(
+		&mut self,
+		class_id: &PrefixedClassId,
+		token: &Nft,
+		receiver: &Self::AccountId,
+	) -> Result<(), Ics721Error> {
+		T::NftRegistry::mint(class_id, token, &receiver.clone().into_account()).map_err(|e| {
+			log::debug!(target: "pallet_ibc", "Failed to mint token {} of class {}: {:?}", token.id, class_id, e);
+			Ics721Error::implementation_specific(format!(
+				"Failed to mint token {} of class {class_id}",
+				token.id
+			))
+		})
+	}
+
+	fn transfer_nft(
+		&mut self,
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+		from: &Self::AccountId,
+		to: &Self::AccountId,
+	) -> Result<(), Ics721Error> {
+		T::NftRegistry::transfer(
+			class_id,
+			token_id,
+			&from.clone().into_account(),
+			&to.clone().into_account(),
+		)
+		.map_err(|e| {
+			log::debug!(target: "pallet_ibc", "Failed to transfer token {} of class {}: {:?}", token_id, class_id, e);
+			Ics721Error::implementation_specific(format!(
+				"Failed to transfer token {token_id} of class {class_id}"
+			))
+		})
+	}
+
+	fn burn_nft(
+		&mut self,
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+		owner: &Self::AccountId,
+	) -> Result<(), Ics721Error> {
+		T::NftRegistry::burn(class_id, token_id, &owner.clone().into_account()).map_err(|e| {
+			log::debug!(target: "pallet_ibc", "Failed to burn token {} of class {}: {:?}", token_id, class_id, e);
+			Ics721Error::implementation_specific(format!(
+				"Failed to burn token {token_id} of class {class_id}"
+			))
+		})
+	}
+}
@@ -0,0 +1,277 @@
+pub mod context;
+
+use crate::{routing::Context, Config};
+use alloc::{format, string::ToString};
+use frame_support::storage::{with_transaction, TransactionOutcome};
+pub use ibc::applications::nft_transfer::{MODULE_ID_STR, PORT_ID_STR};
+use ibc::{
+	applications::nft_transfer::{
+		context::{
+			on_acknowledgement_packet, on_chan_close_confirm, on_chan_close_init, on_chan_open_ack,
+			on_chan_open_confirm, on_chan_open_init, on_chan_open_try, on_recv_packet,
+			on_timeout_packet,
+		},
+		error::Error as Ics721Error,
+		Class, Nft, PrefixedClassId, TokenId,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error as Ics04Error,
+			msgs::acknowledgement::Acknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	signer::Signer,
+};
+use sp_core::crypto::AccountId32;
+use sp_runtime::{DispatchError, DispatchResult};
+use sp_std::marker::PhantomData;
+
+/// The non-fungible tokens of the runtime the ICS721 application transfers, usually backed by
+/// `pallet-nfts` or `pallet-uniques`. Classes and tokens are identified by their ICS721 ids,
+/// mapping them to the collections and items of the runtime is up to the implementation.
+pub trait NftRegistry<AccountId> {
+	/// Returns the class along with its metadata, if it exists.
+	fn class(class_id: &PrefixedClassId) -> Option<Class>;
+
+	/// Returns the token of the class along with its metadata, if it exists.
+	fn nft(class_id: &PrefixedClassId, token_id: &TokenId) -> Option<Nft>;
+
+	/// Creates the class of the vouchers of tokens received from a counterparty chain.
+	fn create_class(class: &Class) -> DispatchResult;
+
+	/// Mints a token of the class to `who`.
+	fn mint(class_id: &PrefixedClassId, token: &Nft, who: &AccountId) -> DispatchResult;
+
+	/// Transfers a token of the class, failing if `from` doesn't own it.
+	fn transfer(
+		class_id: &PrefixedClassId,
+		token_id: &TokenId,
+		from: &AccountId,
+		to: &AccountId,
+	) -> DispatchResult;
+
+	/// Burns a token of the class, failing if `owner` doesn't own it.
+	fn burn(class_id: &PrefixedClassId, token_id: &TokenId, owner: &AccountId) -> DispatchResult;
+}
+
+/// Runtimes without non-fungible tokens can't send or receive any.
+impl<AccountId> NftRegistry<AccountId> for () {
+	fn class(_class_id: &PrefixedClassId) -> Option<Class> {
+		None
+	}
+
+	fn nft(_class_id: &PrefixedClassId, _token_id: &TokenId) -> Option<Nft> {
+		None
+	}
+
+	fn create_class(_class: &Class) -> DispatchResult {
+		Err(DispatchError::Other("Non-fungible tokens are not supported"))
+	}
+
+	fn mint(_class_id: &PrefixedClassId, _token: &Nft, _who: &AccountId) -> DispatchResult {
+		Err(DispatchError::Other("Non-fungible tokens are not supported"))
+	}
+
+	fn transfer(
+		_class_id: &PrefixedClassId,
+		_token_id: &TokenId,
+		_from: &AccountId,
+		_to: &AccountId,
+	) -> DispatchResult {
+		Err(DispatchError::Other("Non-fungible tokens are not supported"))
+	}
+
+	fn burn(
+		_class_id: &PrefixedClassId,
+		_token_id: &TokenId,
+		_owner: &AccountId,
+	) -> DispatchResult {
+		Err(DispatchError::Other("Non-fungible tokens are not supported"))
+	}
+}
+
+/// The ICS721 application, bound to the [`PORT_ID_STR`] port. Tokens are sent with the
+/// `transfer_nft` extrinsic.
+#[derive(Clone, Eq, Debug, PartialEq)]
+pub struct NftTransferModule<T: Config>(PhantomData<T>);
+
+impl<T: Config> Default for NftTransferModule<T> {
+	fn default() -> Self {
+		Self(PhantomData)
+	}
+}
+
+impl<T: Config + Send + Sync> Module for NftTransferModule<T>
+where
+	u32: From<<T as frame_system::Config>::BlockNumber>,
+	AccountId32: From<<T as frame_system::Config>::AccountId>,
+{
+	fn on_chan_open_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_open_init(
+			&mut ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+		)
+		.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_open_try(
+			&mut ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			counterparty_version,
+		)
+		.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_open_ack(&mut ctx, output, port_id, channel_id, counterparty_version)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_open_confirm(&mut ctx, output, port_id, channel_id)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_close_init(&mut ctx, output, port_id, channel_id)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		on_chan_close_confirm(&mut ctx, output, port_id, channel_id)
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<Option<Acknowledgement>, Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		// the tokens of a packet are received atomically, the tokens already received are
+		// reverted if a later one fails
+		let ack = with_transaction::<_, DispatchError, _>(|| {
+			let ack = on_recv_packet(&mut ctx, output, packet, relayer);
+			if ack.is_successful() {
+				TransactionOutcome::Commit(Ok(ack))
+			} else {
+				TransactionOutcome::Rollback(Ok(ack))
+			}
+		})
+		.map_err(|e| Ics04Error::implementation_specific(format!("{e:?}")))?;
+		Ok(Some(Acknowledgement::from_bytes(ack.to_string().into_bytes())))
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &Acknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		atomically(|| on_acknowledgement_packet(&mut ctx, output, packet, acknowledgement, relayer))
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<(), Ics04Error> {
+		let mut ctx = Context::<T>::default();
+		atomically(|| on_timeout_packet(&mut ctx, output, packet, relayer))
+			.map_err(|e| Ics04Error::implementation_specific(e.to_string()))
+	}
+}
+
+/// Runs `f` in a storage transaction, so that the tokens of a packet are either all refunded or
+/// none of them is.
+fn atomically<R>(f: impl FnOnce() -> Result<R, Ics721Error>) -> Result<R, Ics721Error> {
+	with_transaction::<_, DispatchError, _>(|| match f() {
+		Ok(result) => TransactionOutcome::Commit(Ok(Ok(result))),
+		Err(e) => TransactionOutcome::Rollback(Ok(Err(e))),
+	})
+	.map_err(|e| Ics721Error::implementation_specific(format!("{e:?}")))?
+}
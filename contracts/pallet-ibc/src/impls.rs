@@ -2,23 +2,44 @@ use alloc::{format, str::FromStr, string::String};
 use core::time::Duration;
 
 use crate::{
+	errors::IbcError,
+	ics20::Ics20RateLimiter,
 	ics23::{
-		acknowledgements::Acknowledgements, channels::Channels, client_states::ClientStates,
-		connections::Connections, consensus_states::ConsensusStates,
-		next_seq_recv::NextSequenceRecv, packet_commitments::PacketCommitment,
+		acknowledgements::Acknowledgements,
+		channel_upgrades::ChannelUpgrades,
+		channels::Channels,
+		client_states::ClientStates,
+		connections::Connections,
+		consensus_states::ConsensusStates,
+		next_seq_recv::NextSequenceRecv,
+		packet_commitments::PacketCommitment,
+		pagination::{channel_prefix, paginate},
 		receipts::PacketReceipt,
 	},
 	light_clients::AnyClientState,
 	routing::Context,
-	Acks, ChannelsConnection, Config, ConnectionClient, DenomToAssetId, Error, EscrowAddresses,
-	IbcAssets, Pallet, PendingRecvPacketSeqs, PendingSendPacketSeqs, RecvPackets, SendPackets,
-	MODULE_ID,
+	Acks, Any, AssetMetadata, ChannelsConnection, Config, ConnectionClient, DenomToAssetId, Error,
+	EscrowAddresses, Event, IbcAssetMetadata, IbcAssetRegistry, IbcAssets, Pallet, PausedAssets,
+	PausedChannels, PendingRecvPacketSeqs, PendingSendPacketSeqs, RecvPackets, SendPackets,
+	SimulatedMessage, TransferPause, MAX_ASSET_METADATA_LENGTH, MODULE_ID,
 };
 use codec::{Decode, Encode};
-use frame_support::traits::{fungibles::Inspect, Currency};
+use frame_support::{
+	storage::{with_transaction, TransactionOutcome},
+	traits::{fungibles::Inspect, Currency, UnixTime},
+};
 use ibc::{
-	applications::transfer::{
-		msgs::transfer::MsgTransfer, relay::send_transfer::send_transfer, PrefixedCoin,
+	applications::{
+		nft_transfer::{
+			msgs::transfer::MsgTransfer as MsgNftTransfer,
+			relay::send_transfer::send_transfer as send_nft_transfer,
+		},
+		transfer::{
+			is_sender_chain_source,
+			msgs::{transfer::MsgTransfer, transfer_v2::MsgTransferV2},
+			relay::send_transfer::{send_transfer, send_transfer_v2},
+			PrefixedCoin, PrefixedDenom,
+		},
 	},
 	core::{
 		ics02_client::{
@@ -35,11 +56,12 @@ use ibc::{
 		ics24_host::{
 			identifier::*,
 			path::{
-				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
-				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+				AcksPath, ChannelEndsPath, ChannelUpgradePath, ClientConsensusStatePath,
+				ClientStatePath, CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
 			},
+			Path,
 		},
-		ics26_routing::handler::MsgReceipt,
+		ics26_routing::{context::Ics26Context, handler::MsgReceipt},
 	},
 	handler::HandlerOutputBuilder,
 	signer::Signer,
@@ -51,9 +73,10 @@ use ibc_primitives::{
 	apply_prefix, channel_id_from_bytes, client_id_from_bytes, connection_id_from_bytes,
 	get_channel_escrow_address, port_id_from_bytes, runtime_interface, ConnectionHandshake,
 	Error as IbcHandlerError, HandlerMessage, IbcHandler, IdentifiedChannel, IdentifiedClientState,
-	IdentifiedConnection, PacketInfo, PacketState, QueryChannelResponse, QueryChannelsResponse,
-	QueryClientStateResponse, QueryConnectionResponse, QueryConnectionsResponse,
-	QueryConsensusStateResponse, QueryNextSequenceReceiveResponse,
+	IdentifiedConnection, PacketInfo, PacketState, PageRequest, QueryChannelResponse,
+	QueryChannelUpgradeErrorResponse, QueryChannelUpgradeResponse, QueryChannelsResponse,
+	QueryClientStateResponse, QueryClientStatesResponse, QueryConnectionResponse,
+	QueryConnectionsResponse, QueryConsensusStateResponse, QueryNextSequenceReceiveResponse,
 	QueryPacketAcknowledgementResponse, QueryPacketAcknowledgementsResponse,
 	QueryPacketCommitmentResponse, QueryPacketCommitmentsResponse, QueryPacketReceiptResponse,
 	Timeout,
@@ -62,7 +85,7 @@ use scale_info::prelude::string::ToString;
 use sp_core::crypto::AccountId32;
 use sp_runtime::{
 	traits::{Get, IdentifyAccount},
-	Either,
+	DispatchError, Either,
 };
 use sp_std::prelude::*;
 use tendermint_proto::Protobuf;
@@ -99,6 +122,128 @@ where
 			Self::deposit_event(events.into())
 		};
 	}
+
+	/// Dry-runs the delivery of `messages` as done by `deliver`, in a storage transaction that is
+	/// rolled back. Returns the outcome of every message, in order, each one being applied on top
+	/// of the state left by the previous ones.
+	pub fn simulate_deliver(messages: Vec<Any>) -> Vec<SimulatedMessage> {
+		use ibc::core::ics04_channel::msgs::chan_upgrade_init;
+		let simulate = || {
+			let mut ctx = Context::<T>::new();
+			let simulated = messages
+				.into_iter()
+				.map(|message| {
+					let benchmarked_weight =
+						crate::weight::deliver::<T>(core::slice::from_ref(&message));
+					let result = if message.type_url == chan_upgrade_init::TYPE_URL {
+						Err(IbcError::UnknownMessageTypeUrl {
+							message: b"channel upgrades are only initiated through upgrade_channel"
+								.to_vec(),
+						})
+					} else {
+						ibc::core::ics26_routing::handler::deliver(&mut ctx, message.into())
+							.map(|MsgReceipt { events, .. }| {
+								events.into_iter().map(Into::into).collect()
+							})
+							.map_err(Into::into)
+					};
+					SimulatedMessage { result, benchmarked_weight }
+				})
+				.collect();
+			TransactionOutcome::Rollback(Ok::<_, DispatchError>(simulated))
+		};
+		with_transaction(simulate).unwrap_or_default()
+	}
+
+	pub(crate) fn is_valid_asset_metadata(metadata: &AssetMetadata) -> bool {
+		[&metadata.name, &metadata.symbol].into_iter().all(|field| {
+			!field.is_empty() &&
+				field.len() <= MAX_ASSET_METADATA_LENGTH &&
+				core::str::from_utf8(field).is_ok()
+		})
+	}
+
+	/// Registers placeholder metadata for the IBC asset of `denom`, received for the first time,
+	/// if [`Config::AutoRegisterAssetMetadata`] is enabled: its name is the denom trace and its
+	/// symbol the base denom, without decimals.
+	pub(crate) fn register_placeholder_metadata(denom: &PrefixedDenom, asset_id: T::AssetId) {
+		let key = denom.to_string().into_bytes();
+		if !T::AutoRegisterAssetMetadata::get() ||
+			denom.trace_path().is_empty() ||
+			IbcAssetMetadata::<T>::contains_key(&key)
+		{
+			return
+		}
+		let mut name = key.clone();
+		name.truncate(MAX_ASSET_METADATA_LENGTH);
+		let mut symbol = denom.base_denom().as_str().as_bytes().to_vec();
+		symbol.truncate(MAX_ASSET_METADATA_LENGTH);
+		let metadata = AssetMetadata { name, symbol, decimals: 0 };
+		if let Err(e) = T::AssetRegistry::on_metadata_set(asset_id, &key, &metadata) {
+			log::warn!(target: "pallet_ibc", "Failed to register placeholder metadata: {:?}", e);
+			return
+		}
+		IbcAssetMetadata::<T>::insert(&key, metadata.clone());
+		Self::deposit_event(Event::<T>::AssetMetadataSet { denom: key, metadata });
+	}
+
+	/// Returns the directions in which the ICS-20 transfers of `asset_id` over the channel with
+	/// sequence `channel` are paused, by a pause of either the channel or the asset.
+	pub fn transfer_pause(channel: u64, asset_id: Option<&T::AssetId>) -> TransferPause {
+		let channel = PausedChannels::<T>::get(channel);
+		let asset = asset_id.map(PausedAssets::<T>::get).unwrap_or_default();
+		TransferPause {
+			send: channel.send || asset.send,
+			receive: channel.receive || asset.receive,
+		}
+	}
+
+	/// Converts the timeout of a packet sent over the given channel into the timeout height and
+	/// timestamp of the packet. Offsets are relative to the latest height of the counterparty
+	/// client and to the time of this chain.
+	pub(crate) fn packet_timeout(
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		timeout: Timeout,
+	) -> Result<(Height, Timestamp), Error<T>> {
+		let (latest_height, _) = Pallet::<T>::latest_height_and_timestamp(port_id, channel_id)
+			.map_err(|_| Error::<T>::TimestampAndHeightNotFound)?;
+
+		let (timeout_height, timeout_timestamp) = match timeout {
+			Timeout::Offset { timestamp, height } => {
+				let latest_timestamp = T::TimeProvider::now();
+				let timestamp = timestamp
+					.map(|offset| {
+						Timestamp::from_nanoseconds(
+							(latest_timestamp + Duration::from_secs(offset)).as_nanos() as u64,
+						)
+					})
+					.transpose()
+					.map_err(|_| Error::<T>::InvalidTimestamp)?
+					.unwrap_or_default();
+				let height = height.map(|offset| latest_height.add(offset)).unwrap_or_default();
+				(height, timestamp)
+			},
+			Timeout::Absolute { timestamp, height } => {
+				let timestamp = timestamp
+					.map(Timestamp::from_nanoseconds)
+					.transpose()
+					.map_err(|_| Error::<T>::InvalidTimestamp)?
+					.unwrap_or_default();
+				let height = height
+					.map(|revision_height| {
+						Height::new(latest_height.revision_number, revision_height)
+					})
+					.unwrap_or_default();
+				(height, timestamp)
+			},
+		};
+
+		if timeout_height.is_zero() && timeout_timestamp.nanoseconds() == 0 {
+			return Err(Error::<T>::InvalidTimestamp)
+		}
+		Ok((timeout_height, timeout_timestamp))
+	}
 }
 
 impl<T: Config> Pallet<T>
@@ -124,6 +269,42 @@ where
 		Ok(QueryChannelResponse { channel, trie_key: key, height: host_height::<T>() })
 	}
 
+	/// Get the pending upgrade of a channel
+	pub fn channel_upgrade(
+		channel_id: Vec<u8>,
+		port_id: Vec<u8>,
+	) -> Result<QueryChannelUpgradeResponse, Error<T>> {
+		let port_id = port_id_from_bytes(port_id).map_err(|_| Error::<T>::DecodingError)?;
+		let channel_id =
+			channel_id_from_bytes(channel_id).map_err(|_| Error::<T>::DecodingError)?;
+		let upgrade_path = ChannelUpgradePath::Upgrade(port_id, channel_id);
+		let upgrade = ChannelUpgrades::<T>::get(upgrade_path.clone())
+			.ok_or(Error::<T>::ChannelUpgradeNotFound)?;
+		let key = apply_prefix(T::PalletPrefix::get(), vec![format!("{}", upgrade_path)]);
+
+		Ok(QueryChannelUpgradeResponse { upgrade, trie_key: key, height: host_height::<T>() })
+	}
+
+	/// Get the last upgrade error receipt of a channel
+	pub fn channel_upgrade_error(
+		channel_id: Vec<u8>,
+		port_id: Vec<u8>,
+	) -> Result<QueryChannelUpgradeErrorResponse, Error<T>> {
+		let port_id = port_id_from_bytes(port_id).map_err(|_| Error::<T>::DecodingError)?;
+		let channel_id =
+			channel_id_from_bytes(channel_id).map_err(|_| Error::<T>::DecodingError)?;
+		let error_path = ChannelUpgradePath::UpgradeError(port_id, channel_id);
+		let error_receipt = ChannelUpgrades::<T>::get(error_path.clone())
+			.ok_or(Error::<T>::ChannelUpgradeNotFound)?;
+		let key = apply_prefix(T::PalletPrefix::get(), vec![format!("{}", error_path)]);
+
+		Ok(QueryChannelUpgradeErrorResponse {
+			error_receipt,
+			trie_key: key,
+			height: host_height::<T>(),
+		})
+	}
+
 	/// Get a connection state
 	pub fn connection(connection_id: Vec<u8>) -> Result<QueryConnectionResponse, Error<T>> {
 		let connection_id =
@@ -149,12 +330,35 @@ where
 		Ok(QueryClientStateResponse { client_state, trie_key: key, height: host_height::<T>() })
 	}
 
-	/// Get all client states
-	/// Returns a Vec of (client_id, client_state)
-	pub fn clients() -> Vec<(Vec<u8>, Vec<u8>)> {
-		ClientStates::<T>::iter()
-			.map(|(client_id, client_state)| (client_id.as_bytes().to_vec(), client_state))
-			.collect::<Vec<_>>()
+	/// Get all client states, or a page of them
+	pub fn clients(pagination: Option<PageRequest>) -> QueryClientStatesResponse {
+		let (client_states, pagination) = match pagination {
+			Some(page) => {
+				let (client_states, pagination) = paginate::<T, _>(
+					"clients/",
+					page,
+					|path, client_state| match Path::from_str(path).ok()? {
+						Path::ClientState(ClientStatePath(client_id)) =>
+							Some(IdentifiedClientState {
+								client_id: client_id.as_bytes().to_vec(),
+								client_state,
+							}),
+						_ => None,
+					},
+				);
+				(client_states, Some(pagination))
+			},
+			None => (
+				ClientStates::<T>::iter()
+					.map(|(client_id, client_state)| IdentifiedClientState {
+						client_id: client_id.as_bytes().to_vec(),
+						client_state,
+					})
+					.collect::<Vec<_>>(),
+				None,
+			),
+		};
+		QueryClientStatesResponse { client_states, pagination, height: host_height::<T>() }
 	}
 
 	/// Get a consensus state for client
@@ -240,26 +444,70 @@ where
 		Ok(IdentifiedClientState { client_id: client_id.as_bytes().to_vec(), client_state })
 	}
 
-	/// Get all channel states
-	pub fn channels() -> Result<QueryChannelsResponse, Error<T>> {
-		let channels = Channels::<T>::iter()
-			.map(|(port_id, channel_id, channel_end)| {
-				Ok(IdentifiedChannel { channel_id, port_id, channel_end })
-			})
-			.collect::<Result<Vec<_>, Error<T>>>()?;
+	/// Get all channel states, or a page of them
+	pub fn channels(pagination: Option<PageRequest>) -> Result<QueryChannelsResponse, Error<T>> {
+		let (channels, pagination) = match pagination {
+			Some(page) => {
+				let (channels, pagination) =
+					paginate::<T, _>("channelEnds/ports/", page, |path, channel_end| {
+						match Path::from_str(path).ok()? {
+							Path::ChannelEnds(ChannelEndsPath(port_id, channel_id)) =>
+								Some(IdentifiedChannel {
+									channel_id: channel_id.to_string().as_bytes().to_vec(),
+									port_id: port_id.as_bytes().to_vec(),
+									channel_end,
+								}),
+							_ => None,
+						}
+					});
+				(channels, Some(pagination))
+			},
+			None => (
+				Channels::<T>::iter()
+					.map(|(port_id, channel_id, channel_end)| IdentifiedChannel {
+						channel_id,
+						port_id,
+						channel_end,
+					})
+					.collect::<Vec<_>>(),
+				None,
+			),
+		};
 
-		Ok(QueryChannelsResponse { channels, height: host_height::<T>() })
+		Ok(QueryChannelsResponse { channels, pagination, height: host_height::<T>() })
 	}
 
-	/// Get all connection states
-	pub fn connections() -> Result<QueryConnectionsResponse, Error<T>> {
-		let connections = Connections::<T>::iter()
-			.map(|(connection_id, connection_end)| {
-				Ok(IdentifiedConnection { connection_id, connection_end })
-			})
-			.collect::<Result<Vec<_>, Error<T>>>()?;
+	/// Get all connection states, or a page of them
+	pub fn connections(
+		pagination: Option<PageRequest>,
+	) -> Result<QueryConnectionsResponse, Error<T>> {
+		let (connections, pagination) = match pagination {
+			Some(page) => {
+				let (connections, pagination) =
+					paginate::<T, _>("connections/", page, |path, connection_end| {
+						match Path::from_str(path).ok()? {
+							Path::Connections(ConnectionsPath(connection_id)) =>
+								Some(IdentifiedConnection {
+									connection_id: connection_id.as_bytes().to_vec(),
+									connection_end,
+								}),
+							_ => None,
+						}
+					});
+				(connections, Some(pagination))
+			},
+			None => (
+				Connections::<T>::iter()
+					.map(|(connection_id, connection_end)| IdentifiedConnection {
+						connection_id,
+						connection_end,
+					})
+					.collect::<Vec<_>>(),
+				None,
+			),
+		};
 
-		Ok(QueryConnectionsResponse { connections, height: host_height::<T>() })
+		Ok(QueryConnectionsResponse { connections, pagination, height: host_height::<T>() })
 	}
 
 	/// Get all channels bound to this connection
@@ -283,60 +531,105 @@ where
 				})
 			})
 			.collect::<Result<Vec<_>, Error<T>>>()?;
-		Ok(QueryChannelsResponse { channels, height: host_height::<T>() })
+		Ok(QueryChannelsResponse { channels, pagination: None, height: host_height::<T>() })
 	}
 
 	pub fn packet_commitments(
 		channel_id_bytes: Vec<u8>,
 		port_id_bytes: Vec<u8>,
+		pagination: Option<PageRequest>,
 	) -> Result<QueryPacketCommitmentsResponse, Error<T>> {
 		let channel_id = channel_id_from_bytes(channel_id_bytes.clone())
 			.map_err(|_| Error::<T>::DecodingError)?;
 		let port_id =
 			port_id_from_bytes(port_id_bytes.clone()).map_err(|_| Error::<T>::DecodingError)?;
-		let commitments = PacketCommitment::<T>::iter()
-			.filter_map(|((p, c, s), commitment)| {
-				if p == port_id && c == channel_id {
-					let packet_state = PacketState {
-						port_id: port_id_bytes.clone(),
-						channel_id: channel_id_bytes.clone(),
-						sequence: s.into(),
-						data: commitment,
-					};
-					Some(packet_state)
-				} else {
-					None
-				}
-			})
-			.collect::<Vec<_>>();
+		let (commitments, pagination) = match pagination {
+			Some(page) => {
+				let prefix =
+					channel_prefix("commitments", port_id.as_str(), &channel_id.to_string());
+				let (commitments, pagination) =
+					paginate::<T, _>(&prefix, page, |path, commitment| {
+						match Path::from_str(path).ok()? {
+							Path::Commitments(CommitmentsPath { sequence, .. }) =>
+								Some(PacketState {
+									port_id: port_id_bytes.clone(),
+									channel_id: channel_id_bytes.clone(),
+									sequence: sequence.into(),
+									data: commitment,
+								}),
+							_ => None,
+						}
+					});
+				(commitments, Some(pagination))
+			},
+			None => (
+				PacketCommitment::<T>::iter()
+					.filter_map(|((p, c, s), commitment)| {
+						if p == port_id && c == channel_id {
+							let packet_state = PacketState {
+								port_id: port_id_bytes.clone(),
+								channel_id: channel_id_bytes.clone(),
+								sequence: s.into(),
+								data: commitment,
+							};
+							Some(packet_state)
+						} else {
+							None
+						}
+					})
+					.collect::<Vec<_>>(),
+				None,
+			),
+		};
 
-		Ok(QueryPacketCommitmentsResponse { commitments, height: host_height::<T>() })
+		Ok(QueryPacketCommitmentsResponse { commitments, pagination, height: host_height::<T>() })
 	}
 
 	pub fn packet_acknowledgements(
 		channel_id_bytes: Vec<u8>,
 		port_id_bytes: Vec<u8>,
+		pagination: Option<PageRequest>,
 	) -> Result<QueryPacketAcknowledgementsResponse, Error<T>> {
 		let channel_id = channel_id_from_bytes(channel_id_bytes.clone())
 			.map_err(|_| Error::<T>::DecodingError)?;
 		let port_id =
 			port_id_from_bytes(port_id_bytes.clone()).map_err(|_| Error::<T>::DecodingError)?;
-		let acks = Acknowledgements::<T>::iter()
-			.filter_map(|((p, c, s), ack)| {
-				if p == port_id && c == channel_id {
-					let packet_state = PacketState {
-						port_id: port_id_bytes.clone(),
-						channel_id: channel_id_bytes.clone(),
-						sequence: s.into(),
-						data: ack,
-					};
-					Some(packet_state)
-				} else {
-					None
-				}
-			})
-			.collect::<Vec<_>>();
-		Ok(QueryPacketAcknowledgementsResponse { acks, height: host_height::<T>() })
+		let (acks, pagination) = match pagination {
+			Some(page) => {
+				let prefix = channel_prefix("acks", port_id.as_str(), &channel_id.to_string());
+				let (acks, pagination) = paginate::<T, _>(&prefix, page, |path, ack| {
+					match Path::from_str(path).ok()? {
+						Path::Acks(AcksPath { sequence, .. }) => Some(PacketState {
+							port_id: port_id_bytes.clone(),
+							channel_id: channel_id_bytes.clone(),
+							sequence: sequence.into(),
+							data: ack,
+						}),
+						_ => None,
+					}
+				});
+				(acks, Some(pagination))
+			},
+			None => (
+				Acknowledgements::<T>::iter()
+					.filter_map(|((p, c, s), ack)| {
+						if p == port_id && c == channel_id {
+							let packet_state = PacketState {
+								port_id: port_id_bytes.clone(),
+								channel_id: channel_id_bytes.clone(),
+								sequence: s.into(),
+								data: ack,
+							};
+							Some(packet_state)
+						} else {
+							None
+						}
+					})
+					.collect::<Vec<_>>(),
+				None,
+			),
+		};
+		Ok(QueryPacketAcknowledgementsResponse { acks, pagination, height: host_height::<T>() })
 	}
 
 	pub fn unreceived_packets(
@@ -733,7 +1026,9 @@ impl<T: Config> Pallet<T> {
 		asset_id: T::AssetId,
 	) -> Option<ibc_primitives::QueryDenomTraceResponse> {
 		T::IbcDenomToAssetIdConversion::from_asset_id_to_denom(asset_id).map(|denom| {
-			ibc_primitives::QueryDenomTraceResponse { denom: denom.as_bytes().to_vec() }
+			let denom = denom.as_bytes().to_vec();
+			let metadata = IbcAssetMetadata::<T>::get(&denom);
+			ibc_primitives::QueryDenomTraceResponse { denom, metadata }
 		})
 	}
 
@@ -744,8 +1039,10 @@ impl<T: Config> Pallet<T> {
 	) -> ibc_primitives::QueryDenomTracesResponse {
 		let IbcAssets { denoms, total_count, next_id } =
 			T::IbcDenomToAssetIdConversion::ibc_assets(key, limit);
+		let metadata = denoms.iter().map(IbcAssetMetadata::<T>::get).collect();
 		ibc_primitives::QueryDenomTracesResponse {
 			denoms,
+			metadata,
 			total: count_total.then_some(total_count),
 			next_key: next_id.map(|key| key.encode()),
 		}
@@ -959,15 +1256,11 @@ where
 
 	fn write_acknowledgement(packet: Packet, ack: Vec<u8>) -> Result<(), IbcHandlerError> {
 		let mut ctx = Context::<T>::default();
-		let error = |action, err| {
-			let msg = Some(format!("Failed to {action} acknowledgement{err:?}"));
+		let output = ctx.write_acknowledgement(packet, ack.into()).map_err(|e| {
+			let msg = Some(format!("Failed to write acknowledgement{e:?}"));
 			IbcHandlerError::AcknowledgementError { msg }
-		};
-		let result =
-			ibc::core::ics04_channel::handler::write_acknowledgement::process(&ctx, packet, ack)
-				.map_err(|e| error("validate", e))?;
-		ctx.store_packet_result(result.result).map_err(|e| error("store", e))?;
-		Self::deposit_event(result.events.into());
+		})?;
+		Self::deposit_event(output.events.into());
 		Ok(())
 	}
 
@@ -1043,10 +1336,56 @@ where
 	}
 
 	pub(crate) fn send_transfer(msg: MsgTransfer<PrefixedCoin>) -> Result<(), IbcHandlerError> {
+		T::Ics20RateLimiter::on_send(&msg.source_channel, core::slice::from_ref(&msg.token))
+			.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
 		let mut ctx = Context::<T>::default();
 		let mut handler_output = HandlerOutputBuilder::default();
+		let channel_id = msg.source_channel;
+		let token = msg.token.clone();
 		send_transfer::<_, _>(&mut ctx, &mut handler_output, msg)
 			.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
+		Self::record_sent_escrow(channel_id, core::slice::from_ref(&token));
+		let result = handler_output.with_result(());
+		Self::deposit_event(result.events.into());
+		Ok(())
+	}
+
+	pub(crate) fn send_transfer_v2(
+		msg: MsgTransferV2<PrefixedCoin>,
+	) -> Result<(), IbcHandlerError> {
+		T::Ics20RateLimiter::on_send(&msg.source_channel, &msg.tokens)
+			.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
+		let mut ctx = Context::<T>::default();
+		let mut handler_output = HandlerOutputBuilder::default();
+		let channel_id = msg.source_channel;
+		let tokens = msg.tokens.clone();
+		send_transfer_v2::<_, _>(&mut ctx, &mut handler_output, msg)
+			.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
+		Self::record_sent_escrow(channel_id, &tokens);
+		let result = handler_output.with_result(());
+		Self::deposit_event(result.events.into());
+		Ok(())
+	}
+
+	/// Records the tokens sent over `channel_id` which this chain is the source of, and which
+	/// were thus escrowed, or the vouchers which were burnt.
+	fn record_sent_escrow(channel_id: ChannelId, tokens: &[PrefixedCoin]) {
+		for token in tokens {
+			let (channel, denom) = (channel_id.sequence(), token.denom.to_string());
+			let amount = token.amount.as_u256().low_u128();
+			if is_sender_chain_source(PortId::transfer(), channel_id, &token.denom) {
+				Self::record_escrow(channel, &denom, amount);
+			} else {
+				Self::record_voucher_burn(channel, &denom, amount);
+			}
+		}
+	}
+
+	pub(crate) fn send_nft_transfer(msg: MsgNftTransfer) -> Result<(), IbcHandlerError> {
+		let mut ctx = Context::<T>::default();
+		let mut handler_output = HandlerOutputBuilder::default();
+		send_nft_transfer(&mut ctx, &mut handler_output, msg)
+			.map_err(|e| IbcHandlerError::SendTransferError { msg: Some(e.to_string()) })?;
 		let result = handler_output.with_result(());
 		Self::deposit_event(result.events.into());
 		Ok(())
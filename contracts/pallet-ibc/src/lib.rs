@@ -43,7 +43,7 @@ use scale_info::{
 	},
 	TypeInfo,
 };
-use sp_runtime::{Either, RuntimeDebug};
+use sp_runtime::{DispatchResult, Either, RuntimeDebug};
 use sp_std::{marker::PhantomData, prelude::*, str::FromStr};
 
 mod channel;
@@ -53,11 +53,14 @@ pub mod errors;
 pub mod events;
 pub mod ics20;
 mod ics23;
+pub mod ics27;
+pub mod ics29;
+pub mod ics721;
 pub mod light_clients;
 mod port;
 pub mod routing;
 pub use client::HostConsensusProof;
-pub use ibc_primitives::Timeout;
+pub use ibc_primitives::{AssetMetadata, Timeout};
 pub use light_client_common;
 
 pub const MODULE_ID: &str = "pallet_ibc";
@@ -111,6 +114,50 @@ pub struct TransferParams<AccountId> {
 	pub timeout: Timeout,
 }
 
+/// Fees paid to the relayers of a packet, as amounts of assets of this chain.
+#[derive(
+	frame_support::RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo, Encode, Decode, Clone,
+)]
+pub struct PacketFeeParams<AssetId, Balance> {
+	/// Paid to the relayer delivering the packet
+	pub recv_fee: Vec<(AssetId, Balance)>,
+	/// Paid to the relayer delivering the acknowledgement
+	pub ack_fee: Vec<(AssetId, Balance)>,
+	/// Paid to the relayer delivering the timeout
+	pub timeout_fee: Vec<(AssetId, Balance)>,
+}
+
+/// Directions in which the ICS-20 transfers over a channel, or of an asset, are paused.
+#[derive(
+	frame_support::RuntimeDebug,
+	PartialEq,
+	Eq,
+	scale_info::TypeInfo,
+	Encode,
+	Decode,
+	Clone,
+	Copy,
+	Default,
+)]
+pub struct TransferPause {
+	/// Transfers sent by this chain fail
+	pub send: bool,
+	/// Transfers received by this chain are acknowledged with an error
+	pub receive: bool,
+}
+
+/// Outcome of a message dry-run by [`Pallet::simulate_deliver`].
+#[derive(
+	frame_support::RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo, Encode, Decode, Clone,
+)]
+pub struct SimulatedMessage {
+	/// Events emitted by the message, or the error it failed with
+	pub result: Result<Vec<events::IbcEvent>, errors::IbcError>,
+	/// Weight charged for the message by `deliver`, computed from the benchmarks rather than
+	/// measured by the dry-run
+	pub benchmarked_weight: Weight,
+}
+
 #[derive(
 	frame_support::RuntimeDebug, PartialEq, Eq, scale_info::TypeInfo, Encode, Decode, Clone,
 )]
@@ -128,6 +175,7 @@ mod mock;
 mod tests;
 
 pub mod ics20_fee;
+pub mod ics20_rate_limit;
 mod impls;
 pub mod weight;
 
@@ -140,16 +188,14 @@ pub mod pallet {
 	use super::*;
 	use core::fmt::Display;
 
-	use core::time::Duration;
-
 	use frame_support::{
-		dispatch::DispatchResult,
+		dispatch::{DispatchResult, GetDispatchInfo, PostDispatchInfo},
 		pallet_prelude::*,
 		storage::child,
 		traits::{
 			fungibles::{Inspect, Mutate},
 			tokens::{AssetId, Balance},
-			ReservableCurrency, UnixTime,
+			Contains, ReservableCurrency, UnixTime,
 		},
 	};
 	use frame_system::pallet_prelude::*;
@@ -167,23 +213,47 @@ pub mod pallet {
 		routing::{Context, ModuleRouter},
 	};
 	use ibc::{
-		applications::transfer::{
-			context::BankKeeper, is_sender_chain_source, msgs::transfer::MsgTransfer, Amount,
-			PrefixedCoin, PrefixedDenom,
+		applications::{
+			fee::{
+				handler as fee_handler,
+				msgs::{
+					pay_packet_fee::MsgPayPacketFee, pay_packet_fee_async::MsgPayPacketFeeAsync,
+					register_counterparty_payee::MsgRegisterCounterpartyPayee,
+					register_payee::MsgRegisterPayee,
+				},
+				packet_fee::PacketFee as IbcPacketFee,
+			},
+			interchain_accounts::{
+				context::Ics27ControllerContext, controller, controller_port_id,
+				packet::InterchainAccountPacketData,
+			},
+			nft_transfer::{
+				msgs::transfer::MsgTransfer as MsgNftTransfer, PrefixedClassId, TokenId,
+			},
+			transfer::{
+				context::BankKeeper,
+				is_sender_chain_source,
+				msgs::{transfer::MsgTransfer, transfer_v2::MsgTransferV2},
+				Amount, PrefixedCoin, PrefixedDenom,
+			},
 		},
 		bigint::U256,
 		core::{
 			ics02_client::context::{ClientKeeper, ClientReader},
-			ics04_channel::context::ChannelReader,
-			ics24_host::identifier::{ChannelId, ClientId, PortId},
+			ics04_channel::{
+				channel::Order, context::ChannelReader,
+				msgs::chan_upgrade_init::MsgChannelUpgradeInit, upgrade::UpgradeFields, Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 		},
-		timestamp::Timestamp,
+		handler::HandlerOutputBuilder,
+		tx_msg::Msg,
 		Height,
 	};
-	use ibc_primitives::{client_id_from_bytes, get_channel_escrow_address, IbcHandler};
+	use ibc_primitives::{client_id_from_bytes, get_channel_escrow_address};
 	use light_clients::AnyClientState;
 	use sp_runtime::{
-		traits::{IdentifyAccount, Saturating, Zero},
+		traits::{Dispatchable, IdentifyAccount, Saturating, Zero},
 		AccountId32, BoundedBTreeSet, Perbill,
 	};
 	#[cfg(feature = "std")]
@@ -308,6 +378,42 @@ pub mod pallet {
 		type FlatFeeAssetId: Get<Self::AssetId>;
 		//Asset amount that will be charged. for example 10 (USDT)
 		type FlatFeeAmount: Get<Self::Balance>;
+
+		/// The runtime call dispatched by the interchain accounts hosted on this chain
+		type RuntimeCall: Parameter
+			+ Dispatchable<
+				RuntimeOrigin = <Self as frame_system::Config>::RuntimeOrigin,
+				PostInfo = PostDispatchInfo,
+			> + GetDispatchInfo;
+		/// Calls that the interchain accounts controlled by counterparty chains are allowed to
+		/// dispatch
+		type IcaAllowedCalls: Contains<<Self as Config>::RuntimeCall>;
+		/// Calls that the memos of the transfers received may dispatch, see
+		/// [`ics20::hooks::IbcHooksHandler`]
+		type MemoAllowedCalls: Contains<<Self as Config>::RuntimeCall>;
+		/// Smart contracts called by the memos of the transfers received, usually backed by
+		/// `pallet-contracts`
+		type ContractHandler: ics20::hooks::ContractHandler<Self::AccountId>;
+		/// Limits the tokens sent over the transfer port, usually
+		/// [`ics20_rate_limit::Pallet`] along with the [`ics20_rate_limit::Ics20RateLimit`]
+		/// middleware limiting the tokens received
+		type Ics20RateLimiter: ics20::Ics20RateLimiter;
+		/// Whether counterparty chains may register and control interchain accounts on this chain
+		type IsIcaHostEnabled: Get<bool>;
+		/// Whether accounts of this chain may register and control interchain accounts on
+		/// counterparty chains
+		type IsIcaControllerEnabled: Get<bool>;
+		/// Non-fungible tokens of the runtime transferred by the ICS721 application, usually
+		/// backed by `pallet-nfts` or `pallet-uniques`
+		type NftRegistry: ics721::NftRegistry<Self::AccountId>;
+		/// Asset registry of the runtime, notified of the metadata registered for IBC assets
+		type AssetRegistry: IbcAssetRegistry<Self::AssetId>;
+		/// Whether the IBC assets received for the first time are registered with placeholder
+		/// metadata until governance registers theirs
+		type AutoRegisterAssetMetadata: Get<bool>;
+		/// Whether the transfers over a channel are paused when its escrow account is found to
+		/// hold less than the tokens escrowed, see [`ics20::escrow`]
+		type PauseOnEscrowViolation: Get<bool>;
 	}
 
 	#[pallet::pallet]
@@ -400,6 +506,26 @@ pub mod pallet {
 	pub type IbcDenoms<T: Config> =
 		CountedStorageMap<_, Twox64Concat, Vec<u8>, T::AssetId, OptionQuery>;
 
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// ibc denom => metadata of the asset, ibc denoms represented as utf8 string bytes
+	pub type IbcAssetMetadata<T: Config> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, AssetMetadata, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (channel sequence, denom) => net amount of the asset escrowed by the transfers over the
+	/// channel, denoms represented as utf8 string bytes
+	pub type EscrowedAmounts<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, u64, Blake2_128Concat, Vec<u8>, u128, ValueQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (channel sequence, denom) => net amount of vouchers of the asset minted by the transfers
+	/// received over the channel, denoms represented as utf8 string bytes
+	pub type ReceivedVoucherAmounts<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, u64, Blake2_128Concat, Vec<u8>, u128, OptionQuery>;
+
 	#[pallet::storage]
 	#[allow(clippy::disallowed_types)]
 	/// ChannelIds open from this module
@@ -452,6 +578,132 @@ pub mod pallet {
 	pub type PendingRecvPacketSeqs<T: Config> =
 		StorageMap<_, Blake2_128Concat, (Vec<u8>, Vec<u8>), (BTreeSet<u64>, u64), ValueQuery>;
 
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (connection_id, controller port_id) => interchain account hosted on this chain
+	pub type IcaHostAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		<T as frame_system::Config>::AccountId,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (connection_id, controller port_id) => channel sequence of the active host channel
+	pub type IcaHostActiveChannels<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Vec<u8>, Blake2_128Concat, Vec<u8>, u64, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (connection_id, controller port_id) => address of the interchain account on the host
+	/// chain, represented as utf8 string bytes
+	pub type IcaControllerAccounts<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Vec<u8>,
+		Blake2_128Concat,
+		Vec<u8>,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (connection_id, controller port_id) => channel sequence of the active controller channel
+	pub type IcaControllerActiveChannels<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Vec<u8>, Blake2_128Concat, Vec<u8>, u64, OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (port_id, channel sequence) => () for the channels opened with the ICS29 fee version
+	pub type FeeEnabledChannels<T: Config> =
+		StorageDoubleMap<_, Blake2_128Concat, Vec<u8>, Blake2_128Concat, u64, (), OptionQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (channel sequence, relayer) => account the ICS29 fees earned by the relayer are paid to
+	pub type FeePayees<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		<T as frame_system::Config>::AccountId,
+		<T as frame_system::Config>::AccountId,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (channel sequence, relayer) => address on the counterparty chain the ICS29 recv fees
+	/// earned by the relayer are paid to, represented as utf8 string bytes
+	pub type FeeCounterpartyPayees<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		<T as frame_system::Config>::AccountId,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (port_id, channel sequence), packet sequence => address on the counterparty chain the recv
+	/// fee of a packet acknowledged asynchronously is paid to, represented as utf8 string bytes
+	pub type FeeForwardRelayers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(Vec<u8>, u64),
+		Blake2_128Concat,
+		u64,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (port_id, channel sequence), packet sequence => protobuf encoded ICS29 fees escrowed for
+	/// the packet
+	pub type PacketFees<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		(Vec<u8>, u64),
+		Blake2_128Concat,
+		u64,
+		Vec<u8>,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// (channel sequence, packet sequence) of a packet forwarding tokens on the transfer port =>
+	/// the received packet whose memo forwarded them
+	pub type InFlightForwards<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		u64,
+		Blake2_128Concat,
+		u64,
+		ics20::InFlightForward,
+		OptionQuery,
+	>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// channel sequence => directions in which the ICS-20 transfers over the channel are paused
+	pub type PausedChannels<T: Config> =
+		StorageMap<_, Blake2_128Concat, u64, TransferPause, ValueQuery>;
+
+	#[pallet::storage]
+	#[allow(clippy::disallowed_types)]
+	/// asset id => directions in which the ICS-20 transfers of the asset are paused
+	pub type PausedAssets<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, TransferPause, ValueQuery>;
+
 	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 	pub struct AssetConfig<AssetId> {
 		pub id: AssetId,
@@ -616,6 +868,7 @@ pub mod pallet {
 			channel: u64,
 			next_memo: Option<T::MemoMessage>,
 		},
+		/// The execution of a memo failed, `reason` is a [`crate::ics20::MemoFailureReason`] code
 		ExecuteMemoIbcTokenTransferFailedWithReason {
 			from: T::AccountId,
 			memo: String,
@@ -643,6 +896,94 @@ pub mod pallet {
 			asset_id: T::AssetId,
 			para_id: Option<u32>,
 		},
+		/// The call or contract call of the memo of a received transfer has been executed by the
+		/// intermediate account of its sender
+		ExecuteMemoHookSuccess {
+			from: T::AccountId,
+			contract: Option<T::AccountId>,
+		},
+		/// A contract has been notified of the acknowledgement or timeout of a transfer it sent
+		IbcCallbackExecuted {
+			contract: T::AccountId,
+			channel: u64,
+			sequence: u64,
+		},
+		/// The metadata of an IBC asset has been registered or updated
+		AssetMetadataSet {
+			denom: Vec<u8>,
+			metadata: AssetMetadata,
+		},
+		/// The metadata of an IBC asset has been removed
+		AssetMetadataRemoved {
+			denom: Vec<u8>,
+		},
+		/// The escrow account of a channel holds less of an asset than the transfers escrowed
+		EscrowInvariantViolated {
+			channel: u64,
+			denom: Vec<u8>,
+			escrowed: u128,
+			balance: u128,
+		},
+		/// More vouchers of an asset have been issued than the transfers over a channel received
+		VoucherInvariantViolated {
+			channel: u64,
+			denom: Vec<u8>,
+			received: u128,
+			issuance: u128,
+		},
+		/// The ICS-20 transfers over a channel have been paused or resumed
+		ChannelPauseSet {
+			channel: u64,
+			pause: TransferPause,
+		},
+		/// The ICS-20 transfers of an asset have been paused or resumed
+		AssetPauseSet {
+			asset_id: T::AssetId,
+			pause: TransferPause,
+		},
+		/// A packet forwarding the tokens of a received packet timed out and has been sent again
+		ExecuteMemoForwardRetried {
+			channel: u64,
+			sequence: u64,
+			retries_remaining: u64,
+		},
+		/// A packet forwarding the tokens of a received packet failed, the received packet is
+		/// acknowledged with an error so the tokens are refunded to its sender
+		ExecuteMemoForwardFailed {
+			channel: u64,
+			sequence: u64,
+		},
+		/// The channel handshake registering an interchain account has been initiated
+		InterchainAccountRegistrationInitiated {
+			owner: Vec<u8>,
+			connection_id: Vec<u8>,
+			port_id: Vec<u8>,
+		},
+		/// A transaction has been sent to an interchain account
+		InterchainAccountTxSent {
+			owner: Vec<u8>,
+			connection_id: Vec<u8>,
+			sequence: u64,
+		},
+		/// Fees have been escrowed for the relayers of a packet
+		PacketFeeEscrowed {
+			payer: Vec<u8>,
+			port_id: Vec<u8>,
+			channel_id: Vec<u8>,
+			sequence: u64,
+		},
+		/// A relayer registered the account its fees are paid to
+		PayeeRegistered {
+			relayer: T::AccountId,
+			payee: T::AccountId,
+			channel_id: Vec<u8>,
+		},
+		/// A relayer registered the address its recv fees are paid to on the counterparty chain
+		CounterpartyPayeeRegistered {
+			relayer: T::AccountId,
+			counterparty_payee: Vec<u8>,
+			channel_id: Vec<u8>,
+		},
 	}
 
 	/// Errors inform users that something went wrong.
@@ -726,6 +1067,32 @@ pub mod pallet {
 		/// - The memo is in invalid format
 		/// - The memo contains unsupported middlewares
 		InvalidMemo,
+		/// Invalid connection id
+		InvalidConnectionId,
+		/// Error registering an interchain account
+		InterchainAccountRegistration,
+		/// Error sending a transaction to an interchain account
+		InterchainAccountTx,
+		/// Error escrowing the fees of a packet
+		PacketFee,
+		/// Error registering a payee
+		RegisterPayee,
+		/// No assets were provided for a multi-denom transfer
+		EmptyTransfer,
+		/// Invalid ICS721 class id
+		InvalidClassId,
+		/// Invalid ICS721 token id
+		InvalidTokenId,
+		/// Error initiating a channel upgrade
+		ChannelUpgrade,
+		/// Channel upgrade not found
+		ChannelUpgradeNotFound,
+		/// The transfers over the channel or of the asset are paused
+		TransferPaused,
+		/// The name or the symbol of the asset metadata is empty, too long or not utf8
+		InvalidAssetMetadata,
+		/// Error registering the asset metadata in the asset registry of the runtime
+		AssetRegistry,
 	}
 
 	#[pallet::hooks]
@@ -750,6 +1117,20 @@ pub mod pallet {
 		}
 
 		fn offchain_worker(_n: BlockNumberFor<T>) {}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			let violations = Pallet::<T>::escrow_violations();
+			for violation in &violations {
+				log::error!(target: "pallet_ibc", "Escrow invariant violated: {:?}", violation);
+			}
+			ensure!(
+				violations.is_empty(),
+				"Escrow accounts hold less than the tokens escrowed or vouchers were issued \
+				 outside of the transfers received"
+			);
+			Ok(())
+		}
 	}
 
 	// Dispatch able functions allows users to interact with the pallet and invoke state changes.
@@ -768,6 +1149,7 @@ pub mod pallet {
 		pub fn deliver(origin: OriginFor<T>, messages: Vec<Any>) -> DispatchResult {
 			use ibc::core::{
 				ics02_client::msgs::create_client, ics03_connection::msgs::conn_open_init,
+				ics04_channel::msgs::chan_upgrade_init,
 			};
 			let sender = T::RelayerOrigin::ensure_origin(origin)?;
 
@@ -777,6 +1159,14 @@ pub mod pallet {
 			let mut reserve_count = 0u128;
 			let messages = messages
 				.into_iter()
+				.filter(|message| {
+					// channel upgrades are only initiated through `upgrade_channel`
+					let is_upgrade_init = message.type_url.as_str() == chan_upgrade_init::TYPE_URL;
+					if is_upgrade_init {
+						log::warn!(target: "pallet_ibc", "[deliver]: ignoring MsgChannelUpgradeInit");
+					}
+					!is_upgrade_init
+				})
 				.map(|message| {
 					if matches!(
 						message.type_url.as_str(),
@@ -816,6 +1206,10 @@ pub mod pallet {
 			memo: Option<T::MemoMessage>,
 		) -> DispatchResult {
 			let account_id_32 = T::TransferOrigin::ensure_origin(origin)?.into();
+			ensure!(
+				!Self::transfer_pause(params.source_channel, Some(&asset_id)).send,
+				Error::<T>::TransferPaused
+			);
 			let denom = T::IbcDenomToAssetIdConversion::from_asset_id_to_denom(asset_id)
 				.ok_or(Error::<T>::InvalidAssetId)?;
 			let from = {
@@ -843,43 +1237,8 @@ pub mod pallet {
 			let mut coin = PrefixedCoin { denom, amount: ibc_amount };
 			let source_channel = ChannelId::new(params.source_channel);
 			let source_port = PortId::transfer();
-			let (latest_height, _) =
-				Pallet::<T>::latest_height_and_timestamp(&source_port, &source_channel)
-					.map_err(|_| Error::<T>::TimestampAndHeightNotFound)?;
-
-			let (timeout_height, timeout_timestamp) = match params.timeout {
-				Timeout::Offset { timestamp, height } => {
-					let latest_timestamp = T::TimeProvider::now();
-					let timestamp = timestamp
-						.map(|offset| {
-							Timestamp::from_nanoseconds(
-								(latest_timestamp + Duration::from_secs(offset)).as_nanos() as u64,
-							)
-						})
-						.transpose()
-						.map_err(|_| Error::<T>::InvalidTimestamp)?
-						.unwrap_or_default();
-					let height = height.map(|offset| latest_height.add(offset)).unwrap_or_default();
-					(height, timestamp)
-				},
-				Timeout::Absolute { timestamp, height } => {
-					let timestamp = timestamp
-						.map(Timestamp::from_nanoseconds)
-						.transpose()
-						.map_err(|_| Error::<T>::InvalidTimestamp)?
-						.unwrap_or_default();
-					let height = height
-						.map(|revision_height| {
-							Height::new(latest_height.revision_number, revision_height)
-						})
-						.unwrap_or_default();
-					(height, timestamp)
-				},
-			};
-
-			if timeout_height.is_zero() && timeout_timestamp.nanoseconds() == 0 {
-				return Err(Error::<T>::InvalidTimestamp.into())
-			}
+			let (timeout_height, timeout_timestamp) =
+				Pallet::<T>::packet_timeout(&source_port, &source_channel, params.timeout)?;
 
 			let mut ctx = Context::<T>::default();
 			let channel_end = ctx
@@ -1112,6 +1471,16 @@ pub mod pallet {
 							.map_err(|_| Error::<T>::ClientFreezeFailed)?,
 					)
 				},
+				// solo machines are frozen at their current sequence
+				AnyClientState::SoloMachine(solo_machine) =>
+					AnyClientState::wrap(&solo_machine.with_frozen()),
+				AnyClientState::Near(near) => {
+					let latest_height = near.latest_height();
+					AnyClientState::wrap(
+						&near
+							.with_frozen_height(Height::new(latest_height.revision_number, height)),
+					)
+				},
 				AnyClientState::Wasm(_) => return Err(Error::<T>::ClientFreezeFailed.into()),
 				#[cfg(test)]
 				AnyClientState::Mock(mut ms) => {
@@ -1264,6 +1633,591 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Registers an interchain account owned by the origin on the chain at the other end of
+		/// the connection, by initiating an ICS-27 channel handshake. The address of the account
+		/// is known once the handshake completes. An empty `version` proposes the default
+		/// metadata.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_interchain_account())]
+		#[frame_support::transactional]
+		pub fn register_interchain_account(
+			origin: OriginFor<T>,
+			connection_id: String,
+			version: String,
+		) -> DispatchResult {
+			let owner =
+				ics27::context::account_to_signer(T::TransferOrigin::ensure_origin(origin)?.into())
+					.map_err(|_| Error::<T>::OriginAddress)?;
+			let connection_id = ConnectionId::from_str(&connection_id)
+				.map_err(|_| Error::<T>::InvalidConnectionId)?;
+
+			let mut ctx = Context::<T>::new();
+			let msg = controller::register_interchain_account(
+				&ctx,
+				owner.clone(),
+				connection_id.clone(),
+				Version::new(version),
+			)
+			.map_err(|e| {
+				log::debug!(target: "pallet_ibc", "[register_interchain_account]: error: {}", e);
+				Error::<T>::InterchainAccountRegistration
+			})?;
+			let port_id = msg.port_id.clone();
+			let msg = ibc_proto::google::protobuf::Any {
+				type_url: msg.type_url(),
+				value: msg.encode_vec().map_err(|_| Error::<T>::EncodingError)?,
+			};
+			let receipt =
+				ibc::core::ics26_routing::handler::deliver(&mut ctx, msg).map_err(|e| {
+					log::debug!(target: "pallet_ibc", "[register_interchain_account]: error: {}", e);
+					Error::<T>::InterchainAccountRegistration
+				})?;
+			Self::deposit_event(receipt.events.into());
+			Self::deposit_event(Event::<T>::InterchainAccountRegistrationInitiated {
+				owner: owner.as_ref().as_bytes().to_vec(),
+				connection_id: connection_id.as_bytes().to_vec(),
+				port_id: port_id.as_bytes().to_vec(),
+			});
+
+			Ok(())
+		}
+
+		/// Sends a transaction to the interchain account of the origin on the chain at the other
+		/// end of the connection. The account executes the `messages` in order, and the result is
+		/// reported in the acknowledgement of the packet.
+		#[pallet::call_index(11)]
+		#[pallet::weight(<T as Config>::WeightInfo::send_ica_tx())]
+		#[frame_support::transactional]
+		pub fn send_ica_tx(
+			origin: OriginFor<T>,
+			connection_id: String,
+			messages: Vec<Any>,
+			memo: String,
+			timeout: Timeout,
+		) -> DispatchResult {
+			let owner =
+				ics27::context::account_to_signer(T::TransferOrigin::ensure_origin(origin)?.into())
+					.map_err(|_| Error::<T>::OriginAddress)?;
+			let connection_id = ConnectionId::from_str(&connection_id)
+				.map_err(|_| Error::<T>::InvalidConnectionId)?;
+
+			let mut ctx = Context::<T>::new();
+			let port_id = controller_port_id(&owner).map_err(|_| Error::<T>::InvalidPortId)?;
+			let channel_id = Ics27ControllerContext::active_channel(&ctx, &connection_id, &port_id)
+				.ok_or(Error::<T>::ChannelNotFound)?;
+			let (timeout_height, timeout_timestamp) =
+				Pallet::<T>::packet_timeout(&port_id, &channel_id, timeout)?;
+
+			let data = InterchainAccountPacketData::execute_tx(
+				messages.into_iter().map(Into::into).collect(),
+				memo,
+			);
+			let mut output = HandlerOutputBuilder::default();
+			let sequence = controller::send_tx(
+				&mut ctx,
+				&mut output,
+				&owner,
+				&connection_id,
+				data,
+				timeout_height,
+				timeout_timestamp,
+			)
+			.map_err(|e| {
+				log::debug!(target: "pallet_ibc", "[send_ica_tx]: error: {}", e);
+				Error::<T>::InterchainAccountTx
+			})?;
+			Self::deposit_event(output.with_result(()).events.into());
+			Self::deposit_event(Event::<T>::InterchainAccountTxSent {
+				owner: owner.as_ref().as_bytes().to_vec(),
+				connection_id: connection_id.as_bytes().to_vec(),
+				sequence: sequence.into(),
+			});
+
+			Ok(())
+		}
+
+		/// Escrows fees for the relayers of the next packet sent on an ICS-29 fee enabled
+		/// channel. The fees that aren't paid to a relayer are refunded to the origin.
+		#[pallet::call_index(12)]
+		#[pallet::weight(<T as Config>::WeightInfo::pay_packet_fee())]
+		#[frame_support::transactional]
+		pub fn pay_packet_fee(
+			origin: OriginFor<T>,
+			port_id: String,
+			channel_id: String,
+			fee: PacketFeeParams<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let payer =
+				ics27::context::account_to_signer(T::TransferOrigin::ensure_origin(origin)?.into())
+					.map_err(|_| Error::<T>::OriginAddress)?;
+			let msg = MsgPayPacketFee {
+				fee: ics29::fee_from_params::<T>(fee)?,
+				source_port_id: PortId::from_str(&port_id)
+					.map_err(|_| Error::<T>::InvalidPortId)?,
+				source_channel_id: ChannelId::from_str(&channel_id)
+					.map_err(|_| Error::<T>::InvalidChannelId)?,
+				signer: payer.clone(),
+				relayers: vec![],
+			};
+
+			let mut ctx = Context::<T>::new();
+			let mut output = HandlerOutputBuilder::default();
+			let sequence =
+				fee_handler::pay_packet_fee(&mut ctx, &mut output, msg).map_err(|e| {
+					log::debug!(target: "pallet_ibc", "[pay_packet_fee]: error: {}", e);
+					Error::<T>::PacketFee
+				})?;
+			Self::deposit_event(output.with_result(()).events.into());
+			Self::deposit_event(Event::<T>::PacketFeeEscrowed {
+				payer: payer.as_ref().as_bytes().to_vec(),
+				port_id: port_id.as_bytes().to_vec(),
+				channel_id: channel_id.as_bytes().to_vec(),
+				sequence: sequence.into(),
+			});
+
+			Ok(())
+		}
+
+		/// Escrows fees for the relayers of a packet sent on an ICS-29 fee enabled channel which
+		/// hasn't been acknowledged or timed out yet.
+		#[pallet::call_index(13)]
+		#[pallet::weight(<T as Config>::WeightInfo::pay_packet_fee())]
+		#[frame_support::transactional]
+		pub fn pay_packet_fee_async(
+			origin: OriginFor<T>,
+			port_id: String,
+			channel_id: String,
+			sequence: u64,
+			fee: PacketFeeParams<T::AssetId, T::Balance>,
+		) -> DispatchResult {
+			let payer =
+				ics27::context::account_to_signer(T::TransferOrigin::ensure_origin(origin)?.into())
+					.map_err(|_| Error::<T>::OriginAddress)?;
+			let msg = MsgPayPacketFeeAsync {
+				port_id: PortId::from_str(&port_id).map_err(|_| Error::<T>::InvalidPortId)?,
+				channel_id: ChannelId::from_str(&channel_id)
+					.map_err(|_| Error::<T>::InvalidChannelId)?,
+				sequence: sequence.into(),
+				packet_fee: IbcPacketFee::new(ics29::fee_from_params::<T>(fee)?, payer.clone()),
+			};
+
+			let mut ctx = Context::<T>::new();
+			let mut output = HandlerOutputBuilder::default();
+			fee_handler::pay_packet_fee_async(&mut ctx, &mut output, msg).map_err(|e| {
+				log::debug!(target: "pallet_ibc", "[pay_packet_fee_async]: error: {}", e);
+				Error::<T>::PacketFee
+			})?;
+			Self::deposit_event(output.with_result(()).events.into());
+			Self::deposit_event(Event::<T>::PacketFeeEscrowed {
+				payer: payer.as_ref().as_bytes().to_vec(),
+				port_id: port_id.as_bytes().to_vec(),
+				channel_id: channel_id.as_bytes().to_vec(),
+				sequence,
+			});
+
+			Ok(())
+		}
+
+		/// Registers the account the ICS-29 fees earned by the origin for relaying packets over
+		/// the channel are paid to.
+		#[pallet::call_index(14)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_payee())]
+		pub fn register_payee(
+			origin: OriginFor<T>,
+			port_id: String,
+			channel_id: String,
+			payee: <T as frame_system::Config>::AccountId,
+		) -> DispatchResult {
+			let relayer = T::RelayerOrigin::ensure_origin(origin)?;
+			let msg = MsgRegisterPayee {
+				port_id: PortId::from_str(&port_id).map_err(|_| Error::<T>::InvalidPortId)?,
+				channel_id: ChannelId::from_str(&channel_id)
+					.map_err(|_| Error::<T>::InvalidChannelId)?,
+				relayer: ics27::context::account_to_signer(relayer.clone().into())
+					.map_err(|_| Error::<T>::OriginAddress)?,
+				payee: ics27::context::account_to_signer(payee.clone().into())
+					.map_err(|_| Error::<T>::OriginAddress)?,
+			};
+
+			let mut ctx = Context::<T>::new();
+			let mut output = HandlerOutputBuilder::default();
+			fee_handler::register_payee(&mut ctx, &mut output, msg).map_err(|e| {
+				log::debug!(target: "pallet_ibc", "[register_payee]: error: {}", e);
+				Error::<T>::RegisterPayee
+			})?;
+			Self::deposit_event(output.with_result(()).events.into());
+			Self::deposit_event(Event::<T>::PayeeRegistered {
+				relayer,
+				payee,
+				channel_id: channel_id.as_bytes().to_vec(),
+			});
+
+			Ok(())
+		}
+
+		/// Registers the address of the counterparty chain the ICS-29 recv fees earned by the
+		/// origin are paid to, for the packets it delivers over the channel.
+		#[pallet::call_index(15)]
+		#[pallet::weight(<T as Config>::WeightInfo::register_payee())]
+		pub fn register_counterparty_payee(
+			origin: OriginFor<T>,
+			port_id: String,
+			channel_id: String,
+			counterparty_payee: String,
+		) -> DispatchResult {
+			let relayer = T::RelayerOrigin::ensure_origin(origin)?;
+			if counterparty_payee.trim().is_empty() {
+				return Err(Error::<T>::InvalidParams.into())
+			}
+			let msg = MsgRegisterCounterpartyPayee {
+				port_id: PortId::from_str(&port_id).map_err(|_| Error::<T>::InvalidPortId)?,
+				channel_id: ChannelId::from_str(&channel_id)
+					.map_err(|_| Error::<T>::InvalidChannelId)?,
+				relayer: ics27::context::account_to_signer(relayer.clone().into())
+					.map_err(|_| Error::<T>::OriginAddress)?,
+				counterparty_payee: counterparty_payee.clone(),
+			};
+
+			let mut ctx = Context::<T>::new();
+			let mut output = HandlerOutputBuilder::default();
+			fee_handler::register_counterparty_payee(&mut ctx, &mut output, msg).map_err(|e| {
+				log::debug!(target: "pallet_ibc", "[register_counterparty_payee]: error: {}", e);
+				Error::<T>::RegisterPayee
+			})?;
+			Self::deposit_event(output.with_result(()).events.into());
+			Self::deposit_event(Event::<T>::CounterpartyPayeeRegistered {
+				relayer,
+				counterparty_payee: counterparty_payee.into_bytes(),
+				channel_id: channel_id.as_bytes().to_vec(),
+			});
+
+			Ok(())
+		}
+
+		/// Transfers several assets in a single ICS-20 v2 packet. The channel must have
+		/// negotiated the `ics20-2` version. Unlike `transfer`, no service fee is charged.
+		#[pallet::call_index(16)]
+		#[frame_support::transactional]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_multi(assets.len() as u32))]
+		pub fn transfer_multi(
+			origin: OriginFor<T>,
+			params: TransferParams<<T as frame_system::Config>::AccountId>,
+			assets: Vec<(T::AssetId, T::Balance)>,
+			memo: Option<T::MemoMessage>,
+		) -> DispatchResult {
+			let account_id_32 = T::TransferOrigin::ensure_origin(origin)?.into();
+			ensure!(!assets.is_empty(), Error::<T>::EmptyTransfer);
+			ensure!(
+				assets.iter().all(|(asset_id, _)| {
+					!Self::transfer_pause(params.source_channel, Some(asset_id)).send
+				}),
+				Error::<T>::TransferPaused
+			);
+			let from = {
+				let mut hex_string = hex::encode(account_id_32.to_raw_vec());
+				hex_string.insert_str(0, "0x");
+				hex_string
+			};
+
+			let to = match params.to {
+				MultiAddress::Id(id) => {
+					let account_id_32: AccountId32 = id.into();
+					let mut hex_string = hex::encode(account_id_32.to_raw_vec());
+					hex_string.insert_str(0, "0x");
+					hex_string
+				},
+				MultiAddress::Raw(bytes) =>
+					String::from_utf8(bytes).map_err(|_| Error::<T>::Utf8Error)?,
+			};
+			let tokens = assets
+				.iter()
+				.map(|(asset_id, amount)| {
+					let denom = T::IbcDenomToAssetIdConversion::from_asset_id_to_denom(*asset_id)
+						.ok_or(Error::<T>::InvalidAssetId)?;
+					let denom = PrefixedDenom::from_str(&denom)
+						.map_err(|_| Error::<T>::PrefixedDenomParse)?;
+					let amount = Amount::from_str(&format!("{amount:?}"))
+						.map_err(|_| Error::<T>::InvalidAmount)?;
+					Ok(PrefixedCoin { denom, amount })
+				})
+				.collect::<Result<Vec<_>, Error<T>>>()?;
+			let source_channel = ChannelId::new(params.source_channel);
+			let source_port = PortId::transfer();
+			let (timeout_height, timeout_timestamp) =
+				Pallet::<T>::packet_timeout(&source_port, &source_channel, params.timeout)?;
+
+			let ctx = Context::<T>::default();
+			let channel_end = ctx
+				.channel_end(&(source_port.clone(), source_channel))
+				.map_err(|_| Error::<T>::ChannelNotFound)?;
+			let destination_channel =
+				channel_end.counterparty().channel_id.ok_or(Error::<T>::ChannelNotFound)?;
+
+			memo.as_ref()
+				.map(|memo| {
+					memo.validate().map_err(|e| {
+						log::debug!(target: "pallet_ibc", "[transfer_multi]: memo validation error: {}", e);
+						Error::<T>::InvalidMemo
+					})
+				})
+				.transpose()?;
+
+			let msg = MsgTransferV2 {
+				source_port: source_port.clone(),
+				source_channel,
+				tokens: tokens.clone(),
+				sender: Signer::from_str(&from).map_err(|_| Error::<T>::Utf8Error)?,
+				receiver: Signer::from_str(&to).map_err(|_| Error::<T>::Utf8Error)?,
+				timeout_height,
+				timeout_timestamp,
+				memo: memo.map(|memo| memo.to_string()).unwrap_or_default(),
+				forwarding: vec![],
+			};
+
+			let sender_sources = tokens
+				.iter()
+				.map(|coin| {
+					is_sender_chain_source(source_port.clone(), source_channel, &coin.denom)
+				})
+				.collect::<Vec<_>>();
+			if sender_sources.contains(&true) {
+				// Store escrow address
+				let escrow_address = get_channel_escrow_address(&source_port, source_channel)
+					.map_err(|_| Error::<T>::ChannelEscrowAddress)?;
+				let account_id = T::AccountIdConversion::try_from(escrow_address)
+					.map_err(|_| Error::<T>::ChannelEscrowAddress)?
+					.into_account();
+				EscrowAddresses::<T>::mutate(|addresses| {
+					addresses.insert(account_id);
+				});
+			}
+
+			Pallet::<T>::send_transfer_v2(msg).map_err(|e| {
+				log::warn!(target: "pallet_ibc", "[transfer_multi]: error: {:?}", &e);
+				Error::<T>::TransferSend
+			})?;
+
+			for (((_, amount), coin), is_sender_source) in
+				assets.into_iter().zip(tokens).zip(sender_sources)
+			{
+				Self::deposit_event(Event::<T>::TokenTransferInitiated {
+					from: from.as_bytes().to_vec(),
+					to: to.as_bytes().to_vec(),
+					amount,
+					local_asset_id: T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(
+						&coin.denom.to_string(),
+					)
+					.ok(),
+					ibc_denom: coin.denom.to_string().as_bytes().to_vec(),
+					is_sender_source,
+					source_channel: source_channel.to_string().as_bytes().to_vec(),
+					destination_channel: destination_channel.to_string().as_bytes().to_vec(),
+				});
+			}
+			Ok(())
+		}
+
+		/// Transfers non-fungible tokens of a class to an account on a connected chain via the
+		/// ICS721 protocol.
+		#[pallet::call_index(17)]
+		#[frame_support::transactional]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_nft(token_ids.len() as u32))]
+		pub fn transfer_nft(
+			origin: OriginFor<T>,
+			params: TransferParams<<T as frame_system::Config>::AccountId>,
+			class_id: Vec<u8>,
+			token_ids: Vec<Vec<u8>>,
+		) -> DispatchResult {
+			let account_id_32 = T::TransferOrigin::ensure_origin(origin)?.into();
+			let from = {
+				let mut hex_string = hex::encode(account_id_32.to_raw_vec());
+				hex_string.insert_str(0, "0x");
+				hex_string
+			};
+
+			let to = match params.to {
+				MultiAddress::Id(id) => {
+					let account_id_32: AccountId32 = id.into();
+					let mut hex_string = hex::encode(account_id_32.to_raw_vec());
+					hex_string.insert_str(0, "0x");
+					hex_string
+				},
+				MultiAddress::Raw(bytes) =>
+					String::from_utf8(bytes).map_err(|_| Error::<T>::Utf8Error)?,
+			};
+			let class_id = String::from_utf8(class_id)
+				.ok()
+				.and_then(|class_id| PrefixedClassId::from_str(&class_id).ok())
+				.ok_or(Error::<T>::InvalidClassId)?;
+			let token_ids = token_ids
+				.into_iter()
+				.map(|token_id| {
+					String::from_utf8(token_id)
+						.ok()
+						.and_then(|token_id| TokenId::from_str(&token_id).ok())
+				})
+				.collect::<Option<Vec<_>>>()
+				.ok_or(Error::<T>::InvalidTokenId)?;
+			let source_port =
+				PortId::from_str(ics721::PORT_ID_STR).map_err(|_| Error::<T>::InvalidPortId)?;
+			let source_channel = ChannelId::new(params.source_channel);
+			let (timeout_height, timeout_timestamp) =
+				Pallet::<T>::packet_timeout(&source_port, &source_channel, params.timeout)?;
+
+			let msg = MsgNftTransfer {
+				source_port,
+				source_channel,
+				class_id,
+				token_ids,
+				sender: Signer::from_str(&from).map_err(|_| Error::<T>::Utf8Error)?,
+				receiver: Signer::from_str(&to).map_err(|_| Error::<T>::Utf8Error)?,
+				timeout_height,
+				timeout_timestamp,
+				memo: String::new(),
+			};
+
+			Pallet::<T>::send_nft_transfer(msg).map_err(|e| {
+				log::warn!(target: "pallet_ibc", "[transfer_nft]: error: {:?}", &e);
+				Error::<T>::TransferSend
+			})?;
+
+			Ok(())
+		}
+
+		/// Proposes new parameters for an open channel, starting the ICS-04 channel upgrade
+		/// handshake. The counterparty chain has to propose the same upgrade before relayers can
+		/// complete the handshake.
+		#[pallet::call_index(18)]
+		#[pallet::weight(<T as Config>::WeightInfo::upgrade_channel())]
+		#[frame_support::transactional]
+		pub fn upgrade_channel(
+			origin: OriginFor<T>,
+			port_id: String,
+			channel_id: String,
+			ordering: String,
+			connection_id: String,
+			version: String,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let port_id = PortId::from_str(&port_id).map_err(|_| Error::<T>::InvalidPortId)?;
+			let channel_id =
+				ChannelId::from_str(&channel_id).map_err(|_| Error::<T>::InvalidChannelId)?;
+			let ordering = Order::from_str(&ordering).map_err(|_| Error::<T>::InvalidParams)?;
+			let connection_id = ConnectionId::from_str(&connection_id)
+				.map_err(|_| Error::<T>::InvalidConnectionId)?;
+
+			let msg = MsgChannelUpgradeInit::new(
+				port_id,
+				channel_id,
+				UpgradeFields::new(ordering, vec![connection_id], Version::new(version)),
+				Signer::from_str(MODULE_ID).map_err(|_| Error::<T>::Utf8Error)?,
+			);
+			let msg = ibc_proto::google::protobuf::Any {
+				type_url: msg.type_url(),
+				value: msg.encode_vec().map_err(|_| Error::<T>::EncodingError)?,
+			};
+			let mut ctx = Context::<T>::new();
+			let receipt =
+				ibc::core::ics26_routing::handler::deliver(&mut ctx, msg).map_err(|e| {
+					log::debug!(target: "pallet_ibc", "[upgrade_channel]: error: {}", e);
+					Error::<T>::ChannelUpgrade
+				})?;
+			Self::deposit_event(receipt.events.into());
+
+			Ok(())
+		}
+
+		/// Pauses or resumes the ICS-20 transfers over the channel with sequence `channel`.
+		#[pallet::call_index(19)]
+		#[pallet::weight(0)]
+		pub fn set_channel_pause(
+			origin: OriginFor<T>,
+			channel: u64,
+			pause: TransferPause,
+		) -> DispatchResult {
+			<T as Config>::FreezeOrigin::ensure_origin(origin)?;
+			if pause == TransferPause::default() {
+				PausedChannels::<T>::remove(channel);
+			} else {
+				PausedChannels::<T>::insert(channel, pause);
+			}
+			Self::deposit_event(Event::<T>::ChannelPauseSet { channel, pause });
+			Ok(())
+		}
+
+		/// Pauses or resumes the ICS-20 transfers of an asset over every channel.
+		#[pallet::call_index(20)]
+		#[pallet::weight(0)]
+		pub fn set_asset_pause(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			pause: TransferPause,
+		) -> DispatchResult {
+			<T as Config>::FreezeOrigin::ensure_origin(origin)?;
+			if pause == TransferPause::default() {
+				PausedAssets::<T>::remove(asset_id);
+			} else {
+				PausedAssets::<T>::insert(asset_id, pause);
+			}
+			Self::deposit_event(Event::<T>::AssetPauseSet { asset_id, pause });
+			Ok(())
+		}
+
+		/// Registers the metadata of the IBC asset of `denom`, the full denom trace of the asset on
+		/// this chain, replacing its placeholder metadata if any.
+		#[pallet::call_index(21)]
+		#[pallet::weight(0)]
+		#[frame_support::transactional]
+		pub fn set_asset_metadata(
+			origin: OriginFor<T>,
+			denom: Vec<u8>,
+			metadata: AssetMetadata,
+		) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			let denom_str = String::from_utf8(denom.clone()).map_err(|_| Error::<T>::Utf8Error)?;
+			PrefixedDenom::from_str(&denom_str).map_err(|_| Error::<T>::PrefixedDenomParse)?;
+			ensure!(Self::is_valid_asset_metadata(&metadata), Error::<T>::InvalidAssetMetadata);
+			let asset_id = T::IbcDenomToAssetIdConversion::from_denom_to_asset_id(&denom_str)
+				.map_err(|_| Error::<T>::InvalidAssetId)?;
+			T::AssetRegistry::on_metadata_set(asset_id, &denom, &metadata)
+				.map_err(|_| Error::<T>::AssetRegistry)?;
+			IbcAssetMetadata::<T>::insert(&denom, metadata.clone());
+			Self::deposit_event(Event::<T>::AssetMetadataSet { denom, metadata });
+			Ok(())
+		}
+
+		/// Removes the metadata of the IBC asset of `denom`.
+		#[pallet::call_index(22)]
+		#[pallet::weight(0)]
+		pub fn remove_asset_metadata(origin: OriginFor<T>, denom: Vec<u8>) -> DispatchResult {
+			<T as Config>::AdminOrigin::ensure_origin(origin)?;
+			IbcAssetMetadata::<T>::remove(&denom);
+			Self::deposit_event(Event::<T>::AssetMetadataRemoved { denom });
+			Ok(())
+		}
+	}
+}
+
+/// Maximum length of the name and of the symbol of the metadata of IBC assets.
+pub const MAX_ASSET_METADATA_LENGTH: usize = 128;
+
+/// The asset registry of the runtime (e.g. `orml-asset-registry`), notified of the metadata of
+/// the IBC assets so that it's available to the rest of the runtime.
+pub trait IbcAssetRegistry<AssetId> {
+	/// Called when the metadata of the asset `asset_id`, whose IBC denom is `denom`, is
+	/// registered, either by governance or as placeholder metadata.
+	fn on_metadata_set(asset_id: AssetId, denom: &[u8], metadata: &AssetMetadata)
+		-> DispatchResult;
+}
+
+impl<AssetId> IbcAssetRegistry<AssetId> for () {
+	fn on_metadata_set(
+		_asset_id: AssetId,
+		_denom: &[u8],
+		_metadata: &AssetMetadata,
+	) -> DispatchResult {
+		Ok(())
 	}
 }
 
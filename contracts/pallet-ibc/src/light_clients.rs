@@ -16,6 +16,14 @@ use ibc::{
 use ibc_derive::{ClientDef, ClientMessage, ClientState, ConsensusState, Protobuf};
 use ibc_primitives::runtime_interface;
 use ibc_proto::google::protobuf::Any;
+use ics06_solomachine::{
+	client_message::{
+		SOLOMACHINE_CLIENT_MESSAGE_TYPE_URL, SOLOMACHINE_HEADER_TYPE_URL,
+		SOLOMACHINE_MISBEHAVIOUR_TYPE_URL,
+	},
+	client_state::SOLOMACHINE_CLIENT_STATE_TYPE_URL,
+	consensus_state::SOLOMACHINE_CONSENSUS_STATE_TYPE_URL,
+};
 use ics07_tendermint::{
 	client_message::{
 		TENDERMINT_CLIENT_MESSAGE_TYPE_URL, TENDERMINT_HEADER_TYPE_URL,
@@ -44,6 +52,11 @@ use ics11_beefy::{
 	client_message::BEEFY_CLIENT_MESSAGE_TYPE_URL, client_state::BEEFY_CLIENT_STATE_TYPE_URL,
 	consensus_state::BEEFY_CONSENSUS_STATE_TYPE_URL,
 };
+use ics13_near::{
+	client_state::NEAR_CLIENT_STATE_TYPE_URL,
+	consensus_state::NEAR_CONSENSUS_STATE_TYPE_URL,
+	header::{NEAR_CLIENT_MESSAGE_TYPE_URL, NEAR_HEADER_TYPE_URL, NEAR_MISBEHAVIOUR_TYPE_URL},
+};
 use prost::Message;
 use sp_core::{crypto::ByteArray, ed25519, H256};
 use sp_runtime::{
@@ -212,11 +225,61 @@ impl beefy_client_primitives::HostFunctions for HostFunctionsManager {
 	}
 }
 
+impl ics06_solomachine::HostFunctions for HostFunctionsManager {
+	fn sha256(message: &[u8]) -> [u8; 32] {
+		sp_io::hashing::sha2_256(message)
+	}
+
+	fn ed25519_verify(signature: &[u8; 64], message: &[u8], public_key: &[u8; 32]) -> bool {
+		sp_io::crypto::ed25519_verify(
+			&ed25519::Signature::from_raw(*signature),
+			message,
+			&ed25519::Public::from_raw(*public_key),
+		)
+	}
+
+	fn secp256k1_verify(
+		signature: &[u8; 64],
+		message_hash: &[u8; 32],
+		public_key: &[u8; 33],
+	) -> bool {
+		// cosmos-sdk signatures carry no recovery id, so both candidates are tried
+		(0..2).any(|recovery_id| {
+			let mut signature_with_id = [0u8; 65];
+			signature_with_id[..64].copy_from_slice(signature);
+			signature_with_id[64] = recovery_id;
+			sp_io::crypto::secp256k1_ecdsa_recover_compressed(&signature_with_id, message_hash)
+				.map_or(false, |recovered| recovered == *public_key)
+		})
+	}
+}
+
+impl ics13_near::client_def::HostFunctions for HostFunctionsManager {
+	fn ed25519_verify(signature: &[u8; 64], msg: &[u8], pubkey: &[u8]) -> bool {
+		match ed25519::Public::from_slice(pubkey) {
+			Ok(public_key) => sp_io::crypto::ed25519_verify(
+				&ed25519::Signature::from_raw(*signature),
+				msg,
+				&public_key,
+			),
+			Err(_) => false,
+		}
+	}
+
+	fn sha256_digest(data: &[u8]) -> [u8; 32] {
+		sp_io::hashing::sha2_256(data)
+	}
+}
+
+impl ics13_near::client_def::HostFunctionsTrait for HostFunctionsManager {}
+
 #[derive(Clone, Debug, PartialEq, Eq, ClientDef)]
 pub enum AnyClient {
 	Grandpa(ics10_grandpa::client_def::GrandpaClient<HostFunctionsManager>),
 	Beefy(ics11_beefy::client_def::BeefyClient<HostFunctionsManager>),
 	Tendermint(ics07_tendermint::client_def::TendermintClient<HostFunctionsManager>),
+	SoloMachine(ics06_solomachine::client_def::SoloMachineClient<HostFunctionsManager>),
+	Near(ics13_near::client_def::NearClient<HostFunctionsManager>),
 	Wasm(ics08_wasm::client_def::WasmClient<AnyClient, AnyClientState, AnyConsensusState>),
 	#[cfg(test)]
 	Mock(ibc::mock::client_def::MockClient),
@@ -227,6 +290,8 @@ pub enum AnyUpgradeOptions {
 	Grandpa(ics10_grandpa::client_state::UpgradeOptions),
 	Beefy(ics11_beefy::client_state::UpgradeOptions),
 	Tendermint(ics07_tendermint::client_state::UpgradeOptions),
+	SoloMachine(ics06_solomachine::client_state::UpgradeOptions),
+	Near(ics13_near::client_state::NearUpgradeOptions),
 	Wasm(Box<Self>),
 	#[cfg(test)]
 	Mock(()),
@@ -240,6 +305,10 @@ pub enum AnyClientState {
 	Beefy(ics11_beefy::client_state::ClientState<HostFunctionsManager>),
 	#[ibc(proto_url = "TENDERMINT_CLIENT_STATE_TYPE_URL")]
 	Tendermint(ics07_tendermint::client_state::ClientState<HostFunctionsManager>),
+	#[ibc(proto_url = "SOLOMACHINE_CLIENT_STATE_TYPE_URL")]
+	SoloMachine(ics06_solomachine::client_state::ClientState<HostFunctionsManager>),
+	#[ibc(proto_url = "NEAR_CLIENT_STATE_TYPE_URL")]
+	Near(ics13_near::client_state::NearClientState<HostFunctionsManager>),
 	#[ibc(proto_url = "WASM_CLIENT_STATE_TYPE_URL")]
 	Wasm(ics08_wasm::client_state::ClientState<AnyClient, Self, AnyConsensusState>),
 	#[cfg(test)]
@@ -300,6 +369,10 @@ pub enum AnyConsensusState {
 	Beefy(ics11_beefy::consensus_state::ConsensusState),
 	#[ibc(proto_url = "TENDERMINT_CONSENSUS_STATE_TYPE_URL")]
 	Tendermint(ics07_tendermint::consensus_state::ConsensusState),
+	#[ibc(proto_url = "SOLOMACHINE_CONSENSUS_STATE_TYPE_URL")]
+	SoloMachine(ics06_solomachine::consensus_state::ConsensusState),
+	#[ibc(proto_url = "NEAR_CONSENSUS_STATE_TYPE_URL")]
+	Near(ics13_near::consensus_state::ConsensusState),
 	#[ibc(proto_url = "WASM_CONSENSUS_STATE_TYPE_URL")]
 	Wasm(ics08_wasm::consensus_state::ConsensusState<Self>),
 	#[cfg(test)]
@@ -326,6 +399,10 @@ pub enum AnyClientMessage {
 	Beefy(ics11_beefy::client_message::ClientMessage),
 	#[ibc(proto_url = "TENDERMINT_CLIENT_MESSAGE_TYPE_URL")]
 	Tendermint(ics07_tendermint::client_message::ClientMessage),
+	#[ibc(proto_url = "SOLOMACHINE_CLIENT_MESSAGE_TYPE_URL")]
+	SoloMachine(ics06_solomachine::client_message::ClientMessage),
+	#[ibc(proto_url = "NEAR_CLIENT_MESSAGE_TYPE_URL")]
+	Near(ics13_near::header::NearClientMessage),
 	#[ibc(proto_url = "WASM_CLIENT_MESSAGE_TYPE_URL")]
 	Wasm(ics08_wasm::client_message::ClientMessage<Self>),
 	#[cfg(test)]
@@ -349,6 +426,16 @@ impl AnyClientMessage {
 				ics10_grandpa::client_message::ClientMessage::Header(h) => Some(h.height()),
 				ics10_grandpa::client_message::ClientMessage::Misbehaviour(_) => None,
 			},
+			Self::SoloMachine(inner) => match inner {
+				// the header doesn't carry the sequence it updates, which is only known to the
+				// client state
+				ics06_solomachine::client_message::ClientMessage::Header(_) => None,
+				ics06_solomachine::client_message::ClientMessage::Misbehaviour(_) => None,
+			},
+			Self::Near(inner) => match inner {
+				ics13_near::header::NearClientMessage::Header(h) => Some(h.height()),
+				ics13_near::header::NearClientMessage::Misbehaviour(_) => None,
+			},
 			Self::Wasm(inner) => match inner {
 				ics08_wasm::client_message::ClientMessage::Header(h) =>
 					h.inner.maybe_header_height(),
@@ -442,6 +529,34 @@ impl TryFrom<Any> for AnyClientMessage {
 					ics07_tendermint::client_message::Misbehaviour::decode_vec(&value.value)
 						.map_err(ics02_client::error::Error::decode_raw_header)?,
 				))),
+			SOLOMACHINE_CLIENT_MESSAGE_TYPE_URL => Ok(Self::SoloMachine(
+				ics06_solomachine::client_message::ClientMessage::decode_vec(&value.value)
+					.map_err(ics02_client::error::Error::decode_raw_header)?,
+			)),
+			SOLOMACHINE_HEADER_TYPE_URL =>
+				Ok(Self::SoloMachine(ics06_solomachine::client_message::ClientMessage::Header(
+					ics06_solomachine::client_message::Header::decode_vec(&value.value)
+						.map_err(ics02_client::error::Error::decode_raw_header)?,
+				))),
+			SOLOMACHINE_MISBEHAVIOUR_TYPE_URL => Ok(Self::SoloMachine(
+				ics06_solomachine::client_message::ClientMessage::Misbehaviour(
+					ics06_solomachine::client_message::Misbehaviour::decode_vec(&value.value)
+						.map_err(ics02_client::error::Error::decode_raw_header)?,
+				),
+			)),
+			NEAR_CLIENT_MESSAGE_TYPE_URL => Ok(Self::Near(
+				ics13_near::header::NearClientMessage::decode_vec(&value.value)
+					.map_err(ics02_client::error::Error::decode_raw_header)?,
+			)),
+			NEAR_HEADER_TYPE_URL => Ok(Self::Near(ics13_near::header::NearClientMessage::Header(
+				ics13_near::header::NearHeader::decode_vec(&value.value)
+					.map_err(ics02_client::error::Error::decode_raw_header)?,
+			))),
+			NEAR_MISBEHAVIOUR_TYPE_URL =>
+				Ok(Self::Near(ics13_near::header::NearClientMessage::Misbehaviour(
+					ics13_near::header::NearMisbehaviour::decode_vec(&value.value)
+						.map_err(ics02_client::error::Error::decode_raw_header)?,
+				))),
 			WASM_CLIENT_MESSAGE_TYPE_URL => Ok(Self::Wasm(
 				ics08_wasm::client_message::ClientMessage::decode_vec(&value.value)
 					.map_err(ics02_client::error::Error::decode_raw_header)?,
@@ -492,6 +607,8 @@ impl From<AnyClientMessage> for Any {
 				type_url: TENDERMINT_CLIENT_MESSAGE_TYPE_URL.to_string(),
 				value: msg.encode_vec().expect("encode_vec failed"),
 			},
+			AnyClientMessage::SoloMachine(msg) => msg.into(),
+			AnyClientMessage::Near(msg) => msg.into(),
 
 			#[cfg(test)]
 			AnyClientMessage::Mock(_msg) => panic!("MockHeader can't be serialized"),
@@ -12,7 +12,7 @@ use frame_support::{
 			metadata::{Inspect, Mutate},
 			Create,
 		},
-		AsEnsureOriginWithArg, ConstU64, Everything,
+		AsEnsureOriginWithArg, ConstU64, Contains, Everything,
 	},
 };
 use frame_system as system;
@@ -50,7 +50,7 @@ pub type Balance = u128;
 pub type AccountId = <<MultiSignature as Verify>::Signer as IdentifyAccount>::AccountId;
 use super::*;
 use crate::{
-	ics20::{IbcMemoHandler, MemoData},
+	ics20::{hooks::IbcHooksHandler, IbcMemoHandler, MemoData},
 	light_clients::{AnyClientMessage, AnyConsensusState},
 };
 use ibc::mock::{client_state::MockConsensusState, header::MockClientMessage, host::MockHostBlock};
@@ -285,7 +285,7 @@ impl Config for Test {
 	type IbcAccountId = Self::AccountId;
 	type TransferOrigin = EnsureSigned<Self::IbcAccountId>;
 	type RelayerOrigin = EnsureSigned<Self::AccountId>;
-	type HandleMemo = IbcMemoHandler<(), Test>;
+	type HandleMemo = IbcHooksHandler<IbcMemoHandler<(), Test>, Test>;
 	type MemoMessage = RawMemo;
 	type IsReceiveEnabled = sp_core::ConstBool<true>;
 	type IsSendEnabled = sp_core::ConstBool<true>;
@@ -296,6 +296,26 @@ impl Config for Test {
 	type FlatFeeAssetId = FlatFeeAssetId;
 	type FlatFeeAmount = FlatFeeAmount;
 	type SubstrateMultihopXcmHandler = SubstrateMultihopXcmHandlerNone<Test>;
+	type RuntimeCall = RuntimeCall;
+	type IcaAllowedCalls = IcaAllowedCalls;
+	type MemoAllowedCalls = IcaAllowedCalls;
+	type ContractHandler = ();
+	type Ics20RateLimiter = RateLimit;
+	type IsIcaHostEnabled = sp_core::ConstBool<true>;
+	type IsIcaControllerEnabled = sp_core::ConstBool<true>;
+	type NftRegistry = ();
+	type AssetRegistry = ();
+	type AutoRegisterAssetMetadata = frame_support::traits::ConstBool<true>;
+	type PauseOnEscrowViolation = frame_support::traits::ConstBool<true>;
+}
+
+/// Interchain accounts may only transfer native tokens.
+pub struct IcaAllowedCalls;
+
+impl Contains<RuntimeCall> for IcaAllowedCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(call, RuntimeCall::PalletBalances(balances::Call::transfer { .. }))
+	}
 }
 
 #[derive(Debug, Clone)]
@@ -320,6 +340,10 @@ impl crate::ics20_fee::Config for Test {
 	type PalletId = PalletId;
 }
 
+impl crate::ics20_rate_limit::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+}
+
 #[derive(
 	Debug, codec::Encode, Clone, codec::Decode, PartialEq, Eq, scale_info::TypeInfo, Default,
 )]
@@ -423,9 +447,15 @@ where
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Router {
 	ibc_ping: pallet_ibc_ping::IbcModule<Test>,
-	ics20: crate::ics20::memo::Memo<
+	ics20: crate::ics29::FeeMiddleware<
 		Test,
-		crate::ics20_fee::Ics20ServiceCharge<Test, crate::ics20::IbcModule<Test>>,
+		crate::ics20_rate_limit::Ics20RateLimit<
+			Test,
+			crate::ics20::memo::Memo<
+				Test,
+				crate::ics20_fee::Ics20ServiceCharge<Test, crate::ics20::IbcModule<Test>>,
+			>,
+		>,
 	>,
 }
 
@@ -480,6 +510,7 @@ frame_support::construct_runtime!(
 		PalletBalances: balances,
 		IbcPing: pallet_ibc_ping,
 		Ics20Fee: crate::ics20_fee,
+		RateLimit: crate::ics20_rate_limit,
 		Ibc: pallet_ibc,
 		Aura: pallet_aura,
 		Membership: pallet_membership::<Instance2>,
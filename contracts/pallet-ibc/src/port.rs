@@ -2,8 +2,14 @@ use super::*;
 
 use crate::routing::{Context, ModuleRouter};
 use ibc::{
-	applications::transfer::{
-		MODULE_ID_STR as TRANSFER_MODULE_ID, PORT_ID_STR as TRANSFER_PORT_ID,
+	applications::{
+		interchain_accounts::{
+			CONTROLLER_MODULE_ID_STR, CONTROLLER_PORT_PREFIX, HOST_MODULE_ID_STR, HOST_PORT_ID_STR,
+		},
+		nft_transfer::{
+			MODULE_ID_STR as NFT_TRANSFER_MODULE_ID, PORT_ID_STR as NFT_TRANSFER_PORT_ID,
+		},
+		transfer::{MODULE_ID_STR as TRANSFER_MODULE_ID, PORT_ID_STR as TRANSFER_PORT_ID},
 	},
 	core::{
 		ics05_port::{context::PortReader, error::Error as ICS05Error},
@@ -19,10 +25,13 @@ impl<T: Config + Sync + Send> PortReader for Context<T> {
 			return Ok(module_id)
 		}
 
-		match port_id.as_str() {
-			TRANSFER_PORT_ID => Ok(ModuleId::from_str(TRANSFER_MODULE_ID)
-				.map_err(|_| ICS05Error::module_not_found(port_id.clone()))?),
-			_ => Err(ICS05Error::module_not_found(port_id.clone())),
-		}
+		let module_id = match port_id.as_str() {
+			TRANSFER_PORT_ID => TRANSFER_MODULE_ID,
+			NFT_TRANSFER_PORT_ID => NFT_TRANSFER_MODULE_ID,
+			HOST_PORT_ID_STR => HOST_MODULE_ID_STR,
+			port if port.starts_with(CONTROLLER_PORT_PREFIX) => CONTROLLER_MODULE_ID_STR,
+			_ => return Err(ICS05Error::module_not_found(port_id.clone())),
+		};
+		ModuleId::from_str(module_id).map_err(|_| ICS05Error::module_not_found(port_id.clone()))
 	}
 }
@@ -1,13 +1,23 @@
 use super::*;
 use core::fmt::Debug;
 use ibc::{
-	applications::transfer::MODULE_ID_STR as IBC_TRANSFER_MODULE_ID,
+	applications::{
+		fee::middleware::wrap_async_acknowledgement,
+		interchain_accounts::{CONTROLLER_MODULE_ID_STR, HOST_MODULE_ID_STR},
+		nft_transfer::MODULE_ID_STR as NFT_TRANSFER_MODULE_ID,
+		transfer::MODULE_ID_STR as IBC_TRANSFER_MODULE_ID,
+	},
 	core::{
+		ics04_channel::{
+			context::ChannelKeeper, error::Error as Ics04Error, handler::write_acknowledgement,
+			msgs::acknowledgement::Acknowledgement, packet::Packet,
+		},
 		ics24_host::identifier::PortId,
 		ics26_routing::context::{
 			Ics26Context, Module, ModuleCallbackContext, ModuleId, ReaderContext, Router,
 		},
 	},
+	handler::HandlerOutput,
 };
 use scale_info::prelude::string::ToString;
 use sp_core::crypto::AccountId32;
@@ -32,13 +42,22 @@ impl<T: Config + Send + Sync> Context<T> {
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct IbcRouter<T: Config> {
-	ibc_transfer: ics20::IbcModule<T>,
+	ibc_transfer: ics29::FeeMiddleware<T, ics20::IbcModule<T>>,
+	ica_host: ics27::IcaHostModule<T>,
+	ica_controller: ics27::IcaControllerModule<T>,
+	nft_transfer: ics721::NftTransferModule<T>,
 	sub_router: T::Router,
 }
 
 impl<T: Config> Default for IbcRouter<T> {
 	fn default() -> Self {
-		Self { ibc_transfer: ics20::IbcModule::<T>::default(), sub_router: Default::default() }
+		Self {
+			ibc_transfer: Default::default(),
+			ica_host: ics27::IcaHostModule::<T>::default(),
+			ica_controller: ics27::IcaControllerModule::<T>::default(),
+			nft_transfer: ics721::NftTransferModule::<T>::default(),
+			sub_router: Default::default(),
+		}
 	}
 }
 
@@ -65,6 +84,9 @@ where
 
 		match module_id.as_ref() {
 			IBC_TRANSFER_MODULE_ID => Some(&mut self.ibc_transfer),
+			HOST_MODULE_ID_STR => Some(&mut self.ica_host),
+			CONTROLLER_MODULE_ID_STR => Some(&mut self.ica_controller),
+			NFT_TRANSFER_MODULE_ID => Some(&mut self.nft_transfer),
 			&_ => None,
 		}
 	}
@@ -75,7 +97,13 @@ where
 			return true
 		}
 
-		matches!(module_id.to_string().as_str(), IBC_TRANSFER_MODULE_ID,)
+		matches!(
+			module_id.to_string().as_str(),
+			IBC_TRANSFER_MODULE_ID |
+				HOST_MODULE_ID_STR |
+				CONTROLLER_MODULE_ID_STR |
+				NFT_TRANSFER_MODULE_ID
+		)
 	}
 }
 
@@ -93,6 +121,21 @@ where
 	fn router_mut(&mut self) -> &mut Self::Router {
 		&mut self.router
 	}
+
+	fn write_acknowledgement(
+		&mut self,
+		packet: Packet,
+		ack: Acknowledgement,
+	) -> Result<HandlerOutput<()>, Ics04Error> {
+		// acknowledgements of packets received on incentivized channels carry the forward relayer
+		let ack = wrap_async_acknowledgement(self, &packet, ack)?;
+		let output = write_acknowledgement::process(self, packet, ack.into_bytes())?;
+		self.store_packet_result(output.result)?;
+		Ok(HandlerOutput::builder()
+			.with_log(output.log)
+			.with_events(output.events)
+			.with_result(()))
+	}
 }
 
 impl<T: Config + Send + Sync> ReaderContext for Context<T>
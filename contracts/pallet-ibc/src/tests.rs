@@ -1,11 +1,18 @@
 use crate::{
+	ics20::escrow::EscrowViolation,
 	ics20_fee::FlatFeeConverter,
+	ics20_rate_limit::{Quota, RateLimit as RateLimitParams},
+	ics27, ics29,
 	light_clients::{AnyClientState, AnyConsensusState},
 	mock::*,
-	routing::Context,
-	Any, Config, ConsensusHeights, DenomToAssetId, Event, MultiAddress, Pallet,
-	PendingRecvPacketSeqs, PendingSendPacketSeqs, Timeout, TransferParams, MODULE_ID,
+	routing::{Context, ModuleRouter},
+	Any, AssetMetadata, Config, ConsensusHeights, DenomToAssetId, EscrowedAmounts, Event,
+	FeeEnabledChannels, FeePayees, IbcAssetMetadata, IcaHostAccounts, InFlightForwards,
+	MultiAddress, PacketFeeParams, PacketFees, Pallet, PausedChannels, PendingRecvPacketSeqs,
+	PendingSendPacketSeqs, ReceivedVoucherAmounts, Timeout, TransferParams, TransferPause,
+	MODULE_ID,
 };
+use codec::Encode;
 use core::time::Duration;
 use frame_support::{
 	assert_noop, assert_ok,
@@ -16,9 +23,14 @@ use frame_support::{
 	weights::Weight,
 };
 use ibc::{
-	applications::transfer::{
-		acknowledgement::Acknowledgement as Ics20Acknowledgement, packet::PacketData, Coin,
-		PrefixedDenom, VERSION,
+	applications::{
+		interchain_accounts::{
+			controller_port_id, metadata::Metadata, packet::InterchainAccountPacketData,
+		},
+		transfer::{
+			acknowledgement::Acknowledgement as Ics20Acknowledgement, error::Error as Ics20Error,
+			packet::PacketData, Coin, PrefixedDenom, VERSION,
+		},
 	},
 	core::{
 		ics02_client::{
@@ -35,6 +47,7 @@ use ibc::{
 		},
 		ics04_channel::{
 			channel::{ChannelEnd, Counterparty as ChanCounterParty, Order, State},
+			commitment::AcknowledgementCommitment,
 			context::{ChannelKeeper, ChannelReader},
 			msgs::{
 				acknowledgement::{Acknowledgement, MsgAcknowledgement},
@@ -44,8 +57,10 @@ use ibc::{
 			Version as ChanVersion,
 		},
 		ics23_commitment::commitment::CommitmentPrefix,
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+		ics26_routing::context::ModuleId,
 	},
+	handler::HandlerOutputBuilder,
 	mock::{
 		client_state::{MockClientState, MockConsensusState},
 		header::{MockClientMessage, MockHeader},
@@ -54,7 +69,7 @@ use ibc::{
 	signer::Signer,
 	tx_msg::Msg,
 };
-use ibc_primitives::{get_channel_escrow_address, HandlerMessage, IbcHandler};
+use ibc_primitives::{get_channel_escrow_address, HandlerMessage, IbcHandler, PageRequest};
 use sp_core::Pair;
 use sp_runtime::{
 	traits::{Bounded, IdentifyAccount},
@@ -568,7 +583,7 @@ fn on_deliver_ics20_recv_packet_incorrect_memo() {
 		};
 
 		let msg = MsgRecvPacket {
-			packet,
+			packet: packet.clone(),
 			proofs: Proofs::new(
 				vec![0u8; 32].try_into().unwrap(),
 				None,
@@ -587,17 +602,16 @@ fn on_deliver_ics20_recv_packet_incorrect_memo() {
 		assert_eq!(account_data, 0);
 		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
 
-		let balance = <<Test as Config>::NativeCurrency as Currency<
-			<Test as frame_system::Config>::AccountId,
-		>>::free_balance(&reciever);
-
-		let pallet_balance =
-			<<Test as Config>::NativeCurrency as Currency<
-				<Test as frame_system::Config>::AccountId,
-			>>::free_balance(&<Test as crate::Config>::FeeAccount::get().into_account());
-		let fee = <Test as crate::ics20_fee::Config>::ServiceChargeIn::get() * amt;
-		assert_eq!(balance, amt - fee);
-		assert_eq!(pallet_balance, fee);
+		// the sender is refunded by the error acknowledgement, so the received tokens, including
+		// the service charge, are taken back into escrow
+		let fee_account = <Test as crate::Config>::FeeAccount::get().into_account();
+		assert_eq!(native_balance(&reciever), 0);
+		assert_eq!(native_balance(&fee_account), 0);
+		assert_eq!(native_balance(&channel_escrow_address), balance);
+		let success_ack =
+			Acknowledgement::from_bytes(Ics20Acknowledgement::success().to_string().into_bytes());
+		let ack = received_ack_commitment(&packet).unwrap();
+		assert_ne!(ack, Context::<Test>::default().ack_commitment(success_ack));
 
 		// let test_event = TestEvent::PalletIbc($event);
 		assert_eq!(
@@ -637,6 +651,522 @@ fn on_deliver_ics20_recv_packet_incorrect_memo() {
 	})
 }
 
+#[test]
+fn should_acknowledge_received_transfer_as_it_was_credited() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let receiver = AccountId32::new(pair.public().0);
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		Ibc::add_channels_to_feeless_channel_list(RuntimeOrigin::root(), 1, 0).unwrap();
+
+		let amt = 1000 * MILLIS;
+		let recv = |sequence: u64| {
+			let packet_data = PacketData {
+				token: Coin {
+					denom: PrefixedDenom::from_str("PICA").unwrap(),
+					amount: ibc::applications::transfer::Amount::from_str(&format!("{amt:?}"))
+						.unwrap(),
+				},
+				sender: Signer::from_str("alice").unwrap(),
+				receiver: Signer::from_str(&ss58_address).unwrap(),
+				memo: "".to_string(),
+			};
+			let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+			let packet = Packet {
+				sequence: sequence.into(),
+				source_port: PortId::transfer(),
+				source_channel: ChannelId::new(1),
+				destination_port: PortId::transfer(),
+				destination_channel: ChannelId::new(0),
+				data: serde_json::to_vec(&packet_data).unwrap(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+					time_now as u64 + 10000000,
+				)
+				.unwrap(),
+			};
+			let msg = MsgRecvPacket {
+				packet: packet.clone(),
+				proofs: Proofs::new(
+					vec![0u8; 32].try_into().unwrap(),
+					None,
+					None,
+					None,
+					Height::new(0, 1),
+				)
+				.unwrap(),
+				signer: Signer::from_str(MODULE_ID).unwrap(),
+			};
+			let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+			Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+			packet
+		};
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"transfer/channel-0/PICA",
+			)
+			.unwrap();
+		let ctx = Context::<Test>::default();
+		let success_ack =
+			Acknowledgement::from_bytes(Ics20Acknowledgement::success().to_string().into_bytes());
+
+		// the acknowledgement is written once the whole stack has handled the packet, so a success
+		// is only acknowledged for tokens the receiver has been credited with
+		let packet = recv(1);
+		assert_eq!(Assets::balance(asset_id, receiver.clone()), amt);
+		assert_eq!(received_ack_commitment(&packet), Some(ctx.ack_commitment(success_ack.clone())));
+
+		// and an error for tokens it hasn't
+		assert_ok!(Ibc::set_channel_pause(
+			RuntimeOrigin::root(),
+			0,
+			TransferPause { send: false, receive: true }
+		));
+		let packet = recv(2);
+		assert_eq!(Assets::balance(asset_id, receiver.clone()), amt);
+		let ack = received_ack_commitment(&packet).unwrap();
+		assert_ne!(ack, ctx.ack_commitment(success_ack));
+	})
+}
+
+#[test]
+fn on_deliver_ics20_recv_packet_with_call_memo() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		let bob = AccountId32::new([2; 32]);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		let channel_id = ChannelId::new(0);
+		let balance = 100000 * MILLIS;
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap();
+		let channel_escrow_address = channel_escrow_address.into_account();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&channel_escrow_address, balance);
+
+		// the intermediate account of the sender sends part of the tokens received to bob
+		let sent = 100 * MILLIS;
+		let call = RuntimeCall::PalletBalances(balances::Call::transfer {
+			dest: bob.clone(),
+			value: sent,
+		});
+		let memo = format!(r#"{{"call":"0x{}"}}"#, hex::encode(call.encode()));
+
+		let amt = 1000 * MILLIS;
+		let packet_data = PacketData {
+			token: Coin {
+				denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+				amount: ibc::applications::transfer::Amount::from_str(&format!("{:?}", amt))
+					.unwrap(),
+			},
+			sender: Signer::from_str("alice").unwrap(),
+			receiver: Signer::from_str(&ss58_address).unwrap(),
+			memo,
+		};
+
+		let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+		let packet = Packet {
+			sequence: 1u64.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::transfer(),
+			destination_channel: channel_id,
+			data: serde_json::to_vec(&packet_data).unwrap(),
+			timeout_height: Height::new(2000, 5),
+			timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+				time_now as u64 + 10000000,
+			)
+			.unwrap(),
+		};
+		let msg = MsgRecvPacket {
+			packet,
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: Signer::from_str(MODULE_ID).unwrap(),
+		};
+		let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+
+		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		let intermediate =
+			crate::ics20::hooks::intermediate_account(&channel_id.to_string(), "alice");
+		let free_balance = |account: &AccountId32| {
+			<<Test as Config>::NativeCurrency as Currency<
+				<Test as frame_system::Config>::AccountId,
+			>>::free_balance(account)
+		};
+		let fee = <Test as crate::ics20_fee::Config>::ServiceChargeIn::get() * amt;
+		assert_eq!(free_balance(&AccountId32::new(pair.public().0)), 0);
+		assert_eq!(free_balance(&intermediate), amt - fee - sent);
+		assert_eq!(free_balance(&bob), sent);
+		assert!(System::events().iter().any(|record| matches!(
+			&record.event,
+			RuntimeEvent::Ibc(Event::<Test>::ExecuteMemoHookSuccess { from, contract: None })
+				if from == &intermediate
+		)));
+	})
+}
+
+#[test]
+fn on_deliver_ics20_recv_packet_with_failing_contract_memo() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let receiver = AccountId32::new(pair.public().0);
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		let channel_id = ChannelId::new(0);
+		let balance = 100000 * MILLIS;
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap();
+		let channel_escrow_address = channel_escrow_address.into_account();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&channel_escrow_address, balance);
+
+		// the mock runtime doesn't support contracts, so calling one fails
+		let contract = ibc_primitives::runtime_interface::account_id_to_ss58([3; 32], 49);
+		let memo = format!(r#"{{"wasm":{{"contract":"{contract}","msg":{{}}}}}}"#);
+
+		let amt = 1000 * MILLIS;
+		let packet_data = PacketData {
+			token: Coin {
+				denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+				amount: ibc::applications::transfer::Amount::from_str(&format!("{:?}", amt))
+					.unwrap(),
+			},
+			sender: Signer::from_str("alice").unwrap(),
+			receiver: Signer::from_str(&ss58_address).unwrap(),
+			memo: memo.clone(),
+		};
+
+		let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+		let packet = Packet {
+			sequence: 1u64.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::transfer(),
+			destination_channel: channel_id,
+			data: serde_json::to_vec(&packet_data).unwrap(),
+			timeout_height: Height::new(2000, 5),
+			timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+				time_now as u64 + 10000000,
+			)
+			.unwrap(),
+		};
+		let msg = MsgRecvPacket {
+			packet: packet.clone(),
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: Signer::from_str(MODULE_ID).unwrap(),
+		};
+		let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+
+		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		// the tokens received, including the service charge, are taken back into escrow and the
+		// sender is refunded by the error acknowledgement
+		let intermediate =
+			crate::ics20::hooks::intermediate_account(&channel_id.to_string(), "alice");
+		let fee_account = <Test as crate::Config>::FeeAccount::get().into_account();
+		assert_eq!(native_balance(&receiver), 0);
+		assert_eq!(native_balance(&intermediate), 0);
+		assert_eq!(native_balance(&fee_account), 0);
+		assert_eq!(native_balance(&channel_escrow_address), balance);
+		let success_ack =
+			Acknowledgement::from_bytes(Ics20Acknowledgement::success().to_string().into_bytes());
+		let ack = received_ack_commitment(&packet).unwrap();
+		assert_ne!(ack, Context::<Test>::default().ack_commitment(success_ack));
+		assert!(System::events().iter().any(|record| matches!(
+			&record.event,
+			RuntimeEvent::Ibc(Event::<Test>::ExecuteMemoIbcTokenTransferFailedWithReason {
+				from,
+				memo: failed_memo,
+				reason,
+			}) if from == &receiver &&
+				failed_memo == &memo &&
+				*reason == crate::ics20::MemoFailureReason::HookExecutionFailed as u8
+		)));
+	})
+}
+
+/// Delivers a transfer of native tokens back from channel-1 whose memo forwards them over
+/// channel-0, with no service charge in either direction. Returns the received packet and the
+/// packet forwarding its tokens.
+fn recv_forwarded_transfer(retries: u64) -> (Packet, Packet) {
+	setup_client_and_consensus_state(PortId::transfer());
+	Ibc::add_channels_to_feeless_channel_list(RuntimeOrigin::root(), 0, 1).unwrap();
+	crate::ics20_fee::FeeLessChannelIds::<Test>::insert((1, 0), ());
+
+	let channel_id = ChannelId::new(0);
+	let _ = <<Test as Config>::NativeCurrency as Currency<
+		<Test as frame_system::Config>::AccountId,
+	>>::deposit_creating(&forward_escrow_account(), 100000 * MILLIS);
+
+	let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+	let ss58_address = ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+	let memo = format!(
+		r#"{{"forward":{{"receiver":"bob","port":"transfer","channel":"channel-0","timeout":600,"retries":{retries}}}}}"#
+	);
+	let packet_data = PacketData {
+		token: Coin {
+			denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+			amount: ibc::applications::transfer::Amount::from_str(&format!("{:?}", 1000 * MILLIS))
+				.unwrap(),
+		},
+		sender: Signer::from_str("alice").unwrap(),
+		receiver: Signer::from_str(&ss58_address).unwrap(),
+		memo,
+	};
+
+	let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+	let packet = Packet {
+		sequence: 1u64.into(),
+		source_port: PortId::transfer(),
+		source_channel: ChannelId::new(1),
+		destination_port: PortId::transfer(),
+		destination_channel: channel_id,
+		data: serde_json::to_vec(&packet_data).unwrap(),
+		timeout_height: Height::new(2000, 5),
+		timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(time_now as u64 + 10000000)
+			.unwrap(),
+	};
+	let msg = MsgRecvPacket {
+		packet: packet.clone(),
+		proofs: Proofs::new(vec![0u8; 32].try_into().unwrap(), None, None, None, Height::new(0, 1))
+			.unwrap(),
+		signer: Signer::from_str(MODULE_ID).unwrap(),
+	};
+	let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+	Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+	let packet_info = Ibc::get_send_packet_info(
+		channel_id.to_string().as_bytes().to_vec(),
+		PortId::transfer().as_bytes().to_vec(),
+		vec![1],
+	)
+	.unwrap()
+	.get(0)
+	.unwrap()
+	.clone();
+	(packet, Packet::from(packet_info))
+}
+
+fn forward_escrow_account() -> AccountId32 {
+	let escrow_address =
+		get_channel_escrow_address(&PortId::transfer(), ChannelId::new(0)).unwrap();
+	<Test as Config>::AccountIdConversion::try_from(escrow_address)
+		.map_err(|_| ())
+		.unwrap()
+		.into_account()
+}
+
+fn native_balance(account: &AccountId32) -> u128 {
+	<<Test as Config>::NativeCurrency as Currency<<Test as frame_system::Config>::AccountId>>::free_balance(
+		account,
+	)
+}
+
+fn forward_failed_ack() -> Acknowledgement {
+	Acknowledgement::from_bytes(
+		Ics20Acknowledgement::from_error(Ics20Error::implementation_specific(
+			"Forwarded packet failed".to_string(),
+		))
+		.to_string()
+		.into_bytes(),
+	)
+}
+
+fn deliver_forward_acknowledgement(forwarded: Packet, ack: Ics20Acknowledgement) {
+	let msg = MsgAcknowledgement {
+		packet: forwarded,
+		acknowledgement: Acknowledgement::from_bytes(ack.to_string().into_bytes()),
+		proofs: Proofs::new(vec![0u8; 32].try_into().unwrap(), None, None, None, Height::new(0, 1))
+			.unwrap(),
+		signer: Signer::from_str(MODULE_ID).unwrap(),
+	};
+	let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+	Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+}
+
+fn timeout_forward(forwarded: &Packet) {
+	let module_id = ModuleId::from_str(ibc::applications::transfer::MODULE_ID_STR).unwrap();
+	let mut router = Router::default();
+	let module = router.get_route_mut(&module_id).unwrap();
+	let mut output = HandlerOutputBuilder::new();
+	module
+		.on_timeout_packet(
+			&Context::<Test>::default(),
+			&mut output,
+			&mut forwarded.clone(),
+			&Signer::from_str(MODULE_ID).unwrap(),
+		)
+		.unwrap();
+}
+
+fn received_ack_commitment(received: &Packet) -> Option<AcknowledgementCommitment> {
+	Context::<Test>::default()
+		.get_packet_acknowledgement(&(
+			received.destination_port.clone(),
+			received.destination_channel,
+			received.sequence,
+		))
+		.ok()
+}
+
+#[test]
+fn on_deliver_ics20_recv_packet_with_forward_memo() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let (received, forwarded) = recv_forwarded_transfer(2);
+
+		// the received tokens left with the forward, and the received packet is only
+		// acknowledged once the forward is
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		assert_eq!(native_balance(&AccountId32::new(pair.public().0)), 0);
+		assert_eq!(native_balance(&forward_escrow_account()), 100000 * MILLIS);
+		assert!(received_ack_commitment(&received).is_none());
+
+		let forward = InFlightForwards::<Test>::get(0, u64::from(forwarded.sequence)).unwrap();
+		assert_eq!(forward.packet().unwrap(), received);
+		assert_eq!(forward.retries_remaining, 2);
+		assert_eq!(InFlightForwards::<Test>::iter().count(), 1);
+	})
+}
+
+#[test]
+fn should_settle_forward_on_success_acknowledgement() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let (received, forwarded) = recv_forwarded_transfer(2);
+
+		deliver_forward_acknowledgement(forwarded, Ics20Acknowledgement::success());
+
+		assert_eq!(InFlightForwards::<Test>::iter().count(), 0);
+		let ctx = Context::<Test>::default();
+		let success_ack =
+			Acknowledgement::from_bytes(Ics20Acknowledgement::success().to_string().into_bytes());
+		assert_eq!(received_ack_commitment(&received), Some(ctx.ack_commitment(success_ack)));
+		// the forwarded tokens stay escrowed for the next hop
+		assert_eq!(native_balance(&forward_escrow_account()), 100000 * MILLIS);
+	})
+}
+
+#[test]
+fn should_refund_forward_on_error_acknowledgement() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let (received, forwarded) = recv_forwarded_transfer(2);
+		let sequence = u64::from(forwarded.sequence);
+
+		deliver_forward_acknowledgement(
+			forwarded,
+			Ics20Acknowledgement::from_error(Ics20Error::implementation_specific(
+				"failed on the next hop".to_string(),
+			)),
+		);
+
+		// the refund of the forward is taken back, so that the sender of the received packet
+		// can be refunded by the error acknowledgement, without retrying
+		assert_eq!(InFlightForwards::<Test>::iter().count(), 0);
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		assert_eq!(native_balance(&AccountId32::new(pair.public().0)), 0);
+		assert_eq!(native_balance(&forward_escrow_account()), 100000 * MILLIS);
+		let ctx = Context::<Test>::default();
+		assert_eq!(
+			received_ack_commitment(&received),
+			Some(ctx.ack_commitment(forward_failed_ack()))
+		);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Ibc(Event::<Test>::ExecuteMemoForwardFailed { channel: 0, sequence: s })
+				if s == sequence
+		)));
+	})
+}
+
+#[test]
+fn should_retry_forward_on_timeout_until_retries_run_out() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let (received, forwarded) = recv_forwarded_transfer(1);
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let receiver = AccountId32::new(pair.public().0);
+
+		// the forward is sent again with the next sequence, and the received packet is still not
+		// acknowledged
+		timeout_forward(&forwarded);
+		assert!(InFlightForwards::<Test>::get(0, 1).is_none());
+		let retried = InFlightForwards::<Test>::get(0, 2).unwrap();
+		assert_eq!(retried.packet().unwrap(), received);
+		assert_eq!(retried.retries_remaining, 0);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Ibc(Event::<Test>::ExecuteMemoForwardRetried {
+				channel: 0,
+				sequence: 2,
+				retries_remaining: 0
+			})
+		)));
+		assert!(received_ack_commitment(&received).is_none());
+		assert_eq!(native_balance(&receiver), 0);
+		assert_eq!(native_balance(&forward_escrow_account()), 100000 * MILLIS);
+
+		// without retries left, the received packet is acknowledged with an error
+		let mut retried_forward = forwarded;
+		retried_forward.sequence = 2u64.into();
+		timeout_forward(&retried_forward);
+		assert_eq!(InFlightForwards::<Test>::iter().count(), 0);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Ibc(Event::<Test>::ExecuteMemoForwardFailed { channel: 0, sequence: 2 })
+		)));
+		let ctx = Context::<Test>::default();
+		assert_eq!(
+			received_ack_commitment(&received),
+			Some(ctx.ack_commitment(forward_failed_ack()))
+		);
+		assert_eq!(native_balance(&receiver), 0);
+		assert_eq!(native_balance(&forward_escrow_account()), 100000 * MILLIS);
+	})
+}
+
 #[test]
 fn on_deliver_ics20_recv_packet_with_flat_fee() {
 	let mut ext = new_test_ext();
@@ -1052,22 +1582,695 @@ fn on_deliver_ics20_recv_packet_should_not_double_spend() {
 }
 
 #[test]
-fn should_fetch_recv_packet_with_acknowledgement() {
+fn send_transfer_exceeding_rate_limit() {
 	let mut ext = new_test_ext();
+	let balance = 100000 * MILLIS;
 	ext.execute_with(|| {
-		frame_system::Pallet::<Test>::set_block_number(1u32);
-		let channel_id = ChannelId::new(0);
-		let port_id = PortId::transfer();
-
-		let mut ctx = Context::<Test>::default();
-
-		let channel_end = ChannelEnd { state: State::Open, ..Default::default() };
-		ctx.store_channel((port_id.clone(), channel_id), &channel_end).unwrap();
-		let packet = Packet {
-			sequence: 1u64.into(),
-			source_port: port_id.clone(),
-			source_channel: channel_id,
-			destination_port: port_id.clone(),
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"PICA",
+			)
+			.unwrap();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&AccountId32::new([0; 32]), balance);
+
+		assert_noop!(
+			RateLimit::set_rate_limit(
+				RuntimeOrigin::root(),
+				0,
+				b"PICA".to_vec(),
+				RateLimitParams { max_inflow: None, max_outflow: None, period: 0 },
+			),
+			crate::ics20_rate_limit::Error::<Test>::InvalidPeriod
+		);
+		assert_ok!(RateLimit::set_rate_limit(
+			RuntimeOrigin::root(),
+			0,
+			b"PICA".to_vec(),
+			RateLimitParams {
+				max_inflow: None,
+				max_outflow: Some(Quota::Absolute(balance / 2)),
+				period: 3600,
+			},
+		));
+
+		let transfer = |amount| {
+			Ibc::transfer(
+				RuntimeOrigin::signed(AccountId32::new([0; 32])),
+				TransferParams {
+					to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
+					source_channel: 0,
+					timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+				},
+				asset_id,
+				amount,
+				None,
+			)
+		};
+		assert_ok!(transfer(balance / 4));
+		assert!(transfer(balance / 2).is_err());
+		// the tokens sent are net of the service charge
+		let fee = <Test as Config>::ServiceChargeOut::get() * (balance / 4);
+		assert_eq!(
+			RateLimit::rate_limit_usage(0, b"PICA".to_vec()).unwrap().flow.outflow,
+			balance / 4 - fee
+		);
+	})
+}
+
+#[test]
+fn on_deliver_ics20_recv_packet_exceeding_rate_limit() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		let channel_id = ChannelId::new(0);
+		let balance = 100000 * MILLIS;
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap()
+				.into_account();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&channel_escrow_address, balance);
+
+		// the tokens received are native tokens sent back over channel-0
+		let amt = 1000 * MILLIS;
+		assert_ok!(RateLimit::set_rate_limit(
+			RuntimeOrigin::root(),
+			0,
+			b"PICA".to_vec(),
+			RateLimitParams {
+				max_inflow: Some(Quota::Absolute(amt / 2)),
+				max_outflow: None,
+				period: 3600,
+			},
+		));
+
+		let packet_data = PacketData {
+			token: Coin {
+				denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+				amount: ibc::applications::transfer::Amount::from_str(&format!("{amt:?}")).unwrap(),
+			},
+			sender: Signer::from_str("alice").unwrap(),
+			receiver: Signer::from_str(&ss58_address).unwrap(),
+			memo: "".to_string(),
+		};
+		let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+		let packet = Packet {
+			sequence: 1u64.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::transfer(),
+			destination_channel: channel_id,
+			data: serde_json::to_vec(&packet_data).unwrap(),
+			timeout_height: Height::new(2000, 5),
+			timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+				time_now as u64 + 10000000,
+			)
+			.unwrap(),
+		};
+		let msg = MsgRecvPacket {
+			packet,
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: Signer::from_str(MODULE_ID).unwrap(),
+		};
+		let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		// the packet is acknowledged with an error without crediting the receiver
+		let receiver_balance = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::free_balance(&AccountId32::new(pair.public().0));
+		let escrow_balance = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::free_balance(&channel_escrow_address);
+		assert_eq!(receiver_balance, 0);
+		assert_eq!(escrow_balance, balance);
+		assert_eq!(RateLimit::rate_limit_usage(0, b"PICA".to_vec()).unwrap().flow.inflow, 0);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::RateLimit(crate::ics20_rate_limit::Event::RateLimitExceeded { .. })
+		)));
+	})
+}
+
+#[test]
+fn should_undo_inflow_of_refunded_forward() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let amt = 1000 * MILLIS;
+		assert_ok!(RateLimit::set_rate_limit(
+			RuntimeOrigin::root(),
+			0,
+			b"PICA".to_vec(),
+			RateLimitParams {
+				max_inflow: Some(Quota::Absolute(2 * amt)),
+				max_outflow: Some(Quota::Absolute(2 * amt)),
+				period: 3600,
+			},
+		));
+		let (_, forwarded) = recv_forwarded_transfer(0);
+
+		// the tokens flowed in, and out with the forward
+		let flow = RateLimit::rate_limit_usage(0, b"PICA".to_vec()).unwrap().flow;
+		assert_eq!(flow.inflow, amt);
+		assert_eq!(flow.outflow, amt);
+
+		// both flows are undone once the forward fails, as the tokens are refunded to the sender
+		// of the received packet
+		deliver_forward_acknowledgement(
+			forwarded,
+			Ics20Acknowledgement::from_error(Ics20Error::implementation_specific(
+				"failed on the next hop".to_string(),
+			)),
+		);
+		let flow = RateLimit::rate_limit_usage(0, b"PICA".to_vec()).unwrap().flow;
+		assert_eq!(flow.inflow, 0);
+		assert_eq!(flow.outflow, 0);
+	})
+}
+
+#[test]
+fn send_transfer_over_paused_channel() {
+	let mut ext = new_test_ext();
+	let balance = 100000 * MILLIS;
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"PICA",
+			)
+			.unwrap();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&AccountId32::new([0; 32]), balance);
+
+		let transfer = || {
+			Ibc::transfer(
+				RuntimeOrigin::signed(AccountId32::new([0; 32])),
+				TransferParams {
+					to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
+					source_channel: 0,
+					timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+				},
+				asset_id,
+				balance / 2,
+				None,
+			)
+		};
+		assert_ok!(Ibc::set_channel_pause(
+			RuntimeOrigin::root(),
+			0,
+			TransferPause { send: true, receive: false },
+		));
+		assert_noop!(transfer(), crate::Error::<Test>::TransferPaused);
+
+		assert_ok!(Ibc::set_channel_pause(RuntimeOrigin::root(), 0, TransferPause::default()));
+		assert!(!PausedChannels::<Test>::contains_key(0));
+		assert_ok!(transfer());
+	})
+}
+
+#[test]
+fn on_deliver_ics20_recv_packet_of_paused_asset() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"PICA",
+			)
+			.unwrap();
+
+		let channel_id = ChannelId::new(0);
+		let balance = 100000 * MILLIS;
+		let channel_escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), channel_id).unwrap();
+		let channel_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(channel_escrow_address)
+				.map_err(|_| ())
+				.unwrap()
+				.into_account();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&channel_escrow_address, balance);
+
+		assert_ok!(Ibc::set_asset_pause(
+			RuntimeOrigin::root(),
+			asset_id,
+			TransferPause { send: false, receive: true },
+		));
+
+		// the tokens received are native tokens sent back over channel-0
+		let amt = 1000 * MILLIS;
+		let packet_data = PacketData {
+			token: Coin {
+				denom: PrefixedDenom::from_str("transfer/channel-1/PICA").unwrap(),
+				amount: ibc::applications::transfer::Amount::from_str(&format!("{amt:?}")).unwrap(),
+			},
+			sender: Signer::from_str("alice").unwrap(),
+			receiver: Signer::from_str(&ss58_address).unwrap(),
+			memo: "".to_string(),
+		};
+		let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+		let packet = Packet {
+			sequence: 1u64.into(),
+			source_port: PortId::transfer(),
+			source_channel: ChannelId::new(1),
+			destination_port: PortId::transfer(),
+			destination_channel: channel_id,
+			data: serde_json::to_vec(&packet_data).unwrap(),
+			timeout_height: Height::new(2000, 5),
+			timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+				time_now as u64 + 10000000,
+			)
+			.unwrap(),
+		};
+		let msg = MsgRecvPacket {
+			packet,
+			proofs: Proofs::new(
+				vec![0u8; 32].try_into().unwrap(),
+				None,
+				None,
+				None,
+				Height::new(0, 1),
+			)
+			.unwrap(),
+			signer: Signer::from_str(MODULE_ID).unwrap(),
+		};
+		let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+		Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+
+		// the packet is acknowledged with an error so the sender gets refunded
+		let receiver_balance = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::free_balance(&AccountId32::new(pair.public().0));
+		let escrow_balance = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::free_balance(&channel_escrow_address);
+		assert_eq!(receiver_balance, 0);
+		assert_eq!(escrow_balance, balance);
+	})
+}
+
+#[test]
+fn should_register_asset_metadata() {
+	new_test_ext().execute_with(|| {
+		let metadata = |name: &str, symbol: &str, decimals| AssetMetadata {
+			name: name.as_bytes().to_vec(),
+			symbol: symbol.as_bytes().to_vec(),
+			decimals,
+		};
+
+		// assets received for the first time get placeholder metadata
+		let denom = PrefixedDenom::from_str("transfer/channel-0/uatom").unwrap();
+		Pallet::<Test>::register_placeholder_metadata(&denom, 2);
+		assert_eq!(
+			IbcAssetMetadata::<Test>::get(b"transfer/channel-0/uatom".to_vec()),
+			Some(metadata("transfer/channel-0/uatom", "uatom", 0))
+		);
+		// but not native assets
+		Pallet::<Test>::register_placeholder_metadata(&PrefixedDenom::from_str("PICA").unwrap(), 1);
+		assert!(!IbcAssetMetadata::<Test>::contains_key(b"PICA".to_vec()));
+
+		assert_noop!(
+			Ibc::set_asset_metadata(
+				RuntimeOrigin::root(),
+				b"transfer/channel-0/uatom".to_vec(),
+				metadata("Atom", "", 6),
+			),
+			crate::Error::<Test>::InvalidAssetMetadata
+		);
+		assert_ok!(Ibc::set_asset_metadata(
+			RuntimeOrigin::root(),
+			b"transfer/channel-0/uatom".to_vec(),
+			metadata("Atom", "ATOM", 6),
+		));
+		// governance registered metadata isn't overwritten by placeholders
+		Pallet::<Test>::register_placeholder_metadata(&denom, 2);
+		assert_eq!(
+			IbcAssetMetadata::<Test>::get(b"transfer/channel-0/uatom".to_vec()),
+			Some(metadata("Atom", "ATOM", 6))
+		);
+
+		assert_ok!(Ibc::set_asset_metadata(
+			RuntimeOrigin::root(),
+			b"PICA".to_vec(),
+			metadata("Picasso", "PICA", 12),
+		));
+		assert_eq!(
+			Pallet::<Test>::get_denom_trace(1).unwrap().metadata,
+			Some(metadata("Picasso", "PICA", 12))
+		);
+	})
+}
+
+#[test]
+fn should_pause_channel_on_escrow_violation() {
+	let mut ext = new_test_ext();
+	let balance = 100000 * MILLIS;
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		setup_client_and_consensus_state(PortId::transfer());
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				"PICA",
+			)
+			.unwrap();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&AccountId32::new([0; 32]), balance);
+
+		let transfer = || {
+			Ibc::transfer(
+				RuntimeOrigin::signed(AccountId32::new([0; 32])),
+				TransferParams {
+					to: MultiAddress::Raw(ss58_address.as_bytes().to_vec()),
+					source_channel: 0,
+					timeout: Timeout::Offset { timestamp: Some(1000), height: Some(5) },
+				},
+				asset_id,
+				balance / 4,
+				None,
+			)
+		};
+		assert_ok!(transfer());
+		let sent = balance / 4 - <Test as Config>::ServiceChargeOut::get() * (balance / 4);
+		assert_eq!(EscrowedAmounts::<Test>::get(0, b"PICA".to_vec()), sent);
+		assert_eq!(Pallet::<Test>::escrow_balance(0, "PICA"), sent);
+		assert!(Pallet::<Test>::escrow_violations().is_empty());
+
+		// tokens leaving the escrow account outside of the transfer application
+		let escrow_address =
+			get_channel_escrow_address(&PortId::transfer(), ChannelId::new(0)).unwrap();
+		let escrow_account = <Test as Config>::AccountIdConversion::try_from(escrow_address)
+			.map_err(|_| ())
+			.unwrap()
+			.into_account();
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::slash(&escrow_account, MILLIS);
+		assert_eq!(Pallet::<Test>::escrow_violations().len(), 1);
+
+		// are detected by the next transfer, which pauses the channel
+		assert_ok!(transfer());
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Ibc(Event::<Test>::EscrowInvariantViolated { channel: 0, .. })
+		)));
+		assert_eq!(PausedChannels::<Test>::get(0), TransferPause { send: true, receive: true });
+		assert_noop!(transfer(), crate::Error::<Test>::TransferPaused);
+	})
+}
+
+#[test]
+fn should_pause_channel_on_voucher_violation() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let pair = sp_core::sr25519::Pair::from_seed(b"12345678901234567890123456789012");
+		let ss58_address =
+			ibc_primitives::runtime_interface::account_id_to_ss58(pair.public().0, 49);
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		// the tokens received are native to the counterparty, so vouchers are minted for them
+		let amt = 1000 * MILLIS;
+		let recv = |sequence: u64| {
+			let packet_data = PacketData {
+				token: Coin {
+					denom: PrefixedDenom::from_str("PICA").unwrap(),
+					amount: ibc::applications::transfer::Amount::from_str(&format!("{amt:?}"))
+						.unwrap(),
+				},
+				sender: Signer::from_str("alice").unwrap(),
+				receiver: Signer::from_str(&ss58_address).unwrap(),
+				memo: "".to_string(),
+			};
+			let time_now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+			let msg = MsgRecvPacket {
+				packet: Packet {
+					sequence: sequence.into(),
+					source_port: PortId::transfer(),
+					source_channel: ChannelId::new(1),
+					destination_port: PortId::transfer(),
+					destination_channel: ChannelId::new(0),
+					data: serde_json::to_vec(&packet_data).unwrap(),
+					timeout_height: Height::new(2000, 5),
+					timeout_timestamp: ibc::timestamp::Timestamp::from_nanoseconds(
+						time_now as u64 + 10000000,
+					)
+					.unwrap(),
+				},
+				proofs: Proofs::new(
+					vec![0u8; 32].try_into().unwrap(),
+					None,
+					None,
+					None,
+					Height::new(0, 1),
+				)
+				.unwrap(),
+				signer: Signer::from_str(MODULE_ID).unwrap(),
+			};
+			let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+			Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+		};
+		let voucher = "transfer/channel-0/PICA";
+		let asset_id =
+			<<Test as Config>::IbcDenomToAssetIdConversion as DenomToAssetId<Test>>::from_denom_to_asset_id(
+				voucher,
+			)
+			.unwrap();
+
+		recv(1);
+		assert_eq!(ReceivedVoucherAmounts::<Test>::get(0, voucher.as_bytes().to_vec()), Some(amt));
+		assert_eq!(Pallet::<Test>::voucher_issuance(voucher), amt);
+		assert!(Pallet::<Test>::escrow_violations().is_empty());
+
+		// vouchers minted outside of the transfer application
+		<<Test as Config>::Fungibles as Mutate<<Test as frame_system::Config>::AccountId>>::mint_into(
+			asset_id,
+			&AccountId32::new(pair.public().0),
+			MILLIS,
+		)
+		.unwrap();
+		assert_eq!(
+			Pallet::<Test>::escrow_violations(),
+			vec![EscrowViolation::Voucher {
+				channel: 0,
+				denom: voucher.as_bytes().to_vec(),
+				received: amt,
+				issuance: amt + MILLIS,
+			}]
+		);
+
+		// are detected by the next transfer received, which pauses the channel
+		recv(2);
+		assert!(System::events().iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::Ibc(Event::<Test>::VoucherInvariantViolated { channel: 0, .. })
+		)));
+		assert_eq!(PausedChannels::<Test>::get(0), TransferPause { send: true, receive: true });
+	})
+}
+
+#[test]
+fn on_deliver_ics27_recv_packet() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let host_port = PortId::from_str(ics27::HOST_PORT_ID_STR).unwrap();
+		setup_client_and_consensus_state(host_port.clone());
+
+		let owner = ics27::context::account_to_signer(AccountId32::new([1; 32])).unwrap();
+		let controller_port = controller_port_id(&owner).unwrap();
+		let counterparty = ChanCounterParty::new(controller_port.clone(), Some(ChannelId::new(1)));
+		let channel_end = ChannelEnd::new(
+			State::Open,
+			Order::Ordered,
+			counterparty,
+			vec![ConnectionId::new(0)],
+			Metadata::new(ConnectionId::new(1), ConnectionId::new(0)).into(),
+		);
+		let mut ctx = Context::<Test>::default();
+		ctx.store_channel((host_port.clone(), ChannelId::new(0)), &channel_end).unwrap();
+
+		let account = AccountId32::new([7; 32]);
+		let receiver = AccountId32::new([8; 32]);
+		IcaHostAccounts::<Test>::insert(
+			ConnectionId::new(0).as_bytes().to_vec(),
+			controller_port.as_bytes().to_vec(),
+			account.clone(),
+		);
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&account, 100000 * MILLIS);
+
+		let recv_packet = |sequence: u64, calls: Vec<RuntimeCall>| {
+			let messages = calls
+				.into_iter()
+				.map(|call| ibc_proto::google::protobuf::Any {
+					type_url: ics27::RUNTIME_CALL_TYPE_URL.to_string(),
+					value: call.encode(),
+				})
+				.collect();
+			let data = InterchainAccountPacketData::execute_tx(messages, String::new());
+			let packet = Packet {
+				sequence: sequence.into(),
+				source_port: controller_port.clone(),
+				source_channel: ChannelId::new(1),
+				destination_port: host_port.clone(),
+				destination_channel: ChannelId::new(0),
+				data: serde_json::to_vec(&data).unwrap(),
+				timeout_height: Height::new(2000, 5),
+				timeout_timestamp: ibc::timestamp::Timestamp::none(),
+			};
+			let msg = MsgRecvPacket {
+				packet,
+				proofs: Proofs::new(
+					vec![0u8; 32].try_into().unwrap(),
+					None,
+					None,
+					None,
+					Height::new(0, 1),
+				)
+				.unwrap(),
+				signer: Signer::from_str(MODULE_ID).unwrap(),
+			};
+			let msg = Any { type_url: msg.type_url(), value: msg.encode_vec().unwrap() };
+			Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]).unwrap();
+		};
+		let free_balance = |account: &AccountId32| {
+			<<Test as Config>::NativeCurrency as Currency<
+				<Test as frame_system::Config>::AccountId,
+			>>::free_balance(account)
+		};
+		let transfer = RuntimeCall::PalletBalances(balances::Call::transfer {
+			dest: receiver.clone(),
+			value: 1000 * MILLIS,
+		});
+
+		recv_packet(1, vec![transfer.clone()]);
+		assert_eq!(free_balance(&receiver), 1000 * MILLIS);
+
+		// remarks aren't allowed, so the whole transaction is reverted
+		let remark = RuntimeCall::System(frame_system::Call::remark { remark: vec![] });
+		recv_packet(2, vec![transfer, remark]);
+		assert_eq!(free_balance(&receiver), 1000 * MILLIS);
+		assert_eq!(free_balance(&account), 99000 * MILLIS);
+	})
+}
+
+#[test]
+fn should_escrow_packet_fees_on_fee_enabled_channels() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		setup_client_and_consensus_state(PortId::transfer());
+
+		let payer = AccountId32::new([1; 32]);
+		let relayer = AccountId32::new([2; 32]);
+		let payee = AccountId32::new([3; 32]);
+		let _ = <<Test as Config>::NativeCurrency as Currency<
+			<Test as frame_system::Config>::AccountId,
+		>>::deposit_creating(&payer, 1000 * MILLIS);
+		let fee = PacketFeeParams {
+			recv_fee: vec![(NativeAssetId::get(), 30 * MILLIS)],
+			ack_fee: vec![(NativeAssetId::get(), 20 * MILLIS)],
+			timeout_fee: vec![(NativeAssetId::get(), 70 * MILLIS)],
+		};
+		let port_id = PortId::transfer().to_string();
+		let channel_id = ChannelId::new(0).to_string();
+
+		// fees can only be paid on the channels which negotiated the fee version
+		assert_noop!(
+			Ibc::pay_packet_fee(
+				RuntimeOrigin::signed(payer.clone()),
+				port_id.clone(),
+				channel_id.clone(),
+				fee.clone(),
+			),
+			crate::Error::<Test>::PacketFee
+		);
+
+		FeeEnabledChannels::<Test>::insert(PortId::transfer().as_bytes().to_vec(), 0, ());
+		assert_ok!(Ibc::register_payee(
+			RuntimeOrigin::signed(relayer.clone()),
+			port_id.clone(),
+			channel_id.clone(),
+			payee.clone(),
+		));
+		assert_eq!(FeePayees::<Test>::get(0, &relayer), Some(payee));
+		assert_ok!(Ibc::pay_packet_fee(
+			RuntimeOrigin::signed(payer.clone()),
+			port_id.clone(),
+			channel_id,
+			fee,
+		));
+
+		let fee_escrow_address =
+			<Test as Config>::AccountIdConversion::try_from(ics29::fee_escrow_address())
+				.map_err(|_| ())
+				.unwrap()
+				.into_account();
+		let free_balance = |account: &AccountId32| {
+			<<Test as Config>::NativeCurrency as Currency<
+				<Test as frame_system::Config>::AccountId,
+			>>::free_balance(account)
+		};
+		// the largest of the fees of the two outcomes of the packet is escrowed
+		assert_eq!(free_balance(&fee_escrow_address), 70 * MILLIS);
+		assert_eq!(free_balance(&payer), 930 * MILLIS);
+		assert!(PacketFees::<Test>::contains_key((port_id.as_bytes().to_vec(), 0), 1));
+	})
+}
+
+#[test]
+fn should_fetch_recv_packet_with_acknowledgement() {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		frame_system::Pallet::<Test>::set_block_number(1u32);
+		let channel_id = ChannelId::new(0);
+		let port_id = PortId::transfer();
+
+		let mut ctx = Context::<Test>::default();
+
+		let channel_end = ChannelEnd { state: State::Open, ..Default::default() };
+		ctx.store_channel((port_id.clone(), channel_id), &channel_end).unwrap();
+		let packet = Packet {
+			sequence: 1u64.into(),
+			source_port: port_id.clone(),
+			source_channel: channel_id,
+			destination_port: port_id.clone(),
 			destination_channel: channel_id,
 			data: "hello".as_bytes().to_vec(),
 			timeout_height: Default::default(),
@@ -1310,3 +2513,354 @@ fn test_next_and_previous_consensus_state_for_other_client_types() {
 		assert!(ctx.next_consensus_state(&client_id, Height::new(0, 400)).unwrap().is_some());
 	})
 }
+
+#[test]
+fn should_paginate_packet_commitments() {
+	new_test_ext().execute_with(|| {
+		let port_id = PortId::transfer();
+		let channel_id = ChannelId::new(0);
+		let mut ctx = Context::<Test>::default();
+		for (channel, seq) in
+			(1..=5u64).map(|seq| (channel_id, seq)).chain([(ChannelId::new(1), 1)])
+		{
+			ctx.store_packet_commitment((port_id.clone(), channel, seq.into()), vec![1; 32].into())
+				.unwrap();
+		}
+		let query = |pagination| {
+			let response = Ibc::packet_commitments(
+				channel_id.to_string().as_bytes().to_vec(),
+				port_id.as_bytes().to_vec(),
+				Some(pagination),
+			)
+			.unwrap();
+			let sequences = response.commitments.iter().map(|c| c.sequence).collect::<Vec<_>>();
+			(sequences, response.pagination.unwrap())
+		};
+
+		let (sequences, page) =
+			query(PageRequest { limit: 2, count_total: true, ..Default::default() });
+		assert_eq!(sequences, vec![1, 2]);
+		assert_eq!(page.total, 5);
+		let (sequences, page) =
+			query(PageRequest { key: page.next_key, limit: 2, ..Default::default() });
+		assert_eq!(sequences, vec![3, 4]);
+		let (sequences, page) =
+			query(PageRequest { key: page.next_key, limit: 2, ..Default::default() });
+		assert_eq!(sequences, vec![5]);
+		assert!(page.next_key.is_empty());
+
+		let (sequences, page) =
+			query(PageRequest { offset: 1, limit: 3, reverse: true, ..Default::default() });
+		assert_eq!(sequences, vec![4, 3, 2]);
+		let (sequences, _) =
+			query(PageRequest { key: page.next_key, reverse: true, ..Default::default() });
+		assert_eq!(sequences, vec![1]);
+	})
+}
+
+#[test]
+fn should_simulate_deliver_without_persisting() {
+	new_test_ext().execute_with(|| {
+		let mock_client_state =
+			MockClientState::new(MockClientMessage::from(MockHeader::new(Height::new(0, 1))));
+		let mock_cs_state = MockConsensusState::new(MockHeader::new(Height::new(0, 1)));
+		let msg = MsgCreateAnyClient::<Context<Test>>::new(
+			AnyClientState::Mock(mock_client_state),
+			AnyConsensusState::Mock(mock_cs_state),
+			Signer::from_str(MODULE_ID).unwrap(),
+		)
+		.unwrap()
+		.encode_vec()
+		.unwrap();
+		let create_client = Any { type_url: TYPE_URL.to_string(), value: msg };
+		let malformed = Any { type_url: TYPE_URL.to_string(), value: vec![1, 2, 3] };
+
+		let simulated = Ibc::simulate_deliver(vec![create_client, malformed]);
+		assert_eq!(simulated.len(), 2);
+		assert!(matches!(
+			simulated[0].result.as_deref(),
+			Ok([crate::events::IbcEvent::CreateClient { .. }])
+		));
+		assert!(simulated[1].result.is_err());
+		// nothing was persisted
+		assert!(Ibc::clients(None).client_states.is_empty());
+	})
+}
+
+#[test]
+fn should_create_and_update_solo_machine_client() {
+	use crate::light_clients::{AnyClientMessage, HostFunctionsManager};
+	use ibc::core::ics02_client::msgs::update_client::{
+		MsgUpdateAnyClient, TYPE_URL as UPDATE_CLIENT_TYPE_URL,
+	};
+	use ics06_solomachine::{
+		client_message::{ClientMessage, Header},
+		client_state::ClientState as SoloMachineClientState,
+		consensus_state::{ConsensusState as SoloMachineConsensusState, PublicKey},
+		proof::{encode_signature, sign_bytes, SENTINEL_HEADER_PATH},
+		proto::HeaderData,
+	};
+	use prost::Message;
+
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::ed25519::Pair::from_seed(&[1; 32]);
+		let new_pair = sp_core::ed25519::Pair::from_seed(&[2; 32]);
+		let consensus_state = SoloMachineConsensusState::new(
+			PublicKey::Ed25519(pair.public().0),
+			"solo".to_string(),
+			1,
+		);
+		let client_state =
+			SoloMachineClientState::<HostFunctionsManager>::new(1, consensus_state.clone());
+		let client_id = ClientId::new(&client_state.client_type(), 0).unwrap();
+		let msg = MsgCreateAnyClient::<Context<Test>>::new(
+			AnyClientState::SoloMachine(client_state),
+			AnyConsensusState::SoloMachine(consensus_state),
+			Signer::from_str(MODULE_ID).unwrap(),
+		)
+		.unwrap()
+		.encode_vec()
+		.unwrap();
+		let msg = Any { type_url: TYPE_URL.to_string(), value: msg };
+		assert_ok!(Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]));
+
+		let new_public_key = PublicKey::Ed25519(new_pair.public().0);
+		let header_data = HeaderData {
+			new_pub_key: Some(new_public_key.to_any()),
+			new_diversifier: "solo".to_string(),
+		};
+		let sign_bytes = sign_bytes(
+			1,
+			2,
+			"solo".to_string(),
+			SENTINEL_HEADER_PATH.as_bytes().to_vec(),
+			header_data.encode_to_vec(),
+		);
+		let header = Header {
+			timestamp: 2,
+			signature: encode_signature(pair.sign(&sign_bytes).0.to_vec()),
+			new_public_key: new_public_key.clone(),
+			new_diversifier: "solo".to_string(),
+		};
+		let msg = MsgUpdateAnyClient::<Context<Test>>::new(
+			client_id.clone(),
+			AnyClientMessage::SoloMachine(ClientMessage::Header(header)),
+			Signer::from_str(MODULE_ID).unwrap(),
+		)
+		.encode_vec()
+		.unwrap();
+		let msg = Any { type_url: UPDATE_CLIENT_TYPE_URL.to_string(), value: msg };
+		assert_ok!(Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]));
+
+		let ctx = Context::<Test>::default();
+		match ctx.client_state(&client_id).unwrap() {
+			AnyClientState::SoloMachine(client_state) => {
+				assert_eq!(client_state.sequence, 2);
+				assert_eq!(client_state.consensus_state.public_key, new_public_key);
+			},
+			client_state => panic!("unexpected client state {client_state:?}"),
+		}
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 2)).is_ok());
+	})
+}
+
+#[test]
+fn should_advance_solo_machine_sequence_past_verified_proofs() {
+	use crate::light_clients::HostFunctionsManager;
+	use ibc::core::{
+		ics03_connection::msgs::conn_open_confirm::{
+			MsgConnectionOpenConfirm, TYPE_URL as CONN_OPEN_CONFIRM_TYPE_URL,
+		},
+		ics24_host::path::ConnectionsPath,
+	};
+	use ics06_solomachine::{
+		client_state::ClientState as SoloMachineClientState,
+		consensus_state::{ConsensusState as SoloMachineConsensusState, PublicKey},
+		proof::{merkle_path, sign_bytes, signature_proof},
+	};
+
+	new_test_ext().execute_with(|| {
+		let pair = sp_core::ed25519::Pair::from_seed(&[1; 32]);
+		let consensus_state = SoloMachineConsensusState::new(
+			PublicKey::Ed25519(pair.public().0),
+			"solo".to_string(),
+			1,
+		);
+		let client_state =
+			SoloMachineClientState::<HostFunctionsManager>::new(1, consensus_state.clone());
+		let client_id = ClientId::new(&client_state.client_type(), 0).unwrap();
+		let msg = MsgCreateAnyClient::<Context<Test>>::new(
+			AnyClientState::SoloMachine(client_state),
+			AnyConsensusState::SoloMachine(consensus_state),
+			Signer::from_str(MODULE_ID).unwrap(),
+		)
+		.unwrap()
+		.encode_vec()
+		.unwrap();
+		let msg = Any { type_url: TYPE_URL.to_string(), value: msg };
+		assert_ok!(Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]));
+
+		let mut ctx = Context::<Test>::default();
+		let counterparty_prefix: CommitmentPrefix = b"ibc".to_vec().try_into().unwrap();
+		let connection_end = ConnectionEnd::new(
+			ConnState::TryOpen,
+			client_id.clone(),
+			Counterparty::new(
+				ClientId::new("07-tendermint", 0).unwrap(),
+				Some(ConnectionId::new(1)),
+				counterparty_prefix.clone(),
+			),
+			vec![ConnVersion::default()],
+			core::time::Duration::from_nanos(0),
+		);
+		ctx.store_connection(ConnectionId::new(0), &connection_end).unwrap();
+
+		// the solo machine signs over the connection end opened on its side
+		let expected_conn = ConnectionEnd::new(
+			ConnState::Open,
+			ClientId::new("07-tendermint", 0).unwrap(),
+			Counterparty::new(
+				client_id.clone(),
+				Some(ConnectionId::new(0)),
+				ctx.commitment_prefix(),
+			),
+			vec![ConnVersion::default()],
+			core::time::Duration::from_nanos(0),
+		);
+		let sign_bytes = sign_bytes(
+			1,
+			2,
+			"solo".to_string(),
+			merkle_path(counterparty_prefix.as_bytes(), ConnectionsPath(ConnectionId::new(1))),
+			expected_conn.encode_vec().unwrap(),
+		);
+		let proof = signature_proof(pair.sign(&sign_bytes).0.to_vec(), 2);
+		let msg = MsgConnectionOpenConfirm {
+			connection_id: ConnectionId::new(0),
+			proofs: Proofs::new(proof.try_into().unwrap(), None, None, None, Height::new(0, 1))
+				.unwrap(),
+			signer: Signer::from_str(MODULE_ID).unwrap(),
+		};
+		let msg = Any {
+			type_url: CONN_OPEN_CONFIRM_TYPE_URL.to_string(),
+			value: msg.encode_vec().unwrap(),
+		};
+		assert_ok!(Ibc::deliver(
+			RuntimeOrigin::signed(AccountId32::new([0; 32])),
+			vec![msg.clone()]
+		));
+
+		// the proof can't be verified again
+		match ctx.client_state(&client_id).unwrap() {
+			AnyClientState::SoloMachine(client_state) => assert_eq!(client_state.sequence, 2),
+			client_state => panic!("unexpected client state {client_state:?}"),
+		}
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 2)).is_ok());
+		ctx.store_connection(ConnectionId::new(0), &connection_end).unwrap();
+		let result = ibc::core::ics26_routing::handler::deliver(&mut ctx, msg);
+		assert!(result.is_err());
+	})
+}
+
+#[test]
+fn should_create_and_update_near_client() {
+	use crate::light_clients::{AnyClientMessage, HostFunctionsManager};
+	use core::marker::PhantomData;
+	use ibc::core::ics02_client::msgs::update_client::{
+		MsgUpdateAnyClient, TYPE_URL as UPDATE_CLIENT_TYPE_URL,
+	};
+	use ics13_near::{
+		client_def::reconstruct_light_client_block_view_fields,
+		client_state::NearClientState,
+		consensus_state::ConsensusState as NearConsensusState,
+		header::{NearClientMessage, NearHeader},
+		types::{
+			BlockHeaderInnerLiteView, CryptoHash, LightClientBlockView, PublicKey, Signature,
+			ValidatorStakeView, ValidatorStakeViewV1,
+		},
+	};
+
+	let block = |height: u64| LightClientBlockView {
+		prev_block_hash: CryptoHash([height as u8; 32]),
+		next_block_inner_hash: CryptoHash([2; 32]),
+		inner_lite: BlockHeaderInnerLiteView {
+			height,
+			epoch_id: CryptoHash([1; 32]),
+			next_epoch_id: CryptoHash([2; 32]),
+			prev_state_root: CryptoHash([height as u8; 32]),
+			outcome_root: CryptoHash([3; 32]),
+			timestamp: height * 1_000,
+			timestamp_nanosec: height * 1_000_000_000,
+			next_bp_hash: CryptoHash([0; 32]),
+			block_merkle_root: CryptoHash([4; 32]),
+		},
+		inner_rest_hash: CryptoHash([5; 32]),
+		next_bps: None,
+		approvals_after_next: vec![],
+	};
+
+	new_test_ext().execute_with(|| {
+		let pairs =
+			(1..=4).map(|i| sp_core::ed25519::Pair::from_seed(&[i; 32])).collect::<Vec<_>>();
+		let validators = pairs
+			.iter()
+			.map(|pair| {
+				ValidatorStakeView::V1(ValidatorStakeViewV1 {
+					account_id: "validator.near".to_string(),
+					public_key: PublicKey(pair.public().0),
+					stake: 100,
+				})
+			})
+			.collect::<Vec<_>>();
+
+		let head = block(10);
+		let consensus_state = NearConsensusState::from_header(&head).unwrap();
+		let client_state = NearClientState::<HostFunctionsManager> {
+			chain_id: ChainId::new("near".to_string(), 0),
+			head,
+			current_epoch: CryptoHash([1; 32]),
+			next_epoch: CryptoHash([2; 32]),
+			current_validators: validators.clone(),
+			next_validators: validators,
+			ibc_account_id: "ibc.near".to_string(),
+			frozen_height: None,
+			_phantom: PhantomData,
+		};
+		let client_id = ClientId::new(&client_state.client_type(), 0).unwrap();
+		let msg = MsgCreateAnyClient::<Context<Test>>::new(
+			AnyClientState::Near(client_state),
+			AnyConsensusState::Near(consensus_state),
+			Signer::from_str(MODULE_ID).unwrap(),
+		)
+		.unwrap()
+		.encode_vec()
+		.unwrap();
+		let msg = Any { type_url: TYPE_URL.to_string(), value: msg };
+		assert_ok!(Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]));
+
+		let mut new_block = block(11);
+		let (_, _, approval_message) =
+			reconstruct_light_client_block_view_fields::<HostFunctionsManager>(&new_block).unwrap();
+		new_block.approvals_after_next = pairs
+			.iter()
+			.map(|pair| Some(Signature::Ed25519(pair.sign(&approval_message))))
+			.collect();
+		let msg = MsgUpdateAnyClient::<Context<Test>>::new(
+			client_id.clone(),
+			AnyClientMessage::Near(NearClientMessage::Header(NearHeader::new(new_block))),
+			Signer::from_str(MODULE_ID).unwrap(),
+		)
+		.encode_vec()
+		.unwrap();
+		let msg = Any { type_url: UPDATE_CLIENT_TYPE_URL.to_string(), value: msg };
+		assert_ok!(Ibc::deliver(RuntimeOrigin::signed(AccountId32::new([0; 32])), vec![msg]));
+
+		let ctx = Context::<Test>::default();
+		match ctx.client_state(&client_id).unwrap() {
+			AnyClientState::Near(client_state) =>
+				assert_eq!(client_state.latest_height(), Height::new(0, 11)),
+			client_state => panic!("unexpected client state {client_state:?}"),
+		}
+		assert!(ctx.consensus_state(&client_id, Height::new(0, 11)).is_ok());
+	})
+}
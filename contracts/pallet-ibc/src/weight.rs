@@ -42,6 +42,13 @@ pub trait WeightInfo {
 	fn on_timeout_packet() -> Weight;
 	fn update_grandpa_client(i: u32, j: u32) -> Weight;
 	fn packet_cleanup(i: u32) -> Weight;
+	fn register_interchain_account() -> Weight;
+	fn send_ica_tx() -> Weight;
+	fn pay_packet_fee() -> Weight;
+	fn register_payee() -> Weight;
+	fn transfer_multi(n: u32) -> Weight;
+	fn transfer_nft(n: u32) -> Weight;
+	fn upgrade_channel() -> Weight;
 }
 
 impl WeightInfo for () {
@@ -152,6 +159,34 @@ impl WeightInfo for () {
 	fn packet_cleanup(_i: u32) -> Weight {
 		Weight::default()
 	}
+
+	fn register_interchain_account() -> Weight {
+		Weight::default()
+	}
+
+	fn send_ica_tx() -> Weight {
+		Weight::default()
+	}
+
+	fn pay_packet_fee() -> Weight {
+		Weight::default()
+	}
+
+	fn register_payee() -> Weight {
+		Weight::default()
+	}
+
+	fn transfer_multi(_n: u32) -> Weight {
+		Weight::default()
+	}
+
+	fn transfer_nft(_n: u32) -> Weight {
+		Weight::default()
+	}
+
+	fn upgrade_channel() -> Weight {
+		Weight::default()
+	}
 }
 
 pub struct WeightRouter<T: Config>(PhantomData<T>);
@@ -161,6 +196,13 @@ impl<T: Config> WeightRouter<T> {
 		match port_id {
 			#[allow(clippy::box_default)]
 			ibc::applications::transfer::PORT_ID_STR => Some(Box::new(ics20::WeightHandler::<T>::default())),
+			// the callbacks of the nft transfer application are weighed as the token transfer ones
+			#[allow(clippy::box_default)]
+			ics721::PORT_ID_STR => Some(Box::new(ics20::WeightHandler::<T>::default())),
+			#[allow(clippy::box_default)]
+			port if port == ics27::HOST_PORT_ID_STR ||
+				port.starts_with(ics27::CONTROLLER_PORT_PREFIX) =>
+				Some(Box::new(ics27::WeightHandler::<T>::default())),
 			_ => None,
 		}
 	}
@@ -453,6 +495,21 @@ where
 						};
 						cb_weight.saturating_add(lc_verification_weight)
 					},
+					// The steps of the upgrade handshake are weighed as the steps of the opening
+					// and closing handshakes they mirror, which verify the same proofs and write
+					// the same channel state, whatever the client of the channel.
+					ChannelMsg::ChannelUpgradeInit(_) =>
+						<T as Config>::WeightInfo::channel_open_init(),
+					ChannelMsg::ChannelUpgradeTry(_) =>
+						<T as Config>::WeightInfo::channel_open_try_tendermint(),
+					ChannelMsg::ChannelUpgradeAck(_) =>
+						<T as Config>::WeightInfo::channel_open_ack_tendermint(),
+					ChannelMsg::ChannelUpgradeConfirm(_) | ChannelMsg::ChannelUpgradeOpen(_) =>
+						<T as Config>::WeightInfo::channel_open_confirm_tendermint(),
+					// both verify a counterparty proof and abort the upgrade, restoring the channel
+					ChannelMsg::ChannelUpgradeTimeout(_) | ChannelMsg::ChannelUpgradeCancel(_) =>
+						<T as Config>::WeightInfo::channel_close_confirm_tendermint()
+							.saturating_add(<T as Config>::WeightInfo::channel_close_init()),
 				},
 				Ics26Envelope::Ics4PacketMsg(msgs) => match msgs {
 					PacketMsg::RecvPacket(packet_msg) => {
@@ -0,0 +1,249 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replaying of the ibc events that were emitted while the relayer was not running.
+//!
+//! The relayer loop only sees the events finalized after the latest height of the counterparty
+//! light client, so handshake messages from an outage are never relayed, and packets from it are
+//! only picked up by the undelivered sequences scan. [`relay_historical_events`] scans the given
+//! range of blocks in chunks and feeds the events that still await a response on the sink to
+//! [`parse_events`], as if they were received live.
+
+use crate::{events::parse_events, process_messages, Mode};
+use anyhow::anyhow;
+use ibc::{
+	core::{
+		ics02_client::client_state::ClientState as ClientStateT,
+		ics03_connection::connection::{ConnectionEnd, State as ConnectionState},
+		ics04_channel::channel::{ChannelEnd, State as ChannelState},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+	},
+	events::IbcEvent,
+	timestamp::Timestamp,
+	Height,
+};
+use metrics::handler::MetricsHandler;
+use pallet_ibc::light_clients::AnyClientState;
+use primitives::Chain;
+
+/// Number of blocks scanned at once by default.
+pub const DEFAULT_BACKFILL_CHUNK_SIZE: u64 = 500;
+
+/// Relays the events emitted on `source` from `from_height` up to the latest height of the
+/// `source` light client on `sink`. Events after that height are picked up by the relayer loop.
+///
+/// The proofs for the replayed events are taken at the latest height known to the light client,
+/// so only the events whose counterpart hasn't been submitted to `sink` yet are relayed.
+pub async fn relay_historical_events<A: Chain, B: Chain>(
+	source: &mut A,
+	sink: &mut B,
+	metrics: &mut Option<MetricsHandler>,
+	mode: Option<Mode>,
+	from_height: u64,
+	chunk_size: u64,
+) -> anyhow::Result<()> {
+	if chunk_size == 0 {
+		return Err(anyhow!("Backfill chunk size must be greater than zero"))
+	}
+	let (sink_height, sink_timestamp) = sink.latest_height_and_timestamp().await?;
+	let client_state_response = sink.query_client_state(sink_height, source.client_id()).await?;
+	let client_state = client_state_response
+		.client_state
+		.map(AnyClientState::try_from)
+		.ok_or_else(|| {
+			anyhow!("Client state of {} not found on {}", source.name(), sink.name())
+		})??;
+	let client_height = client_state.latest_height();
+
+	// the height at which the state of `source` is provable on `sink` without a client update
+	let proof_height = source.get_proof_height(client_height).await;
+	let query_height = client_height
+		.sub(proof_height.revision_height.saturating_sub(client_height.revision_height))?;
+
+	log::info!(
+		target: "hyperspace",
+		"Replaying events of {} from {from_height} to {}",
+		source.name(),
+		query_height.revision_height
+	);
+	let mut from = from_height;
+	while from <= query_height.revision_height {
+		let to = (from + chunk_size - 1).min(query_height.revision_height);
+		let events = source.query_ibc_events_in_range(from, to, &*sink).await?;
+		log::debug!(target: "hyperspace", "Found {} events in blocks {from}..={to}", events.len());
+
+		let mut pending_events = Vec::new();
+		for mut event in events {
+			if is_pending(&*source, &*sink, &event, sink_height, &sink_timestamp).await? {
+				event.set_height(query_height);
+				pending_events.push(event);
+			}
+		}
+		if !pending_events.is_empty() {
+			log::info!(
+				target: "hyperspace",
+				"Replaying {} events from blocks {from}..={to} of {}",
+				pending_events.len(),
+				source.name()
+			);
+			let messages = parse_events(source, sink, pending_events, mode)
+				.await
+				.map_err(|e| anyhow!("Failed to parse events: {:?}", e))?;
+			process_messages(sink, metrics, messages).await?;
+		}
+		from = to + 1;
+	}
+	Ok(())
+}
+
+/// Returns `true` if the message constructed from the event hasn't been submitted to `sink` yet
+/// and would still be accepted.
+async fn is_pending<A: Chain, B: Chain>(
+	source: &A,
+	sink: &B,
+	event: &IbcEvent,
+	sink_height: Height,
+	sink_timestamp: &Timestamp,
+) -> anyhow::Result<bool> {
+	let pending = match event {
+		IbcEvent::SendPacket(send_packet) => {
+			let packet = &send_packet.packet;
+			!packet.timed_out(sink_timestamp, sink_height) &&
+				!sink
+					.query_unreceived_packets(
+						sink_height,
+						packet.destination_channel,
+						packet.destination_port.clone(),
+						vec![packet.sequence.into()],
+					)
+					.await?
+					.is_empty()
+		},
+		IbcEvent::WriteAcknowledgement(write_ack) => {
+			let packet = &write_ack.packet;
+			!sink
+				.query_unreceived_acknowledgements(
+					sink_height,
+					packet.source_channel,
+					packet.source_port.clone(),
+					vec![packet.sequence.into()],
+				)
+				.await?
+				.is_empty()
+		},
+		IbcEvent::OpenInitConnection(open_init) => match open_init.connection_id() {
+			Some(connection_id) => {
+				let connections = sink
+					.query_connection_using_client(
+						sink_height.revision_height as u32,
+						open_init.attributes().counterparty_client_id.to_string(),
+					)
+					.await?;
+				!connections.into_iter().any(|connection| {
+					connection.counterparty.map_or(false, |counterparty| {
+						counterparty.connection_id == connection_id.to_string()
+					})
+				})
+			},
+			None => false,
+		},
+		IbcEvent::OpenTryConnection(open_try) =>
+			match &open_try.attributes().counterparty_connection_id {
+				Some(counterparty_id) =>
+					connection_state(sink, sink_height, counterparty_id.clone()).await? ==
+						Some(ConnectionState::Init),
+				None => false,
+			},
+		IbcEvent::OpenAckConnection(open_ack) =>
+			match &open_ack.attributes().counterparty_connection_id {
+				Some(counterparty_id) =>
+					connection_state(sink, sink_height, counterparty_id.clone()).await? ==
+						Some(ConnectionState::TryOpen),
+				None => false,
+			},
+		IbcEvent::OpenInitChannel(open_init) => match open_init.channel_id {
+			Some(channel_id) => {
+				let (source_height, _) = source.latest_height_and_timestamp().await?;
+				let connection_response = source
+					.query_connection_end(source_height, open_init.connection_id.clone())
+					.await?;
+				let counterparty_connection_id = connection_response
+					.connection
+					.map(ConnectionEnd::try_from)
+					.transpose()?
+					.and_then(|connection| connection.counterparty().connection_id().cloned());
+				match counterparty_connection_id {
+					Some(counterparty_connection_id) => {
+						let channels = sink
+							.query_connection_channels(sink_height, &counterparty_connection_id)
+							.await?;
+						!channels.channels.into_iter().any(|channel| {
+							channel.counterparty.map_or(false, |counterparty| {
+								counterparty.channel_id == channel_id.to_string() &&
+									counterparty.port_id == open_init.port_id.to_string()
+							})
+						})
+					},
+					// the channel handshake can't proceed before the connection one
+					None => false,
+				}
+			},
+			None => false,
+		},
+		IbcEvent::OpenTryChannel(open_try) => match open_try.counterparty_channel_id {
+			Some(counterparty_id) =>
+				channel_state(sink, sink_height, counterparty_id, &open_try.counterparty_port_id)
+					.await? == Some(ChannelState::Init),
+			None => false,
+		},
+		IbcEvent::OpenAckChannel(open_ack) => match open_ack.counterparty_channel_id {
+			Some(counterparty_id) =>
+				channel_state(sink, sink_height, counterparty_id, &open_ack.counterparty_port_id)
+					.await? == Some(ChannelState::TryOpen),
+			None => false,
+		},
+		IbcEvent::CloseInitChannel(close_init) => match close_init.counterparty_channel_id {
+			Some(counterparty_id) => matches!(
+				channel_state(sink, sink_height, counterparty_id, &close_init.counterparty_port_id)
+					.await?,
+				Some(state) if state != ChannelState::Closed
+			),
+			None => false,
+		},
+		// no message is constructed for the other events
+		_ => false,
+	};
+	Ok(pending)
+}
+
+async fn connection_state<C: Chain>(
+	chain: &C,
+	at: Height,
+	connection_id: ConnectionId,
+) -> anyhow::Result<Option<ConnectionState>> {
+	let response = chain.query_connection_end(at, connection_id).await?;
+	let connection = response.connection.map(ConnectionEnd::try_from).transpose()?;
+	Ok(connection.map(|connection| *connection.state()))
+}
+
+async fn channel_state<C: Chain>(
+	chain: &C,
+	at: Height,
+	channel_id: ChannelId,
+	port_id: &PortId,
+) -> anyhow::Result<Option<ChannelState>> {
+	let response = chain.query_channel_end(at, channel_id, port_id.clone()).await?;
+	let channel = response.channel.map(ChannelEnd::try_from).transpose()?;
+	Ok(channel.map(|channel| *channel.state()))
+}
@@ -53,20 +53,22 @@ use ibc_proto::{
 		channel::v1::{
 			QueryChannelResponse, QueryChannelsResponse, QueryNextSequenceReceiveResponse,
 			QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
-			QueryPacketReceiptResponse,
+			QueryPacketReceiptResponse, QueryUpgradeErrorResponse, QueryUpgradeResponse,
 		},
 		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
 		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
 	},
 };
 use ics08_wasm::Bytes;
+#[cfg(feature = "near")]
+use near::client::{NearClient, NearClientConfig};
 use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState};
 #[cfg(any(test, feature = "testing"))]
 use pallet_ibc::Timeout;
 use parachain::{ParachainClient, ParachainClientConfig};
 use primitives::{
-	mock::LocalClientTypes, Chain, CommonClientState, IbcProvider, KeyProvider, LightClientSync,
-	MisbehaviourHandler, UpdateType,
+	cache::CachedProvider, mock::LocalClientTypes, Chain, CommonClientState, IbcProvider,
+	KeyProvider, LightClientSync, MisbehaviourHandler, UpdateType,
 };
 use serde::{Deserialize, Serialize};
 use std::{pin::Pin, time::Duration};
@@ -99,6 +101,8 @@ chains! {
 	PicassoKusama(ParachainClientConfig, ParachainClient<PicassoKusamaConfig>),
 	#[cfg(feature = "cosmos")]
 	Cosmos(CosmosClientConfig, CosmosClient<DefaultConfig>),
+	#[cfg(feature = "near")]
+	Near(NearClientConfig, NearClient),
 }
 
 fn wrap_any_msg_into_wasm(msg: Any, code_id: Bytes) -> Result<Any, anyhow::Error> {
@@ -13,7 +13,9 @@
 // limitations under the License.
 
 use crate::{
+	backfill::{relay_historical_events, DEFAULT_BACKFILL_CHUNK_SIZE},
 	chain::{AnyConfig, Config, CoreConfig},
+	fees::register_counterparty_payees,
 	fish, relay, Mode,
 };
 use anyhow::{anyhow, Result};
@@ -82,6 +84,15 @@ pub struct Cmd {
 	/// New config path for B to avoid overriding existing configuration
 	#[clap(long)]
 	pub out_config_b: Option<String>,
+	/// Height on chain A to start relaying historical events from, before relaying new ones
+	#[clap(long)]
+	from_height: Option<u64>,
+	/// Height on chain B to start relaying historical events from, before relaying new ones
+	#[clap(long)]
+	from_height_b: Option<u64>,
+	/// Number of blocks scanned at once when relaying historical events
+	#[clap(long, default_value_t = DEFAULT_BACKFILL_CHUNK_SIZE)]
+	backfill_chunk_size: u64,
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -138,8 +149,8 @@ impl Cmd {
 	/// Run the command
 	pub async fn run(&self) -> Result<()> {
 		let config = self.parse_config().await?;
-		let chain_a = config.chain_a.into_client().await?;
-		let chain_b = config.chain_b.into_client().await?;
+		let mut chain_a = config.chain_a.into_client().await?;
+		let mut chain_b = config.chain_b.into_client().await?;
 
 		let registry =
 			Registry::new_custom(None, None).expect("this can only fail if the prefix is empty");
@@ -153,7 +164,34 @@ impl Cmd {
 			tokio::spawn(init_prometheus(addr, registry.clone()));
 		}
 
-		relay(chain_a, chain_b, Some(metrics_handler_a), Some(metrics_handler_b), None).await
+		let (mut metrics_handler_a, mut metrics_handler_b) =
+			(Some(metrics_handler_a), Some(metrics_handler_b));
+		register_counterparty_payees(&chain_a, &chain_b).await;
+		register_counterparty_payees(&chain_b, &chain_a).await;
+		if let Some(from_height) = self.from_height {
+			relay_historical_events(
+				&mut chain_a,
+				&mut chain_b,
+				&mut metrics_handler_a,
+				None,
+				from_height,
+				self.backfill_chunk_size,
+			)
+			.await?;
+		}
+		if let Some(from_height) = self.from_height_b {
+			relay_historical_events(
+				&mut chain_b,
+				&mut chain_a,
+				&mut metrics_handler_b,
+				None,
+				from_height,
+				self.backfill_chunk_size,
+			)
+			.await?;
+		}
+
+		relay(chain_a, chain_b, metrics_handler_a, metrics_handler_b, None).await
 	}
 
 	/// Run fisherman
@@ -28,14 +28,20 @@ use ibc::{
 		},
 		ics04_channel::{
 			channel::{ChannelEnd, Counterparty as ChannelCounterparty, State},
+			events::{FlushComplete, UpgradeConfirm, UpgradeOpen},
 			msgs::{
 				acknowledgement::MsgAcknowledgement, chan_close_confirm::MsgChannelCloseConfirm,
 				chan_open_ack::MsgChannelOpenAck, chan_open_confirm::MsgChannelOpenConfirm,
-				chan_open_try::MsgChannelOpenTry, recv_packet::MsgRecvPacket,
+				chan_open_try::MsgChannelOpenTry, chan_upgrade_ack::MsgChannelUpgradeAck,
+				chan_upgrade_cancel::MsgChannelUpgradeCancel,
+				chan_upgrade_confirm::MsgChannelUpgradeConfirm,
+				chan_upgrade_open::MsgChannelUpgradeOpen, chan_upgrade_try::MsgChannelUpgradeTry,
+				recv_packet::MsgRecvPacket,
 			},
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes},
-		ics24_host::identifier::ConnectionId,
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 	},
 	events::{IbcEvent, IbcEventType},
 	proofs::{ConsensusProof, Proofs},
@@ -45,7 +51,7 @@ use ibc::{
 use ibc_proto::google::protobuf::Any;
 use pallet_ibc::light_clients::AnyClientState;
 use primitives::{error::Error, mock::LocalClientTypes, Chain};
-use std::str::FromStr;
+use std::{collections::HashSet, str::FromStr};
 use tendermint_proto::Protobuf;
 
 /// Connection proof type
@@ -65,6 +71,7 @@ pub async fn parse_events(
 	mode: Option<Mode>,
 ) -> Result<Vec<Any>, anyhow::Error> {
 	let mut messages = vec![];
+	let mut upgrades_to_open = HashSet::new();
 	// 1. translate events to messages
 	for event in events {
 		match event {
@@ -439,6 +446,204 @@ pub async fn parse_events(
 				let msg = Any { value, type_url: msg.type_url() };
 				messages.push(msg)
 			},
+			IbcEvent::UpgradeInitChannel(upgrade_init) => {
+				let (channel_end, upgrade, proofs) = query_channel_upgrade_with_proofs(
+					source,
+					upgrade_init.height(),
+					upgrade_init.port_id.clone(),
+					upgrade_init.channel_id,
+				)
+				.await?;
+				let counterparty = channel_end.counterparty();
+				let connection_id = upgrade.fields.connection_hops[0].clone();
+				let connection_response = source
+					.query_connection_end(upgrade_init.height(), connection_id.clone())
+					.await?;
+				let counterparty_connection = connection_response
+					.connection
+					.and_then(|connection| connection.counterparty)
+					.ok_or_else(|| {
+						Error::Custom(format!(
+							"[get_messages_for_events - upgrade_chan_init] Connection counterparty not found for {:?}",
+							connection_id
+						))
+					})?;
+
+				let msg = MsgChannelUpgradeTry {
+					port_id: counterparty.port_id.clone(),
+					channel_id: counterparty.channel_id.expect("Expect channel id to be set"),
+					proposed_upgrade_connection_hops: vec![ConnectionId::from_str(
+						&counterparty_connection.connection_id,
+					)?],
+					counterparty_upgrade_fields: upgrade.fields,
+					counterparty_upgrade_sequence: channel_end.upgrade_sequence,
+					proofs,
+					signer: sink.account_id(),
+				};
+
+				let value = msg.encode_vec()?;
+				let msg = Any { value, type_url: msg.type_url() };
+				messages.push(msg)
+			},
+			IbcEvent::UpgradeTryChannel(upgrade_try) => {
+				let (channel_end, upgrade, proofs) = query_channel_upgrade_with_proofs(
+					source,
+					upgrade_try.height(),
+					upgrade_try.port_id.clone(),
+					upgrade_try.channel_id,
+				)
+				.await?;
+				let counterparty = channel_end.counterparty();
+
+				let msg = MsgChannelUpgradeAck {
+					port_id: counterparty.port_id.clone(),
+					channel_id: counterparty.channel_id.expect("Expect channel id to be set"),
+					counterparty_upgrade: upgrade,
+					proofs,
+					signer: sink.account_id(),
+				};
+
+				let value = msg.encode_vec()?;
+				let msg = Any { value, type_url: msg.type_url() };
+				messages.push(msg)
+			},
+			IbcEvent::UpgradeAckChannel(upgrade_ack) => {
+				let (channel_end, upgrade, proofs) = query_channel_upgrade_with_proofs(
+					source,
+					upgrade_ack.height(),
+					upgrade_ack.port_id.clone(),
+					upgrade_ack.channel_id,
+				)
+				.await?;
+				let counterparty = channel_end.counterparty();
+
+				let msg = MsgChannelUpgradeConfirm {
+					port_id: counterparty.port_id.clone(),
+					channel_id: counterparty.channel_id.expect("Expect channel id to be set"),
+					counterparty_channel_state: channel_end.state,
+					counterparty_upgrade: upgrade,
+					proofs,
+					signer: sink.account_id(),
+				};
+
+				let value = msg.encode_vec()?;
+				let msg = Any { value, type_url: msg.type_url() };
+				messages.push(msg)
+			},
+			IbcEvent::UpgradeConfirmChannel(UpgradeConfirm {
+				height,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id: Some(counterparty_channel_id),
+				..
+			}) |
+			IbcEvent::FlushCompleteChannel(FlushComplete {
+				height,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id: Some(counterparty_channel_id),
+				..
+			}) |
+			IbcEvent::UpgradeOpenChannel(UpgradeOpen {
+				height,
+				port_id,
+				channel_id,
+				counterparty_port_id,
+				counterparty_channel_id: Some(counterparty_channel_id),
+				..
+			}) => {
+				// The same step of the handshake can emit several of these events, only send
+				// one open message per channel.
+				if !upgrades_to_open.insert((port_id.clone(), channel_id)) {
+					continue
+				}
+				// The counterparty can only be opened once it has flushed all of its packets
+				let (latest_height, _) = sink.latest_height_and_timestamp().await?;
+				let sink_channel = sink
+					.query_channel_end(
+						latest_height,
+						counterparty_channel_id,
+						counterparty_port_id.clone(),
+					)
+					.await?
+					.channel
+					.map(ChannelEnd::try_from)
+					.transpose()?;
+				if !matches!(sink_channel, Some(channel) if channel.state == State::FlushComplete) {
+					continue
+				}
+
+				let channel_response =
+					source.query_channel_end(height, channel_id, port_id.clone()).await?;
+				let channel_end =
+					ChannelEnd::try_from(channel_response.channel.ok_or_else(|| {
+						Error::Custom(format!(
+							"[get_messages_for_events - upgrade_chan_open] ChannelEnd not found for {:?}/{:?}",
+							channel_id, port_id
+						))
+					})?)?;
+				if !matches!(channel_end.state, State::FlushComplete | State::Open) {
+					continue
+				}
+				let channel_proof = CommitmentProofBytes::try_from(channel_response.proof)?;
+
+				let proof_height =
+					channel_response.proof_height.expect("Proof height should be present");
+				let proof_height =
+					Height::new(proof_height.revision_number, proof_height.revision_height);
+
+				let msg = MsgChannelUpgradeOpen {
+					port_id: counterparty_port_id,
+					channel_id: counterparty_channel_id,
+					counterparty_channel_state: channel_end.state,
+					counterparty_upgrade_sequence: channel_end.upgrade_sequence,
+					proofs: Proofs::new(channel_proof, None, None, None, proof_height)?,
+					signer: sink.account_id(),
+				};
+
+				let value = msg.encode_vec()?;
+				let msg = Any { value, type_url: msg.type_url() };
+				messages.push(msg)
+			},
+			IbcEvent::UpgradeErrorChannel(upgrade_error) => {
+				let Some(counterparty_channel_id) = upgrade_error.counterparty_channel_id else {
+					continue
+				};
+				let error_response = source
+					.query_channel_upgrade_error(
+						upgrade_error.height(),
+						upgrade_error.channel_id,
+						upgrade_error.port_id.clone(),
+					)
+					.await?;
+				let error_receipt =
+					ErrorReceipt::try_from(error_response.error_receipt.ok_or_else(|| {
+						Error::Custom(format!(
+							"[get_messages_for_events - upgrade_chan_error] Error receipt not found for {:?}/{:?}",
+							upgrade_error.channel_id, upgrade_error.port_id
+						))
+					})?)?;
+				let error_receipt_proof = CommitmentProofBytes::try_from(error_response.proof)?;
+
+				let proof_height =
+					error_response.proof_height.expect("Proof height should be present");
+				let proof_height =
+					Height::new(proof_height.revision_number, proof_height.revision_height);
+
+				let msg = MsgChannelUpgradeCancel {
+					port_id: upgrade_error.counterparty_port_id.clone(),
+					channel_id: counterparty_channel_id,
+					error_receipt,
+					proofs: Proofs::new(error_receipt_proof, None, None, None, proof_height)?,
+					signer: sink.account_id(),
+				};
+
+				let value = msg.encode_vec()?;
+				let msg = Any { value, type_url: msg.type_url() };
+				messages.push(msg)
+			},
 			IbcEvent::SendPacket(send_packet) => {
 				#[cfg(feature = "testing")]
 				if !packet_relay_status() {
@@ -582,6 +787,38 @@ pub async fn parse_events(
 	Ok(messages)
 }
 
+/// Fetch a channel end along with its pending upgrade, and the proofs of both at the given height.
+async fn query_channel_upgrade_with_proofs(
+	source: &impl Chain,
+	height: Height,
+	port_id: PortId,
+	channel_id: ChannelId,
+) -> Result<(ChannelEnd, Upgrade, Proofs), anyhow::Error> {
+	let channel_response = source.query_channel_end(height, channel_id, port_id.clone()).await?;
+	let channel_end = ChannelEnd::try_from(channel_response.channel.ok_or_else(|| {
+		Error::Custom(format!(
+			"[get_messages_for_events - channel_upgrade] ChannelEnd not found for {:?}/{:?}",
+			channel_id, port_id
+		))
+	})?)?;
+	let upgrade_response =
+		source.query_channel_upgrade(height, channel_id, port_id.clone()).await?;
+	let upgrade = Upgrade::try_from(upgrade_response.upgrade.ok_or_else(|| {
+		Error::Custom(format!(
+			"[get_messages_for_events - channel_upgrade] Upgrade not found for {:?}/{:?}",
+			channel_id, port_id
+		))
+	})?)?;
+
+	let channel_proof = CommitmentProofBytes::try_from(channel_response.proof)?;
+	let upgrade_proof = CommitmentProofBytes::try_from(upgrade_response.proof)?;
+	let proof_height = channel_response.proof_height.expect("Proof height should be present");
+	let proof_height = Height::new(proof_height.revision_number, proof_height.revision_height);
+	let proofs = Proofs::new(channel_proof, None, None, Some(upgrade_proof), proof_height)?;
+
+	Ok((channel_end, upgrade, proofs))
+}
+
 /// Fetch the consensus state proof for the sink chain.
 async fn query_host_consensus_state_proof(
 	sink: &impl Chain,
@@ -0,0 +1,106 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Relaying of the packets by their ICS-29 fees, following the fee policies of the channels.
+
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use primitives::{
+	fees::{PacketFeePolicy, PacketFees},
+	Chain,
+};
+use std::collections::HashMap;
+
+/// Queries the fees of the undelivered packets of the channel, if the channel has a fee policy.
+/// Failures are logged, and no packet is considered incentivized then.
+pub async fn query_packet_fees(
+	source: &impl Chain,
+	channel_id: ChannelId,
+	port_id: PortId,
+	policy: Option<&PacketFeePolicy>,
+) -> HashMap<u64, PacketFees> {
+	if policy.is_none() {
+		return HashMap::new()
+	}
+	source.query_incentivized_packets(channel_id, port_id.clone()).await.unwrap_or_else(|e| {
+		log::warn!(target: "hyperspace", "Failed to query incentivized packets of {channel_id}/{port_id} on {}: {e:?}", source.name());
+		HashMap::new()
+	})
+}
+
+/// Drops the sequences of the packets that can't satisfy the policy, whatever their outcome, and
+/// orders the rest by the fees they pay, highest first.
+pub fn order_by_fee(
+	mut seqs: Vec<u64>,
+	policy: Option<&PacketFeePolicy>,
+	fees: &HashMap<u64, PacketFees>,
+) -> Vec<u64> {
+	let Some(policy) = policy else { return seqs };
+	if policy.requires_fee() {
+		seqs.retain(|seq| {
+			let fees = fees.get(seq);
+			policy.should_relay(fees, false) || policy.should_relay(fees, true)
+		});
+	}
+	seqs.sort_by_key(|seq| std::cmp::Reverse(priority(fees, *seq)));
+	seqs
+}
+
+/// Returns the priority of the packet among the packets of its channel.
+pub fn priority(fees: &HashMap<u64, PacketFees>, seq: u64) -> u128 {
+	fees.get(&seq).map(PacketFees::priority).unwrap_or_default()
+}
+
+/// Registers the relayer's account on `source` as the payee of the recv fees earned on the
+/// channels of `source` that have a fee policy. The registration is made on `sink`, which tells
+/// `source` who delivered the packets in the acknowledgements.
+pub async fn register_counterparty_payees(source: &impl Chain, sink: &impl Chain) {
+	let channels = source.common_state().fee_policies.keys().cloned().collect::<Vec<_>>();
+	if channels.is_empty() {
+		return
+	}
+	let source_height = match source.latest_height_and_timestamp().await {
+		Ok((height, _)) => height,
+		Err(e) => {
+			log::warn!(target: "hyperspace", "Failed to query the latest height of {}: {e:?}", source.name());
+			return
+		},
+	};
+	for (channel_id, port_id) in channels {
+		let counterparty = source
+			.query_channel_end(source_height, channel_id, port_id.clone())
+			.await
+			.ok()
+			.and_then(|response| response.channel)
+			.and_then(|channel| channel.counterparty);
+		let Some(counterparty) = counterparty else {
+			log::warn!(target: "hyperspace", "Channel {channel_id}/{port_id} not found on {}, not registering the counterparty payee", source.name());
+			continue
+		};
+		let (Ok(sink_channel_id), Ok(sink_port_id)) =
+			(counterparty.channel_id.parse::<ChannelId>(), counterparty.port_id.parse::<PortId>())
+		else {
+			log::warn!(target: "hyperspace", "Channel {channel_id}/{port_id} on {} has no counterparty channel yet, not registering the counterparty payee", source.name());
+			continue
+		};
+		match sink
+			.register_counterparty_payee(sink_channel_id, sink_port_id, source.account_id())
+			.await
+		{
+			Ok(()) =>
+				log::info!(target: "hyperspace", "Registered {} as counterparty payee for {channel_id}/{port_id} on {}", source.account_id(), sink.name()),
+			Err(e) =>
+				log::warn!(target: "hyperspace", "Failed to register the counterparty payee for {channel_id}/{port_id} on {}: {e:?}", sink.name()),
+		}
+	}
+}
@@ -14,9 +14,11 @@
 
 #![warn(unused_variables)]
 
+pub mod backfill;
 pub mod chain;
 pub mod command;
 pub mod events;
+pub mod fees;
 pub mod logging;
 mod macros;
 pub mod packets;
@@ -68,11 +70,15 @@ where
 			result = chain_a_finality.next(), if !first_executed => {
 				first_executed = true;
 				process_finality_event(&mut chain_a, &mut chain_b, &mut chain_a_metrics, mode, result, &mut chain_a_finality, &mut chain_b_finality).await?;
+				report_client_stats(&chain_a, &chain_a_metrics);
+				report_client_stats(&chain_b, &chain_b_metrics);
 			}
 			// new finality event from chain B
 			result = chain_b_finality.next() => {
 				first_executed = false;
 				process_finality_event(&mut chain_b, &mut chain_a, &mut chain_b_metrics, mode, result, &mut chain_b_finality, &mut chain_a_finality).await?;
+				report_client_stats(&chain_a, &chain_a_metrics);
+				report_client_stats(&chain_b, &chain_b_metrics);
 			}
 			else => {
 				first_executed = false;
@@ -141,6 +147,24 @@ where
 	Ok(())
 }
 
+/// Reports the rate limiter and query cache counters of a chain, both of which are queried
+/// regardless of the chain being the source or the sink of a finality event.
+fn report_client_stats(chain: &impl Chain, metrics: &Option<MetricsHandler>) {
+	if let Some(metrics) = metrics.as_ref() {
+		let stats = chain.rate_limiter().stats();
+		metrics.handle_rate_limiter_stats(
+			stats.acquired,
+			stats.delayed,
+			stats.rate_limited,
+			stats.wait_time,
+			stats.saturation(),
+			stats.current_rate,
+		);
+		let stats = chain.common_state().query_cache.stats();
+		metrics.handle_query_cache_stats(stats.hits, stats.misses, stats.entries as u64);
+	}
+}
+
 async fn process_finality_event<A: Chain, B: Chain>(
 	source: &mut A,
 	sink: &mut B,
@@ -154,26 +178,13 @@ async fn process_finality_event<A: Chain, B: Chain>(
 		// stream closed
 		None => {
 			log::warn!("Stream closed for {}", source.name());
-			*stream_source = loop {
-				match source.finality_notifications().await {
-					Ok(stream) => break RecentStream::new(stream),
-					Err(e) => {
-						log::error!("Failed to get finality notifications for {} {:?}. Trying again in 30 seconds...", source.name(), e);
-						tokio::time::sleep(std::time::Duration::from_secs(30)).await;
-						let _ = source.reconnect().await;
-					},
-				};
-			};
-			*stream_sink = loop {
-				match sink.finality_notifications().await {
-					Ok(stream) => break RecentStream::new(stream),
-					Err(e) => {
-						log::error!("Failed to get finality notifications for {} {:?}. Trying again in 30 seconds...", sink.name(), e);
-						tokio::time::sleep(std::time::Duration::from_secs(30)).await;
-						let _ = sink.reconnect().await;
-					},
-				};
-			};
+			// the node behind the stream is likely gone, fail over to another endpoint before
+			// re-subscribing
+			if let Err(e) = source.reconnect().await {
+				log::error!("Failed to reconnect to {} {:?}", source.name(), e);
+			}
+			*stream_source = resubscribe_finality_notifications(source).await;
+			*stream_sink = resubscribe_finality_notifications(sink).await;
 		},
 		Some(finality_event) => {
 			log::info!("=======================================================");
@@ -183,20 +194,26 @@ async fn process_finality_event<A: Chain, B: Chain>(
 				process_some_finality_event(source, sink, metrics, mode, finality_event).await;
 
 			match result {
-				Ok(()) => {
-					let sink_initial_rpc_call_delay = sink.initial_rpc_call_delay();
-					let source_initial_rpc_call_delay = source.initial_rpc_call_delay();
-					sink.set_rpc_call_delay(sink_initial_rpc_call_delay);
-					source.set_rpc_call_delay(source_initial_rpc_call_delay);
-				},
+				Ok(()) => (),
 				Err(e) => {
 					log::error!("{}", e);
+					let (source_reconnects, sink_reconnects) =
+						(source.common_state().reconnects(), sink.common_state().reconnects());
 					match sink.handle_error(&e).and_then(|_| source.handle_error(&e)).await {
 						Ok(_) => (),
 						Err(e) => {
 							log::error!("Failed to handle error {:?}", e)
 						},
 					}
+					// Events finalized while switching endpoints are not lost: the next finality
+					// event is processed starting from the latest height of the client on the
+					// counterparty.
+					if source.common_state().reconnects() != source_reconnects {
+						*stream_source = resubscribe_finality_notifications(source).await;
+					}
+					if sink.common_state().reconnects() != sink_reconnects {
+						*stream_sink = resubscribe_finality_notifications(sink).await;
+					}
 				},
 			}
 		},
@@ -204,6 +221,21 @@ async fn process_finality_event<A: Chain, B: Chain>(
 	Ok(())
 }
 
+async fn resubscribe_finality_notifications<C: Chain>(
+	chain: &mut C,
+) -> RecentStream<C::FinalityEvent> {
+	loop {
+		match chain.finality_notifications().await {
+			Ok(stream) => break RecentStream::new(stream),
+			Err(e) => {
+				log::error!("Failed to get finality notifications for {} {:?}. Trying again in 30 seconds...", chain.name(), e);
+				tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+				let _ = chain.reconnect().await;
+			},
+		};
+	}
+}
+
 async fn process_some_finality_event<A: Chain, B: Chain>(
 	source: &mut A,
 	sink: &mut B,
@@ -264,18 +296,21 @@ async fn process_updates<A: Chain, B: Chain>(
 	updates: Vec<(Any, Height, Vec<IbcEvent>, UpdateType)>,
 	msgs: &mut Vec<Any>,
 ) -> anyhow::Result<()> {
-	// for timeouts we need both chains to be up to date
+	// the proofs of the messages sent to the sink are verified with the client of the source.
+	// Timeouts of the packets sent from the source are proven with the client of the sink on the
+	// source, which is scheduled by the `pending_timeouts` index of the sink.
 	let sink_has_undelivered_acks = sink.has_undelivered_sequences(UndeliveredType::Recvs) ||
 		sink.has_undelivered_sequences(UndeliveredType::Acks) ||
 		sink.has_undelivered_sequences(UndeliveredType::Timeouts);
-	let source_has_undelivered_acks = source.has_undelivered_sequences(UndeliveredType::Timeouts);
 
-	let mandatory_heights_for_undelivered_seqs =
-		if (sink_has_undelivered_acks || source_has_undelivered_acks) && !updates.is_empty() {
-			find_mandatory_heights_for_undelivered_sequences(source, &updates).await
-		} else {
-			HashSet::new()
-		};
+	let mandatory_heights_for_undelivered_seqs = if sink_has_undelivered_acks && !updates.is_empty()
+	{
+		find_mandatory_heights_for_undelivered_sequences(source, &updates).await
+	} else {
+		HashSet::new()
+	};
+	let mandatory_heights_for_pending_timeouts =
+		find_mandatory_heights_for_pending_timeouts(source, &updates).await;
 
 	for (msg_update_client, height, events, update_type) in updates {
 		if let Some(metrics) = metrics.as_mut() {
@@ -295,22 +330,20 @@ async fn process_updates<A: Chain, B: Chain>(
 			messages.len(), update_type.is_optional(),
 		);
 
-		let need_to_send_proofs_for_sequences = (sink_has_undelivered_acks ||
-			source_has_undelivered_acks) &&
+		let need_to_send_proofs_for_sequences = sink_has_undelivered_acks &&
 			mandatory_heights_for_undelivered_seqs.contains(&height.revision_height);
+		let need_to_prove_timeouts =
+			mandatory_heights_for_pending_timeouts.contains(&height.revision_height);
 		let common_state = source.common_state();
 		let skip_optional_updates = common_state.skip_optional_client_updates;
 
 		// We want to send client update if packet messages exist but where not sent due
 		// to a connection delay even if client update message is optional
 		match (
-			// TODO: we actually may send only when timeout of some packet has reached,
-			// not when we have *any* undelivered packets. But this requires rewriting
-			// `find_suitable_proof_height_for_client` function, that uses binary
-			// search, which won't work in this case
 			skip_optional_updates &&
 				update_type.is_optional() &&
-				!need_to_send_proofs_for_sequences,
+				!need_to_send_proofs_for_sequences &&
+				!need_to_prove_timeouts,
 			has_packet_events(&event_types),
 			messages.is_empty(),
 		) {
@@ -322,11 +355,14 @@ async fn process_updates<A: Chain, B: Chain>(
 			(false, _, true) =>
 				if update_type.is_optional() && need_to_send_proofs_for_sequences {
 					log::info!("Sending an optional update because source ({}) chain has undelivered sequences", sink.name());
+				} else if update_type.is_optional() && need_to_prove_timeouts {
+					log::info!("Sending an optional update because timeouts of packets sent to {} become provable at {height}", source.name());
 				} else {
 					log::info!("Sending mandatory client update message for {}", sink.name())
 				},
 			_ => log::info!("Received finalized events from: {} {event_types:#?}", source.name()),
 		};
+		source.common_state().pending_timeouts.on_client_updated(height.revision_height);
 		msgs.push(msg_update_client);
 		msgs.append(&mut messages);
 	}
@@ -403,6 +439,43 @@ async fn find_mandatory_heights_for_undelivered_sequences<A: Chain>(
 	mandatory_updates_for_undelivered_seqs
 }
 
+/// Returns the heights of the updates that make the earliest pending timeout of the packets sent
+/// to `source` provable: the first update at or after the height at which the timeout is reached,
+/// and the one providing the consensus state for its proof if the proof height differs.
+async fn find_mandatory_heights_for_pending_timeouts<A: Chain>(
+	source: &A,
+	updates: &[(Any, Height, Vec<IbcEvent>, UpdateType)],
+) -> HashSet<u64> {
+	let mut mandatory_heights = HashSet::new();
+	let Some(provable_at) = source.common_state().pending_timeouts.earliest_provable_height()
+	else {
+		return mandatory_heights
+	};
+	let mut update_heights = updates
+		.iter()
+		.map(|(_, height, ..)| *height)
+		.filter(|height| height.revision_height >= provable_at)
+		.collect::<Vec<_>>();
+	update_heights.sort();
+	let Some(timeout_height) = update_heights.first().cloned() else { return mandatory_heights };
+	mandatory_heights.insert(timeout_height.revision_height);
+	let proof_height = source.get_proof_height(timeout_height).await;
+	if proof_height.revision_height > timeout_height.revision_height {
+		if let Some(height) = update_heights
+			.iter()
+			.find(|height| height.revision_height >= proof_height.revision_height)
+		{
+			mandatory_heights.insert(height.revision_height);
+		}
+	}
+	log::debug!(
+		target: "hyperspace",
+		"Timeouts of packets sent to {} become provable at {provable_at}, updating the client at {mandatory_heights:?}",
+		source.name()
+	);
+	mandatory_heights
+}
+
 #[cfg(feature = "testing")]
 pub mod send_packet_relay {
 	use std::sync::atomic::{AtomicBool, Ordering};
@@ -32,7 +32,7 @@ macro_rules! chains {
 		pub enum AnyChain {
 			$(
 				$(#[$($meta)*])*
-				$name($client),
+				$name(CachedProvider<$client>),
 			)*
 			Wasm(WasmChain),
 		}
@@ -107,6 +107,24 @@ macro_rules! chains {
 				}
 			}
 
+			async fn query_ibc_events_in_range<T>(
+				&self,
+				from: u64,
+				to: u64,
+				counterparty: &T,
+			) -> Result<Vec<IbcEvent>, anyhow::Error>
+			where
+				T: Chain,
+			{
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain.query_ibc_events_in_range(from, to, counterparty).await,
+					)*
+					AnyChain::Wasm(c) => c.inner.query_ibc_events_in_range(from, to, counterparty).await,
+				}
+			}
+
 			async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
 				match self {
 					$(
@@ -188,6 +206,43 @@ macro_rules! chains {
 				}
 			}
 
+			async fn query_channel_upgrade(
+				&self,
+				at: Height,
+				channel_id: ChannelId,
+				port_id: PortId,
+			) -> Result<QueryUpgradeResponse, Self::Error> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain
+							.query_channel_upgrade(at, channel_id, port_id)
+							.await
+							.map_err(AnyError::$name),
+					)*
+					AnyChain::Wasm(c) => c.inner.query_channel_upgrade(at, channel_id, port_id).await,
+				}
+			}
+
+			async fn query_channel_upgrade_error(
+				&self,
+				at: Height,
+				channel_id: ChannelId,
+				port_id: PortId,
+			) -> Result<QueryUpgradeErrorResponse, Self::Error> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain
+							.query_channel_upgrade_error(at, channel_id, port_id)
+							.await
+							.map_err(AnyError::$name),
+					)*
+					AnyChain::Wasm(c) =>
+						c.inner.query_channel_upgrade_error(at, channel_id, port_id).await,
+				}
+			}
+
 			async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
 				match self {
 					$(
@@ -645,6 +700,44 @@ macro_rules! chains {
 				}
 			}
 
+			async fn query_incentivized_packets(
+				&self,
+				channel_id: ChannelId,
+				port_id: PortId,
+			) -> Result<std::collections::HashMap<u64, primitives::fees::PacketFees>, Self::Error> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain
+							.query_incentivized_packets(channel_id, port_id)
+							.await
+							.map_err(AnyError::$name),
+					)*
+					Self::Wasm(c) => c.inner.query_incentivized_packets(channel_id, port_id).await,
+				}
+			}
+
+			async fn register_counterparty_payee(
+				&self,
+				channel_id: ChannelId,
+				port_id: PortId,
+				counterparty_payee: Signer,
+			) -> Result<(), Self::Error> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain
+							.register_counterparty_payee(channel_id, port_id, counterparty_payee)
+							.await
+							.map_err(AnyError::$name),
+					)*
+					Self::Wasm(c) => c
+						.inner
+						.register_counterparty_payee(channel_id, port_id, counterparty_payee)
+						.await,
+				}
+			}
+
 			async fn query_connection_id_from_tx_hash(
 				&self,
 				tx_id: Self::TransactionId,
@@ -776,6 +869,16 @@ macro_rules! chains {
 				}
 			}
 
+			async fn filter_invalid_messages(&self, msgs: Vec<Any>) -> Result<Vec<Any>, Self::Error> {
+				match self {
+					$(
+						$(#[$($meta)*])*
+						Self::$name(chain) => chain.filter_invalid_messages(msgs).await.map_err(AnyError::$name),
+					)*
+					Self::Wasm(c) => c.inner.filter_invalid_messages(msgs).await,
+				}
+			}
+
 			async fn finality_notifications(
 				&self,
 			) -> Result<Pin<Box<dyn Stream<Item = Self::FinalityEvent> + Send + Sync>>, Self::Error> {
@@ -848,26 +951,6 @@ macro_rules! chains {
 				}
 			}
 
-			fn rpc_call_delay(&self) -> std::time::Duration {
-				match self {
-					$(
-						$(#[$($meta)*])*
-						Self::$name(chain) => chain.rpc_call_delay(),
-					)*
-					Self::Wasm(c) => c.inner.rpc_call_delay(),
-				}
-			}
-
-			fn set_rpc_call_delay(&mut self, d: std::time::Duration) {
-				match self {
-					$(
-						$(#[$($meta)*])*
-						Self::$name(chain) => chain.set_rpc_call_delay(d),
-					)*
-					Self::Wasm(c) => c.inner.set_rpc_call_delay(d),
-				}
-			}
-
 			fn common_state(&self) -> &CommonClientState {
 				match self {
 					$(
@@ -993,7 +1076,8 @@ macro_rules! chains {
 				let chain = match self {
 					$(
 						$(#[$($meta)*])*
-						AnyConfig::$name(config) => AnyChain::$name(<$client>::new(config).await?),
+						AnyConfig::$name(config) =>
+							AnyChain::$name(CachedProvider::new(<$client>::new(config).await?)),
 					)*
 				};
 				if let Some(code_id) = maybe_wasm_code_id {
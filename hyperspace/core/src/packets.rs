@@ -14,20 +14,19 @@
 
 #[cfg(feature = "testing")]
 use crate::send_packet_relay::packet_relay_status;
-use rand::Rng;
 use sp_runtime::Either::{Left, Right};
-use std::{
-	sync::{
-		atomic::{AtomicUsize, Ordering},
-		Arc,
-	},
-	time::Duration,
+use std::sync::{
+	atomic::{AtomicUsize, Ordering},
+	Arc,
 };
-use tokio::{task::JoinSet, time::sleep};
+use tokio::task::JoinSet;
 
-use crate::packets::utils::{
-	construct_ack_message, construct_recv_message, construct_timeout_message,
-	get_timeout_proof_height, verify_delay_passed, VerifyDelayOn,
+use crate::{
+	fees::{order_by_fee, priority, query_packet_fees},
+	packets::utils::{
+		construct_ack_message, construct_recv_message, construct_timeout_message,
+		get_timeout_proof_height, verify_delay_passed, VerifyDelayOn,
+	},
 };
 use ibc::{
 	applications::transfer::packet::PacketData,
@@ -42,7 +41,8 @@ use ibc_proto::google::protobuf::Any;
 use pallet_ibc::light_clients::AnyClientState;
 use primitives::{
 	error::Error, find_suitable_proof_height_for_client, packet_info_to_packet,
-	query_undelivered_acks, query_undelivered_sequences, Chain, UndeliveredType,
+	query_undelivered_acks, query_undelivered_sequences, timeouts::PacketTimeout, Chain,
+	UndeliveredType,
 };
 
 pub mod connection_delay;
@@ -59,6 +59,10 @@ pub const PROCESS_PACKETS_BATCH_SIZE: usize = 100;
 /// source -> ack_packet     -> sink   => sink has undelivered acks
 /// source -> timeout_packet -> source => source & sink has undelivered timeouts (since timeouts
 /// need both clients to be up to date)
+///
+/// The packets of the channels with a fee policy are filtered by the policy, and ordered by the
+/// ICS-29 fees they pay. Acknowledgements are always relayed, so that the fees of the delivered
+/// packets are paid out.
 pub async fn query_ready_and_timed_out_packets(
 	source: &impl Chain,
 	sink: &impl Chain,
@@ -179,6 +183,9 @@ pub async fn query_ready_and_timed_out_packets(
 		let latest_source_height_on_sink = source_client_state_on_sink.latest_height();
 
 		let max_packets_to_process = source.common_state().max_packets_to_process;
+		let fee_policy = source.common_state().fee_policy(channel_id, &port_id);
+		let packet_fees =
+			query_packet_fees(source, channel_id, port_id.clone(), fee_policy.as_ref()).await;
 
 		// query packets that are waiting for connection delay.
 		let seqs = query_undelivered_sequences(
@@ -189,10 +196,11 @@ pub async fn query_ready_and_timed_out_packets(
 			source,
 			sink,
 		)
-		.await?
-		.into_iter()
-		.take(max_packets_to_process)
-		.collect::<Vec<_>>();
+		.await?;
+		let seqs = order_by_fee(seqs, fee_policy.as_ref(), &packet_fees)
+			.into_iter()
+			.take(max_packets_to_process)
+			.collect::<Vec<_>>();
 
 		log::debug!(target: "hyperspace", "Found {} undelivered packets for {:?}/{:?} for {seqs:?}", seqs.len(), channel_id, port_id.clone());
 
@@ -201,11 +209,23 @@ pub async fn query_ready_and_timed_out_packets(
 		send_packets.sort();
 		send_packets.dedup();
 		log::trace!(target: "hyperspace", "SendPackets count after deduplication: {}", send_packets.len());
+		// index the timeouts of the undelivered packets, so the client updates of the sink can be
+		// scheduled for the heights at which they become provable
+		let timeouts = send_packets.iter().filter_map(|send_packet| {
+			let packet = packet_info_to_packet(send_packet);
+			PacketTimeout::new(&packet, sink_height, sink_timestamp, sink.expected_block_time())
+				.map(|timeout| (send_packet.sequence, timeout))
+		});
+		sink.common_state()
+			.pending_timeouts
+			.replace_channel(channel_id, port_id.clone(), timeouts);
 		let mut recv_packets_join_set: JoinSet<Result<_, anyhow::Error>> = JoinSet::new();
 		let source = Arc::new(source.clone());
 		let sink = Arc::new(sink.clone());
 		let timeout_packets_count = Arc::new(AtomicUsize::new(0));
 		let send_packets_count = Arc::new(AtomicUsize::new(0));
+		let fee_policy = Arc::new(fee_policy.unwrap_or_default());
+		let packet_fees = Arc::new(packet_fees);
 		for send_packets in send_packets.chunks(PROCESS_PACKETS_BATCH_SIZE) {
 			for send_packet in send_packets.iter().cloned() {
 				let source_connection_end = source_connection_end.clone();
@@ -213,22 +233,26 @@ pub async fn query_ready_and_timed_out_packets(
 				let source_connection_end = source_connection_end.clone();
 				let source = source.clone();
 				let sink = sink.clone();
-				let duration = Duration::from_millis(
-					rand::thread_rng().gen_range(1..source.rpc_call_delay().as_millis() as u64),
-				);
 				let timeout_packets_count = timeout_packets_count.clone();
 				let recv_packets_count = send_packets_count.clone();
+				let fee_policy = fee_policy.clone();
+				let packet_fees = packet_fees.clone();
 				recv_packets_join_set.spawn(async move {
-					sleep(duration).await;
 					let source = &source;
 					let sink = &sink;
 					let packet = packet_info_to_packet(&send_packet);
+					let fees = packet_fees.get(&send_packet.sequence);
+					let priority = priority(&packet_fees, send_packet.sequence);
 					// Check if packet has timed out
 					let packet_height = send_packet.height.ok_or_else(|| {
 						Error::Custom(format!("Packet height not found for packet {packet:?}"))
 					})?;
 
 					if packet.timed_out(&sink_timestamp, sink_height) {
+						if !fee_policy.should_relay(fees, true) {
+							log::trace!(target: "hyperspace", "Skipping timeout as its fee doesn't satisfy the fee policy: {:?}", packet);
+							return Ok(None)
+						}
 						timeout_packets_count.fetch_add(1, Ordering::SeqCst);
 						// so we know this packet has timed out on the sink, we need to find the maximum
 						// consensus state height at which we can generate a non-membership proof of the
@@ -280,7 +304,7 @@ pub async fn query_ready_and_timed_out_packets(
 							proof_height,
 						)
 							.await?;
-						return Ok(Some(Left(msg)))
+						return Ok(Some(Left((priority, msg))))
 					} else {
 						log::trace!(target: "hyperspace", "The packet has not timed out yet: {:?}", packet);
 					}
@@ -293,6 +317,11 @@ pub async fn query_ready_and_timed_out_packets(
 						return Ok(None)
 					}
 
+					if !fee_policy.should_relay(fees, false) {
+						log::trace!(target: "hyperspace", "Skipping packet as its fee doesn't satisfy the fee policy: {:?}", packet);
+						return Ok(None)
+					}
+
 					#[cfg(feature = "testing")]
 					// If packet relay status is paused skip
 					if !packet_relay_status() {
@@ -364,18 +393,27 @@ pub async fn query_ready_and_timed_out_packets(
 					}
 
 					let msg = construct_recv_message(&**source, &**sink, packet, proof_height).await?;
-					Ok(Some(Right(msg)))
+					Ok(Some(Right((priority, msg))))
 				});
 			}
 		}
 
+		let mut channel_messages = vec![];
+		let mut channel_timeout_messages = vec![];
 		while let Some(result) = recv_packets_join_set.join_next().await {
 			let Some(either) = result?? else { continue };
 			match either {
-				Left(msg) => timeout_messages.push(msg),
-				Right(msg) => messages.push(msg),
+				Left(msg) => channel_timeout_messages.push(msg),
+				Right(msg) => channel_messages.push(msg),
 			}
 		}
+		// the packets paying the highest fees go first
+		for (mut msgs, out) in
+			[(channel_timeout_messages, &mut timeout_messages), (channel_messages, &mut messages)]
+		{
+			msgs.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+			out.extend(msgs.into_iter().map(|(_, msg)| msg));
+		}
 
 		let timeouts_count = timeout_packets_count.load(Ordering::SeqCst);
 		log::debug!(target: "hyperspace", "Found {timeouts_count} packets that have timed out");
@@ -418,11 +456,7 @@ pub async fn query_ready_and_timed_out_packets(
 				let source_connection_end = source_connection_end.clone();
 				let source = source.clone();
 				let sink = sink.clone();
-				let duration1 = Duration::from_millis(
-					rand::thread_rng().gen_range(1..source.rpc_call_delay().as_millis() as u64),
-				);
 				acknowledgements_join_set.spawn(async move {
-					sleep(duration1).await;
 					let source = &source;
 					let sink = &sink;
 					let packet = packet_info_to_packet(&acknowledgement);
@@ -69,6 +69,30 @@ pub async fn get_timeout_proof_height(
 			)
 			.await,
 		TimeoutVariant::Timestamp => {
+			// The client updates of the sink were scheduled for the height at which the timeout
+			// became provable, so start the search there if the packet is indexed
+			let pending_timeout = sink.common_state().pending_timeouts.get(
+				packet.source_channel,
+				packet.source_port.clone(),
+				packet.sequence.into(),
+			);
+			if let Some(pending_timeout) = pending_timeout {
+				let start_height =
+					Height::new(sink_height.revision_number, pending_timeout.provable_at);
+				let proof_height = find_suitable_proof_height_for_client(
+					sink,
+					source,
+					source_height,
+					sink.client_id(),
+					start_height,
+					Some(packet.timeout_timestamp),
+					latest_client_height_on_source,
+				)
+				.await;
+				if proof_height.is_some() {
+					return proof_height
+				}
+			}
 			// Get approximate number of blocks contained in this timestamp so we can have a lower
 			// bound for where to start our search
 			// We offset the sink height when this packet was created with the approximate number of
@@ -22,6 +22,10 @@ pub async fn flush_message_batch(
 	metrics: Option<&MetricsHandler>,
 	sink: &impl Chain,
 ) -> Result<(), anyhow::Error> {
+	let msgs = sink.filter_invalid_messages(msgs).await?;
+	if msgs.is_empty() {
+		return Ok(())
+	}
 	let block_max_weight = sink.block_max_weight();
 	let batch_weight = sink.estimate_weight(msgs.clone()).await?;
 
@@ -1,5 +1,12 @@
-use super::{client::CosmosClient, tx::sign_tx};
-use crate::{error::Error, events::client_extract_attributes_from_tx, provider::FinalityEvent};
+use super::{
+	client::{CosmosClient, CosmosConnection},
+	tx::sign_tx,
+};
+use crate::{
+	error::{grpc_error_kind, tendermint_rpc_error_kind, Error},
+	events::client_extract_attributes_from_tx,
+	provider::FinalityEvent,
+};
 use futures::{Stream, StreamExt};
 use ibc::{
 	core::{
@@ -19,15 +26,15 @@ use ibc_proto::{
 };
 use pallet_ibc::light_clients::AnyClientMessage;
 use primitives::{
-	mock::LocalClientTypes, Chain, CommonClientState, IbcProvider, LightClientSync,
-	MisbehaviourHandler,
+	mock::LocalClientTypes, rate_limit::RpcErrorKind, Chain, CommonClientState, IbcProvider,
+	LightClientSync, MisbehaviourHandler,
 };
 use prost::Message;
-use std::{pin::Pin, time::Duration};
+use std::pin::Pin;
 use tendermint_rpc::{
 	event::{Event, EventData},
 	query::{EventType, Query},
-	SubscriptionClient, WebSocketClient,
+	SubscriptionClient,
 };
 
 #[async_trait::async_trait]
@@ -94,11 +101,7 @@ where
 		&self,
 	) -> Result<Pin<Box<dyn Stream<Item = <Self as IbcProvider>::FinalityEvent> + Send + Sync>>, Error> {
 		let ws_client = self.rpc_ws_client().clone();
-		let subscription = ws_client
-			.subscribe(Query::from(EventType::NewBlock))
-			.await
-			.map_err(|e| Error::from(format!("failed to subscribe to new blocks {e:?}")))?
-			.chunks(6);
+		let subscription = ws_client.subscribe(Query::from(EventType::NewBlock)).await?.chunks(6);
 		log::info!(target: "hyperspace_cosmos", "🛰️ Subscribed to {} listening to finality notifications", self.name);
 		let stream = subscription.filter_map(|events| {
 			let events = events
@@ -160,8 +163,7 @@ where
 				limit: 1,
 				..Default::default()
 			})
-			.await
-			.map_err(|e| Error::from(e.to_string()))?
+			.await?
 			.into_inner();
 		let mut idx = None;
 		let tx_response = resp.tx_responses.pop().ok_or_else(|| {
@@ -222,33 +224,35 @@ where
 	}
 
 	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error> {
-		let err_str = if let Some(rpc_err) = error.downcast_ref::<Error>() {
-			match rpc_err {
-				Error::RpcError(s) => s.clone(),
-				_ => "".to_string(),
+		// the error that came back from the endpoint may be wrapped in context, so look at the
+		// whole chain of causes
+		let kind = error.chain().find_map(|cause| {
+			if let Some(err) = cause.downcast_ref::<Error>() {
+				err.rpc_error_kind()
+			} else if let Some(status) = cause.downcast_ref::<tonic::Status>() {
+				Some(grpc_error_kind(status))
+			} else {
+				cause.downcast_ref::<tendermint_rpc::Error>().map(tendermint_rpc_error_kind)
 			}
-		} else {
-			error.to_string()
-		};
-		log::debug!(target: "hyperspace_cosmos", "Handling error: {err_str}");
-		if err_str.contains("dispatch task is gone") ||
-			err_str.contains("failed to send message to internal channel")
-		{
-			self.reconnect().await?;
-			self.common_state.rpc_call_delay *= 2;
+		});
+		log::debug!(target: "hyperspace_cosmos", "Handling error ({kind:?}): {error:#}");
+		if kind.is_some() {
+			// every rpc error counts against the health of the active endpoint
+			self.endpoints.record_failure();
+		}
+		match kind.unwrap_or(RpcErrorKind::Other) {
+			RpcErrorKind::RateLimited => self.common_state.rate_limiter.on_rate_limited(),
+			RpcErrorKind::ConnectionLost => self.fail_over().await?,
+			RpcErrorKind::Other =>
+				if !self.endpoints.is_current_healthy() {
+					log::warn!(target: "hyperspace_cosmos", "Endpoint {} of {} is unhealthy, failing over", self.rpc_url, self.name);
+					self.fail_over().await?;
+				},
 		}
 
 		Ok(())
 	}
 
-	fn rpc_call_delay(&self) -> Duration {
-		self.common_state.rpc_call_delay
-	}
-
-	fn set_rpc_call_delay(&mut self, delay: Duration) {
-		self.common_state.rpc_call_delay = delay;
-	}
-
 	fn common_state(&self) -> &CommonClientState {
 		&self.common_state
 	}
@@ -258,14 +262,9 @@ where
 	}
 
 	async fn reconnect(&mut self) -> anyhow::Result<()> {
-		// TODO: don't reconnect if the url is not presented
-		let (rpc_client, ws_driver) = WebSocketClient::new(self.websocket_url().clone())
-			.await
-			.map_err(|e| Error::RpcError(format!("{e:?}")))?;
-		self.join_handles.lock().await.push(tokio::spawn(ws_driver.run()));
-		self.rpc_ws_client = Some(rpc_client);
-		log::info!(target: "hyperspace_cosmos", "Reconnected to cosmos chain");
-		Ok(())
+		// penalize the active endpoint, so that a healthier one is preferred
+		self.endpoints.record_failure();
+		self.fail_over().await
 	}
 }
 
@@ -273,6 +272,16 @@ impl<H> CosmosClient<H>
 where
 	H: 'static + Clone + Send + Sync,
 {
+	/// Connects to the healthiest endpoint of the pool. The failures of the active endpoint must
+	/// have been recorded already.
+	async fn fail_over(&mut self) -> anyhow::Result<()> {
+		let (endpoint, connection) = CosmosConnection::establish_any(&self.endpoints).await?;
+		log::info!(target: "hyperspace_cosmos", "Reconnected to cosmos chain using {}", endpoint.rpc_url);
+		self.use_connection(endpoint, connection).await;
+		self.common_state.on_reconnect();
+		Ok(())
+	}
+
 	pub fn get_fee(&self) -> Fee {
 		Fee {
 			amount: vec![Coin { denom: self.fee_denom.clone(), amount: self.fee_amount.clone() }],
@@ -27,11 +27,11 @@ use ics07_tendermint::{
 };
 use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager};
 use primitives::{
-	Chain, CommonClientConfig, CommonClientState, IbcProvider, KeyProvider, UpdateType,
+	cache::QueryCache, endpoints::EndpointPool, rate_limit::RateLimiter, Chain, CommonClientConfig,
+	CommonClientState, IbcProvider, KeyProvider, UpdateType,
 };
 use prost::Message;
 use quick_cache::sync::Cache;
-use rand::Rng;
 use ripemd::Ripemd160;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -47,7 +47,7 @@ use tendermint_rpc::{endpoint::abci_query::AbciQuery, Client, HttpClient, Url, W
 use tokio::{
 	sync::{Mutex as TokioMutex, Mutex as AsyncMutex},
 	task::{JoinHandle, JoinSet},
-	time::{error::Elapsed, sleep, timeout},
+	time::{error::Elapsed, timeout},
 };
 
 const DEFAULT_FEE_DENOM: &str = "stake";
@@ -141,6 +141,8 @@ pub struct CosmosClient<H> {
 	pub grpc_url: Option<Url>,
 	/// Websocket chain ws client
 	pub websocket_url: Option<Url>,
+	/// All the configured endpoints of the chain, including the active one
+	pub endpoints: EndpointPool<CosmosEndpoint>,
 	/// Chain Id
 	pub chain_id: ChainId,
 	/// Light client id on counterparty chain
@@ -189,6 +191,10 @@ pub struct CosmosClientConfig {
 	pub grpc_url: Option<Url>,
 	/// websocket url for cosmos
 	pub websocket_url: Option<Url>,
+	/// Endpoints of other nodes of the same chain to fail over to when the primary one is
+	/// unavailable
+	#[serde(default)]
+	pub fallback_endpoints: Vec<CosmosEndpoint>,
 	/// Cosmos chain Id
 	pub chain_id: String,
 	/// Light client id on counterparty chain
@@ -244,30 +250,35 @@ pub struct CosmosClientConfig {
 	pub skip_tokens_list: Option<Vec<String>>,
 }
 
-impl<H> CosmosClient<H>
-where
-	Self: KeyProvider,
-	H: Clone + Send + Sync + 'static,
-{
-	/// Initializes a [`CosmosClient`] given a [`CosmosClientConfig`]
-	pub async fn new(config: CosmosClientConfig) -> Result<Self, Error> {
-		let mut rpc_client = None;
+/// The set of urls exposed by a single cosmos node.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CosmosEndpoint {
+	/// rpc url for cosmos
+	pub rpc_url: Url,
+	/// grpc url for cosmos
+	#[serde(default)]
+	pub grpc_url: Option<Url>,
+	/// websocket url for cosmos
+	#[serde(default)]
+	pub websocket_url: Option<Url>,
+}
 
-		let mut join_handles = vec![];
-		if let Some(websocket_url) = &config.websocket_url {
-			let rpc_driver;
-			(rpc_client, rpc_driver) = WebSocketClient::new(websocket_url.clone())
-				.await
-				.map(|(x, y)| (Some(x), y))
-				.map_err(|e| Error::RpcError(format!("failed to connect to Websocket {:?}", e)))?;
-			join_handles.push(tokio::spawn(rpc_driver.run()));
-		} else {
-			log::warn!(target: "hyperspace_cosmos", "No websocket url provided for cosmos chain");
-		}
-		let rpc_http_client = HttpClient::new(config.rpc_url.clone())
+/// Clients connected to a single [`CosmosEndpoint`].
+pub struct CosmosConnection {
+	pub rpc_ws_client: Option<WebSocketClient>,
+	pub ws_driver_handle: Option<JoinHandle<Result<(), tendermint_rpc::Error>>>,
+	pub rpc_http_client: HttpClient,
+	pub grpc_client: Option<tonic::transport::Channel>,
+	pub light_client: LightClient,
+}
+
+impl CosmosConnection {
+	/// Connects to all the urls of the given endpoint.
+	pub async fn establish(endpoint: &CosmosEndpoint) -> Result<Self, Error> {
+		let rpc_http_client = HttpClient::new(endpoint.rpc_url.clone())
 			.map_err(|e| Error::RpcError(format!("failed to connect to RPC {:?}", e)))?;
 		let mut grpc_client = None;
-		if let Some(grpc_url) = &config.grpc_url {
+		if let Some(grpc_url) = &endpoint.grpc_url {
 			grpc_client = tonic::transport::Endpoint::new(grpc_url.to_string())
 				.map_err(|e| Error::RpcError(format!("failed to connect to RPC {:?}", e)))?
 				.connect()
@@ -277,10 +288,74 @@ where
 		} else {
 			log::warn!(target: "hyperspace_cosmos", "No grpc url provided for cosmos chain");
 		}
+		let light_client =
+			LightClient::init_light_client(endpoint.rpc_url.clone(), Duration::from_secs(10))
+				.await?;
+
+		let mut rpc_ws_client = None;
+		let mut ws_driver_handle = None;
+		if let Some(websocket_url) = &endpoint.websocket_url {
+			let (client, driver) = WebSocketClient::new(websocket_url.clone())
+				.await
+				.map_err(|e| Error::RpcError(format!("failed to connect to Websocket {:?}", e)))?;
+			rpc_ws_client = Some(client);
+			ws_driver_handle = Some(tokio::spawn(driver.run()));
+		} else {
+			log::warn!(target: "hyperspace_cosmos", "No websocket url provided for cosmos chain");
+		}
+
+		Ok(Self { rpc_ws_client, ws_driver_handle, rpc_http_client, grpc_client, light_client })
+	}
+
+	/// Connects to the healthiest endpoint of the pool that is reachable, marking it as the
+	/// active one. Unreachable endpoints are penalized.
+	pub async fn establish_any(
+		endpoints: &EndpointPool<CosmosEndpoint>,
+	) -> Result<(CosmosEndpoint, Self), Error> {
+		let mut last_error = None;
+		for (index, endpoint) in endpoints.candidates() {
+			match Self::establish(&endpoint).await {
+				Ok(connection) => {
+					endpoints.select(index);
+					return Ok((endpoint, connection))
+				},
+				Err(e) => {
+					log::warn!(target: "hyperspace_cosmos", "Failed to connect to {}: {e}", endpoint.rpc_url);
+					endpoints.record_failure_at(index);
+					last_error = Some(e);
+				},
+			}
+		}
+		Err(last_error.unwrap_or_else(|| Error::RpcError("no endpoints configured".to_string())))
+	}
+}
+
+impl<H> CosmosClient<H>
+where
+	Self: KeyProvider,
+	H: Clone + Send + Sync + 'static,
+{
+	/// Initializes a [`CosmosClient`] given a [`CosmosClientConfig`]
+	pub async fn new(config: CosmosClientConfig) -> Result<Self, Error> {
+		let endpoints = EndpointPool::new(
+			CosmosEndpoint {
+				rpc_url: config.rpc_url.clone(),
+				grpc_url: config.grpc_url.clone(),
+				websocket_url: config.websocket_url.clone(),
+			},
+			config.fallback_endpoints.clone(),
+		);
+		let (endpoint, connection) = CosmosConnection::establish_any(&endpoints).await?;
+		let CosmosConnection {
+			rpc_ws_client: rpc_client,
+			ws_driver_handle,
+			rpc_http_client,
+			grpc_client,
+			light_client,
+		} = connection;
+		let join_handles = ws_driver_handle.into_iter().collect::<Vec<_>>();
 
 		let chain_id = ChainId::from(config.chain_id);
-		let light_client =
-			LightClient::init_light_client(config.rpc_url.clone(), Duration::from_secs(10)).await?;
 		let commitment_prefix = CommitmentPrefix::try_from(config.store_prefix.as_bytes().to_vec())
 			.map_err(|e| Error::from(format!("Invalid store prefix {:?}", e)))?;
 
@@ -290,16 +365,16 @@ where
 		})
 		.map_err(|e| e.to_string())?;
 
-		let rpc_call_delay = Duration::from_millis(1000);
 		Ok(Self {
 			name: config.name,
 			chain_id,
 			rpc_ws_client: rpc_client,
 			rpc_http_client,
 			grpc_client,
-			rpc_url: config.rpc_url,
-			grpc_url: config.grpc_url,
-			websocket_url: config.websocket_url,
+			rpc_url: endpoint.rpc_url,
+			grpc_url: endpoint.grpc_url,
+			websocket_url: endpoint.websocket_url,
+			endpoints,
 			client_id: Arc::new(Mutex::new(config.client_id)),
 			connection_id: Arc::new(Mutex::new(config.connection_id)),
 			channel_whitelist: Arc::new(Mutex::new(config.channel_whitelist.into_iter().collect())),
@@ -317,16 +392,49 @@ where
 			common_state: CommonClientState {
 				skip_optional_client_updates: config.common.skip_optional_client_updates,
 				maybe_has_undelivered_packets: Default::default(),
-				rpc_call_delay,
-				initial_rpc_call_delay: rpc_call_delay,
+				pending_timeouts: Default::default(),
+				rate_limiter: RateLimiter::new(config.common.rate_limit.clone()),
+				query_cache: QueryCache::new(config.common.query_cache.clone()),
+				fee_policies: config
+					.common
+					.fee_policies
+					.iter()
+					.map(|p| ((p.channel_id, p.port_id.clone()), p.policy.clone()))
+					.collect(),
 				misbehaviour_client_msg_queue: Arc::new(AsyncMutex::new(vec![])),
 				max_packets_to_process: config.common.max_packets_to_process as usize,
 				skip_tokens_list: config.skip_tokens_list.unwrap_or_default(),
+				reconnects: Default::default(),
 			},
 			join_handles: Arc::new(TokioMutex::new(join_handles)),
 		})
 	}
 
+	/// Replaces the clients of this [`CosmosClient`] with the ones connected to `endpoint`.
+	pub async fn use_connection(&mut self, endpoint: CosmosEndpoint, connection: CosmosConnection) {
+		let CosmosConnection {
+			rpc_ws_client,
+			ws_driver_handle,
+			rpc_http_client,
+			grpc_client,
+			light_client,
+		} = connection;
+		// the driver of the previous websocket client would otherwise keep running
+		let mut join_handles = self.join_handles.lock().await;
+		for handle in join_handles.drain(..) {
+			handle.abort();
+		}
+		join_handles.extend(ws_driver_handle);
+		drop(join_handles);
+		self.rpc_ws_client = rpc_ws_client;
+		self.rpc_http_client = rpc_http_client;
+		self.grpc_client = grpc_client;
+		self.light_client = light_client;
+		self.rpc_url = endpoint.rpc_url;
+		self.grpc_url = endpoint.grpc_url;
+		self.websocket_url = endpoint.websocket_url;
+	}
+
 	pub fn grpc_url(&self) -> Url {
 		self.grpc_url.clone().expect("grpc url is not set")
 	}
@@ -413,10 +521,9 @@ where
 	pub async fn fetch_light_block_with_cache(
 		&self,
 		height: TmHeight,
-		sleep_duration: Duration,
 	) -> Result<LightBlock, Error> {
 		let fut = async move {
-			sleep(sleep_duration).await;
+			self.common_state.rate_limiter.acquire().await;
 			self.light_client.io.fetch_light_block(AtHeight::At(height)).map_err(|e| {
 				Error::from(format!(
 					"Failed to fetch light block for chain {:?} with error {:?}",
@@ -437,17 +544,14 @@ where
 		let mut xs = Vec::new();
 		let heightss = (from.value()..=to.value()).collect::<Vec<_>>();
 		let client = Arc::new(self.clone());
-		let delay_to = self.rpc_call_delay().as_millis();
 		for heights in heightss.chunks(5) {
 			let mut join_set = JoinSet::<Result<Result<_, Error>, Elapsed>>::new();
 			for height in heights.to_owned() {
 				let client = client.clone();
-				let duration =
-					Duration::from_millis(rand::thread_rng().gen_range(0..delay_to) as u64);
 				let fut = async move {
 					log::trace!(target: "hyperspace_cosmos", "Fetching header at height {:?}", height);
 					let latest_light_block =
-						client.fetch_light_block_with_cache(height.try_into()?, duration).await?;
+						client.fetch_light_block_with_cache(height.try_into()?).await?;
 
 					let height =
 						TmHeight::try_from(trusted_height.revision_height).map_err(|e| {
@@ -458,7 +562,7 @@ where
 						})?;
 
 					let trusted_light_block =
-						client.fetch_light_block_with_cache(height.increment(), duration).await?;
+						client.fetch_light_block_with_cache(height.increment()).await?;
 
 					let update_type = match is_validators_equal(
 						&latest_light_block.validators,
@@ -492,6 +596,7 @@ where
 
 	/// Uses the GRPC client to retrieve the account sequence
 	pub async fn query_account(&self) -> Result<BaseAccount, Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let mut client = QueryClient::connect(self.grpc_url().to_string())
 			.await
 			.map_err(|e| Error::from(format!("GRPC client error: {:?}", e)))?;
@@ -502,11 +607,10 @@ where
 		let response = client.account(request).await;
 
 		// Querying for an account might fail, i.e. if the account doesn't actually exist
-		let resp_account =
-			match response.map_err(|e| Error::from(format!("{:?}", e)))?.into_inner().account {
-				Some(account) => account,
-				None => return Err(Error::from(format!("Account not found"))),
-			};
+		let resp_account = match response?.into_inner().account {
+			Some(account) => account,
+			None => return Err(Error::from(format!("Account not found"))),
+		};
 
 		Ok(BaseAccount::decode(resp_account.value.as_slice())
 			.map_err(|e| Error::from(format!("Failed to decode account {}", e)))?)
@@ -527,14 +631,12 @@ where
 			_ => Some(height),
 		};
 
+		self.common_state.rate_limiter.acquire().await;
 		// Use the Tendermint-rs RPC client to do the query.
 		let response = self
 			.rpc_http_client
 			.abci_query(Some(path.to_owned()), data.clone(), height, prove)
-			.await
-			.map_err(|e| {
-				Error::from(format!("Failed to query chain {} with error {:?}", self.name, e))
-			})?;
+			.await?;
 
 		if !response.code.is_ok() {
 			// Fail with response log.
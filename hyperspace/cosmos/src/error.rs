@@ -1,5 +1,8 @@
 use ibc::timestamp::ParseTimestampError;
+use primitives::rate_limit::RpcErrorKind;
 use prost::DecodeError;
+use tendermint_rpc::error::ErrorDetail;
+use tonic::Code;
 
 /// Error definitions for the cosmos client in accordance with the parachain's Error type.
 #[derive(thiserror::Error, Debug)]
@@ -7,6 +10,12 @@ pub enum Error {
 	/// An error from the rpc interface
 	#[error("Rpc client error: {0}")]
 	RpcError(String),
+	/// An error returned by a tendermint rpc endpoint
+	#[error("Tendermint rpc error: {0}")]
+	TendermintRpcError(#[from] tendermint_rpc::Error),
+	/// An error status returned by a gRPC endpoint
+	#[error("gRPC error: {0}")]
+	GrpcError(#[from] tonic::Status),
 	/// Custom error
 	#[error("{0}")]
 	Custom(String),
@@ -32,3 +41,53 @@ impl From<String> for Error {
 		Self::Custom(error)
 	}
 }
+
+impl Error {
+	/// Classifies errors that came back from an rpc or gRPC endpoint, `None` for everything
+	/// else.
+	pub fn rpc_error_kind(&self) -> Option<RpcErrorKind> {
+		match self {
+			Error::TendermintRpcError(e) => Some(tendermint_rpc_error_kind(e)),
+			Error::GrpcError(status) => Some(grpc_error_kind(status)),
+			Error::RpcError(_) => Some(RpcErrorKind::Other),
+			_ => None,
+		}
+	}
+}
+
+/// Maps a tendermint rpc error to the way the relayer should react to it: HTTP 429 means we
+/// are being throttled, server side failures and a dead transport mean the connection is gone.
+pub fn tendermint_rpc_error_kind(error: &tendermint_rpc::Error) -> RpcErrorKind {
+	match error.detail() {
+		ErrorDetail::HttpRequestFailed(e) if e.status.as_u16() == 429 => RpcErrorKind::RateLimited,
+		ErrorDetail::HttpRequestFailed(e) if e.status.is_server_error() =>
+			RpcErrorKind::ConnectionLost,
+		ErrorDetail::Hyper(_) | ErrorDetail::ChannelSend(_) | ErrorDetail::WebSocket(_) =>
+			RpcErrorKind::ConnectionLost,
+		_ => RpcErrorKind::Other,
+	}
+}
+
+/// Maps a gRPC status to the way the relayer should react to it.
+pub fn grpc_error_kind(status: &tonic::Status) -> RpcErrorKind {
+	match status.code() {
+		Code::ResourceExhausted => RpcErrorKind::RateLimited,
+		Code::Unavailable => RpcErrorKind::ConnectionLost,
+		_ => RpcErrorKind::Other,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn grpc_errors_are_classified_by_status_code() {
+		let kind = |code| Error::from(tonic::Status::new(code, "")).rpc_error_kind();
+		assert_eq!(kind(Code::ResourceExhausted), Some(RpcErrorKind::RateLimited));
+		assert_eq!(kind(Code::Unavailable), Some(RpcErrorKind::ConnectionLost));
+		assert_eq!(kind(Code::NotFound), Some(RpcErrorKind::Other));
+		// a message that merely mentions the code is not enough
+		assert_eq!(Error::from("status: ResourceExhausted".to_string()).rpc_error_kind(), None);
+	}
+}
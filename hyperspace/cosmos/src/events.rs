@@ -81,6 +81,15 @@ pub fn event_is_type_channel(ev: &IbcEvent) -> bool {
 			IbcEvent::OpenConfirmChannel(_) |
 			IbcEvent::CloseInitChannel(_) |
 			IbcEvent::CloseConfirmChannel(_) |
+			IbcEvent::UpgradeInitChannel(_) |
+			IbcEvent::UpgradeTryChannel(_) |
+			IbcEvent::UpgradeAckChannel(_) |
+			IbcEvent::UpgradeConfirmChannel(_) |
+			IbcEvent::UpgradeOpenChannel(_) |
+			IbcEvent::UpgradeTimeoutChannel(_) |
+			IbcEvent::UpgradeCancelChannel(_) |
+			IbcEvent::UpgradeErrorChannel(_) |
+			IbcEvent::FlushCompleteChannel(_) |
 			IbcEvent::SendPacket(_) |
 			IbcEvent::ReceivePacket(_) |
 			IbcEvent::WriteAcknowledgement(_) |
@@ -151,6 +160,22 @@ pub fn ibc_event_try_from_abci_event(
 			channel_close_confirm_try_from_abci_event(abci_event)
 				.map_err(IbcEventError::channel)?,
 		)),
+		Ok(
+			IbcEventType::UpgradeInitChannel |
+			IbcEventType::UpgradeTryChannel |
+			IbcEventType::UpgradeAckChannel |
+			IbcEventType::UpgradeConfirmChannel |
+			IbcEventType::UpgradeOpenChannel |
+			IbcEventType::UpgradeTimeoutChannel |
+			IbcEventType::UpgradeCancelChannel |
+			IbcEventType::UpgradeErrorChannel |
+			IbcEventType::FlushCompleteChannel,
+		) => channel_events::try_from_tx(abci_event)
+			.map(|mut event| {
+				event.set_height(height);
+				event
+			})
+			.ok_or_else(|| IbcEventError::unsupported_abci_event(abci_event.kind.to_owned())),
 		Ok(IbcEventType::SendPacket) => Ok(IbcEvent::SendPacket(
 			send_packet_try_from_abci_event(abci_event, height).map_err(IbcEventError::channel)?,
 		)),
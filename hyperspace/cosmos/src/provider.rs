@@ -11,7 +11,10 @@ use futures::{
 	Stream, StreamExt,
 };
 use ibc::{
-	applications::transfer::{Amount, BaseDenom, PrefixedCoin, PrefixedDenom, TracePath},
+	applications::{
+		fee::msgs::register_counterparty_payee::MsgRegisterCounterpartyPayee,
+		transfer::{Amount, BaseDenom, PrefixedCoin, PrefixedDenom, TracePath},
+	},
 	core::{
 		ics02_client::{
 			client_state::ClientType, events as ClientEvents,
@@ -22,8 +25,9 @@ use ibc::{
 		ics24_host::{
 			identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
 			path::{
-				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
-				CommitmentsPath, ConnectionsPath, Path, ReceiptsPath, SeqRecvsPath, SeqSendsPath,
+				AcksPath, ChannelEndsPath, ChannelUpgradePath, ClientConsensusStatePath,
+				ClientStatePath, CommitmentsPath, ConnectionsPath, Path, ReceiptsPath,
+				SeqRecvsPath, SeqSendsPath,
 			},
 		},
 	},
@@ -38,19 +42,25 @@ use ibc_primitives::PacketInfo as IbcPacketInfo;
 use ibc_proto::{
 	cosmos::{bank::v1beta1::QueryBalanceRequest, base::query::v1beta1::PageRequest},
 	google::protobuf::Any,
-	ibc::core::{
-		channel::v1::{
-			Channel, QueryChannelResponse, QueryChannelsRequest, QueryChannelsResponse,
-			QueryConnectionChannelsRequest, QueryNextSequenceReceiveResponse,
-			QueryPacketAcknowledgementResponse, QueryPacketAcknowledgementsRequest,
-			QueryPacketCommitmentResponse, QueryPacketCommitmentsRequest,
-			QueryPacketReceiptResponse, QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
-		},
-		client::v1::{
-			QueryClientStateResponse, QueryClientStatesRequest, QueryConsensusStateResponse,
-		},
-		connection::v1::{
-			ConnectionEnd, IdentifiedConnection, QueryConnectionResponse, QueryConnectionsRequest,
+	ibc::{
+		applications::fee::v1::QueryIncentivizedPacketsForChannelRequest,
+		core::{
+			channel::v1::{
+				Channel, ErrorReceipt, QueryChannelResponse, QueryChannelsRequest,
+				QueryChannelsResponse, QueryConnectionChannelsRequest,
+				QueryNextSequenceReceiveResponse, QueryPacketAcknowledgementResponse,
+				QueryPacketAcknowledgementsRequest, QueryPacketCommitmentResponse,
+				QueryPacketCommitmentsRequest, QueryPacketReceiptResponse,
+				QueryUnreceivedAcksRequest, QueryUnreceivedPacketsRequest,
+				QueryUpgradeErrorResponse, QueryUpgradeResponse, Upgrade,
+			},
+			client::v1::{
+				QueryClientStateResponse, QueryClientStatesRequest, QueryConsensusStateResponse,
+			},
+			connection::v1::{
+				ConnectionEnd, IdentifiedConnection, QueryConnectionResponse,
+				QueryConnectionsRequest,
+			},
 		},
 	},
 };
@@ -63,10 +73,10 @@ use pallet_ibc::light_clients::{
 	AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager,
 };
 use primitives::{
-	filter_events_by_ids, mock::LocalClientTypes, Chain, IbcProvider, KeyProvider, UpdateType,
+	fees::PacketFees, filter_events_by_ids, mock::LocalClientTypes, Chain, IbcProvider,
+	KeyProvider, UpdateType,
 };
 use prost::Message;
-use rand::Rng;
 use std::{
 	collections::{hash_map::Entry, HashMap, HashSet},
 	pin::Pin,
@@ -146,15 +156,12 @@ where
 		let mut block_events = Vec::new();
 		let mut join_set: JoinSet<Result<_, anyhow::Error>> = JoinSet::new();
 		let range = (from.value()..to.value()).collect::<Vec<_>>();
-		let to = self.rpc_call_delay().as_millis();
 		for heights in range.chunks(100) {
 			for height in heights.iter().copied() {
 				log::trace!(target: "hyperspace_cosmos", "Parsing events at height {:?}", height);
 				let client = self.clone();
-				let duration = Duration::from_millis(rand::thread_rng().gen_range(0..to) as u64);
 				let counterparty = counterparty.clone();
 				join_set.spawn(async move {
-					sleep(duration).await;
 					let xs = tokio::time::timeout(
 						Duration::from_secs(30),
 						client.parse_ibc_events_at(&counterparty, latest_revision, height),
@@ -207,6 +214,40 @@ where
 		Ok(updates)
 	}
 
+	async fn query_ibc_events_in_range<C>(
+		&self,
+		from: u64,
+		to: u64,
+		counterparty: &C,
+	) -> Result<Vec<IbcEvent>, anyhow::Error>
+	where
+		C: Chain,
+	{
+		let latest_revision = self.latest_height_and_timestamp().await?.0.revision_number;
+		let mut block_events = Vec::new();
+		let mut join_set: JoinSet<Result<_, anyhow::Error>> = JoinSet::new();
+		let range = (from..=to).collect::<Vec<_>>();
+		for heights in range.chunks(100) {
+			for height in heights.iter().copied() {
+				let client = self.clone();
+				let counterparty = counterparty.clone();
+				join_set.spawn(async move {
+					let xs = tokio::time::timeout(
+						Duration::from_secs(30),
+						client.parse_ibc_events_at(&counterparty, latest_revision, height),
+					)
+					.await??;
+					Ok((height, xs))
+				});
+			}
+			while let Some(res) = join_set.join_next().await {
+				block_events.push(res??);
+			}
+		}
+		block_events.sort_by_key(|(height, _)| *height);
+		Ok(block_events.into_iter().flat_map(|(_, events)| events).collect())
+	}
+
 	// TODO: Changed result: `Item =` from `IbcEvent` to `IbcEventWithHeight` to include the
 	// necessary height field, as `height` is removed from `Attribute` from ibc-rs v0.22.0
 	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
@@ -366,6 +407,43 @@ where
 		})
 	}
 
+	async fn query_channel_upgrade(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeResponse, Self::Error> {
+		let path_bytes = Path::ChannelUpgrade(ChannelUpgradePath::Upgrade(port_id, channel_id))
+			.to_string()
+			.into_bytes();
+		let (q, proof) = self.query_path(path_bytes.clone(), at, true).await?;
+		let upgrade = Upgrade::decode(&*q.value)?;
+		Ok(QueryUpgradeResponse {
+			upgrade: Some(upgrade),
+			proof,
+			proof_height: increment_proof_height(Some(at.into())),
+		})
+	}
+
+	async fn query_channel_upgrade_error(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeErrorResponse, Self::Error> {
+		let path_bytes =
+			Path::ChannelUpgrade(ChannelUpgradePath::UpgradeError(port_id, channel_id))
+				.to_string()
+				.into_bytes();
+		let (q, proof) = self.query_path(path_bytes.clone(), at, true).await?;
+		let error_receipt = ErrorReceipt::decode(&*q.value)?;
+		Ok(QueryUpgradeErrorResponse {
+			error_receipt: Some(error_receipt),
+			proof,
+			proof_height: increment_proof_height(Some(at.into())),
+		})
+	}
+
 	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
 		let (_, proof) = self.query_path(keys[0].clone(), at, true).await?;
 		Ok(proof)
@@ -465,25 +543,18 @@ where
 		// We cannot rely on `/status` endpoint to provide details about the latest block.
 		// Instead, we need to pull block height via `/abci_info` and then fetch block
 		// metadata at the given height via `/blockchain` endpoint.
-		let abci_info = self
-			.rpc_http_client
-			.abci_info()
-			.await
-			.map_err(|e| Error::RpcError(format!("{e:?}")))?;
+		self.common_state.rate_limiter.acquire().await;
+		let abci_info = self.rpc_http_client.abci_info().await?;
 
 		// Query `/blockchain` endpoint to pull the block metadata corresponding to
 		// the latest block that the application committed.
 		// TODO: Replace this query with `/header`, once it's available.
 		//  https://github.com/informalsystems/tendermint-rs/pull/1101
+		self.common_state.rate_limiter.acquire().await;
 		let blocks = self
 			.rpc_http_client
 			.blockchain(abci_info.last_block_height, abci_info.last_block_height)
-			.await
-			.map_err(|e| {
-				Error::RpcError(format!(
-					"failed to query /blockchain endpoint for latest app. block: {e:?}"
-				))
-			})?;
+			.await?;
 
 		let latest_app_block = blocks.block_metas.first().ok_or_else(|| {
 			Error::Custom("/blockchain endpoint for latest app. block".to_owned())
@@ -503,6 +574,7 @@ where
 		channel_id: ChannelId,
 		port_id: PortId,
 	) -> Result<Vec<u64>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let mut grpc_client =
 			ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
 				self.grpc_url().to_string(),
@@ -516,11 +588,7 @@ where
 			pagination: Some(PageRequest { limit: u32::MAX as _, ..Default::default() }),
 		};
 		let request = tonic::Request::new(request);
-		let response = grpc_client
-			.packet_commitments(request)
-			.await
-			.map_err(|e| Error::from(e.to_string()))?
-			.into_inner();
+		let response = grpc_client.packet_commitments(request).await?.into_inner();
 
 		let commitment_sequences: Vec<u64> =
 			response.commitments.into_iter().map(|v| v.sequence).collect();
@@ -539,6 +607,7 @@ where
 			channel_id,
 			port_id
 		);
+		self.common_state.rate_limiter.acquire().await;
 		let mut grpc_client =
 			ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
 				self.grpc_url().to_string(),
@@ -553,11 +622,7 @@ where
 			pagination: Some(PageRequest { limit: u32::MAX as _, ..Default::default() }),
 		};
 		let request = tonic::Request::new(request);
-		let response = grpc_client
-			.packet_acknowledgements(request)
-			.await
-			.map_err(|e| Error::from(e.to_string()))?
-			.into_inner();
+		let response = grpc_client.packet_acknowledgements(request).await?.into_inner();
 
 		let commitment_sequences: Vec<u64> =
 			response.acknowledgements.into_iter().map(|v| v.sequence).collect();
@@ -572,6 +637,7 @@ where
 		port_id: PortId,
 		seqs: Vec<u64>,
 	) -> Result<Vec<u64>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let mut grpc_client =
 			ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
 				self.grpc_url().to_string(),
@@ -585,11 +651,7 @@ where
 			packet_commitment_sequences: seqs,
 		};
 		let request = tonic::Request::new(request);
-		let response = grpc_client
-			.unreceived_packets(request)
-			.await
-			.map_err(|e| Error::from(e.to_string()))?
-			.into_inner();
+		let response = grpc_client.unreceived_packets(request).await?.into_inner();
 
 		let commitment_sequences: Vec<u64> = response.sequences.into_iter().collect();
 
@@ -603,6 +665,7 @@ where
 		port_id: PortId,
 		seqs: Vec<u64>,
 	) -> Result<Vec<u64>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let mut grpc_client =
 			ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
 				self.grpc_url().to_string(),
@@ -616,11 +679,7 @@ where
 			packet_ack_sequences: seqs,
 		};
 		let request = tonic::Request::new(request);
-		let response = grpc_client
-			.unreceived_acks(request)
-			.await
-			.map_err(|e| Error::from(e.to_string()))?
-			.into_inner();
+		let response = grpc_client.unreceived_acks(request).await?.into_inner();
 
 		let commitment_sequences: Vec<u64> = response.sequences.into_iter().collect();
 
@@ -636,6 +695,7 @@ where
 		_at: Height,
 		connection_id: &ConnectionId,
 	) -> Result<QueryChannelsResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let mut grpc_client =
 			ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
 				self.grpc_url().to_string(),
@@ -647,11 +707,7 @@ where
 			pagination: Some(PageRequest { limit: u32::MAX as _, ..Default::default() }),
 		});
 
-		let response = grpc_client
-			.connection_channels(request)
-			.await
-			.map_err(|e| Error::from(format!("{e:?}")))?
-			.into_inner();
+		let response = grpc_client.connection_channels(request).await?.into_inner();
 		let channels = QueryChannelsResponse {
 			channels: response.channels,
 			pagination: response.pagination,
@@ -681,6 +737,7 @@ where
 				.and_eq("send_packet.packet_src_port", port_id.to_string())
 				.and_eq("send_packet.packet_sequence", seq.to_string());
 
+			self.common_state.rate_limiter.acquire().await;
 			let response = self
 				.rpc_http_client
 				.tx_search(
@@ -692,8 +749,7 @@ where
 					    * sequence number is used twice in send_packet event (in case of an
 					    * error during the message processing) */
 				)
-				.await
-				.map_err(|e| Error::RpcError(format!("{e:?}")))?;
+				.await?;
 
 			for tx in response.txs {
 				for ev in &tx.tx_result.events {
@@ -704,8 +760,8 @@ where
 					match ev {
 						Ok(IbcEvent::SendPacket(p))
 							if seqs.contains(&p.packet.sequence.0) &&
-								p.packet.source_port == port_id && p.packet.source_channel ==
-								channel_id =>
+								p.packet.source_port == port_id &&
+								p.packet.source_channel == channel_id =>
 						{
 							let seq = p.packet.sequence.0;
 							let mut info = PacketInfo::try_from(IbcPacketInfo::from(p.packet))
@@ -759,6 +815,7 @@ where
 					.and_eq("write_acknowledgement.packet_dst_port", port_id.to_string())
 					.and_eq("write_acknowledgement.packet_sequence", seq.to_string());
 
+			self.common_state.rate_limiter.acquire().await;
 			let response = self
 				.rpc_http_client
 				.tx_search(
@@ -770,8 +827,7 @@ where
 					    * sequence number is used twice in write_acknowledgement event (in case
 					    * of an error during the message processing) */
 				)
-				.await
-				.map_err(|e| Error::RpcError(format!("{e:?}")))?;
+				.await?;
 
 			for tx in response.txs {
 				for ev in &tx.tx_result.events {
@@ -836,6 +892,7 @@ where
 		let query_create = Query::eq("create_client.client_id", client_id.to_string())
 			.and_eq("create_client.consensus_height", client_height.to_string());
 		for query_str in [query_update, query_create] {
+			self.common_state.rate_limiter.acquire().await;
 			let response = self
 				.rpc_http_client
 				.tx_search(
@@ -845,18 +902,14 @@ where
 					1, // get only the first Tx matching the query
 					Order::Ascending,
 				)
-				.await
-				.map_err(|e| Error::RpcError(format!("{e:?}")))?;
+				.await?;
 
 			for tx in response.txs {
 				for ev in &tx.tx_result.events {
 					let height = tx.height.value();
 					let ev =
 						ibc_event_try_from_abci_event(ev, Height::new(self.id().version(), height));
-					let timestamp = self
-						.query_timestamp_at(height)
-						.await
-						.map_err(|e| Error::RpcError(format!("{e:?}")))?;
+					let timestamp = self.query_timestamp_at(height).await?;
 					match ev {
 						Ok(IbcEvent::UpdateClient(e)) if e.client_id() == &client_id =>
 							return Ok((
@@ -888,6 +941,7 @@ where
 		asset_id: Self::AssetId,
 	) -> Result<Vec<PrefixedCoin>, Self::Error> {
 		let denom = &asset_id;
+		self.common_state.rate_limiter.acquire().await;
 		let mut grpc_client = ibc_proto::cosmos::bank::v1beta1::query_client::QueryClient::connect(
 			self.grpc_url().to_string(),
 		)
@@ -903,7 +957,7 @@ where
 			.balance(request)
 			.await
 			.map(|r| r.into_inner())
-			.map_err(|e| Error::from(format!("{e:?}")))?;
+			.map_err(Error::from)?;
 
 		// Querying for a balance might fail, i.e. if the account doesn't actually exist
 		let balance = response
@@ -955,11 +1009,8 @@ where
 	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
 		let height = TmHeight::try_from(block_number)
 			.map_err(|e| Error::from(format!("Invalid block number: {e}")))?;
-		let response = self
-			.rpc_ws_client()
-			.block(height)
-			.await
-			.map_err(|e| Error::RpcError(e.to_string()))?;
+		self.common_state.rate_limiter.acquire().await;
+		let response = self.rpc_ws_client().block(height).await?;
 		let time: Timestamp = response.block.header.time.into();
 		Ok(time.nanoseconds())
 	}
@@ -968,17 +1019,11 @@ where
 		let request = tonic::Request::new(QueryClientStatesRequest {
 			pagination: Some(PageRequest { limit: u32::MAX as _, ..Default::default() }),
 		});
+		self.common_state.rate_limiter.acquire().await;
 		let grpc_client = ibc_proto::ibc::core::client::v1::query_client::QueryClient::new(
 			self.grpc_client().clone(),
 		);
-		let response = grpc_client
-			.clone()
-			.client_states(request)
-			.await
-			.map_err(|e| {
-				Error::from(format!("Failed to query client states from grpc client: {e:?}"))
-			})?
-			.into_inner();
+		let response = grpc_client.clone().client_states(request).await?.into_inner();
 
 		// Deserialize into domain type
 		let clients: Vec<ClientId> = response
@@ -996,6 +1041,7 @@ where
 		let request = tonic::Request::new(QueryChannelsRequest {
 			pagination: Some(PageRequest { limit: u32::MAX as _, ..Default::default() }),
 		});
+		self.common_state.rate_limiter.acquire().await;
 		let mut grpc_client =
 			ibc_proto::ibc::core::channel::v1::query_client::QueryClient::connect(
 				self.grpc_url().to_string(),
@@ -1004,8 +1050,7 @@ where
 			.map_err(|e| Error::from(format!("{e:?}")))?;
 		let response = grpc_client
 			.channels(request)
-			.await
-			.map_err(|e| Error::from(format!("{e:?}")))?
+			.await?
 			.into_inner()
 			.channels
 			.into_iter()
@@ -1023,6 +1068,7 @@ where
 		_height: u32,
 		client_id: String,
 	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let mut grpc_client =
 			ibc_proto::ibc::core::connection::v1::query_client::QueryClient::connect(
 				self.grpc_url().to_string(),
@@ -1034,11 +1080,7 @@ where
 			pagination: Some(PageRequest { limit: u32::MAX as _, ..Default::default() }),
 		});
 
-		let response = grpc_client
-			.connections(request)
-			.await
-			.map_err(|e| Error::from(format!("{e:?}")))?
-			.into_inner();
+		let response = grpc_client.connections(request).await?.into_inner();
 
 		let connections = response
 			.connections
@@ -1097,6 +1139,7 @@ where
 		let start_time = std::time::Instant::now();
 
 		let response: Response = loop {
+			self.common_state.rate_limiter.acquire().await;
 			let response = self
 				.rpc_ws_client()
 				.tx_search(
@@ -1106,8 +1149,7 @@ where
 					1, // get only the first Tx matching the query
 					Order::Ascending,
 				)
-				.await
-				.map_err(|e| Error::from(format!("Failed to query tx hash: {e}")))?;
+				.await?;
 			match response.txs.into_iter().next() {
 				None => {
 					let elapsed = start_time.elapsed();
@@ -1164,6 +1206,7 @@ where
 		let start_time = std::time::Instant::now();
 
 		let response: Response = loop {
+			self.common_state.rate_limiter.acquire().await;
 			let response = self
 				.rpc_ws_client()
 				.tx_search(
@@ -1173,8 +1216,7 @@ where
 					1, // get only the first Tx matching the query
 					Order::Ascending,
 				)
-				.await
-				.map_err(|e| Error::from(format!("Failed to query tx hash: {e}")))?;
+				.await?;
 			match response.txs.into_iter().next() {
 				None => {
 					let elapsed = start_time.elapsed();
@@ -1232,6 +1274,7 @@ where
 		let start_time = std::time::Instant::now();
 
 		let response: Response = loop {
+			self.common_state.rate_limiter.acquire().await;
 			let response = self
 				.rpc_ws_client()
 				.tx_search(
@@ -1241,8 +1284,7 @@ where
 					1, // get only the first Tx matching the query
 					Order::Ascending,
 				)
-				.await
-				.map_err(|e| Error::from(format!("Failed to query tx hash: {e}")))?;
+				.await?;
 			match response.txs.into_iter().next() {
 				None => {
 					let elapsed = start_time.elapsed();
@@ -1331,6 +1373,56 @@ where
 
 		Ok(code_id)
 	}
+
+	async fn query_incentivized_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<HashMap<u64, PacketFees>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
+		let mut grpc_client =
+			ibc_proto::ibc::applications::fee::v1::query_client::QueryClient::connect(
+				self.grpc_url().to_string(),
+			)
+			.await
+			.map_err(|e| Error::from(e.to_string()))?;
+
+		let request = QueryIncentivizedPacketsForChannelRequest {
+			pagination: Some(PageRequest { limit: u32::MAX as _, ..Default::default() }),
+			port_id: port_id.to_string(),
+			channel_id: channel_id.to_string(),
+			query_height: 0,
+		};
+		let response = grpc_client
+			.incentivized_packets_for_channel(tonic::Request::new(request))
+			.await?
+			.into_inner();
+
+		response
+			.incentivized_packets
+			.into_iter()
+			.map(|packet| {
+				<(u64, PacketFees)>::try_from(packet).map_err(|e| Error::from(e.to_string()))
+			})
+			.collect()
+	}
+
+	async fn register_counterparty_payee(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		counterparty_payee: Signer,
+	) -> Result<(), Self::Error> {
+		let msg = MsgRegisterCounterpartyPayee {
+			port_id,
+			channel_id,
+			relayer: self.account_id(),
+			counterparty_payee: counterparty_payee.to_string(),
+		};
+		let hash = self.submit(vec![msg.to_any()]).await?;
+		self.wait_for_tx_result(hash).await?;
+		Ok(())
+	}
 }
 
 impl<H> CosmosClient<H>
@@ -1345,13 +1437,8 @@ where
 	) -> Result<Vec<IbcEvent>, <Self as IbcProvider>::Error> {
 		let mut ibc_events = Vec::new();
 
-		let block_results = self
-			.rpc_http_client
-			.block_results(TmHeight::try_from(height)?)
-			.await
-			.map_err(|e| {
-			Error::from(format!("Failed to query block result for height {height:?}: {e:?}"))
-		})?;
+		self.common_state.rate_limiter.acquire().await;
+		let block_results = self.rpc_http_client.block_results(TmHeight::try_from(height)?).await?;
 
 		let tx_events = block_results
 			.txs_results
@@ -1425,6 +1512,7 @@ impl<H: Clone + Send + Sync + 'static> CosmosClient<H> {
 		let start_time = std::time::Instant::now();
 
 		let response: Response = loop {
+			self.common_state.rate_limiter.acquire().await;
 			let response = self
 				.rpc_http_client
 				.tx_search(
@@ -1434,8 +1522,7 @@ impl<H: Clone + Send + Sync + 'static> CosmosClient<H> {
 					1, // get only the first Tx matching the query
 					Order::Ascending,
 				)
-				.await
-				.map_err(|e| Error::from(format!("Failed to query tx hash: {e}")))?;
+				.await?;
 			match response.txs.into_iter().next() {
 				None => {
 					let elapsed = start_time.elapsed();
@@ -74,7 +74,7 @@ pub async fn simulate_tx(
 
 	let response = tokio::time::timeout(
 		Duration::from_secs(15),
-		client.simulate(request).map_err(|e| Error::from(e.to_string())),
+		client.simulate(request).map_err(Error::from),
 	)
 	.await
 	.map_err(|_| Error::from("simulation timeout".to_string()))??
@@ -83,10 +83,7 @@ pub async fn simulate_tx(
 }
 
 pub async fn broadcast_tx(rpc_client: &WebSocketClient, tx_bytes: Vec<u8>) -> Result<Hash, Error> {
-	let response = rpc_client
-		.broadcast_tx_sync(tx_bytes)
-		.await
-		.map_err(|e| Error::from(format!("failed to broadcast transaction {e:?}")))?;
+	let response = rpc_client.broadcast_tx_sync(tx_bytes).await?;
 	Ok(response.hash)
 }
 
@@ -103,8 +100,7 @@ pub async fn confirm_tx(rpc_client: &WebSocketClient, tx_hash: Hash) -> Result<H
 				1, // get only the first Tx matching the query
 				Order::Ascending,
 			)
-			.await
-			.map_err(|e| Error::from(format!("failed to search for transaction {e:?}")))?;
+			.await?;
 		match response.txs.into_iter().next() {
 			None => {
 				let elapsed = start_time.elapsed();
@@ -144,6 +144,26 @@ pub struct Metrics {
 	/// Latest processed height - helpful to prevent pushing the same event twice
 	pub latest_processed_height: Gauge<U64>,
 
+	/// Total number of RPC requests that went through the rate limiter.
+	pub number_of_rpc_requests: Counter<U64>,
+	/// Total number of RPC requests that had to wait for the rate limiter.
+	pub number_of_delayed_rpc_requests: Counter<U64>,
+	/// Total number of responses indicating that the endpoint rate limited the relayer.
+	pub number_of_rate_limited_rpc_responses: Counter<U64>,
+	/// Total time (in milliseconds) RPC requests spent waiting for the rate limiter.
+	pub rpc_rate_limiter_wait_time: Counter<U64>,
+	/// Fraction of the rate limiter's burst capacity in use.
+	pub rpc_rate_limiter_saturation: Gauge<F64>,
+	/// Current rate (in requests per second) allowed by the rate limiter.
+	pub rpc_rate_limiter_rate: Gauge<F64>,
+
+	/// Total number of queries served from the query cache.
+	pub number_of_query_cache_hits: Counter<U64>,
+	/// Total number of queries that missed the query cache and were sent to the chain.
+	pub number_of_query_cache_misses: Counter<U64>,
+	/// Number of query results currently held in the query cache.
+	pub query_cache_entries: Gauge<U64>,
+
 	/// Metrics prefix.
 	pub prefix: String,
 }
@@ -353,6 +373,96 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			number_of_rpc_requests: register(
+				Counter::with_opts(
+					Opts::new(
+						"hyperspace_number_of_rpc_requests".to_string(),
+						"Total number of RPC requests that went through the rate limiter",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
+			number_of_delayed_rpc_requests: register(
+				Counter::with_opts(
+					Opts::new(
+						"hyperspace_number_of_delayed_rpc_requests".to_string(),
+						"Total number of RPC requests that had to wait for the rate limiter",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
+			number_of_rate_limited_rpc_responses: register(
+				Counter::with_opts(
+					Opts::new(
+						"hyperspace_number_of_rate_limited_rpc_responses".to_string(),
+						"Total number of responses indicating that the endpoint rate limited the relayer",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
+			rpc_rate_limiter_wait_time: register(
+				Counter::with_opts(
+					Opts::new(
+						"hyperspace_rpc_rate_limiter_wait_time".to_string(),
+						"Total time in milliseconds RPC requests spent waiting for the rate limiter",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
+			rpc_rate_limiter_saturation: register(
+				Gauge::with_opts(
+					Opts::new(
+						"hyperspace_rpc_rate_limiter_saturation".to_string(),
+						"Fraction of the rate limiter burst capacity in use",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
+			rpc_rate_limiter_rate: register(
+				Gauge::with_opts(
+					Opts::new(
+						"hyperspace_rpc_rate_limiter_rate".to_string(),
+						"Current number of requests per second allowed by the rate limiter",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
+			number_of_query_cache_hits: register(
+				Counter::with_opts(
+					Opts::new(
+						"hyperspace_number_of_query_cache_hits".to_string(),
+						"Total number of queries served from the query cache",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
+			number_of_query_cache_misses: register(
+				Counter::with_opts(
+					Opts::new(
+						"hyperspace_number_of_query_cache_misses".to_string(),
+						"Total number of queries that missed the query cache",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
+			query_cache_entries: register(
+				Gauge::with_opts(
+					Opts::new(
+						"hyperspace_query_cache_entries".to_string(),
+						"Number of query results held in the query cache",
+					)
+					.const_label("name", prefix.to_string()),
+				)?,
+				registry,
+			)?,
 			prefix: prefix.to_string(),
 		})
 	}
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::data::Metrics;
+use crate::{data::Metrics, Counter, U64};
 use ibc::{
 	core::{
 		ics04_channel::{
@@ -29,7 +29,7 @@ use std::{
 	collections::HashMap,
 	ops::DerefMut,
 	sync::{Arc, Mutex},
-	time::Instant,
+	time::{Duration, Instant},
 };
 
 #[derive(Eq, PartialEq, Hash)]
@@ -227,6 +227,36 @@ impl MetricsHandler {
 		self.metrics.transaction_length_for_sent_tx_bundle.observe(batch_size as f64);
 	}
 
+	/// Updates the rate limiter metrics from the limiter's cumulative counters.
+	pub fn handle_rate_limiter_stats(
+		&self,
+		requests: u64,
+		delayed_requests: u64,
+		rate_limited_responses: u64,
+		wait_time: Duration,
+		saturation: f64,
+		rate: f64,
+	) {
+		let inc_to = |counter: &Counter<U64>, value: u64| {
+			counter.inc_by(value.saturating_sub(counter.get()));
+		};
+		inc_to(&self.metrics.number_of_rpc_requests, requests);
+		inc_to(&self.metrics.number_of_delayed_rpc_requests, delayed_requests);
+		inc_to(&self.metrics.number_of_rate_limited_rpc_responses, rate_limited_responses);
+		inc_to(&self.metrics.rpc_rate_limiter_wait_time, wait_time.as_millis() as u64);
+		self.metrics.rpc_rate_limiter_saturation.set(saturation);
+		self.metrics.rpc_rate_limiter_rate.set(rate);
+	}
+
+	pub fn handle_query_cache_stats(&self, hits: u64, misses: u64, entries: u64) {
+		let inc_to = |counter: &Counter<U64>, value: u64| {
+			counter.inc_by(value.saturating_sub(counter.get()));
+		};
+		inc_to(&self.metrics.number_of_query_cache_hits, hits);
+		inc_to(&self.metrics.number_of_query_cache_misses, misses);
+		self.metrics.query_cache_entries.set(entries);
+	}
+
 	pub fn observe_last_packet_time(
 		&self,
 		packet: &Packet,
@@ -1,83 +1,146 @@
-// Copyright 2022 ComposableFi
-//
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-//
-//      http://www.apache.org/licenses/LICENSE-2.0
-//
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
-
+use super::{
+	client::{NearClient, NearConnection, MAX_GAS},
+	provider::{FinalityEvent, TransactionId},
+};
+use crate::error::Error;
+use futures::{Stream, StreamExt};
+use ibc::{core::ics02_client::events::UpdateClient, events::IbcEvent, Height};
+use ibc_proto::google::protobuf::Any;
+use pallet_ibc::light_clients::AnyClientMessage;
+use primitives::{
+	rate_limit::RpcErrorKind, Chain, CommonClientState, IbcProvider, LightClientSync,
+	MisbehaviourHandler,
+};
+use prost::Message;
 use std::pin::Pin;
 
-use futures::Stream;
-use ibc::core::ics03_connection::msgs::{conn_open_ack, conn_open_init};
-use ibc_proto::google::protobuf::Any;
-use near_primitives::{hash::CryptoHash, transaction::Transaction};
-use primitives::{Chain, IbcProvider};
-use tokio_stream::wrappers::ReceiverStream;
+#[async_trait::async_trait]
+impl LightClientSync for NearClient {
+	async fn is_synced<C: Chain>(&self, _counterparty: &C) -> Result<bool, anyhow::Error> {
+		Ok(true)
+	}
 
-use super::error::Error;
-use crate::Client;
+	async fn fetch_mandatory_updates<C: Chain>(
+		&self,
+		_counterparty: &C,
+	) -> Result<(Vec<Any>, Vec<IbcEvent>), anyhow::Error> {
+		Ok((vec![], vec![]))
+	}
+}
 
 #[async_trait::async_trait]
-impl Chain for Client {
+impl Chain for NearClient {
+	fn name(&self) -> &str {
+		&self.name
+	}
+
+	fn block_max_weight(&self) -> u64 {
+		MAX_GAS
+	}
+
+	async fn estimate_weight(&self, messages: Vec<Any>) -> Result<u64, Self::Error> {
+		Ok(self.gas_per_message * messages.len() as u64)
+	}
+
 	async fn finality_notifications(
 		&self,
-	) -> Pin<Box<dyn Stream<Item = <Self as IbcProvider>::FinalityEvent> + Send + Sync>> {
-		let stream = self.indexer.streamer();
-		Box::pin(ReceiverStream::new(stream))
+	) -> Result<
+		Pin<Box<dyn Stream<Item = <Self as IbcProvider>::FinalityEvent> + Send + Sync>>,
+		Error,
+	> {
+		log::info!(target: "hyperspace_near", "🛰️ Polling {} for finality notifications", self.name);
+		let stream = self.final_heights().map(|height| FinalityEvent::Near { height });
+		Ok(Box::pin(stream))
 	}
 
-	async fn submit(&self, mut messages: Vec<Any>) -> Result<(), Error> {
-		let update_client_message = messages.remove(0);
-		let mut permissioned_messages = vec![];
-		let mut non_permissioned_messages = vec![];
+	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Error> {
+		let outcome = self.submit_call(messages).await?;
+		log::debug!(target: "hyperspace_near", "Submitted. Tx hash: {}", outcome.transaction.hash);
+		Ok(TransactionId { hash: outcome.transaction.hash, sender: outcome.transaction.signer_id })
+	}
 
-		for msg in messages {
-			if matches!(msg.type_url.as_str(), conn_open_init::TYPE_URL | conn_open_ack::TYPE_URL) {
-				permissioned_messages.push(msg)
-			} else {
-				non_permissioned_messages.push(msg)
+	async fn query_client_message(
+		&self,
+		update: UpdateClient,
+	) -> Result<AnyClientMessage, Self::Error> {
+		// the contract sets the header of the event to the `Any` encoded client message
+		let header = update
+			.header
+			.as_ref()
+			.ok_or_else(|| Error::from(format!("Missing header in update client: {update:?}")))?;
+		AnyClientMessage::try_from(Any::decode(&**header)?)
+			.map_err(|e| Error::from(format!("Failed to decode client message: {e:?}")))
+	}
+
+	async fn get_proof_height(&self, block_height: Height) -> Height {
+		// the state root of a block commits to the state of its parent, which is what is proven
+		// at `block_height`
+		block_height
+	}
+
+	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error> {
+		let err_str = if let Some(rpc_err) = error.downcast_ref::<Error>() {
+			match rpc_err {
+				Error::RpcError(s) => {
+					// every rpc error counts against the health of the active endpoint
+					self.endpoints.record_failure();
+					s.clone()
+				},
+				_ => "".to_string(),
 			}
+		} else {
+			// include the whole chain of causes, the root cause is the one that matters
+			format!("{error:#}")
+		};
+		log::debug!(target: "hyperspace_near", "Handling error: {err_str}");
+		match RpcErrorKind::classify(&err_str) {
+			RpcErrorKind::RateLimited => self.common_state.rate_limiter.on_rate_limited(),
+			RpcErrorKind::ConnectionLost => self.fail_over().await?,
+			RpcErrorKind::Other =>
+				if !self.endpoints.is_current_healthy() {
+					log::warn!(target: "hyperspace_near", "Endpoint {} of {} is unhealthy, failing over", self.rpc_url, self.name);
+					self.fail_over().await?;
+				},
 		}
 
-		let mut messages = vec![update_client_message];
-		if !non_permissioned_messages.is_empty() {
-			messages.extend(non_permissioned_messages.into_iter())
-		}
+		Ok(())
+	}
 
-		let transaction = Transaction::new(
-			self.signer.clone(),
-			self.public_key(),
-			self.contract_id.clone(),
-			0,
-			CryptoHash::default(),
-		);
-
-		let gas = 1_000_000_000_000;
-		let deliver_tx = transaction.clone().function_call(
-			"deliver".to_owned(),
-			serde_json::to_vec(&messages)?,
-			gas,
-			0,
-		);
-		// TODO: handle intermediate receipts
-		let _ = self.send_transaction(deliver_tx).await?;
-
-		if !permissioned_messages.is_empty() {
-			let deliver_permissioned_tx = transaction.clone().function_call(
-				"deliver_permissioned".to_owned(),
-				serde_json::to_vec(&permissioned_messages)?,
-				gas,
-				0,
-			);
-			let _ = self.send_transaction(deliver_permissioned_tx).await?;
-		}
+	fn common_state(&self) -> &CommonClientState {
+		&self.common_state
+	}
+
+	fn common_state_mut(&mut self) -> &mut CommonClientState {
+		&mut self.common_state
+	}
+
+	async fn reconnect(&mut self) -> anyhow::Result<()> {
+		// penalize the active endpoint, so that a healthier one is preferred
+		self.endpoints.record_failure();
+		self.fail_over().await
+	}
+}
+
+impl NearClient {
+	/// Connects to the healthiest endpoint of the pool. The failures of the active endpoint must
+	/// have been recorded already.
+	async fn fail_over(&mut self) -> anyhow::Result<()> {
+		let (rpc_url, connection) =
+			NearConnection::establish_any(&self.endpoints, &self.contract_id).await?;
+		log::info!(target: "hyperspace_near", "Reconnected to near chain using {rpc_url}");
+		self.use_connection(rpc_url, connection);
+		self.common_state.on_reconnect();
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl MisbehaviourHandler for NearClient {
+	async fn check_for_misbehaviour<C: Chain>(
+		&self,
+		_counterparty: &C,
+		_client_message: AnyClientMessage,
+	) -> Result<(), anyhow::Error> {
 		Ok(())
 	}
 }
@@ -0,0 +1,613 @@
+use crate::error::Error;
+use borsh::BorshSerialize;
+use ibc::core::{
+	ics23_commitment::commitment::CommitmentPrefix,
+	ics24_host::identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
+};
+use ibc_proto::google::protobuf::Any;
+use ics13_near::{
+	proof::StateProof,
+	types::{
+		BlockHeaderInnerLiteView, CryptoHash as NearHash, Direction as NearDirection,
+		LightClientBlockView, MerklePathItem as NearMerklePathItem, PublicKey as NearPublicKey,
+		Signature as NearSignature, ValidatorStakeView as NearValidatorStakeView,
+		ValidatorStakeViewV1 as NearValidatorStakeViewV1,
+	},
+};
+use near_crypto::{InMemorySigner, PublicKey, SecretKey, Signature};
+use near_jsonrpc_client::{
+	errors::{JsonRpcError, JsonRpcServerError},
+	methods::{
+		block::RpcBlockRequest, broadcast_tx_commit::RpcBroadcastTxCommitRequest,
+		chunk::RpcChunkRequest, light_client_proof::RpcLightClientExecutionProofRequest,
+		next_light_client_block::RpcLightClientNextBlockRequest, query::RpcQueryRequest,
+		status::RpcStatusRequest, tx::RpcTransactionStatusRequest,
+		EXPERIMENTAL_protocol_config::RpcProtocolConfigRequest,
+		EXPERIMENTAL_validators_ordered::RpcValidatorsOrderedRequest, RpcMethod,
+	},
+	JsonRpcClient,
+};
+use near_jsonrpc_primitives::types::{
+	blocks::RpcBlockError, chunks::ChunkReference, query::QueryResponseKind,
+	transactions::TransactionInfo,
+};
+use near_primitives::{
+	hash::CryptoHash,
+	merkle::{merklize, Direction},
+	shard_layout::account_id_to_shard_id,
+	transaction::{Action, FunctionCallAction, Transaction},
+	types::{
+		AccountId, BlockHeight, BlockId, BlockReference, Finality, FunctionArgs, Gas, ShardId,
+		StoreKey, TransactionOrReceiptId,
+	},
+	views::{
+		self, validator_stake_view::ValidatorStakeView, BlockView, ChunkView,
+		ExecutionOutcomeWithIdView, ExecutionStatusView, FinalExecutionOutcomeView,
+		FinalExecutionStatus, QueryRequest,
+	},
+};
+use primitives::{
+	cache::QueryCache, endpoints::EndpointPool, rate_limit::RateLimiter, CommonClientConfig,
+	CommonClientState,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	str::FromStr,
+	sync::{Arc, Mutex},
+};
+use tokio::sync::Mutex as AsyncMutex;
+
+/// One TGas, in gas units
+pub const TGAS: Gas = 1_000_000_000_000;
+/// The maximum amount of gas that can be attached to a function call
+pub const MAX_GAS: Gas = 300 * TGAS;
+/// Number of blocks before a range of blocks in which the receipts executed in that range may
+/// have been included.
+pub const RECEIPT_EXECUTION_LOOKBACK: u64 = 3;
+
+fn default_gas_per_message() -> Gas {
+	30 * TGAS
+}
+
+/// Implements the [`crate::Chain`] trait for NEAR.
+/// This is responsible for:
+/// 1. Tracking a NEAR light client on a counter-party chain, advancing this light
+/// client state as new finality proofs are observed.
+/// 2. Submitting new IBC messages to the IBC contract on NEAR.
+#[derive(Clone)]
+pub struct NearClient {
+	/// Chain name
+	pub name: String,
+	/// Chain rpc client
+	pub rpc_client: JsonRpcClient,
+	/// Chain rpc address
+	pub rpc_url: String,
+	/// All the configured rpc urls of the chain, including the active one
+	pub endpoints: EndpointPool<String>,
+	/// Chain Id
+	pub chain_id: ChainId,
+	/// Account of the IBC contract
+	pub contract_id: AccountId,
+	/// Shard holding the IBC contract account
+	pub shard_id: ShardId,
+	/// The key that signs transactions
+	pub signer: InMemorySigner,
+	/// Light client id on counterparty chain
+	pub client_id: Arc<Mutex<Option<ClientId>>>,
+	/// Connection Id
+	pub connection_id: Arc<Mutex<Option<ConnectionId>>>,
+	/// Channels cleared for packet relay
+	pub channel_whitelist: Arc<Mutex<HashSet<(ChannelId, PortId)>>>,
+	/// Prefix of the keys of the IBC contract storage
+	pub commitment_prefix: CommitmentPrefix,
+	/// Gas attached to a transaction per delivered message
+	pub gas_per_message: Gas,
+	/// Mutex used to sequentially send transactions. This is necessary because
+	/// access key nonces are not updated until the transaction is processed.
+	pub tx_mutex: Arc<AsyncMutex<()>>,
+	/// Relayer data
+	pub common_state: CommonClientState,
+}
+
+/// config options for [`NearClient`]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NearClientConfig {
+	/// Chain name
+	pub name: String,
+	/// rpc url for near
+	pub rpc_url: String,
+	/// Rpc urls of other nodes of the same chain to fail over to when the primary one is
+	/// unavailable
+	#[serde(default)]
+	pub fallback_rpc_urls: Vec<String>,
+	/// Account of the IBC contract
+	pub contract_id: AccountId,
+	/// Account that signs transactions
+	pub signer_id: AccountId,
+	/// Secret key of the signer's access key, e.g. `ed25519:...`
+	pub secret_key: String,
+	/// Light client id on counterparty chain
+	pub client_id: Option<ClientId>,
+	/// Connection Id
+	pub connection_id: Option<ConnectionId>,
+	/// Prefix of the keys of the IBC contract storage
+	pub commitment_prefix: String,
+	/// Gas attached to a transaction per delivered message
+	#[serde(default = "default_gas_per_message")]
+	pub gas_per_message: Gas,
+	/// All the client states and headers will be wrapped in WASM ones using the WASM code ID.
+	#[serde(default)]
+	pub wasm_code_id: Option<String>,
+	/// Whitelisted channels
+	pub channel_whitelist: Vec<(ChannelId, PortId)>,
+	/// Common client config
+	#[serde(flatten)]
+	pub common: CommonClientConfig,
+	/// Skip transfer packets with the following tokens base denoms
+	pub skip_tokens_list: Option<Vec<String>>,
+}
+
+/// Client connected to a single rpc url.
+pub struct NearConnection {
+	pub rpc_client: JsonRpcClient,
+	pub chain_id: ChainId,
+	pub shard_id: ShardId,
+}
+
+impl NearConnection {
+	/// Connects to `rpc_url`, checking that the node is reachable.
+	pub async fn establish(rpc_url: &str, contract_id: &AccountId) -> Result<Self, Error> {
+		let rpc_client = JsonRpcClient::connect(rpc_url);
+		let status = rpc_client.call(RpcStatusRequest).await?;
+		let protocol_config = rpc_client
+			.call(RpcProtocolConfigRequest {
+				block_reference: BlockReference::Finality(Finality::Final),
+			})
+			.await?;
+		let shard_id = account_id_to_shard_id(contract_id, &protocol_config.shard_layout);
+		Ok(Self { rpc_client, chain_id: ChainId::from(status.chain_id), shard_id })
+	}
+
+	/// Connects to the healthiest url of the pool that is reachable, marking it as the
+	/// active one. Unreachable urls are penalized.
+	pub async fn establish_any(
+		endpoints: &EndpointPool<String>,
+		contract_id: &AccountId,
+	) -> Result<(String, Self), Error> {
+		let mut last_error = None;
+		for (index, rpc_url) in endpoints.candidates() {
+			match Self::establish(&rpc_url, contract_id).await {
+				Ok(connection) => {
+					endpoints.select(index);
+					return Ok((rpc_url, connection))
+				},
+				Err(e) => {
+					log::warn!(target: "hyperspace_near", "Failed to connect to {rpc_url}: {e}");
+					endpoints.record_failure_at(index);
+					last_error = Some(e);
+				},
+			}
+		}
+		Err(last_error.unwrap_or_else(|| Error::RpcError("no endpoints configured".to_string())))
+	}
+}
+
+impl NearClient {
+	/// Initializes a [`NearClient`] given a [`NearClientConfig`]
+	pub async fn new(config: NearClientConfig) -> Result<Self, Error> {
+		let endpoints = EndpointPool::new(config.rpc_url.clone(), config.fallback_rpc_urls);
+		let (rpc_url, connection) =
+			NearConnection::establish_any(&endpoints, &config.contract_id).await?;
+
+		let commitment_prefix =
+			CommitmentPrefix::try_from(config.commitment_prefix.as_bytes().to_vec())
+				.map_err(|e| Error::from(format!("Invalid commitment prefix {:?}", e)))?;
+		let secret_key = SecretKey::from_str(&config.secret_key)
+			.map_err(|e| Error::from(format!("Invalid secret key: {e}")))?;
+		let signer = InMemorySigner::from_secret_key(config.signer_id, secret_key);
+
+		Ok(Self {
+			name: config.name,
+			rpc_client: connection.rpc_client,
+			rpc_url,
+			endpoints,
+			chain_id: connection.chain_id,
+			contract_id: config.contract_id,
+			shard_id: connection.shard_id,
+			signer,
+			client_id: Arc::new(Mutex::new(config.client_id)),
+			connection_id: Arc::new(Mutex::new(config.connection_id)),
+			channel_whitelist: Arc::new(Mutex::new(config.channel_whitelist.into_iter().collect())),
+			commitment_prefix,
+			gas_per_message: config.gas_per_message,
+			tx_mutex: Default::default(),
+			common_state: CommonClientState {
+				skip_optional_client_updates: config.common.skip_optional_client_updates,
+				maybe_has_undelivered_packets: Default::default(),
+				pending_timeouts: Default::default(),
+				rate_limiter: RateLimiter::new(config.common.rate_limit.clone()),
+				query_cache: QueryCache::new(config.common.query_cache.clone()),
+				fee_policies: config
+					.common
+					.fee_policies
+					.iter()
+					.map(|p| ((p.channel_id, p.port_id.clone()), p.policy.clone()))
+					.collect(),
+				misbehaviour_client_msg_queue: Arc::new(AsyncMutex::new(vec![])),
+				max_packets_to_process: config.common.max_packets_to_process as usize,
+				skip_tokens_list: config.skip_tokens_list.unwrap_or_default(),
+				reconnects: Default::default(),
+			},
+		})
+	}
+
+	/// Replaces the rpc client of this [`NearClient`] with the one connected to `rpc_url`.
+	pub fn use_connection(&mut self, rpc_url: String, connection: NearConnection) {
+		self.rpc_client = connection.rpc_client;
+		self.shard_id = connection.shard_id;
+		self.rpc_url = rpc_url;
+	}
+
+	pub fn client_id(&self) -> ClientId {
+		self.client_id
+			.lock()
+			.unwrap()
+			.as_ref()
+			.expect("Client Id should be defined")
+			.clone()
+	}
+
+	/// Calls an rpc method, respecting the rate limit of the endpoint.
+	pub async fn call<M: RpcMethod>(&self, method: M) -> Result<M::Response, Error>
+	where
+		M::Error: std::fmt::Debug,
+	{
+		self.common_state.rate_limiter.acquire().await;
+		Ok(self.rpc_client.call(method).await?)
+	}
+
+	pub async fn block(&self, block_reference: BlockReference) -> Result<BlockView, Error> {
+		self.call(RpcBlockRequest { block_reference }).await
+	}
+
+	pub async fn final_block(&self) -> Result<BlockView, Error> {
+		self.block(BlockReference::Finality(Finality::Final)).await
+	}
+
+	/// Returns the block at `height`, or `None` if no block was produced at that height.
+	pub async fn block_at(&self, height: BlockHeight) -> Result<Option<BlockView>, Error> {
+		self.common_state.rate_limiter.acquire().await;
+		let request =
+			RpcBlockRequest { block_reference: BlockReference::BlockId(BlockId::Height(height)) };
+		match self.rpc_client.call(request).await {
+			Ok(block) => Ok(Some(block)),
+			Err(JsonRpcError::ServerError(JsonRpcServerError::HandlerError(
+				RpcBlockError::UnknownBlock { .. },
+			))) => Ok(None),
+			Err(e) => Err(e.into()),
+		}
+	}
+
+	pub async fn chunk(&self, chunk_id: CryptoHash) -> Result<ChunkView, Error> {
+		self.call(RpcChunkRequest { chunk_reference: ChunkReference::ChunkHash { chunk_id } })
+			.await
+	}
+
+	pub async fn tx_status(
+		&self,
+		hash: CryptoHash,
+		account_id: AccountId,
+	) -> Result<FinalExecutionOutcomeView, Error> {
+		self.call(RpcTransactionStatusRequest {
+			transaction_info: TransactionInfo::TransactionId { hash, account_id },
+		})
+		.await
+	}
+
+	/// Calls a view method of the IBC contract on the last final block, passing and returning
+	/// JSON.
+	pub async fn view<T: DeserializeOwned>(
+		&self,
+		method_name: &str,
+		args: serde_json::Value,
+	) -> Result<T, Error> {
+		let response = self
+			.call(RpcQueryRequest {
+				block_reference: BlockReference::Finality(Finality::Final),
+				request: QueryRequest::CallFunction {
+					account_id: self.contract_id.clone(),
+					method_name: method_name.to_string(),
+					args: FunctionArgs::from(serde_json::to_vec(&args)?),
+				},
+			})
+			.await?;
+		match response.kind {
+			QueryResponseKind::CallResult(result) => Ok(serde_json::from_slice(&result.result)?),
+			kind => Err(Error::from(format!("Unexpected response to {method_name}: {kind:?}"))),
+		}
+	}
+
+	/// Reads the value stored under `path` in the IBC contract storage at `at`, together with
+	/// its [`StateProof`] against the state root of block `at`.
+	///
+	/// The state root of a block commits to the state of its parent, so the value is read from
+	/// the parent of `at` and the trie proof is anchored to the state root of the contract's
+	/// shard in the chunk included in `at`.
+	pub async fn query_state(
+		&self,
+		at: BlockHeight,
+		path: Vec<u8>,
+	) -> Result<(Option<Vec<u8>>, Vec<u8>), Error> {
+		let key = [self.commitment_prefix.as_bytes(), &path].concat();
+		let block = self.block_at(at).await?.ok_or_else(|| {
+			Error::from(format!("No block at height {at} to prove the state against"))
+		})?;
+		let shard_index = block
+			.chunks
+			.iter()
+			.position(|chunk| chunk.shard_id == self.shard_id)
+			.ok_or_else(|| Error::from(format!("No chunk for shard {} at {at}", self.shard_id)))?;
+		let chunk = &block.chunks[shard_index];
+		if chunk.height_included != block.header.height {
+			return Err(Error::from(format!(
+				"Chunk of shard {} is missing at {at}, the state can't be proven at this height",
+				self.shard_id
+			)))
+		}
+		let (state_root, shard_paths) =
+			merklize(&block.chunks.iter().map(|chunk| chunk.prev_state_root).collect::<Vec<_>>());
+		if state_root != block.header.prev_state_root {
+			return Err(Error::from(format!("Chunk state roots don't match the block at {at}")))
+		}
+
+		let response = self
+			.call(RpcQueryRequest {
+				block_reference: BlockReference::BlockId(BlockId::Hash(block.header.prev_hash)),
+				request: QueryRequest::ViewState {
+					account_id: self.contract_id.clone(),
+					prefix: StoreKey::from(key.clone()),
+					include_proof: true,
+				},
+			})
+			.await?;
+		let result = match response.kind {
+			QueryResponseKind::ViewState(result) => result,
+			kind => return Err(Error::from(format!("Unexpected response to view_state: {kind:?}"))),
+		};
+		let value = result
+			.values
+			.into_iter()
+			.find(|item| item.key.as_slice() == key.as_slice())
+			.map(|item| item.value.to_vec());
+		let proof = StateProof {
+			shard_state_root: NearHash(chunk.prev_state_root.0),
+			shard_proof: shard_paths[shard_index]
+				.iter()
+				.map(|item| NearMerklePathItem {
+					hash: NearHash(item.hash.0),
+					direction: match item.direction {
+						Direction::Left => NearDirection::Left,
+						Direction::Right => NearDirection::Right,
+					},
+				})
+				.collect(),
+			nodes: result.proof.iter().map(|node| node.to_vec()).collect(),
+		};
+		Ok((value, proof.try_to_vec()?))
+	}
+
+	/// Returns the light client block following `last_block_hash`: either the last final block
+	/// of the epoch after the one of `last_block_hash`, or the latest final block.
+	pub async fn next_light_client_block(
+		&self,
+		last_block_hash: CryptoHash,
+	) -> Result<Option<LightClientBlockView>, Error> {
+		self.call(RpcLightClientNextBlockRequest { last_block_hash })
+			.await?
+			.map(convert_light_client_block)
+			.transpose()
+	}
+
+	/// Block producers of the epoch of the block at `height`, in the order of their approvals.
+	pub async fn block_producers(
+		&self,
+		height: BlockHeight,
+	) -> Result<Vec<NearValidatorStakeView>, Error> {
+		self.call(RpcValidatorsOrderedRequest { block_id: Some(BlockId::Height(height)) })
+			.await?
+			.into_iter()
+			.map(convert_validator_stake)
+			.collect()
+	}
+
+	/// Returns the receipts executed by the IBC contract in the blocks `from..to`, ordered by
+	/// the height of the block they were executed in.
+	///
+	/// Receipts are found through the transactions and the cross-contract receipts sent to the
+	/// contract, which are executed at most [`RECEIPT_EXECUTION_LOOKBACK`] blocks after being
+	/// included.
+	pub async fn contract_outcomes_in_range(
+		&self,
+		from: BlockHeight,
+		to: BlockHeight,
+	) -> Result<Vec<(BlockHeight, ExecutionOutcomeWithIdView)>, Error> {
+		let head = self.final_block().await?.header.hash;
+		let mut outcomes = BTreeMap::new();
+		let mut block_heights = HashMap::new();
+		for height in from.saturating_sub(RECEIPT_EXECUTION_LOOKBACK)..to {
+			let block = match self.block_at(height).await? {
+				Some(block) => block,
+				None => continue,
+			};
+			block_heights.insert(block.header.hash, height);
+			for chunk_header in block.chunks.iter().filter(|chunk| chunk.height_included == height)
+			{
+				let chunk = self.chunk(chunk_header.chunk_hash).await?;
+				for tx in chunk.transactions.iter().filter(|tx| tx.receiver_id == self.contract_id)
+				{
+					let outcome = self.tx_status(tx.hash, tx.signer_id.clone()).await?;
+					outcomes.extend(
+						outcome
+							.receipts_outcome
+							.into_iter()
+							.filter(|receipt| receipt.outcome.executor_id == self.contract_id)
+							.map(|receipt| (receipt.id, receipt)),
+					);
+				}
+				for receipt in chunk.receipts.iter().filter(|r| r.receiver_id == self.contract_id) {
+					let proof = self
+						.call(RpcLightClientExecutionProofRequest {
+							id: TransactionOrReceiptId::Receipt {
+								receipt_id: receipt.receipt_id,
+								receiver_id: receipt.receiver_id.clone(),
+							},
+							light_client_head: head,
+						})
+						.await?;
+					outcomes.insert(proof.outcome_proof.id, proof.outcome_proof);
+				}
+			}
+		}
+
+		let mut executed = Vec::new();
+		for outcome in outcomes.into_values() {
+			// the state changes of failed receipts are reverted, so are their events
+			if matches!(outcome.outcome.status, ExecutionStatusView::Failure(_)) {
+				continue
+			}
+			let height = match block_heights.get(&outcome.block_hash) {
+				Some(height) => *height,
+				None =>
+					self.block(BlockReference::BlockId(BlockId::Hash(outcome.block_hash)))
+						.await?
+						.header
+						.height,
+			};
+			if (from..to).contains(&height) {
+				executed.push((height, outcome));
+			}
+		}
+		executed.sort_by_key(|(height, _)| *height);
+		Ok(executed)
+	}
+
+	/// Delivers `messages` to the IBC contract in a single transaction and waits for its
+	/// execution.
+	pub async fn submit_call(
+		&self,
+		messages: Vec<Any>,
+	) -> Result<FinalExecutionOutcomeView, Error> {
+		let gas = (self.gas_per_message * messages.len() as Gas).min(MAX_GAS);
+		let args = serde_json::to_vec(&serde_json::json!({ "messages": messages }))?;
+		self.call_contract("deliver", args, gas).await
+	}
+
+	/// Calls `method_name` of the IBC contract with the json encoded `args` in a transaction
+	/// signed by the relayer, and waits for its execution.
+	pub async fn call_contract(
+		&self,
+		method_name: &str,
+		args: Vec<u8>,
+		gas: Gas,
+	) -> Result<FinalExecutionOutcomeView, Error> {
+		let _lock = self.tx_mutex.lock().await;
+		let response = self
+			.call(RpcQueryRequest {
+				block_reference: BlockReference::Finality(Finality::Final),
+				request: QueryRequest::ViewAccessKey {
+					account_id: self.signer.account_id.clone(),
+					public_key: self.signer.public_key.clone(),
+				},
+			})
+			.await?;
+		let nonce = match response.kind {
+			QueryResponseKind::AccessKey(access_key) => access_key.nonce,
+			kind =>
+				return Err(Error::from(format!("Unexpected response to view_access_key: {kind:?}"))),
+		};
+
+		let transaction = Transaction {
+			signer_id: self.signer.account_id.clone(),
+			public_key: self.signer.public_key.clone(),
+			nonce: nonce + 1,
+			receiver_id: self.contract_id.clone(),
+			block_hash: response.block_hash,
+			actions: vec![Action::FunctionCall(FunctionCallAction {
+				method_name: method_name.to_string(),
+				args,
+				gas,
+				deposit: 0,
+			})],
+		};
+		let signed_transaction = transaction.sign(&self.signer);
+		log::debug!(target: "hyperspace_near", "Sending transaction {}", signed_transaction.get_hash());
+		let outcome = self.call(RpcBroadcastTxCommitRequest { signed_transaction }).await?;
+
+		if let FinalExecutionStatus::Failure(e) = &outcome.status {
+			return Err(Error::TransactionFailed(format!("{}: {e:?}", outcome.transaction.hash)))
+		}
+		for receipt in &outcome.receipts_outcome {
+			if let ExecutionStatusView::Failure(e) = &receipt.outcome.status {
+				return Err(Error::TransactionFailed(format!(
+					"{}, receipt {}: {e:?}",
+					outcome.transaction.hash, receipt.id
+				)))
+			}
+		}
+		log::info!(target: "hyperspace_near", "🤝 Transaction confirmed with hash: {}", outcome.transaction.hash);
+		Ok(outcome)
+	}
+}
+
+/// Converts a light client block returned by the rpc into the one of the ICS-13 light client.
+pub fn convert_light_client_block(
+	block: views::LightClientBlockView,
+) -> Result<LightClientBlockView, Error> {
+	let inner_lite = block.inner_lite;
+	Ok(LightClientBlockView {
+		prev_block_hash: NearHash(block.prev_block_hash.0),
+		next_block_inner_hash: NearHash(block.next_block_inner_hash.0),
+		inner_lite: BlockHeaderInnerLiteView {
+			height: inner_lite.height,
+			epoch_id: NearHash(inner_lite.epoch_id.0),
+			next_epoch_id: NearHash(inner_lite.next_epoch_id.0),
+			prev_state_root: NearHash(inner_lite.prev_state_root.0),
+			outcome_root: NearHash(inner_lite.outcome_root.0),
+			timestamp: inner_lite.timestamp,
+			timestamp_nanosec: inner_lite.timestamp_nanosec,
+			next_bp_hash: NearHash(inner_lite.next_bp_hash.0),
+			block_merkle_root: NearHash(inner_lite.block_merkle_root.0),
+		},
+		inner_rest_hash: NearHash(block.inner_rest_hash.0),
+		next_bps: block
+			.next_bps
+			.map(|bps| bps.into_iter().map(convert_validator_stake).collect::<Result<_, _>>())
+			.transpose()?,
+		approvals_after_next: block
+			.approvals_after_next
+			.into_iter()
+			.map(|approval| approval.map(|signature| convert_signature(&signature)).transpose())
+			.collect::<Result<_, _>>()?,
+	})
+}
+
+fn convert_validator_stake(validator: ValidatorStakeView) -> Result<NearValidatorStakeView, Error> {
+	let ValidatorStakeView::V1(validator) = validator;
+	let public_key = match validator.public_key {
+		PublicKey::ED25519(key) => NearPublicKey(key.0),
+		key => return Err(Error::from(format!("Unsupported validator key type: {key}"))),
+	};
+	Ok(NearValidatorStakeView::V1(NearValidatorStakeViewV1 {
+		account_id: validator.account_id.to_string(),
+		public_key,
+		stake: validator.stake,
+	}))
+}
+
+fn convert_signature(signature: &Signature) -> Result<NearSignature, Error> {
+	match signature {
+		Signature::ED25519(signature) => Ok(NearSignature::from_raw(&signature.to_bytes())),
+		signature => Err(Error::from(format!("Unsupported approval signature: {signature}"))),
+	}
+}
@@ -12,64 +12,35 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use ibc::core::ics02_client;
-
+use ibc::timestamp::ParseTimestampError;
 use near_jsonrpc_client::errors::JsonRpcError;
-use near_jsonrpc_primitives::types::{
-	blocks::RpcBlockError, query::RpcQueryError, transactions::RpcTransactionError,
-	validator::RpcValidatorError,
-};
-use std::io;
-use thiserror::Error;
-
-/// Near RPC error
-#[derive(Error, Debug)]
-pub enum RpcError {
-	#[error("{0}")]
-	Query(#[from] JsonRpcError<RpcQueryError>),
-	#[error("{0}")]
-	Transaction(#[from] JsonRpcError<RpcTransactionError>),
-	#[error("{0}")]
-	Block(#[from] JsonRpcError<RpcBlockError>),
-	#[error("{0}")]
-	Validator(#[from] JsonRpcError<RpcValidatorError>),
-}
+use prost::DecodeError;
+use std::fmt::Debug;
 
-/// Error definition for the NEAR client
-#[derive(Error, Debug)]
+/// Error definitions for the near client in accordance with the parachain's Error type.
+#[derive(thiserror::Error, Debug)]
 pub enum Error {
-	/// Borsh de/serialization error
-	#[error("Borsh error: {0}")]
-	Borsh(#[from] io::Error),
-	/// Json de/serialization error
-	#[error("Json error: {0}")]
-	Json(#[from] serde_json::Error),
-	/// RPC error
-	#[error("RPC error: {0}")]
-	Rpc(#[from] RpcError),
-	/// Update pallet name in call definition
-	#[error("Pallet '{0}' not found in metadata, update static definition of call")]
-	PalletNotFound(&'static str),
-	/// hex error
-	#[error("Error decoding hex: {0:?}")]
-	Hex(#[from] hex::FromHexError),
+	/// An error from the rpc interface
+	#[error("Rpc client error: {0}")]
+	RpcError(String),
 	/// Custom error
 	#[error("{0}")]
 	Custom(String),
-	#[error("Ibc channel error")]
-	IbcChannel(#[from] ibc::core::ics04_channel::error::Error),
-	/// Error querying packets
-	#[error("Could not retrieve packets from {channel_id}/{port_id} for sequences {:?}", .sequences)]
-	QueryPackets { channel_id: String, port_id: String, sequences: Vec<u64>, err: String },
-	/// Failed to rehydrate client state
-	#[error("Error decoding some value: {0}")]
-	ClientStateRehydration(String),
-	/// Failed to get client update header from finality notification
-	#[error("Error constructing a client update header: {0}")]
-	HeaderConstruction(String),
-	/// Errors associated with ics-02 client
-	#[error("Ibc client error: {0}")]
-	IbcClient(#[from] ics02_client::error::Error),
+	/// Decode error
+	#[error("Decode error: {0}")]
+	DecodeError(#[from] DecodeError),
+	/// Json error
+	#[error("Json error: {0}")]
+	Json(#[from] serde_json::Error),
+	/// Borsh error
+	#[error("Borsh error: {0}")]
+	Borsh(#[from] std::io::Error),
+	/// Parse timestamp error
+	#[error("Parse timestamp error: {0}")]
+	ParseTimestampError(#[from] ParseTimestampError),
+	/// A transaction or one of its receipts failed
+	#[error("Transaction failed: {0}")]
+	TransactionFailed(String),
 }
 
 impl From<String> for Error {
@@ -78,11 +49,8 @@ impl From<String> for Error {
 	}
 }
 
-impl<T> From<JsonRpcError<T>> for Error
-where
-	RpcError: From<JsonRpcError<T>>,
-{
-	fn from(error: JsonRpcError<T>) -> Self {
-		Self::Rpc(RpcError::from(error))
+impl<E: Debug> From<JsonRpcError<E>> for Error {
+	fn from(error: JsonRpcError<E>) -> Self {
+		Self::RpcError(format!("{error:?}"))
 	}
 }
@@ -0,0 +1,62 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The IBC contract emits its events as [NEP-297](https://nomicon.io/Standards/EventsFormat)
+//! logs of the `ibc` standard, with the serialized [`IbcEvent`] as the event data:
+//!
+//! `EVENT_JSON:{"standard":"ibc","version":"1.0.0","event":"send_packet","data":{..}}`
+
+use crate::error::Error;
+use ibc::{events::IbcEvent, Height};
+use serde::Deserialize;
+
+/// Prefix of the logs following the NEP-297 events standard
+pub const EVENT_LOG_PREFIX: &str = "EVENT_JSON:";
+/// Standard of the events emitted by the IBC contract
+pub const IBC_EVENT_STANDARD: &str = "ibc";
+
+#[derive(Deserialize)]
+struct EventLog {
+	standard: String,
+	data: serde_json::Value,
+}
+
+/// Parses an [`IbcEvent`] out of a contract log. Returns `None` if the log isn't an IBC event.
+pub fn ibc_event_try_from_log(log: &str) -> Option<Result<IbcEvent, Error>> {
+	let json = log.strip_prefix(EVENT_LOG_PREFIX)?;
+	let event_log = match serde_json::from_str::<EventLog>(json) {
+		Ok(event_log) => event_log,
+		Err(e) => return Some(Err(e.into())),
+	};
+	if event_log.standard != IBC_EVENT_STANDARD {
+		return None
+	}
+	Some(serde_json::from_value(event_log.data).map_err(Into::into))
+}
+
+/// Parses the IBC events out of the logs of a receipt executed at `height`.
+pub fn ibc_events_from_logs(logs: &[String], height: Height) -> Vec<IbcEvent> {
+	logs.iter()
+		.filter_map(|log| match ibc_event_try_from_log(log)? {
+			Ok(mut event) => {
+				event.set_height(height);
+				Some(event)
+			},
+			Err(e) => {
+				log::warn!(target: "hyperspace_near", "Failed to parse event log {log}: {e}");
+				None
+			},
+		})
+		.collect()
+}
@@ -1,23 +1,11 @@
-// Copyright 2022 ComposableFi
-//
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-//
-//      http://www.apache.org/licenses/LICENSE-2.0
-//
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
-
-use super::Client;
+use super::client::NearClient;
+use ibc::signer::Signer;
 use primitives::KeyProvider;
 use std::str::FromStr;
 
-impl KeyProvider for Client {
-	fn account_id(&self) -> ibc::signer::Signer {
-		ibc::signer::Signer::from_str(self.signer.as_str()).expect("Account Id should be valid")
+impl KeyProvider for NearClient {
+	fn account_id(&self) -> Signer {
+		Signer::from_str(self.signer.account_id.as_str())
+			.expect("NEAR account ids are valid signers; qed")
 	}
 }
@@ -1,138 +1,187 @@
-// Copyright 2022 ComposableFi
-//
-// Licensed under the Apache License, Version 2.0 (the "License");
-// you may not use this file except in compliance with the License.
-// You may obtain a copy of the License at
-//
-//      http://www.apache.org/licenses/LICENSE-2.0
-//
-// Unless required by applicable law or agreed to in writing, software
-// distributed under the License is distributed on an "AS IS" BASIS,
-// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
-// See the License for the specific language governing permissions and
-// limitations under the License.
-
-#[cfg(feature = "testing")]
-use actix::dev::Stream;
-#[cfg(feature = "testing")]
-use std::pin::Pin;
-
-use super::error::Error;
-use crate::Client;
+use super::{client::NearClient, events::ibc_events_from_logs};
+use crate::error::Error;
+use borsh::BorshDeserialize;
+use futures::{Stream, StreamExt};
 use ibc::{
+	applications::transfer::{Amount, PrefixedCoin, PrefixedDenom},
 	core::{
-		ics02_client::{
-			client_consensus::AnyConsensusState, client_state::AnyClientState, header::AnyHeader,
-		},
-		ics04_channel::packet::Packet,
+		ics02_client::{client_state::ClientType, msgs::update_client::MsgUpdateAnyClient},
+		ics04_channel::packet::Sequence,
 		ics23_commitment::commitment::CommitmentPrefix,
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ChannelUpgradePath, ClientConsensusStatePath,
+				ClientStatePath, CommitmentsPath, ConnectionsPath, Path, ReceiptsPath,
+				SeqRecvsPath,
+			},
+		},
 	},
 	events::IbcEvent,
+	protobuf::Protobuf,
+	signer::Signer,
+	timestamp::Timestamp,
+	tx_msg::Msg,
 	Height,
 };
-use ibc_proto::ibc::core::{
-	channel::v1::{
-		Packet as RawPacket, QueryChannelResponse, QueryNextSequenceReceiveResponse,
-		QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
-		QueryPacketReceiptResponse,
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::{
+		channel::v1::{
+			Channel, ErrorReceipt, IdentifiedChannel, QueryChannelResponse, QueryChannelsResponse,
+			QueryNextSequenceReceiveResponse, QueryPacketAcknowledgementResponse,
+			QueryPacketCommitmentResponse, QueryPacketReceiptResponse, QueryUpgradeErrorResponse,
+			QueryUpgradeResponse, Upgrade,
+		},
+		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
+		connection::v1::{ConnectionEnd, IdentifiedConnection, QueryConnectionResponse},
 	},
-	client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
-	connection::v1::QueryConnectionResponse,
 };
-use near_indexer::StreamerMessage;
-use near_jsonrpc_primitives::types::{
-	blocks::RpcBlockRequest,
-	query::{QueryResponseKind, RpcQueryRequest},
-	validator::RpcValidatorRequest,
+use ibc_rpc::PacketInfo;
+use ics13_near::{
+	client_state::NearClientState,
+	consensus_state::ConsensusState,
+	header::{NearClientMessage, NearHeader},
+	types::LightClientBlockView,
 };
+use near_jsonrpc_client::methods::validators::RpcValidatorRequest;
 use near_primitives::{
-	types::{BlockId, BlockReference, EpochReference, Finality, FunctionArgs},
-	views::QueryRequest,
+	hash::CryptoHash,
+	types::{AccountId, BlockHeight, BlockId, BlockReference, EpochReference},
+};
+use pallet_ibc::light_clients::{
+	AnyClientMessage, AnyClientState, AnyConsensusState, HostFunctionsManager,
+};
+use primitives::{
+	fees::PacketFees, filter_events_by_ids, mock::LocalClientTypes, Chain, IbcProvider,
+	KeyProvider, UpdateType,
+};
+use prost::Message;
+use std::{
+	collections::{HashMap, HashSet},
+	marker::PhantomData,
+	pin::Pin,
+	str::FromStr,
+	time::Duration,
 };
-use near_sdk::BlockHeight;
-use primitives::{Chain, IbcProvider, UpdateType};
-use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 
-impl Client {
-	fn make_contract_query_at<T: Serialize>(
-		&self,
-		at: BlockHeight,
-		method: impl ToString,
-		args: &T,
-	) -> Result<RpcQueryRequest, <Self as IbcProvider>::Error> {
-		Ok(RpcQueryRequest {
-			block_reference: BlockReference::BlockId(BlockId::Height(at)),
-			request: QueryRequest::CallFunction {
-				account_id: self.contract_id.clone(),
-				method_name: method.to_string(),
-				args: FunctionArgs::from(serde_json::to_vec(args)?),
-			},
-		})
-	}
+/// Maximum number of light client blocks fetched in one iteration
+pub const NUMBER_OF_HEADERS_TO_PROCESS_PER_ITER: usize = 100;
 
-	fn make_contract_query_at_final<T: Serialize>(
-		&self,
-		method: impl ToString,
-		args: &T,
-	) -> Result<RpcQueryRequest, <Self as IbcProvider>::Error> {
-		Ok(RpcQueryRequest {
-			block_reference: BlockReference::Finality(Finality::Final),
-			request: QueryRequest::CallFunction {
-				account_id: self.contract_id.clone(),
-				method_name: method.to_string(),
-				args: FunctionArgs::from(serde_json::to_vec(args)?),
-			},
-		})
-	}
+#[derive(Clone, Debug)]
+pub enum FinalityEvent {
+	Near { height: BlockHeight },
+}
 
-	async fn send_query<R: DeserializeOwned>(
-		&self,
-		query: RpcQueryRequest,
-	) -> Result<R, <Self as IbcProvider>::Error> {
-		self.rpc_client
-			.call(query)
-			.await
-			.map_err(|e| e.into())
-			.and_then(|resp| match resp.kind {
-				QueryResponseKind::CallResult(res) =>
-					serde_json::from_slice(&res.result).map_err(|e| e.into()),
-				_ => unreachable!(),
-			})
-	}
+#[derive(Clone, Debug)]
+pub struct TransactionId {
+	pub hash: CryptoHash,
+	pub sender: AccountId,
+}
+
+/// Heights of NEAR blocks are never reset, all of them are in revision 0.
+pub fn near_height(height: BlockHeight) -> Height {
+	Height::new(0, height)
 }
 
 #[async_trait::async_trait]
-impl IbcProvider for Client {
-	type IbcEvent = Result<Vec<IbcEvent>, String>;
-	type FinalityEvent = StreamerMessage;
+impl IbcProvider for NearClient {
+	type FinalityEvent = FinalityEvent;
+	type TransactionId = TransactionId;
+	type AssetId = String;
 	type Error = Error;
 
-	async fn client_update_header<C>(
+	async fn query_latest_ibc_events<C>(
 		&mut self,
-		_finality_event: Self::FinalityEvent,
-		_counterparty: &C,
-	) -> Result<(AnyHeader, AnyClientState, UpdateType), Self::Error>
+		finality_event: Self::FinalityEvent,
+		counterparty: &C,
+	) -> Result<Vec<(Any, Height, Vec<IbcEvent>, UpdateType)>, anyhow::Error>
 	where
 		C: Chain,
-		Self::Error: From<C::Error>,
 	{
-		unimplemented!()
-	}
+		let FinalityEvent::Near { height: finality_height } = finality_event;
+		let client_id = self.client_id();
+		let latest_cp_height = counterparty.latest_height_and_timestamp().await?.0;
+		let latest_cp_client_state =
+			counterparty.query_client_state(latest_cp_height, client_id.clone()).await?;
+		let client_state_response = latest_cp_client_state
+			.client_state
+			.ok_or_else(|| Error::Custom("counterparty returned empty client state".to_string()))?;
+		let client_state =
+			NearClientState::<HostFunctionsManager>::decode_vec(&client_state_response.value)
+				.map_err(|_| Error::Custom("failed to decode client state response".to_string()))?;
 
-	async fn query_latest_ibc_events(
-		&mut self,
-		_header: &AnyHeader,
-		_client_state: &AnyClientState,
-	) -> Result<Vec<IbcEvent>, Self::Error> {
-		unimplemented!()
+		let mut from = client_state.head.inner_lite.height;
+		if finality_height <= from {
+			return Ok(vec![])
+		}
+		let headers = self.light_client_blocks_since(client_state.head).await?;
+		log::info!(target: "hyperspace_near", "Got {} light client blocks since {from}", headers.len());
+
+		let mut updates = Vec::new();
+		for (block, update_type) in headers {
+			let height = block.inner_lite.height;
+			// the state root of a block commits to the state of its parent, the events of the block
+			// itself are proven with the next header.
+			let events = self.filtered_ibc_events(from, height, counterparty).await?;
+			from = height;
+			let update_client_header = {
+				let msg = MsgUpdateAnyClient::<LocalClientTypes> {
+					client_id: client_id.clone(),
+					client_message: AnyClientMessage::Near(NearClientMessage::Header(
+						NearHeader::new(block),
+					)),
+					signer: counterparty.account_id(),
+				};
+				let value = msg.encode_vec().map_err(|e| {
+					Error::from(format!("Failed to encode MsgUpdateClient {msg:?}: {e:?}"))
+				})?;
+				Any { value, type_url: msg.type_url() }
+			};
+			updates.push((update_client_header, near_height(height), events, update_type));
+		}
+		Ok(updates)
 	}
 
-	async fn host_consensus_state(
+	async fn query_ibc_events_in_range<C>(
 		&self,
-		_height: Height,
-	) -> Result<AnyConsensusState, Self::Error> {
-		unimplemented!()
+		from: u64,
+		to: u64,
+		counterparty: &C,
+	) -> Result<Vec<IbcEvent>, anyhow::Error>
+	where
+		C: Chain,
+	{
+		Ok(self.filtered_ibc_events(from, to + 1, counterparty).await?)
+	}
+
+	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
+		let client = self.clone();
+		let heights = self.final_heights();
+		let mut last_height = None;
+		heights
+			.then(move |height| {
+				let client = client.clone();
+				let from = last_height.replace(height).unwrap_or(height);
+				async move {
+					client
+						.contract_outcomes_in_range(from, height)
+						.await
+						.map_err(
+							|e| log::error!(target: "hyperspace_near", "Failed to fetch events {from}..{height}: {e}"),
+						)
+						.unwrap_or_default()
+						.into_iter()
+						.flat_map(|(height, outcome)| {
+							ibc_events_from_logs(&outcome.outcome.logs, near_height(height))
+						})
+						.collect::<Vec<_>>()
+				}
+			})
+			.flat_map(futures::stream::iter)
+			.boxed()
 	}
 
 	async fn query_client_consensus(
@@ -141,10 +190,16 @@ impl IbcProvider for Client {
 		client_id: ClientId,
 		consensus_height: Height,
 	) -> Result<QueryConsensusStateResponse, Self::Error> {
-		let args = (consensus_height, client_id, false);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_client_consensus_state", &args)?;
-		self.send_query(query).await
+		let path_bytes = Path::ClientConsensusState(ClientConsensusStatePath {
+			client_id,
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		})
+		.to_string()
+		.into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		let consensus_state = value.map(|value| Any::decode(&*value)).transpose()?;
+		Ok(QueryConsensusStateResponse { consensus_state, proof, proof_height: Some(at.into()) })
 	}
 
 	async fn query_client_state(
@@ -152,9 +207,17 @@ impl IbcProvider for Client {
 		at: Height,
 		client_id: ClientId,
 	) -> Result<QueryClientStateResponse, Self::Error> {
-		let args = (client_id,);
-		let query = self.make_contract_query_at(at.revision_height, "query_client_state", &args)?;
-		self.send_query(query).await
+		let path_bytes = Path::ClientState(ClientStatePath(client_id)).to_string().into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		let client_state = value
+			.map(|value| Any::decode(&*value))
+			.transpose()?
+			.ok_or_else(|| Error::Custom(format!("empty client state for height {at}")))?;
+		Ok(QueryClientStateResponse {
+			client_state: Some(client_state),
+			proof,
+			proof_height: Some(at.into()),
+		})
 	}
 
 	async fn query_connection_end(
@@ -162,9 +225,10 @@ impl IbcProvider for Client {
 		at: Height,
 		connection_id: ConnectionId,
 	) -> Result<QueryConnectionResponse, Self::Error> {
-		let args = (connection_id,);
-		let query = self.make_contract_query_at(at.revision_height, "query_connection", &args)?;
-		self.send_query(query).await
+		let path_bytes = Path::Connections(ConnectionsPath(connection_id)).to_string().into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		let connection = value.map(|value| ConnectionEnd::decode(&*value)).transpose()?;
+		Ok(QueryConnectionResponse { connection, proof, proof_height: Some(at.into()) })
 	}
 
 	async fn query_channel_end(
@@ -173,38 +237,49 @@ impl IbcProvider for Client {
 		channel_id: ChannelId,
 		port_id: PortId,
 	) -> Result<QueryChannelResponse, Self::Error> {
-		let args = (channel_id, port_id);
-		let query = self.make_contract_query_at(at.revision_height, "query_channel", &args)?;
-		self.send_query(query).await
+		let path_bytes =
+			Path::ChannelEnds(ChannelEndsPath(port_id, channel_id)).to_string().into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		let channel = value.map(|value| Channel::decode(&*value)).transpose()?;
+		Ok(QueryChannelResponse { channel, proof, proof_height: Some(at.into()) })
 	}
 
-	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
-		let args = (keys,);
-		let query = self.make_contract_query_at(at.revision_height, "query_proof", &args)?;
-		self.send_query(query).await
+	async fn query_channel_upgrade(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeResponse, Self::Error> {
+		let path_bytes = Path::ChannelUpgrade(ChannelUpgradePath::Upgrade(port_id, channel_id))
+			.to_string()
+			.into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		let upgrade = value.map(|value| Upgrade::decode(&*value)).transpose()?;
+		Ok(QueryUpgradeResponse { upgrade, proof, proof_height: Some(at.into()) })
 	}
 
-	async fn query_packets(
+	async fn query_channel_upgrade_error(
 		&self,
-		_at: Height,
-		port_id: &PortId,
-		channel_id: &ChannelId,
-		seqs: Vec<u64>,
-	) -> Result<Vec<Packet>, Self::Error> {
-		let args = (port_id, channel_id, &seqs);
-		let query = self.make_contract_query_at_final("query_packets", &args)?;
-		let packets: Vec<RawPacket> =
-			self.send_query(query).await.map_err(|e| Error::QueryPackets {
-				channel_id: channel_id.to_string(),
-				port_id: port_id.to_string(),
-				sequences: seqs,
-				err: e.to_string(),
-			})?;
-		let packets = packets
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeErrorResponse, Self::Error> {
+		let path_bytes =
+			Path::ChannelUpgrade(ChannelUpgradePath::UpgradeError(port_id, channel_id))
+				.to_string()
+				.into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		let error_receipt = value.map(|value| ErrorReceipt::decode(&*value)).transpose()?;
+		Ok(QueryUpgradeErrorResponse { error_receipt, proof, proof_height: Some(at.into()) })
+	}
+
+	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
+		let key = keys
 			.into_iter()
-			.map(|raw_packet| raw_packet.try_into())
-			.collect::<Result<Vec<Packet>, _>>()?;
-		Ok(packets)
+			.next()
+			.ok_or_else(|| Error::from("No key to prove".to_string()))?;
+		let (_, proof) = self.query_state(at.revision_height, key).await?;
+		Ok(proof)
 	}
 
 	async fn query_packet_commitment(
@@ -214,10 +289,19 @@ impl IbcProvider for Client {
 		channel_id: &ChannelId,
 		seq: u64,
 	) -> Result<QueryPacketCommitmentResponse, Self::Error> {
-		let args = (port_id, channel_id, seq);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_packet_commitment", &args)?;
-		self.send_query(query).await
+		let path_bytes = Path::Commitments(CommitmentsPath {
+			port_id: port_id.clone(),
+			channel_id: *channel_id,
+			sequence: Sequence::from(seq),
+		})
+		.to_string()
+		.into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		Ok(QueryPacketCommitmentResponse {
+			commitment: value.unwrap_or_default(),
+			proof,
+			proof_height: Some(at.into()),
+		})
 	}
 
 	async fn query_packet_acknowledgement(
@@ -227,10 +311,19 @@ impl IbcProvider for Client {
 		channel_id: &ChannelId,
 		seq: u64,
 	) -> Result<QueryPacketAcknowledgementResponse, Self::Error> {
-		let args = (port_id, channel_id, seq);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_packet_acknowledgement", &args)?;
-		self.send_query(query).await
+		let path_bytes = Path::Acks(AcksPath {
+			port_id: port_id.clone(),
+			channel_id: *channel_id,
+			sequence: Sequence::from(seq),
+		})
+		.to_string()
+		.into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		Ok(QueryPacketAcknowledgementResponse {
+			acknowledgement: value.unwrap_or_default(),
+			proof,
+			proof_height: Some(at.into()),
+		})
 	}
 
 	async fn query_next_sequence_recv(
@@ -239,10 +332,19 @@ impl IbcProvider for Client {
 		port_id: &PortId,
 		channel_id: &ChannelId,
 	) -> Result<QueryNextSequenceReceiveResponse, Self::Error> {
-		let args = (port_id, channel_id);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_next_seq_recv", &args)?;
-		self.send_query(query).await
+		let path_bytes = Path::SeqRecvs(SeqRecvsPath(port_id.clone(), *channel_id))
+			.to_string()
+			.into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		let next_sequence_receive = value
+			.map(|value| u64::try_from_slice(&value))
+			.transpose()?
+			.ok_or_else(|| Error::Custom("missing next_sequence_receive value".to_owned()))?;
+		Ok(QueryNextSequenceReceiveResponse {
+			next_sequence_receive,
+			proof,
+			proof_height: Some(at.into()),
+		})
 	}
 
 	async fn query_packet_receipt(
@@ -252,45 +354,460 @@ impl IbcProvider for Client {
 		channel_id: &ChannelId,
 		seq: u64,
 	) -> Result<QueryPacketReceiptResponse, Self::Error> {
-		let args = (port_id, channel_id, seq);
-		let query =
-			self.make_contract_query_at(at.revision_height, "query_packet_receipt", &args)?;
-		self.send_query(query).await
+		let path_bytes = Path::Receipts(ReceiptsPath {
+			port_id: port_id.clone(),
+			channel_id: *channel_id,
+			sequence: Sequence::from(seq),
+		})
+		.to_string()
+		.into_bytes();
+		let (value, proof) = self.query_state(at.revision_height, path_bytes).await?;
+		Ok(QueryPacketReceiptResponse {
+			received: value.is_some(),
+			proof,
+			proof_height: Some(at.into()),
+		})
+	}
+
+	async fn latest_height_and_timestamp(&self) -> Result<(Height, Timestamp), Self::Error> {
+		let block = self.final_block().await?;
+		Ok((
+			near_height(block.header.height),
+			Timestamp::from_nanoseconds(block.header.timestamp_nanosec)?,
+		))
 	}
 
-	async fn latest_height_and_timestamp(&self) -> Result<Height, Self::Error> {
-		let finalized_block = self
-			.rpc_client
-			.call(RpcBlockRequest { block_reference: BlockReference::Finality(Finality::Final) })
+	async fn query_packet_commitments(
+		&self,
+		_at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.view(
+			"query_packet_commitments",
+			serde_json::json!({ "port_id": port_id, "channel_id": channel_id }),
+		)
+		.await
+	}
+
+	async fn query_packet_acknowledgements(
+		&self,
+		_at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.view(
+			"query_packet_acknowledgements",
+			serde_json::json!({ "port_id": port_id, "channel_id": channel_id }),
+		)
+		.await
+	}
+
+	async fn query_unreceived_packets(
+		&self,
+		_at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.view(
+			"query_unreceived_packets",
+			serde_json::json!({ "port_id": port_id, "channel_id": channel_id, "sequences": seqs }),
+		)
+		.await
+	}
+
+	async fn query_unreceived_acknowledgements(
+		&self,
+		_at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.view(
+			"query_unreceived_acknowledgements",
+			serde_json::json!({ "port_id": port_id, "channel_id": channel_id, "sequences": seqs }),
+		)
+		.await
+	}
+
+	fn channel_whitelist(&self) -> HashSet<(ChannelId, PortId)> {
+		self.channel_whitelist.lock().unwrap().clone()
+	}
+
+	async fn query_connection_channels(
+		&self,
+		at: Height,
+		connection_id: &ConnectionId,
+	) -> Result<QueryChannelsResponse, Self::Error> {
+		let channels: Vec<IdentifiedChannel> = self
+			.view(
+				"query_connection_channels",
+				serde_json::json!({ "connection_id": connection_id }),
+			)
 			.await?;
-		let validator_response = self
-			.rpc_client
-			.call(RpcValidatorRequest {
-				epoch_reference: EpochReference::BlockId(BlockId::Height(
-					finalized_block.header.height,
-				)),
-			})
+		Ok(QueryChannelsResponse { channels, pagination: None, height: Some(at.into()) })
+	}
+
+	async fn query_send_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.view(
+			"query_send_packets",
+			serde_json::json!({ "port_id": port_id, "channel_id": channel_id, "sequences": seqs }),
+		)
+		.await
+	}
+
+	async fn query_received_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.view(
+			"query_received_packets",
+			serde_json::json!({ "port_id": port_id, "channel_id": channel_id, "sequences": seqs }),
+		)
+		.await
+	}
+
+	fn expected_block_time(&self) -> Duration {
+		// near produces a block roughly every second
+		Duration::from_secs(1)
+	}
+
+	async fn query_client_update_time_and_height(
+		&self,
+		client_id: ClientId,
+		client_height: Height,
+	) -> Result<(Height, Timestamp), Self::Error> {
+		let (height, timestamp): (u64, u64) = self
+			.view(
+				"query_client_update_time_and_height",
+				serde_json::json!({ "client_id": client_id, "height": client_height }),
+			)
 			.await?;
-		if validator_response.epoch_start_height > finalized_block.header.height {
-			return Err(Error::Custom(
-				"validator epoch height is greater than finalized header epoch height".to_string(),
-			))
-		}
-		let epoch = validator_response.epoch_height;
-		let latest_height = finalized_block.header.height;
-		Ok(Height::new(epoch, latest_height.into()))
+		Ok((near_height(height), Timestamp::from_nanoseconds(timestamp)?))
+	}
+
+	async fn query_host_consensus_state_proof(
+		&self,
+		_client_state: &AnyClientState,
+	) -> Result<Option<Vec<u8>>, Self::Error> {
+		Ok(None)
+	}
+
+	async fn query_ibc_balance(
+		&self,
+		asset_id: Self::AssetId,
+	) -> Result<Vec<PrefixedCoin>, Self::Error> {
+		let amount: String = self
+			.view(
+				"query_balance",
+				serde_json::json!({ "account_id": self.signer.account_id, "denom": asset_id }),
+			)
+			.await?;
+		Ok(vec![PrefixedCoin {
+			denom: PrefixedDenom::from_str(&asset_id).map_err(|e| Error::from(e.to_string()))?,
+			amount: Amount::from_str(&amount).map_err(|e| Error::from(e.to_string()))?,
+		}])
 	}
 
 	fn connection_prefix(&self) -> CommitmentPrefix {
-		CommitmentPrefix::try_from(self.commitment_prefix.clone()).expect("Should not fail")
+		self.commitment_prefix.clone()
 	}
 
 	fn client_id(&self) -> ClientId {
 		self.client_id()
 	}
 
-	#[cfg(feature = "testing")]
-	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + Sync>> {
-		todo!()
+	fn set_client_id(&mut self, client_id: ClientId) {
+		*self.client_id.lock().unwrap() = Some(client_id);
+	}
+
+	fn connection_id(&self) -> Option<ConnectionId> {
+		self.connection_id.lock().unwrap().clone()
+	}
+
+	/// Set the channel whitelist for the relayer task.
+	fn set_channel_whitelist(&mut self, channel_whitelist: HashSet<(ChannelId, PortId)>) {
+		*self.channel_whitelist.lock().unwrap() = channel_whitelist;
+	}
+
+	fn add_channel_to_whitelist(&mut self, channel: (ChannelId, PortId)) {
+		self.channel_whitelist.lock().unwrap().insert(channel);
+	}
+
+	fn set_connection_id(&mut self, connection_id: ConnectionId) {
+		*self.connection_id.lock().unwrap() = Some(connection_id);
+	}
+
+	fn client_type(&self) -> ClientType {
+		NearClientState::<()>::client_type()
+	}
+
+	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
+		let block = self.block(BlockReference::BlockId(BlockId::Height(block_number))).await?;
+		Ok(block.header.timestamp_nanosec)
+	}
+
+	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
+		self.view("query_clients", serde_json::json!({})).await
+	}
+
+	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
+		let channels: Vec<IdentifiedChannel> =
+			self.view("query_channels", serde_json::json!({})).await?;
+		Ok(channels
+			.into_iter()
+			.filter_map(|c| {
+				let id = ChannelId::from_str(&c.channel_id).ok()?;
+				let port_id = PortId::from_str(&c.port_id).ok()?;
+				Some((id, port_id))
+			})
+			.collect())
+	}
+
+	async fn query_connection_using_client(
+		&self,
+		_height: u32,
+		client_id: String,
+	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		let connections: Vec<IdentifiedConnection> =
+			self.view("query_connections", serde_json::json!({})).await?;
+		Ok(connections
+			.into_iter()
+			.filter(|conn| {
+				conn.client_id == client_id ||
+					conn.counterparty.as_ref().map(|x| x.client_id == client_id).unwrap_or(false)
+			})
+			.collect())
+	}
+
+	async fn is_update_required(
+		&self,
+		_latest_height: u64,
+		_latest_client_height_on_counterparty: u64,
+	) -> Result<bool, Self::Error> {
+		// every epoch change is a mandatory update which is submitted via
+		// `finality_notifications`
+		Ok(false)
+	}
+
+	async fn initialize_client_state(
+		&self,
+	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
+		// start from the first light client block of the current epoch, which carries the block
+		// producers of the next epoch
+		let final_block = self.final_block().await?;
+		let epoch_info = self
+			.call(RpcValidatorRequest {
+				epoch_reference: EpochReference::BlockId(BlockId::Hash(final_block.header.hash)),
+			})
+			.await?;
+		let epoch_start = self
+			.block(BlockReference::BlockId(BlockId::Height(epoch_info.epoch_start_height)))
+			.await?;
+		let head =
+			self.next_light_client_block(epoch_start.header.prev_hash)
+				.await?
+				.ok_or_else(|| {
+					Error::from("No light client block for the current epoch".to_string())
+				})?;
+		let next_validators = head.next_bps.clone().ok_or_else(|| {
+			Error::from("Light client block is missing the next block producers".to_string())
+		})?;
+		let current_validators = self.block_producers(head.inner_lite.height).await?;
+
+		let consensus_state = ConsensusState::from_header(&head)
+			.map_err(|e| Error::from(format!("Invalid light client block {e}")))?;
+		let client_state = NearClientState {
+			chain_id: self.chain_id.clone(),
+			current_epoch: head.inner_lite.epoch_id,
+			next_epoch: head.inner_lite.next_epoch_id,
+			head,
+			current_validators,
+			next_validators,
+			ibc_account_id: self.contract_id.to_string(),
+			// the contract stores the upgraded states under its commitment prefix
+			upgrade_prefix: self.commitment_prefix.as_bytes().to_vec(),
+			frozen_height: None,
+			_phantom: PhantomData,
+		};
+		Ok((AnyClientState::Near(client_state), AnyConsensusState::Near(consensus_state)))
+	}
+
+	async fn query_client_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ClientId, Self::Error> {
+		self.tx_events(tx_id)
+			.await?
+			.into_iter()
+			.find_map(|ev| match ev {
+				IbcEvent::CreateClient(ev) => Some(ev.client_id().clone()),
+				_ => None,
+			})
+			.ok_or_else(|| Error::from("Expected a CreateClient event".to_string()))
+	}
+
+	async fn query_connection_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ConnectionId, Self::Error> {
+		self.tx_events(tx_id)
+			.await?
+			.into_iter()
+			.find_map(|ev| match ev {
+				IbcEvent::OpenInitConnection(ev) => ev.connection_id().cloned(),
+				_ => None,
+			})
+			.ok_or_else(|| Error::from("Expected an OpenInitConnection event".to_string()))
+	}
+
+	async fn query_channel_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<(ChannelId, PortId), Self::Error> {
+		self.tx_events(tx_id)
+			.await?
+			.into_iter()
+			.find_map(|ev| match ev {
+				IbcEvent::OpenInitChannel(ev) => Some((ev.channel_id?, ev.port_id)),
+				_ => None,
+			})
+			.ok_or_else(|| Error::from("Expected an OpenInitChannel event".to_string()))
+	}
+
+	async fn upload_wasm(&self, _wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error> {
+		Err(Error::Custom("Uploading WASM to NEAR is not supported".to_string()))
+	}
+
+	async fn query_incentivized_packets(
+		&self,
+		_channel_id: ChannelId,
+		_port_id: PortId,
+	) -> Result<HashMap<u64, PacketFees>, Self::Error> {
+		Err(Error::Custom("Fee incentivized packets are not supported on NEAR".to_string()))
+	}
+
+	async fn register_counterparty_payee(
+		&self,
+		_channel_id: ChannelId,
+		_port_id: PortId,
+		_counterparty_payee: Signer,
+	) -> Result<(), Self::Error> {
+		Err(Error::Custom("Fee incentivized packets are not supported on NEAR".to_string()))
+	}
+}
+
+impl NearClient {
+	/// Returns the light client blocks following `head`, up to the latest final block. Blocks
+	/// starting a new epoch are mandatory updates.
+	pub async fn light_client_blocks_since(
+		&self,
+		head: LightClientBlockView,
+	) -> Result<Vec<(LightClientBlockView, UpdateType)>, Error> {
+		let mut blocks = Vec::new();
+		let mut last_height = head.inner_lite.height;
+		let mut last_hash = head.current_block_hash::<HostFunctionsManager>();
+		while blocks.len() < NUMBER_OF_HEADERS_TO_PROCESS_PER_ITER {
+			let block = match self.next_light_client_block(CryptoHash(last_hash.0)).await? {
+				Some(block) if block.inner_lite.height > last_height => block,
+				_ => break,
+			};
+			last_height = block.inner_lite.height;
+			last_hash = block.current_block_hash::<HostFunctionsManager>();
+			let update_type =
+				if block.next_bps.is_some() { UpdateType::Mandatory } else { UpdateType::Optional };
+			blocks.push((block, update_type));
+		}
+		if let Some((_, update_type)) = blocks.last_mut() {
+			// the client must be advanced at least once per iteration
+			*update_type = UpdateType::Mandatory;
+		}
+		Ok(blocks)
+	}
+
+	/// Returns the IBC events emitted in the blocks `from..to` that are relevant to the
+	/// relayed clients, connections and channels.
+	pub async fn filtered_ibc_events<C: Chain>(
+		&self,
+		from: BlockHeight,
+		to: BlockHeight,
+		counterparty: &C,
+	) -> Result<Vec<IbcEvent>, Error> {
+		let mut channel_and_port_ids = self.channel_whitelist();
+		channel_and_port_ids.extend(counterparty.channel_whitelist());
+		let client_ids = [self.client_id(), counterparty.client_id()];
+		let connection_ids = [self.connection_id(), counterparty.connection_id()]
+			.into_iter()
+			.flatten()
+			.collect::<Vec<_>>();
+
+		Ok(self
+			.contract_outcomes_in_range(from, to)
+			.await?
+			.into_iter()
+			.flat_map(|(height, outcome)| {
+				ibc_events_from_logs(&outcome.outcome.logs, near_height(height))
+			})
+			.filter(|ev| {
+				let is_filtered =
+					filter_events_by_ids(ev, &client_ids, &connection_ids, &channel_and_port_ids);
+				if !is_filtered {
+					log::debug!(target: "hyperspace_near", "Filtered out event: {:?}", ev);
+				}
+				is_filtered
+			})
+			.collect())
+	}
+
+	/// Returns the IBC events emitted by the execution of the transaction `tx_id`.
+	pub async fn tx_events(&self, tx_id: TransactionId) -> Result<Vec<IbcEvent>, Error> {
+		let outcome = self.tx_status(tx_id.hash, tx_id.sender).await?;
+		let mut events = Vec::new();
+		for receipt in outcome
+			.receipts_outcome
+			.into_iter()
+			.filter(|receipt| receipt.outcome.executor_id == self.contract_id)
+		{
+			let height = self
+				.block(BlockReference::BlockId(BlockId::Hash(receipt.block_hash)))
+				.await?
+				.header
+				.height;
+			events.extend(ibc_events_from_logs(&receipt.outcome.logs, near_height(height)));
+		}
+		Ok(events)
+	}
+
+	/// Returns a stream of the heights of the new final blocks, polled every
+	/// [`IbcProvider::expected_block_time`].
+	pub fn final_heights(&self) -> ReceiverStream<BlockHeight> {
+		let (sender, receiver) = mpsc::channel(32);
+		let client = self.clone();
+		tokio::spawn(async move {
+			let mut last_height = 0;
+			loop {
+				match client.final_block().await {
+					Ok(block) if block.header.height > last_height => {
+						last_height = block.header.height;
+						if sender.send(last_height).await.is_err() {
+							break
+						}
+					},
+					Ok(_) => {},
+					Err(e) =>
+						log::warn!(target: "hyperspace_near", "Failed to fetch the final block of {}: {e}", client.name),
+				}
+				tokio::time::sleep(client.expected_block_time()).await;
+			}
+		});
+		ReceiverStream::new(receiver)
 	}
 }
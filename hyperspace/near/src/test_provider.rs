@@ -0,0 +1,42 @@
+use super::client::{NearClient, MAX_GAS};
+use crate::error::Error;
+use core::pin::Pin;
+use futures::Stream;
+use ibc::{
+	applications::transfer::{msgs::transfer::MsgTransfer, PrefixedCoin},
+	core::ics24_host::identifier::ChannelId,
+	tx_msg::Msg,
+};
+use primitives::TestProvider;
+
+#[async_trait::async_trait]
+impl TestProvider for NearClient {
+	/// Initiate an ibc transfer on chain.
+	async fn send_transfer(&self, msg: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
+		let outcome = self.submit_call(vec![msg.to_any()]).await?;
+		log::info!(target: "hyperspace_near", "🤝 Transfer transaction confirmed with hash: {}", outcome.transaction.hash);
+		Ok(())
+	}
+
+	/// Send a packet on an ordered channel
+	async fn send_ordered_packet(
+		&self,
+		_channel_id: ChannelId,
+		_timeout: pallet_ibc::Timeout,
+	) -> Result<(), Self::Error> {
+		Err(Error::Custom("send_ordered_packet is not implemented yet".to_string()))
+	}
+
+	/// Returns a stream that yields chain Block number
+	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
+		Box::pin(self.final_heights())
+	}
+
+	/// Increases all the IBC counters of the contract by 1, which only its testing build exposes
+	/// to its owner, e.g. when deployed to the NEAR sandbox.
+	async fn increase_counters(&mut self) -> Result<(), Self::Error> {
+		let outcome = self.call_contract("increase_counters", b"{}".to_vec(), MAX_GAS).await?;
+		log::info!(target: "hyperspace_near", "Increased the counters of {} with transaction {}", self.contract_id, outcome.transaction.hash);
+		Ok(())
+	}
+}
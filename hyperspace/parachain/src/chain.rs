@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::{error::Error, signer::ExtrinsicSigner, ParachainClient};
+use super::{connect_to_any_endpoint, error::Error, signer::ExtrinsicSigner, ParachainClient};
 use crate::{parachain::UncheckedExtrinsic, provider::TransactionId, FinalityProtocol};
 use anyhow::anyhow;
 use codec::{Decode, Encode};
@@ -33,13 +33,14 @@ use ibc::{
 	Height,
 };
 use ibc_proto::google::protobuf::Any;
+use ibc_rpc::IbcApiClient;
 use ics10_grandpa::client_message::{ClientMessage, Misbehaviour, RelayChainHeader};
 use itertools::Itertools;
-use jsonrpsee_ws_client::WsClientBuilder;
 use light_client_common::config::{EventRecordT, RuntimeCall, RuntimeTransactions};
 use pallet_ibc::light_clients::AnyClientMessage;
 use primitives::{
-	mock::LocalClientTypes, Chain, CommonClientState, IbcProvider, MisbehaviourHandler,
+	mock::LocalClientTypes, rate_limit::RpcErrorKind, Chain, CommonClientState, IbcProvider,
+	MisbehaviourHandler,
 };
 use sc_consensus_beefy_rpc::BeefyApiClient;
 use sp_core::{twox_128, H256};
@@ -47,7 +48,7 @@ use sp_runtime::{
 	traits::{IdentifyAccount, One, Verify},
 	MultiSignature, MultiSigner,
 };
-use std::{collections::BTreeMap, fmt::Display, pin::Pin, sync::Arc, time::Duration};
+use std::{collections::BTreeMap, fmt::Display, pin::Pin, time::Duration};
 use subxt::{
 	config::{
 		extrinsic_params::{BaseExtrinsicParamsBuilder, Era},
@@ -136,6 +137,38 @@ where
 		Ok(dispatch_info.weight.ref_time())
 	}
 
+	async fn filter_invalid_messages(&self, messages: Vec<Any>) -> Result<Vec<Any>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
+		let simulated = match IbcApiClient::<
+			u32,
+			H256,
+			<T as light_client_common::config::Config>::AssetId,
+		>::simulate_deliver(&*self.para_ws_client, messages.clone(), None)
+		.await
+		{
+			Ok(simulated) => simulated,
+			Err(e) => {
+				log::debug!(target: "hyperspace_parachain", "Failed to simulate deliver: {e:?}");
+				return Ok(messages)
+			},
+		};
+		Ok(messages
+			.into_iter()
+			.zip(simulated)
+			.filter_map(|(message, simulated)| match simulated.error {
+				None => Some(message),
+				Some(e) => {
+					log::warn!(
+						target: "hyperspace_parachain",
+						"Dropping {} that would fail: {e}",
+						message.type_url
+					);
+					None
+				},
+			})
+			.collect())
+	}
+
 	async fn finality_notifications(
 		&self,
 	) -> Result<
@@ -324,49 +357,66 @@ where
 	}
 
 	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error> {
-		let err_str = if let Some(rpc_err) = error.downcast_ref::<Error>() {
-			match rpc_err {
-				Error::RpcError(s) => s.clone(),
-				_ => "".to_string(),
-			}
-		} else {
-			error.to_string()
+		let kind = match error.downcast_ref::<Error>() {
+			Some(Error::JosnrpseeError(jsonrpsee::core::Error::MaxSlotsExceeded)) =>
+				RpcErrorKind::RateLimited,
+			Some(Error::JosnrpseeError(jsonrpsee::core::Error::RestartNeeded(_))) =>
+				RpcErrorKind::ConnectionLost,
+			Some(Error::RpcError(s)) => {
+				// every rpc error counts against the health of the active endpoint
+				self.parachain_endpoints.record_failure();
+				RpcErrorKind::classify(s)
+			},
+			Some(_) => RpcErrorKind::Other,
+			// include the whole chain of causes, the root cause is the one that matters
+			None => RpcErrorKind::classify(&format!("{error:#}")),
 		};
-		log::debug!(target: "hyperspace", "Handling error: {err_str}");
-
-		if err_str.contains("MaxSlotsExceeded") {
-			self.common_state.rpc_call_delay = self.common_state.rpc_call_delay * 2;
-		} else if err_str.contains("RestartNeeded") || err_str.contains("restart required") {
-			self.reconnect().await?;
-			self.common_state.rpc_call_delay = self.common_state.rpc_call_delay * 2;
+		log::debug!(target: "hyperspace", "Handling error ({kind:?}): {error}");
+
+		match kind {
+			RpcErrorKind::RateLimited => self.common_state.rate_limiter.on_rate_limited(),
+			RpcErrorKind::ConnectionLost => self.reconnect().await?,
+			RpcErrorKind::Other =>
+				if !self.parachain_endpoints.is_current_healthy() {
+					log::warn!(target: "hyperspace", "Endpoint {} of {} is unhealthy, failing over", self.parachain_rpc_url, self.name);
+					self.reconnect().await?;
+				},
 		}
 
 		Ok(())
 	}
 
 	async fn reconnect(&mut self) -> anyhow::Result<()> {
-		let relay_ws_client = Arc::new(
-			WsClientBuilder::default()
-				.build(&self.relay_chain_rpc_url)
-				.await
-				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
-		);
-		let para_ws_client = Arc::new(
-			WsClientBuilder::default()
-				.build(&self.parachain_rpc_url)
-				.await
-				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
-		);
-
-		let para_client = subxt::OnlineClient::from_rpc_client(para_ws_client.clone()).await?;
-		let relay_client = subxt::OnlineClient::from_rpc_client(relay_ws_client.clone()).await?;
-
-		self.relay_ws_client = relay_ws_client;
-		self.para_ws_client = para_ws_client;
-		self.relay_client = relay_client;
-		self.para_client = para_client;
-
-		log::info!(target: "hyperspace", "Reconnected to relay chain and parachain");
+		// penalize only the endpoints whose client lost its connection, so that healthier ones
+		// are preferred. When neither did, the error can't be pinned on one and both reconnect.
+		let relay_lost = !self.relay_ws_client.is_connected();
+		let para_lost = !self.para_ws_client.is_connected();
+		let reconnect_all = !relay_lost && !para_lost;
+
+		if relay_lost || reconnect_all {
+			if relay_lost {
+				self.relay_chain_endpoints.record_failure();
+			}
+			let (relay_chain_rpc_url, relay_ws_client) =
+				connect_to_any_endpoint(&self.relay_chain_endpoints).await?;
+			self.relay_client =
+				subxt::OnlineClient::from_rpc_client(relay_ws_client.clone()).await?;
+			self.relay_ws_client = relay_ws_client;
+			self.relay_chain_rpc_url = relay_chain_rpc_url;
+		}
+		if para_lost || reconnect_all {
+			if para_lost {
+				self.parachain_endpoints.record_failure();
+			}
+			let (parachain_rpc_url, para_ws_client) =
+				connect_to_any_endpoint(&self.parachain_endpoints).await?;
+			self.para_client = subxt::OnlineClient::from_rpc_client(para_ws_client.clone()).await?;
+			self.para_ws_client = para_ws_client;
+			self.parachain_rpc_url = parachain_rpc_url;
+		}
+		self.common_state.on_reconnect();
+
+		log::info!(target: "hyperspace", "Reconnected to relay chain ({}) and parachain ({})", self.relay_chain_rpc_url, self.parachain_rpc_url);
 
 		Ok(())
 	}
@@ -38,8 +38,7 @@ use ics11_beefy::client_message::{
 };
 use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState};
 use primitives::{
-	filter_events_by_ids, mock::LocalClientTypes, query_maximum_height_for_timeout_proofs, Chain,
-	IbcProvider, KeyProvider, UpdateType,
+	filter_events_by_ids, mock::LocalClientTypes, Chain, IbcProvider, KeyProvider, UpdateType,
 };
 use rand::Rng;
 use serde::{Deserialize, Serialize};
@@ -230,17 +229,17 @@ where
 		.map(|h| BlockNumberOrHash::Number(h))
 		.collect::<Vec<_>>();
 
-	// 1. we should query the sink chain for any outgoing packets to the source chain
-	// and return the maximum height at which we can construct non-existence proofs for
-	// all these packets on the source chain
-	let max_height_for_timeouts =
-		query_maximum_height_for_timeout_proofs(counterparty, source).await;
-	let timeout_update_required = if let Some(max_height) = max_height_for_timeouts {
-		let max_height = max_height as u32;
-		finalized_blocks.contains(&max_height)
-	} else {
-		false
-	};
+	// 1. find the first finalized block at which the timeout of some packet sent from the
+	// counterparty chain becomes provable, so we can construct its non-existence proof on the
+	// counterparty
+	let height_for_timeouts = source
+		.common_state()
+		.pending_timeouts
+		.earliest_provable_height()
+		.and_then(|height| {
+			finalized_blocks.iter().copied().filter(|block| *block as u64 >= height).min()
+		});
+	let timeout_update_required = height_for_timeouts.is_some();
 
 	let latest_finalized_block = finalized_blocks.into_iter().max().unwrap_or_default();
 
@@ -302,13 +301,11 @@ where
 		})
 		.collect();
 
-	if timeout_update_required {
-		let max_height_for_timeouts = max_height_for_timeouts.unwrap();
-		if max_height_for_timeouts > client_state.latest_height().revision_height {
-			let max_timeout_height = <<T as subxt::Config>::Header as Header>::Number::from(
-				max_height_for_timeouts as u32,
-			);
-			headers_with_events.insert(max_timeout_height);
+	if let Some(height_for_timeouts) = height_for_timeouts {
+		if height_for_timeouts as u64 > client_state.latest_height().revision_height {
+			let timeout_height =
+				<<T as subxt::Config>::Header as Header>::Number::from(height_for_timeouts);
+			headers_with_events.insert(timeout_height);
 		}
 	}
 
@@ -563,17 +560,17 @@ where
 		.map(|h| BlockNumberOrHash::Number(*h))
 		.collect::<Vec<_>>();
 
-	// 1. we should query the sink chain for any outgoing packets to the source chain
-	// and return the maximum height at which we can construct non-existence proofs for
-	// all these packets on the source chain
-	let max_height_for_timeouts =
-		query_maximum_height_for_timeout_proofs(counterparty, source).await;
-	let timeout_update_required = if let Some(max_height) = max_height_for_timeouts {
-		let max_height = max_height as u32;
-		finalized_blocks.contains(&max_height)
-	} else {
-		false
-	};
+	// 1. find the first finalized block at which the timeout of some packet sent from the
+	// counterparty chain becomes provable, so we can construct its non-existence proof on the
+	// counterparty
+	let height_for_timeouts = source
+		.common_state()
+		.pending_timeouts
+		.earliest_provable_height()
+		.and_then(|height| {
+			finalized_blocks.iter().copied().filter(|block| *block as u64 >= height).min()
+		});
+	let timeout_update_required = height_for_timeouts.is_some();
 
 	// block_number => events
 	let events: HashMap<String, Vec<IbcEvent>> = IbcApiClient::<
@@ -619,13 +616,11 @@ where
 		})
 		.collect();
 
-	if timeout_update_required {
-		let max_height_for_timeouts = max_height_for_timeouts.unwrap();
-		if max_height_for_timeouts > client_state.latest_height().revision_height {
-			let max_timeout_height = <<T as subxt::Config>::Header as Header>::Number::from(
-				max_height_for_timeouts as u32,
-			);
-			headers_with_events.insert(max_timeout_height);
+	if let Some(height_for_timeouts) = height_for_timeouts {
+		if height_for_timeouts as u64 > client_state.latest_height().revision_height {
+			let timeout_height =
+				<<T as subxt::Config>::Header as Header>::Number::from(height_for_timeouts);
+			headers_with_events.insert(timeout_height);
 		}
 	}
 
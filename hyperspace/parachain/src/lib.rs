@@ -63,7 +63,10 @@ use jsonrpsee_ws_client::WsClientBuilder;
 use light_client_common::config::{AsInner, RuntimeStorage};
 use pallet_ibc::light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager};
 use pallet_mmr_primitives::Proof;
-use primitives::{CommonClientState, KeyProvider};
+use primitives::{
+	cache::QueryCache, endpoints::EndpointPool, rate_limit::RateLimiter, CommonClientConfig,
+	CommonClientState, KeyProvider,
+};
 use sc_keystore::LocalKeystore;
 use sp_core::{ecdsa, ed25519, sr25519, Bytes, Pair, H256};
 use sp_keystore::KeystorePtr;
@@ -91,6 +94,10 @@ pub struct ParachainClient<T: light_client_common::config::Config> {
 	pub parachain_rpc_url: String,
 	/// rpc url for relay chain
 	pub relay_chain_rpc_url: String,
+	/// All the configured parachain endpoints, including the active one
+	pub parachain_endpoints: EndpointPool<String>,
+	/// All the configured relay chain endpoints, including the active one
+	pub relay_chain_endpoints: EndpointPool<String>,
 	/// Relay chain rpc client
 	pub relay_client: subxt::OnlineClient<T>,
 	/// Parachain rpc client
@@ -168,6 +175,12 @@ pub struct ParachainClientConfig {
 	pub parachain_rpc_url: String,
 	/// rpc url for relay chain
 	pub relay_chain_rpc_url: String,
+	/// Parachain rpc urls to fail over to when the primary one is unavailable
+	#[serde(default)]
+	pub fallback_parachain_rpc_urls: Vec<String>,
+	/// Relay chain rpc urls to fail over to when the primary one is unavailable
+	#[serde(default)]
+	pub fallback_relay_chain_rpc_urls: Vec<String>,
 	/// Light client id on counterparty chain
 	pub client_id: Option<ClientId>,
 	/// Connection Id
@@ -187,6 +200,31 @@ pub struct ParachainClientConfig {
 	/// All the client states and headers will be wrapped in WASM ones using the WASM code ID.
 	#[serde(default)]
 	pub wasm_code_id: Option<String>,
+	/// Common client config
+	#[serde(flatten)]
+	pub common: CommonClientConfig,
+}
+
+/// Connects to the healthiest reachable endpoint of the pool, marking it as the active one.
+/// Unreachable endpoints are penalized.
+pub async fn connect_to_any_endpoint(
+	endpoints: &EndpointPool<String>,
+) -> Result<(String, Arc<jsonrpsee_ws_client::WsClient>), Error> {
+	let mut last_error = None;
+	for (index, url) in endpoints.candidates() {
+		match WsClientBuilder::default().build(&url).await {
+			Ok(client) => {
+				endpoints.select(index);
+				return Ok((url, Arc::new(client)))
+			},
+			Err(e) => {
+				log::warn!(target: "hyperspace_parachain", "Failed to connect to {url}: {e:?}");
+				endpoints.record_failure_at(index);
+				last_error = Some(Error::from(format!("Rpc Error {:?}", e)));
+			},
+		}
+	}
+	Err(last_error.unwrap_or_else(|| Error::RpcError("no endpoints configured".to_string())))
 }
 
 impl<T> ParachainClient<T>
@@ -195,18 +233,18 @@ where
 {
 	/// Initializes a [`ParachainClient`] given a [`ParachainConfig`]
 	pub async fn new(config: ParachainClientConfig) -> Result<Self, Error> {
-		let relay_ws_client = Arc::new(
-			WsClientBuilder::default()
-				.build(&config.relay_chain_rpc_url)
-				.await
-				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
+		let relay_chain_endpoints = EndpointPool::new(
+			config.relay_chain_rpc_url.clone(),
+			config.fallback_relay_chain_rpc_urls.clone(),
 		);
-		let para_ws_client = Arc::new(
-			WsClientBuilder::default()
-				.build(&config.parachain_rpc_url)
-				.await
-				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?,
+		let parachain_endpoints = EndpointPool::new(
+			config.parachain_rpc_url.clone(),
+			config.fallback_parachain_rpc_urls.clone(),
 		);
+		let (relay_chain_rpc_url, relay_ws_client) =
+			connect_to_any_endpoint(&relay_chain_endpoints).await?;
+		let (parachain_rpc_url, para_ws_client) =
+			connect_to_any_endpoint(&parachain_endpoints).await?;
 
 		let para_client = subxt::OnlineClient::from_rpc_client(para_ws_client.clone()).await?;
 
@@ -244,8 +282,10 @@ where
 		assert!(key_store.has_keys(&[(public_key.as_ref().to_vec(), key_type_id)]));
 		Ok(Self {
 			name: config.name,
-			parachain_rpc_url: config.parachain_rpc_url,
-			relay_chain_rpc_url: config.relay_chain_rpc_url,
+			parachain_rpc_url,
+			relay_chain_rpc_url,
+			parachain_endpoints,
+			relay_chain_endpoints,
 			para_client,
 			relay_client,
 			para_id: config.para_id,
@@ -262,11 +302,18 @@ where
 			channel_whitelist: Arc::new(Mutex::new(config.channel_whitelist.into_iter().collect())),
 			finality_protocol: config.finality_protocol,
 			common_state: CommonClientState {
-				skip_optional_client_updates: true,
+				skip_optional_client_updates: config.common.skip_optional_client_updates,
 				maybe_has_undelivered_packets: Arc::new(Mutex::new(Default::default())),
-				rpc_call_delay: DEFAULT_RPC_CALL_DELAY,
-				initial_rpc_call_delay: DEFAULT_RPC_CALL_DELAY,
+				rate_limiter: RateLimiter::new(config.common.rate_limit),
+				query_cache: QueryCache::new(config.common.query_cache),
+				fee_policies: config
+					.common
+					.fee_policies
+					.iter()
+					.map(|p| ((p.channel_id, p.port_id.clone()), p.policy.clone()))
+					.collect(),
 				misbehaviour_client_msg_queue: Arc::new(AsyncMutex::new(vec![])),
+				max_packets_to_process: config.common.max_packets_to_process as usize,
 				..Default::default()
 			},
 		})
@@ -298,7 +345,7 @@ where
 			para_client: self.para_client.clone(),
 			para_ws_client,
 			para_id: self.para_id,
-			rpc_call_delay: self.common_state.rpc_call_delay,
+			rpc_call_delay: DEFAULT_RPC_CALL_DELAY,
 		}
 	}
 
@@ -583,7 +630,7 @@ where
 			para_client: self.para_client.clone(),
 			para_ws_client,
 			para_id: self.para_id,
-			rpc_call_delay: self.common_state.rpc_call_delay,
+			rpc_call_delay: DEFAULT_RPC_CALL_DELAY,
 		};
 		let api = self.relay_client.storage();
 		let para_client_api = self.para_client.storage();
@@ -27,38 +27,42 @@ use ibc::{
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
 	events::IbcEvent,
+	signer::Signer,
 	timestamp::Timestamp,
 	Height,
 };
 use ibc_proto::{
+	cosmos::base::query::v1beta1::{PageRequest, PageResponse},
 	google::protobuf::Any,
 	ibc::core::{
 		channel::v1::{
 			IdentifiedChannel, QueryChannelResponse, QueryChannelsResponse,
 			QueryNextSequenceReceiveResponse, QueryPacketAcknowledgementResponse,
-			QueryPacketCommitmentResponse, QueryPacketReceiptResponse,
-		},
-		client::v1::{
-			IdentifiedClientState, QueryClientStateResponse, QueryConsensusStateResponse,
+			QueryPacketCommitmentResponse, QueryPacketReceiptResponse, QueryUpgradeErrorResponse,
+			QueryUpgradeResponse,
 		},
+		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
 		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
 	},
 };
-use ibc_rpc::{IbcApiClient, PacketInfo};
+use ibc_rpc::{BlockNumberOrHash, IbcApiClient, PacketInfo};
 use ics11_beefy::client_state::ClientState as BeefyClientState;
 use light_client_common::config::{AsInnerEvent, IbcEventsT, RuntimeStorage};
 use pallet_ibc::{
 	light_clients::{AnyClientState, AnyConsensusState, HostFunctionsManager},
 	HostConsensusProof,
 };
-use primitives::{apply_prefix, Chain, IbcProvider, KeyProvider, UpdateType};
+use primitives::{
+	apply_prefix, fees::PacketFees, filter_events_by_ids, Chain, IbcProvider, KeyProvider,
+	UpdateType,
+};
 use sp_core::H256;
 use sp_runtime::{
 	traits::{IdentifyAccount, One, Verify},
 	MultiSignature, MultiSigner,
 };
 use std::{
-	collections::{BTreeMap, HashSet},
+	collections::{BTreeMap, HashMap, HashSet},
 	fmt::Display,
 	pin::Pin,
 	str::FromStr,
@@ -69,6 +73,20 @@ use subxt::config::{
 };
 use tokio_stream::wrappers::ReceiverStream;
 
+/// Number of items requested per page from the paginated rpc queries
+const PAGE_LIMIT: u64 = 500;
+
+/// Returns the request of the page following the one of `page` if there's any, given the
+/// pagination of its response.
+fn next_page(mut page: PageRequest, response: Option<PageResponse>) -> Option<PageRequest> {
+	let next_key = response?.next_key;
+	if next_key.is_empty() {
+		return None
+	}
+	page.key = next_key;
+	Some(page)
+}
+
 #[derive(Debug)]
 pub struct TransactionId<Hash> {
 	pub ext_hash: Hash,
@@ -120,12 +138,57 @@ where
 	where
 		C: Chain,
 	{
+		self.common_state.rate_limiter.acquire().await;
 		self.finality_protocol
 			.clone()
 			.query_latest_ibc_events(self, finality_event, counterparty)
 			.await
 	}
 
+	async fn query_ibc_events_in_range<C>(
+		&self,
+		from: u64,
+		to: u64,
+		counterparty: &C,
+	) -> Result<Vec<IbcEvent>, anyhow::Error>
+	where
+		C: Chain,
+	{
+		self.common_state.rate_limiter.acquire().await;
+		let block_numbers =
+			(from as u32..=to as u32).map(BlockNumberOrHash::Number).collect::<Vec<_>>();
+		// block_number => events
+		let events: HashMap<String, Vec<IbcEvent>> = IbcApiClient::<
+			u32,
+			H256,
+			<T as light_client_common::config::Config>::AssetId,
+		>::query_events(
+			&*self.para_ws_client, block_numbers
+		)
+		.await?;
+
+		let mut channel_and_port_ids = self.channel_whitelist();
+		channel_and_port_ids.extend(counterparty.channel_whitelist());
+		let client_ids = [self.client_id(), counterparty.client_id()];
+		let connection_ids = [self.connection_id(), counterparty.connection_id()]
+			.into_iter()
+			.flatten()
+			.collect::<Vec<_>>();
+		// header number is serialized to string
+		let mut events = events
+			.into_iter()
+			.filter_map(|(num, events)| Some((str::parse::<u32>(&num).ok()?, events)))
+			.collect::<Vec<_>>();
+		events.sort_by_key(|(num, _)| *num);
+		Ok(events
+			.into_iter()
+			.flat_map(|(_, events)| events)
+			.filter(|e| {
+				filter_events_by_ids(e, &client_ids, &connection_ids, &channel_and_port_ids)
+			})
+			.collect())
+	}
+
 	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
 		use futures::StreamExt;
 
@@ -186,6 +249,7 @@ where
 		client_id: ClientId,
 		consensus_height: Height,
 	) -> Result<QueryConsensusStateResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let res = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_client_consensus_state(
 			&*self.para_ws_client,
 			Some(at.revision_height as u32),
@@ -204,6 +268,7 @@ where
 		at: Height,
 		client_id: ClientId,
 	) -> Result<QueryClientStateResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let response =
 			IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_client_state(
 				&*self.para_ws_client,
@@ -220,6 +285,7 @@ where
 		at: Height,
 		connection_id: ConnectionId,
 	) -> Result<QueryConnectionResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let response = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_connection(
 			&*self.para_ws_client,
 			at.revision_height as u32,
@@ -236,6 +302,7 @@ where
 		channel_id: ChannelId,
 		port_id: PortId,
 	) -> Result<QueryChannelResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let response = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_channel(
 			&*self.para_ws_client,
 			at.revision_height as u32,
@@ -247,10 +314,47 @@ where
 		Ok(response)
 	}
 
+	async fn query_channel_upgrade(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
+		let response = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_channel_upgrade(
+			&*self.para_ws_client,
+			at.revision_height as u32,
+			channel_id.to_string(),
+			port_id.to_string(),
+		)
+		.await
+		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		Ok(response)
+	}
+
+	async fn query_channel_upgrade_error(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeErrorResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
+		let response = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_channel_upgrade_error(
+			&*self.para_ws_client,
+			at.revision_height as u32,
+			channel_id.to_string(),
+			port_id.to_string(),
+		)
+		.await
+		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+		Ok(response)
+	}
+
 	/// Query the proof of the given keys at the given height.
 	///
 	/// Note: all the keys will be prefixed with the connection prefix.
 	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let prefix = self.connection_prefix().into_vec();
 		let prefixed_keys =
 			keys.into_iter().map(|path| apply_prefix(prefix.clone(), path)).collect();
@@ -272,6 +376,7 @@ where
 		channel_id: &ChannelId,
 		seq: u64,
 	) -> Result<QueryPacketCommitmentResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let res =
 			IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_packet_commitment(
 				&*self.para_ws_client,
@@ -292,6 +397,7 @@ where
 		channel_id: &ChannelId,
 		seq: u64,
 	) -> Result<QueryPacketAcknowledgementResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let res = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_packet_acknowledgement(
 			&*self.para_ws_client,
 			at.revision_height as u32,
@@ -310,6 +416,7 @@ where
 		port_id: &PortId,
 		channel_id: &ChannelId,
 	) -> Result<QueryNextSequenceReceiveResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let res = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_next_seq_recv(
 			&*self.para_ws_client,
 			at.revision_height as u32,
@@ -328,6 +435,7 @@ where
 		channel_id: &ChannelId,
 		seq: u64,
 	) -> Result<QueryPacketReceiptResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let res = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_packet_receipt(
 			&*self.para_ws_client,
 			at.revision_height as u32,
@@ -341,6 +449,7 @@ where
 	}
 
 	async fn latest_height_and_timestamp(&self) -> Result<(Height, Timestamp), Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let finalized_header = self
 			.para_client
 			.rpc()
@@ -374,16 +483,24 @@ where
 		channel_id: ChannelId,
 		port_id: PortId,
 	) -> Result<Vec<u64>, Self::Error> {
-		let res =
-			IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_packet_commitments(
-				&*self.para_ws_client,
-				at.revision_height as u32,
-				channel_id.to_string(),
-				port_id.to_string(),
-			)
-			.await
-			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
-		Ok(res.commitments.into_iter().map(|packet_state| packet_state.sequence).collect())
+		let mut sequences = vec![];
+		let mut page = Some(PageRequest { limit: PAGE_LIMIT, ..Default::default() });
+		while let Some(request) = page {
+			self.common_state.rate_limiter.acquire().await;
+			let res =
+				IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_packet_commitments(
+					&*self.para_ws_client,
+					at.revision_height as u32,
+					channel_id.to_string(),
+					port_id.to_string(),
+					Some(request.clone()),
+				)
+				.await
+				.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+			sequences.extend(res.commitments.into_iter().map(|packet_state| packet_state.sequence));
+			page = next_page(request, res.pagination);
+		}
+		Ok(sequences)
 	}
 
 	async fn query_packet_acknowledgements(
@@ -392,19 +509,24 @@ where
 		channel_id: ChannelId,
 		port_id: PortId,
 	) -> Result<Vec<u64>, Self::Error> {
-		let res = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_packet_acknowledgements(
-			&*self.para_ws_client,
-			at.revision_height as u32,
-			channel_id.to_string(),
-			port_id.to_string(),
-		)
-		.await
-		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
-		Ok(res
-			.acknowledgements
-			.into_iter()
-			.map(|packet_state| packet_state.sequence)
-			.collect())
+		let mut sequences = vec![];
+		let mut page = Some(PageRequest { limit: PAGE_LIMIT, ..Default::default() });
+		while let Some(request) = page {
+			self.common_state.rate_limiter.acquire().await;
+			let res = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_packet_acknowledgements(
+				&*self.para_ws_client,
+				at.revision_height as u32,
+				channel_id.to_string(),
+				port_id.to_string(),
+				Some(request.clone()),
+			)
+			.await
+			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+			sequences
+				.extend(res.acknowledgements.into_iter().map(|packet_state| packet_state.sequence));
+			page = next_page(request, res.pagination);
+		}
+		Ok(sequences)
 	}
 
 	async fn query_unreceived_packets(
@@ -414,6 +536,7 @@ where
 		port_id: PortId,
 		seqs: Vec<u64>,
 	) -> Result<Vec<u64>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let res =
 			IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_unreceived_packets(
 				&*self.para_ws_client,
@@ -434,6 +557,7 @@ where
 		port_id: PortId,
 		seqs: Vec<u64>,
 	) -> Result<Vec<u64>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		log::trace!(
 			target: "hyperspace_parachain",
 			"query_unreceived_acknowledgements at: {:?}, channel_id: {:?}, port_id: {:?}, seqs: {:?}",
@@ -463,6 +587,7 @@ where
 		at: Height,
 		connection_id: &ConnectionId,
 	) -> Result<QueryChannelsResponse, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let response =
 			IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_connection_channels(
 				&*self.para_ws_client,
@@ -480,6 +605,7 @@ where
 		port_id: PortId,
 		seqs: Vec<u64>,
 	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let response =
 			IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_send_packets(
 				&*self.para_ws_client,
@@ -499,6 +625,7 @@ where
 		port_id: PortId,
 		seqs: Vec<u64>,
 	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let response =
 			IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_recv_packets(
 				&*self.para_ws_client,
@@ -521,6 +648,7 @@ where
 		client_id: ClientId,
 		client_height: Height,
 	) -> Result<(Height, Timestamp), Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		log::trace!(
 			target: "hyperspace_parachain",
 			"Querying client update time and height for client {:?} at height {:?}",
@@ -546,6 +674,7 @@ where
 		&self,
 		client_state: &AnyClientState,
 	) -> Result<Option<Vec<u8>>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let hash = self
 			.para_client
 			.rpc()
@@ -579,6 +708,7 @@ where
 		&self,
 		asset_id: Self::AssetId,
 	) -> Result<Vec<PrefixedCoin>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let account = self.public_key.clone().into_account();
 		let account = subxt::utils::AccountId32::from(<[u8; 32]>::from(account));
 		let mut hex_string = hex::encode(account.0.to_vec());
@@ -624,6 +754,7 @@ where
 	}
 
 	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let subxt_block_number: subxt::rpc::types::BlockNumber = block_number.into();
 		let block_hash =
 			self.para_client.rpc().block_hash(Some(subxt_block_number)).await?.ok_or_else(
@@ -643,40 +774,51 @@ where
 	}
 
 	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
-		let response: Vec<IdentifiedClientState> = IbcApiClient::<
-			u32,
-			H256,
-			<T as light_client_common::config::Config>::AssetId,
-		>::query_clients(&*self.para_ws_client)
-		.await
-		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
-		response
-			.into_iter()
-			.map(|client| {
-				ClientId::from_str(&client.client_id)
-					.map_err(|_| Error::Custom("Invalid client id ".to_string()))
-			})
-			.collect()
+		let mut client_ids = vec![];
+		let mut page = Some(PageRequest { limit: PAGE_LIMIT, ..Default::default() });
+		while let Some(request) = page {
+			self.common_state.rate_limiter.acquire().await;
+			let response = IbcApiClient::<
+				u32,
+				H256,
+				<T as light_client_common::config::Config>::AssetId,
+			>::query_clients(&*self.para_ws_client, Some(request.clone()))
+			.await
+			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+			for client in response.client_states {
+				client_ids.push(
+					ClientId::from_str(&client.client_id)
+						.map_err(|_| Error::Custom("Invalid client id ".to_string()))?,
+				);
+			}
+			page = next_page(request, response.pagination);
+		}
+		Ok(client_ids)
 	}
 
 	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
-		let response = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_channels(
-			&*self.para_ws_client,
-		)
-		.await
-		.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
-		response
-			.channels
-			.into_iter()
-			.map(|identified_chan| {
-				Ok((
+		let mut channels = vec![];
+		let mut page = Some(PageRequest { limit: PAGE_LIMIT, ..Default::default() });
+		while let Some(request) = page {
+			self.common_state.rate_limiter.acquire().await;
+			let response = IbcApiClient::<
+				u32,
+				H256,
+				<T as light_client_common::config::Config>::AssetId,
+			>::query_channels(&*self.para_ws_client, Some(request.clone()))
+			.await
+			.map_err(|e| Error::from(format!("Rpc Error {:?}", e)))?;
+			channels.extend(response.channels.into_iter().map(|identified_chan| {
+				(
 					ChannelId::from_str(&identified_chan.channel_id)
 						.expect("Failed to convert invalid string to channel id"),
 					PortId::from_str(&identified_chan.port_id)
 						.expect("Failed to convert invalid string to port id"),
-				))
-			})
-			.collect::<Result<Vec<_>, _>>()
+				)
+			}));
+			page = next_page(request, response.pagination);
+		}
+		Ok(channels)
 	}
 
 	async fn query_connection_using_client(
@@ -684,6 +826,7 @@ where
 		height: u32,
 		client_id: String,
 	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let response = IbcApiClient::<u32, H256, <T as light_client_common::config::Config>::AssetId>::query_connection_using_client(
 			&*self.para_ws_client,
 			height,
@@ -700,6 +843,7 @@ where
 		latest_height: u64,
 		latest_client_height_on_counterparty: u64,
 	) -> Result<bool, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		let prover = self.grandpa_prover();
 		let session_length = prover
 			.session_length()
@@ -717,6 +861,7 @@ where
 	async fn initialize_client_state(
 		&self,
 	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		match self.finality_protocol {
 			FinalityProtocol::Grandpa => {
 				let res = self.construct_grandpa_client_state().await?;
@@ -733,6 +878,7 @@ where
 		&self,
 		tx_id: Self::TransactionId,
 	) -> Result<ClientId, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		// Query newly created client Id
 		let TransactionId { ext_hash, block_hash } = tx_id;
 		let identified_client_state = IbcApiClient::<
@@ -754,6 +900,7 @@ where
 		&self,
 		tx_id: Self::TransactionId,
 	) -> Result<ConnectionId, Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		// Query newly created connection Id
 		let TransactionId { ext_hash, block_hash } = tx_id;
 		let identified_connection: IdentifiedConnection = IbcApiClient::<
@@ -777,6 +924,7 @@ where
 		&self,
 		tx_id: Self::TransactionId,
 	) -> Result<(ChannelId, PortId), Self::Error> {
+		self.common_state.rate_limiter.acquire().await;
 		// Query newly created channel Id
 		let TransactionId { ext_hash, block_hash } = tx_id;
 		let identified_channel: IdentifiedChannel = IbcApiClient::<
@@ -814,4 +962,25 @@ where
 	async fn upload_wasm(&self, _wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error> {
 		Err(Error::Custom("Uploading WASM to parachain is not supported".to_string()))
 	}
+
+	async fn query_incentivized_packets(
+		&self,
+		_channel_id: ChannelId,
+		_port_id: PortId,
+	) -> Result<HashMap<u64, PacketFees>, Self::Error> {
+		Err(Error::Custom(
+			"Querying incentivized packets on parachain is not supported".to_string(),
+		))
+	}
+
+	async fn register_counterparty_payee(
+		&self,
+		_channel_id: ChannelId,
+		_port_id: PortId,
+		_counterparty_payee: Signer,
+	) -> Result<(), Self::Error> {
+		Err(Error::Custom(
+			"Registering a counterparty payee on parachain is not supported".to_string(),
+		))
+	}
 }
@@ -0,0 +1,729 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Height-keyed cache of the query results of a chain.
+//!
+//! Within a relayer cycle, and across the two relaying directions, the same proofs, client states
+//! and channel ends are queried at identical heights. The state of a chain at a given height never
+//! changes, so [`CachedProvider`] wraps any [`IbcProvider`] and serves the repeated queries from a
+//! [`QueryCache`]. The cache is bounded by the number of entries, and evicts whole heights,
+//! starting from the lowest one.
+
+use crate::{
+	fees::PacketFees, Chain, CommonClientState, IbcProvider, KeyProvider, LightClientSync,
+	MisbehaviourHandler, UpdateType,
+};
+use futures::Stream;
+#[cfg(any(feature = "testing", test))]
+use ibc::applications::transfer::msgs::transfer::MsgTransfer;
+use ibc::{
+	applications::transfer::PrefixedCoin,
+	core::{
+		ics02_client::{client_state::ClientType, events::UpdateClient},
+		ics23_commitment::commitment::CommitmentPrefix,
+		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+	},
+	events::IbcEvent,
+	signer::Signer,
+	timestamp::Timestamp,
+	Height,
+};
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::core::{
+		channel::v1::{
+			QueryChannelResponse, QueryChannelsResponse, QueryNextSequenceReceiveResponse,
+			QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
+			QueryPacketReceiptResponse, QueryUpgradeErrorResponse, QueryUpgradeResponse,
+		},
+		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
+		connection::v1::{IdentifiedConnection, QueryConnectionResponse},
+	},
+};
+use ibc_rpc::PacketInfo;
+use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::{BTreeMap, HashMap, HashSet},
+	ops::{Deref, DerefMut},
+	pin::Pin,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+fn default_capacity() -> usize {
+	10_000
+}
+
+/// Query cache parameters.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct QueryCacheConfig {
+	/// Maximum number of cached query results. A value of zero disables caching.
+	#[serde(default = "default_capacity")]
+	pub capacity: usize,
+}
+
+impl Default for QueryCacheConfig {
+	fn default() -> Self {
+		Self { capacity: default_capacity() }
+	}
+}
+
+/// A cached query, without the height.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum QueryKey {
+	Proof(Vec<Vec<u8>>),
+	ClientState(ClientId),
+	ChannelEnd(ChannelId, PortId),
+}
+
+#[derive(Debug, Clone)]
+enum QueryValue {
+	Proof(Vec<u8>),
+	ClientState(QueryClientStateResponse),
+	ChannelEnd(QueryChannelResponse),
+}
+
+/// Snapshot of the cache counters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryCacheStats {
+	/// Number of queries served from the cache.
+	pub hits: u64,
+	/// Number of queries sent to the chain.
+	pub misses: u64,
+	/// Number of results currently in the cache.
+	pub entries: usize,
+}
+
+impl QueryCacheStats {
+	/// Fraction of queries served from the cache.
+	pub fn hit_ratio(&self) -> f64 {
+		let total = self.hits + self.misses;
+		if total == 0 {
+			return 0.0
+		}
+		self.hits as f64 / total as f64
+	}
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+	heights: BTreeMap<Height, HashMap<QueryKey, QueryValue>>,
+	stats: QueryCacheStats,
+}
+
+/// Size-bounded cache of query results, keyed by the height they were queried at. The state is
+/// shared between clones.
+#[derive(Debug, Clone)]
+pub struct QueryCache {
+	capacity: usize,
+	entries: Arc<Mutex<Entries>>,
+}
+
+impl QueryCache {
+	pub fn new(config: QueryCacheConfig) -> Self {
+		Self { capacity: config.capacity, entries: Default::default() }
+	}
+
+	fn get(&self, at: Height, key: &QueryKey) -> Option<QueryValue> {
+		if self.capacity == 0 {
+			return None
+		}
+		let mut entries = self.entries.lock().unwrap();
+		let value = entries.heights.get(&at).and_then(|queries| queries.get(key)).cloned();
+		if value.is_some() {
+			entries.stats.hits += 1;
+		} else {
+			entries.stats.misses += 1;
+		}
+		value
+	}
+
+	fn insert(&self, at: Height, key: QueryKey, value: QueryValue) {
+		if self.capacity == 0 {
+			return
+		}
+		let mut entries = self.entries.lock().unwrap();
+		if entries.heights.entry(at).or_default().insert(key, value).is_none() {
+			entries.stats.entries += 1;
+		}
+		while entries.stats.entries > self.capacity {
+			let Some((_, evicted)) = entries.heights.pop_first() else { break };
+			entries.stats.entries -= evicted.len();
+		}
+	}
+
+	/// Removes the results queried at the given height.
+	pub fn invalidate(&self, at: Height) {
+		let mut entries = self.entries.lock().unwrap();
+		if let Some(evicted) = entries.heights.remove(&at) {
+			entries.stats.entries -= evicted.len();
+		}
+	}
+
+	/// Removes all the results. The counters are preserved.
+	pub fn clear(&self) {
+		let mut entries = self.entries.lock().unwrap();
+		entries.heights.clear();
+		entries.stats.entries = 0;
+	}
+
+	pub fn stats(&self) -> QueryCacheStats {
+		self.entries.lock().unwrap().stats.clone()
+	}
+}
+
+impl Default for QueryCache {
+	fn default() -> Self {
+		Self::new(QueryCacheConfig::default())
+	}
+}
+
+/// Adaptor that serves `query_proof`, `query_client_state` and `query_channel_end` of the wrapped
+/// provider from the [`QueryCache`] of its [`CommonClientState`]. All the other calls are
+/// forwarded as is.
+#[derive(Debug, Clone)]
+pub struct CachedProvider<C> {
+	pub inner: C,
+}
+
+impl<C> CachedProvider<C> {
+	pub fn new(inner: C) -> Self {
+		Self { inner }
+	}
+
+	pub fn into_inner(self) -> C {
+		self.inner
+	}
+}
+
+impl<C> Deref for CachedProvider<C> {
+	type Target = C;
+
+	fn deref(&self) -> &Self::Target {
+		&self.inner
+	}
+}
+
+impl<C> DerefMut for CachedProvider<C> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.inner
+	}
+}
+
+#[async_trait::async_trait]
+impl<C: Chain> IbcProvider for CachedProvider<C> {
+	type FinalityEvent = C::FinalityEvent;
+	type TransactionId = C::TransactionId;
+	type AssetId = C::AssetId;
+	type Error = C::Error;
+
+	async fn query_latest_ibc_events<T>(
+		&mut self,
+		finality_event: Self::FinalityEvent,
+		counterparty: &T,
+	) -> Result<Vec<(Any, Height, Vec<IbcEvent>, UpdateType)>, anyhow::Error>
+	where
+		T: Chain,
+	{
+		self.inner.query_latest_ibc_events(finality_event, counterparty).await
+	}
+
+	async fn query_ibc_events_in_range<T>(
+		&self,
+		from: u64,
+		to: u64,
+		counterparty: &T,
+	) -> Result<Vec<IbcEvent>, anyhow::Error>
+	where
+		T: Chain,
+	{
+		self.inner.query_ibc_events_in_range(from, to, counterparty).await
+	}
+
+	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>> {
+		self.inner.ibc_events().await
+	}
+
+	async fn query_client_consensus(
+		&self,
+		at: Height,
+		client_id: ClientId,
+		consensus_height: Height,
+	) -> Result<QueryConsensusStateResponse, Self::Error> {
+		self.inner.query_client_consensus(at, client_id, consensus_height).await
+	}
+
+	async fn query_client_state(
+		&self,
+		at: Height,
+		client_id: ClientId,
+	) -> Result<QueryClientStateResponse, Self::Error> {
+		let cache = &self.inner.common_state().query_cache;
+		let key = QueryKey::ClientState(client_id.clone());
+		if let Some(QueryValue::ClientState(response)) = cache.get(at, &key) {
+			return Ok(response)
+		}
+		let response = self.inner.query_client_state(at, client_id).await?;
+		cache.insert(at, key, QueryValue::ClientState(response.clone()));
+		Ok(response)
+	}
+
+	async fn query_connection_end(
+		&self,
+		at: Height,
+		connection_id: ConnectionId,
+	) -> Result<QueryConnectionResponse, Self::Error> {
+		self.inner.query_connection_end(at, connection_id).await
+	}
+
+	async fn query_channel_end(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryChannelResponse, Self::Error> {
+		let cache = &self.inner.common_state().query_cache;
+		let key = QueryKey::ChannelEnd(channel_id, port_id.clone());
+		if let Some(QueryValue::ChannelEnd(response)) = cache.get(at, &key) {
+			return Ok(response)
+		}
+		let response = self.inner.query_channel_end(at, channel_id, port_id).await?;
+		cache.insert(at, key, QueryValue::ChannelEnd(response.clone()));
+		Ok(response)
+	}
+
+	async fn query_channel_upgrade(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeResponse, Self::Error> {
+		self.inner.query_channel_upgrade(at, channel_id, port_id).await
+	}
+
+	async fn query_channel_upgrade_error(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeErrorResponse, Self::Error> {
+		self.inner.query_channel_upgrade_error(at, channel_id, port_id).await
+	}
+
+	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error> {
+		let cache = &self.inner.common_state().query_cache;
+		let key = QueryKey::Proof(keys.clone());
+		if let Some(QueryValue::Proof(proof)) = cache.get(at, &key) {
+			return Ok(proof)
+		}
+		let proof = self.inner.query_proof(at, keys).await?;
+		cache.insert(at, key, QueryValue::Proof(proof.clone()));
+		Ok(proof)
+	}
+
+	async fn query_packet_commitment(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketCommitmentResponse, Self::Error> {
+		self.inner.query_packet_commitment(at, port_id, channel_id, seq).await
+	}
+
+	async fn query_packet_acknowledgement(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketAcknowledgementResponse, Self::Error> {
+		self.inner.query_packet_acknowledgement(at, port_id, channel_id, seq).await
+	}
+
+	async fn query_next_sequence_recv(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+	) -> Result<QueryNextSequenceReceiveResponse, Self::Error> {
+		self.inner.query_next_sequence_recv(at, port_id, channel_id).await
+	}
+
+	async fn query_packet_receipt(
+		&self,
+		at: Height,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		seq: u64,
+	) -> Result<QueryPacketReceiptResponse, Self::Error> {
+		self.inner.query_packet_receipt(at, port_id, channel_id, seq).await
+	}
+
+	async fn latest_height_and_timestamp(&self) -> Result<(Height, Timestamp), Self::Error> {
+		self.inner.latest_height_and_timestamp().await
+	}
+
+	async fn query_packet_commitments(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.inner.query_packet_commitments(at, channel_id, port_id).await
+	}
+
+	async fn query_packet_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.inner.query_packet_acknowledgements(at, channel_id, port_id).await
+	}
+
+	async fn query_unreceived_packets(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.inner.query_unreceived_packets(at, channel_id, port_id, seqs).await
+	}
+
+	async fn query_unreceived_acknowledgements(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<u64>, Self::Error> {
+		self.inner
+			.query_unreceived_acknowledgements(at, channel_id, port_id, seqs)
+			.await
+	}
+
+	fn channel_whitelist(&self) -> HashSet<(ChannelId, PortId)> {
+		self.inner.channel_whitelist()
+	}
+
+	async fn query_connection_channels(
+		&self,
+		at: Height,
+		connection_id: &ConnectionId,
+	) -> Result<QueryChannelsResponse, Self::Error> {
+		self.inner.query_connection_channels(at, connection_id).await
+	}
+
+	async fn query_send_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.inner.query_send_packets(channel_id, port_id, seqs).await
+	}
+
+	async fn query_received_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		seqs: Vec<u64>,
+	) -> Result<Vec<PacketInfo>, Self::Error> {
+		self.inner.query_received_packets(channel_id, port_id, seqs).await
+	}
+
+	fn expected_block_time(&self) -> Duration {
+		self.inner.expected_block_time()
+	}
+
+	async fn query_client_update_time_and_height(
+		&self,
+		client_id: ClientId,
+		client_height: Height,
+	) -> Result<(Height, Timestamp), Self::Error> {
+		self.inner.query_client_update_time_and_height(client_id, client_height).await
+	}
+
+	async fn query_host_consensus_state_proof(
+		&self,
+		client_state: &AnyClientState,
+	) -> Result<Option<Vec<u8>>, Self::Error> {
+		self.inner.query_host_consensus_state_proof(client_state).await
+	}
+
+	async fn query_ibc_balance(
+		&self,
+		asset_id: Self::AssetId,
+	) -> Result<Vec<PrefixedCoin>, Self::Error> {
+		self.inner.query_ibc_balance(asset_id).await
+	}
+
+	fn connection_prefix(&self) -> CommitmentPrefix {
+		self.inner.connection_prefix()
+	}
+
+	fn client_id(&self) -> ClientId {
+		self.inner.client_id()
+	}
+
+	fn set_client_id(&mut self, client_id: ClientId) {
+		self.inner.set_client_id(client_id)
+	}
+
+	fn connection_id(&self) -> Option<ConnectionId> {
+		self.inner.connection_id()
+	}
+
+	fn set_channel_whitelist(&mut self, channel_whitelist: HashSet<(ChannelId, PortId)>) {
+		self.inner.set_channel_whitelist(channel_whitelist)
+	}
+
+	fn add_channel_to_whitelist(&mut self, channel: (ChannelId, PortId)) {
+		self.inner.add_channel_to_whitelist(channel)
+	}
+
+	fn set_connection_id(&mut self, connection_id: ConnectionId) {
+		self.inner.set_connection_id(connection_id)
+	}
+
+	fn client_type(&self) -> ClientType {
+		self.inner.client_type()
+	}
+
+	async fn query_timestamp_at(&self, block_number: u64) -> Result<u64, Self::Error> {
+		self.inner.query_timestamp_at(block_number).await
+	}
+
+	async fn query_clients(&self) -> Result<Vec<ClientId>, Self::Error> {
+		self.inner.query_clients().await
+	}
+
+	async fn query_channels(&self) -> Result<Vec<(ChannelId, PortId)>, Self::Error> {
+		self.inner.query_channels().await
+	}
+
+	async fn query_connection_using_client(
+		&self,
+		height: u32,
+		client_id: String,
+	) -> Result<Vec<IdentifiedConnection>, Self::Error> {
+		self.inner.query_connection_using_client(height, client_id).await
+	}
+
+	async fn is_update_required(
+		&self,
+		latest_height: u64,
+		latest_client_height_on_counterparty: u64,
+	) -> Result<bool, Self::Error> {
+		self.inner
+			.is_update_required(latest_height, latest_client_height_on_counterparty)
+			.await
+	}
+
+	async fn initialize_client_state(
+		&self,
+	) -> Result<(AnyClientState, AnyConsensusState), Self::Error> {
+		self.inner.initialize_client_state().await
+	}
+
+	async fn query_client_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ClientId, Self::Error> {
+		self.inner.query_client_id_from_tx_hash(tx_id).await
+	}
+
+	async fn query_connection_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<ConnectionId, Self::Error> {
+		self.inner.query_connection_id_from_tx_hash(tx_id).await
+	}
+
+	async fn query_channel_id_from_tx_hash(
+		&self,
+		tx_id: Self::TransactionId,
+	) -> Result<(ChannelId, PortId), Self::Error> {
+		self.inner.query_channel_id_from_tx_hash(tx_id).await
+	}
+
+	async fn upload_wasm(&self, wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error> {
+		self.inner.upload_wasm(wasm).await
+	}
+
+	async fn query_incentivized_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<HashMap<u64, PacketFees>, Self::Error> {
+		self.inner.query_incentivized_packets(channel_id, port_id).await
+	}
+
+	async fn register_counterparty_payee(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		counterparty_payee: Signer,
+	) -> Result<(), Self::Error> {
+		self.inner
+			.register_counterparty_payee(channel_id, port_id, counterparty_payee)
+			.await
+	}
+}
+
+impl<C: Chain> KeyProvider for CachedProvider<C> {
+	fn account_id(&self) -> Signer {
+		self.inner.account_id()
+	}
+}
+
+#[async_trait::async_trait]
+impl<C: Chain> MisbehaviourHandler for CachedProvider<C> {
+	async fn check_for_misbehaviour<T: Chain>(
+		&self,
+		counterparty: &T,
+		client_message: AnyClientMessage,
+	) -> Result<(), anyhow::Error> {
+		self.inner.check_for_misbehaviour(counterparty, client_message).await
+	}
+}
+
+#[async_trait::async_trait]
+impl<C: Chain> LightClientSync for CachedProvider<C> {
+	async fn is_synced<T: Chain>(&self, counterparty: &T) -> Result<bool, anyhow::Error> {
+		self.inner.is_synced(counterparty).await
+	}
+
+	async fn fetch_mandatory_updates<T: Chain>(
+		&self,
+		counterparty: &T,
+	) -> Result<(Vec<Any>, Vec<IbcEvent>), anyhow::Error> {
+		self.inner.fetch_mandatory_updates(counterparty).await
+	}
+}
+
+#[async_trait::async_trait]
+impl<C: Chain> Chain for CachedProvider<C> {
+	fn name(&self) -> &str {
+		self.inner.name()
+	}
+
+	fn block_max_weight(&self) -> u64 {
+		self.inner.block_max_weight()
+	}
+
+	async fn estimate_weight(&self, msg: Vec<Any>) -> Result<u64, Self::Error> {
+		self.inner.estimate_weight(msg).await
+	}
+
+	async fn filter_invalid_messages(&self, msgs: Vec<Any>) -> Result<Vec<Any>, Self::Error> {
+		self.inner.filter_invalid_messages(msgs).await
+	}
+
+	async fn finality_notifications(
+		&self,
+	) -> Result<Pin<Box<dyn Stream<Item = Self::FinalityEvent> + Send + Sync>>, Self::Error> {
+		self.inner.finality_notifications().await
+	}
+
+	async fn submit(&self, messages: Vec<Any>) -> Result<Self::TransactionId, Self::Error> {
+		self.inner.submit(messages).await
+	}
+
+	async fn query_client_message(
+		&self,
+		update: UpdateClient,
+	) -> Result<AnyClientMessage, Self::Error> {
+		self.inner.query_client_message(update).await
+	}
+
+	async fn get_proof_height(&self, block_height: Height) -> Height {
+		self.inner.get_proof_height(block_height).await
+	}
+
+	async fn handle_error(&mut self, error: &anyhow::Error) -> Result<(), anyhow::Error> {
+		self.inner.handle_error(error).await
+	}
+
+	fn common_state(&self) -> &CommonClientState {
+		self.inner.common_state()
+	}
+
+	fn common_state_mut(&mut self) -> &mut CommonClientState {
+		self.inner.common_state_mut()
+	}
+
+	async fn reconnect(&mut self) -> anyhow::Result<()> {
+		self.inner.reconnect().await
+	}
+}
+
+#[cfg(any(feature = "testing", test))]
+#[async_trait::async_trait]
+impl<C: crate::TestProvider> crate::TestProvider for CachedProvider<C> {
+	async fn send_transfer(&self, params: MsgTransfer<PrefixedCoin>) -> Result<(), Self::Error> {
+		self.inner.send_transfer(params).await
+	}
+
+	async fn send_ordered_packet(
+		&self,
+		channel_id: ChannelId,
+		timeout: pallet_ibc::Timeout,
+	) -> Result<(), Self::Error> {
+		self.inner.send_ordered_packet(channel_id, timeout).await
+	}
+
+	async fn subscribe_blocks(&self) -> Pin<Box<dyn Stream<Item = u64> + Send + Sync>> {
+		self.inner.subscribe_blocks().await
+	}
+
+	async fn increase_counters(&mut self) -> Result<(), Self::Error> {
+		self.inner.increase_counters().await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn proof(byte: u8) -> (QueryKey, QueryValue) {
+		(QueryKey::Proof(vec![vec![byte]]), QueryValue::Proof(vec![byte]))
+	}
+
+	#[test]
+	fn evicts_lowest_heights_first() {
+		let cache = QueryCache::new(QueryCacheConfig { capacity: 3 });
+		for height in 1..=2 {
+			for byte in 0..2 {
+				let (key, value) = proof(byte);
+				cache.insert(Height::new(1, height), key, value);
+			}
+		}
+		// the entries of height 1 were evicted together
+		assert_eq!(cache.stats().entries, 2);
+		assert!(cache.get(Height::new(1, 1), &proof(0).0).is_none());
+		assert!(
+			matches!(cache.get(Height::new(1, 2), &proof(1).0), Some(QueryValue::Proof(p)) if p == vec![1])
+		);
+		assert_eq!(cache.stats().hits, 1);
+		assert_eq!(cache.stats().misses, 1);
+
+		cache.invalidate(Height::new(1, 2));
+		assert_eq!(cache.stats().entries, 0);
+		assert!(cache.get(Height::new(1, 2), &proof(1).0).is_none());
+	}
+}
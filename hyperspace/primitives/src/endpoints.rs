@@ -0,0 +1,189 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A list of interchangeable RPC endpoints with health scoring, used by the chain clients to
+//! fail over to another node when the current one misbehaves.
+
+use std::{
+	collections::VecDeque,
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+
+/// Failures older than this are forgotten when computing an endpoint's health.
+pub const FAILURE_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+/// Number of recent failures after which an endpoint is considered unhealthy.
+pub const MAX_RECENT_FAILURES: usize = 3;
+
+#[derive(Debug, Default)]
+struct EndpointHealth {
+	/// Timestamps of the failures observed within [`FAILURE_WINDOW`]
+	failures: VecDeque<Instant>,
+}
+
+impl EndpointHealth {
+	fn recent_failures(&mut self, now: Instant) -> usize {
+		while let Some(at) = self.failures.front() {
+			if now.duration_since(*at) <= FAILURE_WINDOW {
+				break
+			}
+			self.failures.pop_front();
+		}
+		self.failures.len()
+	}
+}
+
+#[derive(Debug)]
+struct PoolState {
+	current: usize,
+	health: Vec<EndpointHealth>,
+}
+
+/// An ordered set of endpoints for the same chain. The first endpoint is the preferred one,
+/// the rest are used as fallbacks. The state is shared between clones, so every clone of a
+/// client observes the same active endpoint and health scores.
+#[derive(Debug, Clone)]
+pub struct EndpointPool<T> {
+	endpoints: Arc<Vec<T>>,
+	state: Arc<Mutex<PoolState>>,
+}
+
+impl<T: Clone> EndpointPool<T> {
+	/// Creates a pool from the primary endpoint and a list of fallbacks.
+	pub fn new(primary: T, fallbacks: impl IntoIterator<Item = T>) -> Self {
+		let endpoints = std::iter::once(primary).chain(fallbacks).collect::<Vec<_>>();
+		let health = endpoints.iter().map(|_| EndpointHealth::default()).collect();
+		Self {
+			endpoints: Arc::new(endpoints),
+			state: Arc::new(Mutex::new(PoolState { current: 0, health })),
+		}
+	}
+
+	/// Number of endpoints in the pool.
+	pub fn len(&self) -> usize {
+		self.endpoints.len()
+	}
+
+	/// Whether the pool has no endpoints, which [`new`](Self::new) rules out.
+	pub fn is_empty(&self) -> bool {
+		self.endpoints.is_empty()
+	}
+
+	/// Index of the active endpoint.
+	pub fn current_index(&self) -> usize {
+		self.state.lock().unwrap().current
+	}
+
+	/// The active endpoint.
+	pub fn current(&self) -> T {
+		self.endpoints[self.current_index()].clone()
+	}
+
+	/// Returns the endpoint at the given index.
+	pub fn get(&self, index: usize) -> Option<T> {
+		self.endpoints.get(index).cloned()
+	}
+
+	/// Makes the endpoint at `index` the active one.
+	pub fn select(&self, index: usize) {
+		assert!(index < self.endpoints.len(), "endpoint index out of bounds");
+		self.state.lock().unwrap().current = index;
+	}
+
+	/// Records a failure for the active endpoint.
+	pub fn record_failure(&self) {
+		let index = self.current_index();
+		self.record_failure_at(index);
+	}
+
+	/// Records a failure for the endpoint at `index`.
+	pub fn record_failure_at(&self, index: usize) {
+		let mut state = self.state.lock().unwrap();
+		if let Some(health) = state.health.get_mut(index) {
+			health.failures.push_back(Instant::now());
+		}
+	}
+
+	/// Number of failures recorded for the endpoint at `index` within [`FAILURE_WINDOW`].
+	pub fn recent_failures(&self, index: usize) -> usize {
+		let mut state = self.state.lock().unwrap();
+		state
+			.health
+			.get_mut(index)
+			.map(|h| h.recent_failures(Instant::now()))
+			.unwrap_or_default()
+	}
+
+	/// Returns `true` if the active endpoint has failed less than [`MAX_RECENT_FAILURES`] times
+	/// recently.
+	pub fn is_current_healthy(&self) -> bool {
+		self.recent_failures(self.current_index()) < MAX_RECENT_FAILURES
+	}
+
+	/// Returns the endpoints in the order they should be tried when (re)connecting: the
+	/// healthiest first, ties broken by the position relative to the active endpoint. Callers
+	/// failing over should [`record_failure`](Self::record_failure) first, so that the active
+	/// endpoint is tried after the healthy ones.
+	pub fn candidates(&self) -> Vec<(usize, T)> {
+		let now = Instant::now();
+		let mut state = self.state.lock().unwrap();
+		let current = state.current;
+		let len = self.endpoints.len();
+		let mut candidates = (0..len)
+			.map(|index| {
+				let failures = state.health[index].recent_failures(now);
+				let distance = (index + len - current) % len;
+				(failures, distance, index)
+			})
+			.collect::<Vec<_>>();
+		candidates.sort();
+		candidates
+			.into_iter()
+			.map(|(_, _, index)| (index, self.endpoints[index].clone()))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn failover_prefers_next_healthy_endpoint() {
+		let pool = EndpointPool::new("a", ["b", "c"]);
+		assert_eq!(pool.current(), "a");
+
+		pool.record_failure();
+		let candidates = pool.candidates().into_iter().map(|(_, e)| e).collect::<Vec<_>>();
+		assert_eq!(candidates, vec!["b", "c", "a"]);
+
+		pool.select(1);
+		pool.record_failure_at(2);
+		pool.record_failure_at(2);
+		let candidates = pool.candidates().into_iter().map(|(_, e)| e).collect::<Vec<_>>();
+		assert_eq!(candidates, vec!["b", "a", "c"]);
+	}
+
+	#[test]
+	fn endpoint_becomes_unhealthy_after_repeated_failures() {
+		let pool = EndpointPool::new("a", []);
+		for _ in 0..MAX_RECENT_FAILURES - 1 {
+			pool.record_failure();
+		}
+		assert!(pool.is_current_healthy());
+		pool.record_failure();
+		assert!(!pool.is_current_healthy());
+	}
+}
@@ -0,0 +1,172 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Selection of the packets to relay based on their ICS-29 fees.
+//!
+//! Each channel of the source chain may have a [`PacketFeePolicy`], which decides whether a
+//! packet is worth relaying given the fees escrowed for it on the source chain. The fees of the
+//! channels with a policy are queried on every pass, and their packets are relayed in the order
+//! of the fees they pay. Channels without a policy are relayed as usual and never queried, so
+//! chains without the fee middleware are not affected.
+
+use crate::error::Error;
+use ibc::core::ics24_host::identifier::{ChannelId, PortId};
+use ibc_proto::{cosmos::base::v1beta1::Coin, ibc::applications::fee::v1::IdentifiedPacketFees};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Amounts of a fee, keyed by denom.
+pub type FeeCoins = BTreeMap<String, u128>;
+
+/// Decides which packets of a channel are relayed, based on the fees paid for them.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PacketFeePolicy {
+	/// Relay every packet, whether it pays fees or not.
+	#[default]
+	All,
+	/// Relay only the packets that pay a fee.
+	FeePaying,
+	/// Relay only the packets that pay at least the given amount in one of the denoms.
+	MinFee(FeeCoins),
+}
+
+impl PacketFeePolicy {
+	/// Returns true if only the packets paying a fee may be relayed.
+	pub fn requires_fee(&self) -> bool {
+		!matches!(self, Self::All)
+	}
+
+	/// Returns true if the packet with the given fees should be relayed. The fees a relayer earns
+	/// for a packet depend on its outcome, so `timed_out` tells whether the packet is relayed as a
+	/// timeout or delivered to the counterparty.
+	pub fn should_relay(&self, fees: Option<&PacketFees>, timed_out: bool) -> bool {
+		let reward = fees.map(|fees| fees.reward(timed_out)).unwrap_or_default();
+		match self {
+			Self::All => true,
+			Self::FeePaying => reward.values().any(|amount| *amount > 0),
+			Self::MinFee(min_fee) => min_fee.iter().any(|(denom, min_amount)| {
+				reward.get(denom).map_or(false, |amount| amount >= min_amount)
+			}),
+		}
+	}
+}
+
+/// Fee policy of a channel of the source chain.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ChannelFeePolicy {
+	pub channel_id: ChannelId,
+	pub port_id: PortId,
+	pub policy: PacketFeePolicy,
+}
+
+/// Fees escrowed for a packet, summed over all the payers.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PacketFees {
+	/// Paid to the relayer delivering the packet
+	pub recv_fee: FeeCoins,
+	/// Paid to the relayer delivering the acknowledgement
+	pub ack_fee: FeeCoins,
+	/// Paid to the relayer delivering the timeout
+	pub timeout_fee: FeeCoins,
+}
+
+impl PacketFees {
+	/// Returns the fees earned by relaying the packet, which are the recv and ack fees if the
+	/// packet is delivered, and the timeout fee if it timed out.
+	pub fn reward(&self, timed_out: bool) -> FeeCoins {
+		if timed_out {
+			self.timeout_fee.clone()
+		} else {
+			let mut reward = self.recv_fee.clone();
+			add_coins(&mut reward, &self.ack_fee);
+			reward
+		}
+	}
+
+	/// Returns the priority of the packet among the packets of its channel. Amounts of different
+	/// denoms are added up, so the priority is only meaningful when the fees of a channel are paid
+	/// in the same denoms.
+	pub fn priority(&self) -> u128 {
+		let total = |coins: FeeCoins| coins.values().fold(0u128, |acc, v| acc.saturating_add(*v));
+		total(self.reward(false)).max(total(self.reward(true)))
+	}
+}
+
+fn add_coins(coins: &mut FeeCoins, other: &FeeCoins) {
+	for (denom, amount) in other {
+		let entry = coins.entry(denom.clone()).or_default();
+		*entry = entry.saturating_add(*amount);
+	}
+}
+
+fn to_fee_coins(coins: &[Coin]) -> Result<FeeCoins, Error> {
+	let mut fee_coins = FeeCoins::new();
+	for coin in coins {
+		let amount = coin
+			.amount
+			.parse::<u128>()
+			.map_err(|e| Error::Custom(format!("Invalid fee amount {}: {e}", coin.amount)))?;
+		add_coins(&mut fee_coins, &FeeCoins::from([(coin.denom.clone(), amount)]));
+	}
+	Ok(fee_coins)
+}
+
+impl TryFrom<IdentifiedPacketFees> for (u64, PacketFees) {
+	type Error = Error;
+
+	fn try_from(value: IdentifiedPacketFees) -> Result<Self, Self::Error> {
+		let sequence = value
+			.packet_id
+			.ok_or_else(|| Error::Custom("Incentivized packet without packet id".to_string()))?
+			.sequence;
+		let mut fees = PacketFees::default();
+		for packet_fee in value.packet_fees {
+			let fee = packet_fee.fee.unwrap_or_default();
+			add_coins(&mut fees.recv_fee, &to_fee_coins(&fee.recv_fee)?);
+			add_coins(&mut fees.ack_fee, &to_fee_coins(&fee.ack_fee)?);
+			add_coins(&mut fees.timeout_fee, &to_fee_coins(&fee.timeout_fee)?);
+		}
+		Ok((sequence, fees))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn coins(amounts: &[(&str, u128)]) -> FeeCoins {
+		amounts.iter().map(|(denom, amount)| (denom.to_string(), *amount)).collect()
+	}
+
+	#[test]
+	fn policies_select_packets_by_the_fees_of_their_outcome() {
+		let fees = PacketFees {
+			recv_fee: coins(&[("stake", 30)]),
+			ack_fee: coins(&[("stake", 20)]),
+			timeout_fee: coins(&[("atom", 70)]),
+		};
+		assert_eq!(fees.reward(false), coins(&[("stake", 50)]));
+		assert_eq!(fees.priority(), 70);
+
+		assert!(PacketFeePolicy::All.should_relay(None, false));
+		assert!(!PacketFeePolicy::FeePaying.should_relay(None, false));
+		assert!(PacketFeePolicy::FeePaying.should_relay(Some(&fees), true));
+
+		let policy = PacketFeePolicy::MinFee(coins(&[("stake", 50), ("atom", 100)]));
+		assert!(policy.should_relay(Some(&fees), false));
+		assert!(!policy.should_relay(Some(&fees), true));
+		assert!(!policy.should_relay(None, false));
+	}
+}
@@ -21,25 +21,29 @@ use ibc_proto::{
 		channel::v1::{
 			QueryChannelResponse, QueryNextSequenceReceiveResponse,
 			QueryPacketAcknowledgementResponse, QueryPacketCommitmentResponse,
-			QueryPacketReceiptResponse,
+			QueryPacketReceiptResponse, QueryUpgradeErrorResponse, QueryUpgradeResponse,
 		},
 		client::v1::{QueryClientStateResponse, QueryConsensusStateResponse},
 		connection::v1::QueryConnectionResponse,
 	},
 };
-use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::{
 	collections::{HashMap, HashSet},
 	fmt::Debug,
 	pin::Pin,
 	str::FromStr,
-	sync::{Arc, Mutex},
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
 	time::Duration,
 };
-use tokio::{sync::Mutex as AsyncMutex, task::JoinSet, time::sleep};
+use tokio::sync::Mutex as AsyncMutex;
 
 use crate::error::Error;
+use cache::{QueryCache, QueryCacheConfig};
+use fees::{ChannelFeePolicy, PacketFeePolicy, PacketFees};
 #[cfg(any(feature = "testing", test))]
 use ibc::applications::transfer::msgs::transfer::MsgTransfer;
 use ibc::{
@@ -52,7 +56,6 @@ use ibc::{
 		},
 		ics04_channel::{
 			channel::{ChannelEnd, Order},
-			context::calculate_block_delay,
 			packet::Packet,
 		},
 		ics23_commitment::commitment::CommitmentPrefix,
@@ -68,9 +71,16 @@ use ibc_proto::ibc::core::{
 };
 use ibc_rpc::PacketInfo;
 use pallet_ibc::light_clients::{AnyClientMessage, AnyClientState, AnyConsensusState};
+use rate_limit::{RateLimitConfig, RateLimiter};
+use timeouts::PendingTimeouts;
 
+pub mod cache;
+pub mod endpoints;
 pub mod error;
+pub mod fees;
 pub mod mock;
+pub mod rate_limit;
+pub mod timeouts;
 pub mod utils;
 
 pub enum UpdateMessage {
@@ -112,6 +122,15 @@ pub struct CommonClientConfig {
 	pub skip_optional_client_updates: bool,
 	#[serde(default = "max_packets_to_process")]
 	pub max_packets_to_process: u32,
+	/// Rate limit for the RPC calls made to the chain's endpoint
+	#[serde(default)]
+	pub rate_limit: RateLimitConfig,
+	/// Cache of the proofs, client states and channel ends queried at the same heights
+	#[serde(default)]
+	pub query_cache: QueryCacheConfig,
+	/// ICS-29 fee policies of the channels, deciding which of their packets are relayed
+	#[serde(default)]
+	pub fee_policies: Vec<ChannelFeePolicy>,
 }
 
 /// A common data that all clients should keep.
@@ -125,27 +144,39 @@ pub struct CommonClientState {
 	///
 	/// Set inside `on_undelivered_sequences`.
 	pub maybe_has_undelivered_packets: Arc<Mutex<HashMap<UndeliveredType, bool>>>,
-	/// Delay between parallel RPC calls to be friendly with the node and avoid MaxSlotsExceeded
-	/// error
-	pub rpc_call_delay: Duration,
-	/// Initial value for the [`rpc_call_delay`] to reset it after a successful RPC call
-	pub initial_rpc_call_delay: Duration,
+	/// Timeouts of the undelivered packets sent to this chain, proven with the state of this
+	/// chain. Used to send optional client updates only when some timeout becomes provable.
+	pub pending_timeouts: PendingTimeouts,
+	/// Rate limiter that all RPC calls to the active endpoint go through, to be friendly with the
+	/// node and avoid `MaxSlotsExceeded` and HTTP 429 errors.
+	pub rate_limiter: RateLimiter,
+	/// Results of the queries that are repeated at the same heights, shared by both relaying
+	/// directions. Used by [`cache::CachedProvider`].
+	pub query_cache: QueryCache,
+	/// ICS-29 fee policies of the channels of this chain. Packets sent on the channels without a
+	/// policy are relayed regardless of their fees.
+	pub fee_policies: HashMap<(ChannelId, PortId), PacketFeePolicy>,
 	pub misbehaviour_client_msg_queue: Arc<AsyncMutex<Vec<AnyClientMessage>>>,
 	pub max_packets_to_process: usize,
 	pub skip_tokens_list: Vec<String>,
+	/// Incremented every time the client reconnects, possibly to a different endpoint. The
+	/// relayer loop uses it to know when finality notifications must be re-subscribed.
+	pub reconnects: Arc<AtomicUsize>,
 }
 
 impl Default for CommonClientState {
 	fn default() -> Self {
-		let rpc_call_delay = Duration::from_millis(100);
 		Self {
 			skip_optional_client_updates: true,
 			maybe_has_undelivered_packets: Default::default(),
-			rpc_call_delay,
-			initial_rpc_call_delay: rpc_call_delay,
+			pending_timeouts: Default::default(),
+			rate_limiter: Default::default(),
+			query_cache: Default::default(),
+			fee_policies: Default::default(),
 			misbehaviour_client_msg_queue: Arc::new(Default::default()),
 			max_packets_to_process: 100,
 			skip_tokens_list: Default::default(),
+			reconnects: Default::default(),
 		}
 	}
 }
@@ -170,12 +201,19 @@ impl CommonClientState {
 			.unwrap_or_default()
 	}
 
-	pub fn rpc_call_delay(&self) -> Duration {
-		self.rpc_call_delay
+	pub fn on_reconnect(&self) {
+		// the new endpoint has its own limits
+		self.rate_limiter.reset();
+		self.reconnects.fetch_add(1, Ordering::SeqCst);
+	}
+
+	/// Returns the fee policy of the channel, if one is configured.
+	pub fn fee_policy(&self, channel_id: ChannelId, port_id: &PortId) -> Option<PacketFeePolicy> {
+		self.fee_policies.get(&(channel_id, port_id.clone())).cloned()
 	}
 
-	pub fn set_rpc_call_delay(&mut self, delay: Duration) {
-		self.rpc_call_delay = delay;
+	pub fn reconnects(&self) -> usize {
+		self.reconnects.load(Ordering::SeqCst)
 	}
 }
 
@@ -221,6 +259,18 @@ pub trait IbcProvider {
 	where
 		T: Chain;
 
+	/// Query the ibc events emitted in the blocks `from..=to`, filtered the same way as the events
+	/// returned by [`Self::query_latest_ibc_events`]. Used to replay the events that were emitted
+	/// while the relayer was not running.
+	async fn query_ibc_events_in_range<T>(
+		&self,
+		from: u64,
+		to: u64,
+		counterparty: &T,
+	) -> Result<Vec<IbcEvent>, anyhow::Error>
+	where
+		T: Chain;
+
 	/// Return a stream that yields when new [`IbcEvents`] are parsed from a finality notification
 	async fn ibc_events(&self) -> Pin<Box<dyn Stream<Item = IbcEvent> + Send + 'static>>;
 
@@ -255,6 +305,22 @@ pub trait IbcProvider {
 		port_id: PortId,
 	) -> Result<QueryChannelResponse, Self::Error>;
 
+	/// Query the pending upgrade of a channel with proof
+	async fn query_channel_upgrade(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeResponse, Self::Error>;
+
+	/// Query the last upgrade error receipt of a channel with proof
+	async fn query_channel_upgrade_error(
+		&self,
+		at: Height,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<QueryUpgradeErrorResponse, Self::Error>;
+
 	/// Query proof for provided key path
 	async fn query_proof(&self, at: Height, keys: Vec<Vec<u8>>) -> Result<Vec<u8>, Self::Error>;
 
@@ -463,6 +529,23 @@ pub trait IbcProvider {
 	) -> Result<(ChannelId, PortId), Self::Error>;
 
 	async fn upload_wasm(&self, wasm: Vec<u8>) -> Result<Vec<u8>, Self::Error>;
+
+	/// Should return the ICS-29 fees escrowed for the packets sent on the channel that haven't
+	/// been acknowledged or timed out yet, keyed by packet sequence.
+	async fn query_incentivized_packets(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+	) -> Result<HashMap<u64, PacketFees>, Self::Error>;
+
+	/// Registers the address the ICS-29 recv fees earned by the relayer for delivering the
+	/// packets of the channel are paid to, on the chain the packets are sent from.
+	async fn register_counterparty_payee(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		counterparty_payee: Signer,
+	) -> Result<(), Self::Error>;
 }
 
 /// Provides an interface that allows us run the hyperspace-testsuite
@@ -533,6 +616,12 @@ pub trait Chain:
 	/// Should return an estimate of the weight of a batch of messages.
 	async fn estimate_weight(&self, msg: Vec<Any>) -> Result<u64, Self::Error>;
 
+	/// Should return the messages of the batch that would be executed successfully by this chain,
+	/// dropping the ones that would fail, e.g. on invalid proofs.
+	async fn filter_invalid_messages(&self, msgs: Vec<Any>) -> Result<Vec<Any>, Self::Error> {
+		Ok(msgs)
+	}
+
 	/// Return a stream that yields when new [`IbcEvents`] are ready to be queried.
 	async fn finality_notifications(
 		&self,
@@ -565,16 +654,8 @@ pub trait Chain:
 		self.common_state().has_undelivered_sequences(kind)
 	}
 
-	fn rpc_call_delay(&self) -> Duration {
-		self.common_state().rpc_call_delay()
-	}
-
-	fn initial_rpc_call_delay(&self) -> Duration {
-		self.common_state().initial_rpc_call_delay
-	}
-
-	fn set_rpc_call_delay(&mut self, delay: Duration) {
-		self.common_state_mut().set_rpc_call_delay(delay)
+	fn rate_limiter(&self) -> &RateLimiter {
+		&self.common_state().rate_limiter
 	}
 
 	async fn reconnect(&mut self) -> anyhow::Result<()>;
@@ -744,30 +825,29 @@ pub async fn find_suitable_proof_height_for_client(
 			return Some(temp_height)
 		}
 	} else {
+		// The client skips optional updates, so most heights have no consensus state and a binary
+		// search over them doesn't work. The start height is the estimated height at which the
+		// timeout becomes provable, and the relayer schedules client updates for that height, so
+		// the suitable consensus state is usually among the first ones after it.
 		let timestamp_to_match = timestamp_to_match.unwrap();
-		let mut start = start_height.revision_height;
-		let mut end = latest_client_height.revision_height;
-		let mut last_known_valid_height = None;
-		if start > end {
-			return None
-		}
-
 		log::debug!(
 			target: "hyperspace",
-			"Entered binary search for proof height on {} for client {} starting at {}", sink.name(), client_id, start_height
+			"Searching for proof height on {} for client {} with timestamp {} starting at {}",
+			sink.name(), client_id, timestamp_to_match, start_height
 		);
-		while end - start > 1 {
-			let mid = (end + start) / 2;
-			let temp_height = Height::new(start_height.revision_number, mid);
+		for height in start_height.revision_height..=latest_client_height.revision_height {
+			let temp_height = Height::new(start_height.revision_number, height);
 			let consensus_state =
 				sink.query_client_consensus(at, client_id.clone(), temp_height).await.ok();
 			let Some(Ok(consensus_state)) = consensus_state
 				.map(|x| x.consensus_state.map(AnyConsensusState::try_from))
 				.flatten()
 			else {
-				start += 1;
 				continue
 			};
+			if consensus_state.timestamp().nanoseconds() < timestamp_to_match.nanoseconds() {
+				continue
+			}
 			let proof_height = source.get_proof_height(temp_height).await;
 			let has_client_state = sink
 				.query_client_update_time_and_height(client_id.clone(), proof_height)
@@ -775,115 +855,15 @@ pub async fn find_suitable_proof_height_for_client(
 				.ok()
 				.is_some();
 			if !has_client_state {
-				start += 1;
 				continue
 			}
-
-			if consensus_state.timestamp().nanoseconds() < timestamp_to_match.nanoseconds() {
-				start = mid + 1;
-				continue
-			} else {
-				last_known_valid_height = Some(temp_height);
-				end = mid;
-			}
-		}
-		let start_height = Height::new(start_height.revision_number, start);
-
-		let consensus_state =
-			sink.query_client_consensus(at, client_id.clone(), start_height).await.ok();
-		if let Some(Ok(consensus_state)) = consensus_state
-			.map(|x| x.consensus_state.map(AnyConsensusState::try_from))
-			.flatten()
-		{
-			if consensus_state.timestamp().nanoseconds() >= timestamp_to_match.nanoseconds() {
-				let proof_height = source.get_proof_height(start_height).await;
-				let has_client_state = sink
-					.query_client_update_time_and_height(client_id.clone(), proof_height)
-					.await
-					.ok()
-					.is_some();
-				if has_client_state {
-					return Some(start_height)
-				}
-			}
+			log::info!("Found proof height on {} as {}:{}", sink.name(), temp_height, proof_height);
+			return Some(temp_height)
 		}
-
-		return last_known_valid_height
 	}
 	None
 }
 
-pub async fn query_maximum_height_for_timeout_proofs(
-	source: &impl Chain,
-	sink: &impl Chain,
-) -> Option<u64> {
-	let (source_height, ..) = source.latest_height_and_timestamp().await.ok()?;
-	let (sink_height, ..) = sink.latest_height_and_timestamp().await.ok()?;
-	let mut join_set: JoinSet<Option<_>> = JoinSet::new();
-	for (channel, port_id) in source.channel_whitelist() {
-		let undelivered_sequences = query_undelivered_sequences(
-			source_height,
-			sink_height,
-			channel,
-			port_id.clone(),
-			source,
-			sink,
-		)
-		.await
-		.ok()?;
-		let undelivered_sequences = undelivered_sequences
-			.into_iter()
-			.rev()
-			.take(source.common_state().max_packets_to_process)
-			.collect();
-		let send_packets =
-			source.query_send_packets(channel, port_id, undelivered_sequences).await.ok()?;
-		for send_packet in send_packets {
-			let source = source.clone();
-			let sink = sink.clone();
-			let duration = Duration::from_millis(
-				rand::thread_rng().gen_range(1..source.rpc_call_delay().as_millis()) as u64,
-			);
-			join_set.spawn(async move {
-				sleep(duration).await;
-				let revision_height = send_packet.height.expect("expected height for packet");
-				let sink_client_state = source
-					.query_client_state(
-						Height::new(source_height.revision_number, revision_height),
-						sink.client_id(),
-					)
-					.await
-					.ok()?;
-				let sink_client_state =
-					AnyClientState::try_from(sink_client_state.client_state?).ok()?;
-				let height = sink_client_state.latest_height();
-				let timestamp_at_creation =
-					sink.query_timestamp_at(height.revision_height).await.ok()?;
-				let period = send_packet.timeout_timestamp.saturating_sub(timestamp_at_creation);
-				if period == 0 {
-					return Some(send_packet.timeout_height.revision_height)
-				}
-				let period = Duration::from_nanos(period);
-				let period =
-					calculate_block_delay(period, sink.expected_block_time()).saturating_add(1);
-				let approx_height = revision_height + period;
-				let timeout_height = if send_packet.timeout_height.revision_height < approx_height {
-					send_packet.timeout_height.revision_height
-				} else {
-					approx_height
-				};
-
-				Some(timeout_height)
-			});
-		}
-	}
-	let mut min_timeout_height = None;
-	while let Some(timeout_height) = join_set.join_next().await {
-		min_timeout_height = min_timeout_height.max(timeout_height.ok()?)
-	}
-	min_timeout_height
-}
-
 pub fn filter_events_by_ids(
 	ev: &IbcEvent,
 	client_ids: &[ClientId],
@@ -893,7 +873,7 @@ pub fn filter_events_by_ids(
 	use ibc::core::{
 		ics02_client::events::Attributes as ClientAttributes,
 		ics03_connection::events::Attributes as ConnectionAttributes,
-		ics04_channel::events::Attributes as ChannelAttributes,
+		ics04_channel::events::{Attributes as ChannelAttributes, UpgradeAttributes},
 	};
 	let channel_ids = channel_and_port_ids
 		.iter()
@@ -928,6 +908,15 @@ pub fn filter_events_by_ids(
 				.unwrap_or(false)
 	};
 
+	let filter_upgrade_attributes = |attributes: &UpgradeAttributes| {
+		channel_ids.contains(&&attributes.channel_id) ||
+			attributes
+				.counterparty_channel_id
+				.as_ref()
+				.map(|id| channel_ids.contains(&id))
+				.unwrap_or(false)
+	};
+
 	let v = match ev {
 		IbcEvent::SendPacket(e) => filter_packet(&e.packet),
 		IbcEvent::WriteAcknowledgement(e) => filter_packet(&e.packet),
@@ -955,6 +944,24 @@ pub fn filter_events_by_ids(
 			filter_channel_attributes(&ChannelAttributes::from(e.clone())),
 		IbcEvent::CloseConfirmChannel(e) =>
 			filter_channel_attributes(&ChannelAttributes::from(e.clone())),
+		IbcEvent::UpgradeInitChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
+		IbcEvent::UpgradeTryChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
+		IbcEvent::UpgradeAckChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
+		IbcEvent::UpgradeConfirmChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
+		IbcEvent::UpgradeOpenChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
+		IbcEvent::UpgradeTimeoutChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
+		IbcEvent::UpgradeCancelChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
+		IbcEvent::UpgradeErrorChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
+		IbcEvent::FlushCompleteChannel(e) =>
+			filter_upgrade_attributes(&UpgradeAttributes::from(e.clone())),
 		IbcEvent::PushWasmCode(_) => true,
 		IbcEvent::NewBlock(_) |
 		IbcEvent::AppModule(_) |
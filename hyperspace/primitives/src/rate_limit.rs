@@ -0,0 +1,343 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Token-bucket rate limiter for the RPC calls a chain client makes to its active endpoint.
+//!
+//! Every query goes through [`RateLimiter::acquire`]. When the node reports that it is
+//! overloaded (HTTP 429, `MaxSlotsExceeded`, gRPC `ResourceExhausted`), the client calls
+//! [`RateLimiter::on_rate_limited`], which pauses all callers for an exponentially growing
+//! backoff and halves the refill rate. The rate recovers gradually once the node stops
+//! throttling us.
+
+use serde::{Deserialize, Serialize};
+use std::{
+	sync::{Arc, Mutex},
+	time::{Duration, Instant},
+};
+use tokio::time::sleep;
+
+/// Pause applied after the first rate-limited response.
+pub const MIN_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Upper bound for the pause applied after consecutive rate-limited responses.
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Time without rate-limited responses after which the refill rate is doubled back towards the
+/// configured one.
+pub const RECOVERY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The refill rate is never reduced below `requests_per_second / MAX_RATE_REDUCTION`.
+pub const MAX_RATE_REDUCTION: f64 = 16.0;
+
+fn default_requests_per_second() -> f64 {
+	10.0
+}
+
+fn default_burst() -> u32 {
+	20
+}
+
+/// Rate limiter parameters.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RateLimitConfig {
+	/// Number of requests per second allowed to be sent to the endpoint on average. A value of
+	/// zero disables rate limiting.
+	#[serde(default = "default_requests_per_second")]
+	pub requests_per_second: f64,
+	/// Maximum number of requests that may be sent at once after a period of inactivity.
+	#[serde(default = "default_burst")]
+	pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+	fn default() -> Self {
+		Self { requests_per_second: default_requests_per_second(), burst: default_burst() }
+	}
+}
+
+/// Classification of an RPC error, used by the chain clients to decide how to react to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcErrorKind {
+	/// The node refused the request because we are sending too many of them.
+	RateLimited,
+	/// The connection to the node is gone and the client has to reconnect.
+	ConnectionLost,
+	/// Any other error.
+	Other,
+}
+
+/// Error messages of the rpc libraries we use that indicate that the node throttled us.
+const RATE_LIMITED_PATTERNS: &[&str] = &[
+	// HTTP 429 as reported by tendermint-rpc and reqwest
+	"429 too many requests",
+	"too many requests",
+	// jsonrpsee `Error::MaxSlotsExceeded`, both the debug and the display representation
+	"maxslotsexceeded",
+	"max concurrent requests exceeded",
+	// tonic `Code::ResourceExhausted`
+	"resourceexhausted",
+	"resource exhausted",
+	"rate limit",
+];
+
+/// Error messages of the rpc libraries we use that indicate that the connection is lost.
+const CONNECTION_LOST_PATTERNS: &[&str] = &[
+	// tendermint-rpc websocket client
+	"dispatch task is gone",
+	"failed to send message to internal channel",
+	// jsonrpsee `Error::RestartNeeded`
+	"restartneeded",
+	"restart required",
+];
+
+impl RpcErrorKind {
+	/// Classifies an error by its message. Errors reach the relayer loop as type-erased
+	/// [`anyhow::Error`]s, so the message is the only thing all of them have in common. Clients
+	/// should match on typed errors first where they have them.
+	pub fn classify(error: &str) -> Self {
+		let error = error.to_lowercase();
+		if RATE_LIMITED_PATTERNS.iter().any(|pattern| error.contains(pattern)) {
+			RpcErrorKind::RateLimited
+		} else if CONNECTION_LOST_PATTERNS.iter().any(|pattern| error.contains(pattern)) {
+			RpcErrorKind::ConnectionLost
+		} else {
+			RpcErrorKind::Other
+		}
+	}
+}
+
+/// Snapshot of the rate limiter counters.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RateLimiterStats {
+	/// Total number of acquired permits.
+	pub acquired: u64,
+	/// Number of acquisitions that had to wait for a token.
+	pub delayed: u64,
+	/// Number of rate-limited responses reported by the client.
+	pub rate_limited: u64,
+	/// Total time spent waiting for tokens.
+	pub wait_time: Duration,
+	/// Tokens currently available in the bucket.
+	pub available_tokens: f64,
+	/// Bucket capacity, i.e. the configured burst.
+	pub capacity: f64,
+	/// Current refill rate, which may be lower than the configured one after rate-limited
+	/// responses.
+	pub current_rate: f64,
+}
+
+impl RateLimiterStats {
+	/// Fraction of the bucket that is used up, between `0` (idle) and `1` (saturated).
+	pub fn saturation(&self) -> f64 {
+		if self.capacity <= 0.0 {
+			return 0.0
+		}
+		(1.0 - self.available_tokens / self.capacity).clamp(0.0, 1.0)
+	}
+}
+
+#[derive(Debug)]
+struct Bucket {
+	tokens: f64,
+	rate: f64,
+	last_refill: Instant,
+	paused_until: Option<Instant>,
+	backoff: Duration,
+	last_rate_limited: Option<Instant>,
+	stats: RateLimiterStats,
+}
+
+impl Bucket {
+	fn refill(&mut self, config: &RateLimitConfig, now: Instant) {
+		if let Some(at) = self.last_rate_limited {
+			if now.duration_since(at) >= RECOVERY_INTERVAL {
+				self.rate = (self.rate * 2.0).min(config.requests_per_second);
+				self.backoff = MIN_BACKOFF;
+				self.last_rate_limited =
+					if self.rate < config.requests_per_second { Some(now) } else { None };
+			}
+		}
+		let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+		self.tokens = (self.tokens + elapsed * self.rate).min(config.burst as f64);
+		self.last_refill = now;
+	}
+}
+
+/// Token-bucket rate limiter. The state is shared between clones, so every clone of a client
+/// draws from the same bucket.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+	config: RateLimitConfig,
+	bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+	pub fn new(config: RateLimitConfig) -> Self {
+		let bucket = Bucket {
+			tokens: config.burst as f64,
+			rate: config.requests_per_second,
+			last_refill: Instant::now(),
+			paused_until: None,
+			backoff: MIN_BACKOFF,
+			last_rate_limited: None,
+			stats: RateLimiterStats::default(),
+		};
+		Self { config, bucket: Arc::new(Mutex::new(bucket)) }
+	}
+
+	pub fn config(&self) -> &RateLimitConfig {
+		&self.config
+	}
+
+	fn is_enabled(&self) -> bool {
+		self.config.requests_per_second > 0.0 && self.config.burst > 0
+	}
+
+	/// Takes a token from the bucket, returning how long the caller has to wait if there is
+	/// none available.
+	fn try_acquire(&self, now: Instant) -> Option<Duration> {
+		let mut bucket = self.bucket.lock().unwrap();
+		bucket.refill(&self.config, now);
+		if let Some(until) = bucket.paused_until {
+			if until > now {
+				return Some(until - now)
+			}
+			bucket.paused_until = None;
+		}
+		if bucket.tokens >= 1.0 {
+			bucket.tokens -= 1.0;
+			bucket.stats.acquired += 1;
+			None
+		} else {
+			Some(Duration::from_secs_f64((1.0 - bucket.tokens) / bucket.rate))
+		}
+	}
+
+	/// Waits until a request may be sent to the endpoint.
+	pub async fn acquire(&self) {
+		if !self.is_enabled() {
+			return
+		}
+		let started = Instant::now();
+		let mut delayed = false;
+		while let Some(wait) = self.try_acquire(Instant::now()) {
+			delayed = true;
+			sleep(wait).await;
+		}
+		if delayed {
+			let mut bucket = self.bucket.lock().unwrap();
+			bucket.stats.delayed += 1;
+			bucket.stats.wait_time += started.elapsed();
+		}
+	}
+
+	/// Must be called when the endpoint rejected a request because of rate limiting. Pauses all
+	/// callers for the current backoff, which doubles on every consecutive call, and halves the
+	/// refill rate.
+	pub fn on_rate_limited(&self) {
+		if !self.is_enabled() {
+			return
+		}
+		let now = Instant::now();
+		let mut bucket = self.bucket.lock().unwrap();
+		bucket.refill(&self.config, now);
+		let min_rate = self.config.requests_per_second / MAX_RATE_REDUCTION;
+		bucket.rate = (bucket.rate / 2.0).max(min_rate);
+		bucket.tokens = 0.0;
+		bucket.paused_until = Some(now + bucket.backoff);
+		bucket.backoff = (bucket.backoff * 2).min(MAX_BACKOFF);
+		bucket.last_rate_limited = Some(now);
+		bucket.stats.rate_limited += 1;
+		log::warn!(
+			target: "hyperspace",
+			"RPC endpoint is rate limiting requests, reducing rate to {:.2} req/s",
+			bucket.rate
+		);
+	}
+
+	/// Restores the configured rate and a full bucket. Must be called after switching to another
+	/// endpoint, since the throttling of the previous one doesn't apply to it. The counters are
+	/// preserved.
+	pub fn reset(&self) {
+		let mut bucket = self.bucket.lock().unwrap();
+		bucket.tokens = self.config.burst as f64;
+		bucket.rate = self.config.requests_per_second;
+		bucket.last_refill = Instant::now();
+		bucket.paused_until = None;
+		bucket.backoff = MIN_BACKOFF;
+		bucket.last_rate_limited = None;
+	}
+
+	pub fn stats(&self) -> RateLimiterStats {
+		let mut bucket = self.bucket.lock().unwrap();
+		bucket.refill(&self.config, Instant::now());
+		RateLimiterStats {
+			available_tokens: bucket.tokens,
+			capacity: self.config.burst as f64,
+			current_rate: bucket.rate,
+			..bucket.stats.clone()
+		}
+	}
+}
+
+impl Default for RateLimiter {
+	fn default() -> Self {
+		Self::new(RateLimitConfig::default())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn classifies_rpc_errors() {
+		assert_eq!(
+			RpcErrorKind::classify("HTTP error: 429 Too Many Requests"),
+			RpcErrorKind::RateLimited
+		);
+		assert_eq!(
+			RpcErrorKind::classify("RPC error: MaxSlotsExceeded"),
+			RpcErrorKind::RateLimited
+		);
+		assert_eq!(
+			RpcErrorKind::classify("status: ResourceExhausted, message: \"\""),
+			RpcErrorKind::RateLimited
+		);
+		assert_eq!(
+			RpcErrorKind::classify("client internal error: dispatch task is gone"),
+			RpcErrorKind::ConnectionLost
+		);
+		assert_eq!(RpcErrorKind::classify("packet not found at height 429"), RpcErrorKind::Other);
+	}
+
+	#[test]
+	fn bucket_limits_bursts_and_backs_off() {
+		let limiter = RateLimiter::new(RateLimitConfig { requests_per_second: 1.0, burst: 2 });
+		let now = Instant::now();
+		assert_eq!(limiter.try_acquire(now), None);
+		assert_eq!(limiter.try_acquire(now), None);
+		assert!(limiter.try_acquire(now).is_some());
+
+		limiter.on_rate_limited();
+		let stats = limiter.stats();
+		assert_eq!(stats.rate_limited, 1);
+		assert_eq!(stats.current_rate, 0.5);
+		assert!(limiter.try_acquire(Instant::now()).unwrap() <= MIN_BACKOFF);
+
+		limiter.reset();
+		assert_eq!(limiter.stats().current_rate, 1.0);
+		assert_eq!(limiter.try_acquire(Instant::now()), None);
+	}
+}
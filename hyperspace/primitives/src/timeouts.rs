@@ -0,0 +1,206 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Index of the timeouts of the packets that were sent to a chain and haven't been delivered yet.
+//!
+//! A packet sent from chain A to chain B times out once B reaches the packet's `timeout_height`
+//! or `timeout_timestamp`, and the timeout is proven on A with the state of B. So the client of B
+//! on A needs a consensus state past the timeout, which only an update of B's client provides.
+//! The index is kept by B (the chain whose state proves the timeouts) and tells the relayer at
+//! which height of B the earliest timeout becomes provable, so optional updates of B's client
+//! are only sent when one of them is needed.
+
+use ibc::{
+	core::{
+		ics04_channel::{context::calculate_block_delay, packet::Packet},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	timestamp::Timestamp,
+	Height,
+};
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+/// Timeout of a pending packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PacketTimeout {
+	pub timeout_height: Height,
+	pub timeout_timestamp: Timestamp,
+	/// Estimated height of the receiving chain from which the timeout can be proven.
+	pub provable_at: u64,
+}
+
+impl PacketTimeout {
+	/// Creates the timeout of `packet` given the latest height and timestamp of the receiving
+	/// chain. Returns `None` if the packet never times out.
+	///
+	/// Timestamp timeouts are converted into a height using the expected block time of the
+	/// receiving chain.
+	pub fn new(
+		packet: &Packet,
+		latest_height: Height,
+		latest_timestamp: Timestamp,
+		expected_block_time: Duration,
+	) -> Option<Self> {
+		let by_height = (!packet.timeout_height.is_zero())
+			.then(|| packet.timeout_height.revision_height.max(latest_height.revision_height));
+		let by_timestamp = (packet.timeout_timestamp.nanoseconds() != 0).then(|| {
+			let period = packet
+				.timeout_timestamp
+				.nanoseconds()
+				.saturating_sub(latest_timestamp.nanoseconds());
+			latest_height.revision_height +
+				calculate_block_delay(Duration::from_nanos(period), expected_block_time)
+		});
+		let provable_at = match (by_height, by_timestamp) {
+			(Some(h), Some(t)) => h.min(t),
+			(Some(h), None) => h,
+			(None, Some(t)) => t,
+			(None, None) => return None,
+		};
+		Some(Self {
+			timeout_height: packet.timeout_height,
+			timeout_timestamp: packet.timeout_timestamp,
+			provable_at,
+		})
+	}
+}
+
+#[derive(Debug, Default)]
+struct Index {
+	packets: HashMap<(ChannelId, PortId), BTreeMap<u64, PacketTimeout>>,
+	/// The latest height at which an update of the client has been sent. Timeouts provable at or
+	/// below it don't require another update.
+	covered_height: u64,
+}
+
+/// Pending packet timeouts, keyed by the sending channel. The state is shared between clones.
+#[derive(Debug, Clone, Default)]
+pub struct PendingTimeouts {
+	index: Arc<Mutex<Index>>,
+}
+
+impl PendingTimeouts {
+	/// Replaces the pending timeouts of the packets sent over the given channel. Packets that
+	/// are not in `timeouts` have been delivered and are removed from the index.
+	pub fn replace_channel(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		timeouts: impl IntoIterator<Item = (u64, PacketTimeout)>,
+	) {
+		let mut index = self.index.lock().unwrap();
+		let mut channel_timeouts = timeouts.into_iter().collect::<BTreeMap<_, _>>();
+		// keep the earliest estimate, otherwise the estimate of a timed out packet would follow the
+		// latest height and require an update at every height until the timeout is delivered
+		if let Some(previous) = index.packets.get(&(channel_id, port_id.clone())) {
+			for (sequence, timeout) in channel_timeouts.iter_mut() {
+				if let Some(prev) = previous.get(sequence) {
+					timeout.provable_at = timeout.provable_at.min(prev.provable_at);
+				}
+			}
+		}
+		if channel_timeouts.is_empty() {
+			index.packets.remove(&(channel_id, port_id));
+		} else {
+			index.packets.insert((channel_id, port_id), channel_timeouts);
+		}
+	}
+
+	/// Returns the timeout of the given packet, if it's pending.
+	pub fn get(
+		&self,
+		channel_id: ChannelId,
+		port_id: PortId,
+		sequence: u64,
+	) -> Option<PacketTimeout> {
+		let index = self.index.lock().unwrap();
+		index.packets.get(&(channel_id, port_id))?.get(&sequence).cloned()
+	}
+
+	/// Returns the earliest height at which a timeout becomes provable and which hasn't been
+	/// covered by a client update yet.
+	pub fn earliest_provable_height(&self) -> Option<u64> {
+		let index = self.index.lock().unwrap();
+		index
+			.packets
+			.values()
+			.flat_map(|timeouts| timeouts.values())
+			.map(|timeout| timeout.provable_at)
+			.filter(|provable_at| *provable_at > index.covered_height)
+			.min()
+	}
+
+	/// Returns `true` if a client update at `height` makes some timeout provable.
+	pub fn is_update_required(&self, height: u64) -> bool {
+		self.earliest_provable_height()
+			.map_or(false, |provable_at| provable_at <= height)
+	}
+
+	/// Must be called when an update of the client to `height` has been sent.
+	pub fn on_client_updated(&self, height: u64) {
+		let mut index = self.index.lock().unwrap();
+		index.covered_height = index.covered_height.max(height);
+	}
+
+	pub fn len(&self) -> usize {
+		let index = self.index.lock().unwrap();
+		index.packets.values().map(BTreeMap::len).sum()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn timeout(provable_at: u64) -> PacketTimeout {
+		PacketTimeout {
+			timeout_height: Height::new(1, provable_at),
+			timeout_timestamp: Timestamp::none(),
+			provable_at,
+		}
+	}
+
+	#[test]
+	fn schedules_updates_for_earliest_timeout() {
+		let pending = PendingTimeouts::default();
+		let port = PortId::transfer();
+		pending.replace_channel(
+			ChannelId::new(0),
+			port.clone(),
+			[(1, timeout(120)), (2, timeout(100))],
+		);
+		pending.replace_channel(ChannelId::new(1), port.clone(), [(1, timeout(150))]);
+		assert_eq!(pending.earliest_provable_height(), Some(100));
+		assert!(!pending.is_update_required(99));
+		assert!(pending.is_update_required(100));
+
+		pending.on_client_updated(110);
+		assert_eq!(pending.earliest_provable_height(), Some(120));
+
+		// the packet was delivered
+		pending.replace_channel(ChannelId::new(0), port.clone(), []);
+		assert_eq!(pending.earliest_provable_height(), Some(150));
+		assert_eq!(pending.len(), 1);
+
+		// the earliest estimate of an already known packet is kept
+		pending.replace_channel(ChannelId::new(1), port.clone(), [(1, timeout(170))]);
+		assert_eq!(pending.get(ChannelId::new(1), port.clone(), 1).unwrap().provable_at, 150);
+		pending.replace_channel(ChannelId::new(1), port.clone(), [(1, timeout(140))]);
+		assert_eq!(pending.get(ChannelId::new(1), port, 1).unwrap().provable_at, 140);
+	}
+}
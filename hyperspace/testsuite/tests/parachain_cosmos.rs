@@ -78,6 +78,8 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		para_id: args.para_id,
 		parachain_rpc_url: args.chain_a,
 		relay_chain_rpc_url: args.relay_chain.clone(),
+		fallback_parachain_rpc_urls: vec![],
+		fallback_relay_chain_rpc_urls: vec![],
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_a.as_bytes().to_vec().into(),
@@ -87,6 +89,13 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		private_key: "//Alice".to_string(),
 		key_type: "sr25519".to_string(),
 		wasm_code_id: None,
+		common: CommonClientConfig {
+			skip_optional_client_updates: true,
+			max_packets_to_process: 200,
+			rate_limit: Default::default(),
+			query_cache: Default::default(),
+			fee_policies: vec![],
+		},
 	};
 
 	let mut config_b = CosmosClientConfig {
@@ -94,6 +103,7 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		rpc_url: args.chain_b.clone().parse().unwrap(),
 		grpc_url: args.cosmos_grpc.clone().parse().unwrap(),
 		websocket_url: args.cosmos_ws.clone().parse().unwrap(),
+		fallback_endpoints: vec![],
 		chain_id: "ibcgo-1".to_string(),
 		client_id: None,
 		connection_id: None,
@@ -111,6 +121,9 @@ async fn setup_clients() -> (AnyChain, AnyChain) {
 		common: CommonClientConfig {
 			skip_optional_client_updates: true,
 			max_packets_to_process: 200,
+			rate_limit: Default::default(),
+			query_cache: Default::default(),
+			fee_policies: vec![],
 		},
 		skip_tokens_list: None,
 	};
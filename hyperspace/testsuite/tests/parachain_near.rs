@@ -0,0 +1,211 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::time::Duration;
+use futures::StreamExt;
+use hyperspace_core::{
+	chain::{AnyAssetId, AnyChain, AnyConfig},
+	logging,
+};
+use hyperspace_near::client::NearClientConfig;
+use hyperspace_parachain::{finality_protocol::FinalityProtocol, ParachainClientConfig};
+use hyperspace_primitives::{utils::create_clients, CommonClientConfig, IbcProvider};
+use hyperspace_testsuite::{
+	ibc_messaging_packet_height_timeout_with_connection_delay,
+	ibc_messaging_packet_timestamp_timeout_with_connection_delay,
+	ibc_messaging_with_connection_delay, setup_connection_and_channel,
+};
+use ibc::core::ics24_host::identifier::PortId;
+
+#[derive(Debug, Clone)]
+pub struct Args {
+	pub chain_a: String,
+	pub chain_b: String,
+	pub relay_chain: String,
+	pub para_id: u32,
+	pub connection_prefix_a: String,
+	pub connection_prefix_b: String,
+	pub near_contract_id: String,
+	pub near_signer_id: String,
+	pub near_secret_key: String,
+}
+
+impl Default for Args {
+	fn default() -> Self {
+		let relay = std::env::var("RELAY_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+		let para = std::env::var("PARA_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+		let near = std::env::var("NEAR_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+		let near_contract_id =
+			std::env::var("NEAR_CONTRACT_ID").unwrap_or_else(|_| "ibc.test.near".to_string());
+		let near_signer_id =
+			std::env::var("NEAR_SIGNER_ID").unwrap_or_else(|_| "test.near".to_string());
+		// the key of `test.near` is written to `~/.near/validator_key.json` by `near-sandbox init`
+		let near_secret_key = std::env::var("NEAR_SECRET_KEY")
+			.expect("NEAR_SECRET_KEY should be set to the secret key of the signer");
+
+		Args {
+			chain_a: format!("ws://{para}:9188"),
+			chain_b: format!("http://{near}:3030"),
+			relay_chain: format!("ws://{relay}:9944"),
+			para_id: 2000,
+			connection_prefix_a: "ibc/".to_string(),
+			connection_prefix_b: "ibc/".to_string(),
+			near_contract_id,
+			near_signer_id,
+			near_secret_key,
+		}
+	}
+}
+
+async fn setup_clients() -> (AnyChain, AnyChain) {
+	log::info!(target: "hyperspace", "=========================== Starting Test ===========================");
+	let args = Args::default();
+
+	// Create client configurations
+	let config_a = ParachainClientConfig {
+		name: "parachain".to_string(),
+		para_id: args.para_id,
+		parachain_rpc_url: args.chain_a,
+		relay_chain_rpc_url: args.relay_chain.clone(),
+		fallback_parachain_rpc_urls: vec![],
+		fallback_relay_chain_rpc_urls: vec![],
+		client_id: None,
+		connection_id: None,
+		commitment_prefix: args.connection_prefix_a.as_bytes().to_vec().into(),
+		ss58_version: 42,
+		channel_whitelist: vec![],
+		finality_protocol: FinalityProtocol::Grandpa,
+		private_key: "//Alice".to_string(),
+		key_type: "sr25519".to_string(),
+		wasm_code_id: None,
+		common: CommonClientConfig {
+			skip_optional_client_updates: true,
+			max_packets_to_process: 200,
+			rate_limit: Default::default(),
+			query_cache: Default::default(),
+			fee_policies: vec![],
+		},
+	};
+
+	let config_b = NearClientConfig {
+		name: "near".to_string(),
+		rpc_url: args.chain_b,
+		fallback_rpc_urls: vec![],
+		contract_id: args.near_contract_id.parse().unwrap(),
+		signer_id: args.near_signer_id.parse().unwrap(),
+		secret_key: args.near_secret_key,
+		client_id: None,
+		connection_id: None,
+		commitment_prefix: args.connection_prefix_b,
+		gas_per_message: 30_000_000_000_000,
+		wasm_code_id: None,
+		channel_whitelist: vec![],
+		common: CommonClientConfig {
+			skip_optional_client_updates: true,
+			max_packets_to_process: 200,
+			rate_limit: Default::default(),
+			query_cache: Default::default(),
+			fee_policies: vec![],
+		},
+		skip_tokens_list: None,
+	};
+
+	let mut chain_a_wrapped = AnyConfig::Parachain(config_a).into_client().await.unwrap();
+	let mut chain_b_wrapped = AnyConfig::Near(config_b).into_client().await.unwrap();
+
+	let AnyChain::Parachain(chain_a) = &mut chain_a_wrapped else { unreachable!() };
+
+	// Wait until for parachains to start producing blocks
+	log::info!(target: "hyperspace", "Waiting for block production from parachain");
+	let session_length = chain_a.grandpa_prover().session_length().await.unwrap();
+	let _ = chain_a
+		.relay_client
+		.rpc()
+		.subscribe_finalized_block_headers()
+		.await
+		.unwrap()
+		.filter_map(|result| futures::future::ready(result.ok()))
+		.skip_while(|h| futures::future::ready(h.number < (session_length * 2) + 10))
+		.take(1)
+		.collect::<Vec<_>>()
+		.await;
+	log::info!(target: "hyperspace", "Parachain have started block production");
+
+	let clients_on_a = chain_a_wrapped.query_clients().await.unwrap();
+	let clients_on_b = chain_b_wrapped.query_clients().await.unwrap();
+
+	if !clients_on_a.is_empty() && !clients_on_b.is_empty() {
+		chain_a_wrapped.set_client_id(clients_on_b[0].clone());
+		chain_b_wrapped.set_client_id(clients_on_a[0].clone());
+		return (chain_a_wrapped, chain_b_wrapped)
+	}
+
+	let (client_b, client_a) =
+		create_clients(&mut chain_b_wrapped, &mut chain_a_wrapped).await.unwrap();
+	chain_a_wrapped.set_client_id(client_a);
+	chain_b_wrapped.set_client_id(client_b);
+	(chain_a_wrapped, chain_b_wrapped)
+}
+
+#[tokio::test]
+#[ignore]
+async fn parachain_to_near_ibc_messaging_full_integration_test() {
+	logging::setup_logging();
+
+	let (mut chain_a, mut chain_b) = setup_clients().await;
+	let (handle, channel_a, channel_b, connection_id_a, connection_id_b) =
+		setup_connection_and_channel(&mut chain_a, &mut chain_b, Duration::from_secs(60 * 2)).await;
+	handle.abort();
+
+	// Set connections and channel whitelist
+	chain_a.set_connection_id(connection_id_a);
+	chain_b.set_connection_id(connection_id_b);
+
+	chain_a.set_channel_whitelist(vec![(channel_a, PortId::transfer())].into_iter().collect());
+	chain_b.set_channel_whitelist(vec![(channel_b, PortId::transfer())].into_iter().collect());
+
+	let asset_id_a = AnyAssetId::Parachain(1);
+	let asset_id_b = AnyAssetId::Near(format!("{}/{channel_b}/1", PortId::transfer()));
+
+	// Run tests sequentially
+
+	// no timeouts + connection delay
+	ibc_messaging_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		asset_id_a.clone(),
+		asset_id_b.clone(),
+		channel_a,
+		channel_b,
+	)
+	.await;
+
+	// timeouts + connection delay
+	ibc_messaging_packet_height_timeout_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		asset_id_a.clone(),
+		channel_a,
+		channel_b,
+	)
+	.await;
+	ibc_messaging_packet_timestamp_timeout_with_connection_delay(
+		&mut chain_a,
+		&mut chain_b,
+		asset_id_a.clone(),
+		channel_a,
+		channel_b,
+	)
+	.await;
+}
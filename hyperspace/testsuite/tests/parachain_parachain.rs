@@ -17,7 +17,7 @@ use hyperspace_core::{logging, substrate::DefaultConfig};
 use hyperspace_parachain::{
 	finality_protocol::FinalityProtocol, ParachainClient, ParachainClientConfig,
 };
-use hyperspace_primitives::{utils::create_clients, IbcProvider, TestProvider};
+use hyperspace_primitives::{utils::create_clients, CommonClientConfig, IbcProvider, TestProvider};
 use hyperspace_testsuite::{
 	client_synchronization_test, ibc_channel_close,
 	ibc_messaging_packet_height_timeout_with_connection_delay,
@@ -65,6 +65,8 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		para_id: args.para_id_a,
 		parachain_rpc_url: args.chain_a,
 		relay_chain_rpc_url: args.relay_chain.clone(),
+		fallback_parachain_rpc_urls: vec![],
+		fallback_relay_chain_rpc_urls: vec![],
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_b.as_bytes().to_vec().into(),
@@ -74,12 +76,21 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		private_key: "//Alice".to_string(),
 		key_type: "sr25519".to_string(),
 		wasm_code_id: None,
+		common: CommonClientConfig {
+			skip_optional_client_updates: true,
+			max_packets_to_process: 200,
+			rate_limit: Default::default(),
+			query_cache: Default::default(),
+			fee_policies: vec![],
+		},
 	};
 	let config_b = ParachainClientConfig {
 		name: "9188".to_string(),
 		para_id: args.para_id_b,
 		parachain_rpc_url: args.chain_b,
 		relay_chain_rpc_url: args.relay_chain,
+		fallback_parachain_rpc_urls: vec![],
+		fallback_relay_chain_rpc_urls: vec![],
 		client_id: None,
 		connection_id: None,
 		commitment_prefix: args.connection_prefix_b.as_bytes().to_vec().into(),
@@ -89,6 +100,13 @@ async fn setup_clients() -> (ParachainClient<DefaultConfig>, ParachainClient<Def
 		finality_protocol: FinalityProtocol::Grandpa,
 		key_type: "sr25519".to_string(),
 		wasm_code_id: None,
+		common: CommonClientConfig {
+			skip_optional_client_updates: true,
+			max_packets_to_process: 200,
+			rate_limit: Default::default(),
+			query_cache: Default::default(),
+			fee_policies: vec![],
+		},
 	};
 
 	let mut chain_a = ParachainClient::<DefaultConfig>::new(config_a).await.unwrap();
@@ -456,6 +456,112 @@ impl State {
 		}
 	}
 
+	fn impl_fn_verify_channel_upgrade(&self) -> proc_macro2::TokenStream {
+		let crate_ = &self.crate_ident;
+		let trait_ = &self.current_impl_trait;
+		let error = &self.current_impl_error;
+		let client_state_trait = &self.client_state_trait;
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(client) => {
+					let client_type = #client_state_trait::client_type(client_state).to_owned();
+					let client_state = #crate_::downcast!(client_state => Self::ClientState::#variant_ident)
+						.ok_or_else(|| #error::client_args_type_mismatch(client_type))?;
+
+					#trait_::verify_channel_upgrade(
+						client,
+						ctx,
+						client_id,
+						client_state,
+						height,
+						prefix,
+						proof,
+						root,
+						port_id,
+						channel_id,
+						upgrade,
+					)
+				}
+			}
+		});
+
+		quote! {
+			fn verify_channel_upgrade<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
+				&self,
+				ctx: &Ctx,
+				client_id: &#crate_::core::ics24_host::identifier::ClientId,
+				client_state: &Self::ClientState,
+				height: #crate_::core::ics02_client::height::Height,
+				prefix: &#crate_::core::ics23_commitment::commitment::CommitmentPrefix,
+				proof: &#crate_::core::ics23_commitment::commitment::CommitmentProofBytes,
+				root: &#crate_::core::ics23_commitment::commitment::CommitmentRoot,
+				port_id: &#crate_::core::ics24_host::identifier::PortId,
+				channel_id: &#crate_::core::ics24_host::identifier::ChannelId,
+				upgrade: &#crate_::core::ics04_channel::upgrade::Upgrade,
+			) -> ::core::result::Result<(), #error> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
+	fn impl_fn_verify_channel_upgrade_error(&self) -> proc_macro2::TokenStream {
+		let crate_ = &self.crate_ident;
+		let trait_ = &self.current_impl_trait;
+		let error = &self.current_impl_error;
+		let client_state_trait = &self.client_state_trait;
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(client) => {
+					let client_type = #client_state_trait::client_type(client_state).to_owned();
+					let client_state = #crate_::downcast!(client_state => Self::ClientState::#variant_ident)
+						.ok_or_else(|| #error::client_args_type_mismatch(client_type))?;
+
+					#trait_::verify_channel_upgrade_error(
+						client,
+						ctx,
+						client_id,
+						client_state,
+						height,
+						prefix,
+						proof,
+						root,
+						port_id,
+						channel_id,
+						error_receipt,
+					)
+				}
+			}
+		});
+
+		quote! {
+			fn verify_channel_upgrade_error<Ctx: #crate_::core::ics26_routing::context::ReaderContext>(
+				&self,
+				ctx: &Ctx,
+				client_id: &#crate_::core::ics24_host::identifier::ClientId,
+				client_state: &Self::ClientState,
+				height: #crate_::core::ics02_client::height::Height,
+				prefix: &#crate_::core::ics23_commitment::commitment::CommitmentPrefix,
+				proof: &#crate_::core::ics23_commitment::commitment::CommitmentProofBytes,
+				root: &#crate_::core::ics23_commitment::commitment::CommitmentRoot,
+				port_id: &#crate_::core::ics24_host::identifier::PortId,
+				channel_id: &#crate_::core::ics24_host::identifier::ChannelId,
+				error_receipt: &#crate_::core::ics04_channel::upgrade::ErrorReceipt,
+			) -> ::core::result::Result<(), #error> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
 	fn impl_fn_verify_client_full_state(&self) -> proc_macro2::TokenStream {
 		let crate_ = &self.crate_ident;
 		let trait_ = &self.current_impl_trait;
@@ -755,6 +861,8 @@ impl State {
 		let fn_verify_client_consensus_state = self.impl_fn_verify_client_consensus_state();
 		let fn_verify_connection_state = self.impl_fn_verify_connection_state();
 		let fn_verify_channel_state = self.impl_fn_verify_channel_state();
+		let fn_verify_channel_upgrade = self.impl_fn_verify_channel_upgrade();
+		let fn_verify_channel_upgrade_error = self.impl_fn_verify_channel_upgrade_error();
 		let fn_verify_client_full_state = self.impl_fn_verify_client_full_state();
 		let fn_verify_packet_data = self.impl_fn_verify_packet_data();
 		let fn_verify_packet_acknowledgement = self.impl_fn_verify_packet_acknowledgement();
@@ -776,6 +884,8 @@ impl State {
 				#fn_verify_client_consensus_state
 				#fn_verify_connection_state
 				#fn_verify_channel_state
+				#fn_verify_channel_upgrade
+				#fn_verify_channel_upgrade_error
 				#fn_verify_client_full_state
 				#fn_verify_packet_data
 				#fn_verify_packet_acknowledgement
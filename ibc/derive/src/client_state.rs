@@ -177,6 +177,26 @@ impl State {
 		}
 	}
 
+	fn impl_fn_on_proofs_verified(&self) -> proc_macro2::TokenStream {
+		let cases = self.clients.iter().map(|client| {
+			let variant_ident = &client.variant_ident;
+			let attrs = &client.attrs;
+			let trait_ = &self.current_impl_trait;
+			quote! {
+				#(#attrs)*
+				Self::#variant_ident(state) => #trait_::on_proofs_verified(state).map(Self::#variant_ident),
+			}
+		});
+
+		quote! {
+			fn on_proofs_verified(&self) -> ::core::option::Option<Self> {
+				match self {
+					#(#cases)*
+				}
+			}
+		}
+	}
+
 	pub fn impl_client_state(&mut self) -> proc_macro2::TokenStream {
 		let crate_ = &self.crate_ident;
 		self.current_impl_trait =
@@ -194,6 +214,7 @@ impl State {
 		let fn_frozen_height = self.impl_fn_frozen_height();
 		let fn_upgrade = self.impl_fn_upgrade();
 		let fn_expired = self.impl_fn_expired();
+		let fn_on_proofs_verified = self.impl_fn_on_proofs_verified();
 		let fn_downcast = self.impl_fn_downcast();
 		let fn_wrap = self.impl_fn_wrap();
 		let fn_encode_to_vec = self.impl_fn_encode_to_vec();
@@ -211,6 +232,7 @@ impl State {
 				#fn_frozen_height
 				#fn_upgrade
 				#fn_expired
+				#fn_on_proofs_verified
 				#fn_downcast
 				#fn_wrap
 				#fn_encode_to_vec
@@ -0,0 +1,74 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	applications::fee::error::Error,
+	core::ics04_channel::msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+	prelude::*,
+};
+
+/// The acknowledgement written on fee enabled channels. It wraps the acknowledgement of the
+/// underlying application along with the address of the relayer to pay the recv fee to, on the
+/// chain which sent the packet.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct IncentivizedAcknowledgement {
+	#[serde(with = "ibc_proto::base64")]
+	pub app_acknowledgement: Vec<u8>,
+	/// Empty if the relayer didn't register its address on the counterparty chain.
+	pub forward_relayer_address: String,
+	pub underlying_app_success: bool,
+}
+
+impl IncentivizedAcknowledgement {
+	pub fn new(
+		app_acknowledgement: GenericAcknowledgement,
+		forward_relayer_address: String,
+	) -> Self {
+		let underlying_app_success = is_successful(app_acknowledgement.as_ref());
+		Self {
+			app_acknowledgement: app_acknowledgement.into_bytes(),
+			forward_relayer_address,
+			underlying_app_success,
+		}
+	}
+
+	pub fn app_acknowledgement(&self) -> GenericAcknowledgement {
+		GenericAcknowledgement::from_bytes(self.app_acknowledgement.clone())
+	}
+}
+
+impl TryFrom<&GenericAcknowledgement> for IncentivizedAcknowledgement {
+	type Error = Error;
+
+	fn try_from(ack: &GenericAcknowledgement) -> Result<Self, Self::Error> {
+		serde_json::from_slice(ack.as_ref()).map_err(|_| Error::ack_deserialization())
+	}
+}
+
+impl From<IncentivizedAcknowledgement> for GenericAcknowledgement {
+	fn from(ack: IncentivizedAcknowledgement) -> Self {
+		GenericAcknowledgement::from_bytes(
+			serde_json::to_vec(&ack)
+				.expect("IncentivizedAcknowledgement's infallible Serialize impl failed"),
+		)
+	}
+}
+
+/// Applications write the standard JSON acknowledgement, a `result` on success or an `error` on
+/// failure. Any other acknowledgement is considered successful.
+fn is_successful(ack: &[u8]) -> bool {
+	serde_json::from_slice::<serde_json::Value>(ack)
+		.map_or(true, |value| value.get("error").is_none())
+}
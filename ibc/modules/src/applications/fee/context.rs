@@ -0,0 +1,100 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	applications::{
+		fee::{error::Error, packet_fee::PacketFee},
+		transfer::PrefixedCoin,
+	},
+	core::{
+		ics04_channel::{context::ChannelReader, packet::Sequence},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+/// Captures all the dependencies which the ICS29 middleware requires to escrow the fees of the
+/// packets and pay them to the relayers.
+pub trait Ics29Context: ChannelReader {
+	/// Returns true iff the fee version was negotiated in the channel handshake.
+	fn is_fee_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool;
+
+	fn set_fee_enabled(&mut self, port_id: PortId, channel_id: ChannelId) -> Result<(), Error>;
+
+	/// Returns the address of this chain the fees earned by `relayer` on the channel are paid to.
+	fn payee(&self, channel_id: &ChannelId, relayer: &Signer) -> Option<Signer>;
+
+	fn store_payee(
+		&mut self,
+		channel_id: ChannelId,
+		relayer: Signer,
+		payee: Signer,
+	) -> Result<(), Error>;
+
+	/// Returns the address of the counterparty chain the recv fees earned by `relayer` for the
+	/// packets received on the channel are paid to.
+	fn counterparty_payee(&self, channel_id: &ChannelId, relayer: &Signer) -> Option<String>;
+
+	fn store_counterparty_payee(
+		&mut self,
+		channel_id: ChannelId,
+		relayer: Signer,
+		counterparty_payee: String,
+	) -> Result<(), Error>;
+
+	/// Returns the address registered by the relayer of a packet received on the channel, whose
+	/// acknowledgement the application writes asynchronously.
+	fn forward_relayer(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Option<String>;
+
+	/// Stores the forward relayer of an asynchronously acknowledged packet, or removes it if
+	/// `forward_relayer` is `None`.
+	fn store_forward_relayer(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+		forward_relayer: Option<String>,
+	) -> Result<(), Error>;
+
+	/// Returns the fees escrowed for the packet sent on the channel with the given sequence.
+	fn packet_fees(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Vec<PacketFee>;
+
+	/// Stores the fees of a packet, or removes them if `packet_fees` is empty.
+	fn store_packet_fees(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+		packet_fees: Vec<PacketFee>,
+	) -> Result<(), Error>;
+
+	/// Returns the sequences of the packets sent on the channel which have fees in escrow.
+	fn incentivized_packets(&self, port_id: &PortId, channel_id: &ChannelId) -> Vec<Sequence>;
+
+	/// Moves the coins from the account of `payer` to the fee escrow.
+	fn escrow_coins(&mut self, payer: &Signer, coins: &[PrefixedCoin]) -> Result<(), Error>;
+
+	/// Moves the coins from the fee escrow to the account of `receiver`.
+	fn distribute_coins(&mut self, receiver: &Signer, coins: &[PrefixedCoin]) -> Result<(), Error>;
+}
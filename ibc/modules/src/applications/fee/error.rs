@@ -0,0 +1,100 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use crate::{
+	applications::{fee::VERSION, transfer::error::Error as Ics20Error},
+	core::{
+		ics04_channel::{error as channel_error, packet::Sequence, Version},
+		ics24_host::{
+			error::ValidationError,
+			identifier::{ChannelId, PortId},
+		},
+	},
+	prelude::*,
+	signer::SignerError,
+};
+use flex_error::define_error;
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		Ics04Channel
+			[ channel_error::Error ]
+			| _ | { "Ics04 channel error" },
+
+		InvalidPortId
+			{ context: String }
+			[ ValidationError ]
+			| _ | { "invalid port identifier" },
+
+		InvalidChannelId
+			{ context: String }
+			[ ValidationError ]
+			| _ | { "invalid channel identifier" },
+
+		InvalidCoin
+			[ Ics20Error ]
+			| _ | { "invalid fee coin" },
+
+		MissingFee
+			| _ | { "missing fee" },
+
+		MissingPacketId
+			| _ | { "missing packet identifier" },
+
+		InvalidFee
+			| _ | { "the fee must contain at least one non zero coin" },
+
+		RelayersNotSupported
+			| _ | { "restricting the relayers allowed to receive the fee is not supported" },
+
+		EmptyCounterpartyPayee
+			| _ | { "the counterparty payee is empty" },
+
+		FeeNotEnabled
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | { format_args!("fee is not enabled on channel {0} of port {1}", e.channel_id, e.port_id) },
+
+		PacketNotFound
+			{ port_id: PortId, channel_id: ChannelId, sequence: Sequence }
+			| e | { format_args!("no in-flight packet with sequence {0} on channel {1} of port {2}", e.sequence, e.channel_id, e.port_id) },
+
+		InvalidVersion
+			{ version: Version }
+			| e | { format_args!("invalid fee metadata in version '{0}'", e.version) },
+
+		UnsupportedVersion
+			{ version: String }
+			| e | { format_args!("expected fee version '{0}', got '{1}'", VERSION, e.version) },
+
+		AckDeserialization
+			| _ | { "failed to deserialize incentivized acknowledgement" },
+
+		AmountOutOfRange
+			| _ | { "fee amount out of range" },
+
+		Signer
+			[ SignerError ]
+			| _ | { "failed to parse signer" },
+
+		ImplementationSpecific
+			{ reason: String }
+			| e | { format_args!("implementation specific error: {}", e.reason) },
+	}
+}
+
+impl From<Error> for channel_error::Error {
+	fn from(e: Error) -> Self {
+		channel_error::Error::app_module(e.to_string())
+	}
+}
@@ -0,0 +1,155 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Handlers of the ICS29 messages.
+
+use crate::{
+	applications::fee::{
+		context::Ics29Context,
+		error::Error,
+		events::{IncentivizedPacketEvent, RegisterCounterpartyPayeeEvent, RegisterPayeeEvent},
+		msgs::{
+			pay_packet_fee::MsgPayPacketFee, pay_packet_fee_async::MsgPayPacketFeeAsync,
+			register_counterparty_payee::MsgRegisterCounterpartyPayee,
+			register_payee::MsgRegisterPayee,
+		},
+		packet_fee::{add_coins, Fee, PacketFee},
+	},
+	core::{
+		ics04_channel::packet::Sequence,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	handler::HandlerOutputBuilder,
+	prelude::*,
+};
+
+/// Checks that the channel exists and was opened with the fee version.
+fn ensure_fee_enabled(
+	ctx: &impl Ics29Context,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+) -> Result<(), Error> {
+	ctx.channel_end(&(port_id.clone(), *channel_id)).map_err(Error::ics04_channel)?;
+	if !ctx.is_fee_enabled(port_id, channel_id) {
+		return Err(Error::fee_not_enabled(port_id.clone(), *channel_id))
+	}
+	Ok(())
+}
+
+/// Escrows the fee for the next packet sent on the channel, and returns the sequence of the
+/// packet.
+pub fn pay_packet_fee(
+	ctx: &mut impl Ics29Context,
+	output: &mut HandlerOutputBuilder<()>,
+	msg: MsgPayPacketFee,
+) -> Result<Sequence, Error> {
+	let packet_fee = PacketFee { fee: msg.fee, refund_address: msg.signer, relayers: msg.relayers };
+	packet_fee.validate_basic()?;
+	ensure_fee_enabled(ctx, &msg.source_port_id, &msg.source_channel_id)?;
+
+	let sequence = ctx
+		.get_next_sequence_send(&(msg.source_port_id.clone(), msg.source_channel_id))
+		.map_err(Error::ics04_channel)?;
+	escrow_packet_fee(
+		ctx,
+		output,
+		msg.source_port_id,
+		msg.source_channel_id,
+		sequence,
+		packet_fee,
+	)?;
+	Ok(sequence)
+}
+
+/// Escrows a fee for a packet that was already sent and hasn't been acknowledged or timed out.
+pub fn pay_packet_fee_async(
+	ctx: &mut impl Ics29Context,
+	output: &mut HandlerOutputBuilder<()>,
+	msg: MsgPayPacketFeeAsync,
+) -> Result<(), Error> {
+	msg.packet_fee.validate_basic()?;
+	ensure_fee_enabled(ctx, &msg.port_id, &msg.channel_id)?;
+
+	// the commitment is deleted once the packet is acknowledged or timed out
+	if ctx
+		.get_packet_commitment(&(msg.port_id.clone(), msg.channel_id, msg.sequence))
+		.is_err()
+	{
+		return Err(Error::packet_not_found(msg.port_id, msg.channel_id, msg.sequence))
+	}
+	escrow_packet_fee(ctx, output, msg.port_id, msg.channel_id, msg.sequence, msg.packet_fee)
+}
+
+fn escrow_packet_fee(
+	ctx: &mut impl Ics29Context,
+	output: &mut HandlerOutputBuilder<()>,
+	port_id: PortId,
+	channel_id: ChannelId,
+	sequence: Sequence,
+	packet_fee: PacketFee,
+) -> Result<(), Error> {
+	ctx.escrow_coins(&packet_fee.refund_address, &packet_fee.fee.total()?)?;
+
+	let mut packet_fees = ctx.packet_fees(&port_id, &channel_id, sequence);
+	packet_fees.push(packet_fee);
+	let total_fee = packet_fees.iter().try_fold(Fee::default(), |total, packet_fee| {
+		Ok::<_, Error>(Fee {
+			recv_fee: add_coins(&total.recv_fee, &packet_fee.fee.recv_fee)?,
+			ack_fee: add_coins(&total.ack_fee, &packet_fee.fee.ack_fee)?,
+			timeout_fee: add_coins(&total.timeout_fee, &packet_fee.fee.timeout_fee)?,
+		})
+	})?;
+	ctx.store_packet_fees(port_id.clone(), channel_id, sequence, packet_fees)?;
+
+	output.emit(IncentivizedPacketEvent { port_id, channel_id, sequence, total_fee }.into());
+	output.log(format!("ICS29 fee escrowed for packet {} on channel {}", sequence, channel_id));
+	Ok(())
+}
+
+pub fn register_payee(
+	ctx: &mut impl Ics29Context,
+	output: &mut HandlerOutputBuilder<()>,
+	msg: MsgRegisterPayee,
+) -> Result<(), Error> {
+	ensure_fee_enabled(ctx, &msg.port_id, &msg.channel_id)?;
+	ctx.store_payee(msg.channel_id, msg.relayer.clone(), msg.payee.clone())?;
+
+	output.emit(
+		RegisterPayeeEvent { relayer: msg.relayer, payee: msg.payee, channel_id: msg.channel_id }
+			.into(),
+	);
+	Ok(())
+}
+
+pub fn register_counterparty_payee(
+	ctx: &mut impl Ics29Context,
+	output: &mut HandlerOutputBuilder<()>,
+	msg: MsgRegisterCounterpartyPayee,
+) -> Result<(), Error> {
+	ensure_fee_enabled(ctx, &msg.port_id, &msg.channel_id)?;
+	ctx.store_counterparty_payee(
+		msg.channel_id,
+		msg.relayer.clone(),
+		msg.counterparty_payee.clone(),
+	)?;
+
+	output.emit(
+		RegisterCounterpartyPayeeEvent {
+			relayer: msg.relayer,
+			counterparty_payee: msg.counterparty_payee,
+			channel_id: msg.channel_id,
+		}
+		.into(),
+	);
+	Ok(())
+}
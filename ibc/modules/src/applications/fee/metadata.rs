@@ -0,0 +1,62 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	applications::fee::{error::Error, VERSION},
+	core::ics04_channel::Version,
+	prelude::*,
+};
+
+/// The version of fee enabled channels, which wraps the version of the underlying application
+/// into JSON encoded metadata.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Metadata {
+	pub fee_version: String,
+	pub app_version: String,
+}
+
+impl Metadata {
+	pub fn new(app_version: &Version) -> Self {
+		Self { fee_version: VERSION.to_string(), app_version: app_version.to_string() }
+	}
+
+	pub fn app_version(&self) -> Version {
+		Version::new(self.app_version.clone())
+	}
+
+	pub fn validate(&self) -> Result<(), Error> {
+		if self.fee_version != VERSION {
+			return Err(Error::unsupported_version(self.fee_version.clone()))
+		}
+		Ok(())
+	}
+}
+
+impl TryFrom<&Version> for Metadata {
+	type Error = Error;
+
+	fn try_from(version: &Version) -> Result<Self, Self::Error> {
+		serde_json::from_str(&version.to_string())
+			.map_err(|_| Error::invalid_version(version.clone()))
+	}
+}
+
+impl From<Metadata> for Version {
+	fn from(metadata: Metadata) -> Self {
+		Version::new(
+			serde_json::to_string(&metadata).expect("Metadata's infallible Serialize impl failed"),
+		)
+	}
+}
@@ -0,0 +1,740 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! The ICS29 middleware, which wraps the callbacks of an application to negotiate the fee
+//! version, wrap the acknowledgements and pay the relayers.
+//!
+//! The callbacks are also exposed as functions so they can be used by hosts whose context can't
+//! be owned by the middleware.
+
+use crate::{
+	applications::{
+		fee::{
+			acknowledgement::IncentivizedAcknowledgement,
+			context::Ics29Context,
+			error::Error as Ics29Error,
+			events::DistributeFeeEvent,
+			metadata::Metadata,
+			packet_fee::{add_coins, sub_coins},
+		},
+		transfer::PrefixedCoin,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error,
+			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+/// Enables fees on the channel if the fee version is proposed, the version of the application
+/// is passed to it unwrapped.
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_init(
+	ctx: &mut impl Ics29Context,
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	counterparty: &Counterparty,
+	version: &Version,
+	relayer: &Signer,
+) -> Result<(), Error> {
+	let metadata = match Metadata::try_from(version) {
+		Ok(metadata) => metadata,
+		Err(_) =>
+			return app.on_chan_open_init(
+				module_ctx,
+				output,
+				order,
+				connection_hops,
+				port_id,
+				channel_id,
+				counterparty,
+				version,
+				relayer,
+			),
+	};
+	metadata.validate()?;
+	app.on_chan_open_init(
+		module_ctx,
+		output,
+		order,
+		connection_hops,
+		port_id,
+		channel_id,
+		counterparty,
+		&metadata.app_version(),
+		relayer,
+	)?;
+	ctx.set_fee_enabled(port_id.clone(), *channel_id)?;
+	Ok(())
+}
+
+/// Enables fees on the channel if the counterparty proposed the fee version, and wraps the
+/// version returned by the application.
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_try(
+	ctx: &mut impl Ics29Context,
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	counterparty: &Counterparty,
+	version: &Version,
+	counterparty_version: &Version,
+	relayer: &Signer,
+) -> Result<Version, Error> {
+	let metadata = match Metadata::try_from(counterparty_version) {
+		Ok(metadata) => metadata,
+		Err(_) =>
+			return app.on_chan_open_try(
+				module_ctx,
+				output,
+				order,
+				connection_hops,
+				port_id,
+				channel_id,
+				counterparty,
+				version,
+				counterparty_version,
+				relayer,
+			),
+	};
+	metadata.validate()?;
+	let proposed_version = Metadata::try_from(version)
+		.map(|m| m.app_version())
+		.unwrap_or_else(|_| version.clone());
+	let app_version = app.on_chan_open_try(
+		module_ctx,
+		output,
+		order,
+		connection_hops,
+		port_id,
+		channel_id,
+		counterparty,
+		&proposed_version,
+		&metadata.app_version(),
+		relayer,
+	)?;
+	ctx.set_fee_enabled(port_id.clone(), *channel_id)?;
+	Ok(Metadata::new(&app_version).into())
+}
+
+/// Fails if fees were proposed and the counterparty didn't accept the fee version.
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_ack(
+	ctx: &mut impl Ics29Context,
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	counterparty_version: &Version,
+	relayer: &Signer,
+) -> Result<(), Error> {
+	if !ctx.is_fee_enabled(port_id, channel_id) {
+		return app.on_chan_open_ack(
+			module_ctx,
+			output,
+			port_id,
+			channel_id,
+			counterparty_version,
+			relayer,
+		)
+	}
+	let metadata = Metadata::try_from(counterparty_version)?;
+	metadata.validate()?;
+	app.on_chan_open_ack(module_ctx, output, port_id, channel_id, &metadata.app_version(), relayer)
+}
+
+/// Unwraps the fee version proposed for an upgrade before passing it to the application, and
+/// wraps the version it returns.
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_upgrade_init(
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	version: &Version,
+) -> Result<Version, Error> {
+	let metadata = match Metadata::try_from(version) {
+		Ok(metadata) => metadata,
+		Err(_) =>
+			return app.on_chan_upgrade_init(
+				module_ctx,
+				output,
+				port_id,
+				channel_id,
+				order,
+				connection_hops,
+				version,
+			),
+	};
+	metadata.validate()?;
+	let app_version = app.on_chan_upgrade_init(
+		module_ctx,
+		output,
+		port_id,
+		channel_id,
+		order,
+		connection_hops,
+		&metadata.app_version(),
+	)?;
+	Ok(Metadata::new(&app_version).into())
+}
+
+/// Unwraps the fee version proposed by the counterparty for an upgrade before passing it to the
+/// application, and wraps the version it returns.
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_upgrade_try(
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	counterparty_version: &Version,
+) -> Result<Version, Error> {
+	let metadata = match Metadata::try_from(counterparty_version) {
+		Ok(metadata) => metadata,
+		Err(_) =>
+			return app.on_chan_upgrade_try(
+				module_ctx,
+				output,
+				port_id,
+				channel_id,
+				order,
+				connection_hops,
+				counterparty_version,
+			),
+	};
+	metadata.validate()?;
+	let app_version = app.on_chan_upgrade_try(
+		module_ctx,
+		output,
+		port_id,
+		channel_id,
+		order,
+		connection_hops,
+		&metadata.app_version(),
+	)?;
+	Ok(Metadata::new(&app_version).into())
+}
+
+pub fn on_chan_upgrade_ack(
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	counterparty_version: &Version,
+) -> Result<(), Error> {
+	let app_version = match Metadata::try_from(counterparty_version) {
+		Ok(metadata) => {
+			metadata.validate()?;
+			metadata.app_version()
+		},
+		Err(_) => counterparty_version.clone(),
+	};
+	app.on_chan_upgrade_ack(module_ctx, output, port_id, channel_id, &app_version)
+}
+
+/// Enables fees on the channel if it was upgraded to the fee version.
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_upgrade_open(
+	ctx: &mut impl Ics29Context,
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	version: &Version,
+) -> Result<(), Error> {
+	let metadata = match Metadata::try_from(version) {
+		Ok(metadata) => metadata,
+		Err(_) =>
+			return app.on_chan_upgrade_open(
+				module_ctx,
+				output,
+				port_id,
+				channel_id,
+				order,
+				connection_hops,
+				version,
+			),
+	};
+	app.on_chan_upgrade_open(
+		module_ctx,
+		output,
+		port_id,
+		channel_id,
+		order,
+		connection_hops,
+		&metadata.app_version(),
+	)?;
+	ctx.set_fee_enabled(port_id.clone(), *channel_id)?;
+	Ok(())
+}
+
+/// Refunds the fees of the in-flight packets once the application closed the channel.
+pub fn on_chan_close(
+	ctx: &mut impl Ics29Context,
+	output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+) -> Result<(), Error> {
+	if !ctx.is_fee_enabled(port_id, channel_id) {
+		return Ok(())
+	}
+	for sequence in ctx.incentivized_packets(port_id, channel_id) {
+		for packet_fee in ctx.packet_fees(port_id, channel_id, sequence) {
+			let refund = packet_fee.fee.total()?;
+			distribute(ctx, output, &packet_fee.refund_address, &refund)?;
+		}
+		ctx.store_packet_fees(port_id.clone(), *channel_id, sequence, vec![])?;
+	}
+	Ok(())
+}
+
+/// Wraps the acknowledgement of the application with the address the relayer registered to
+/// receive the recv fee on the counterparty chain.
+///
+/// If the application acknowledges the packet asynchronously, the address is recorded instead and
+/// the acknowledgement is wrapped when it's written, see [`wrap_async_acknowledgement`].
+pub fn on_recv_packet(
+	ctx: &mut impl Ics29Context,
+	app: &dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &mut Packet,
+	relayer: &Signer,
+) -> Result<Option<GenericAcknowledgement>, Error> {
+	let ack = app.on_recv_packet(module_ctx, output, packet, relayer)?;
+	if !ctx.is_fee_enabled(&packet.destination_port, &packet.destination_channel) {
+		return Ok(ack)
+	}
+	let forward_relayer =
+		ctx.counterparty_payee(&packet.destination_channel, relayer).unwrap_or_default();
+	match ack {
+		Some(ack) => Ok(Some(IncentivizedAcknowledgement::new(ack, forward_relayer).into())),
+		None => {
+			ctx.store_forward_relayer(
+				packet.destination_port.clone(),
+				packet.destination_channel,
+				packet.sequence,
+				Some(forward_relayer),
+			)?;
+			Ok(None)
+		},
+	}
+}
+
+/// Wraps the acknowledgement an application writes asynchronously for a packet received on an
+/// incentivized channel with the forward relayer recorded when the packet was received, or with
+/// an empty one if the channel was not incentivized yet then. Hosts must call it before writing
+/// the acknowledgement.
+pub fn wrap_async_acknowledgement(
+	ctx: &mut impl Ics29Context,
+	packet: &Packet,
+	ack: GenericAcknowledgement,
+) -> Result<GenericAcknowledgement, Ics29Error> {
+	let (port_id, channel_id) = (&packet.destination_port, &packet.destination_channel);
+	if !ctx.is_fee_enabled(port_id, channel_id) {
+		return Ok(ack)
+	}
+	let forward_relayer =
+		ctx.forward_relayer(port_id, channel_id, packet.sequence).unwrap_or_default();
+	ctx.store_forward_relayer(port_id.clone(), *channel_id, packet.sequence, None)?;
+	Ok(IncentivizedAcknowledgement::new(ack, forward_relayer).into())
+}
+
+/// Pays the recv fee to the forward relayer and the ack fee to the relayer of the
+/// acknowledgement, refunds the rest of the escrowed fees and passes the acknowledgement of the
+/// application to it.
+#[allow(clippy::too_many_arguments)]
+pub fn on_acknowledgement_packet(
+	ctx: &mut impl Ics29Context,
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &mut Packet,
+	acknowledgement: &GenericAcknowledgement,
+	relayer: &Signer,
+) -> Result<(), Error> {
+	if !ctx.is_fee_enabled(&packet.source_port, &packet.source_channel) {
+		return app.on_acknowledgement_packet(module_ctx, output, packet, acknowledgement, relayer)
+	}
+	let ack = IncentivizedAcknowledgement::try_from(acknowledgement)?;
+
+	let packet_fees = ctx.packet_fees(&packet.source_port, &packet.source_channel, packet.sequence);
+	if !packet_fees.is_empty() {
+		// the recv fee is refunded if the relayer didn't register an address on this chain
+		let forward_relayer = ack.forward_relayer_address.parse::<Signer>().ok();
+		let reverse_relayer =
+			ctx.payee(&packet.source_channel, relayer).unwrap_or_else(|| relayer.clone());
+		for packet_fee in packet_fees {
+			let fee = &packet_fee.fee;
+			let refund_address = &packet_fee.refund_address;
+			let forward_relayer = forward_relayer.as_ref().unwrap_or(refund_address);
+			distribute_or_refund(ctx, output, forward_relayer, refund_address, &fee.recv_fee)?;
+			distribute_or_refund(ctx, output, &reverse_relayer, refund_address, &fee.ack_fee)?;
+			let refund = sub_coins(&fee.total()?, &add_coins(&fee.recv_fee, &fee.ack_fee)?)?;
+			distribute(ctx, output, refund_address, &refund)?;
+		}
+		ctx.store_packet_fees(
+			packet.source_port.clone(),
+			packet.source_channel,
+			packet.sequence,
+			vec![],
+		)?;
+	}
+
+	app.on_acknowledgement_packet(module_ctx, output, packet, &ack.app_acknowledgement(), relayer)
+}
+
+/// Pays the timeout fee to the relayer of the timeout and refunds the rest of the escrowed fees.
+pub fn on_timeout_packet(
+	ctx: &mut impl Ics29Context,
+	app: &mut dyn Module,
+	module_ctx: &dyn ModuleCallbackContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &mut Packet,
+	relayer: &Signer,
+) -> Result<(), Error> {
+	if ctx.is_fee_enabled(&packet.source_port, &packet.source_channel) {
+		let packet_fees =
+			ctx.packet_fees(&packet.source_port, &packet.source_channel, packet.sequence);
+		let reverse_relayer =
+			ctx.payee(&packet.source_channel, relayer).unwrap_or_else(|| relayer.clone());
+		for packet_fee in &packet_fees {
+			let fee = &packet_fee.fee;
+			let refund_address = &packet_fee.refund_address;
+			distribute_or_refund(ctx, output, &reverse_relayer, refund_address, &fee.timeout_fee)?;
+			let refund = sub_coins(&fee.total()?, &fee.timeout_fee)?;
+			distribute(ctx, output, refund_address, &refund)?;
+		}
+		if !packet_fees.is_empty() {
+			ctx.store_packet_fees(
+				packet.source_port.clone(),
+				packet.source_channel,
+				packet.sequence,
+				vec![],
+			)?;
+		}
+	}
+
+	app.on_timeout_packet(module_ctx, output, packet, relayer)
+}
+
+fn distribute(
+	ctx: &mut impl Ics29Context,
+	output: &mut ModuleOutputBuilder,
+	receiver: &Signer,
+	coins: &[PrefixedCoin],
+) -> Result<(), Ics29Error> {
+	if coins.is_empty() {
+		return Ok(())
+	}
+	ctx.distribute_coins(receiver, coins)?;
+	output.emit(DistributeFeeEvent { receiver: receiver.clone(), fee: coins.to_vec() }.into());
+	Ok(())
+}
+
+/// The fee is refunded if it can't be paid to the relayer, e.g. because its address is invalid.
+fn distribute_or_refund(
+	ctx: &mut impl Ics29Context,
+	output: &mut ModuleOutputBuilder,
+	receiver: &Signer,
+	refund_address: &Signer,
+	coins: &[PrefixedCoin],
+) -> Result<(), Ics29Error> {
+	if distribute(ctx, output, receiver, coins).is_err() {
+		distribute(ctx, output, refund_address, coins)?;
+	}
+	Ok(())
+}
+
+/// The ICS29 middleware wrapping the application `M`, bound to the ports of the application.
+#[derive(Debug, Clone)]
+pub struct FeeMiddleware<Ctx, M> {
+	ctx: Ctx,
+	app: M,
+}
+
+impl<Ctx, M> FeeMiddleware<Ctx, M> {
+	pub fn new(ctx: Ctx, app: M) -> Self {
+		Self { ctx, app }
+	}
+
+	pub fn context(&self) -> &Ctx {
+		&self.ctx
+	}
+
+	pub fn app(&self) -> &M {
+		&self.app
+	}
+}
+
+impl<Ctx: Ics29Context + Clone + Send + Sync + 'static, M: Module> Module
+	for FeeMiddleware<Ctx, M>
+{
+	fn on_chan_open_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		on_chan_open_init(
+			&mut self.ctx,
+			&mut self.app,
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<Version, Error> {
+		on_chan_open_try(
+			&mut self.ctx,
+			&mut self.app,
+			ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+			counterparty_version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		on_chan_open_ack(
+			&mut self.ctx,
+			&mut self.app,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			counterparty_version,
+			relayer,
+		)
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		self.app.on_chan_open_confirm(ctx, output, port_id, channel_id, relayer)
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		self.app.on_chan_close_init(ctx, output, port_id, channel_id, relayer)?;
+		on_chan_close(&mut self.ctx, output, port_id, channel_id)
+	}
+
+	fn on_chan_close_confirm(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		self.app.on_chan_close_confirm(ctx, output, port_id, channel_id, relayer)?;
+		on_chan_close(&mut self.ctx, output, port_id, channel_id)
+	}
+
+	fn on_chan_upgrade_init(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<Version, Error> {
+		on_chan_upgrade_init(
+			&mut self.app,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_chan_upgrade_try(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		counterparty_version: &Version,
+	) -> Result<Version, Error> {
+		on_chan_upgrade_try(
+			&mut self.app,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			counterparty_version,
+		)
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+	) -> Result<(), Error> {
+		on_chan_upgrade_ack(&mut self.app, ctx, output, port_id, channel_id, counterparty_version)
+	}
+
+	fn on_chan_upgrade_open(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		version: &Version,
+	) -> Result<(), Error> {
+		on_chan_upgrade_open(
+			&mut self.ctx,
+			&mut self.app,
+			ctx,
+			output,
+			port_id,
+			channel_id,
+			order,
+			connection_hops,
+			version,
+		)
+	}
+
+	fn on_recv_packet(
+		&self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<Option<GenericAcknowledgement>, Error> {
+		// the context is a handle to the storage of the host
+		on_recv_packet(&mut self.ctx.clone(), &self.app, ctx, output, packet, relayer)
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &GenericAcknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		on_acknowledgement_packet(
+			&mut self.ctx,
+			&mut self.app,
+			ctx,
+			output,
+			packet,
+			acknowledgement,
+			relayer,
+		)
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		on_timeout_packet(&mut self.ctx, &mut self.app, ctx, output, packet, relayer)
+	}
+}
@@ -0,0 +1,407 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! ICS 29: Fee Middleware allows to incentivize the relaying of the packets of any application.
+//! Payers escrow fees for the packets sent on fee enabled channels, which are paid to the
+//! relayers delivering the packets and their acknowledgements or timeouts. Fees are enabled by
+//! negotiating the fee version in the channel handshake, wrapping the application version.
+pub mod acknowledgement;
+pub mod context;
+pub mod error;
+pub mod events;
+pub mod handler;
+pub mod metadata;
+pub mod middleware;
+pub mod msgs;
+pub mod packet_fee;
+
+/// Module identifier for the ICS29 middleware.
+pub const MODULE_ID_STR: &str = "feeibc";
+
+/// ICS29 middleware current version.
+pub const VERSION: &str = "ics29-1";
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		applications::{
+			fee::{
+				acknowledgement::IncentivizedAcknowledgement,
+				context::Ics29Context,
+				metadata::Metadata,
+				msgs::{
+					pay_packet_fee::MsgPayPacketFee,
+					register_counterparty_payee::MsgRegisterCounterpartyPayee,
+					register_payee::MsgRegisterPayee,
+				},
+				packet_fee::Fee,
+			},
+			transfer::PrefixedCoin,
+		},
+		core::{
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				error::Error,
+				msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+				packet::Packet,
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+			ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+		},
+		handler::HandlerOutputBuilder,
+		mock::context::MockContext,
+		prelude::*,
+		signer::Signer,
+		timestamp::Timestamp,
+		Height,
+	};
+
+	const APP_VERSION: &str = "ics20-1";
+	const APP_ACK: &[u8] = br#"{"result":"AQ=="}"#;
+
+	#[derive(Debug, Default)]
+	struct App;
+
+	impl Module for App {
+		fn on_chan_open_try(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			_output: &mut ModuleOutputBuilder,
+			_order: Order,
+			_connection_hops: &[ConnectionId],
+			_port_id: &PortId,
+			_channel_id: &ChannelId,
+			_counterparty: &Counterparty,
+			_version: &Version,
+			counterparty_version: &Version,
+			_relayer: &Signer,
+		) -> Result<Version, Error> {
+			assert_eq!(counterparty_version.to_string(), APP_VERSION);
+			Ok(counterparty_version.clone())
+		}
+
+		fn on_recv_packet(
+			&self,
+			_ctx: &dyn ModuleCallbackContext,
+			_output: &mut ModuleOutputBuilder,
+			_packet: &mut Packet,
+			_relayer: &Signer,
+		) -> Result<Option<GenericAcknowledgement>, Error> {
+			Ok(Some(GenericAcknowledgement::from_bytes(APP_ACK.to_vec())))
+		}
+
+		fn on_acknowledgement_packet(
+			&mut self,
+			_ctx: &dyn ModuleCallbackContext,
+			_output: &mut ModuleOutputBuilder,
+			_packet: &mut Packet,
+			acknowledgement: &GenericAcknowledgement,
+			_relayer: &Signer,
+		) -> Result<(), Error> {
+			assert_eq!(acknowledgement.as_ref(), APP_ACK);
+			Ok(())
+		}
+	}
+
+	/// Acknowledges the packets it receives asynchronously.
+	#[derive(Debug, Default)]
+	struct AsyncApp;
+
+	impl Module for AsyncApp {
+		fn on_recv_packet(
+			&self,
+			_ctx: &dyn ModuleCallbackContext,
+			_output: &mut ModuleOutputBuilder,
+			_packet: &mut Packet,
+			_relayer: &Signer,
+		) -> Result<Option<GenericAcknowledgement>, Error> {
+			Ok(None)
+		}
+	}
+
+	fn signer(s: &str) -> Signer {
+		s.parse().unwrap()
+	}
+
+	fn coins(amount: u64) -> Vec<PrefixedCoin> {
+		vec![PrefixedCoin { denom: "stake".parse().unwrap(), amount: amount.into() }]
+	}
+
+	fn channel_end(counterparty: Counterparty, version: &Version) -> ChannelEnd {
+		ChannelEnd::new(
+			State::Open,
+			Order::Unordered,
+			counterparty,
+			vec![ConnectionId::new(0)],
+			version.clone(),
+		)
+	}
+
+	fn distributed(ctx: &MockContext) -> Vec<(Signer, PrefixedCoin)> {
+		core::mem::take(&mut ctx.ibc_store.lock().unwrap().fee_distributed)
+	}
+
+	#[test]
+	fn pay_relayers_of_incentivized_packets() {
+		let port_id = PortId::transfer();
+		let source_channel = ChannelId::new(0);
+		let destination_channel = ChannelId::new(1);
+		let relayer = signer("relayer");
+		let version: Version = Metadata::new(&Version::new(APP_VERSION.to_string())).into();
+		let mut app = App;
+		let mut output = ModuleOutputBuilder::new();
+
+		// the handshake only enables fees when the fee version is negotiated
+		let mut src_ctx = MockContext::default();
+		let mut dst_ctx = MockContext::default();
+		let module_ctx = MockContext::default();
+		middleware::on_chan_open_init(
+			&mut src_ctx,
+			&mut app,
+			&module_ctx,
+			&mut output,
+			Order::Unordered,
+			&[ConnectionId::new(0)],
+			&port_id,
+			&source_channel,
+			&Counterparty::new(port_id.clone(), None),
+			&version,
+			&relayer,
+		)
+		.unwrap();
+		assert!(src_ctx.is_fee_enabled(&port_id, &source_channel));
+		let open_try = |ctx: &mut MockContext, app: &mut App, channel_id, version: &Version| {
+			middleware::on_chan_open_try(
+				ctx,
+				app,
+				&module_ctx,
+				&mut ModuleOutputBuilder::new(),
+				Order::Unordered,
+				&[ConnectionId::new(0)],
+				&port_id,
+				&channel_id,
+				&Counterparty::new(port_id.clone(), Some(source_channel)),
+				version,
+				version,
+				&relayer,
+			)
+			.unwrap()
+		};
+		let app_version = Version::new(APP_VERSION.to_string());
+		assert_eq!(open_try(&mut dst_ctx, &mut app, ChannelId::new(2), &app_version), app_version);
+		assert!(!dst_ctx.is_fee_enabled(&port_id, &ChannelId::new(2)));
+		assert_eq!(open_try(&mut dst_ctx, &mut app, destination_channel, &version), version);
+		assert!(dst_ctx.is_fee_enabled(&port_id, &destination_channel));
+
+		let mut src_ctx = src_ctx
+			.with_channel(
+				port_id.clone(),
+				source_channel,
+				channel_end(
+					Counterparty::new(port_id.clone(), Some(destination_channel)),
+					&version,
+				),
+			)
+			.with_send_sequence(port_id.clone(), source_channel, 1.into());
+		let mut dst_ctx = dst_ctx.with_channel(
+			port_id.clone(),
+			destination_channel,
+			channel_end(Counterparty::new(port_id.clone(), Some(source_channel)), &version),
+		);
+
+		// the payer escrows the fees of the next packet
+		let payer = signer("payer");
+		let fee = Fee { recv_fee: coins(30), ack_fee: coins(20), timeout_fee: coins(70) };
+		let msg = MsgPayPacketFee {
+			fee,
+			source_port_id: port_id.clone(),
+			source_channel_id: source_channel,
+			signer: payer.clone(),
+			relayers: vec![],
+		};
+		let sequence =
+			handler::pay_packet_fee(&mut src_ctx, &mut HandlerOutputBuilder::new(), msg.clone())
+				.unwrap();
+		assert_eq!(sequence, 1.into());
+		assert_eq!(
+			src_ctx.ibc_store.lock().unwrap().fee_escrowed,
+			vec![(payer.clone(), coins(70)[0].clone())]
+		);
+
+		// the relayer registers its payees on both chains
+		let payee = signer("payee");
+		let counterparty_payee = signer("counterparty_payee");
+		handler::register_payee(
+			&mut src_ctx,
+			&mut HandlerOutputBuilder::new(),
+			MsgRegisterPayee {
+				port_id: port_id.clone(),
+				channel_id: source_channel,
+				relayer: relayer.clone(),
+				payee: payee.clone(),
+			},
+		)
+		.unwrap();
+		handler::register_counterparty_payee(
+			&mut dst_ctx,
+			&mut HandlerOutputBuilder::new(),
+			MsgRegisterCounterpartyPayee {
+				port_id: port_id.clone(),
+				channel_id: destination_channel,
+				relayer: relayer.clone(),
+				counterparty_payee: counterparty_payee.to_string(),
+			},
+		)
+		.unwrap();
+
+		// the acknowledgement tells the source chain where to pay the recv fee
+		let mut packet = Packet {
+			sequence,
+			source_port: port_id.clone(),
+			source_channel,
+			destination_port: port_id.clone(),
+			destination_channel,
+			data: vec![],
+			timeout_height: Height::new(0, 100),
+			timeout_timestamp: Timestamp::none(),
+		};
+		let ack = middleware::on_recv_packet(
+			&mut dst_ctx,
+			&app,
+			&module_ctx,
+			&mut output,
+			&mut packet,
+			&relayer,
+		)
+		.unwrap()
+		.unwrap();
+		let incentivized_ack = IncentivizedAcknowledgement::try_from(&ack).unwrap();
+		assert_eq!(incentivized_ack.app_acknowledgement, APP_ACK);
+		assert_eq!(incentivized_ack.forward_relayer_address, counterparty_payee.to_string());
+		assert!(incentivized_ack.underlying_app_success);
+
+		middleware::on_acknowledgement_packet(
+			&mut src_ctx,
+			&mut app,
+			&module_ctx,
+			&mut output,
+			&mut packet,
+			&ack,
+			&relayer,
+		)
+		.unwrap();
+		assert_eq!(
+			distributed(&src_ctx),
+			vec![
+				(counterparty_payee, coins(30)[0].clone()),
+				(payee.clone(), coins(20)[0].clone()),
+				(payer.clone(), coins(20)[0].clone()),
+			]
+		);
+		assert!(src_ctx.packet_fees(&port_id, &source_channel, sequence).is_empty());
+
+		// the relayer of the timeout is paid the timeout fee
+		let mut src_ctx = src_ctx.with_send_sequence(port_id.clone(), source_channel, 2.into());
+		let sequence =
+			handler::pay_packet_fee(&mut src_ctx, &mut HandlerOutputBuilder::new(), msg).unwrap();
+		packet.sequence = sequence;
+		middleware::on_timeout_packet(
+			&mut src_ctx,
+			&mut app,
+			&module_ctx,
+			&mut output,
+			&mut packet,
+			&relayer,
+		)
+		.unwrap();
+		assert_eq!(distributed(&src_ctx), vec![(payee, coins(70)[0].clone())]);
+	}
+
+	#[test]
+	fn wrap_async_acknowledgements_of_incentivized_packets() {
+		let port_id = PortId::transfer();
+		let relayer = signer("relayer");
+		let counterparty_payee = signer("counterparty_payee").to_string();
+		let module_ctx = MockContext::default();
+		let mut ctx = MockContext::default();
+		ctx.set_fee_enabled(port_id.clone(), ChannelId::new(1)).unwrap();
+		ctx.store_counterparty_payee(
+			ChannelId::new(1),
+			relayer.clone(),
+			counterparty_payee.clone(),
+		)
+		.unwrap();
+
+		let packet = |destination_channel| Packet {
+			sequence: 1.into(),
+			source_port: port_id.clone(),
+			source_channel: ChannelId::new(0),
+			destination_port: port_id.clone(),
+			destination_channel,
+			data: vec![],
+			timeout_height: Height::new(0, 100),
+			timeout_timestamp: Timestamp::none(),
+		};
+		let app_ack = || GenericAcknowledgement::from_bytes(APP_ACK.to_vec());
+
+		// nothing is acknowledged on recv, the forward relayer is recorded instead
+		let mut incentivized = packet(ChannelId::new(1));
+		let ack = middleware::on_recv_packet(
+			&mut ctx,
+			&AsyncApp,
+			&module_ctx,
+			&mut ModuleOutputBuilder::new(),
+			&mut incentivized,
+			&relayer,
+		)
+		.unwrap();
+		assert!(ack.is_none());
+		assert_eq!(
+			ctx.forward_relayer(&port_id, &ChannelId::new(1), 1.into()),
+			Some(counterparty_payee.clone())
+		);
+
+		// and the acknowledgement written later by the application is wrapped with it
+		let ack =
+			middleware::wrap_async_acknowledgement(&mut ctx, &incentivized, app_ack()).unwrap();
+		let incentivized_ack = IncentivizedAcknowledgement::try_from(&ack).unwrap();
+		assert_eq!(incentivized_ack.app_acknowledgement, APP_ACK);
+		assert_eq!(incentivized_ack.forward_relayer_address, counterparty_payee);
+		assert!(ctx.forward_relayer(&port_id, &ChannelId::new(1), 1.into()).is_none());
+
+		// acknowledgements of channels without fees are written as is
+		let mut plain = packet(ChannelId::new(2));
+		let ack = middleware::on_recv_packet(
+			&mut ctx,
+			&AsyncApp,
+			&module_ctx,
+			&mut ModuleOutputBuilder::new(),
+			&mut plain,
+			&relayer,
+		)
+		.unwrap();
+		assert!(ack.is_none());
+		let ack = middleware::wrap_async_acknowledgement(&mut ctx, &plain, app_ack()).unwrap();
+		assert_eq!(ack.as_ref(), APP_ACK);
+
+		// unless fees were enabled after the packet was received, no forward relayer being
+		// recorded for it then
+		ctx.set_fee_enabled(port_id.clone(), ChannelId::new(2)).unwrap();
+		let ack = middleware::wrap_async_acknowledgement(&mut ctx, &plain, app_ack()).unwrap();
+		let incentivized_ack = IncentivizedAcknowledgement::try_from(&ack).unwrap();
+		assert_eq!(incentivized_ack.app_acknowledgement, APP_ACK);
+		assert!(incentivized_ack.forward_relayer_address.is_empty());
+	}
+}
@@ -0,0 +1,84 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use ibc_proto::ibc::applications::fee::v1::MsgPayPacketFee as RawMsgPayPacketFee;
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::fee::{error::Error, packet_fee::Fee},
+	core::ics24_host::identifier::{ChannelId, PortId},
+	prelude::*,
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.applications.fee.v1.MsgPayPacketFee";
+
+/// Escrows a fee for the next packet sent on the channel. It's meant to be submitted in the same
+/// transaction as the message sending the packet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgPayPacketFee {
+	pub fee: Fee,
+	pub source_port_id: PortId,
+	pub source_channel_id: ChannelId,
+	/// The payer of the fee, which is also refunded the fees not paid to a relayer.
+	pub signer: Signer,
+	pub relayers: Vec<String>,
+}
+
+impl Msg for MsgPayPacketFee {
+	type ValidationError = Error;
+	type Raw = RawMsgPayPacketFee;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl TryFrom<RawMsgPayPacketFee> for MsgPayPacketFee {
+	type Error = Error;
+
+	fn try_from(raw: RawMsgPayPacketFee) -> Result<Self, Self::Error> {
+		Ok(Self {
+			fee: raw.fee.ok_or_else(Error::missing_fee)?.try_into()?,
+			source_port_id: raw
+				.source_port_id
+				.parse()
+				.map_err(|e| Error::invalid_port_id(raw.source_port_id.clone(), e))?,
+			source_channel_id: raw
+				.source_channel_id
+				.parse()
+				.map_err(|e| Error::invalid_channel_id(raw.source_channel_id.clone(), e))?,
+			signer: raw.signer.parse().map_err(Error::signer)?,
+			relayers: raw.relayers,
+		})
+	}
+}
+
+impl From<MsgPayPacketFee> for RawMsgPayPacketFee {
+	fn from(msg: MsgPayPacketFee) -> Self {
+		RawMsgPayPacketFee {
+			fee: Some(msg.fee.into()),
+			source_port_id: msg.source_port_id.to_string(),
+			source_channel_id: msg.source_channel_id.to_string(),
+			signer: msg.signer.to_string(),
+			relayers: msg.relayers,
+		}
+	}
+}
+
+impl Protobuf<RawMsgPayPacketFee> for MsgPayPacketFee {}
@@ -0,0 +1,84 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use ibc_proto::ibc::applications::fee::v1::MsgRegisterCounterpartyPayee as RawMsgRegisterCounterpartyPayee;
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::fee::error::Error,
+	core::ics24_host::identifier::{ChannelId, PortId},
+	prelude::*,
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.applications.fee.v1.MsgRegisterCounterpartyPayee";
+
+/// Registers the address of the counterparty chain the recv fees earned by the relayer are paid
+/// to, for the packets it delivers on the channel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgRegisterCounterpartyPayee {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub relayer: Signer,
+	/// Not validated, since the address format of the counterparty chain isn't known.
+	pub counterparty_payee: String,
+}
+
+impl Msg for MsgRegisterCounterpartyPayee {
+	type ValidationError = Error;
+	type Raw = RawMsgRegisterCounterpartyPayee;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl TryFrom<RawMsgRegisterCounterpartyPayee> for MsgRegisterCounterpartyPayee {
+	type Error = Error;
+
+	fn try_from(raw: RawMsgRegisterCounterpartyPayee) -> Result<Self, Self::Error> {
+		if raw.counterparty_payee.trim().is_empty() {
+			return Err(Error::empty_counterparty_payee())
+		}
+		Ok(Self {
+			port_id: raw
+				.port_id
+				.parse()
+				.map_err(|e| Error::invalid_port_id(raw.port_id.clone(), e))?,
+			channel_id: raw
+				.channel_id
+				.parse()
+				.map_err(|e| Error::invalid_channel_id(raw.channel_id.clone(), e))?,
+			relayer: raw.relayer.parse().map_err(Error::signer)?,
+			counterparty_payee: raw.counterparty_payee,
+		})
+	}
+}
+
+impl From<MsgRegisterCounterpartyPayee> for RawMsgRegisterCounterpartyPayee {
+	fn from(msg: MsgRegisterCounterpartyPayee) -> Self {
+		RawMsgRegisterCounterpartyPayee {
+			port_id: msg.port_id.to_string(),
+			channel_id: msg.channel_id.to_string(),
+			relayer: msg.relayer.to_string(),
+			counterparty_payee: msg.counterparty_payee,
+		}
+	}
+}
+
+impl Protobuf<RawMsgRegisterCounterpartyPayee> for MsgRegisterCounterpartyPayee {}
@@ -0,0 +1,79 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use ibc_proto::ibc::applications::fee::v1::MsgRegisterPayee as RawMsgRegisterPayee;
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::fee::error::Error,
+	core::ics24_host::identifier::{ChannelId, PortId},
+	prelude::*,
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.applications.fee.v1.MsgRegisterPayee";
+
+/// Registers the address the fees earned by the relayer on the channel are paid to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MsgRegisterPayee {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub relayer: Signer,
+	pub payee: Signer,
+}
+
+impl Msg for MsgRegisterPayee {
+	type ValidationError = Error;
+	type Raw = RawMsgRegisterPayee;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl TryFrom<RawMsgRegisterPayee> for MsgRegisterPayee {
+	type Error = Error;
+
+	fn try_from(raw: RawMsgRegisterPayee) -> Result<Self, Self::Error> {
+		Ok(Self {
+			port_id: raw
+				.port_id
+				.parse()
+				.map_err(|e| Error::invalid_port_id(raw.port_id.clone(), e))?,
+			channel_id: raw
+				.channel_id
+				.parse()
+				.map_err(|e| Error::invalid_channel_id(raw.channel_id.clone(), e))?,
+			relayer: raw.relayer.parse().map_err(Error::signer)?,
+			payee: raw.payee.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgRegisterPayee> for RawMsgRegisterPayee {
+	fn from(msg: MsgRegisterPayee) -> Self {
+		RawMsgRegisterPayee {
+			port_id: msg.port_id.to_string(),
+			channel_id: msg.channel_id.to_string(),
+			relayer: msg.relayer.to_string(),
+			payee: msg.payee.to_string(),
+		}
+	}
+}
+
+impl Protobuf<RawMsgRegisterPayee> for MsgRegisterPayee {}
@@ -0,0 +1,199 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use ibc_proto::ibc::applications::fee::v1::{
+	Fee as RawFee, PacketFee as RawPacketFee, PacketFees as RawPacketFees,
+};
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::{fee::error::Error, transfer::PrefixedCoin},
+	prelude::*,
+	signer::Signer,
+};
+
+/// The fees paid to the relayers of a packet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Fee {
+	/// Paid to the relayer delivering the packet to the counterparty chain.
+	pub recv_fee: Vec<PrefixedCoin>,
+	/// Paid to the relayer delivering the acknowledgement of the packet.
+	pub ack_fee: Vec<PrefixedCoin>,
+	/// Paid to the relayer delivering the timeout of the packet.
+	pub timeout_fee: Vec<PrefixedCoin>,
+}
+
+impl Fee {
+	/// Checks that the fee pays at least one of the relayers.
+	pub fn validate_basic(&self) -> Result<(), Error> {
+		let zero = 0u64.into();
+		let is_paying = |coins: &[PrefixedCoin]| coins.iter().any(|coin| coin.amount != zero);
+		if !is_paying(&self.recv_fee) && !is_paying(&self.ack_fee) && !is_paying(&self.timeout_fee)
+		{
+			return Err(Error::invalid_fee())
+		}
+		Ok(())
+	}
+
+	/// Returns the amount escrowed for the fee. The recv and ack fees are paid together while
+	/// the timeout fee is only paid instead of them, so the maximum of both is escrowed for
+	/// each denomination.
+	pub fn total(&self) -> Result<Vec<PrefixedCoin>, Error> {
+		let mut total = add_coins(&self.recv_fee, &self.ack_fee)?;
+		for coin in merge_coins(&self.timeout_fee)? {
+			match total.iter_mut().find(|c| c.denom == coin.denom) {
+				Some(c) => c.amount = c.amount.max(coin.amount),
+				None => total.push(coin),
+			}
+		}
+		Ok(total)
+	}
+}
+
+/// Returns the sum of the coins, with a single coin per denomination.
+fn merge_coins(coins: &[PrefixedCoin]) -> Result<Vec<PrefixedCoin>, Error> {
+	let mut merged: Vec<PrefixedCoin> = vec![];
+	for coin in coins {
+		match merged.iter_mut().find(|c| c.denom == coin.denom) {
+			Some(c) =>
+				c.amount =
+					c.amount.checked_add(coin.amount).ok_or_else(Error::amount_out_of_range)?,
+			None => merged.push(coin.clone()),
+		}
+	}
+	Ok(merged)
+}
+
+pub(crate) fn add_coins(
+	lhs: &[PrefixedCoin],
+	rhs: &[PrefixedCoin],
+) -> Result<Vec<PrefixedCoin>, Error> {
+	merge_coins(&lhs.iter().chain(rhs).cloned().collect::<Vec<_>>())
+}
+
+/// Subtracts `rhs` from `lhs`, leaving out the denominations whose amount drops to zero.
+pub(crate) fn sub_coins(
+	lhs: &[PrefixedCoin],
+	rhs: &[PrefixedCoin],
+) -> Result<Vec<PrefixedCoin>, Error> {
+	let zero = 0u64.into();
+	let mut result = merge_coins(lhs)?;
+	for coin in merge_coins(rhs)? {
+		let c = result
+			.iter_mut()
+			.find(|c| c.denom == coin.denom)
+			.ok_or_else(Error::amount_out_of_range)?;
+		c.amount = c.amount.checked_sub(coin.amount).ok_or_else(Error::amount_out_of_range)?;
+	}
+	result.retain(|c| c.amount != zero);
+	Ok(result)
+}
+
+impl TryFrom<RawFee> for Fee {
+	type Error = Error;
+
+	fn try_from(raw: RawFee) -> Result<Self, Self::Error> {
+		let coins = |raw: Vec<_>| {
+			raw.into_iter()
+				.map(PrefixedCoin::try_from)
+				.collect::<Result<Vec<_>, _>>()
+				.map_err(Error::invalid_coin)
+		};
+		Ok(Self {
+			recv_fee: coins(raw.recv_fee)?,
+			ack_fee: coins(raw.ack_fee)?,
+			timeout_fee: coins(raw.timeout_fee)?,
+		})
+	}
+}
+
+impl From<Fee> for RawFee {
+	fn from(fee: Fee) -> Self {
+		RawFee {
+			recv_fee: fee.recv_fee.into_iter().map(Into::into).collect(),
+			ack_fee: fee.ack_fee.into_iter().map(Into::into).collect(),
+			timeout_fee: fee.timeout_fee.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// A fee escrowed for a packet, refunded to `refund_address` when it isn't paid to a relayer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PacketFee {
+	pub fee: Fee,
+	pub refund_address: Signer,
+	/// Relayers allowed to relay the packet, must be empty since the restriction isn't
+	/// supported.
+	pub relayers: Vec<String>,
+}
+
+impl PacketFee {
+	pub fn new(fee: Fee, refund_address: Signer) -> Self {
+		Self { fee, refund_address, relayers: vec![] }
+	}
+
+	pub fn validate_basic(&self) -> Result<(), Error> {
+		if !self.relayers.is_empty() {
+			return Err(Error::relayers_not_supported())
+		}
+		self.fee.validate_basic()
+	}
+}
+
+impl TryFrom<RawPacketFee> for PacketFee {
+	type Error = Error;
+
+	fn try_from(raw: RawPacketFee) -> Result<Self, Self::Error> {
+		Ok(Self {
+			fee: raw.fee.ok_or_else(Error::missing_fee)?.try_into()?,
+			refund_address: raw.refund_address.parse().map_err(Error::signer)?,
+			relayers: raw.relayers,
+		})
+	}
+}
+
+impl From<PacketFee> for RawPacketFee {
+	fn from(packet_fee: PacketFee) -> Self {
+		RawPacketFee {
+			fee: Some(packet_fee.fee.into()),
+			refund_address: packet_fee.refund_address.to_string(),
+			relayers: packet_fee.relayers,
+		}
+	}
+}
+
+impl Protobuf<RawPacketFee> for PacketFee {}
+
+/// All the fees escrowed for a packet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PacketFees {
+	pub packet_fees: Vec<PacketFee>,
+}
+
+impl TryFrom<RawPacketFees> for PacketFees {
+	type Error = Error;
+
+	fn try_from(raw: RawPacketFees) -> Result<Self, Self::Error> {
+		let packet_fees =
+			raw.packet_fees.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?;
+		Ok(Self { packet_fees })
+	}
+}
+
+impl From<PacketFees> for RawPacketFees {
+	fn from(fees: PacketFees) -> Self {
+		RawPacketFees { packet_fees: fees.packet_fees.into_iter().map(Into::into).collect() }
+	}
+}
+
+impl Protobuf<RawPacketFees> for PacketFees {}
@@ -0,0 +1,81 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::error::Error;
+use crate::prelude::*;
+use core::{
+	fmt::{Display, Formatter},
+	str::FromStr,
+};
+
+use ibc_proto::{cosmos::base::abci::v1beta1::TxMsgData, google::protobuf::Any};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+
+/// A string constant included in error acknowledgements. The reason of the failure is emitted in
+/// the events instead, since it's not deterministic across hosts.
+/// NOTE: Changing this const is state machine breaking as acknowledgements are written into state
+pub const ACK_ERR_STR: &str = "error handling packet on host chain: see events for details";
+
+/// The acknowledgement written by the host. A successful acknowledgement contains the protobuf
+/// encoded `TxMsgData` with the responses of the executed messages.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Acknowledgement {
+	Result(#[serde(with = "ibc_proto::base64")] Vec<u8>),
+	Error(String),
+}
+
+impl Acknowledgement {
+	/// Creates a successful acknowledgement from the responses of the executed messages.
+	pub fn success(msg_responses: Vec<Any>) -> Self {
+		#[allow(deprecated)]
+		let data = TxMsgData { data: vec![], msg_responses };
+		Self::Result(data.encode_to_vec())
+	}
+
+	pub fn from_error(_err: Error) -> Self {
+		Self::Error(ACK_ERR_STR.to_string())
+	}
+
+	pub fn is_successful(&self) -> bool {
+		!matches!(self, Self::Error(_))
+	}
+
+	/// Returns the responses of the executed messages of a successful acknowledgement.
+	pub fn msg_responses(&self) -> Result<Vec<Any>, Error> {
+		match self {
+			Self::Result(data) => TxMsgData::decode(data.as_slice())
+				.map(|data| data.msg_responses)
+				.map_err(|_| Error::ack_deserialization()),
+			Self::Error(_) => Ok(vec![]),
+		}
+	}
+}
+
+impl Display for Acknowledgement {
+	fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+		serde_json::to_string(self)
+			.map_err(|_| core::fmt::Error)
+			.and_then(|s| write!(f, "{}", s))
+	}
+}
+
+impl FromStr for Acknowledgement {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		serde_json::from_str(s).map_err(|_e| Error::ack_deserialization())
+	}
+}
@@ -0,0 +1,107 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc_proto::google::protobuf::Any;
+use subtle_encoding::hex;
+
+use crate::{
+	applications::interchain_accounts::error::Error,
+	core::{
+		ics04_channel::context::ChannelKeeper,
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{ModuleCallbackContext, ReaderContext},
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+/// Captures all the dependencies which the ICS27 host requires to register interchain accounts
+/// and execute the transactions sent by the controllers.
+///
+/// Accounts and active channels are indexed by the host connection and the controller port, which
+/// identifies the owner of the account.
+pub trait Ics27HostContext: ModuleCallbackContext {
+	/// Returns true iff the host submodule is enabled.
+	fn is_host_enabled(&self) -> bool;
+
+	/// Returns true iff messages of the given type may be executed by interchain accounts.
+	fn is_message_allowed(&self, type_url: &str) -> bool;
+
+	/// Converts a derived address into an account of the host chain.
+	fn account_address(&self, address: Vec<u8>) -> Result<Signer, Error> {
+		String::from_utf8(hex::encode_upper(address))
+			.expect("hex encoded bytes are not valid UTF8")
+			.parse::<Signer>()
+			.map_err(Error::signer)
+	}
+
+	/// Returns the interchain account owned by the controller port over the given connection.
+	fn interchain_account(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<Signer>;
+
+	/// Registers a new interchain account, creating it on the host chain if necessary.
+	fn store_interchain_account(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		account: Signer,
+	) -> Result<(), Error>;
+
+	/// Returns the channel over which the controller port sends transactions.
+	fn active_channel(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<ChannelId>;
+
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Error>;
+
+	/// Executes an allowed message on behalf of the interchain account and returns the response
+	/// of the message handler.
+	///
+	/// The messages of a transaction are executed in order and the transaction fails at the first
+	/// message that fails. If this method returns an error, the runtime is expected to rollback
+	/// all state modifications caused by the previous messages of the transaction.
+	fn execute_message(&mut self, account: &Signer, message: Any) -> Result<Any, Error>;
+}
+
+/// Captures all the dependencies which the ICS27 controller requires to open interchain account
+/// channels and send transactions over them.
+///
+/// Accounts and active channels are indexed by the controller connection and port.
+pub trait Ics27ControllerContext: ReaderContext + ChannelKeeper {
+	/// Returns true iff the controller submodule is enabled.
+	fn is_controller_enabled(&self) -> bool;
+
+	/// Returns the address of the interchain account registered by the port over the given
+	/// connection.
+	fn interchain_account(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<Signer>;
+
+	fn store_interchain_account(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		account: Signer,
+	) -> Result<(), Error>;
+
+	/// Returns the channel over which the port sends transactions to its interchain account.
+	fn active_channel(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<ChannelId>;
+
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Error>;
+}
@@ -0,0 +1,392 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The controller side of ICS27, which registers interchain accounts on a host and sends
+//! transactions to them.
+
+use crate::{
+	applications::interchain_accounts::{
+		acknowledgement::Acknowledgement,
+		context::Ics27ControllerContext,
+		controller_port_id, counterparty_connection_id,
+		error::Error as Ics27Error,
+		events::{AckStatusEvent, TimeoutEvent},
+		metadata::Metadata,
+		owner_from_port_id,
+		packet::InterchainAccountPacketData,
+		single_connection_hop, HOST_PORT_ID_STR,
+	},
+	core::{
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, Order, State},
+			error::Error,
+			handler::send_packet::send_packet,
+			msgs::{
+				acknowledgement::Acknowledgement as GenericAcknowledgement,
+				chan_open_init::MsgChannelOpenInit,
+			},
+			packet::{Packet, Sequence},
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	handler::{HandlerOutput, HandlerOutputBuilder},
+	prelude::*,
+	signer::Signer,
+	timestamp::Timestamp,
+	Height,
+};
+
+/// Builds the message opening the channel to the host over which the interchain account of
+/// `owner` is registered. An empty `version` proposes the default metadata.
+///
+/// The message must be delivered by the caller, with the controller port of `owner` bound to the
+/// controller module.
+pub fn register_interchain_account(
+	ctx: &impl Ics27ControllerContext,
+	owner: Signer,
+	connection_id: ConnectionId,
+	version: Version,
+) -> Result<MsgChannelOpenInit, Ics27Error> {
+	if !ctx.is_controller_enabled() {
+		return Err(Ics27Error::controller_disabled())
+	}
+
+	let port_id = controller_port_id(&owner)?;
+	if let Some(channel_id) = ctx.active_channel(&connection_id, &port_id) {
+		let channel_end = ctx
+			.channel_end(&(port_id.clone(), channel_id))
+			.map_err(Ics27Error::ics04_channel)?;
+		if channel_end.is_open() {
+			return Err(Ics27Error::active_channel_exists(port_id, channel_id))
+		}
+	}
+
+	let version = if version == Version::empty() {
+		let host_connection_id = counterparty_connection_id(ctx, &connection_id)?;
+		Metadata::new(&connection_id, &host_connection_id).into()
+	} else {
+		version
+	};
+	let channel = ChannelEnd::new(
+		State::Init,
+		Order::Ordered,
+		Counterparty::new(HOST_PORT_ID_STR.parse().expect("valid port id"), None),
+		vec![connection_id],
+		version,
+	);
+	Ok(MsgChannelOpenInit::new(port_id, channel, owner))
+}
+
+/// Sends a transaction to the interchain account of `owner` over its active channel on the given
+/// connection, and returns the sequence of the packet.
+/// If this method returns an error, the runtime is expected to rollback all state modifications to
+/// the `Ctx` caused by all messages from the transaction that this call is a part of.
+pub fn send_tx(
+	ctx: &mut impl Ics27ControllerContext,
+	output: &mut HandlerOutputBuilder<()>,
+	owner: &Signer,
+	connection_id: &ConnectionId,
+	data: InterchainAccountPacketData,
+	timeout_height: Height,
+	timeout_timestamp: Timestamp,
+) -> Result<Sequence, Ics27Error> {
+	if !ctx.is_controller_enabled() {
+		return Err(Ics27Error::controller_disabled())
+	}
+	data.validate_basic()?;
+
+	let source_port = controller_port_id(owner)?;
+	let source_channel = ctx.active_channel(connection_id, &source_port).ok_or_else(|| {
+		Ics27Error::active_channel_not_found(connection_id.clone(), source_port.clone())
+	})?;
+	let source_channel_end = ctx
+		.channel_end(&(source_port.clone(), source_channel))
+		.map_err(Ics27Error::ics04_channel)?;
+
+	let destination_port = source_channel_end.counterparty().port_id().clone();
+	let destination_channel = *source_channel_end.counterparty().channel_id().ok_or_else(|| {
+		Ics27Error::destination_channel_not_found(source_port.clone(), source_channel)
+	})?;
+
+	let sequence = ctx
+		.get_next_sequence_send(&(source_port.clone(), source_channel))
+		.map_err(Ics27Error::ics04_channel)?;
+
+	let packet = Packet {
+		sequence,
+		source_port,
+		source_channel,
+		destination_port,
+		destination_channel,
+		data: serde_json::to_vec(&data)
+			.expect("InterchainAccountPacketData's infallible Serialize impl failed"),
+		timeout_height,
+		timeout_timestamp,
+	};
+
+	let HandlerOutput { result, log, events } =
+		send_packet(ctx, packet).map_err(Ics27Error::ics04_channel)?;
+
+	ctx.store_packet_result(result).map_err(Ics27Error::ics04_channel)?;
+
+	output.merge_output(HandlerOutput::builder().with_log(log).with_events(events).with_result(()));
+
+	output.log(format!(
+		"IBC interchain account transaction: {} --({})--> {}",
+		owner, sequence, connection_id
+	));
+
+	Ok(sequence)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_init(
+	ctx: &mut impl Ics27ControllerContext,
+	_output: &mut ModuleOutputBuilder,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	port_id: &PortId,
+	_channel_id: &ChannelId,
+	counterparty: &Counterparty,
+	version: &Version,
+) -> Result<(), Ics27Error> {
+	if order != Order::Ordered {
+		return Err(Ics27Error::channel_not_ordered(order))
+	}
+	owner_from_port_id(port_id)?;
+	if counterparty.port_id().as_str() != HOST_PORT_ID_STR {
+		return Err(Ics27Error::invalid_host_port(counterparty.port_id().clone()))
+	}
+
+	let connection_id = single_connection_hop(connection_hops)?;
+	let host_connection_id = counterparty_connection_id(&*ctx, connection_id)?;
+	let metadata = Metadata::try_from(version)?;
+	metadata.validate(connection_id, &host_connection_id)?;
+
+	// a closed channel may be replaced by a new one with the same metadata
+	if let Some(channel_id) = ctx.active_channel(connection_id, port_id) {
+		let channel_end = ctx
+			.channel_end(&(port_id.clone(), channel_id))
+			.map_err(Ics27Error::ics04_channel)?;
+		if channel_end.is_open() {
+			return Err(Ics27Error::active_channel_exists(port_id.clone(), channel_id))
+		}
+		if !Metadata::try_from(channel_end.version())?.is_compatible(&metadata) {
+			return Err(Ics27Error::metadata_changed())
+		}
+	}
+
+	if let Some(account) = ctx.interchain_account(connection_id, port_id) {
+		if !metadata.address.is_empty() && metadata.address != account.as_ref() {
+			return Err(Ics27Error::address_mismatch(account.to_string(), metadata.address))
+		}
+	}
+
+	Ok(())
+}
+
+/// Activates the channel and records the address of the interchain account chosen by the host.
+pub fn on_chan_open_ack(
+	ctx: &mut impl Ics27ControllerContext,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	counterparty_version: &Version,
+) -> Result<(), Ics27Error> {
+	owner_from_port_id(port_id)?;
+	let channel_end = ctx
+		.channel_end(&(port_id.clone(), *channel_id))
+		.map_err(Ics27Error::ics04_channel)?;
+	let connection_id = single_connection_hop(channel_end.connection_hops())?;
+	let host_connection_id = counterparty_connection_id(&*ctx, connection_id)?;
+
+	let metadata = Metadata::try_from(counterparty_version)?;
+	metadata.validate(connection_id, &host_connection_id)?;
+	if !Metadata::try_from(channel_end.version())?.is_compatible(&metadata) {
+		return Err(Ics27Error::metadata_changed())
+	}
+	let account = metadata.address.parse::<Signer>().map_err(|_| Ics27Error::empty_address())?;
+
+	if let Some(active_channel_id) = ctx.active_channel(connection_id, port_id) {
+		let active_channel_end = ctx
+			.channel_end(&(port_id.clone(), active_channel_id))
+			.map_err(Ics27Error::ics04_channel)?;
+		if active_channel_end.is_open() {
+			return Err(Ics27Error::active_channel_exists(port_id.clone(), active_channel_id))
+		}
+	}
+	if let Some(existing) = ctx.interchain_account(connection_id, port_id) {
+		if existing != account {
+			return Err(Ics27Error::address_mismatch(existing.to_string(), account.to_string()))
+		}
+	}
+
+	ctx.store_active_channel(connection_id.clone(), port_id.clone(), *channel_id)?;
+	ctx.store_interchain_account(connection_id.clone(), port_id.clone(), account)
+}
+
+pub fn on_acknowledgement_packet(
+	_ctx: &mut impl Ics27ControllerContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	acknowledgement: &GenericAcknowledgement,
+	_relayer: &Signer,
+) -> Result<(), Ics27Error> {
+	let acknowledgement = serde_json::from_slice::<Acknowledgement>(acknowledgement.as_ref())
+		.map_err(|_| Ics27Error::ack_deserialization())?;
+
+	output.emit(AckStatusEvent { sequence: packet.sequence, acknowledgement }.into());
+
+	Ok(())
+}
+
+pub fn on_timeout_packet(
+	_ctx: &mut impl Ics27ControllerContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	_relayer: &Signer,
+) -> Result<(), Ics27Error> {
+	let timeout_event =
+		TimeoutEvent { controller_channel_id: packet.source_channel, sequence: packet.sequence };
+	output.emit(timeout_event.into());
+
+	Ok(())
+}
+
+/// The ICS27 controller application, bound to the controller ports.
+#[derive(Debug, Clone)]
+pub struct InterchainAccountsControllerModule<Ctx> {
+	ctx: Ctx,
+}
+
+impl<Ctx> InterchainAccountsControllerModule<Ctx> {
+	pub fn new(ctx: Ctx) -> Self {
+		Self { ctx }
+	}
+
+	pub fn context(&self) -> &Ctx {
+		&self.ctx
+	}
+}
+
+impl<Ctx: Ics27ControllerContext + Clone + Send + Sync + 'static> Module
+	for InterchainAccountsControllerModule<Ctx>
+{
+	fn on_chan_open_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(on_chan_open_init(
+			&mut self.ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			version,
+		)?)
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		_counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Error> {
+		Err(Ics27Error::handshake_not_by_controller().into())
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(on_chan_open_ack(&mut self.ctx, output, port_id, channel_id, counterparty_version)?)
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Err(Ics27Error::handshake_not_by_controller().into())
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Err(Ics27Error::cant_close_channel().into())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_packet: &mut Packet,
+		_relayer: &Signer,
+	) -> Result<Option<GenericAcknowledgement>, Error> {
+		Err(Ics27Error::unexpected_packet().into())
+	}
+
+	fn on_acknowledgement_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		acknowledgement: &GenericAcknowledgement,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(on_acknowledgement_packet(&mut self.ctx, output, packet, acknowledgement, relayer)?)
+	}
+
+	fn on_timeout_packet(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(on_timeout_packet(&mut self.ctx, output, packet, relayer)?)
+	}
+}
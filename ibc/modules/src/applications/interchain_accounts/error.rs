@@ -0,0 +1,165 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::interchain_accounts::{CONTROLLER_PORT_PREFIX, HOST_PORT_ID_STR, VERSION},
+	core::{
+		ics03_connection::error as connection_error,
+		ics04_channel::{channel::Order, error as channel_error, Version},
+		ics24_host::{
+			error::ValidationError,
+			identifier::{ChannelId, ConnectionId, PortId},
+		},
+	},
+	prelude::*,
+	signer::SignerError,
+};
+use flex_error::{define_error, TraceError};
+
+define_error! {
+	#[derive(Debug, PartialEq, Eq)]
+	Error {
+		Ics04Channel
+			[ channel_error::Error ]
+			| _ | { "Ics04 channel error" },
+
+		Ics03Connection
+			[ connection_error::Error ]
+			| _ | { "Ics03 connection error" },
+
+		InvalidPortId
+			{ context: String }
+			[ ValidationError ]
+			| _ | { "invalid port identifier" },
+
+		MissingCounterpartyConnection
+			{ connection_id: ConnectionId }
+			| e | { format_args!("the counterparty of connection {0} is not set", e.connection_id) },
+
+		DestinationChannelNotFound
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | { format_args!("destination channel not found in the counterparty of port_id {0} and channel_id {1} ", e.port_id, e.channel_id) },
+
+		InvalidControllerPort
+			{ port_id: PortId }
+			| e | { format_args!("invalid controller port '{0}', expected the '{1}' prefix", e.port_id, CONTROLLER_PORT_PREFIX) },
+
+		InvalidHostPort
+			{ port_id: PortId }
+			| e | { format_args!("invalid host port '{0}', expected '{1}'", e.port_id, HOST_PORT_ID_STR) },
+
+		ChannelNotOrdered
+			{ order: Order }
+			| e | { format_args!("expected '{0}' channel, got '{1}'", Order::Ordered, e.order) },
+
+		InvalidConnectionHops
+			{ length: usize }
+			| e | { format_args!("expected a single connection hop, got {0}", e.length) },
+
+		InvalidVersion
+			{ version: Version }
+			| e | { format_args!("invalid metadata in version '{0}'", e.version) },
+
+		UnsupportedVersion
+			{ version: String }
+			| e | { format_args!("expected version '{0}', got '{1}'", VERSION, e.version) },
+
+		UnsupportedEncoding
+			{ encoding: String }
+			| e | { format_args!("unsupported encoding '{0}'", e.encoding) },
+
+		UnsupportedTxType
+			{ tx_type: String }
+			| e | { format_args!("unsupported transaction type '{0}'", e.tx_type) },
+
+		InvalidMetadataConnection
+			{ expected: ConnectionId, actual: String }
+			| e | { format_args!("expected connection '{0}' in metadata, got '{1}'", e.expected, e.actual) },
+
+		MetadataChanged
+			| _ | { "the metadata of an active channel can't be changed" },
+
+		AddressMismatch
+			{ expected: String, actual: String }
+			| e | { format_args!("expected interchain account address '{0}', got '{1}'", e.expected, e.actual) },
+
+		EmptyAddress
+			| _ | { "interchain account address is empty" },
+
+		ActiveChannelExists
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | { format_args!("an active channel {0} already exists on port {1}", e.channel_id, e.port_id) },
+
+		ActiveChannelNotFound
+			{ connection_id: ConnectionId, port_id: PortId }
+			| e | { format_args!("no active channel for port {0} on connection {1}", e.port_id, e.connection_id) },
+
+		AccountNotFound
+			{ connection_id: ConnectionId, port_id: PortId }
+			| e | { format_args!("no interchain account for port {0} on connection {1}", e.port_id, e.connection_id) },
+
+		HandshakeNotByController
+			| _ | { "the channel handshake must be initiated by the controller" },
+
+		CantCloseChannel
+			| _ | { "interchain account channels can't be closed by the application" },
+
+		UnexpectedPacket
+			| _ | { "the controller doesn't receive packets" },
+
+		HostDisabled
+			| _ | { "the host submodule is disabled" },
+
+		ControllerDisabled
+			| _ | { "the controller submodule is disabled" },
+
+		PacketDataDeserialization
+			| _ | { "failed to deserialize packet data" },
+
+		InvalidPacketType
+			{ packet_type: i32 }
+			| e | { format_args!("unsupported packet type {0}", e.packet_type) },
+
+		EmptyPacketData
+			| _ | { "packet data is empty" },
+
+		DecodeTx
+			[ TraceError<prost::DecodeError> ]
+			| _ | { "error decoding the transaction in the packet data" },
+
+		EmptyTx
+			| _ | { "transaction contains no messages" },
+
+		MessageNotAllowed
+			{ type_url: String }
+			| e | { format_args!("message type '{0}' is not allowed by the host", e.type_url) },
+
+		AckDeserialization
+			| _ | { "failed to deserialize acknowledgement" },
+
+		Signer
+			[ SignerError ]
+			| _ | { "failed to parse signer" },
+
+		ImplementationSpecific
+			{ reason: String }
+			| e | { format_args!("implementation specific error: {}", e.reason) },
+	}
+}
+
+impl From<Error> for channel_error::Error {
+	fn from(e: Error) -> Self {
+		channel_error::Error::app_module(e.to_string())
+	}
+}
@@ -0,0 +1,96 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::interchain_accounts::{
+		acknowledgement::Acknowledgement, CONTROLLER_MODULE_ID_STR, HOST_MODULE_ID_STR,
+	},
+	core::{ics04_channel::packet::Sequence, ics24_host::identifier::ChannelId},
+	events::{IbcEvent, ModuleEvent},
+	prelude::*,
+	signer::Signer,
+};
+
+const EVENT_TYPE_PACKET: &str = "ics27_packet";
+const EVENT_TYPE_TIMEOUT: &str = "ics27_timeout";
+
+/// Emitted by the host when it receives a packet.
+pub struct PacketEvent {
+	pub host_channel_id: ChannelId,
+	pub account: Option<Signer>,
+	pub success: bool,
+	/// The reason of the failure, which isn't written into the acknowledgement.
+	pub error: Option<String>,
+}
+
+impl From<PacketEvent> for IbcEvent {
+	fn from(ev: PacketEvent) -> Self {
+		let PacketEvent { host_channel_id, account, success, error } = ev;
+		let mut event = ModuleEvent {
+			kind: EVENT_TYPE_PACKET.to_string(),
+			module_name: HOST_MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![
+				("host_channel_id", host_channel_id).into(),
+				("success", success).into(),
+			],
+		};
+		if let Some(account) = account {
+			event.attributes.push(("account", account).into());
+		}
+		if let Some(error) = error {
+			event.attributes.push(("error", error).into());
+		}
+		IbcEvent::AppModule(event)
+	}
+}
+
+/// Emitted by the controller when the acknowledgement of a packet is received.
+pub struct AckStatusEvent {
+	pub sequence: Sequence,
+	pub acknowledgement: Acknowledgement,
+}
+
+impl From<AckStatusEvent> for IbcEvent {
+	fn from(ev: AckStatusEvent) -> Self {
+		let AckStatusEvent { sequence, acknowledgement } = ev;
+		let mut event = ModuleEvent {
+			kind: EVENT_TYPE_PACKET.to_string(),
+			module_name: CONTROLLER_MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![("sequence", sequence).into()],
+		};
+		let attr_label = if acknowledgement.is_successful() { "success" } else { "error" };
+		event.attributes.push((attr_label, acknowledgement.to_string()).into());
+		IbcEvent::AppModule(event)
+	}
+}
+
+/// Emitted by the controller when a packet times out, which closes the ordered channel.
+pub struct TimeoutEvent {
+	pub controller_channel_id: ChannelId,
+	pub sequence: Sequence,
+}
+
+impl From<TimeoutEvent> for IbcEvent {
+	fn from(ev: TimeoutEvent) -> Self {
+		let TimeoutEvent { controller_channel_id, sequence } = ev;
+		IbcEvent::AppModule(ModuleEvent {
+			kind: EVENT_TYPE_TIMEOUT.to_string(),
+			module_name: CONTROLLER_MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![
+				("controller_channel_id", controller_channel_id).into(),
+				("sequence", sequence).into(),
+			],
+		})
+	}
+}
@@ -0,0 +1,287 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The host side of ICS27, which registers the interchain accounts and executes the transactions
+//! sent to them.
+
+use ibc_proto::google::protobuf::Any;
+
+use crate::{
+	applications::interchain_accounts::{
+		acknowledgement::Acknowledgement, context::Ics27HostContext, counterparty_connection_id,
+		derive_address, error::Error as Ics27Error, events::PacketEvent, metadata::Metadata,
+		owner_from_port_id, packet::InterchainAccountPacketData, single_connection_hop,
+		HOST_PORT_ID_STR,
+	},
+	core::{
+		ics04_channel::{
+			channel::{Counterparty, Order},
+			error::Error,
+			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+			packet::Packet,
+			Version,
+		},
+		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		ics26_routing::context::{Module, ModuleCallbackContext, ModuleOutputBuilder},
+	},
+	prelude::*,
+	signer::Signer,
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn on_chan_open_try(
+	ctx: &mut impl Ics27HostContext,
+	_output: &mut ModuleOutputBuilder,
+	order: Order,
+	connection_hops: &[ConnectionId],
+	port_id: &PortId,
+	_channel_id: &ChannelId,
+	counterparty: &Counterparty,
+	counterparty_version: &Version,
+) -> Result<Version, Ics27Error> {
+	if order != Order::Ordered {
+		return Err(Ics27Error::channel_not_ordered(order))
+	}
+	if port_id.as_str() != HOST_PORT_ID_STR {
+		return Err(Ics27Error::invalid_host_port(port_id.clone()))
+	}
+	let controller_port_id = counterparty.port_id();
+	owner_from_port_id(controller_port_id)?;
+
+	let connection_id = single_connection_hop(connection_hops)?;
+	let controller_connection_id = counterparty_connection_id(&*ctx, connection_id)?;
+	let mut metadata = Metadata::try_from(counterparty_version)?;
+	metadata.validate(&controller_connection_id, connection_id)?;
+
+	// a closed channel may be replaced by a new one with the same metadata
+	if let Some(channel_id) = ctx.active_channel(connection_id, controller_port_id) {
+		let channel_end = ctx
+			.channel_end(&(port_id.clone(), channel_id))
+			.map_err(Ics27Error::ics04_channel)?;
+		if channel_end.is_open() {
+			return Err(Ics27Error::active_channel_exists(port_id.clone(), channel_id))
+		}
+		if !Metadata::try_from(channel_end.version())?.is_compatible(&metadata) {
+			return Err(Ics27Error::metadata_changed())
+		}
+	}
+
+	let account = match ctx.interchain_account(connection_id, controller_port_id) {
+		Some(account) => account,
+		None => {
+			let address = derive_address(|data| ctx.hash(data), connection_id, controller_port_id);
+			let account = ctx.account_address(address)?;
+			ctx.store_interchain_account(
+				connection_id.clone(),
+				controller_port_id.clone(),
+				account.clone(),
+			)?;
+			account
+		},
+	};
+	if !metadata.address.is_empty() && metadata.address != account.as_ref() {
+		return Err(Ics27Error::address_mismatch(account.to_string(), metadata.address))
+	}
+	metadata.address = account.to_string();
+
+	Ok(metadata.into())
+}
+
+pub fn on_chan_open_confirm(
+	ctx: &mut impl Ics27HostContext,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+) -> Result<(), Ics27Error> {
+	let channel_end = ctx
+		.channel_end(&(port_id.clone(), *channel_id))
+		.map_err(Ics27Error::ics04_channel)?;
+	let connection_id = single_connection_hop(channel_end.connection_hops())?;
+	ctx.store_active_channel(
+		connection_id.clone(),
+		channel_end.counterparty().port_id().clone(),
+		*channel_id,
+	)
+}
+
+/// Executes the transaction in the packet with the interchain account of the sending port. The
+/// reason of a failure is only emitted in the events, the acknowledgement contains a constant
+/// error string.
+pub fn on_recv_packet(
+	ctx: &mut impl Ics27HostContext,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	_relayer: &Signer,
+) -> Acknowledgement {
+	let (ack, account, error) = match execute_tx(ctx, packet) {
+		Ok((account, msg_responses)) =>
+			(Acknowledgement::success(msg_responses), Some(account), None),
+		Err(e) => {
+			log::debug!(target: "ics::interchain_accounts", "failed to execute tx: {}", e);
+			let error = e.to_string();
+			(Acknowledgement::from_error(e), None, Some(error))
+		},
+	};
+
+	let packet_event = PacketEvent {
+		host_channel_id: packet.destination_channel,
+		account,
+		success: ack.is_successful(),
+		error,
+	};
+	output.emit(packet_event.into());
+
+	ack
+}
+
+fn execute_tx(
+	ctx: &mut impl Ics27HostContext,
+	packet: &Packet,
+) -> Result<(Signer, Vec<Any>), Ics27Error> {
+	if !ctx.is_host_enabled() {
+		return Err(Ics27Error::host_disabled())
+	}
+
+	let data = serde_json::from_slice::<InterchainAccountPacketData>(&packet.data)
+		.map_err(|_| Ics27Error::packet_data_deserialization())?;
+	let messages = data.messages()?;
+	if let Some(message) =
+		messages.iter().find(|message| !ctx.is_message_allowed(&message.type_url))
+	{
+		return Err(Ics27Error::message_not_allowed(message.type_url.clone()))
+	}
+
+	let channel_end = ctx
+		.channel_end(&(packet.destination_port.clone(), packet.destination_channel))
+		.map_err(Ics27Error::ics04_channel)?;
+	let connection_id = single_connection_hop(channel_end.connection_hops())?;
+	let account = ctx.interchain_account(connection_id, &packet.source_port).ok_or_else(|| {
+		Ics27Error::account_not_found(connection_id.clone(), packet.source_port.clone())
+	})?;
+
+	let msg_responses = messages
+		.into_iter()
+		.map(|message| ctx.execute_message(&account, message))
+		.collect::<Result<Vec<_>, _>>()?;
+	Ok((account, msg_responses))
+}
+
+/// The ICS27 host application, bound to the [`HOST_PORT_ID_STR`] port.
+///
+/// Packets are received through a shared reference, so the context is cloned to execute them.
+/// Clones of the context must share the same state.
+#[derive(Debug, Clone)]
+pub struct InterchainAccountsHostModule<Ctx> {
+	ctx: Ctx,
+}
+
+impl<Ctx> InterchainAccountsHostModule<Ctx> {
+	pub fn new(ctx: Ctx) -> Self {
+		Self { ctx }
+	}
+
+	pub fn context(&self) -> &Ctx {
+		&self.ctx
+	}
+}
+
+impl<Ctx: Ics27HostContext + Clone + Send + Sync + 'static> Module
+	for InterchainAccountsHostModule<Ctx>
+{
+	fn on_chan_open_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty: &Counterparty,
+		_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Err(Ics27Error::handshake_not_by_controller().into())
+	}
+
+	fn on_chan_open_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		order: Order,
+		connection_hops: &[ConnectionId],
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		counterparty: &Counterparty,
+		_version: &Version,
+		counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<Version, Error> {
+		Ok(on_chan_open_try(
+			&mut self.ctx,
+			output,
+			order,
+			connection_hops,
+			port_id,
+			channel_id,
+			counterparty,
+			counterparty_version,
+		)?)
+	}
+
+	fn on_chan_open_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty_version: &Version,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Err(Ics27Error::handshake_not_by_controller().into())
+	}
+
+	fn on_chan_open_confirm(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Ok(on_chan_open_confirm(&mut self.ctx, output, port_id, channel_id)?)
+	}
+
+	fn on_chan_close_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_relayer: &Signer,
+	) -> Result<(), Error> {
+		Err(Ics27Error::cant_close_channel().into())
+	}
+
+	fn on_recv_packet(
+		&self,
+		_ctx: &dyn ModuleCallbackContext,
+		output: &mut ModuleOutputBuilder,
+		packet: &mut Packet,
+		relayer: &Signer,
+	) -> Result<Option<GenericAcknowledgement>, Error> {
+		let mut ctx = self.ctx.clone();
+		let ack = on_recv_packet(&mut ctx, output, packet, relayer);
+		Ok(Some(GenericAcknowledgement::from_bytes(ack.to_string().into_bytes())))
+	}
+}
@@ -0,0 +1,140 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc_proto::ibc::applications::interchain_accounts::v1::Metadata as RawMetadata;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	applications::interchain_accounts::{error::Error, VERSION},
+	core::{ics04_channel::Version, ics24_host::identifier::ConnectionId},
+	prelude::*,
+};
+
+/// The only encoding of the transactions in the packet data supported by ICS27.
+pub const ENCODING_PROTOBUF: &str = "proto3";
+
+/// The only transaction type supported by ICS27, a list of messages executed atomically.
+pub const TX_TYPE_SDK_MULTI_MSG: &str = "sdk_multi_msg";
+
+/// The metadata negotiated in the version of the interchain account channels, encoded as JSON.
+///
+/// The controller proposes the metadata with an empty address, the host fills in the address of
+/// the interchain account in its version.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Metadata {
+	pub version: String,
+	pub controller_connection_id: String,
+	pub host_connection_id: String,
+	#[serde(default)]
+	pub address: String,
+	pub encoding: String,
+	pub tx_type: String,
+}
+
+impl Metadata {
+	/// Returns the default metadata proposed by a controller for the given connections.
+	pub fn new(controller_connection_id: &ConnectionId, host_connection_id: &ConnectionId) -> Self {
+		Self {
+			version: VERSION.to_string(),
+			controller_connection_id: controller_connection_id.to_string(),
+			host_connection_id: host_connection_id.to_string(),
+			address: String::new(),
+			encoding: ENCODING_PROTOBUF.to_string(),
+			tx_type: TX_TYPE_SDK_MULTI_MSG.to_string(),
+		}
+	}
+
+	/// Checks that the metadata uses the supported version, encoding and transaction type, and
+	/// refers to the given connections.
+	pub fn validate(
+		&self,
+		controller_connection_id: &ConnectionId,
+		host_connection_id: &ConnectionId,
+	) -> Result<(), Error> {
+		if self.version != VERSION {
+			return Err(Error::unsupported_version(self.version.clone()))
+		}
+		if self.encoding != ENCODING_PROTOBUF {
+			return Err(Error::unsupported_encoding(self.encoding.clone()))
+		}
+		if self.tx_type != TX_TYPE_SDK_MULTI_MSG {
+			return Err(Error::unsupported_tx_type(self.tx_type.clone()))
+		}
+		if self.controller_connection_id != controller_connection_id.as_str() {
+			return Err(Error::invalid_metadata_connection(
+				controller_connection_id.clone(),
+				self.controller_connection_id.clone(),
+			))
+		}
+		if self.host_connection_id != host_connection_id.as_str() {
+			return Err(Error::invalid_metadata_connection(
+				host_connection_id.clone(),
+				self.host_connection_id.clone(),
+			))
+		}
+		Ok(())
+	}
+
+	/// Returns `true` if `other` only differs from `self` in the address, which the host sets.
+	pub fn is_compatible(&self, other: &Self) -> bool {
+		self.version == other.version &&
+			self.controller_connection_id == other.controller_connection_id &&
+			self.host_connection_id == other.host_connection_id &&
+			self.encoding == other.encoding &&
+			self.tx_type == other.tx_type
+	}
+}
+
+impl TryFrom<&Version> for Metadata {
+	type Error = Error;
+
+	fn try_from(version: &Version) -> Result<Self, Self::Error> {
+		serde_json::from_str(&version.to_string())
+			.map_err(|_| Error::invalid_version(version.clone()))
+	}
+}
+
+impl From<Metadata> for Version {
+	fn from(metadata: Metadata) -> Self {
+		Version::new(
+			serde_json::to_string(&metadata).expect("Metadata's infallible Serialize impl failed"),
+		)
+	}
+}
+
+impl From<RawMetadata> for Metadata {
+	fn from(raw: RawMetadata) -> Self {
+		Self {
+			version: raw.version,
+			controller_connection_id: raw.controller_connection_id,
+			host_connection_id: raw.host_connection_id,
+			address: raw.address,
+			encoding: raw.encoding,
+			tx_type: raw.tx_type,
+		}
+	}
+}
+
+impl From<Metadata> for RawMetadata {
+	fn from(metadata: Metadata) -> Self {
+		Self {
+			version: metadata.version,
+			controller_connection_id: metadata.controller_connection_id,
+			host_connection_id: metadata.host_connection_id,
+			address: metadata.address,
+			encoding: metadata.encoding,
+			tx_type: metadata.tx_type,
+		}
+	}
+}
@@ -0,0 +1,335 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! ICS 27: Interchain Accounts allows a controller chain to register an account on a host chain
+//! over an ordered channel and to execute transactions from it by sending packets. The
+//! [`controller`] side opens the channel and sends the transactions, the [`host`] side creates
+//! the account and executes the messages of the transactions it allows.
+pub mod acknowledgement;
+pub mod context;
+pub mod controller;
+pub mod error;
+pub mod events;
+pub mod host;
+pub mod metadata;
+pub mod packet;
+
+use crate::{
+	core::{
+		ics03_connection::context::ConnectionReader,
+		ics24_host::identifier::{ConnectionId, PortId},
+	},
+	prelude::*,
+	signer::Signer,
+};
+use error::Error;
+
+/// Module identifier for the ICS27 host application.
+pub const HOST_MODULE_ID_STR: &str = "icahost";
+
+/// Module identifier for the ICS27 controller application.
+pub const CONTROLLER_MODULE_ID_STR: &str = "icacontroller";
+
+/// The port identifier the ICS27 host application binds with.
+pub const HOST_PORT_ID_STR: &str = "icahost";
+
+/// Prefix of the controller port identifiers, which are followed by the account owner.
+pub const CONTROLLER_PORT_PREFIX: &str = "icacontroller-";
+
+/// ICS27 application current version.
+pub const VERSION: &str = "ics27-1";
+
+/// Name of the module whose address the interchain account addresses are derived from.
+pub const MODULE_NAME: &str = "interchainaccounts";
+
+/// Returns the controller port identifier of the accounts owned by `owner`.
+pub fn controller_port_id(owner: &Signer) -> Result<PortId, Error> {
+	format!("{}{}", CONTROLLER_PORT_PREFIX, owner)
+		.parse()
+		.map_err(|e| Error::invalid_port_id(owner.to_string(), e))
+}
+
+/// Returns the owner of the accounts controlled through `port_id`, which must be a controller
+/// port.
+pub fn owner_from_port_id(port_id: &PortId) -> Result<Signer, Error> {
+	port_id
+		.as_str()
+		.strip_prefix(CONTROLLER_PORT_PREFIX)
+		.ok_or_else(|| Error::invalid_controller_port(port_id.clone()))?
+		.parse()
+		.map_err(Error::signer)
+}
+
+/// Derives the address of the interchain account registered over `connection_id` by the owner of
+/// `port_id`, following the ADR-028 derivation used by ibc-go:
+/// `hash(hash(module_address) || connection_id || port_id)`, where the module address is the
+/// first 20 bytes of `hash(MODULE_NAME)`.
+///
+/// The address only depends on the connection and the controller port, so it can be known by the
+/// controller before the channel is opened.
+///
+/// https://github.com/cosmos/cosmos-sdk/blob/master/docs/architecture/adr-028-public-key-addresses.md
+pub fn derive_address(
+	hash: impl Fn(Vec<u8>) -> Vec<u8>,
+	connection_id: &ConnectionId,
+	port_id: &PortId,
+) -> Vec<u8> {
+	let mut module_address = hash(MODULE_NAME.as_bytes().to_vec());
+	module_address.truncate(20);
+
+	let mut data = hash(module_address);
+	data.extend_from_slice(connection_id.as_bytes());
+	data.extend_from_slice(port_id.as_bytes());
+	hash(data)
+}
+
+/// Interchain account channels are established over a single connection.
+fn single_connection_hop(connection_hops: &[ConnectionId]) -> Result<&ConnectionId, Error> {
+	match connection_hops {
+		[connection_id] => Ok(connection_id),
+		_ => Err(Error::invalid_connection_hops(connection_hops.len())),
+	}
+}
+
+/// Returns the identifier of the connection on the counterparty chain.
+fn counterparty_connection_id(
+	ctx: &dyn ConnectionReader,
+	connection_id: &ConnectionId,
+) -> Result<ConnectionId, Error> {
+	ctx.connection_end(connection_id)
+		.map_err(Error::ics03_connection)?
+		.counterparty()
+		.connection_id()
+		.cloned()
+		.ok_or_else(|| Error::missing_counterparty_connection(connection_id.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+	use ibc_proto::google::protobuf::Any;
+
+	use super::*;
+	use crate::{
+		applications::interchain_accounts::{
+			acknowledgement::Acknowledgement,
+			context::{Ics27ControllerContext, Ics27HostContext},
+			controller, host,
+			metadata::Metadata,
+			packet::InterchainAccountPacketData,
+		},
+		core::{
+			ics02_client::height::Height,
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				context::ChannelReader,
+				packet::Packet,
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId},
+		},
+		events::IbcEvent,
+		handler::HandlerOutputBuilder,
+		mock::context::MockContext,
+		test_utils::get_dummy_bech32_account,
+		timestamp::{Timestamp, ZERO_DURATION},
+	};
+
+	const MSG_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+
+	fn context(connection_id: u64, counterparty_connection_id: u64) -> MockContext {
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::new(counterparty_connection_id)),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		MockContext::default()
+			.with_client(&ClientId::default(), Height::new(0, 5))
+			.with_connection(ConnectionId::new(connection_id), connection_end)
+	}
+
+	#[test]
+	fn register_account_and_execute_tx() {
+		let mut controller_ctx = context(0, 1);
+		let mut host_ctx = context(1, 0);
+		host_ctx
+			.ibc_store
+			.lock()
+			.unwrap()
+			.ica_allowed_messages
+			.push(MSG_SEND_TYPE_URL.to_string());
+
+		let owner: Signer = get_dummy_bech32_account().parse().unwrap();
+		let controller_connection_id = ConnectionId::new(0);
+		let host_connection_id = ConnectionId::new(1);
+		let controller_port_id = controller_port_id(&owner).unwrap();
+		let host_port_id: PortId = HOST_PORT_ID_STR.parse().unwrap();
+		let controller_channel_id = ChannelId::new(0);
+		let host_channel_id = ChannelId::new(1);
+		let mut output = HandlerOutputBuilder::new();
+
+		// the controller proposes the default metadata
+		let msg = controller::register_interchain_account(
+			&controller_ctx,
+			owner.clone(),
+			controller_connection_id.clone(),
+			Version::empty(),
+		)
+		.unwrap();
+		assert_eq!(msg.port_id, controller_port_id);
+		let version = msg.channel.version().clone();
+		assert_eq!(
+			Metadata::try_from(&version).unwrap(),
+			Metadata::new(&controller_connection_id, &host_connection_id)
+		);
+		controller::on_chan_open_init(
+			&mut controller_ctx,
+			&mut output,
+			Order::Ordered,
+			&[controller_connection_id.clone()],
+			&controller_port_id,
+			&controller_channel_id,
+			msg.channel.counterparty(),
+			&version,
+		)
+		.unwrap();
+		let controller_ctx = controller_ctx.with_channel(
+			controller_port_id.clone(),
+			controller_channel_id,
+			msg.channel.clone(),
+		);
+
+		// the host only accepts ordered channels and fills in the derived address
+		let counterparty =
+			Counterparty::new(controller_port_id.clone(), Some(controller_channel_id));
+		let open_try = |ctx: &mut MockContext, order| {
+			host::on_chan_open_try(
+				ctx,
+				&mut HandlerOutputBuilder::new(),
+				order,
+				&[host_connection_id.clone()],
+				&host_port_id,
+				&host_channel_id,
+				&counterparty,
+				&version,
+			)
+		};
+		assert!(open_try(&mut host_ctx, Order::Unordered).is_err());
+		let host_version = open_try(&mut host_ctx, Order::Ordered).unwrap();
+		let address =
+			derive_address(|data| host_ctx.hash(data), &host_connection_id, &controller_port_id);
+		let account = host_ctx.account_address(address).unwrap();
+		assert_eq!(Metadata::try_from(&host_version).unwrap().address, account.to_string());
+
+		let mut host_ctx = host_ctx.with_channel(
+			host_port_id.clone(),
+			host_channel_id,
+			ChannelEnd::new(
+				State::TryOpen,
+				Order::Ordered,
+				counterparty,
+				vec![host_connection_id.clone()],
+				host_version.clone(),
+			),
+		);
+		host::on_chan_open_confirm(&mut host_ctx, &mut output, &host_port_id, &host_channel_id)
+			.unwrap();
+
+		let mut controller_ctx = controller_ctx;
+		controller::on_chan_open_ack(
+			&mut controller_ctx,
+			&mut output,
+			&controller_port_id,
+			&controller_channel_id,
+			&host_version,
+		)
+		.unwrap();
+		assert_eq!(
+			Ics27ControllerContext::interchain_account(
+				&controller_ctx,
+				&controller_connection_id,
+				&controller_port_id
+			),
+			Some(account.clone())
+		);
+
+		// the channel is open, the owner can send transactions
+		let mut controller_ctx = controller_ctx
+			.with_channel(
+				controller_port_id.clone(),
+				controller_channel_id,
+				ChannelEnd::new(
+					State::Open,
+					Order::Ordered,
+					Counterparty::new(host_port_id.clone(), Some(host_channel_id)),
+					vec![controller_connection_id.clone()],
+					host_version,
+				),
+			)
+			.with_send_sequence(controller_port_id.clone(), controller_channel_id, 1.into());
+		let send = |ctx: &mut MockContext, type_url: &str| {
+			let message = Any { type_url: type_url.to_string(), value: vec![1, 2, 3] };
+			let mut output = HandlerOutputBuilder::new();
+			controller::send_tx(
+				ctx,
+				&mut output,
+				&owner,
+				&controller_connection_id,
+				InterchainAccountPacketData::execute_tx(vec![message], String::new()),
+				Height::new(0, 100),
+				Timestamp::none(),
+			)
+			.unwrap();
+			output
+				.with_result(())
+				.events
+				.into_iter()
+				.find_map(|event| match event {
+					IbcEvent::SendPacket(send_packet) => Some(send_packet.packet),
+					_ => None,
+				})
+				.unwrap()
+		};
+		let recv = |ctx: &mut MockContext, packet: &Packet| {
+			host::on_recv_packet(ctx, &mut HandlerOutputBuilder::new(), packet, &owner)
+		};
+
+		let packet = send(&mut controller_ctx, MSG_SEND_TYPE_URL);
+		assert_eq!(packet.destination_port, host_port_id);
+		let ack = recv(&mut host_ctx, &packet);
+		assert!(ack.is_successful());
+		let responses = ack.msg_responses().unwrap();
+		assert_eq!(responses[0].type_url, format!("{}Response", MSG_SEND_TYPE_URL));
+		let executed = host_ctx.ibc_store.lock().unwrap().ica_executed_messages.clone();
+		assert_eq!(executed.len(), 1);
+		assert_eq!(executed[0].0, account);
+
+		// messages outside of the allow-list are rejected
+		let packet = send(&mut controller_ctx, "/cosmos.staking.v1beta1.MsgDelegate");
+		let ack = recv(&mut host_ctx, &packet);
+		assert!(matches!(ack, Acknowledgement::Error(_)));
+		assert_eq!(host_ctx.ibc_store.lock().unwrap().ica_executed_messages.len(), 1);
+	}
+}
@@ -0,0 +1,132 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use ibc_proto::{
+	google::protobuf::Any,
+	ibc::applications::interchain_accounts::v1::{
+		CosmosTx, InterchainAccountPacketData as RawPacketData, Type,
+	},
+};
+use prost::Message;
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{applications::interchain_accounts::error::Error, prelude::*};
+
+/// The data of the packets sent by a controller to a host. It's encoded as JSON, with the
+/// transaction in `data` encoded as a protobuf `CosmosTx`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterchainAccountPacketData {
+	pub r#type: Type,
+	pub data: Vec<u8>,
+	pub memo: String,
+}
+
+impl InterchainAccountPacketData {
+	/// Creates the packet data of a transaction executing `messages` on the host.
+	pub fn execute_tx(messages: Vec<Any>, memo: String) -> Self {
+		Self { r#type: Type::ExecuteTx, data: CosmosTx { messages }.encode_to_vec(), memo }
+	}
+
+	/// Decodes the messages of the transaction in the packet.
+	pub fn messages(&self) -> Result<Vec<Any>, Error> {
+		if self.r#type != Type::ExecuteTx {
+			return Err(Error::invalid_packet_type(self.r#type as i32))
+		}
+		let tx = CosmosTx::decode(self.data.as_slice()).map_err(Error::decode_tx)?;
+		if tx.messages.is_empty() {
+			return Err(Error::empty_tx())
+		}
+		Ok(tx.messages)
+	}
+
+	/// Checks that the packet executes a non-empty transaction.
+	pub fn validate_basic(&self) -> Result<(), Error> {
+		if self.r#type != Type::ExecuteTx {
+			return Err(Error::invalid_packet_type(self.r#type as i32))
+		}
+		if self.data.is_empty() {
+			return Err(Error::empty_packet_data())
+		}
+		Ok(())
+	}
+}
+
+impl TryFrom<RawPacketData> for InterchainAccountPacketData {
+	type Error = Error;
+
+	fn try_from(raw: RawPacketData) -> Result<Self, Self::Error> {
+		let r#type = Type::from_i32(raw.r#type)
+			.filter(|ty| *ty != Type::Unspecified)
+			.ok_or_else(|| Error::invalid_packet_type(raw.r#type))?;
+		Ok(Self { r#type, data: raw.data, memo: raw.memo })
+	}
+}
+
+impl From<InterchainAccountPacketData> for RawPacketData {
+	fn from(data: InterchainAccountPacketData) -> Self {
+		Self { r#type: data.r#type as i32, data: data.data, memo: data.memo }
+	}
+}
+
+/// The JSON representation used by ibc-go, where the type is named and the data is base64
+/// encoded.
+#[derive(Serialize, Deserialize)]
+struct JsonPacketData {
+	r#type: String,
+	#[serde(with = "ibc_proto::base64")]
+	data: Vec<u8>,
+	#[serde(default)]
+	memo: String,
+}
+
+impl Serialize for InterchainAccountPacketData {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		JsonPacketData {
+			r#type: self.r#type.as_str_name().to_string(),
+			data: self.data.clone(),
+			memo: self.memo.clone(),
+		}
+		.serialize(serializer)
+	}
+}
+
+impl<'de> Deserialize<'de> for InterchainAccountPacketData {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let json = JsonPacketData::deserialize(deserializer)?;
+		let r#type = Type::from_str_name(&json.r#type)
+			.filter(|ty| *ty != Type::Unspecified)
+			.ok_or_else(|| D::Error::custom(format!("unsupported packet type {}", json.r#type)))?;
+		Ok(Self { r#type, data: json.data, memo: json.memo })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn packet_data_json_roundtrip() {
+		let message = Any { type_url: "/cosmos.bank.v1beta1.MsgSend".to_string(), value: vec![1] };
+		let data = InterchainAccountPacketData::execute_tx(vec![message.clone()], "memo".into());
+
+		let json = serde_json::to_string(&data).unwrap();
+		assert!(json.starts_with(r#"{"type":"TYPE_EXECUTE_TX","data":""#));
+		let decoded = serde_json::from_str::<InterchainAccountPacketData>(&json).unwrap();
+		assert_eq!(decoded, data);
+		assert_eq!(decoded.messages().unwrap(), vec![message]);
+
+		let unspecified = r#"{"type":"TYPE_UNSPECIFIED","data":"","memo":""}"#;
+		assert!(serde_json::from_str::<InterchainAccountPacketData>(unspecified).is_err());
+	}
+}
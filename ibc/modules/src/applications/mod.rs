@@ -14,4 +14,7 @@
 
 //! Various packet encoding semantics which underpin the various types of transactions.
 
+pub mod fee;
+pub mod interchain_accounts;
+pub mod nft_transfer;
 pub mod transfer;
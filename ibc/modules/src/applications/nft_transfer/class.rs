@@ -0,0 +1,252 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use core::{fmt, str::FromStr};
+
+use derive_more::Display;
+use ibc_proto::ibc::applications::nft_transfer::v1::ClassTrace as RawClassTrace;
+use serde::{Deserialize, Serialize};
+
+use super::error::Error;
+use crate::{
+	applications::transfer::{TracePath, TracePrefix},
+	core::ics24_host::identifier::{ChannelId, PortId},
+	prelude::*,
+};
+
+/// Identifier of a class of non-fungible tokens on the chain the class originates from.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize, Display)]
+#[serde(transparent)]
+pub struct ClassId(String);
+
+impl ClassId {
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+impl FromStr for ClassId {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.trim().is_empty() {
+			Err(Error::empty_class_id())
+		} else {
+			Ok(ClassId(s.to_owned()))
+		}
+	}
+}
+
+/// Identifier of a non-fungible token within its class.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize, Display)]
+#[serde(transparent)]
+pub struct TokenId(String);
+
+impl TokenId {
+	pub fn as_str(&self) -> &str {
+		&self.0
+	}
+}
+
+impl FromStr for TokenId {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		if s.trim().is_empty() {
+			Err(Error::empty_token_id())
+		} else {
+			Ok(TokenId(s.to_owned()))
+		}
+	}
+}
+
+/// A type that contains the base class id for ICS721 and the source tracing information path.
+#[derive(Clone, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(into = "String")]
+#[serde(try_from = "String")]
+pub struct PrefixedClassId {
+	/// A series of `{port-id}/{channel-id}`s for tracing the source of the class.
+	pub trace_path: TracePath,
+	/// Base class id of the relayed non-fungible tokens.
+	pub base_class_id: ClassId,
+}
+
+impl PrefixedClassId {
+	/// Removes the specified prefix from the trace path if there is a match, otherwise does
+	/// nothing.
+	pub fn remove_trace_prefix(&mut self, prefix: &TracePrefix) {
+		self.trace_path.remove_prefix(prefix)
+	}
+
+	/// Adds the specified prefix to the trace path.
+	pub fn add_trace_prefix(&mut self, prefix: TracePrefix) {
+		self.trace_path.add_prefix(prefix)
+	}
+
+	pub fn trace_path(&self) -> &TracePath {
+		&self.trace_path
+	}
+
+	pub fn base_class_id(&self) -> &ClassId {
+		&self.base_class_id
+	}
+}
+
+/// Returns true if the class originally came from the sender chain and false otherwise.
+///
+/// As for ICS20 denominations, the "source" chain is the one escrowing the tokens of the class,
+/// while the other chain mints and burns vouchers of them. See
+/// [`crate::applications::transfer::is_sender_chain_source`].
+pub fn is_sender_chain_source(
+	source_port: PortId,
+	source_channel: ChannelId,
+	class_id: &PrefixedClassId,
+) -> bool {
+	!is_receiver_chain_source(source_port, source_channel, class_id)
+}
+
+/// Returns true if the class originally came from the receiving chain and false otherwise.
+pub fn is_receiver_chain_source(
+	source_port: PortId,
+	source_channel: ChannelId,
+	class_id: &PrefixedClassId,
+) -> bool {
+	let prefix = TracePrefix::new(source_port, source_channel);
+	class_id.trace_path.starts_with(&prefix)
+}
+
+impl FromStr for PrefixedClassId {
+	type Err = Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		// unlike denominations, class ids may contain '/', so the trace only consists of the
+		// leading `{port-id}/{channel-id}` pairs
+		let parts: Vec<&str> = s.split('/').collect();
+		let mut trace_len = 0;
+		while trace_len + 2 < parts.len() &&
+			PortId::from_str(parts[trace_len]).is_ok() &&
+			ChannelId::from_str(parts[trace_len + 1]).is_ok()
+		{
+			trace_len += 2;
+		}
+
+		let base_class_id = ClassId::from_str(&parts[trace_len..].join("/"))?;
+		let trace_path =
+			TracePath::try_from(parts[..trace_len].to_vec()).map_err(Error::invalid_trace)?;
+		Ok(Self { trace_path, base_class_id })
+	}
+}
+
+impl TryFrom<String> for PrefixedClassId {
+	type Error = Error;
+
+	fn try_from(value: String) -> Result<Self, Self::Error> {
+		Self::from_str(&value)
+	}
+}
+
+impl TryFrom<RawClassTrace> for PrefixedClassId {
+	type Error = Error;
+
+	fn try_from(value: RawClassTrace) -> Result<Self, Self::Error> {
+		let base_class_id = ClassId::from_str(&value.base_class_id)?;
+		let trace_path = TracePath::from_str(&value.path).map_err(Error::invalid_trace)?;
+		Ok(Self { trace_path, base_class_id })
+	}
+}
+
+impl From<PrefixedClassId> for RawClassTrace {
+	fn from(value: PrefixedClassId) -> Self {
+		Self { path: value.trace_path.to_string(), base_class_id: value.base_class_id.to_string() }
+	}
+}
+
+impl From<PrefixedClassId> for String {
+	fn from(class_id: PrefixedClassId) -> Self {
+		class_id.to_string()
+	}
+}
+
+impl fmt::Display for PrefixedClassId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.trace_path.is_empty() {
+			write!(f, "{}", self.base_class_id)
+		} else {
+			write!(f, "{}/{}", self.trace_path, self.base_class_id)
+		}
+	}
+}
+
+/// A class of non-fungible tokens along with its metadata.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Class {
+	pub id: PrefixedClassId,
+	pub uri: String,
+	pub data: String,
+}
+
+/// A non-fungible token along with its metadata.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Nft {
+	pub id: TokenId,
+	pub uri: String,
+	pub data: String,
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_class_id_trace() -> Result<(), Error> {
+		assert!(PrefixedClassId::from_str("").is_err(), "empty class id");
+		assert!(PrefixedClassId::from_str("nft-transfer/channel-0/").is_err(), "empty base");
+
+		let class_id = PrefixedClassId::from_str("nft-transfer/channel-0/kitties")?;
+		assert_eq!(class_id.trace_path, "nft-transfer/channel-0".parse().unwrap());
+		assert_eq!(class_id.base_class_id.as_str(), "kitties");
+
+		// the base class id keeps the segments which aren't a port and channel pair
+		let class_id =
+			PrefixedClassId::from_str("nft-transfer/channel-0/nft-transfer/channel-1/a/b")?;
+		assert_eq!(
+			class_id.trace_path,
+			"nft-transfer/channel-0/nft-transfer/channel-1".parse().unwrap()
+		);
+		assert_eq!(class_id.base_class_id.as_str(), "a/b");
+		assert_eq!(class_id.to_string(), "nft-transfer/channel-0/nft-transfer/channel-1/a/b");
+
+		let class_id = PrefixedClassId::from_str("nft-transfer/channel-0")?;
+		assert!(class_id.trace_path.is_empty());
+		assert_eq!(class_id.base_class_id.as_str(), "nft-transfer/channel-0");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_class_id_source() -> Result<(), Error> {
+		let port_id = PortId::from_str("nft-transfer").unwrap();
+		let mut class_id = PrefixedClassId::from_str("kitties")?;
+		assert!(is_sender_chain_source(port_id.clone(), ChannelId::new(0), &class_id));
+
+		class_id.add_trace_prefix(TracePrefix::new(port_id.clone(), ChannelId::new(0)));
+		assert!(is_receiver_chain_source(port_id.clone(), ChannelId::new(0), &class_id));
+		assert!(is_sender_chain_source(port_id.clone(), ChannelId::new(1), &class_id));
+
+		class_id.remove_trace_prefix(&TracePrefix::new(port_id, ChannelId::new(0)));
+		assert_eq!(class_id, PrefixedClassId::from_str("kitties")?);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,161 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::nft_transfer::{
+		acknowledgement::Acknowledgement, PrefixedClassId, TokenId, MODULE_ID_STR,
+	},
+	events::{IbcEvent, ModuleEvent},
+	prelude::*,
+	signer::Signer,
+};
+
+const EVENT_TYPE_PACKET: &str = "non_fungible_token_packet";
+const EVENT_TYPE_TIMEOUT: &str = "timeout";
+const EVENT_TYPE_CLASS_TRACE: &str = "class_trace";
+const EVENT_TYPE_TRANSFER: &str = "ibc_nft_transfer";
+
+pub enum Event {
+	Recv(RecvEvent),
+	Ack(AckEvent),
+	AckStatus(AckStatusEvent),
+	Timeout(TimeoutEvent),
+	ClassTrace(ClassTraceEvent),
+	Transfer(TransferEvent),
+}
+
+fn token_ids_attribute(token_ids: &[TokenId]) -> String {
+	token_ids.iter().map(TokenId::as_str).collect::<Vec<_>>().join(",")
+}
+
+pub struct RecvEvent {
+	pub receiver: Signer,
+	pub class_id: PrefixedClassId,
+	pub token_ids: Vec<TokenId>,
+	pub success: bool,
+}
+
+impl From<RecvEvent> for IbcEvent {
+	fn from(ev: RecvEvent) -> Self {
+		let RecvEvent { receiver, class_id, token_ids, success } = ev;
+		IbcEvent::AppModule(ModuleEvent {
+			kind: EVENT_TYPE_PACKET.to_string(),
+			module_name: MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![
+				("receiver", receiver).into(),
+				("class_id", class_id).into(),
+				("token_ids", token_ids_attribute(&token_ids)).into(),
+				("success", success).into(),
+			],
+		})
+	}
+}
+
+pub struct AckEvent {
+	pub receiver: Signer,
+	pub class_id: PrefixedClassId,
+	pub token_ids: Vec<TokenId>,
+	pub acknowledgement: Acknowledgement,
+}
+
+impl From<AckEvent> for IbcEvent {
+	fn from(ev: AckEvent) -> Self {
+		let AckEvent { receiver, class_id, token_ids, acknowledgement } = ev;
+		IbcEvent::AppModule(ModuleEvent {
+			kind: EVENT_TYPE_PACKET.to_string(),
+			module_name: MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![
+				("receiver", receiver).into(),
+				("class_id", class_id).into(),
+				("token_ids", token_ids_attribute(&token_ids)).into(),
+				("acknowledgement", acknowledgement).into(),
+			],
+		})
+	}
+}
+
+pub struct AckStatusEvent {
+	pub acknowledgement: Acknowledgement,
+}
+
+impl From<AckStatusEvent> for IbcEvent {
+	fn from(ev: AckStatusEvent) -> Self {
+		let AckStatusEvent { acknowledgement } = ev;
+		let mut event = ModuleEvent {
+			kind: EVENT_TYPE_PACKET.to_string(),
+			module_name: MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![],
+		};
+		let attr_label = if acknowledgement.is_successful() { "success" } else { "error" };
+		event.attributes.push((attr_label, acknowledgement.to_string()).into());
+		IbcEvent::AppModule(event)
+	}
+}
+
+pub struct TimeoutEvent {
+	pub refund_receiver: Signer,
+	pub refund_class_id: PrefixedClassId,
+	pub refund_token_ids: Vec<TokenId>,
+}
+
+impl From<TimeoutEvent> for IbcEvent {
+	fn from(ev: TimeoutEvent) -> Self {
+		let TimeoutEvent { refund_receiver, refund_class_id, refund_token_ids } = ev;
+		IbcEvent::AppModule(ModuleEvent {
+			kind: EVENT_TYPE_TIMEOUT.to_string(),
+			module_name: MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![
+				("refund_receiver", refund_receiver).into(),
+				("refund_class_id", refund_class_id).into(),
+				("refund_token_ids", token_ids_attribute(&refund_token_ids)).into(),
+			],
+		})
+	}
+}
+
+pub struct ClassTraceEvent {
+	pub trace_hash: Option<String>,
+	pub class_id: PrefixedClassId,
+}
+
+impl From<ClassTraceEvent> for IbcEvent {
+	fn from(ev: ClassTraceEvent) -> Self {
+		let ClassTraceEvent { trace_hash, class_id } = ev;
+		let mut ev = ModuleEvent {
+			kind: EVENT_TYPE_CLASS_TRACE.to_string(),
+			module_name: MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![("class_id", class_id).into()],
+		};
+		if let Some(hash) = trace_hash {
+			ev.attributes.push(("trace_hash", hash).into());
+		}
+		IbcEvent::AppModule(ev)
+	}
+}
+
+pub struct TransferEvent {
+	pub sender: Signer,
+	pub receiver: Signer,
+}
+
+impl From<TransferEvent> for IbcEvent {
+	fn from(ev: TransferEvent) -> Self {
+		let TransferEvent { sender, receiver } = ev;
+		IbcEvent::AppModule(ModuleEvent {
+			kind: EVENT_TYPE_TRANSFER.to_string(),
+			module_name: MODULE_ID_STR.parse().expect("invalid ModuleId"),
+			attributes: vec![("sender", sender).into(), ("receiver", receiver).into()],
+		})
+	}
+}
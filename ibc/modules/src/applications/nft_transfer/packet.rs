@@ -0,0 +1,128 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use alloc::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use super::{error::Error, Class, Nft, PrefixedClassId, TokenId};
+use crate::{prelude::*, signer::Signer};
+
+/// The data of an ICS721 packet, encoded as JSON.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketData {
+	pub class_id: PrefixedClassId,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub class_uri: String,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub class_data: String,
+	pub token_ids: Vec<TokenId>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub token_uris: Vec<String>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub token_data: Vec<String>,
+	pub sender: Signer,
+	pub receiver: Signer,
+	#[serde(default, skip_serializing_if = "String::is_empty")]
+	pub memo: String,
+}
+
+impl PacketData {
+	/// Decodes and validates the data of a packet.
+	pub fn decode(data: &[u8]) -> Result<Self, Error> {
+		let data = serde_json::from_slice::<Self>(data)
+			.map_err(|_| Error::packet_data_deserialization())?;
+		data.validate_basic()?;
+		Ok(data)
+	}
+
+	pub fn encode(&self) -> Vec<u8> {
+		serde_json::to_vec(self).expect("PacketData serialization is infallible; qed")
+	}
+
+	/// Checks that at least one token is transferred, that no token is transferred twice and that
+	/// the uris and data, when given, are given for every token.
+	pub fn validate_basic(&self) -> Result<(), Error> {
+		if self.token_ids.is_empty() {
+			return Err(Error::empty_token_ids())
+		}
+
+		let mut token_ids = BTreeSet::new();
+		for token_id in &self.token_ids {
+			if !token_ids.insert(token_id) {
+				return Err(Error::duplicate_token_id(token_id.to_string()))
+			}
+		}
+
+		for metadata in [&self.token_uris, &self.token_data] {
+			if !metadata.is_empty() && metadata.len() != self.token_ids.len() {
+				return Err(Error::token_metadata_length(self.token_ids.len(), metadata.len()))
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Returns the class of the transferred tokens.
+	pub fn class(&self) -> Class {
+		Class {
+			id: self.class_id.clone(),
+			uri: self.class_uri.clone(),
+			data: self.class_data.clone(),
+		}
+	}
+
+	/// Returns the transferred tokens along with their metadata.
+	pub fn tokens(&self) -> Vec<Nft> {
+		self.token_ids
+			.iter()
+			.enumerate()
+			.map(|(i, id)| Nft {
+				id: id.clone(),
+				uri: self.token_uris.get(i).cloned().unwrap_or_default(),
+				data: self.token_data.get(i).cloned().unwrap_or_default(),
+			})
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn packet_data_encoding() {
+		let json = r#"{"classId":"nft-transfer/channel-0/kitties","classUri":"https://kitties","tokenIds":["1","2"],"tokenUris":["https://kitties/1","https://kitties/2"],"sender":"alice","receiver":"bob"}"#;
+		let data = PacketData::decode(json.as_bytes()).unwrap();
+		assert_eq!(data.class_id.to_string(), "nft-transfer/channel-0/kitties");
+		assert_eq!(data.tokens()[1].uri, "https://kitties/2");
+		assert_eq!(data.tokens()[1].data, "");
+		assert_eq!(data.encode(), json.as_bytes());
+
+		let mut invalid = data.clone();
+		invalid.token_ids.push("1".parse().unwrap());
+		invalid.token_uris.push("https://kitties/1".to_string());
+		assert!(PacketData::decode(&invalid.encode()).is_err(), "duplicate token");
+
+		let mut invalid = data.clone();
+		invalid.token_data = vec!["data".to_string()];
+		assert!(PacketData::decode(&invalid.encode()).is_err(), "missing token data");
+
+		let mut invalid = data;
+		invalid.token_ids.clear();
+		invalid.token_uris.clear();
+		assert!(PacketData::decode(&invalid.encode()).is_err(), "no tokens");
+	}
+}
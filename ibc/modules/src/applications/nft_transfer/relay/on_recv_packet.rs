@@ -0,0 +1,75 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::{
+		nft_transfer::{
+			context::Ics721Context, error::Error as Ics721Error, events::ClassTraceEvent,
+			is_receiver_chain_source, packet::PacketData,
+		},
+		transfer::TracePrefix,
+	},
+	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
+};
+
+pub fn process_recv_packet<Ctx: 'static + Ics721Context>(
+	ctx: &mut Ctx,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	data: &PacketData,
+) -> Result<(), Ics721Error> {
+	if !ctx.is_receive_enabled() {
+		return Err(Ics721Error::receive_disabled())
+	}
+
+	let receiver_account = data
+		.receiver
+		.clone()
+		.try_into()
+		.map_err(|_| Ics721Error::parse_account_failure())?;
+
+	// either all tokens are received or the whole packet fails
+	if is_receiver_chain_source(packet.source_port.clone(), packet.source_channel, &data.class_id) {
+		// sender chain is not the source, unescrow tokens
+		let prefix = TracePrefix::new(packet.source_port.clone(), packet.source_channel);
+		let mut class_id = data.class_id.clone();
+		class_id.remove_trace_prefix(&prefix);
+
+		let escrow_address =
+			ctx.get_channel_escrow_address(&packet.destination_port, packet.destination_channel)?;
+		for token_id in &data.token_ids {
+			ctx.transfer_nft(&class_id, token_id, &escrow_address, &receiver_account)?;
+		}
+	} else {
+		// sender chain is the source, mint vouchers of the prefixed class
+		let prefix = TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
+		let mut class = data.class();
+		class.id.add_trace_prefix(prefix);
+
+		let class_trace_event = ClassTraceEvent {
+			trace_hash: ctx.class_hash_string(&class.id),
+			class_id: class.id.clone(),
+		};
+		output.emit(class_trace_event.into());
+
+		if ctx.get_class(&class.id).is_none() {
+			ctx.create_class(&class)?;
+		}
+		for token in data.tokens() {
+			ctx.mint_nft(&class.id, &token, &receiver_account)?;
+		}
+	}
+
+	Ok(())
+}
@@ -0,0 +1,121 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	applications::nft_transfer::{
+		context::Ics721Context, error::Error, events::TransferEvent, is_sender_chain_source,
+		msgs::transfer::MsgTransfer, packet::PacketData,
+	},
+	core::ics04_channel::{handler::send_packet::send_packet, packet::Packet},
+	handler::{HandlerOutput, HandlerOutputBuilder},
+	prelude::*,
+};
+
+/// Sends the tokens of a class, escrowing them if this chain is the source of the class and
+/// burning their vouchers otherwise.
+pub fn send_transfer<Ctx>(
+	ctx: &mut Ctx,
+	output: &mut HandlerOutputBuilder<()>,
+	msg: MsgTransfer,
+) -> Result<(), Error>
+where
+	Ctx: Ics721Context,
+{
+	if !ctx.is_send_enabled() {
+		return Err(Error::send_disabled())
+	}
+
+	let source_channel_end = ctx
+		.channel_end(&(msg.source_port.clone(), msg.source_channel))
+		.map_err(Error::ics04_channel)?;
+
+	let destination_port = source_channel_end.counterparty().port_id().clone();
+	let destination_channel = *source_channel_end.counterparty().channel_id().ok_or_else(|| {
+		Error::destination_channel_not_found(msg.source_port.clone(), msg.source_channel)
+	})?;
+
+	// get the next sequence
+	let sequence = ctx
+		.get_next_sequence_send(&(msg.source_port.clone(), msg.source_channel))
+		.map_err(Error::ics04_channel)?;
+
+	let class = ctx
+		.get_class(&msg.class_id)
+		.ok_or_else(|| Error::class_not_found(msg.class_id.to_string()))?;
+	let tokens = msg
+		.token_ids
+		.iter()
+		.map(|token_id| {
+			ctx.get_nft(&msg.class_id, token_id).ok_or_else(|| {
+				Error::token_not_found(msg.class_id.to_string(), token_id.to_string())
+			})
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let data = PacketData {
+		class_id: msg.class_id.clone(),
+		class_uri: class.uri,
+		class_data: class.data,
+		token_ids: msg.token_ids.clone(),
+		token_uris: tokens.iter().map(|token| token.uri.clone()).collect(),
+		token_data: tokens.iter().map(|token| token.data.clone()).collect(),
+		sender: msg.sender.clone(),
+		receiver: msg.receiver.clone(),
+		memo: msg.memo,
+	};
+	data.validate_basic()?;
+
+	let sender = msg.sender.clone().try_into().map_err(|_| Error::parse_account_failure())?;
+
+	if is_sender_chain_source(msg.source_port.clone(), msg.source_channel, &msg.class_id) {
+		let escrow_address =
+			ctx.get_channel_escrow_address(&msg.source_port, msg.source_channel)?;
+		for token_id in &msg.token_ids {
+			ctx.transfer_nft(&msg.class_id, token_id, &sender, &escrow_address)?;
+		}
+	} else {
+		for token_id in &msg.token_ids {
+			ctx.burn_nft(&msg.class_id, token_id, &sender)?;
+		}
+	}
+
+	let packet = Packet {
+		sequence,
+		source_port: msg.source_port,
+		source_channel: msg.source_channel,
+		destination_port,
+		destination_channel,
+		data: data.encode(),
+		timeout_height: msg.timeout_height,
+		timeout_timestamp: msg.timeout_timestamp,
+	};
+
+	let HandlerOutput { result, log, events } =
+		send_packet(ctx, packet).map_err(Error::ics04_channel)?;
+
+	ctx.store_packet_result(result).map_err(Error::ics04_channel)?;
+
+	output.merge_output(HandlerOutput::builder().with_log(log).with_events(events).with_result(()));
+
+	let token_ids = msg.token_ids.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+	output.log(format!(
+		"IBC non-fungible token transfer: {} --({}: {})--> {}",
+		msg.sender, msg.class_id, token_ids, msg.receiver
+	));
+
+	let transfer_event = TransferEvent { sender: msg.sender, receiver: msg.receiver };
+	output.emit(transfer_event.into());
+
+	Ok(())
+}
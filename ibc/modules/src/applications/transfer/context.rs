@@ -19,10 +19,9 @@ use crate::{
 	applications::transfer::{
 		acknowledgement::Acknowledgement,
 		events::{AckEvent, AckStatusEvent, RecvEvent, TimeoutEvent},
-		packet::PacketData,
 		relay::{
-			on_ack_packet::process_ack_packet, on_recv_packet::process_recv_packet,
-			on_timeout_packet::process_timeout_packet,
+			decode_packet_data, on_ack_packet::process_ack_packet,
+			on_recv_packet::process_recv_packet, on_timeout_packet::process_timeout_packet,
 		},
 		PrefixedCoin, PrefixedDenom, VERSION,
 	},
@@ -31,7 +30,7 @@ use crate::{
 			channel::{Counterparty, Order},
 			context::{ChannelKeeper, ChannelReader},
 			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
-			packet::Packet,
+			packet::{Packet, Sequence},
 			Version,
 		},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
@@ -45,6 +44,28 @@ pub trait Ics20Keeper:
 	ChannelKeeper + BankKeeper<AccountId = <Self as Ics20Keeper>::AccountId>
 {
 	type AccountId;
+
+	/// Stores the packet received on this chain whose tokens are forwarded by the packet sent
+	/// with the given sequence. Implement only if the host chain supports forwarding.
+	fn store_forwarded_packet(
+		&mut self,
+		_port_id: PortId,
+		_channel_id: ChannelId,
+		_sequence: Sequence,
+		_packet: Packet,
+	) -> Result<(), Ics20Error> {
+		Err(Ics20Error::forwarding_not_supported())
+	}
+
+	/// Removes the packet stored for the forwarding packet sent with the given sequence.
+	fn delete_forwarded_packet(
+		&mut self,
+		_port_id: &PortId,
+		_channel_id: ChannelId,
+		_sequence: Sequence,
+	) -> Result<(), Ics20Error> {
+		Ok(())
+	}
 }
 
 pub trait Ics20Reader: ChannelReader
@@ -83,6 +104,27 @@ where
 	fn denom_hash_string(&self, _denom: &PrefixedDenom) -> Option<String> {
 		None
 	}
+
+	/// Returns the address holding the tokens received on a channel while they are forwarded
+	/// to their next hop. Implement only if the host chain supports forwarding.
+	fn get_forwarding_address(
+		&self,
+		_port_id: &PortId,
+		_channel_id: ChannelId,
+	) -> Result<Signer, Ics20Error> {
+		Err(Ics20Error::forwarding_not_supported())
+	}
+
+	/// Returns the packet received on this chain whose tokens are forwarded by the packet sent
+	/// with the given sequence.
+	fn get_forwarded_packet(
+		&self,
+		_port_id: &PortId,
+		_channel_id: ChannelId,
+		_sequence: Sequence,
+	) -> Option<Packet> {
+		None
+	}
 }
 
 // https://github.com/cosmos/cosmos-sdk/blob/master/docs/architecture/adr-028-public-key-addresses.md
@@ -157,15 +199,19 @@ fn validate_transfer_channel_params(
 		return Err(Ics20Error::invalid_port(port_id.clone(), bound_port))
 	}
 
-	if version != &Version::ics20() {
+	if !is_supported_version(version) {
 		return Err(Ics20Error::invalid_version(version.clone()))
 	}
 
 	Ok(())
 }
 
+fn is_supported_version(version: &Version) -> bool {
+	version == &Version::ics20() || version == &Version::ics20_v2()
+}
+
 fn validate_counterparty_version(counterparty_version: &Version) -> Result<(), Ics20Error> {
-	if counterparty_version == &Version::ics20() {
+	if is_supported_version(counterparty_version) {
 		Ok(())
 	} else {
 		Err(Ics20Error::invalid_counterparty_version(counterparty_version.clone()))
@@ -200,7 +246,8 @@ pub fn on_chan_open_try(
 ) -> Result<Version, Ics20Error> {
 	validate_transfer_channel_params(ctx, order, port_id, channel_id, version)?;
 	validate_counterparty_version(counterparty_version)?;
-	Ok(Version::ics20())
+	// both versions are supported, so the one proposed by the counterparty is accepted
+	Ok(counterparty_version.clone())
 }
 
 pub fn on_chan_open_ack(
@@ -223,6 +270,44 @@ pub fn on_chan_open_confirm(
 	Ok(())
 }
 
+/// Accepts an upgrade of a transfer channel as long as it stays unordered and the proposed
+/// version is a supported transfer version.
+pub fn on_chan_upgrade_init(
+	ctx: &mut impl Ics20Context,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	order: Order,
+	_connection_hops: &[ConnectionId],
+	version: &Version,
+) -> Result<Version, Ics20Error> {
+	validate_transfer_channel_params(ctx, order, port_id, channel_id, version)?;
+	Ok(version.clone())
+}
+
+pub fn on_chan_upgrade_try(
+	ctx: &mut impl Ics20Context,
+	_output: &mut ModuleOutputBuilder,
+	port_id: &PortId,
+	channel_id: &ChannelId,
+	order: Order,
+	_connection_hops: &[ConnectionId],
+	counterparty_version: &Version,
+) -> Result<Version, Ics20Error> {
+	validate_transfer_channel_params(ctx, order, port_id, channel_id, counterparty_version)?;
+	Ok(counterparty_version.clone())
+}
+
+pub fn on_chan_upgrade_ack(
+	_ctx: &mut impl Ics20Context,
+	_output: &mut ModuleOutputBuilder,
+	_port_id: &PortId,
+	_channel_id: &ChannelId,
+	counterparty_version: &Version,
+) -> Result<(), Ics20Error> {
+	validate_counterparty_version(counterparty_version)
+}
+
 pub fn on_chan_close_init(
 	_ctx: &mut impl Ics20Context,
 	_output: &mut ModuleOutputBuilder,
@@ -247,9 +332,14 @@ pub fn on_recv_packet<Ctx: 'static + Ics20Context>(
 	packet: &Packet,
 	_relayer: &Signer,
 ) -> Acknowledgement {
-	let data = match serde_json::from_slice::<PacketData>(&packet.data) {
+	let data = match decode_packet_data(
+		ctx,
+		&packet.destination_port,
+		packet.destination_channel,
+		&packet.data,
+	) {
 		Ok(data) => data,
-		Err(_) => return Acknowledgement::from_error(Ics20Error::packet_data_deserialization()),
+		Err(e) => return Acknowledgement::from_error(e),
 	};
 
 	log::debug!(target: "ics::transfer", "dispatch {:?}",&data);
@@ -258,13 +348,15 @@ pub fn on_recv_packet<Ctx: 'static + Ics20Context>(
 		Err(e) => Acknowledgement::from_error(e),
 	};
 
-	let recv_event = RecvEvent {
-		receiver: data.receiver,
-		denom: data.token.denom,
-		amount: data.token.amount,
-		success: ack.is_successful(),
-	};
-	output.emit(recv_event.into());
+	for token in data.tokens {
+		let recv_event = RecvEvent {
+			receiver: data.receiver.clone(),
+			denom: token.denom,
+			amount: token.amount,
+			success: ack.is_successful(),
+		};
+		output.emit(recv_event.into());
+	}
 
 	ack
 }
@@ -276,21 +368,22 @@ pub fn on_acknowledgement_packet(
 	acknowledgement: &GenericAcknowledgement,
 	_relayer: &Signer,
 ) -> Result<(), Ics20Error> {
-	let data = serde_json::from_slice::<PacketData>(&packet.data)
-		.map_err(|_| Ics20Error::packet_data_deserialization())?;
+	let data = decode_packet_data(ctx, &packet.source_port, packet.source_channel, &packet.data)?;
 
 	let acknowledgement = serde_json::from_slice::<Acknowledgement>(acknowledgement.as_ref())
 		.map_err(|_| Ics20Error::ack_deserialization())?;
 
-	process_ack_packet(ctx, packet, &data, &acknowledgement)?;
+	process_ack_packet(ctx, output, packet, &data, &acknowledgement)?;
 
-	let ack_event = AckEvent {
-		receiver: data.receiver,
-		denom: data.token.denom,
-		amount: data.token.amount,
-		acknowledgement: acknowledgement.clone(),
-	};
-	output.emit(ack_event.into());
+	for token in data.tokens {
+		let ack_event = AckEvent {
+			receiver: data.receiver.clone(),
+			denom: token.denom,
+			amount: token.amount,
+			acknowledgement: acknowledgement.clone(),
+		};
+		output.emit(ack_event.into());
+	}
 	output.emit(AckStatusEvent { acknowledgement }.into());
 
 	Ok(())
@@ -302,17 +395,18 @@ pub fn on_timeout_packet(
 	packet: &Packet,
 	_relayer: &Signer,
 ) -> Result<(), Ics20Error> {
-	let data = serde_json::from_slice::<PacketData>(&packet.data)
-		.map_err(|_| Ics20Error::packet_data_deserialization())?;
+	let data = decode_packet_data(ctx, &packet.source_port, packet.source_channel, &packet.data)?;
 
-	process_timeout_packet(ctx, packet, &data)?;
+	process_timeout_packet(ctx, output, packet, &data)?;
 
-	let timeout_event = TimeoutEvent {
-		refund_receiver: data.sender,
-		refund_denom: data.token.denom,
-		refund_amount: data.token.amount,
-	};
-	output.emit(timeout_event.into());
+	for token in data.tokens {
+		let timeout_event = TimeoutEvent {
+			refund_receiver: data.sender.clone(),
+			refund_denom: token.denom,
+			refund_amount: token.amount,
+		};
+		output.emit(timeout_event.into());
+	}
 
 	Ok(())
 }
@@ -326,14 +420,40 @@ pub(crate) mod test {
 
 	use crate::{
 		applications::transfer::{
-			context::cosmos_adr028_escrow_address, error::Error as Ics20Error,
-			msgs::transfer::MsgTransfer, relay::send_transfer::send_transfer, PrefixedCoin,
+			acknowledgement::Acknowledgement,
+			context::{
+				cosmos_adr028_escrow_address, on_acknowledgement_packet, on_chan_open_try,
+				on_recv_packet, Ics20Reader,
+			},
+			error::Error as Ics20Error,
+			msgs::transfer::MsgTransfer,
+			packet::{Forwarding, PacketDataV2},
+			relay::send_transfer::send_transfer,
+			PrefixedCoin, TracePrefix,
 		},
-		core::ics04_channel::error::Error,
+		core::{
+			ics02_client::height::Height,
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, Order, State},
+				error::Error,
+				msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
+				packet::Packet,
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		},
+		events::IbcEvent,
 		handler::HandlerOutputBuilder,
-		mock::context::{HostBlockType, MockClientTypes, MockIbcStore},
+		mock::context::{HostBlockType, MockClientTypes, MockContext, MockIbcStore},
 		prelude::*,
-		test_utils::DummyTransferModule,
+		test_utils::{get_dummy_account_id, DummyTransferModule},
+		timestamp::{Timestamp, ZERO_DURATION},
 	};
 
 	pub(crate) fn deliver<C: HostBlockType>(
@@ -375,4 +495,143 @@ pub(crate) mod test {
 			"cosmos177x69sver58mcfs74x6dg0tv6ls4s3xmmcaw53",
 		);
 	}
+
+	#[test]
+	fn test_version_negotiation() {
+		let ibc_store = MockIbcStore::<MockClientTypes>::default();
+		let mut ctx = DummyTransferModule::new(Arc::new(Mutex::new(ibc_store)));
+		let counterparty = Counterparty::new(PortId::transfer(), Some(ChannelId::new(1)));
+		let mut try_open = |version: Version, counterparty_version: Version| {
+			on_chan_open_try(
+				&mut ctx,
+				&mut HandlerOutputBuilder::new(),
+				Order::Unordered,
+				&[ConnectionId::default()],
+				&PortId::transfer(),
+				&ChannelId::new(0),
+				&counterparty,
+				&version,
+				&counterparty_version,
+			)
+		};
+
+		assert_eq!(try_open(Version::ics20(), Version::ics20()).unwrap(), Version::ics20());
+		assert_eq!(
+			try_open(Version::ics20_v2(), Version::ics20_v2()).unwrap(),
+			Version::ics20_v2()
+		);
+		assert!(try_open(Version::ics20_v2(), Version::new("ics20-3".to_string())).is_err());
+	}
+
+	#[test]
+	fn test_forwarded_tokens_are_returned_on_failure() {
+		let port_id = PortId::transfer();
+		let (inbound, outbound) = (ChannelId::new(0), ChannelId::new(1));
+		let channel_end = |counterparty_channel| {
+			ChannelEnd::new(
+				State::Open,
+				Order::Unordered,
+				Counterparty::new(PortId::transfer(), Some(ChannelId::new(counterparty_channel))),
+				vec![ConnectionId::default()],
+				Version::ics20_v2(),
+			)
+		};
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			ClientId::default(),
+			ConnectionCounterparty::new(
+				ClientId::default(),
+				Some(ConnectionId::default()),
+				Default::default(),
+			),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let ctx = MockContext::<MockClientTypes>::default()
+			.with_client(&ClientId::default(), Height::new(0, 5))
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(port_id.clone(), inbound, channel_end(7))
+			.with_channel(port_id.clone(), outbound, channel_end(8))
+			.with_send_sequence(port_id.clone(), inbound, 1.into())
+			.with_send_sequence(port_id.clone(), outbound, 1.into());
+		let mut module = DummyTransferModule::new(ctx.ibc_store_share());
+		let sent_packets = |output: HandlerOutputBuilder<()>| {
+			output
+				.with_result(())
+				.events
+				.into_iter()
+				.filter_map(|event| match event {
+					IbcEvent::SendPacket(event) => Some(event.packet),
+					_ => None,
+				})
+				.collect::<Vec<_>>()
+		};
+
+		let sender = get_dummy_account_id();
+		let data = PacketDataV2 {
+			tokens: vec![
+				PrefixedCoin { denom: "uatom".parse().unwrap(), amount: 10u64.into() },
+				PrefixedCoin {
+					denom: "transfer/channel-7/uosmo".parse().unwrap(),
+					amount: 5u64.into(),
+				},
+			],
+			sender: sender.clone(),
+			receiver: "receiver".parse().unwrap(),
+			memo: String::new(),
+			forwarding: Forwarding {
+				hops: vec![TracePrefix::new(port_id.clone(), outbound)],
+				destination_memo: "memo".to_string(),
+			},
+		};
+		let packet = Packet {
+			sequence: 1.into(),
+			source_port: port_id.clone(),
+			source_channel: ChannelId::new(7),
+			destination_port: port_id.clone(),
+			destination_channel: inbound,
+			data: data.encode(&Version::ics20_v2()).unwrap(),
+			timeout_height: Height::zero(),
+			timeout_timestamp: Timestamp::none(),
+		};
+
+		// the received tokens are sent to the next hop by the forwarding address
+		let mut output = HandlerOutputBuilder::new();
+		let ack = on_recv_packet(&mut module, &mut output, &packet, &sender);
+		assert!(ack.is_successful());
+		let forward_packet = sent_packets(output).pop().unwrap();
+		assert_eq!(forward_packet.source_channel, outbound);
+		let forwarding_address = module.get_forwarding_address(&port_id, inbound).unwrap();
+		let forward_data =
+			PacketDataV2::decode(&Version::ics20_v2(), &forward_packet.data).unwrap();
+		let local_tokens = vec![
+			PrefixedCoin {
+				denom: "transfer/channel-0/uatom".parse().unwrap(),
+				amount: 10u64.into(),
+			},
+			PrefixedCoin { denom: "uosmo".parse().unwrap(), amount: 5u64.into() },
+		];
+		assert_eq!(forward_data.tokens, local_tokens);
+		assert_eq!(forward_data.sender, forwarding_address);
+		assert_eq!(forward_data.memo, "memo");
+		assert!(forward_data.forwarding.is_empty());
+		assert_eq!(
+			module.get_forwarded_packet(&port_id, outbound, forward_packet.sequence),
+			Some(packet)
+		);
+
+		// an error acknowledgement sends the tokens back to the original sender
+		let ack = Acknowledgement::from_error(Ics20Error::receive_disabled());
+		let ack = GenericAcknowledgement::from_bytes(serde_json::to_vec(&ack).unwrap());
+		let mut output = HandlerOutputBuilder::new();
+		on_acknowledgement_packet(&mut module, &mut output, &forward_packet, &ack, &sender)
+			.unwrap();
+		let return_packet = sent_packets(output).pop().unwrap();
+		assert_eq!(return_packet.source_channel, inbound);
+		let return_data = PacketDataV2::decode(&Version::ics20_v2(), &return_packet.data).unwrap();
+		assert_eq!(return_data.tokens, local_tokens);
+		assert_eq!(return_data.sender, forwarding_address);
+		assert_eq!(return_data.receiver, sender);
+		assert_eq!(module.get_forwarded_packet(&port_id, outbound, forward_packet.sequence), None);
+	}
 }
@@ -17,7 +17,9 @@ use core::{fmt, str::FromStr};
 use derive_more::{Display, From, Into};
 use ibc_proto::{
 	cosmos::base::v1beta1::Coin as RawCoin,
-	ibc::applications::transfer::v1::DenomTrace as RawDenomTrace,
+	ibc::applications::transfer::v1::{
+		Denom as RawDenom, DenomTrace as RawDenomTrace, Hop as RawHop, Token as RawToken,
+	},
 };
 use serde::{Deserialize, Serialize};
 
@@ -68,6 +70,32 @@ impl TracePrefix {
 	pub fn new(port_id: PortId, channel_id: ChannelId) -> Self {
 		Self { port_id, channel_id }
 	}
+
+	pub fn port_id(&self) -> &PortId {
+		&self.port_id
+	}
+
+	pub fn channel_id(&self) -> ChannelId {
+		self.channel_id
+	}
+}
+
+impl TryFrom<RawHop> for TracePrefix {
+	type Error = Error;
+
+	fn try_from(value: RawHop) -> Result<Self, Self::Error> {
+		let port_id = PortId::from_str(&value.port_id)
+			.map_err(|e| Error::invalid_port_id(value.port_id.clone(), e))?;
+		let channel_id = ChannelId::from_str(&value.channel_id)
+			.map_err(|e| Error::invalid_channel_id(value.channel_id.clone(), e))?;
+		Ok(Self { port_id, channel_id })
+	}
+}
+
+impl From<TracePrefix> for RawHop {
+	fn from(value: TracePrefix) -> Self {
+		Self { port_id: value.port_id.to_string(), channel_id: value.channel_id.to_string() }
+	}
 }
 
 impl fmt::Display for TracePrefix {
@@ -284,6 +312,32 @@ impl From<PrefixedDenom> for RawDenomTrace {
 	}
 }
 
+impl TryFrom<RawDenom> for PrefixedDenom {
+	type Error = Error;
+
+	fn try_from(value: RawDenom) -> Result<Self, Self::Error> {
+		let base_denom = BaseDenom::from_str(&value.base)?;
+		// the raw trace lists the most recent hop first, while `TracePath` keeps it last
+		let trace_path = value
+			.trace
+			.into_iter()
+			.rev()
+			.map(TracePrefix::try_from)
+			.collect::<Result<Vec<_>, _>>()?
+			.into();
+		Ok(Self { trace_path, base_denom })
+	}
+}
+
+impl From<PrefixedDenom> for RawDenom {
+	fn from(value: PrefixedDenom) -> Self {
+		Self {
+			base: value.base_denom.to_string(),
+			trace: value.trace_path.0.into_iter().rev().map(Into::into).collect(),
+		}
+	}
+}
+
 impl From<BaseDenom> for PrefixedDenom {
 	fn from(denom: BaseDenom) -> Self {
 		Self { trace_path: Default::default(), base_denom: denom }
@@ -380,6 +434,22 @@ impl<D: ToString> From<Coin<D>> for RawCoin {
 	}
 }
 
+impl TryFrom<RawToken> for PrefixedCoin {
+	type Error = Error;
+
+	fn try_from(token: RawToken) -> Result<Self, Self::Error> {
+		let denom = token.denom.ok_or_else(Error::invalid_token)?.try_into()?;
+		let amount = Amount::from_str(&token.amount)?;
+		Ok(Self { denom, amount })
+	}
+}
+
+impl From<PrefixedCoin> for RawToken {
+	fn from(coin: PrefixedCoin) -> RawToken {
+		RawToken { denom: Some(coin.denom.into()), amount: coin.amount.to_string() }
+	}
+}
+
 impl From<BaseCoin> for PrefixedCoin {
 	fn from(coin: BaseCoin) -> PrefixedCoin {
 		PrefixedCoin { denom: coin.denom.into(), amount: coin.amount }
@@ -395,7 +465,11 @@ impl fmt::Display for PrefixedCoin {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::{applications::transfer::packet::PacketData, signer::Signer};
+	use crate::{
+		applications::transfer::packet::{Forwarding, PacketData, PacketDataV2},
+		core::ics04_channel::Version,
+		signer::Signer,
+	};
 
 	#[test]
 	fn test_denom_validation() -> Result<(), Error> {
@@ -515,4 +589,37 @@ mod tests {
 		);
 		assert_eq!(packet_data, serde_json::from_str(&string).unwrap());
 	}
+
+	#[test]
+	fn packet_data_v2_encoding() {
+		let denom = PrefixedDenom::from_str("transfer/channel-0/transfer/channel-1/UNIT").unwrap();
+		let raw_denom = RawDenom::from(denom.clone());
+		assert_eq!(raw_denom.trace[0].channel_id, "channel-0", "most recent hop comes first");
+		assert_eq!(PrefixedDenom::try_from(raw_denom).unwrap(), denom);
+
+		let token = PrefixedCoin { denom, amount: Amount::from_str("10000000000").unwrap() };
+		let mut packet_data = PacketDataV2 {
+			tokens: vec![token.clone()],
+			sender: Signer::from_str("sender").unwrap(),
+			receiver: Signer::from_str("receiver").unwrap(),
+			memo: "".to_string(),
+			forwarding: Forwarding::default(),
+		};
+
+		// single token transfers remain compatible with `ics20-1` channels
+		let bytes = packet_data.clone().encode(&Version::ics20()).unwrap();
+		let v1_data: PacketData = serde_json::from_slice(&bytes).unwrap();
+		assert_eq!(PacketDataV2::from(v1_data), packet_data);
+
+		packet_data
+			.tokens
+			.push(PrefixedCoin { denom: "uatom".parse().unwrap(), amount: 1u64.into() });
+		packet_data
+			.forwarding
+			.hops
+			.push(TracePrefix::new("transfer".parse().unwrap(), "channel-2".parse().unwrap()));
+		assert!(packet_data.clone().encode(&Version::ics20()).is_err());
+		let bytes = packet_data.clone().encode(&Version::ics20_v2()).unwrap();
+		assert_eq!(PacketDataV2::decode(&Version::ics20_v2(), &bytes).unwrap(), packet_data);
+	}
 }
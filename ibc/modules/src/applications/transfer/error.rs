@@ -119,11 +119,11 @@ define_error! {
 
 		InvalidVersion
 			{ version: Version }
-			| e | { format_args!("expected version '{0}', got '{1}'", Version::ics20(), e.version) },
+			| e | { format_args!("expected version '{0}' or '{1}', got '{2}'", Version::ics20(), Version::ics20_v2(), e.version) },
 
 		InvalidCounterpartyVersion
 			{ version: Version }
-			| e | { format_args!("expected counterparty version '{0}', got '{1}'", Version::ics20(), e.version) },
+			| e | { format_args!("expected counterparty version '{0}' or '{1}', got '{2}'", Version::ics20(), Version::ics20_v2(), e.version) },
 
 		CantCloseChannel
 			| _ | { "channel cannot be closed" },
@@ -158,6 +158,19 @@ define_error! {
 			{ msg_type: String }
 			| e | { format_args!("unknown msg type: {0}", e.msg_type) },
 
+		EmptyTokens
+			| _ | { "at least one token must be transferred" },
+
+		UnsupportedTransfer
+			{ version: Version }
+			| e | { format_args!("channel version '{0}' does not support multi-denom or forwarded transfers", e.version) },
+
+		ForwardingNotSupported
+			| _ | { "packet forwarding is not supported by this chain" },
+
+		ForwardingUnwindNotSupported
+			| _ | { "unwinding forwarded tokens is not supported" },
+
 		ImplementationSpecific
 			{ reason: String }
 			| e | { format_args!("implementation specific error: {}", e.reason) },
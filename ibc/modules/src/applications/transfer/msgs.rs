@@ -13,3 +13,4 @@
 // limitations under the License.
 
 pub mod transfer;
+pub mod transfer_v2;
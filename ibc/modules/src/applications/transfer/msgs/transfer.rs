@@ -89,6 +89,14 @@ where
 	type Error = Error;
 
 	fn try_from(raw_msg: RawMsgTransfer) -> Result<Self, Self::Error> {
+		// multi-denom and forwarded transfers are handled by `MsgTransferV2`
+		if !raw_msg.tokens.is_empty() {
+			return Err(Error::invalid_token())
+		}
+		if raw_msg.forwarding.is_some() {
+			return Err(Error::forwarding_not_supported())
+		}
+
 		let timeout_timestamp = Timestamp::from_nanoseconds(raw_msg.timeout_timestamp)
 			.map_err(|_| Error::invalid_packet_timeout_timestamp(raw_msg.timeout_timestamp))?;
 
@@ -132,6 +140,8 @@ where
 			timeout_height: Some(domain_msg.timeout_height.into()),
 			timeout_timestamp: domain_msg.timeout_timestamp.nanoseconds(),
 			memo: domain_msg.memo,
+			tokens: vec![],
+			forwarding: None,
 		}
 	}
 }
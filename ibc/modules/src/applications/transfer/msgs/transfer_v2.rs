@@ -0,0 +1,228 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Definition of the multi-denom transfer message introduced with `ics20-2`. It shares the type
+//! url of `MsgTransfer`, using the `tokens` and `forwarding` fields of the raw message.
+
+use crate::prelude::*;
+use core::fmt::Display;
+
+use ibc_proto::{
+	cosmos::base::v1beta1::Coin,
+	google::protobuf::Any,
+	ibc::applications::transfer::v1::{Forwarding as RawForwarding, MsgTransfer as RawMsgTransfer},
+};
+use tendermint_proto::Protobuf;
+
+use crate::{
+	applications::transfer::{
+		error::Error,
+		msgs::transfer::{MsgTransfer, TYPE_URL},
+		TracePrefix,
+	},
+	core::{
+		ics02_client::height::Height,
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	signer::Signer,
+	timestamp::Timestamp,
+	tx_msg::Msg,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgTransferV2<C = Coin> {
+	/// the port on which the packet will be sent
+	pub source_port: PortId,
+	/// the channel by which the packet will be sent
+	pub source_channel: ChannelId,
+	/// the tokens to be transferred
+	pub tokens: Vec<C>,
+	/// the sender address
+	pub sender: Signer,
+	/// the recipient address on the final destination chain
+	pub receiver: Signer,
+	/// Timeout height relative to the current block height.
+	/// The timeout is disabled when set to 0.
+	pub timeout_height: Height,
+	/// Timeout timestamp relative to the current block timestamp.
+	/// The timeout is disabled when set to 0.
+	pub timeout_timestamp: Timestamp,
+	/// Memo field, delivered on the final destination chain
+	pub memo: String,
+	/// The hops through which the tokens are forwarded once they reach the destination chain
+	pub forwarding: Vec<TracePrefix>,
+}
+
+impl<C> From<MsgTransfer<C>> for MsgTransferV2<C> {
+	fn from(msg: MsgTransfer<C>) -> Self {
+		Self {
+			source_port: msg.source_port,
+			source_channel: msg.source_channel,
+			tokens: vec![msg.token],
+			sender: msg.sender,
+			receiver: msg.receiver,
+			timeout_height: msg.timeout_height,
+			timeout_timestamp: msg.timeout_timestamp,
+			memo: msg.memo,
+			forwarding: vec![],
+		}
+	}
+}
+
+impl<C> Msg for MsgTransferV2<C>
+where
+	C: Clone,
+	Coin: From<C>,
+{
+	type ValidationError = Error;
+	type Raw = RawMsgTransfer;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl<C: TryFrom<Coin>> TryFrom<RawMsgTransfer> for MsgTransferV2<C>
+where
+	Error: From<<C as TryFrom<Coin>>::Error>,
+{
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgTransfer) -> Result<Self, Self::Error> {
+		let timeout_timestamp = Timestamp::from_nanoseconds(raw_msg.timeout_timestamp)
+			.map_err(|_| Error::invalid_packet_timeout_timestamp(raw_msg.timeout_timestamp))?;
+
+		let timeout_height = match raw_msg.timeout_height.clone() {
+			None => Height::zero(),
+			Some(raw_height) => raw_height.try_into().map_err(|e| {
+				Error::invalid_packet_timeout_height(format!("invalid timeout height {}", e))
+			})?,
+		};
+
+		// either the legacy `token` or the `tokens` field may be set, but not both
+		let tokens = match (raw_msg.token, raw_msg.tokens) {
+			(Some(_), tokens) if !tokens.is_empty() => return Err(Error::invalid_token()),
+			(Some(token), _) => vec![token],
+			(None, tokens) if tokens.is_empty() => return Err(Error::empty_tokens()),
+			(None, tokens) => tokens,
+		};
+
+		let forwarding = match raw_msg.forwarding {
+			None => vec![],
+			Some(RawForwarding { unwind: true, .. }) =>
+				return Err(Error::forwarding_unwind_not_supported()),
+			Some(RawForwarding { hops, .. }) =>
+				hops.into_iter().map(TracePrefix::try_from).collect::<Result<_, _>>()?,
+		};
+
+		Ok(MsgTransferV2 {
+			source_port: raw_msg
+				.source_port
+				.parse()
+				.map_err(|e| Error::invalid_port_id(raw_msg.source_port.clone(), e))?,
+			source_channel: raw_msg
+				.source_channel
+				.parse()
+				.map_err(|e| Error::invalid_channel_id(raw_msg.source_channel.clone(), e))?,
+			tokens: tokens.into_iter().map(C::try_from).collect::<Result<_, _>>()?,
+			sender: raw_msg.sender.parse().map_err(Error::signer)?,
+			receiver: raw_msg.receiver.parse().map_err(Error::signer)?,
+			timeout_height,
+			timeout_timestamp,
+			memo: raw_msg.memo,
+			forwarding,
+		})
+	}
+}
+
+impl<C> From<MsgTransferV2<C>> for RawMsgTransfer
+where
+	Coin: From<C>,
+{
+	fn from(domain_msg: MsgTransferV2<C>) -> Self {
+		let forwarding = (!domain_msg.forwarding.is_empty()).then(|| RawForwarding {
+			unwind: false,
+			hops: domain_msg.forwarding.into_iter().map(Into::into).collect(),
+		});
+		RawMsgTransfer {
+			source_port: domain_msg.source_port.to_string(),
+			source_channel: domain_msg.source_channel.to_string(),
+			token: None,
+			sender: domain_msg.sender.to_string(),
+			receiver: domain_msg.receiver.to_string(),
+			timeout_height: Some(domain_msg.timeout_height.into()),
+			timeout_timestamp: domain_msg.timeout_timestamp.nanoseconds(),
+			memo: domain_msg.memo,
+			tokens: domain_msg.tokens.into_iter().map(Into::into).collect(),
+			forwarding,
+		}
+	}
+}
+
+impl<C: Protobuf<Coin>> Protobuf<RawMsgTransfer> for MsgTransferV2<C>
+where
+	Coin: From<C>,
+	<C as TryFrom<Coin>>::Error: Display,
+	MsgTransferV2<C>: TryFrom<RawMsgTransfer>,
+	<MsgTransferV2<C> as TryFrom<RawMsgTransfer>>::Error: Display,
+{
+}
+
+impl<C> TryFrom<Any> for MsgTransferV2<C>
+where
+	C: TryFrom<Any> + Protobuf<Coin>,
+	<C as TryFrom<Coin>>::Error: Display,
+	Coin: From<C>,
+	MsgTransferV2<C>: TryFrom<RawMsgTransfer>,
+	<MsgTransferV2<C> as TryFrom<RawMsgTransfer>>::Error: Display,
+{
+	type Error = Error;
+
+	fn try_from(raw: Any) -> Result<Self, Self::Error> {
+		match raw.type_url.as_str() {
+			TYPE_URL => MsgTransferV2::decode_vec(&raw.value).map_err(Error::decode_raw_msg),
+			_ => Err(Error::unknown_msg_type(raw.type_url)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::applications::transfer::{
+		msgs::transfer::test_util::get_dummy_msg_transfer, PrefixedCoin,
+	};
+
+	#[test]
+	fn msg_transfer_v2_raw_roundtrip() {
+		let mut msg = MsgTransferV2::from(get_dummy_msg_transfer(10));
+		msg.tokens.push(PrefixedCoin {
+			denom: "transfer/channel-1/uosmo".parse().unwrap(),
+			amount: 5u64.into(),
+		});
+		msg.forwarding = vec![TracePrefix::new(PortId::transfer(), ChannelId::new(2))];
+
+		let raw = RawMsgTransfer::from(msg.clone());
+		assert!(raw.token.is_none());
+		assert_eq!(raw.tokens.len(), 2);
+		assert_eq!(MsgTransferV2::<PrefixedCoin>::try_from(raw.clone()).unwrap(), msg);
+
+		// the legacy message only carries a single token
+		assert!(MsgTransfer::<PrefixedCoin>::try_from(raw).is_err());
+	}
+}
@@ -15,11 +15,15 @@
 use alloc::string::{String, ToString};
 use core::{convert::TryFrom, str::FromStr};
 
-use ibc_proto::ibc::applications::transfer::v2::FungibleTokenPacketData as RawPacketData;
+use ibc_proto::ibc::applications::transfer::v2::{
+	ForwardingPacketData as RawForwardingPacketData, FungibleTokenPacketData as RawPacketData,
+	FungibleTokenPacketDataV2 as RawPacketDataV2,
+};
+use prost::Message;
 use serde::{Deserialize, Serialize};
 
-use super::{error::Error, Amount, PrefixedCoin, PrefixedDenom};
-use crate::signer::Signer;
+use super::{error::Error, Amount, PrefixedCoin, PrefixedDenom, TracePrefix};
+use crate::{core::ics04_channel::Version, prelude::*, signer::Signer};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PacketData {
@@ -58,3 +62,134 @@ impl From<PacketData> for RawPacketData {
 		}
 	}
 }
+
+/// Forwarding information of an `ics20-2` packet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Forwarding {
+	/// The hops the tokens still have to travel through, the next one first.
+	pub hops: Vec<TracePrefix>,
+	/// The memo delivered along with the tokens on the final destination chain.
+	pub destination_memo: String,
+}
+
+impl Forwarding {
+	/// Returns true if the tokens are not forwarded any further.
+	pub fn is_empty(&self) -> bool {
+		self.hops.is_empty()
+	}
+}
+
+impl TryFrom<RawForwardingPacketData> for Forwarding {
+	type Error = Error;
+
+	fn try_from(raw: RawForwardingPacketData) -> Result<Self, Self::Error> {
+		Ok(Self {
+			hops: raw.hops.into_iter().map(TracePrefix::try_from).collect::<Result<_, _>>()?,
+			destination_memo: raw.destination_memo,
+		})
+	}
+}
+
+impl From<Forwarding> for RawForwardingPacketData {
+	fn from(forwarding: Forwarding) -> Self {
+		Self {
+			destination_memo: forwarding.destination_memo,
+			hops: forwarding.hops.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// The packet data of a multi-denom (`ics20-2`) transfer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PacketDataV2 {
+	pub tokens: Vec<PrefixedCoin>,
+	pub sender: Signer,
+	pub receiver: Signer,
+	pub memo: String,
+	pub forwarding: Forwarding,
+}
+
+impl PacketDataV2 {
+	/// Decodes the data of a packet sent over a channel with the given version. `ics20-2`
+	/// packets are protobuf encoded, `ics20-1` packets are JSON encoded.
+	pub fn decode(version: &Version, data: &[u8]) -> Result<Self, Error> {
+		if version == &Version::ics20_v2() {
+			RawPacketDataV2::decode(data)
+				.map_err(|_| Error::packet_data_deserialization())?
+				.try_into()
+		} else {
+			serde_json::from_slice::<PacketData>(data)
+				.map(Into::into)
+				.map_err(|_| Error::packet_data_deserialization())
+		}
+	}
+
+	/// Encodes the packet data for a channel with the given version. Only transfers of a single
+	/// token without forwarding can be sent over `ics20-1` channels.
+	pub fn encode(self, version: &Version) -> Result<Vec<u8>, Error> {
+		if version == &Version::ics20_v2() {
+			return Ok(RawPacketDataV2::from(self).encode_to_vec())
+		}
+
+		if self.tokens.len() != 1 || !self.forwarding.is_empty() {
+			return Err(Error::unsupported_transfer(version.clone()))
+		}
+		let data = PacketData {
+			token: self.tokens.into_iter().next().expect("length checked above; qed"),
+			sender: self.sender,
+			receiver: self.receiver,
+			memo: self.memo,
+		};
+		Ok(serde_json::to_vec(&data).expect("PacketData's infallible Serialize impl failed"))
+	}
+}
+
+impl From<PacketData> for PacketDataV2 {
+	fn from(data: PacketData) -> Self {
+		Self {
+			tokens: vec![data.token],
+			sender: data.sender,
+			receiver: data.receiver,
+			memo: data.memo,
+			forwarding: Forwarding::default(),
+		}
+	}
+}
+
+impl TryFrom<RawPacketDataV2> for PacketDataV2 {
+	type Error = Error;
+
+	fn try_from(raw_pkt_data: RawPacketDataV2) -> Result<Self, Self::Error> {
+		if raw_pkt_data.tokens.is_empty() {
+			return Err(Error::empty_tokens())
+		}
+
+		Ok(Self {
+			tokens: raw_pkt_data
+				.tokens
+				.into_iter()
+				.map(PrefixedCoin::try_from)
+				.collect::<Result<_, _>>()?,
+			sender: raw_pkt_data.sender.parse().map_err(Error::signer)?,
+			receiver: raw_pkt_data.receiver.parse().map_err(Error::signer)?,
+			memo: raw_pkt_data.memo,
+			forwarding: raw_pkt_data
+				.forwarding
+				.map(TryInto::try_into)
+				.transpose()?
+				.unwrap_or_default(),
+		})
+	}
+}
+
+impl From<PacketDataV2> for RawPacketDataV2 {
+	fn from(pkt_data: PacketDataV2) -> Self {
+		Self {
+			tokens: pkt_data.tokens.into_iter().map(Into::into).collect(),
+			sender: pkt_data.sender.to_string(),
+			receiver: pkt_data.receiver.to_string(),
+			memo: pkt_data.memo,
+			forwarding: (!pkt_data.forwarding.is_empty()).then(|| pkt_data.forwarding.into()),
+		}
+	}
+}
@@ -14,23 +14,58 @@
 
 //! This module implements the processing logic for ICS20 (token transfer) message.
 use crate::{
-	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, is_sender_chain_source,
-		packet::PacketData,
+	applications::{
+		fee::metadata::Metadata,
+		transfer::{
+			context::Ics20Context, error::Error as Ics20Error, is_sender_chain_source,
+			msgs::transfer_v2::MsgTransferV2, packet::PacketDataV2,
+			relay::send_transfer::send_transfer_v2,
+		},
+	},
+	core::{
+		ics04_channel::{packet::Packet, Version},
+		ics24_host::identifier::{ChannelId, PortId},
+		ics26_routing::context::ModuleOutputBuilder,
 	},
-	core::ics04_channel::packet::Packet,
 	prelude::*,
 };
+use core::time::Duration;
 
 pub mod on_ack_packet;
 pub mod on_recv_packet;
 pub mod on_timeout_packet;
 pub mod send_transfer;
 
-fn refund_packet_token(
+/// Timeout of the packets sending forwarded tokens to their next hop, relative to the host
+/// timestamp.
+pub const FORWARD_PACKET_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Decodes the data of a packet sent or received on the given channel, according to the version
+/// of the channel.
+pub fn decode_packet_data(
+	ctx: &impl Ics20Context,
+	port_id: &PortId,
+	channel_id: ChannelId,
+	data: &[u8],
+) -> Result<PacketDataV2, Ics20Error> {
+	let channel_end = ctx
+		.channel_end(&(port_id.clone(), channel_id))
+		.map_err(Ics20Error::ics04_channel)?;
+	PacketDataV2::decode(&channel_app_version(channel_end.version()), data)
+}
+
+/// Returns the ICS20 version of a channel, unwrapping the application version of fee enabled
+/// channels.
+pub fn channel_app_version(version: &Version) -> Version {
+	Metadata::try_from(version)
+		.map(|metadata| metadata.app_version())
+		.unwrap_or_else(|_| version.clone())
+}
+
+fn refund_packet_tokens(
 	ctx: &mut impl Ics20Context,
 	packet: &Packet,
-	data: &PacketData,
+	data: &PacketDataV2,
 ) -> Result<(), Ics20Error> {
 	let sender = data
 		.sender
@@ -38,16 +73,65 @@ fn refund_packet_token(
 		.try_into()
 		.map_err(|_| Ics20Error::parse_account_failure())?;
 
-	if is_sender_chain_source(packet.source_port.clone(), packet.source_channel, &data.token.denom)
-	{
-		// unescrow tokens back to sender
-		let escrow_address =
-			ctx.get_channel_escrow_address(&packet.source_port, packet.source_channel)?;
+	// either all tokens are refunded or the whole packet fails
+	for token in &data.tokens {
+		if is_sender_chain_source(packet.source_port.clone(), packet.source_channel, &token.denom) {
+			// unescrow tokens back to sender
+			let escrow_address =
+				ctx.get_channel_escrow_address(&packet.source_port, packet.source_channel)?;
 
-		ctx.send_coins(&escrow_address, &sender, &data.token)
+			ctx.send_coins(&escrow_address, &sender, token)?;
+		}
+		// mint vouchers back to sender
+		else {
+			ctx.mint_coins(&sender, token)?;
+		}
 	}
-	// mint vouchers back to sender
-	else {
-		ctx.mint_coins(&sender, &data.token)
+
+	Ok(())
+}
+
+/// Settles the packet received on this chain whose tokens were forwarded by `packet`, once
+/// `packet` is acknowledged or timed out. If forwarding failed, the tokens refunded to the
+/// forwarding address are sent back to the original sender.
+fn settle_forwarded_packet(
+	ctx: &mut impl Ics20Context,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	data: &PacketDataV2,
+	success: bool,
+) -> Result<(), Ics20Error> {
+	let forwarded_packet =
+		match ctx.get_forwarded_packet(&packet.source_port, packet.source_channel, packet.sequence)
+		{
+			Some(forwarded_packet) => forwarded_packet,
+			None => return Ok(()),
+		};
+	ctx.delete_forwarded_packet(&packet.source_port, packet.source_channel, packet.sequence)?;
+
+	if success {
+		return Ok(())
 	}
+
+	let forwarded_data = decode_packet_data(
+		ctx,
+		&forwarded_packet.destination_port,
+		forwarded_packet.destination_channel,
+		&forwarded_packet.data,
+	)?;
+	let host_timestamp = ctx.host_timestamp();
+	let timeout_timestamp = (host_timestamp + FORWARD_PACKET_TIMEOUT)
+		.map_err(|_| Ics20Error::invalid_packet_timeout_timestamp(host_timestamp.nanoseconds()))?;
+	let msg = MsgTransferV2 {
+		source_port: forwarded_packet.destination_port,
+		source_channel: forwarded_packet.destination_channel,
+		tokens: data.tokens.clone(),
+		sender: data.sender.clone(),
+		receiver: forwarded_data.sender,
+		timeout_height: Default::default(),
+		timeout_timestamp,
+		memo: String::new(),
+		forwarding: vec![],
+	};
+	send_transfer_v2(ctx, output, msg)
 }
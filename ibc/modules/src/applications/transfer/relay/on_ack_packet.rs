@@ -14,20 +14,24 @@
 
 use crate::{
 	applications::transfer::{
-		acknowledgement::Acknowledgement, context::Ics20Context, error::Error as Ics20Error,
-		packet::PacketData, relay::refund_packet_token,
+		acknowledgement::Acknowledgement,
+		context::Ics20Context,
+		error::Error as Ics20Error,
+		packet::PacketDataV2,
+		relay::{refund_packet_tokens, settle_forwarded_packet},
 	},
-	core::ics04_channel::packet::Packet,
+	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
 };
 
 pub fn process_ack_packet(
 	ctx: &mut impl Ics20Context,
+	output: &mut ModuleOutputBuilder,
 	packet: &Packet,
-	data: &PacketData,
+	data: &PacketDataV2,
 	ack: &Acknowledgement,
 ) -> Result<(), Ics20Error> {
 	if !ack.is_successful() {
-		refund_packet_token(ctx, packet, data)?;
+		refund_packet_tokens(ctx, packet, data)?;
 	}
-	Ok(())
+	settle_forwarded_packet(ctx, output, packet, data, ack.is_successful())
 }
@@ -14,8 +14,14 @@
 
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, events::DenomTraceEvent,
-		is_receiver_chain_source, packet::PacketData, TracePrefix,
+		context::{BankKeeper, Ics20Context},
+		error::Error as Ics20Error,
+		events::DenomTraceEvent,
+		is_receiver_chain_source,
+		msgs::transfer_v2::MsgTransferV2,
+		packet::PacketDataV2,
+		relay::{send_transfer::send_transfer_v2, FORWARD_PACKET_TIMEOUT},
+		PrefixedCoin, TracePrefix,
 	},
 	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
 	prelude::*,
@@ -25,27 +31,75 @@ pub fn process_recv_packet<Ctx: 'static + Ics20Context>(
 	ctx: &mut Ctx,
 	output: &mut ModuleOutputBuilder,
 	packet: &Packet,
-	data: PacketData,
+	data: PacketDataV2,
 ) -> Result<(), Ics20Error> {
 	if !ctx.is_receive_enabled() {
 		return Err(Ics20Error::receive_disabled())
 	}
 
-	let receiver_account = data
-		.receiver
-		.clone()
-		.try_into()
-		.map_err(|_| Ics20Error::parse_account_failure())?;
+	// tokens which are forwarded are held by the forwarding address until they reach their
+	// next hop
+	let receiver = if data.forwarding.is_empty() {
+		data.receiver.clone()
+	} else {
+		ctx.get_forwarding_address(&packet.destination_port, packet.destination_channel)?
+	};
+	let receiver_account =
+		receiver.clone().try_into().map_err(|_| Ics20Error::parse_account_failure())?;
+
+	// either all tokens are received or the whole packet fails
+	let mut tokens = Vec::with_capacity(data.tokens.len());
+	for token in data.tokens.iter().cloned() {
+		tokens.push(receive_token(ctx, output, packet, &receiver_account, token)?);
+	}
+
+	if data.forwarding.is_empty() {
+		return Ok(())
+	}
+
+	let mut hops = data.forwarding.hops.into_iter();
+	let next_hop = hops.next().expect("forwarding is not empty; qed");
+	let host_timestamp = ctx.host_timestamp();
+	let timeout_timestamp = (host_timestamp + FORWARD_PACKET_TIMEOUT)
+		.map_err(|_| Ics20Error::invalid_packet_timeout_timestamp(host_timestamp.nanoseconds()))?;
+	let sequence = ctx
+		.get_next_sequence_send(&(next_hop.port_id().clone(), next_hop.channel_id()))
+		.map_err(Ics20Error::ics04_channel)?;
+	let msg = MsgTransferV2 {
+		source_port: next_hop.port_id().clone(),
+		source_channel: next_hop.channel_id(),
+		tokens,
+		sender: receiver,
+		receiver: data.receiver,
+		timeout_height: Default::default(),
+		timeout_timestamp,
+		memo: data.forwarding.destination_memo,
+		forwarding: hops.collect(),
+	};
+	send_transfer_v2(ctx, output, msg)?;
 
-	if is_receiver_chain_source(
-		packet.source_port.clone(),
-		packet.source_channel,
-		&data.token.denom,
-	) {
+	ctx.store_forwarded_packet(
+		next_hop.port_id().clone(),
+		next_hop.channel_id(),
+		sequence,
+		packet.clone(),
+	)
+}
+
+/// Unescrows or mints the received token to the receiver and returns it, as denominated on this
+/// chain.
+fn receive_token<Ctx: 'static + Ics20Context>(
+	ctx: &mut Ctx,
+	output: &mut ModuleOutputBuilder,
+	packet: &Packet,
+	receiver_account: &<Ctx as BankKeeper>::AccountId,
+	token: PrefixedCoin,
+) -> Result<PrefixedCoin, Ics20Error> {
+	if is_receiver_chain_source(packet.source_port.clone(), packet.source_channel, &token.denom) {
 		// sender chain is not the source, unescrow tokens
 		let prefix = TracePrefix::new(packet.source_port.clone(), packet.source_channel);
 		let coin = {
-			let mut c = data.token;
+			let mut c = token;
 			c.denom.remove_trace_prefix(&prefix);
 			c
 		};
@@ -53,12 +107,13 @@ pub fn process_recv_packet<Ctx: 'static + Ics20Context>(
 		let escrow_address =
 			ctx.get_channel_escrow_address(&packet.destination_port, packet.destination_channel)?;
 
-		ctx.send_coins(&escrow_address, &receiver_account, &coin)
+		ctx.send_coins(&escrow_address, receiver_account, &coin)?;
+		Ok(coin)
 	} else {
 		// sender chain is the source, mint vouchers
 		let prefix = TracePrefix::new(packet.destination_port.clone(), packet.destination_channel);
 		let coin = {
-			let mut c = data.token;
+			let mut c = token;
 			c.denom.add_trace_prefix(prefix);
 			c
 		};
@@ -69,6 +124,7 @@ pub fn process_recv_packet<Ctx: 'static + Ics20Context>(
 		};
 		output.emit(denom_trace_event.into());
 
-		ctx.mint_coins(&receiver_account, &coin)
+		ctx.mint_coins(receiver_account, &coin)?;
+		Ok(coin)
 	}
 }
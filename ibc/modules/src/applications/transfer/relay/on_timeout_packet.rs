@@ -14,16 +14,20 @@
 
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error as Ics20Error, packet::PacketData,
-		relay::refund_packet_token,
+		context::Ics20Context,
+		error::Error as Ics20Error,
+		packet::PacketDataV2,
+		relay::{refund_packet_tokens, settle_forwarded_packet},
 	},
-	core::ics04_channel::packet::Packet,
+	core::{ics04_channel::packet::Packet, ics26_routing::context::ModuleOutputBuilder},
 };
 
 pub fn process_timeout_packet(
 	ctx: &mut impl Ics20Context,
+	output: &mut ModuleOutputBuilder,
 	packet: &Packet,
-	data: &PacketData,
+	data: &PacketDataV2,
 ) -> Result<(), Ics20Error> {
-	refund_packet_token(ctx, packet, data)
+	refund_packet_tokens(ctx, packet, data)?;
+	settle_forwarded_packet(ctx, output, packet, data, false)
 }
@@ -14,22 +14,39 @@
 
 use crate::{
 	applications::transfer::{
-		context::Ics20Context, error::Error, events::TransferEvent, is_sender_chain_source,
-		msgs::transfer::MsgTransfer, packet::PacketData, Coin, PrefixedCoin,
+		context::Ics20Context,
+		error::Error,
+		events::TransferEvent,
+		is_sender_chain_source,
+		msgs::{transfer::MsgTransfer, transfer_v2::MsgTransferV2},
+		packet::{Forwarding, PacketDataV2},
+		relay::channel_app_version,
+		PrefixedCoin,
 	},
 	core::ics04_channel::{handler::send_packet::send_packet, packet::Packet},
 	handler::{HandlerOutput, HandlerOutputBuilder},
 	prelude::*,
 };
 
-/// This function handles the transfer sending logic.
-/// If this method returns an error, the runtime is expected to rollback all state modifications to
-/// the `Ctx` caused by all messages from the transaction that this `msg` is a part of.
 pub fn send_transfer<Ctx, C>(
 	ctx: &mut Ctx,
 	output: &mut HandlerOutputBuilder<()>,
 	msg: MsgTransfer<C>,
 ) -> Result<(), Error>
+where
+	Ctx: Ics20Context,
+	C: TryInto<PrefixedCoin>,
+{
+	send_transfer_v2(ctx, output, msg.into())
+}
+
+/// Sends the tokens of a multi-denom transfer. Transfers of several tokens or with forwarding
+/// hops can only be sent over `ics20-2` channels.
+pub fn send_transfer_v2<Ctx, C>(
+	ctx: &mut Ctx,
+	output: &mut HandlerOutputBuilder<()>,
+	msg: MsgTransferV2<C>,
+) -> Result<(), Error>
 where
 	Ctx: Ics20Context,
 	C: TryInto<PrefixedCoin>,
@@ -52,29 +69,41 @@ where
 		.get_next_sequence_send(&(msg.source_port.clone(), msg.source_channel))
 		.map_err(Error::ics04_channel)?;
 
-	let token = msg.token.try_into().map_err(|_| Error::invalid_token())?;
-	let denom = token.denom.clone();
-	let coin = Coin { denom: denom.clone(), amount: token.amount };
-
-	let sender = msg.sender.clone().try_into().map_err(|_| Error::parse_account_failure())?;
+	let tokens = msg
+		.tokens
+		.into_iter()
+		.map(|token| token.try_into().map_err(|_| Error::invalid_token()))
+		.collect::<Result<Vec<PrefixedCoin>, _>>()?;
+	if tokens.is_empty() {
+		return Err(Error::empty_tokens())
+	}
 
-	if is_sender_chain_source(msg.source_port.clone(), msg.source_channel, &denom) {
-		let escrow_address =
-			ctx.get_channel_escrow_address(&msg.source_port, msg.source_channel)?;
-		ctx.send_coins(&sender, &escrow_address, &coin)?;
+	// the memo is only delivered on the final destination of forwarded tokens
+	let (memo, forwarding) = if msg.forwarding.is_empty() {
+		(msg.memo, Forwarding::default())
 	} else {
-		ctx.burn_coins(&sender, &coin)?;
+		(String::new(), Forwarding { hops: msg.forwarding, destination_memo: msg.memo })
+	};
+	let data = PacketDataV2 {
+		tokens: tokens.clone(),
+		sender: msg.sender.clone(),
+		receiver: msg.receiver.clone(),
+		memo,
+		forwarding,
 	}
+	.encode(&channel_app_version(source_channel_end.version()))?;
 
-	let data = {
-		let data = PacketData {
-			token: coin,
-			sender: msg.sender.clone(),
-			receiver: msg.receiver.clone(),
-			memo: msg.memo,
-		};
-		serde_json::to_vec(&data).expect("PacketData's infallible Serialize impl failed")
-	};
+	let sender = msg.sender.clone().try_into().map_err(|_| Error::parse_account_failure())?;
+
+	for token in &tokens {
+		if is_sender_chain_source(msg.source_port.clone(), msg.source_channel, &token.denom) {
+			let escrow_address =
+				ctx.get_channel_escrow_address(&msg.source_port, msg.source_channel)?;
+			ctx.send_coins(&sender, &escrow_address, token)?;
+		} else {
+			ctx.burn_coins(&sender, token)?;
+		}
+	}
 
 	let packet = Packet {
 		sequence,
@@ -94,9 +123,10 @@ where
 
 	output.merge_output(HandlerOutput::builder().with_log(log).with_events(events).with_result(()));
 
+	let tokens = tokens.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
 	output.log(format!(
 		"IBC fungible token transfer: {} --({})--> {}",
-		msg.sender, token, msg.receiver
+		msg.sender, tokens, msg.receiver
 	));
 
 	let transfer_event = TransferEvent { sender: msg.sender, receiver: msg.receiver };
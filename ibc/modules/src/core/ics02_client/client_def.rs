@@ -22,6 +22,7 @@ use crate::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			packet::Sequence,
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -161,6 +162,48 @@ pub trait ClientDef: Clone {
 		expected_channel_end: &ChannelEnd,
 	) -> Result<(), Error>;
 
+	/// Verify a `proof` that the upgrade of a channel matches the input `upgrade`. Clients
+	/// that don't override it can't be used to upgrade channels.
+	#[allow(clippy::too_many_arguments)]
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		_client_state: &Self::ClientState,
+		_height: Height,
+		_prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_upgrade: &Upgrade,
+	) -> Result<(), Error> {
+		Err(Error::implementation_specific(
+			"channel upgrades are not supported by this client".to_string(),
+		))
+	}
+
+	/// Verify a `proof` that the upgrade error receipt of a channel matches the input
+	/// `error_receipt`.
+	#[allow(clippy::too_many_arguments)]
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		_client_state: &Self::ClientState,
+		_height: Height,
+		_prefix: &CommitmentPrefix,
+		_proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_error_receipt: &ErrorReceipt,
+	) -> Result<(), Error> {
+		Err(Error::implementation_specific(
+			"channel upgrades are not supported by this client".to_string(),
+		))
+	}
+
 	/// Verify the client state for this chain that it is stored on the counterparty chain.
 	#[allow(clippy::too_many_arguments)]
 	fn verify_client_full_state<Ctx: ReaderContext>(
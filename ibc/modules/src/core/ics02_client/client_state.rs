@@ -87,6 +87,13 @@ pub trait ClientState: Clone + Debug + Send + Sync {
 	/// Helper function to verify the upgrade client procedure.
 	fn expired(&self, elapsed: Duration) -> bool;
 
+	/// Returns the client state to store once a message whose proofs were verified against this
+	/// client has been handled, `None` if it doesn't change. Clients whose proofs can only be
+	/// verified once, e.g. signatures over the sequence of a solo machine, advance past them here.
+	fn on_proofs_verified(&self) -> Option<Self> {
+		None
+	}
+
 	/// Performs downcast of the client state from an "AnyClientState" type to T, otherwise
 	/// panics. Downcast from `T` to `T` is always successful.
 	fn downcast<T: Clone + 'static>(self) -> Option<T>
@@ -58,6 +58,7 @@ impl TryFrom<RawIdentifiedChannel> for IdentifiedChannelEnd {
 			counterparty: value.counterparty,
 			connection_hops: value.connection_hops,
 			version: value.version,
+			upgrade_sequence: value.upgrade_sequence,
 		};
 
 		Ok(IdentifiedChannelEnd {
@@ -83,6 +84,7 @@ impl From<IdentifiedChannelEnd> for RawIdentifiedChannel {
 			version: value.channel_end.version.to_string(),
 			port_id: value.port_id.to_string(),
 			channel_id: value.channel_id.to_string(),
+			upgrade_sequence: value.channel_end.upgrade_sequence.into(),
 		}
 	}
 }
@@ -94,6 +96,9 @@ pub struct ChannelEnd {
 	pub remote: Counterparty,
 	pub connection_hops: Vec<ConnectionId>,
 	pub version: Version,
+	/// The latest upgrade attempt performed by this channel, zero if it was never upgraded.
+	#[serde(default)]
+	pub upgrade_sequence: Sequence,
 }
 
 impl Default for ChannelEnd {
@@ -104,6 +109,7 @@ impl Default for ChannelEnd {
 			remote: Counterparty::default(),
 			connection_hops: Vec::new(),
 			version: Version::default(),
+			upgrade_sequence: Sequence::default(),
 		}
 	}
 }
@@ -131,7 +137,8 @@ impl TryFrom<RawChannel> for ChannelEnd {
 
 		let version = value.version.into();
 
-		Ok(ChannelEnd::new(chan_state, chan_ordering, remote, connection_hops, version))
+		Ok(ChannelEnd::new(chan_state, chan_ordering, remote, connection_hops, version)
+			.with_upgrade_sequence(value.upgrade_sequence.into()))
 	}
 }
 
@@ -143,6 +150,7 @@ impl From<ChannelEnd> for RawChannel {
 			counterparty: Some(value.counterparty().clone().into()),
 			connection_hops: value.connection_hops.iter().map(|v| v.as_str().to_string()).collect(),
 			version: value.version.to_string(),
+			upgrade_sequence: value.upgrade_sequence.into(),
 		}
 	}
 }
@@ -156,7 +164,20 @@ impl ChannelEnd {
 		connection_hops: Vec<ConnectionId>,
 		version: Version,
 	) -> Self {
-		Self { state, ordering, remote, connection_hops, version }
+		Self {
+			state,
+			ordering,
+			remote,
+			connection_hops,
+			version,
+			upgrade_sequence: Sequence::default(),
+		}
+	}
+
+	/// Sets the sequence of the latest upgrade attempt performed by this channel.
+	pub fn with_upgrade_sequence(mut self, upgrade_sequence: Sequence) -> Self {
+		self.upgrade_sequence = upgrade_sequence;
+		self
 	}
 
 	/// Updates the ChannelEnd to assume a new State 's'.
@@ -197,6 +218,10 @@ impl ChannelEnd {
 		&self.version
 	}
 
+	pub fn upgrade_sequence(&self) -> Sequence {
+		self.upgrade_sequence
+	}
+
 	pub fn validate_basic(&self) -> Result<(), Error> {
 		if self.connection_hops.len() != 1 {
 			return Err(Error::invalid_connection_hops_length(1, self.connection_hops.len()))
@@ -311,6 +336,15 @@ impl Order {
 			_ => Err(Error::unknown_order_type(nr.to_string())),
 		}
 	}
+
+	/// Returns whether a channel with this ordering may be upgraded to the `other` ordering, i.e.
+	/// whether all the packet orderings allowed by this ordering are allowed by the other one.
+	pub fn is_subset_of(&self, other: &Order) -> bool {
+		match self {
+			Self::Unordered => matches!(other, Self::Unordered),
+			Self::Ordered => true,
+		}
+	}
 }
 
 impl FromStr for Order {
@@ -331,6 +365,10 @@ pub enum State {
 	TryOpen = 2,
 	Open = 3,
 	Closed = 4,
+	/// The channel accepted an upgrade and is flushing the packets sent before the upgrade.
+	Flushing = 5,
+	/// The channel flushed all the packets sent before the upgrade.
+	FlushComplete = 6,
 }
 
 impl State {
@@ -341,6 +379,8 @@ impl State {
 			Self::TryOpen => "TRYOPEN",
 			Self::Open => "OPEN",
 			Self::Closed => "CLOSED",
+			Self::Flushing => "FLUSHING",
+			Self::FlushComplete => "FLUSHCOMPLETE",
 		}
 	}
 
@@ -351,6 +391,8 @@ impl State {
 			2 => Ok(Self::TryOpen),
 			3 => Ok(Self::Open),
 			4 => Ok(Self::Closed),
+			5 => Ok(Self::Flushing),
+			6 => Ok(Self::FlushComplete),
 			_ => Err(Error::unknown_state(s)),
 		}
 	}
@@ -360,6 +402,12 @@ impl State {
 		self == State::Open
 	}
 
+	/// Returns whether or not the channel is in the middle of an upgrade, i.e. it is either
+	/// `Flushing` or `FlushComplete`.
+	pub fn is_upgrading(self) -> bool {
+		matches!(self, State::Flushing | State::FlushComplete)
+	}
+
 	/// Returns whether or not the channel with this state
 	/// has progressed less or the same than the argument.
 	///
@@ -421,6 +469,7 @@ pub mod test_util {
 			counterparty: Some(get_dummy_raw_counterparty()),
 			connection_hops: vec![ConnectionId::default().to_string()],
 			version: "ics20".to_string(), // The version is not validated.
+			upgrade_sequence: 0,
 		}
 	}
 }
@@ -26,6 +26,7 @@ use crate::{
 			handler::{recv_packet::RecvPacketResult, ChannelIdState, ChannelResult},
 			msgs::acknowledgement::Acknowledgement,
 			packet::Receipt,
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
@@ -109,6 +110,34 @@ pub trait ChannelReader {
 	/// Returns the maximum expected time per block
 	fn max_expected_time_per_block(&self) -> Duration;
 
+	/// Returns the upgrade proposed by the channel end for the given `port_id` and `chan_id`.
+	fn channel_upgrade(&self, port_channel_id: &(PortId, ChannelId)) -> Result<Upgrade, Error>;
+
+	/// Returns the counterparty upgrade stored while the given channel is being flushed.
+	fn channel_counterparty_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, Error>;
+
+	/// Returns the error receipt of the latest aborted upgrade of the given channel.
+	fn channel_upgrade_error(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, Error>;
+
+	/// Returns the sequences of the packets sent on the given channel that still have a packet
+	/// commitment stored, i.e. which have been neither acknowledged nor timed out.
+	fn inflight_packets(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<Sequence>, Error>;
+
+	/// Returns the time, relative to the host timestamp, the counterparty has to complete a
+	/// channel upgrade before it can be timed out.
+	fn upgrade_timeout(&self) -> Duration {
+		Duration::from_secs(10 * 60)
+	}
+
 	/// Calculates the block delay period using the connection's delay period and the maximum
 	/// expected time per block.
 	fn block_delay(&self, delay_period_time: Duration) -> u64 {
@@ -137,7 +166,24 @@ pub trait ChannelKeeper: ChannelReader {
 			// Initialize send, recv, and ack sequence numbers.
 			self.store_next_sequence_send((result.port_id.clone(), result.channel_id), 1.into())?;
 			self.store_next_sequence_recv((result.port_id.clone(), result.channel_id), 1.into())?;
-			self.store_next_sequence_ack((result.port_id, result.channel_id), 1.into())?;
+			self.store_next_sequence_ack((result.port_id.clone(), result.channel_id), 1.into())?;
+		}
+
+		// The handler processed an upgrade handshake message.
+		if let Some(upgrade) = result.upgrade {
+			let port_channel_id = (result.port_id, result.channel_id);
+			match upgrade.upgrade {
+				Some(upgrade) => self.store_channel_upgrade(port_channel_id.clone(), upgrade)?,
+				None => self.delete_channel_upgrade(port_channel_id.clone())?,
+			}
+			match upgrade.counterparty_upgrade {
+				Some(upgrade) =>
+					self.store_counterparty_upgrade(port_channel_id.clone(), upgrade)?,
+				None => self.delete_counterparty_upgrade(port_channel_id.clone())?,
+			}
+			if let Some(receipt) = upgrade.error_receipt {
+				self.store_upgrade_error_receipt(port_channel_id, receipt)?;
+			}
 		}
 
 		Ok(())
@@ -187,6 +233,11 @@ pub trait ChannelKeeper: ChannelReader {
 					self.store_next_sequence_ack((res.port_id.clone(), res.channel_id), s)?;
 				}
 
+				if let Some(c) = res.channel {
+					// The channel completed flushing its packets
+					self.store_channel((res.port_id.clone(), res.channel_id), &c)?;
+				}
+
 				// Delete packet commitment since packet has been aknowledged
 				self.delete_packet_commitment((res.port_id.clone(), res.channel_id, res.seq))?;
 			},
@@ -278,6 +329,37 @@ pub trait ChannelKeeper: ChannelReader {
 		seq: Sequence,
 	) -> Result<(), Error>;
 
+	/// Stores the upgrade proposed by the given channel end.
+	fn store_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), Error>;
+
+	/// Removes the upgrade of the given channel end, should not fail if there is none.
+	fn delete_channel_upgrade(&mut self, port_channel_id: (PortId, ChannelId))
+		-> Result<(), Error>;
+
+	/// Stores the counterparty upgrade of a channel that is being flushed.
+	fn store_counterparty_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), Error>;
+
+	/// Removes the counterparty upgrade of the given channel, should not fail if there is none.
+	fn delete_counterparty_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Error>;
+
+	/// Stores the error receipt of an aborted upgrade, overwriting any previous one.
+	fn store_upgrade_error_receipt(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		receipt: ErrorReceipt,
+	) -> Result<(), Error>;
+
 	/// Called upon channel identifier creation (Init or Try message processing).
 	/// Increases the counter which keeps track of how many channels have been created.
 	/// Should never fail.
@@ -17,7 +17,7 @@ use crate::{
 	core::{
 		ics02_client::error as client_error,
 		ics03_connection::error as connection_error,
-		ics04_channel::channel::State,
+		ics04_channel::channel::{Order, State},
 		ics05_port::error as port_error,
 		ics24_host::{
 			error::ValidationError,
@@ -396,6 +396,75 @@ define_error! {
 
 		NotEnoughtFundsForFee
 			| _ | { "not enough funds for fee" },
+
+		MissingUpgradeFields
+			| _ | { "missing upgrade fields" },
+
+		MissingUpgrade
+			| _ | { "missing upgrade" },
+
+		MissingErrorReceipt
+			| _ | { "missing upgrade error receipt" },
+
+		UpgradeNotFound
+			{ port_id: PortId, channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"no upgrade in progress for port {0} and channel {1}",
+					e.port_id, e.channel_id)
+			},
+
+		UpgradeFieldsUnchanged
+			| _ | { "proposed upgrade fields are identical to the current channel parameters" },
+
+		InvalidUpgradeOrdering
+			{ current: Order, proposed: Order }
+			| e | {
+				format_args!(
+					"channel ordering {0} cannot be upgraded to {1}",
+					e.current, e.proposed)
+			},
+
+		InvalidUpgradeSequence
+			{ expected: Sequence, actual: Sequence }
+			| e | {
+				format_args!(
+					"invalid upgrade sequence: expected at least {0}; actual {1}",
+					e.expected, e.actual)
+			},
+
+		IncompatibleUpgrade
+			{ reason: String }
+			| e | { format_args!("incompatible counterparty upgrade: {}", e.reason) },
+
+		UpgradeTimeoutNotReached
+			| _ | { "upgrade timeout has not been reached on the counterparty chain" },
+
+		UpgradeTimedOut
+			| _ | { "upgrade timeout has elapsed on the counterparty chain" },
+
+		CounterpartyUpgraded
+			| _ | { "counterparty channel has already completed the upgrade" },
+
+		UpgradeInProgress
+			{ channel_id: ChannelId }
+			| e | {
+				format_args!(
+					"channel {0} is being upgraded and cannot accept new packets",
+					e.channel_id)
+			},
+
+		VerifyUpgradeFailed
+			[ client_error::Error ]
+			| _ | { "Error verifying channel upgrade" },
+
+		UpgradeCallback
+			{ description: String }
+			| e | {
+				format_args!(
+					"application rejected the channel upgrade: {0}",
+					e.description)
+			},
 	}
 }
 
@@ -20,7 +20,10 @@ use tendermint::abci::{Event as AbciEvent, EventAttribute};
 use crate::{
 	core::{
 		ics02_client::height::Height,
-		ics04_channel::{error::Error, packet::Packet},
+		ics04_channel::{
+			error::Error,
+			packet::{Packet, Sequence},
+		},
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 	},
 	events::{
@@ -37,6 +40,7 @@ pub const CHANNEL_ID_ATTRIBUTE_KEY: &str = "channel_id";
 pub const PORT_ID_ATTRIBUTE_KEY: &str = "port_id";
 pub const COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY: &str = "counterparty_channel_id";
 pub const COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY: &str = "counterparty_port_id";
+pub const UPGRADE_SEQUENCE_ATTRIBUTE_KEY: &str = "upgrade_sequence";
 
 /// Packet event attribute keys
 pub const PKT_SEQ_ATTRIBUTE_KEY: &str = "packet_sequence";
@@ -81,6 +85,33 @@ pub fn try_from_tx(event: &tendermint::abci::Event) -> Option<IbcEvent> {
 			.map(|res| res.ok().map(IbcEvent::CloseConfirmChannel))
 			.ok()
 			.flatten(),
+		Ok(IbcEventType::UpgradeInitChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::UpgradeInitChannel(UpgradeInit::from(attrs)))
+			.ok(),
+		Ok(IbcEventType::UpgradeTryChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::UpgradeTryChannel(UpgradeTry::from(attrs)))
+			.ok(),
+		Ok(IbcEventType::UpgradeAckChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::UpgradeAckChannel(UpgradeAck::from(attrs)))
+			.ok(),
+		Ok(IbcEventType::UpgradeConfirmChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::UpgradeConfirmChannel(UpgradeConfirm::from(attrs)))
+			.ok(),
+		Ok(IbcEventType::UpgradeOpenChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::UpgradeOpenChannel(UpgradeOpen::from(attrs)))
+			.ok(),
+		Ok(IbcEventType::UpgradeTimeoutChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::UpgradeTimeoutChannel(UpgradeTimeout::from(attrs)))
+			.ok(),
+		Ok(IbcEventType::UpgradeCancelChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::UpgradeCancelChannel(UpgradeCancel::from(attrs)))
+			.ok(),
+		Ok(IbcEventType::UpgradeErrorChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::UpgradeErrorChannel(UpgradeError::from(attrs)))
+			.ok(),
+		Ok(IbcEventType::FlushCompleteChannel) => extract_upgrade_attributes_from_tx(event)
+			.map(|attrs| IbcEvent::FlushCompleteChannel(FlushComplete::from(attrs)))
+			.ok(),
 		Ok(IbcEventType::SendPacket) => {
 			extract_packet_and_write_ack_from_tx(event)
 				.map(|(packet, write_ack)| {
@@ -151,6 +182,34 @@ fn extract_attributes_from_tx(event: &tendermint::abci::Event) -> Result<Attribu
 	Ok(attr)
 }
 
+fn extract_upgrade_attributes_from_tx(
+	event: &tendermint::abci::Event,
+) -> Result<UpgradeAttributes, Error> {
+	let mut attr = UpgradeAttributes::default();
+
+	for tag in &event.attributes {
+		let key = tag.key.as_str();
+		let value = tag.value.as_str();
+		match key {
+			PORT_ID_ATTRIBUTE_KEY => attr.port_id = value.parse().map_err(Error::identifier)?,
+			CHANNEL_ID_ATTRIBUTE_KEY =>
+				attr.channel_id = value.parse().map_err(Error::identifier)?,
+			COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY => {
+				attr.counterparty_port_id = value.parse().map_err(Error::identifier)?;
+			},
+			COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY => {
+				attr.counterparty_channel_id = value.parse().ok();
+			},
+			UPGRADE_SEQUENCE_ATTRIBUTE_KEY => {
+				attr.upgrade_sequence = value.parse()?;
+			},
+			_ => {},
+		}
+	}
+
+	Ok(attr)
+}
+
 fn extract_packet_and_write_ack_from_tx(
 	event: &tendermint::abci::Event,
 ) -> Result<(Packet, Vec<u8>), Error> {
@@ -750,6 +809,157 @@ macro_rules! impl_try_from_raw_obj_for_event {
 
 impl_try_from_raw_obj_for_event!(OpenInit, OpenTry, OpenAck, OpenConfirm, CloseInit, CloseConfirm);
 
+/// Attributes shared by the events of the channel upgrade handshake.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UpgradeAttributes {
+	pub height: Height,
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub counterparty_port_id: PortId,
+	pub counterparty_channel_id: Option<ChannelId>,
+	pub upgrade_sequence: Sequence,
+}
+
+/// Convert upgrade attributes to Tendermint ABCI tags
+impl From<UpgradeAttributes> for Vec<EventAttribute> {
+	fn from(a: UpgradeAttributes) -> Self {
+		let mut attributes = vec![
+			EventAttribute {
+				key: HEIGHT_ATTRIBUTE_KEY.to_string(),
+				value: a.height.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: PORT_ID_ATTRIBUTE_KEY.to_string(),
+				value: a.port_id.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: CHANNEL_ID_ATTRIBUTE_KEY.to_string(),
+				value: a.channel_id.to_string(),
+				index: false,
+			},
+			EventAttribute {
+				key: COUNTERPARTY_PORT_ID_ATTRIBUTE_KEY.to_string(),
+				value: a.counterparty_port_id.to_string(),
+				index: false,
+			},
+		];
+		if let Some(channel_id) = a.counterparty_channel_id {
+			attributes.push(EventAttribute {
+				key: COUNTERPARTY_CHANNEL_ID_ATTRIBUTE_KEY.to_string(),
+				value: channel_id.to_string(),
+				index: false,
+			});
+		}
+		attributes.push(EventAttribute {
+			key: UPGRADE_SEQUENCE_ATTRIBUTE_KEY.to_string(),
+			value: a.upgrade_sequence.to_string(),
+			index: false,
+		});
+		attributes
+	}
+}
+
+macro_rules! define_upgrade_events {
+    ($($(#[$doc:meta])* $event:ident => $variant:ident),+ $(,)?) => {
+        $(
+            $(#[$doc])*
+            #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+            pub struct $event {
+                pub height: Height,
+                pub port_id: PortId,
+                pub channel_id: ChannelId,
+                pub counterparty_port_id: PortId,
+                pub counterparty_channel_id: Option<ChannelId>,
+                pub upgrade_sequence: Sequence,
+            }
+
+            impl $event {
+                pub fn channel_id(&self) -> &ChannelId {
+                    &self.channel_id
+                }
+                pub fn port_id(&self) -> &PortId {
+                    &self.port_id
+                }
+                pub fn height(&self) -> Height {
+                    self.height
+                }
+                pub fn set_height(&mut self, height: Height) {
+                    self.height = height;
+                }
+            }
+
+            impl From<UpgradeAttributes> for $event {
+                fn from(attrs: UpgradeAttributes) -> Self {
+                    Self {
+                        height: attrs.height,
+                        port_id: attrs.port_id,
+                        channel_id: attrs.channel_id,
+                        counterparty_port_id: attrs.counterparty_port_id,
+                        counterparty_channel_id: attrs.counterparty_channel_id,
+                        upgrade_sequence: attrs.upgrade_sequence,
+                    }
+                }
+            }
+
+            impl From<$event> for UpgradeAttributes {
+                fn from(ev: $event) -> Self {
+                    Self {
+                        height: ev.height,
+                        port_id: ev.port_id,
+                        channel_id: ev.channel_id,
+                        counterparty_port_id: ev.counterparty_port_id,
+                        counterparty_channel_id: ev.counterparty_channel_id,
+                        upgrade_sequence: ev.upgrade_sequence,
+                    }
+                }
+            }
+
+            impl From<$event> for IbcEvent {
+                fn from(v: $event) -> Self {
+                    IbcEvent::$variant(v)
+                }
+            }
+
+            impl EventType for $event {
+                fn event_type() -> IbcEventType {
+                    IbcEventType::$variant
+                }
+            }
+
+            impl From<$event> for AbciEvent {
+                fn from(v: $event) -> Self {
+                    let attributes = Vec::<EventAttribute>::from(UpgradeAttributes::from(v));
+                    let kind = <$event>::event_type().as_str().to_string();
+                    AbciEvent { kind, attributes }
+                }
+            }
+        )+
+    };
+}
+
+define_upgrade_events!(
+	/// An upgrade of the channel was initiated on this chain.
+	UpgradeInit => UpgradeInitChannel,
+	/// The channel accepted the upgrade proposed by the counterparty and started flushing.
+	UpgradeTry => UpgradeTryChannel,
+	/// The channel acknowledged the upgrade accepted by the counterparty.
+	UpgradeAck => UpgradeAckChannel,
+	/// The channel confirmed that the counterparty acknowledged the upgrade.
+	UpgradeConfirm => UpgradeConfirmChannel,
+	/// The upgrade completed and the channel is open with the upgraded parameters.
+	UpgradeOpen => UpgradeOpenChannel,
+	/// The upgrade was aborted because the counterparty didn't complete it in time.
+	UpgradeTimeout => UpgradeTimeoutChannel,
+	/// The upgrade was cancelled following an error on the counterparty.
+	UpgradeCancel => UpgradeCancelChannel,
+	/// The upgrade was aborted on this chain, an error receipt was written.
+	UpgradeError => UpgradeErrorChannel,
+	/// All the packets sent before the upgrade have been flushed.
+	FlushComplete => FlushCompleteChannel,
+);
+
 #[derive(Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct SendPacket {
 	pub height: Height,
@@ -16,12 +16,14 @@
 
 use crate::{
 	core::{
+		ics03_connection::connection::State as ConnectionState,
 		ics04_channel::{
-			channel::ChannelEnd,
+			channel::{ChannelEnd, State},
 			error::Error,
-			events::WriteAcknowledgement,
+			events::{FlushComplete, UpgradeAttributes, UpgradeError, WriteAcknowledgement},
 			msgs::{ChannelMsg, PacketMsg},
-			packet::PacketResult,
+			packet::{PacketResult, Sequence},
+			upgrade::{ErrorReceipt, Upgrade, UpgradeFields, UpgradeTimeout},
 		},
 		ics24_host::identifier::{ChannelId, PortId},
 		ics26_routing::context::{
@@ -30,8 +32,9 @@ use crate::{
 	},
 	events::IbcEvent,
 	handler::{HandlerOutput, HandlerOutputBuilder},
+	prelude::*,
+	Height,
 };
-use alloc::string::ToString;
 use core::fmt::Debug;
 
 pub mod acknowledgement;
@@ -41,6 +44,13 @@ pub mod chan_open_ack;
 pub mod chan_open_confirm;
 pub mod chan_open_init;
 pub mod chan_open_try;
+pub mod chan_upgrade_ack;
+pub mod chan_upgrade_cancel;
+pub mod chan_upgrade_confirm;
+pub mod chan_upgrade_init;
+pub mod chan_upgrade_open;
+pub mod chan_upgrade_timeout;
+pub mod chan_upgrade_try;
 pub mod recv_packet;
 pub mod send_packet;
 pub mod timeout;
@@ -65,6 +75,150 @@ pub struct ChannelResult {
 	pub channel_id: ChannelId,
 	pub channel_id_state: ChannelIdState,
 	pub channel_end: ChannelEnd,
+	/// Set by the channel upgrade handlers only.
+	pub upgrade: Option<UpgradeResult>,
+}
+
+/// The upgrade state written alongside the channel end by the channel upgrade handlers.
+#[derive(Clone, Debug)]
+pub struct UpgradeResult {
+	/// The upgrade proposed on this end, `None` once the upgrade completed or was aborted.
+	pub upgrade: Option<Upgrade>,
+	/// The upgrade proposed by the counterparty, once it is known.
+	pub counterparty_upgrade: Option<Upgrade>,
+	/// Written when the upgrade is aborted, so that the counterparty can cancel its side.
+	pub error_receipt: Option<ErrorReceipt>,
+}
+
+/// Returns the attributes of the channel upgrade events emitted for the given channel end.
+pub(crate) fn upgrade_attributes<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	port_id: &PortId,
+	channel_id: ChannelId,
+	channel_end: &ChannelEnd,
+) -> UpgradeAttributes {
+	UpgradeAttributes {
+		height: ctx.host_height(),
+		port_id: port_id.clone(),
+		channel_id,
+		counterparty_port_id: channel_end.counterparty().port_id().clone(),
+		counterparty_channel_id: channel_end.counterparty().channel_id().cloned(),
+		upgrade_sequence: channel_end.upgrade_sequence(),
+	}
+}
+
+/// Checks that the upgrade proposed on this end agrees with the one proposed by the counterparty.
+pub(crate) fn check_upgrade_compatibility<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	fields: &UpgradeFields,
+	counterparty_fields: &UpgradeFields,
+) -> Result<(), Error> {
+	if fields.ordering != counterparty_fields.ordering {
+		return Err(Error::incompatible_upgrade(format!(
+			"ordering {} does not match counterparty ordering {}",
+			fields.ordering, counterparty_fields.ordering
+		)))
+	}
+	if fields.version != counterparty_fields.version {
+		return Err(Error::incompatible_upgrade(format!(
+			"version {} does not match counterparty version {}",
+			fields.version, counterparty_fields.version
+		)))
+	}
+
+	let connection_id = &fields.connection_hops[0];
+	let connection = ctx.connection_end(connection_id).map_err(Error::ics03_connection)?;
+	if !connection.state_matches(&ConnectionState::Open) {
+		return Err(Error::connection_not_open(connection_id.clone()))
+	}
+	if connection.counterparty().connection_id() != counterparty_fields.connection_hops.first() {
+		return Err(Error::incompatible_upgrade(format!(
+			"connection {} is not the counterparty of the proposed connection {}",
+			connection_id, counterparty_fields.connection_hops[0]
+		)))
+	}
+
+	Ok(())
+}
+
+/// Moves the channel to `FLUSHING`, giving the counterparty until the upgrade timeout of the host
+/// to complete its side of the upgrade.
+pub(crate) fn start_flushing<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	port_channel_id: &(PortId, ChannelId),
+	channel_end: &mut ChannelEnd,
+	upgrade: &mut Upgrade,
+) -> Result<(), Error> {
+	let timeout_timestamp = (ctx.host_timestamp() + ctx.upgrade_timeout())
+		.map_err(|e| Error::implementation_specific(e.to_string()))?;
+
+	channel_end.set_state(State::Flushing);
+	upgrade.timeout = UpgradeTimeout::new(Height::zero(), timeout_timestamp);
+	upgrade.next_sequence_send = ctx.get_next_sequence_send(port_channel_id)?;
+
+	Ok(())
+}
+
+/// Returns the channel end moved to `FLUSHCOMPLETE` if the packet with the given sequence is the
+/// last in-flight packet of a flushing channel, emitting the matching event.
+pub(crate) fn complete_flush<Ctx: ReaderContext, T>(
+	ctx: &Ctx,
+	output: &mut HandlerOutputBuilder<T>,
+	port_channel_id: &(PortId, ChannelId),
+	channel_end: &ChannelEnd,
+	sequence: Sequence,
+) -> Result<Option<ChannelEnd>, Error> {
+	if !channel_end.state_matches(&State::Flushing) {
+		return Ok(None)
+	}
+	let inflight = ctx.inflight_packets(port_channel_id)?;
+	if inflight.iter().any(|seq| *seq != sequence) {
+		return Ok(None)
+	}
+
+	let mut channel_end = channel_end.clone();
+	channel_end.set_state(State::FlushComplete);
+	output.emit(IbcEvent::FlushCompleteChannel(FlushComplete::from(upgrade_attributes(
+		ctx,
+		&port_channel_id.0,
+		port_channel_id.1,
+		&channel_end,
+	))));
+	Ok(Some(channel_end))
+}
+
+/// Aborts the upgrade in progress on a channel: the channel goes back to `OPEN` with its current
+/// parameters and an error receipt is written, so that the counterparty can cancel the upgrade.
+pub(crate) fn abort_upgrade<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	output: &mut HandlerOutputBuilder<ChannelResult>,
+	port_id: &PortId,
+	channel_id: ChannelId,
+	mut channel_end: ChannelEnd,
+	reason: Error,
+) -> ChannelResult {
+	output.log(format!("channel upgrade aborted: {}", reason));
+
+	channel_end.set_state(State::Open);
+	let error_receipt = ErrorReceipt::new(channel_end.upgrade_sequence(), reason);
+	output.emit(IbcEvent::UpgradeErrorChannel(UpgradeError::from(upgrade_attributes(
+		ctx,
+		port_id,
+		channel_id,
+		&channel_end,
+	))));
+
+	ChannelResult {
+		port_id: port_id.clone(),
+		channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult {
+			upgrade: None,
+			counterparty_upgrade: None,
+			error_receipt: Some(error_receipt),
+		}),
+	}
 }
 
 pub fn channel_validate<Ctx>(ctx: &Ctx, msg: &ChannelMsg) -> Result<ModuleId, Error>
@@ -96,6 +250,13 @@ where
 		ChannelMsg::ChannelOpenConfirm(msg) => chan_open_confirm::process::<_>(ctx, msg),
 		ChannelMsg::ChannelCloseInit(msg) => chan_close_init::process(ctx, msg),
 		ChannelMsg::ChannelCloseConfirm(msg) => chan_close_confirm::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeInit(msg) => chan_upgrade_init::process(ctx, msg),
+		ChannelMsg::ChannelUpgradeTry(msg) => chan_upgrade_try::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeAck(msg) => chan_upgrade_ack::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeConfirm(msg) => chan_upgrade_confirm::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeOpen(msg) => chan_upgrade_open::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeTimeout(msg) => chan_upgrade_timeout::process::<_>(ctx, msg),
+		ChannelMsg::ChannelUpgradeCancel(msg) => chan_upgrade_cancel::process::<_>(ctx, msg),
 	}?;
 	let HandlerOutput { result, log, events } = output;
 	let builder = HandlerOutput::builder().with_log(log).with_events(events);
@@ -172,6 +333,77 @@ where
 			&result.channel_id,
 			&msg.signer,
 		)?,
+		ChannelMsg::ChannelUpgradeInit(msg) => {
+			let version = cb.on_chan_upgrade_init(
+				&ctx_clone,
+				module_output,
+				&msg.port_id,
+				&result.channel_id,
+				msg.fields.ordering,
+				&msg.fields.connection_hops,
+				&msg.fields.version,
+			)?;
+			if let Some(upgrade) =
+				result.upgrade.as_mut().and_then(|upgrade| upgrade.upgrade.as_mut())
+			{
+				upgrade.fields.version = version;
+			}
+		},
+		ChannelMsg::ChannelUpgradeTry(msg) => {
+			// The upgrade may have been aborted by the handler, the application is not involved
+			// then.
+			if let Some(upgrade) =
+				result.upgrade.as_mut().and_then(|upgrade| upgrade.upgrade.as_mut())
+			{
+				upgrade.fields.version = cb.on_chan_upgrade_try(
+					&ctx_clone,
+					module_output,
+					&msg.port_id,
+					&result.channel_id,
+					upgrade.fields.ordering,
+					&upgrade.fields.connection_hops,
+					&msg.counterparty_upgrade_fields.version,
+				)?;
+			}
+		},
+		ChannelMsg::ChannelUpgradeAck(msg) =>
+			if result.channel_end.state().is_upgrading() {
+				cb.on_chan_upgrade_ack(
+					&ctx_clone,
+					module_output,
+					&msg.port_id,
+					&result.channel_id,
+					&msg.counterparty_upgrade.fields.version,
+				)?
+			},
+		ChannelMsg::ChannelUpgradeConfirm(msg) =>
+			if result.channel_end.is_open() &&
+				result.upgrade.as_ref().map_or(false, |upgrade| upgrade.error_receipt.is_none())
+			{
+				let channel_end = &result.channel_end;
+				cb.on_chan_upgrade_open(
+					&ctx_clone,
+					module_output,
+					&msg.port_id,
+					&result.channel_id,
+					*channel_end.ordering(),
+					channel_end.connection_hops(),
+					channel_end.version(),
+				)?
+			},
+		ChannelMsg::ChannelUpgradeOpen(msg) => {
+			let channel_end = &result.channel_end;
+			cb.on_chan_upgrade_open(
+				&ctx_clone,
+				module_output,
+				&msg.port_id,
+				&result.channel_id,
+				*channel_end.ordering(),
+				channel_end.connection_hops(),
+				channel_end.version(),
+			)?
+		},
+		ChannelMsg::ChannelUpgradeTimeout(_) | ChannelMsg::ChannelUpgradeCancel(_) => {},
 	}
 	Ok(result)
 }
@@ -237,6 +469,8 @@ where
 			let ack = cb
 				.on_recv_packet(&ctx_clone, module_output, &mut packet, &msg.signer)
 				.map_err(|e| Error::app_module(e.to_string()))?;
+			// The module will write the acknowledgement itself in a later block
+			let Some(ack) = ack else { return Ok(()) };
 			if ack.as_ref().is_empty() {
 				return Err(Error::invalid_acknowledgement())
 			}
@@ -16,10 +16,10 @@ use crate::{
 	core::{
 		ics03_connection::connection::State as ConnectionState,
 		ics04_channel::{
-			channel::{Counterparty, Order, State},
+			channel::{ChannelEnd, Counterparty, Order, State},
 			error::Error,
 			events::AcknowledgePacket,
-			handler::verify::verify_packet_acknowledgement_proofs,
+			handler::{complete_flush, verify::verify_packet_acknowledgement_proofs},
 			msgs::acknowledgement::MsgAcknowledgement,
 			packet::{PacketResult, Sequence},
 		},
@@ -38,6 +38,8 @@ pub struct AckPacketResult {
 	pub channel_id: ChannelId,
 	pub seq: Sequence,
 	pub seq_number: Option<Sequence>,
+	/// The channel end, if acknowledging the packet completed the flush of an upgrading channel.
+	pub channel: Option<ChannelEnd>,
 }
 
 pub fn process<Ctx: ReaderContext>(
@@ -51,7 +53,9 @@ pub fn process<Ctx: ReaderContext>(
 	let source_channel_end =
 		ctx.channel_end(&(packet.source_port.clone(), packet.source_channel))?;
 
-	if !source_channel_end.state_matches(&State::Open) {
+	if !source_channel_end.state_matches(&State::Open) &&
+		!source_channel_end.state_matches(&State::Flushing)
+	{
 		return Err(Error::channel_closed(packet.source_channel))
 	}
 
@@ -99,6 +103,10 @@ pub fn process<Ctx: ReaderContext>(
 		&msg.proofs,
 	)?;
 
+	let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+	let channel =
+		complete_flush(ctx, &mut output, &port_channel_id, &source_channel_end, packet.sequence)?;
+
 	let result = if source_channel_end.order_matches(&Order::Ordered) {
 		let next_seq_ack =
 			ctx.get_next_sequence_ack(&(packet.source_port.clone(), packet.source_channel))?;
@@ -112,6 +120,7 @@ pub fn process<Ctx: ReaderContext>(
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
 			seq_number: Some(next_seq_ack.increment()),
+			channel,
 		})
 	} else {
 		PacketResult::Ack(AckPacketResult {
@@ -119,6 +128,7 @@ pub fn process<Ctx: ReaderContext>(
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
 			seq_number: None,
+			channel,
 		})
 	};
 
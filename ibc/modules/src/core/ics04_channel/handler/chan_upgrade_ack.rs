@@ -0,0 +1,285 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeAck`.
+
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, State},
+			error::Error,
+			events::UpgradeAck,
+			handler::{
+				abort_upgrade, check_upgrade_compatibility, start_flushing, upgrade_attributes,
+				verify::{verify_channel_proofs, verify_channel_upgrade_proofs},
+				ChannelIdState, ChannelResult, UpgradeResult,
+			},
+			msgs::chan_upgrade_ack::MsgChannelUpgradeAck,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeAck,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	// The channel is already flushing if both ends initialised the upgrade and sent a try.
+	if !channel_end.state_matches(&State::Open) && !channel_end.state_matches(&State::Flushing) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	let mut upgrade = ctx.channel_upgrade(&port_channel_id)?;
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	// Proof verification in two steps:
+	// 1. Setup: build the channel end as we expect to find it on the other party.
+	let ccid = conn.counterparty().connection_id().ok_or_else(|| {
+		Error::undefined_connection_counterparty(channel_end.connection_hops()[0].clone())
+	})?;
+
+	let expected_channel_end = ChannelEnd::new(
+		State::Flushing,
+		*channel_end.ordering(),
+		Counterparty::new(msg.port_id.clone(), Some(msg.channel_id)),
+		vec![ccid.clone()],
+		channel_end.version().clone(),
+	)
+	.with_upgrade_sequence(channel_end.upgrade_sequence());
+
+	// 2. Verify proofs
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		msg.proofs.object_proof(),
+	)?;
+	verify_channel_upgrade_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&msg.counterparty_upgrade,
+		msg.proofs.other_proof().as_ref().ok_or_else(Error::missing_upgrade)?,
+	)?;
+
+	if channel_end.is_open() {
+		start_flushing(ctx, &port_channel_id, &mut channel_end, &mut upgrade)?;
+	}
+
+	let counterparty_upgrade = msg.counterparty_upgrade.clone();
+	let checks = check_upgrade_compatibility(ctx, &upgrade.fields, &counterparty_upgrade.fields)
+		.and_then(|_| {
+			if counterparty_upgrade
+				.timeout
+				.has_expired(ctx.host_height(), ctx.host_timestamp())
+			{
+				Err(Error::upgrade_timed_out())
+			} else {
+				Ok(())
+			}
+		});
+	if let Err(e) = checks {
+		let result = abort_upgrade(ctx, &mut output, &msg.port_id, msg.channel_id, channel_end, e);
+		return Ok(output.with_result(result))
+	}
+
+	if ctx.inflight_packets(&port_channel_id)?.is_empty() {
+		channel_end.set_state(State::FlushComplete);
+	}
+
+	output.log("success: channel upgrade ack");
+
+	output.emit(IbcEvent::UpgradeAckChannel(UpgradeAck::from(upgrade_attributes(
+		ctx,
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+	))));
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult {
+			upgrade: Some(upgrade),
+			counterparty_upgrade: Some(counterparty_upgrade),
+			error_receipt: None,
+		}),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics04_channel::{
+				channel::State,
+				handler::channel_dispatch,
+				msgs::{
+					chan_upgrade_ack::{
+						test_util::get_dummy_raw_msg_chan_upgrade_ack, MsgChannelUpgradeAck,
+					},
+					ChannelMsg,
+				},
+				upgrade::{
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context, PROOF_HEIGHT},
+					UpgradeTimeout,
+				},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+		timestamp::Timestamp,
+	};
+
+	fn upgrading_context(state: State) -> MockContext<MockClientTypes> {
+		get_dummy_upgrade_context(state).with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(),
+		)
+	}
+
+	#[test]
+	fn chan_upgrade_ack_msg_processing() {
+		struct Test {
+			name: String,
+			ctx: MockContext<MockClientTypes>,
+			want_state: State,
+		}
+
+		let msg = MsgChannelUpgradeAck::try_from(get_dummy_raw_msg_chan_upgrade_ack(PROOF_HEIGHT))
+			.unwrap();
+
+		let tests: Vec<Test> = vec![
+			Test {
+				name: "Open channel without in-flight packets completes the flush".to_string(),
+				ctx: upgrading_context(State::Open),
+				want_state: State::FlushComplete,
+			},
+			Test {
+				name: "Flushing channel without in-flight packets completes the flush".to_string(),
+				ctx: upgrading_context(State::Flushing),
+				want_state: State::FlushComplete,
+			},
+			Test {
+				name: "Open channel with in-flight packets keeps flushing".to_string(),
+				ctx: upgrading_context(State::Open).with_packet_commitment(
+					PortId::default(),
+					ChannelId::default(),
+					1.into(),
+					vec![0].into(),
+				),
+				want_state: State::Flushing,
+			},
+		];
+
+		for test in tests {
+			let (handler_output_builder, res) =
+				channel_dispatch(&test.ctx, &ChannelMsg::ChannelUpgradeAck(msg.clone()))
+					.unwrap_or_else(|e| panic!("{} failed: {}", test.name, e));
+			let handler_output = handler_output_builder.with_result(());
+
+			assert!(
+				matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeAckChannel(_)]),
+				"{}",
+				test.name
+			);
+			assert_eq!(res.channel_end.state(), &test.want_state, "{}", test.name);
+			let upgrade_result = res.upgrade.unwrap();
+			assert!(upgrade_result.upgrade.is_some(), "{}", test.name);
+			assert_eq!(
+				upgrade_result.counterparty_upgrade,
+				Some(msg.counterparty_upgrade.clone()),
+				"{}",
+				test.name
+			);
+			assert!(upgrade_result.error_receipt.is_none(), "{}", test.name);
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_ack_aborts_upgrade() {
+		let msg = MsgChannelUpgradeAck::try_from(get_dummy_raw_msg_chan_upgrade_ack(PROOF_HEIGHT))
+			.unwrap();
+
+		let context = upgrading_context(State::Open);
+
+		let mut timed_out_msg = msg.clone();
+		timed_out_msg.counterparty_upgrade.timeout =
+			UpgradeTimeout::new(context.host_height(), Timestamp::none());
+
+		let mut incompatible_msg = msg;
+		incompatible_msg.counterparty_upgrade.fields.version = Version::ics20();
+
+		for msg in [timed_out_msg, incompatible_msg] {
+			let (handler_output_builder, res) =
+				channel_dispatch(&context, &ChannelMsg::ChannelUpgradeAck(msg)).unwrap();
+			let handler_output = handler_output_builder.with_result(());
+
+			assert!(matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeErrorChannel(_)]));
+			assert_eq!(res.channel_end.state(), &State::Open);
+			let upgrade_result = res.upgrade.unwrap();
+			assert!(upgrade_result.upgrade.is_none());
+			assert_eq!(upgrade_result.error_receipt.unwrap().sequence, 1.into());
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_ack_msg_processing_failures() {
+		let msg = MsgChannelUpgradeAck::try_from(get_dummy_raw_msg_chan_upgrade_ack(PROOF_HEIGHT))
+			.unwrap();
+
+		for (name, ctx) in [
+			("no upgrade was initialised", get_dummy_upgrade_context(State::Open)),
+			("the channel has completed its flush", upgrading_context(State::FlushComplete)),
+			("the channel is closed", upgrading_context(State::Closed)),
+		] {
+			let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeAck(msg.clone()));
+			assert!(res.is_err(), "processing unexpectedly succeeded although {}", name);
+		}
+
+		let msg =
+			MsgChannelUpgradeAck::try_from(get_dummy_raw_msg_chan_upgrade_ack(PROOF_HEIGHT + 1))
+				.unwrap();
+		let res =
+			channel_dispatch(&upgrading_context(State::Open), &ChannelMsg::ChannelUpgradeAck(msg));
+		assert!(res.is_err(), "processing unexpectedly succeeded without a consensus state");
+	}
+}
@@ -0,0 +1,198 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeCancel`.
+
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::State,
+			error::Error,
+			events::UpgradeCancel,
+			handler::{
+				upgrade_attributes, verify::verify_channel_upgrade_error_proofs, ChannelIdState,
+				ChannelResult, UpgradeResult,
+			},
+			msgs::chan_upgrade_cancel::MsgChannelUpgradeCancel,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeCancel,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	// There must be an upgrade in progress to cancel.
+	ctx.channel_upgrade(&port_channel_id)?;
+
+	// The receipt must be about the current upgrade attempt, or a later one.
+	if msg.error_receipt.sequence < channel_end.upgrade_sequence() {
+		return Err(Error::invalid_upgrade_sequence(
+			channel_end.upgrade_sequence(),
+			msg.error_receipt.sequence,
+		))
+	}
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	verify_channel_upgrade_error_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&msg.error_receipt,
+		msg.proofs.object_proof(),
+	)?;
+
+	// Restore the channel, catching up with the upgrade sequence of the counterparty.
+	channel_end.set_state(State::Open);
+	channel_end.upgrade_sequence = msg.error_receipt.sequence;
+
+	output.log("success: channel upgrade cancel");
+
+	output.emit(IbcEvent::UpgradeCancelChannel(UpgradeCancel::from(upgrade_attributes(
+		ctx,
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+	))));
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult {
+			upgrade: None,
+			counterparty_upgrade: None,
+			error_receipt: None,
+		}),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics04_channel::{
+				channel::State,
+				handler::channel_dispatch,
+				msgs::{
+					chan_upgrade_cancel::{
+						test_util::get_dummy_raw_msg_chan_upgrade_cancel, MsgChannelUpgradeCancel,
+					},
+					ChannelMsg,
+				},
+				upgrade::test_util::{get_dummy_upgrade, get_dummy_upgrade_context, PROOF_HEIGHT},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+	};
+
+	fn upgrading_context(state: State) -> MockContext<MockClientTypes> {
+		get_dummy_upgrade_context(state).with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(),
+		)
+	}
+
+	fn dummy_msg() -> MsgChannelUpgradeCancel {
+		MsgChannelUpgradeCancel::try_from(get_dummy_raw_msg_chan_upgrade_cancel(PROOF_HEIGHT))
+			.unwrap()
+	}
+
+	#[test]
+	fn chan_upgrade_cancel_msg_processing() {
+		// A receipt for a later upgrade attempt moves the channel to the upgrade sequence of the
+		// counterparty.
+		let mut later_receipt_msg = dummy_msg();
+		later_receipt_msg.error_receipt.sequence = 3.into();
+
+		let tests = [
+			(upgrading_context(State::Flushing), dummy_msg(), 1),
+			(upgrading_context(State::FlushComplete), dummy_msg(), 1),
+			(upgrading_context(State::Open), later_receipt_msg, 3),
+		];
+
+		for (context, msg, want_sequence) in tests {
+			let (handler_output_builder, res) =
+				channel_dispatch(&context, &ChannelMsg::ChannelUpgradeCancel(msg)).unwrap();
+			let handler_output = handler_output_builder.with_result(());
+
+			assert!(matches!(
+				handler_output.events.as_slice(),
+				[IbcEvent::UpgradeCancelChannel(_)]
+			));
+			assert_eq!(res.channel_end.state(), &State::Open);
+			assert_eq!(res.channel_end.upgrade_sequence(), want_sequence.into());
+			// The original channel parameters are kept.
+			assert_eq!(res.channel_end.version(), &Version::ics20());
+			let upgrade_result = res.upgrade.unwrap();
+			assert!(upgrade_result.upgrade.is_none());
+			assert!(upgrade_result.error_receipt.is_none());
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_cancel_msg_processing_failures() {
+		let mut stale_receipt_msg = dummy_msg();
+		stale_receipt_msg.error_receipt.sequence = 0.into();
+
+		let missing_consensus_state_msg = MsgChannelUpgradeCancel::try_from(
+			get_dummy_raw_msg_chan_upgrade_cancel(PROOF_HEIGHT + 1),
+		)
+		.unwrap();
+
+		let tests = [
+			("no upgrade was initialised", get_dummy_upgrade_context(State::Flushing), dummy_msg()),
+			(
+				"the error receipt is for an earlier upgrade",
+				upgrading_context(State::Flushing),
+				stale_receipt_msg,
+			),
+			(
+				"there is no consensus state at the proof height",
+				upgrading_context(State::Flushing),
+				missing_consensus_state_msg,
+			),
+		];
+
+		for (name, ctx, msg) in tests {
+			let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeCancel(msg));
+			assert!(res.is_err(), "processing unexpectedly succeeded although {}", name);
+		}
+	}
+}
@@ -0,0 +1,300 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeConfirm`.
+
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, State},
+			error::Error,
+			events::{UpgradeConfirm, UpgradeOpen},
+			handler::{
+				abort_upgrade,
+				chan_upgrade_open::open_upgraded_channel,
+				upgrade_attributes,
+				verify::{verify_channel_proofs, verify_channel_upgrade_proofs},
+				ChannelIdState, ChannelResult, UpgradeResult,
+			},
+			msgs::chan_upgrade_confirm::MsgChannelUpgradeConfirm,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeConfirm,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	if !channel_end.state_matches(&State::Flushing) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+	if !msg.counterparty_channel_state.is_upgrading() {
+		return Err(Error::invalid_channel_state(msg.channel_id, msg.counterparty_channel_state))
+	}
+
+	let upgrade = ctx.channel_upgrade(&port_channel_id)?;
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	// Proof verification in two steps:
+	// 1. Setup: build the channel end as we expect to find it on the other party.
+	let ccid = conn.counterparty().connection_id().ok_or_else(|| {
+		Error::undefined_connection_counterparty(channel_end.connection_hops()[0].clone())
+	})?;
+
+	let expected_channel_end = ChannelEnd::new(
+		msg.counterparty_channel_state,
+		*channel_end.ordering(),
+		Counterparty::new(msg.port_id.clone(), Some(msg.channel_id)),
+		vec![ccid.clone()],
+		channel_end.version().clone(),
+	)
+	.with_upgrade_sequence(channel_end.upgrade_sequence());
+
+	// 2. Verify proofs
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		msg.proofs.object_proof(),
+	)?;
+	verify_channel_upgrade_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&msg.counterparty_upgrade,
+		msg.proofs.other_proof().as_ref().ok_or_else(Error::missing_upgrade)?,
+	)?;
+
+	if msg
+		.counterparty_upgrade
+		.timeout
+		.has_expired(ctx.host_height(), ctx.host_timestamp())
+	{
+		let result = abort_upgrade(
+			ctx,
+			&mut output,
+			&msg.port_id,
+			msg.channel_id,
+			channel_end,
+			Error::upgrade_timed_out(),
+		);
+		return Ok(output.with_result(result))
+	}
+
+	if ctx.inflight_packets(&port_channel_id)?.is_empty() {
+		channel_end.set_state(State::FlushComplete);
+	}
+
+	output.log("success: channel upgrade confirm");
+
+	output.emit(IbcEvent::UpgradeConfirmChannel(UpgradeConfirm::from(upgrade_attributes(
+		ctx,
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+	))));
+
+	// Both ends flushed their packets, the upgrade can complete right away.
+	let upgrade_result = if channel_end.state_matches(&State::FlushComplete) &&
+		msg.counterparty_channel_state == State::FlushComplete
+	{
+		open_upgraded_channel(&mut channel_end, &upgrade);
+		output.emit(IbcEvent::UpgradeOpenChannel(UpgradeOpen::from(upgrade_attributes(
+			ctx,
+			&msg.port_id,
+			msg.channel_id,
+			&channel_end,
+		))));
+		UpgradeResult { upgrade: None, counterparty_upgrade: None, error_receipt: None }
+	} else {
+		UpgradeResult {
+			upgrade: Some(upgrade),
+			counterparty_upgrade: Some(msg.counterparty_upgrade.clone()),
+			error_receipt: None,
+		}
+	};
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(upgrade_result),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics02_client::context::ClientReader,
+			ics04_channel::{
+				channel::State,
+				handler::channel_dispatch,
+				msgs::{
+					chan_upgrade_confirm::{
+						test_util::get_dummy_raw_msg_chan_upgrade_confirm, MsgChannelUpgradeConfirm,
+					},
+					ChannelMsg,
+				},
+				upgrade::{
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context, PROOF_HEIGHT},
+					UpgradeTimeout,
+				},
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+		timestamp::Timestamp,
+	};
+
+	fn flushing_context() -> MockContext<MockClientTypes> {
+		get_dummy_upgrade_context(State::Flushing).with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(),
+		)
+	}
+
+	fn dummy_msg() -> MsgChannelUpgradeConfirm {
+		MsgChannelUpgradeConfirm::try_from(get_dummy_raw_msg_chan_upgrade_confirm(PROOF_HEIGHT))
+			.unwrap()
+	}
+
+	#[test]
+	fn chan_upgrade_confirm_completes_flush() {
+		let context = flushing_context();
+		let (handler_output_builder, res) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeConfirm(dummy_msg())).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert!(matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeConfirmChannel(_)]));
+		assert_eq!(res.channel_end.state(), &State::FlushComplete);
+		let upgrade_result = res.upgrade.unwrap();
+		assert!(upgrade_result.upgrade.is_some());
+		assert!(upgrade_result.counterparty_upgrade.is_some());
+	}
+
+	#[test]
+	fn chan_upgrade_confirm_keeps_flushing_in_flight_packets() {
+		let context = flushing_context().with_packet_commitment(
+			PortId::default(),
+			ChannelId::default(),
+			1.into(),
+			vec![0].into(),
+		);
+
+		// The upgrade cannot complete while packets are in flight, even if the counterparty is
+		// done flushing.
+		let mut msg = dummy_msg();
+		msg.counterparty_channel_state = State::FlushComplete;
+
+		let (handler_output_builder, res) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeConfirm(msg)).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert!(matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeConfirmChannel(_)]));
+		assert_eq!(res.channel_end.state(), &State::Flushing);
+		assert!(res.upgrade.unwrap().upgrade.is_some());
+	}
+
+	#[test]
+	fn chan_upgrade_confirm_opens_upgraded_channel() {
+		let mut msg = dummy_msg();
+		msg.counterparty_channel_state = State::FlushComplete;
+
+		let context = flushing_context();
+		let (handler_output_builder, res) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeConfirm(msg)).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert!(matches!(
+			handler_output.events.as_slice(),
+			[IbcEvent::UpgradeConfirmChannel(_), IbcEvent::UpgradeOpenChannel(_)]
+		));
+		assert_eq!(res.channel_end.state(), &State::Open);
+		assert_eq!(res.channel_end.version(), &get_dummy_upgrade().fields.version);
+		let upgrade_result = res.upgrade.unwrap();
+		assert!(upgrade_result.upgrade.is_none());
+		assert!(upgrade_result.counterparty_upgrade.is_none());
+		assert!(upgrade_result.error_receipt.is_none());
+	}
+
+	#[test]
+	fn chan_upgrade_confirm_aborts_timed_out_upgrade() {
+		let context = flushing_context();
+
+		let mut msg = dummy_msg();
+		msg.counterparty_upgrade.timeout =
+			UpgradeTimeout::new(context.host_height(), Timestamp::none());
+
+		let (handler_output_builder, res) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeConfirm(msg)).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert!(matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeErrorChannel(_)]));
+		assert_eq!(res.channel_end.state(), &State::Open);
+		let upgrade_result = res.upgrade.unwrap();
+		assert!(upgrade_result.upgrade.is_none());
+		assert_eq!(upgrade_result.error_receipt.unwrap().sequence, 1.into());
+	}
+
+	#[test]
+	fn chan_upgrade_confirm_msg_processing_failures() {
+		let mut counterparty_open_msg = dummy_msg();
+		counterparty_open_msg.counterparty_channel_state = State::Open;
+
+		let tests = [
+			(
+				"the channel is not flushing",
+				get_dummy_upgrade_context(State::Open).with_channel_upgrade(
+					PortId::default(),
+					ChannelId::default(),
+					get_dummy_upgrade(),
+				),
+				dummy_msg(),
+			),
+			("no upgrade was initialised", get_dummy_upgrade_context(State::Flushing), dummy_msg()),
+			("the counterparty is not upgrading", flushing_context(), counterparty_open_msg),
+		];
+
+		for (name, ctx, msg) in tests {
+			let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeConfirm(msg));
+			assert!(res.is_err(), "processing unexpectedly succeeded although {}", name);
+		}
+	}
+}
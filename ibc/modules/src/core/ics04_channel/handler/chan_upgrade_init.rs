@@ -0,0 +1,210 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeInit`.
+
+use crate::{
+	core::{
+		ics03_connection::connection::State as ConnectionState,
+		ics04_channel::{
+			channel::State,
+			error::Error,
+			events::UpgradeInit,
+			handler::{upgrade_attributes, ChannelIdState, ChannelResult, UpgradeResult},
+			msgs::chan_upgrade_init::MsgChannelUpgradeInit,
+			upgrade::{ErrorReceipt, Upgrade, UpgradeTimeout},
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeInit,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	// Only an open channel, which is not already flushing, can be upgraded.
+	if !channel_end.is_open() {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	let fields = &msg.fields;
+	if &fields.ordering == channel_end.ordering() &&
+		&fields.connection_hops == channel_end.connection_hops() &&
+		&fields.version == channel_end.version()
+	{
+		return Err(Error::upgrade_fields_unchanged())
+	}
+
+	if !channel_end.ordering().is_subset_of(&fields.ordering) {
+		return Err(Error::invalid_upgrade_ordering(*channel_end.ordering(), fields.ordering))
+	}
+
+	let connection_id = &fields.connection_hops[0];
+	let conn = ctx.connection_end(connection_id).map_err(Error::ics03_connection)?;
+	if !conn.state_matches(&ConnectionState::Open) {
+		return Err(Error::connection_not_open(connection_id.clone()))
+	}
+
+	// A previous upgrade attempt which did not complete is superseded by the new one, the
+	// counterparty is told about it through an error receipt.
+	let error_receipt = ctx.channel_upgrade(&port_channel_id).ok().map(|_| {
+		ErrorReceipt::new(channel_end.upgrade_sequence(), "upgrade superseded by a new upgrade")
+	});
+
+	channel_end.upgrade_sequence = (u64::from(channel_end.upgrade_sequence()) + 1).into();
+
+	output.log("success: channel upgrade init");
+
+	let upgrade = Upgrade::new(fields.clone(), UpgradeTimeout::default(), Default::default());
+
+	output.emit(IbcEvent::UpgradeInitChannel(UpgradeInit::from(upgrade_attributes(
+		ctx,
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+	))));
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult {
+			upgrade: Some(upgrade),
+			counterparty_upgrade: None,
+			error_receipt,
+		}),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics04_channel::{
+				channel::{Order, State},
+				handler::channel_dispatch,
+				msgs::{
+					chan_upgrade_init::{
+						test_util::get_dummy_raw_msg_chan_upgrade_init, MsgChannelUpgradeInit,
+					},
+					ChannelMsg,
+				},
+				upgrade::test_util::{get_dummy_upgrade, get_dummy_upgrade_context},
+				Version,
+			},
+			ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		},
+		events::IbcEvent,
+		prelude::*,
+	};
+
+	#[test]
+	fn chan_upgrade_init_msg_processing() {
+		let msg = MsgChannelUpgradeInit::try_from(get_dummy_raw_msg_chan_upgrade_init()).unwrap();
+
+		let context = get_dummy_upgrade_context(State::Open);
+		let (handler_output_builder, res) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeInit(msg.clone())).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert!(matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeInitChannel(_)]));
+		assert_eq!(res.channel_end.state(), &State::Open);
+		assert_eq!(res.channel_end.upgrade_sequence(), 2.into());
+		let upgrade_result = res.upgrade.unwrap();
+		assert_eq!(upgrade_result.upgrade.unwrap().fields, msg.fields);
+		assert!(upgrade_result.error_receipt.is_none());
+	}
+
+	#[test]
+	fn chan_upgrade_init_supersedes_previous_upgrade() {
+		let msg = MsgChannelUpgradeInit::try_from(get_dummy_raw_msg_chan_upgrade_init()).unwrap();
+
+		let context = get_dummy_upgrade_context(State::Open).with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(),
+		);
+		let (_, res) = channel_dispatch(&context, &ChannelMsg::ChannelUpgradeInit(msg)).unwrap();
+
+		assert_eq!(res.channel_end.upgrade_sequence(), 2.into());
+		let error_receipt = res.upgrade.unwrap().error_receipt.unwrap();
+		assert_eq!(error_receipt.sequence, 1.into());
+	}
+
+	#[test]
+	fn chan_upgrade_init_msg_processing_failures() {
+		struct Test {
+			name: String,
+			state: State,
+			msg: MsgChannelUpgradeInit,
+		}
+
+		let msg = MsgChannelUpgradeInit::try_from(get_dummy_raw_msg_chan_upgrade_init()).unwrap();
+
+		let mut unchanged_msg = msg.clone();
+		unchanged_msg.fields.version = Version::ics20();
+
+		let mut ordered_msg = msg.clone();
+		ordered_msg.fields.ordering = Order::Ordered;
+
+		let mut unknown_connection_msg = msg.clone();
+		unknown_connection_msg.fields.connection_hops = vec![ConnectionId::new(7)];
+
+		let tests: Vec<Test> = vec![
+			Test {
+				name: "Processing fails because the channel is flushing".to_string(),
+				state: State::Flushing,
+				msg: msg.clone(),
+			},
+			Test {
+				name: "Processing fails because the channel is closed".to_string(),
+				state: State::Closed,
+				msg,
+			},
+			Test {
+				name: "Processing fails because the upgrade changes nothing".to_string(),
+				state: State::Open,
+				msg: unchanged_msg,
+			},
+			Test {
+				name: "Processing fails because an unordered channel cannot become ordered"
+					.to_string(),
+				state: State::Open,
+				msg: ordered_msg,
+			},
+			Test {
+				name: "Processing fails because the upgrade connection does not exist".to_string(),
+				state: State::Open,
+				msg: unknown_connection_msg,
+			},
+		];
+
+		for test in tests {
+			let context = get_dummy_upgrade_context(test.state);
+			let res = channel_dispatch(&context, &ChannelMsg::ChannelUpgradeInit(test.msg));
+			assert!(res.is_err(), "{} unexpectedly succeeded", test.name);
+		}
+	}
+}
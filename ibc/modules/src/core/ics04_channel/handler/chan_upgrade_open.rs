@@ -0,0 +1,243 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeOpen`.
+
+use crate::{
+	core::{
+		ics03_connection::connection::State as ConnectionState,
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, State},
+			error::Error,
+			events::UpgradeOpen,
+			handler::{
+				upgrade_attributes, verify::verify_channel_proofs, ChannelIdState, ChannelResult,
+				UpgradeResult,
+			},
+			msgs::chan_upgrade_open::MsgChannelUpgradeOpen,
+			upgrade::Upgrade,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+/// Applies the upgraded parameters to the channel end and opens it again.
+pub(crate) fn open_upgraded_channel(channel_end: &mut ChannelEnd, upgrade: &Upgrade) {
+	channel_end.ordering = upgrade.fields.ordering;
+	channel_end.connection_hops = upgrade.fields.connection_hops.clone();
+	channel_end.set_version(upgrade.fields.version.clone());
+	channel_end.set_state(State::Open);
+}
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeOpen,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	if !channel_end.state_matches(&State::FlushComplete) {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	let upgrade = ctx.channel_upgrade(&port_channel_id)?;
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	let upgrade_conn = ctx
+		.connection_end(&upgrade.fields.connection_hops[0])
+		.map_err(Error::ics03_connection)?;
+	if !upgrade_conn.state_matches(&ConnectionState::Open) {
+		return Err(Error::connection_not_open(upgrade.fields.connection_hops[0].clone()))
+	}
+
+	// Proof verification in two steps:
+	// 1. Setup: build the channel end as we expect to find it on the other party. The counterparty
+	// has either flushed its packets as well, or already opened the upgraded channel.
+	let expected_counterparty = Counterparty::new(msg.port_id.clone(), Some(msg.channel_id));
+	let expected_channel_end = match msg.counterparty_channel_state {
+		State::FlushComplete => {
+			let ccid = conn.counterparty().connection_id().ok_or_else(|| {
+				Error::undefined_connection_counterparty(channel_end.connection_hops()[0].clone())
+			})?;
+			ChannelEnd::new(
+				State::FlushComplete,
+				*channel_end.ordering(),
+				expected_counterparty,
+				vec![ccid.clone()],
+				channel_end.version().clone(),
+			)
+			.with_upgrade_sequence(channel_end.upgrade_sequence())
+		},
+		State::Open => {
+			if msg.counterparty_upgrade_sequence < channel_end.upgrade_sequence() {
+				return Err(Error::invalid_upgrade_sequence(
+					channel_end.upgrade_sequence(),
+					msg.counterparty_upgrade_sequence,
+				))
+			}
+			let ccid = upgrade_conn.counterparty().connection_id().ok_or_else(|| {
+				Error::undefined_connection_counterparty(upgrade.fields.connection_hops[0].clone())
+			})?;
+			ChannelEnd::new(
+				State::Open,
+				upgrade.fields.ordering,
+				expected_counterparty,
+				vec![ccid.clone()],
+				upgrade.fields.version.clone(),
+			)
+			.with_upgrade_sequence(msg.counterparty_upgrade_sequence)
+		},
+		state => return Err(Error::invalid_channel_state(msg.channel_id, state)),
+	};
+
+	// 2. Verify proofs
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		msg.proofs.object_proof(),
+	)?;
+
+	open_upgraded_channel(&mut channel_end, &upgrade);
+
+	output.log("success: channel upgrade open");
+
+	output.emit(IbcEvent::UpgradeOpenChannel(UpgradeOpen::from(upgrade_attributes(
+		ctx,
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+	))));
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult {
+			upgrade: None,
+			counterparty_upgrade: None,
+			error_receipt: None,
+		}),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics04_channel::{
+				channel::State,
+				handler::channel_dispatch,
+				msgs::{
+					chan_upgrade_open::{
+						test_util::get_dummy_raw_msg_chan_upgrade_open, MsgChannelUpgradeOpen,
+					},
+					ChannelMsg,
+				},
+				upgrade::test_util::{get_dummy_upgrade, get_dummy_upgrade_context, PROOF_HEIGHT},
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+	};
+
+	fn upgrading_context(state: State) -> MockContext<MockClientTypes> {
+		get_dummy_upgrade_context(state).with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(),
+		)
+	}
+
+	fn dummy_msg() -> MsgChannelUpgradeOpen {
+		MsgChannelUpgradeOpen::try_from(get_dummy_raw_msg_chan_upgrade_open(PROOF_HEIGHT)).unwrap()
+	}
+
+	#[test]
+	fn chan_upgrade_open_msg_processing() {
+		// The counterparty either completed its flush, or already opened the upgraded channel.
+		let mut counterparty_open_msg = dummy_msg();
+		counterparty_open_msg.counterparty_channel_state = State::Open;
+
+		for msg in [dummy_msg(), counterparty_open_msg] {
+			let context = upgrading_context(State::FlushComplete);
+			let (handler_output_builder, res) =
+				channel_dispatch(&context, &ChannelMsg::ChannelUpgradeOpen(msg)).unwrap();
+			let handler_output = handler_output_builder.with_result(());
+
+			assert!(matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeOpenChannel(_)]));
+			let upgrade = get_dummy_upgrade();
+			assert_eq!(res.channel_end.state(), &State::Open);
+			assert_eq!(res.channel_end.ordering(), &upgrade.fields.ordering);
+			assert_eq!(res.channel_end.connection_hops(), &upgrade.fields.connection_hops);
+			assert_eq!(res.channel_end.version(), &upgrade.fields.version);
+			let upgrade_result = res.upgrade.unwrap();
+			assert!(upgrade_result.upgrade.is_none());
+			assert!(upgrade_result.counterparty_upgrade.is_none());
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_open_msg_processing_failures() {
+		let mut counterparty_behind_msg = dummy_msg();
+		counterparty_behind_msg.counterparty_channel_state = State::Open;
+		counterparty_behind_msg.counterparty_upgrade_sequence = 0.into();
+
+		let mut counterparty_flushing_msg = dummy_msg();
+		counterparty_flushing_msg.counterparty_channel_state = State::Flushing;
+
+		let tests = [
+			("the channel is still flushing", upgrading_context(State::Flushing), dummy_msg()),
+			(
+				"no upgrade was initialised",
+				get_dummy_upgrade_context(State::FlushComplete),
+				dummy_msg(),
+			),
+			(
+				"the counterparty opened an older upgrade",
+				upgrading_context(State::FlushComplete),
+				counterparty_behind_msg,
+			),
+			(
+				"the counterparty is still flushing",
+				upgrading_context(State::FlushComplete),
+				counterparty_flushing_msg,
+			),
+		];
+
+		for (name, ctx, msg) in tests {
+			let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeOpen(msg));
+			assert!(res.is_err(), "processing unexpectedly succeeded although {}", name);
+		}
+	}
+}
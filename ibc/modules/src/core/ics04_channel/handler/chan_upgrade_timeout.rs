@@ -0,0 +1,227 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeTimeout`.
+
+use crate::{
+	core::{
+		ics02_client::client_consensus::ConsensusState,
+		ics04_channel::{
+			channel::State,
+			error::Error,
+			events::UpgradeTimeout,
+			handler::{
+				abort_upgrade, upgrade_attributes, verify::verify_channel_proofs, ChannelResult,
+			},
+			msgs::chan_upgrade_timeout::MsgChannelUpgradeTimeout,
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeTimeout,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let channel_end = ctx.channel_end(&port_channel_id)?;
+
+	if !channel_end.state().is_upgrading() {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	let upgrade = ctx.channel_upgrade(&port_channel_id)?;
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	// The timeout must have elapsed on the counterparty at the height of the proof.
+	let proof_height = msg.proofs.height();
+	let consensus_state = ctx
+		.consensus_state(conn.client_id(), proof_height)
+		.map_err(|_| Error::error_invalid_consensus_state())?;
+	if !upgrade.timeout.has_expired(proof_height, consensus_state.timestamp()) {
+		return Err(Error::upgrade_timeout_not_reached())
+	}
+
+	// The counterparty must not have completed its side of the upgrade.
+	let counterparty_channel = &msg.counterparty_channel;
+	if counterparty_channel.state_matches(&State::FlushComplete) {
+		return Err(Error::invalid_channel_state(msg.channel_id, counterparty_channel.state))
+	}
+	if counterparty_channel.is_open() &&
+		counterparty_channel.upgrade_sequence() == channel_end.upgrade_sequence()
+	{
+		return Err(Error::counterparty_upgraded())
+	}
+
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		proof_height,
+		&channel_end,
+		&conn,
+		counterparty_channel,
+		msg.proofs.object_proof(),
+	)?;
+
+	output.log("success: channel upgrade timeout");
+
+	output.emit(IbcEvent::UpgradeTimeoutChannel(UpgradeTimeout::from(upgrade_attributes(
+		ctx,
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+	))));
+
+	let result = abort_upgrade(
+		ctx,
+		&mut output,
+		&msg.port_id,
+		msg.channel_id,
+		channel_end,
+		Error::upgrade_timed_out(),
+	);
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics04_channel::{
+				channel::State,
+				handler::channel_dispatch,
+				msgs::{
+					chan_upgrade_timeout::{
+						test_util::get_dummy_raw_msg_chan_upgrade_timeout, MsgChannelUpgradeTimeout,
+					},
+					ChannelMsg,
+				},
+				upgrade::{
+					test_util::{get_dummy_upgrade, get_dummy_upgrade_context, PROOF_HEIGHT},
+					UpgradeTimeout,
+				},
+			},
+			ics24_host::identifier::{ChannelId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+		timestamp::Timestamp,
+		Height,
+	};
+
+	/// Returns a context with an upgrade which timed out on the counterparty at [`PROOF_HEIGHT`].
+	fn timed_out_context(state: State) -> MockContext<MockClientTypes> {
+		let mut upgrade = get_dummy_upgrade();
+		upgrade.timeout = UpgradeTimeout::new(Height::new(0, PROOF_HEIGHT), Timestamp::none());
+		get_dummy_upgrade_context(state).with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			upgrade,
+		)
+	}
+
+	fn dummy_msg() -> MsgChannelUpgradeTimeout {
+		MsgChannelUpgradeTimeout::try_from(get_dummy_raw_msg_chan_upgrade_timeout(PROOF_HEIGHT))
+			.unwrap()
+	}
+
+	#[test]
+	fn chan_upgrade_timeout_msg_processing() {
+		// The counterparty may still be flushing, or may have restored its channel.
+		let mut counterparty_flushing_msg = dummy_msg();
+		counterparty_flushing_msg.counterparty_channel.set_state(State::Flushing);
+
+		let tests = [
+			(timed_out_context(State::Flushing), dummy_msg()),
+			(timed_out_context(State::FlushComplete), dummy_msg()),
+			(timed_out_context(State::Flushing), counterparty_flushing_msg),
+		];
+
+		for (context, msg) in tests {
+			let (handler_output_builder, res) =
+				channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTimeout(msg)).unwrap();
+			let handler_output = handler_output_builder.with_result(());
+
+			assert!(matches!(
+				handler_output.events.as_slice(),
+				[IbcEvent::UpgradeTimeoutChannel(_), IbcEvent::UpgradeErrorChannel(_)]
+			));
+			assert_eq!(res.channel_end.state(), &State::Open);
+			let upgrade_result = res.upgrade.unwrap();
+			assert!(upgrade_result.upgrade.is_none());
+			assert!(upgrade_result.counterparty_upgrade.is_none());
+			assert_eq!(upgrade_result.error_receipt.unwrap().sequence, 1.into());
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_timeout_msg_processing_failures() {
+		let pending_context = get_dummy_upgrade_context(State::Flushing).with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(),
+		);
+
+		let mut counterparty_flushed_msg = dummy_msg();
+		counterparty_flushed_msg.counterparty_channel.set_state(State::FlushComplete);
+
+		let mut counterparty_upgraded_msg = dummy_msg();
+		counterparty_upgraded_msg.counterparty_channel.set_state(State::Open);
+		counterparty_upgraded_msg.counterparty_channel.upgrade_sequence = 1.into();
+
+		let missing_consensus_state_msg = MsgChannelUpgradeTimeout::try_from(
+			get_dummy_raw_msg_chan_upgrade_timeout(PROOF_HEIGHT + 1),
+		)
+		.unwrap();
+
+		let tests = [
+			("the channel is not upgrading", timed_out_context(State::Open), dummy_msg()),
+			("no upgrade was initialised", get_dummy_upgrade_context(State::Flushing), dummy_msg()),
+			("the upgrade timeout was not reached", pending_context, dummy_msg()),
+			(
+				"the counterparty completed its flush",
+				timed_out_context(State::Flushing),
+				counterparty_flushed_msg,
+			),
+			(
+				"the counterparty opened the upgraded channel",
+				timed_out_context(State::Flushing),
+				counterparty_upgraded_msg,
+			),
+			(
+				"there is no consensus state at the proof height",
+				timed_out_context(State::Flushing),
+				missing_consensus_state_msg,
+			),
+		];
+
+		for (name, ctx, msg) in tests {
+			let res = channel_dispatch(&ctx, &ChannelMsg::ChannelUpgradeTimeout(msg));
+			assert!(res.is_err(), "processing unexpectedly succeeded although {}", name);
+		}
+	}
+}
@@ -0,0 +1,276 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protocol logic specific to ICS4 messages of type `MsgChannelUpgradeTry`.
+
+use crate::{
+	core::{
+		ics04_channel::{
+			channel::{ChannelEnd, Counterparty, State},
+			error::Error,
+			events::UpgradeTry,
+			handler::{
+				abort_upgrade, check_upgrade_compatibility, start_flushing, upgrade_attributes,
+				verify::{verify_channel_proofs, verify_channel_upgrade_proofs},
+				ChannelIdState, ChannelResult, UpgradeResult,
+			},
+			msgs::chan_upgrade_try::MsgChannelUpgradeTry,
+			upgrade::{Upgrade, UpgradeTimeout},
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	events::IbcEvent,
+	handler::{HandlerOutput, HandlerResult},
+	prelude::*,
+};
+
+pub(crate) fn process<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	msg: &MsgChannelUpgradeTry,
+) -> HandlerResult<ChannelResult, Error> {
+	let mut output = HandlerOutput::builder();
+
+	let port_channel_id = (msg.port_id.clone(), msg.channel_id);
+	let mut channel_end = ctx.channel_end(&port_channel_id)?;
+
+	if !channel_end.is_open() {
+		return Err(Error::invalid_channel_state(msg.channel_id, channel_end.state))
+	}
+
+	// The upgrade must have been initialised on this end as well, and agree on the connection.
+	let mut upgrade = ctx.channel_upgrade(&port_channel_id)?;
+	if upgrade.fields.connection_hops != msg.proposed_upgrade_connection_hops {
+		return Err(Error::incompatible_upgrade(
+			"proposed connection hops do not match the upgrade".to_string(),
+		))
+	}
+
+	if channel_end.connection_hops().len() != 1 {
+		return Err(Error::invalid_connection_hops_length(1, channel_end.connection_hops().len()))
+	}
+
+	let conn = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+
+	// Proof verification in two steps:
+	// 1. Setup: build the channel end and the upgrade as we expect to find them on the other party.
+	let ccid = conn.counterparty().connection_id().ok_or_else(|| {
+		Error::undefined_connection_counterparty(channel_end.connection_hops()[0].clone())
+	})?;
+
+	let expected_channel_end = ChannelEnd::new(
+		State::Open,
+		*channel_end.ordering(),
+		Counterparty::new(msg.port_id.clone(), Some(msg.channel_id)),
+		vec![ccid.clone()],
+		channel_end.version().clone(),
+	)
+	.with_upgrade_sequence(msg.counterparty_upgrade_sequence);
+
+	let expected_upgrade = Upgrade::new(
+		msg.counterparty_upgrade_fields.clone(),
+		UpgradeTimeout::default(),
+		Default::default(),
+	);
+
+	// 2. Verify proofs
+	verify_channel_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_channel_end,
+		msg.proofs.object_proof(),
+	)?;
+	verify_channel_upgrade_proofs::<Ctx>(
+		ctx,
+		msg.proofs.height(),
+		&channel_end,
+		&conn,
+		&expected_upgrade,
+		msg.proofs.other_proof().as_ref().ok_or_else(Error::missing_upgrade)?,
+	)?;
+
+	// The counterparty is behind: abort, so that it can cancel its upgrade and catch up.
+	if msg.counterparty_upgrade_sequence < channel_end.upgrade_sequence() {
+		let expected = channel_end.upgrade_sequence();
+		let result = abort_upgrade(
+			ctx,
+			&mut output,
+			&msg.port_id,
+			msg.channel_id,
+			channel_end,
+			Error::invalid_upgrade_sequence(expected, msg.counterparty_upgrade_sequence),
+		);
+		return Ok(output.with_result(result))
+	}
+	channel_end.upgrade_sequence = msg.counterparty_upgrade_sequence;
+
+	if let Err(e) =
+		check_upgrade_compatibility(ctx, &upgrade.fields, &msg.counterparty_upgrade_fields)
+	{
+		let result = abort_upgrade(ctx, &mut output, &msg.port_id, msg.channel_id, channel_end, e);
+		return Ok(output.with_result(result))
+	}
+
+	start_flushing(ctx, &port_channel_id, &mut channel_end, &mut upgrade)?;
+
+	output.log("success: channel upgrade try");
+
+	output.emit(IbcEvent::UpgradeTryChannel(UpgradeTry::from(upgrade_attributes(
+		ctx,
+		&msg.port_id,
+		msg.channel_id,
+		&channel_end,
+	))));
+
+	let result = ChannelResult {
+		port_id: msg.port_id.clone(),
+		channel_id: msg.channel_id,
+		channel_id_state: ChannelIdState::Reused,
+		channel_end,
+		upgrade: Some(UpgradeResult {
+			upgrade: Some(upgrade),
+			counterparty_upgrade: None,
+			error_receipt: None,
+		}),
+	};
+
+	Ok(output.with_result(result))
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::{
+		core::{
+			ics04_channel::{
+				channel::{Order, State},
+				handler::channel_dispatch,
+				msgs::{
+					chan_upgrade_try::{
+						test_util::get_dummy_raw_msg_chan_upgrade_try, MsgChannelUpgradeTry,
+					},
+					ChannelMsg,
+				},
+				upgrade::test_util::{get_dummy_upgrade, get_dummy_upgrade_context, PROOF_HEIGHT},
+			},
+			ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		},
+		events::IbcEvent,
+		mock::context::{MockClientTypes, MockContext},
+		prelude::*,
+	};
+
+	fn upgrading_context(state: State) -> MockContext<MockClientTypes> {
+		get_dummy_upgrade_context(state).with_channel_upgrade(
+			PortId::default(),
+			ChannelId::default(),
+			get_dummy_upgrade(),
+		)
+	}
+
+	#[test]
+	fn chan_upgrade_try_msg_processing() {
+		let msg = MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(PROOF_HEIGHT))
+			.unwrap();
+
+		let context = upgrading_context(State::Open);
+		let (handler_output_builder, res) =
+			channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTry(msg)).unwrap();
+		let handler_output = handler_output_builder.with_result(());
+
+		assert!(matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeTryChannel(_)]));
+		assert_eq!(res.channel_end.state(), &State::Flushing);
+		assert_eq!(res.channel_end.upgrade_sequence(), 1.into());
+		let upgrade_result = res.upgrade.unwrap();
+		assert!(upgrade_result.error_receipt.is_none());
+		let upgrade = upgrade_result.upgrade.unwrap();
+		assert_eq!(upgrade.fields, get_dummy_upgrade().fields);
+		assert_eq!(upgrade.next_sequence_send, 1.into());
+	}
+
+	#[test]
+	fn chan_upgrade_try_aborts_upgrade() {
+		let msg = MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(PROOF_HEIGHT))
+			.unwrap();
+
+		let mut behind_msg = msg.clone();
+		behind_msg.counterparty_upgrade_sequence = 0.into();
+
+		let mut incompatible_msg = msg;
+		incompatible_msg.counterparty_upgrade_fields.ordering = Order::Ordered;
+
+		for msg in [behind_msg, incompatible_msg] {
+			let context = upgrading_context(State::Open);
+			let (handler_output_builder, res) =
+				channel_dispatch(&context, &ChannelMsg::ChannelUpgradeTry(msg)).unwrap();
+			let handler_output = handler_output_builder.with_result(());
+
+			assert!(matches!(handler_output.events.as_slice(), [IbcEvent::UpgradeErrorChannel(_)]));
+			assert_eq!(res.channel_end.state(), &State::Open);
+			let upgrade_result = res.upgrade.unwrap();
+			assert!(upgrade_result.upgrade.is_none());
+			assert_eq!(upgrade_result.error_receipt.unwrap().sequence, 1.into());
+		}
+	}
+
+	#[test]
+	fn chan_upgrade_try_msg_processing_failures() {
+		struct Test {
+			name: String,
+			ctx: MockContext<MockClientTypes>,
+			msg: MsgChannelUpgradeTry,
+		}
+
+		let msg = MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(PROOF_HEIGHT))
+			.unwrap();
+
+		let mut other_hops_msg = msg.clone();
+		other_hops_msg.proposed_upgrade_connection_hops = vec![ConnectionId::new(7)];
+
+		let missing_consensus_state_msg =
+			MsgChannelUpgradeTry::try_from(get_dummy_raw_msg_chan_upgrade_try(PROOF_HEIGHT + 1))
+				.unwrap();
+
+		let tests: Vec<Test> = vec![
+			Test {
+				name: "Processing fails because no upgrade was initialised".to_string(),
+				ctx: get_dummy_upgrade_context(State::Open),
+				msg: msg.clone(),
+			},
+			Test {
+				name: "Processing fails because the channel is already flushing".to_string(),
+				ctx: upgrading_context(State::Flushing),
+				msg,
+			},
+			Test {
+				name: "Processing fails because the proposed connection hops differ".to_string(),
+				ctx: upgrading_context(State::Open),
+				msg: other_hops_msg,
+			},
+			Test {
+				name: "Processing fails because of a missing consensus state at the proof height"
+					.to_string(),
+				ctx: upgrading_context(State::Open),
+				msg: missing_consensus_state_msg,
+			},
+		];
+
+		for test in tests {
+			let res = channel_dispatch(&test.ctx, &ChannelMsg::ChannelUpgradeTry(test.msg));
+			assert!(res.is_err(), "{} unexpectedly succeeded", test.name);
+		}
+	}
+}
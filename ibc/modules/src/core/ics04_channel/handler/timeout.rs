@@ -19,7 +19,10 @@ use crate::{
 			channel::{ChannelEnd, Counterparty, Order, State},
 			error::Error,
 			events::TimeoutPacket,
-			handler::verify::{verify_next_sequence_recv, verify_packet_receipt_absence},
+			handler::{
+				complete_flush,
+				verify::{verify_next_sequence_recv, verify_packet_receipt_absence},
+			},
 			msgs::timeout::MsgTimeout,
 			packet::{PacketResult, Sequence},
 		},
@@ -51,7 +54,9 @@ where
 	let mut source_channel_end =
 		ctx.channel_end(&(packet.source_port.clone(), packet.source_channel))?;
 
-	if !source_channel_end.state_matches(&State::Open) {
+	if !source_channel_end.state_matches(&State::Open) &&
+		!source_channel_end.state_matches(&State::Flushing)
+	{
 		return Err(Error::channel_closed(packet.source_channel))
 	}
 
@@ -131,11 +136,20 @@ where
 			&msg.proofs,
 		)?;
 
+		let port_channel_id = (packet.source_port.clone(), packet.source_channel);
+		let channel = complete_flush(
+			ctx,
+			&mut output,
+			&port_channel_id,
+			&source_channel_end,
+			packet.sequence,
+		)?;
+
 		PacketResult::Timeout(TimeoutPacketResult {
 			port_id: packet.source_port.clone(),
 			channel_id: packet.source_channel,
 			seq: packet.sequence,
-			channel: None,
+			channel,
 		})
 	};
 
@@ -46,8 +46,8 @@ pub fn process<Ctx: ReaderContext>(
 	let dest_channel_end =
 		ctx.channel_end(&(packet.destination_port.clone(), packet.destination_channel))?;
 
-	if !dest_channel_end.state_matches(&State::Open) {
-		return Err(Error::invalid_channel_state(packet.source_channel, dest_channel_end.state))
+	if !dest_channel_end.state_matches(&State::Open) && !dest_channel_end.state().is_upgrading() {
+		return Err(Error::invalid_channel_state(packet.destination_channel, dest_channel_end.state))
 	}
 
 	// NOTE: IBC app modules might have written the acknowledgement synchronously on
@@ -69,8 +69,8 @@ pub fn process<Ctx: ReaderContext>(
 	}
 
 	let result = PacketResult::WriteAck(WriteAckPacketResult {
-		port_id: packet.source_port.clone(),
-		channel_id: packet.source_channel,
+		port_id: packet.destination_port.clone(),
+		channel_id: packet.destination_channel,
 		seq: packet.sequence,
 		ack: ack.clone(),
 	});
@@ -103,11 +103,13 @@ mod tests {
 			},
 			ics04_channel::{
 				channel::{ChannelEnd, Counterparty, Order, State},
+				context::ChannelReader,
 				handler::write_acknowledgement::process,
 				packet::{test_utils::get_dummy_raw_packet, Packet},
 				Version,
 			},
 			ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			ics26_routing::context::Ics26Context,
 		},
 		events::IbcEvent,
 		mock::context::{MockClientTypes, MockContext},
@@ -225,4 +227,33 @@ mod tests {
 			}
 		}
 	}
+
+	#[test]
+	fn write_ack_through_context_stores_ack_on_destination() {
+		let mut packet: Packet = get_dummy_raw_packet(1, 6).try_into().unwrap();
+		packet.sequence = 1.into();
+		packet.data = vec![0];
+
+		let dest_channel_end = ChannelEnd::new(
+			State::Open,
+			Order::default(),
+			Counterparty::new(packet.source_port.clone(), Some(packet.source_channel)),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		);
+
+		let mut ctx = MockContext::<MockClientTypes>::default().with_channel(
+			packet.destination_port.clone(),
+			packet.destination_channel,
+			dest_channel_end,
+		);
+
+		let output = ctx.write_acknowledgement(packet.clone(), vec![1].into()).unwrap();
+		assert!(matches!(output.events.as_slice(), [IbcEvent::WriteAcknowledgement(_)]));
+
+		let key = (packet.destination_port.clone(), packet.destination_channel, packet.sequence);
+		assert!(ctx.get_packet_acknowledgement(&key).is_ok());
+		// the acknowledgement can only be written once
+		assert!(ctx.write_acknowledgement(packet, vec![1].into()).is_err());
+	}
 }
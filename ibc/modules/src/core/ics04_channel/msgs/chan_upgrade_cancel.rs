@@ -0,0 +1,159 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::channel::v1::MsgChannelUpgradeCancel as RawMsgChannelUpgradeCancel;
+
+use crate::{
+	core::{
+		ics04_channel::{error::Error, upgrade::ErrorReceipt},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	proofs::Proofs,
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgChannelUpgradeCancel";
+
+///
+/// Message definition for cancelling a channel upgrade after the counterparty aborted it
+/// (`ChanUpgradeCancel` datagram). The object proof is the proof of the counterparty error receipt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgChannelUpgradeCancel {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub error_receipt: ErrorReceipt,
+	pub proofs: Proofs,
+	pub signer: Signer,
+}
+
+impl Msg for MsgChannelUpgradeCancel {
+	type ValidationError = Error;
+	type Raw = RawMsgChannelUpgradeCancel;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgChannelUpgradeCancel> for MsgChannelUpgradeCancel {}
+
+impl TryFrom<RawMsgChannelUpgradeCancel> for MsgChannelUpgradeCancel {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgChannelUpgradeCancel) -> Result<Self, Self::Error> {
+		let proofs = Proofs::new(
+			raw_msg.proof_error_receipt.try_into().map_err(Error::invalid_proof)?,
+			None,
+			None,
+			None,
+			raw_msg.proof_height.ok_or_else(Error::missing_height)?.into(),
+		)
+		.map_err(Error::invalid_proof)?;
+
+		Ok(MsgChannelUpgradeCancel {
+			port_id: raw_msg.port_id.parse().map_err(Error::identifier)?,
+			channel_id: raw_msg.channel_id.parse().map_err(Error::identifier)?,
+			error_receipt: raw_msg
+				.error_receipt
+				.ok_or_else(Error::missing_error_receipt)?
+				.try_into()?,
+			proofs,
+			signer: raw_msg.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgChannelUpgradeCancel> for RawMsgChannelUpgradeCancel {
+	fn from(domain_msg: MsgChannelUpgradeCancel) -> Self {
+		RawMsgChannelUpgradeCancel {
+			port_id: domain_msg.port_id.to_string(),
+			channel_id: domain_msg.channel_id.to_string(),
+			error_receipt: Some(domain_msg.error_receipt.into()),
+			proof_error_receipt: domain_msg.proofs.object_proof().clone().into(),
+			proof_height: Some(domain_msg.proofs.height().into()),
+			signer: domain_msg.signer.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use crate::prelude::*;
+	use ibc_proto::ibc::core::{
+		channel::v1::{
+			ErrorReceipt as RawErrorReceipt, MsgChannelUpgradeCancel as RawMsgChannelUpgradeCancel,
+		},
+		client::v1::Height,
+	};
+
+	use crate::{
+		core::ics24_host::identifier::{ChannelId, PortId},
+		test_utils::{get_dummy_bech32_account, get_dummy_proof},
+	};
+
+	/// Returns a dummy `RawMsgChannelUpgradeCancel`, for testing only!
+	pub fn get_dummy_raw_msg_chan_upgrade_cancel(proof_height: u64) -> RawMsgChannelUpgradeCancel {
+		RawMsgChannelUpgradeCancel {
+			port_id: PortId::default().to_string(),
+			channel_id: ChannelId::default().to_string(),
+			error_receipt: Some(RawErrorReceipt {
+				sequence: 1,
+				message: "upgrade aborted".to_string(),
+			}),
+			proof_error_receipt: get_dummy_proof(),
+			proof_height: Some(Height { revision_number: 0, revision_height: proof_height }),
+			signer: get_dummy_bech32_account(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use ibc_proto::ibc::core::channel::v1::MsgChannelUpgradeCancel as RawMsgChannelUpgradeCancel;
+	use test_log::test;
+
+	use crate::core::ics04_channel::msgs::chan_upgrade_cancel::{
+		test_util::get_dummy_raw_msg_chan_upgrade_cancel, MsgChannelUpgradeCancel,
+	};
+
+	#[test]
+	fn parse_channel_upgrade_cancel_msg() {
+		let raw = get_dummy_raw_msg_chan_upgrade_cancel(10);
+		assert!(MsgChannelUpgradeCancel::try_from(raw.clone()).is_ok());
+
+		let no_receipt = RawMsgChannelUpgradeCancel { error_receipt: None, ..raw.clone() };
+		assert!(MsgChannelUpgradeCancel::try_from(no_receipt).is_err());
+	}
+
+	#[test]
+	fn to_and_from() {
+		let raw = get_dummy_raw_msg_chan_upgrade_cancel(10);
+		let msg = MsgChannelUpgradeCancel::try_from(raw.clone()).unwrap();
+		let raw_back = RawMsgChannelUpgradeCancel::from(msg.clone());
+		let msg_back = MsgChannelUpgradeCancel::try_from(raw_back.clone()).unwrap();
+		assert_eq!(raw, raw_back);
+		assert_eq!(msg, msg_back);
+	}
+}
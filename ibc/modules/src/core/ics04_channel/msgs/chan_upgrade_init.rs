@@ -0,0 +1,162 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::prelude::*;
+
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::channel::v1::MsgChannelUpgradeInit as RawMsgChannelUpgradeInit;
+
+use crate::{
+	core::{
+		ics04_channel::{error::Error, upgrade::UpgradeFields},
+		ics24_host::identifier::{ChannelId, PortId},
+	},
+	signer::Signer,
+	tx_msg::Msg,
+};
+
+pub const TYPE_URL: &str = "/ibc.core.channel.v1.MsgChannelUpgradeInit";
+
+///
+/// Message definition for the first step in the channel upgrade handshake (`ChanUpgradeInit`
+/// datagram). Proposes new parameters for an open channel.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MsgChannelUpgradeInit {
+	pub port_id: PortId,
+	pub channel_id: ChannelId,
+	pub fields: UpgradeFields,
+	pub signer: Signer,
+}
+
+impl MsgChannelUpgradeInit {
+	pub fn new(
+		port_id: PortId,
+		channel_id: ChannelId,
+		fields: UpgradeFields,
+		signer: Signer,
+	) -> Self {
+		Self { port_id, channel_id, fields, signer }
+	}
+}
+
+impl Msg for MsgChannelUpgradeInit {
+	type ValidationError = Error;
+	type Raw = RawMsgChannelUpgradeInit;
+
+	fn route(&self) -> String {
+		crate::keys::ROUTER_KEY.to_string()
+	}
+
+	fn type_url(&self) -> String {
+		TYPE_URL.to_string()
+	}
+}
+
+impl Protobuf<RawMsgChannelUpgradeInit> for MsgChannelUpgradeInit {}
+
+impl TryFrom<RawMsgChannelUpgradeInit> for MsgChannelUpgradeInit {
+	type Error = Error;
+
+	fn try_from(raw_msg: RawMsgChannelUpgradeInit) -> Result<Self, Self::Error> {
+		let fields: UpgradeFields =
+			raw_msg.fields.ok_or_else(Error::missing_upgrade_fields)?.try_into()?;
+		fields.validate_basic()?;
+
+		Ok(MsgChannelUpgradeInit {
+			port_id: raw_msg.port_id.parse().map_err(Error::identifier)?,
+			channel_id: raw_msg.channel_id.parse().map_err(Error::identifier)?,
+			fields,
+			signer: raw_msg.signer.parse().map_err(Error::signer)?,
+		})
+	}
+}
+
+impl From<MsgChannelUpgradeInit> for RawMsgChannelUpgradeInit {
+	fn from(domain_msg: MsgChannelUpgradeInit) -> Self {
+		RawMsgChannelUpgradeInit {
+			port_id: domain_msg.port_id.to_string(),
+			channel_id: domain_msg.channel_id.to_string(),
+			fields: Some(domain_msg.fields.into()),
+			signer: domain_msg.signer.to_string(),
+		}
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use crate::prelude::*;
+	use ibc_proto::ibc::core::channel::v1::{
+		MsgChannelUpgradeInit as RawMsgChannelUpgradeInit, UpgradeFields as RawUpgradeFields,
+	};
+
+	use crate::{
+		core::ics24_host::identifier::{ChannelId, ConnectionId, PortId},
+		test_utils::get_dummy_bech32_account,
+	};
+
+	/// Returns dummy `RawUpgradeFields`, for testing only!
+	pub fn get_dummy_raw_upgrade_fields() -> RawUpgradeFields {
+		RawUpgradeFields {
+			ordering: 1,
+			connection_hops: vec![ConnectionId::default().to_string()],
+			version: "ics20-2".to_string(),
+		}
+	}
+
+	/// Returns a dummy `RawMsgChannelUpgradeInit`, for testing only!
+	pub fn get_dummy_raw_msg_chan_upgrade_init() -> RawMsgChannelUpgradeInit {
+		RawMsgChannelUpgradeInit {
+			port_id: PortId::default().to_string(),
+			channel_id: ChannelId::default().to_string(),
+			fields: Some(get_dummy_raw_upgrade_fields()),
+			signer: get_dummy_bech32_account(),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::prelude::*;
+
+	use ibc_proto::ibc::core::channel::v1::MsgChannelUpgradeInit as RawMsgChannelUpgradeInit;
+	use test_log::test;
+
+	use crate::core::ics04_channel::msgs::chan_upgrade_init::{
+		test_util::get_dummy_raw_msg_chan_upgrade_init, MsgChannelUpgradeInit,
+	};
+
+	#[test]
+	fn parse_channel_upgrade_init_msg() {
+		let raw = get_dummy_raw_msg_chan_upgrade_init();
+		assert!(MsgChannelUpgradeInit::try_from(raw.clone()).is_ok());
+
+		let no_fields = RawMsgChannelUpgradeInit { fields: None, ..raw.clone() };
+		assert!(MsgChannelUpgradeInit::try_from(no_fields).is_err());
+
+		let mut no_hops = raw.clone();
+		no_hops.fields.as_mut().unwrap().connection_hops.clear();
+		assert!(MsgChannelUpgradeInit::try_from(no_hops).is_err());
+	}
+
+	#[test]
+	fn to_and_from() {
+		let raw = get_dummy_raw_msg_chan_upgrade_init();
+		let msg = MsgChannelUpgradeInit::try_from(raw.clone()).unwrap();
+		let raw_back = RawMsgChannelUpgradeInit::from(msg.clone());
+		let msg_back = MsgChannelUpgradeInit::try_from(raw_back.clone()).unwrap();
+		assert_eq!(raw, raw_back);
+		assert_eq!(msg, msg_back);
+	}
+}
@@ -0,0 +1,297 @@
+// Copyright 2022 ComposableFi
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Domain types of the ICS4 channel upgrade handshake.
+
+use crate::prelude::*;
+
+use core::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use tendermint_proto::Protobuf;
+
+use ibc_proto::ibc::core::channel::v1::{
+	ErrorReceipt as RawErrorReceipt, Timeout as RawTimeout, Upgrade as RawUpgrade,
+	UpgradeFields as RawUpgradeFields,
+};
+
+use crate::{
+	core::{
+		ics02_client::height::Height,
+		ics04_channel::{channel::Order, error::Error, packet::Sequence, Version},
+		ics24_host::identifier::ConnectionId,
+	},
+	timestamp::{Expiry, Timestamp},
+};
+
+/// The parameters of a channel that may be changed by an upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeFields {
+	pub ordering: Order,
+	pub connection_hops: Vec<ConnectionId>,
+	pub version: Version,
+}
+
+impl UpgradeFields {
+	pub fn new(ordering: Order, connection_hops: Vec<ConnectionId>, version: Version) -> Self {
+		Self { ordering, connection_hops, version }
+	}
+
+	pub fn validate_basic(&self) -> Result<(), Error> {
+		if self.connection_hops.len() != 1 {
+			return Err(Error::invalid_connection_hops_length(1, self.connection_hops.len()))
+		}
+		Ok(())
+	}
+}
+
+impl Protobuf<RawUpgradeFields> for UpgradeFields {}
+
+impl TryFrom<RawUpgradeFields> for UpgradeFields {
+	type Error = Error;
+
+	fn try_from(value: RawUpgradeFields) -> Result<Self, Self::Error> {
+		let connection_hops = value
+			.connection_hops
+			.into_iter()
+			.map(|conn_id| ConnectionId::from_str(conn_id.as_str()))
+			.collect::<Result<Vec<_>, _>>()
+			.map_err(Error::identifier)?;
+
+		Ok(Self {
+			ordering: Order::from_i32(value.ordering)?,
+			connection_hops,
+			version: value.version.into(),
+		})
+	}
+}
+
+impl From<UpgradeFields> for RawUpgradeFields {
+	fn from(value: UpgradeFields) -> Self {
+		RawUpgradeFields {
+			ordering: value.ordering as i32,
+			connection_hops: value.connection_hops.iter().map(|v| v.as_str().to_string()).collect(),
+			version: value.version.to_string(),
+		}
+	}
+}
+
+/// The deadline, on the counterparty chain, for an upgrade to complete. A zero height or timestamp
+/// disables the respective check.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeTimeout {
+	pub height: Height,
+	pub timestamp: Timestamp,
+}
+
+impl UpgradeTimeout {
+	pub fn new(height: Height, timestamp: Timestamp) -> Self {
+		Self { height, timestamp }
+	}
+
+	/// Returns true if neither the timeout height nor the timeout timestamp is set.
+	pub fn is_zero(&self) -> bool {
+		self.height.is_zero() && self.timestamp == Timestamp::none()
+	}
+
+	/// Returns whether the timeout has elapsed on a chain at the given height and timestamp.
+	pub fn has_expired(&self, height: Height, timestamp: Timestamp) -> bool {
+		(!self.height.is_zero() && self.height <= height) ||
+			(self.timestamp != Timestamp::none() &&
+				timestamp.check_expiry(&self.timestamp) == Expiry::Expired)
+	}
+}
+
+impl Protobuf<RawTimeout> for UpgradeTimeout {}
+
+impl TryFrom<RawTimeout> for UpgradeTimeout {
+	type Error = Error;
+
+	fn try_from(value: RawTimeout) -> Result<Self, Self::Error> {
+		Ok(Self {
+			height: value.height.map(Height::from).unwrap_or_else(Height::zero),
+			timestamp: Timestamp::from_nanoseconds(value.timestamp)
+				.map_err(Error::invalid_packet_timestamp)?,
+		})
+	}
+}
+
+impl From<UpgradeTimeout> for RawTimeout {
+	fn from(value: UpgradeTimeout) -> Self {
+		RawTimeout { height: Some(value.height.into()), timestamp: value.timestamp.nanoseconds() }
+	}
+}
+
+/// A proposed channel upgrade, as stored under the channel upgrade path.
+///
+/// The timeout and the next send sequence are only known once the channel starts flushing, a
+/// freshly initialised upgrade has them set to zero.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Upgrade {
+	pub fields: UpgradeFields,
+	pub timeout: UpgradeTimeout,
+	pub next_sequence_send: Sequence,
+}
+
+impl Upgrade {
+	pub fn new(
+		fields: UpgradeFields,
+		timeout: UpgradeTimeout,
+		next_sequence_send: Sequence,
+	) -> Self {
+		Self { fields, timeout, next_sequence_send }
+	}
+}
+
+impl Protobuf<RawUpgrade> for Upgrade {}
+
+impl TryFrom<RawUpgrade> for Upgrade {
+	type Error = Error;
+
+	fn try_from(value: RawUpgrade) -> Result<Self, Self::Error> {
+		Ok(Self {
+			fields: value.fields.ok_or_else(Error::missing_upgrade_fields)?.try_into()?,
+			timeout: value.timeout.map(TryInto::try_into).transpose()?.unwrap_or_default(),
+			next_sequence_send: value.next_sequence_send.into(),
+		})
+	}
+}
+
+impl From<Upgrade> for RawUpgrade {
+	fn from(value: Upgrade) -> Self {
+		RawUpgrade {
+			fields: Some(value.fields.into()),
+			timeout: Some(value.timeout.into()),
+			next_sequence_send: value.next_sequence_send.into(),
+		}
+	}
+}
+
+/// Records the reason an upgrade attempt was aborted, so that the counterparty can cancel its
+/// side of the upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorReceipt {
+	pub sequence: Sequence,
+	pub message: String,
+}
+
+impl ErrorReceipt {
+	pub fn new(sequence: Sequence, message: impl ToString) -> Self {
+		Self { sequence, message: message.to_string() }
+	}
+}
+
+impl Protobuf<RawErrorReceipt> for ErrorReceipt {}
+
+impl TryFrom<RawErrorReceipt> for ErrorReceipt {
+	type Error = Error;
+
+	fn try_from(value: RawErrorReceipt) -> Result<Self, Self::Error> {
+		Ok(Self { sequence: value.sequence.into(), message: value.message })
+	}
+}
+
+impl From<ErrorReceipt> for RawErrorReceipt {
+	fn from(value: ErrorReceipt) -> Self {
+		RawErrorReceipt { sequence: value.sequence.into(), message: value.message }
+	}
+}
+
+#[cfg(test)]
+pub mod test_util {
+	use super::*;
+	use crate::{
+		core::{
+			ics03_connection::{
+				connection::{
+					ConnectionEnd, Counterparty as ConnectionCounterparty, State as ConnectionState,
+				},
+				msgs::test_util::get_dummy_raw_counterparty,
+				version::get_compatible_versions,
+			},
+			ics04_channel::{
+				channel::{ChannelEnd, Counterparty, State},
+				msgs::chan_upgrade_ack::test_util::get_dummy_raw_upgrade,
+			},
+			ics24_host::identifier::{ChannelId, ClientId, PortId},
+		},
+		mock::{
+			client_state::MockClientState,
+			context::{MockClientTypes, MockContext},
+		},
+		timestamp::ZERO_DURATION,
+	};
+
+	/// Height of the consensus state the proofs of the dummy upgrade messages are verified at.
+	pub const PROOF_HEIGHT: u64 = 10;
+
+	/// Returns the upgrade of the dummy upgrade messages, moving an unordered channel from the
+	/// `ics20-1` version to `ics20-2`, which times out at height 100.
+	pub fn get_dummy_upgrade() -> Upgrade {
+		Upgrade::try_from(get_dummy_raw_upgrade()).unwrap()
+	}
+
+	/// Returns a context holding the channel the dummy upgrade messages are sent for, in `state`
+	/// and at upgrade sequence 1, over an open connection whose client has a consensus state at
+	/// [`PROOF_HEIGHT`].
+	pub fn get_dummy_upgrade_context(state: State) -> MockContext<MockClientTypes> {
+		let client_id = ClientId::new(&MockClientState::client_type(), 0).unwrap();
+		let connection_end = ConnectionEnd::new(
+			ConnectionState::Open,
+			client_id.clone(),
+			ConnectionCounterparty::try_from(get_dummy_raw_counterparty()).unwrap(),
+			get_compatible_versions(),
+			ZERO_DURATION,
+		);
+		let channel_end = ChannelEnd::new(
+			state,
+			Order::Unordered,
+			Counterparty::new(PortId::default(), Some(ChannelId::default())),
+			vec![ConnectionId::default()],
+			Version::ics20(),
+		)
+		.with_upgrade_sequence(1.into());
+
+		MockContext::default()
+			.with_client(&client_id, Height::new(0, PROOF_HEIGHT))
+			.with_connection(ConnectionId::default(), connection_end)
+			.with_channel(PortId::default(), ChannelId::default(), channel_end)
+			.with_send_sequence(PortId::default(), ChannelId::default(), 1.into())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::core::ics24_host::identifier::ConnectionId;
+
+	#[test]
+	fn upgrade_timeout_expiry() {
+		let timeout = UpgradeTimeout::new(Height::new(0, 10), Timestamp::none());
+		assert!(!timeout.has_expired(Height::new(0, 9), Timestamp::none()));
+		assert!(timeout.has_expired(Height::new(0, 10), Timestamp::none()));
+		assert!(!UpgradeTimeout::default().has_expired(Height::new(0, 100), Timestamp::none()));
+	}
+
+	#[test]
+	fn upgrade_raw_roundtrip() {
+		let upgrade = Upgrade::new(
+			UpgradeFields::new(Order::Unordered, vec![ConnectionId::default()], Version::ics20()),
+			UpgradeTimeout::new(Height::new(1, 5), Timestamp::from_nanoseconds(7).unwrap()),
+			3.into(),
+		);
+		let raw: RawUpgrade = upgrade.clone().into();
+		assert_eq!(Upgrade::try_from(raw).unwrap(), upgrade);
+	}
+}
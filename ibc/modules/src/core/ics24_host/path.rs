@@ -41,6 +41,11 @@ const UPGRADED_IBC_STATE: &str = "upgradedIBCState";
 const UPGRADED_CLIENT_STATE: &str = "upgradedClient";
 /// - The key identifying the upgraded consensus state
 const UPGRADED_CLIENT_CONSENSUS_STATE: &str = "upgradedConsState";
+/// A prefix for the paths of the channel upgrade handshake.
+const CHANNEL_UPGRADES: &str = "channelUpgrades";
+const CHANNEL_UPGRADE: &str = "upgrades";
+const CHANNEL_UPGRADE_ERROR: &str = "upgradeError";
+const CHANNEL_COUNTERPARTY_UPGRADE: &str = "counterpartyUpgrade";
 
 /// The Path enum abstracts out the different sub-paths.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, From, Display)]
@@ -59,6 +64,7 @@ pub enum Path {
 	Acks(AcksPath),
 	Receipts(ReceiptsPath),
 	Upgrade(ClientUpgradePath),
+	ChannelUpgrade(ChannelUpgradePath),
 	Outside(OutsidePath),
 }
 
@@ -150,6 +156,26 @@ pub enum ClientUpgradePath {
 	UpgradedClientConsensusState(u64),
 }
 
+/// Paths that are specific for channel upgrades.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+pub enum ChannelUpgradePath {
+	/// The upgrade proposed by this end of the channel.
+	#[display(fmt = "{}/{}/ports/{}/channels/{}", CHANNEL_UPGRADES, CHANNEL_UPGRADE, _0, _1)]
+	Upgrade(PortId, ChannelId),
+	/// The error receipt of the latest aborted upgrade attempt.
+	#[display(fmt = "{}/{}/ports/{}/channels/{}", CHANNEL_UPGRADES, CHANNEL_UPGRADE_ERROR, _0, _1)]
+	UpgradeError(PortId, ChannelId),
+	/// The upgrade of the counterparty, stored while the channel is being flushed.
+	#[display(
+		fmt = "{}/{}/ports/{}/channels/{}",
+		CHANNEL_UPGRADES,
+		CHANNEL_COUNTERPARTY_UPGRADE,
+		_0,
+		_1
+	)]
+	CounterpartyUpgrade(PortId, ChannelId),
+}
+
 /// Sub-paths which are not part of the specification, but are still
 /// useful to represent for parsing purposes.
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -195,6 +221,7 @@ impl FromStr for Path {
 			.or_else(|| parse_acks(&components))
 			.or_else(|| parse_receipts(&components))
 			.or_else(|| parse_upgrades(&components))
+			.or_else(|| parse_channel_upgrades(&components))
 			.or_else(|| parse_outside_paths(&components))
 			.ok_or_else(|| PathError::parse_failure(s.to_string()))
 	}
@@ -539,6 +566,33 @@ fn parse_upgrades(components: &[&str]) -> Option<Path> {
 	}
 }
 
+fn parse_channel_upgrades(components: &[&str]) -> Option<Path> {
+	if components.len() != 6 {
+		return None
+	}
+
+	if components[0] != CHANNEL_UPGRADES {
+		return None
+	}
+
+	let port = parse_ports(&components[2..=3]);
+	let channel = parse_channels(&components[4..=5]);
+
+	let port_id =
+		if let Some(Path::Ports(PortsPath(port_id))) = port { port_id } else { return None };
+
+	let channel_id =
+		if let Some(SubPath::Channels(channel_id)) = channel { channel_id } else { return None };
+
+	match components[1] {
+		CHANNEL_UPGRADE => Some(ChannelUpgradePath::Upgrade(port_id, channel_id).into()),
+		CHANNEL_UPGRADE_ERROR => Some(ChannelUpgradePath::UpgradeError(port_id, channel_id).into()),
+		CHANNEL_COUNTERPARTY_UPGRADE =>
+			Some(ChannelUpgradePath::CounterpartyUpgrade(port_id, channel_id).into()),
+		_ => None,
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -915,4 +969,21 @@ mod tests {
 			Path::Upgrade(ClientUpgradePath::UpgradedClientConsensusState(0)),
 		);
 	}
+
+	#[test]
+	fn channel_upgrade_paths_parse() {
+		let paths = [
+			ChannelUpgradePath::Upgrade(PortId::default(), ChannelId::default()),
+			ChannelUpgradePath::UpgradeError(PortId::default(), ChannelId::default()),
+			ChannelUpgradePath::CounterpartyUpgrade(PortId::default(), ChannelId::default()),
+		];
+
+		for path in paths {
+			assert_eq!(Path::from_str(&path.to_string()), Ok(Path::ChannelUpgrade(path)));
+		}
+		assert_eq!(
+			ChannelUpgradePath::Upgrade(PortId::default(), ChannelId::default()).to_string(),
+			"channelUpgrades/upgrades/ports/defaultPort/channels/channel-0"
+		);
+	}
 }
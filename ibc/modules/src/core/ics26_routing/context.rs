@@ -22,6 +22,7 @@ use crate::{
 			channel::{Counterparty, Order},
 			context::{ChannelKeeper, ChannelReader},
 			error::Error,
+			handler::write_acknowledgement,
 			msgs::acknowledgement::Acknowledgement as GenericAcknowledgement,
 			packet::Packet,
 			Version,
@@ -30,7 +31,7 @@ use crate::{
 		ics24_host::identifier::{ChannelId, ConnectionId, PortId},
 	},
 	events::IbcEvent,
-	handler::HandlerOutputBuilder,
+	handler::{HandlerOutput, HandlerOutputBuilder},
 	signer::Signer,
 };
 use alloc::borrow::{Borrow, Cow};
@@ -54,6 +55,22 @@ pub trait Ics26Context:
 	fn router(&self) -> &Self::Router;
 
 	fn router_mut(&mut self) -> &mut Self::Router;
+
+	/// Writes the acknowledgement of a packet whose module returned an asynchronous
+	/// acknowledgement from [`Module::on_recv_packet`], and returns the `WriteAcknowledgement`
+	/// event to be emitted by the host.
+	fn write_acknowledgement(
+		&mut self,
+		packet: Packet,
+		ack: GenericAcknowledgement,
+	) -> Result<HandlerOutput<()>, Error> {
+		let output = write_acknowledgement::process(self, packet, ack.into_bytes())?;
+		self.store_packet_result(output.result)?;
+		Ok(HandlerOutput::builder()
+			.with_log(output.log)
+			.with_events(output.events)
+			.with_result(()))
+	}
 }
 
 #[derive(Debug, PartialEq)]
@@ -180,15 +197,76 @@ pub trait Module: Send + Sync + AsAnyMut {
 		Ok(())
 	}
 
-	/// Modules can choose to write acknowledgement to storage in this callback
+	/// Called when this chain initiates an upgrade of the channel, returns the version the
+	/// application agrees to. Modules that don't override it reject all channel upgrades.
+	#[allow(clippy::too_many_arguments)]
+	fn on_chan_upgrade_init(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_version: &Version,
+	) -> Result<Version, Error> {
+		Err(Error::upgrade_callback("channel upgrades are not supported".to_string()))
+	}
+
+	/// Called when the counterparty proposed an upgrade of the channel, returns the version the
+	/// application agrees to. Modules that don't override it reject all channel upgrades.
+	#[allow(clippy::too_many_arguments)]
+	fn on_chan_upgrade_try(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_counterparty_version: &Version,
+	) -> Result<Version, Error> {
+		Err(Error::upgrade_callback("channel upgrades are not supported".to_string()))
+	}
+
+	fn on_chan_upgrade_ack(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_counterparty_version: &Version,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	/// Called once the upgraded channel is open again with the given parameters.
+	#[allow(clippy::too_many_arguments)]
+	fn on_chan_upgrade_open(
+		&mut self,
+		_ctx: &dyn ModuleCallbackContext,
+		_output: &mut ModuleOutputBuilder,
+		_port_id: &PortId,
+		_channel_id: &ChannelId,
+		_order: Order,
+		_connection_hops: &[ConnectionId],
+		_version: &Version,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	/// Modules can choose to write acknowledgement to storage in this callback.
+	/// Returning `None` signals an asynchronous acknowledgement: nothing is written when the packet
+	/// is received and the module is expected to call [`Ics26Context::write_acknowledgement`] in a
+	/// later block.
 	fn on_recv_packet(
 		&self,
 		_ctx: &dyn ModuleCallbackContext,
 		_output: &mut ModuleOutputBuilder,
 		_packet: &mut Packet,
 		_relayer: &Signer,
-	) -> Result<GenericAcknowledgement, Error> {
-		Ok(GenericAcknowledgement::from_bytes(Default::default()))
+	) -> Result<Option<GenericAcknowledgement>, Error> {
+		Ok(Some(GenericAcknowledgement::from_bytes(Default::default())))
 	}
 
 	fn on_acknowledgement_packet(
@@ -15,19 +15,24 @@
 use crate::{
 	core::{
 		ics02_client::{
+			client_def::ConsensusUpdateResult,
+			client_state::ClientState,
 			context::{ClientKeeper, ClientTypes},
-			handler::dispatch as ics2_msg_dispatcher,
+			handler::{dispatch as ics2_msg_dispatcher, update_client, ClientResult},
 		},
-		ics03_connection::handler::dispatch as ics3_msg_dispatcher,
+		ics03_connection::{handler::dispatch as ics3_msg_dispatcher, msgs::ConnectionMsg},
 		ics04_channel::{
+			channel::ChannelEnd,
 			handler::{
 				channel_callback as ics4_callback, channel_dispatch as ics4_msg_dispatcher,
 				channel_validate as ics4_validate, get_module_for_packet_msg,
 				packet_callback as ics4_packet_callback,
 				packet_dispatch as ics4_packet_msg_dispatcher, recv_packet::RecvPacketResult,
 			},
+			msgs::{ChannelMsg, PacketMsg},
 			packet::PacketResult,
 		},
+		ics24_host::identifier::ClientId,
 		ics26_routing::{
 			context::{Ics26Context, ModuleOutputBuilder, ReaderContext},
 			error::Error,
@@ -101,12 +106,17 @@ where
 		},
 
 		Ics3Msg(msg) => {
+			let verifies_proofs = !matches!(msg, ConnectionMsg::ConnectionOpenInit(_));
 			let handler_output =
 				ics3_msg_dispatcher::<_>(ctx, msg).map_err(Error::ics03_connection)?;
+			let client_id = handler_output.result.connection_end.client_id().clone();
 
 			// Apply any results to the host chain store.
 			ctx.store_connection_result(handler_output.result)
 				.map_err(Error::ics03_connection)?;
+			if verifies_proofs {
+				store_verified_proofs(ctx, &client_id)?;
+			}
 
 			HandlerOutput::builder()
 				.with_log(handler_output.log)
@@ -124,9 +134,19 @@ where
 				ics4_callback(ctx, &module_id, &msg, channel_result, &mut module_output);
 			handler_builder.merge(module_output);
 			let channel_result = cb_result.map_err(Error::ics04_channel)?;
+			let verifies_proofs = !matches!(
+				msg,
+				ChannelMsg::ChannelOpenInit(_) |
+					ChannelMsg::ChannelCloseInit(_) |
+					ChannelMsg::ChannelUpgradeInit(_)
+			);
+			let client_id = channel_client_id(ctx, &channel_result.channel_end)?;
 
 			// Apply any results to the host chain store.
 			ctx.store_channel_result(channel_result).map_err(Error::ics04_channel)?;
+			if verifies_proofs {
+				store_verified_proofs(ctx, &client_id)?;
+			}
 
 			handler_builder.with_result(())
 		},
@@ -144,9 +164,22 @@ where
 			let cb_result = ics4_packet_callback(ctx, &module_id, &msg, &mut module_output);
 			handler_builder.merge(module_output);
 			cb_result.map_err(Error::ics04_channel)?;
+			let port_channel_id = match &msg {
+				PacketMsg::RecvPacket(msg) =>
+					(msg.packet.destination_port.clone(), msg.packet.destination_channel),
+				PacketMsg::AckPacket(msg) =>
+					(msg.packet.source_port.clone(), msg.packet.source_channel),
+				PacketMsg::ToPacket(msg) =>
+					(msg.packet.source_port.clone(), msg.packet.source_channel),
+				PacketMsg::ToClosePacket(msg) =>
+					(msg.packet.source_port.clone(), msg.packet.source_channel),
+			};
+			let channel_end = ctx.channel_end(&port_channel_id).map_err(Error::ics04_channel)?;
+			let client_id = channel_client_id(ctx, &channel_end)?;
 
 			// Apply any results to the host chain store.
 			ctx.store_packet_result(packet_result).map_err(Error::ics04_channel)?;
+			store_verified_proofs(ctx, &client_id)?;
 
 			handler_builder.with_result(())
 		},
@@ -155,6 +188,40 @@ where
 	Ok(output)
 }
 
+/// Returns the identifier of the client the proofs of the messages of a channel are verified
+/// against.
+fn channel_client_id<Ctx: ReaderContext>(
+	ctx: &Ctx,
+	channel_end: &ChannelEnd,
+) -> Result<ClientId, Error> {
+	let connection_end = ctx
+		.connection_end(&channel_end.connection_hops()[0])
+		.map_err(Error::ics03_connection)?;
+	Ok(connection_end.client_id().clone())
+}
+
+/// Stores the client state of `client_id` once a message whose proofs were verified against it
+/// has been handled, along with the consensus state of the client at its new height, see
+/// [`ClientState::on_proofs_verified`].
+fn store_verified_proofs<Ctx>(ctx: &mut Ctx, client_id: &ClientId) -> Result<(), Error>
+where
+	Ctx: Ics26Context + ClientKeeper,
+{
+	let client_state = ctx.client_state(client_id).map_err(Error::ics02_client)?;
+	let Some(next_client_state) = client_state.on_proofs_verified() else { return Ok(()) };
+	let consensus_state = ctx
+		.consensus_state(client_id, client_state.latest_height())
+		.map_err(Error::ics02_client)?;
+	ctx.store_client_result(ClientResult::Update(update_client::Result {
+		client_id: client_id.clone(),
+		client_state: next_client_state,
+		consensus_state: Some(ConsensusUpdateResult::Single(consensus_state)),
+		processed_time: ctx.host_timestamp(),
+		processed_height: ctx.host_height(),
+	}))
+	.map_err(Error::ics02_client)
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::prelude::*;
@@ -15,7 +15,10 @@
 //! Implementation of a global context mock. Used in testing handlers of all IBC modules.
 use crate::prelude::*;
 
-use alloc::{collections::btree_map::BTreeMap, sync::Arc};
+use alloc::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	sync::Arc,
+};
 use core::{
 	cmp::min,
 	fmt::{Debug, Formatter},
@@ -31,6 +34,14 @@ use tracing::debug;
 #[cfg(test)]
 use crate::core::ics02_client::events::Attributes;
 use crate::{
+	applications::{
+		fee::{context::Ics29Context, error::Error as Ics29Error, packet_fee::PacketFee},
+		interchain_accounts::{
+			context::{Ics27ControllerContext, Ics27HostContext},
+			error::Error as Ics27Error,
+		},
+		transfer::PrefixedCoin,
+	},
 	core::{
 		ics02_client::{
 			client_state::{ClientState, ClientType},
@@ -48,7 +59,8 @@ use crate::{
 			context::{ChannelKeeper, ChannelReader},
 			error::Error as Ics04Error,
 			msgs::acknowledgement::Acknowledgement,
-			packet::{Receipt, Sequence},
+			packet::{Packet, Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics05_port::{
 			context::PortReader,
@@ -75,6 +87,7 @@ use crate::{
 		header::{AnyClientMessage, MockHeader},
 		host::{HostBlock, MockHostBlock},
 	},
+	signer::Signer,
 	timestamp::Timestamp,
 	Height,
 };
@@ -244,6 +257,21 @@ where
 		self
 	}
 
+	/// Associates an upgrade in progress to a channel of this context.
+	pub fn with_channel_upgrade(
+		self,
+		port_id: PortId,
+		chan_id: ChannelId,
+		upgrade: Upgrade,
+	) -> Self {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.channel_upgrades
+			.insert((port_id, chan_id), upgrade);
+		self
+	}
+
 	pub fn with_send_sequence(
 		self,
 		port_id: PortId,
@@ -542,6 +570,58 @@ pub struct MockIbcStore<C: HostBlockType> {
 
 	// Used by unordered channel
 	pub packet_receipt: BTreeMap<(PortId, ChannelId, Sequence), Receipt>,
+
+	/// Upgrades proposed by the channels of the store.
+	pub channel_upgrades: BTreeMap<(PortId, ChannelId), Upgrade>,
+
+	/// Upgrades proposed by the counterparties of the flushing channels.
+	pub counterparty_upgrades: BTreeMap<(PortId, ChannelId), Upgrade>,
+
+	/// Error receipts of the aborted channel upgrades.
+	pub upgrade_errors: BTreeMap<(PortId, ChannelId), ErrorReceipt>,
+
+	/// Interchain accounts registered by the ICS27 host, indexed by connection and controller
+	/// port.
+	pub ica_host_accounts: BTreeMap<(ConnectionId, PortId), Signer>,
+
+	/// Active ICS27 channels of the host, indexed by connection and controller port.
+	pub ica_host_active_channels: BTreeMap<(ConnectionId, PortId), ChannelId>,
+
+	/// Message types the ICS27 host executes, `*` allows all of them.
+	pub ica_allowed_messages: Vec<String>,
+
+	/// Messages executed by the ICS27 host, along with the interchain account executing them.
+	pub ica_executed_messages: Vec<(Signer, Any)>,
+
+	/// Interchain accounts registered by the ICS27 controller, indexed by connection and port.
+	pub ica_controller_accounts: BTreeMap<(ConnectionId, PortId), Signer>,
+
+	/// Active ICS27 channels of the controller, indexed by connection and port.
+	pub ica_controller_active_channels: BTreeMap<(ConnectionId, PortId), ChannelId>,
+
+	/// Channels opened with the ICS29 fee version.
+	pub fee_enabled_channels: BTreeSet<(PortId, ChannelId)>,
+
+	/// ICS29 payees, indexed by channel and relayer.
+	pub fee_payees: BTreeMap<(ChannelId, Signer), Signer>,
+
+	/// ICS29 counterparty payees, indexed by channel and relayer.
+	pub fee_counterparty_payees: BTreeMap<(ChannelId, Signer), String>,
+
+	/// ICS29 forward relayers of the packets acknowledged asynchronously.
+	pub fee_forward_relayers: BTreeMap<(PortId, ChannelId, Sequence), String>,
+
+	/// Fees escrowed for the packets sent on fee enabled channels.
+	pub packet_fees: BTreeMap<(PortId, ChannelId, Sequence), Vec<PacketFee>>,
+
+	/// Coins moved to the ICS29 escrow, along with their payer.
+	pub fee_escrowed: Vec<(Signer, PrefixedCoin)>,
+
+	/// Coins moved out of the ICS29 escrow, along with their receiver.
+	pub fee_distributed: Vec<(Signer, PrefixedCoin)>,
+
+	/// Received ICS20 packets whose tokens are forwarded, indexed by the forwarding packet.
+	pub forwarded_packets: BTreeMap<(PortId, ChannelId, Sequence), Packet>,
 }
 
 #[derive(Default)]
@@ -749,6 +829,68 @@ impl<C: HostBlockType> ChannelReader for MockContext<C> {
 	fn max_expected_time_per_block(&self) -> Duration {
 		self.block_time
 	}
+
+	fn channel_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.channel_upgrades
+			.get(port_channel_id)
+			.cloned()
+			.ok_or_else(|| {
+				Ics04Error::upgrade_not_found(port_channel_id.0.clone(), port_channel_id.1)
+			})
+	}
+
+	fn channel_counterparty_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.counterparty_upgrades
+			.get(port_channel_id)
+			.cloned()
+			.ok_or_else(|| {
+				Ics04Error::upgrade_not_found(port_channel_id.0.clone(), port_channel_id.1)
+			})
+	}
+
+	fn channel_upgrade_error(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.upgrade_errors
+			.get(port_channel_id)
+			.cloned()
+			.ok_or_else(|| {
+				Ics04Error::upgrade_not_found(port_channel_id.0.clone(), port_channel_id.1)
+			})
+	}
+
+	fn inflight_packets(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<Sequence>, Ics04Error> {
+		Ok(self
+			.ibc_store
+			.lock()
+			.unwrap()
+			.packet_commitment
+			.keys()
+			.filter(|(port_id, channel_id, _)| {
+				port_id == &port_channel_id.0 && channel_id == &port_channel_id.1
+			})
+			.map(|(_, _, seq)| *seq)
+			.collect())
+	}
 }
 
 impl<C: HostBlockType> ChannelKeeper for MockContext<C> {
@@ -881,6 +1023,57 @@ impl<C: HostBlockType> ChannelKeeper for MockContext<C> {
 	) -> Result<(), Ics04Error> {
 		Ok(())
 	}
+
+	fn store_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store.lock().unwrap().channel_upgrades.insert(port_channel_id, upgrade);
+		Ok(())
+	}
+
+	fn delete_channel_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Ics04Error> {
+		self.ibc_store.lock().unwrap().channel_upgrades.remove(&port_channel_id);
+		Ok(())
+	}
+
+	fn store_counterparty_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		upgrade: Upgrade,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.counterparty_upgrades
+			.insert(port_channel_id, upgrade);
+		Ok(())
+	}
+
+	fn delete_counterparty_upgrade(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Ics04Error> {
+		self.ibc_store.lock().unwrap().counterparty_upgrades.remove(&port_channel_id);
+		Ok(())
+	}
+
+	fn store_upgrade_error_receipt(
+		&mut self,
+		port_channel_id: (PortId, ChannelId),
+		error_receipt: ErrorReceipt,
+	) -> Result<(), Ics04Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.upgrade_errors
+			.insert(port_channel_id, error_receipt);
+		Ok(())
+	}
 }
 
 impl<C: HostBlockType> ConnectionReader for MockContext<C>
@@ -947,6 +1140,276 @@ impl<C: HostBlockType> ConnectionKeeper for MockContext<C> {
 	}
 }
 
+impl<C: HostBlockType + Default> Ics27HostContext for MockContext<C>
+where
+	C::AnyClientMessage: TryFrom<Any, Error = Ics02Error> + Into<Any> + From<C::HostBlock>,
+	C::AnyClientState: Eq + TryFrom<Any, Error = Ics02Error> + Into<Any>,
+	C::AnyConsensusState:
+		Eq + TryFrom<Any, Error = Ics02Error> + Into<Any> + From<C::HostBlock> + 'static,
+{
+	fn is_host_enabled(&self) -> bool {
+		true
+	}
+
+	fn is_message_allowed(&self, type_url: &str) -> bool {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.ica_allowed_messages
+			.iter()
+			.any(|allowed| allowed == "*" || allowed == type_url)
+	}
+
+	fn interchain_account(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<Signer> {
+		let key = (connection_id.clone(), port_id.clone());
+		self.ibc_store.lock().unwrap().ica_host_accounts.get(&key).cloned()
+	}
+
+	fn store_interchain_account(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		account: Signer,
+	) -> Result<(), Ics27Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.ica_host_accounts
+			.insert((connection_id, port_id), account);
+		Ok(())
+	}
+
+	fn active_channel(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<ChannelId> {
+		let key = (connection_id.clone(), port_id.clone());
+		self.ibc_store.lock().unwrap().ica_host_active_channels.get(&key).cloned()
+	}
+
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Ics27Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.ica_host_active_channels
+			.insert((connection_id, port_id), channel_id);
+		Ok(())
+	}
+
+	fn execute_message(&mut self, account: &Signer, message: Any) -> Result<Any, Ics27Error> {
+		let response =
+			Any { type_url: format!("{}Response", message.type_url), value: Default::default() };
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.ica_executed_messages
+			.push((account.clone(), message));
+		Ok(response)
+	}
+}
+
+impl<C: HostBlockType + Default> Ics27ControllerContext for MockContext<C>
+where
+	C::AnyClientMessage: TryFrom<Any, Error = Ics02Error> + Into<Any> + From<C::HostBlock>,
+	C::AnyClientState: Eq + TryFrom<Any, Error = Ics02Error> + Into<Any>,
+	C::AnyConsensusState:
+		Eq + TryFrom<Any, Error = Ics02Error> + Into<Any> + From<C::HostBlock> + 'static,
+{
+	fn is_controller_enabled(&self) -> bool {
+		true
+	}
+
+	fn interchain_account(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<Signer> {
+		let key = (connection_id.clone(), port_id.clone());
+		self.ibc_store.lock().unwrap().ica_controller_accounts.get(&key).cloned()
+	}
+
+	fn store_interchain_account(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		account: Signer,
+	) -> Result<(), Ics27Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.ica_controller_accounts
+			.insert((connection_id, port_id), account);
+		Ok(())
+	}
+
+	fn active_channel(&self, connection_id: &ConnectionId, port_id: &PortId) -> Option<ChannelId> {
+		let key = (connection_id.clone(), port_id.clone());
+		self.ibc_store.lock().unwrap().ica_controller_active_channels.get(&key).cloned()
+	}
+
+	fn store_active_channel(
+		&mut self,
+		connection_id: ConnectionId,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Ics27Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.ica_controller_active_channels
+			.insert((connection_id, port_id), channel_id);
+		Ok(())
+	}
+}
+
+impl<C: HostBlockType + Default> Ics29Context for MockContext<C>
+where
+	C::AnyClientMessage: TryFrom<Any, Error = Ics02Error> + Into<Any> + From<C::HostBlock>,
+	C::AnyClientState: Eq + TryFrom<Any, Error = Ics02Error> + Into<Any>,
+	C::AnyConsensusState:
+		Eq + TryFrom<Any, Error = Ics02Error> + Into<Any> + From<C::HostBlock> + 'static,
+{
+	fn is_fee_enabled(&self, port_id: &PortId, channel_id: &ChannelId) -> bool {
+		let key = (port_id.clone(), *channel_id);
+		self.ibc_store.lock().unwrap().fee_enabled_channels.contains(&key)
+	}
+
+	fn set_fee_enabled(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+	) -> Result<(), Ics29Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.fee_enabled_channels
+			.insert((port_id, channel_id));
+		Ok(())
+	}
+
+	fn payee(&self, channel_id: &ChannelId, relayer: &Signer) -> Option<Signer> {
+		let key = (*channel_id, relayer.clone());
+		self.ibc_store.lock().unwrap().fee_payees.get(&key).cloned()
+	}
+
+	fn store_payee(
+		&mut self,
+		channel_id: ChannelId,
+		relayer: Signer,
+		payee: Signer,
+	) -> Result<(), Ics29Error> {
+		self.ibc_store.lock().unwrap().fee_payees.insert((channel_id, relayer), payee);
+		Ok(())
+	}
+
+	fn counterparty_payee(&self, channel_id: &ChannelId, relayer: &Signer) -> Option<String> {
+		let key = (*channel_id, relayer.clone());
+		self.ibc_store.lock().unwrap().fee_counterparty_payees.get(&key).cloned()
+	}
+
+	fn store_counterparty_payee(
+		&mut self,
+		channel_id: ChannelId,
+		relayer: Signer,
+		counterparty_payee: String,
+	) -> Result<(), Ics29Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.fee_counterparty_payees
+			.insert((channel_id, relayer), counterparty_payee);
+		Ok(())
+	}
+
+	fn forward_relayer(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Option<String> {
+		let key = (port_id.clone(), *channel_id, sequence);
+		self.ibc_store.lock().unwrap().fee_forward_relayers.get(&key).cloned()
+	}
+
+	fn store_forward_relayer(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+		forward_relayer: Option<String>,
+	) -> Result<(), Ics29Error> {
+		let key = (port_id, channel_id, sequence);
+		let mut ibc_store = self.ibc_store.lock().unwrap();
+		match forward_relayer {
+			Some(forward_relayer) => ibc_store.fee_forward_relayers.insert(key, forward_relayer),
+			None => ibc_store.fee_forward_relayers.remove(&key),
+		};
+		Ok(())
+	}
+
+	fn packet_fees(
+		&self,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Vec<PacketFee> {
+		let key = (port_id.clone(), *channel_id, sequence);
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.packet_fees
+			.get(&key)
+			.cloned()
+			.unwrap_or_default()
+	}
+
+	fn store_packet_fees(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+		packet_fees: Vec<PacketFee>,
+	) -> Result<(), Ics29Error> {
+		let key = (port_id, channel_id, sequence);
+		let mut ibc_store = self.ibc_store.lock().unwrap();
+		if packet_fees.is_empty() {
+			ibc_store.packet_fees.remove(&key);
+		} else {
+			ibc_store.packet_fees.insert(key, packet_fees);
+		}
+		Ok(())
+	}
+
+	fn incentivized_packets(&self, port_id: &PortId, channel_id: &ChannelId) -> Vec<Sequence> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.packet_fees
+			.keys()
+			.filter(|(port, channel, _)| port == port_id && channel == channel_id)
+			.map(|(_, _, sequence)| *sequence)
+			.collect()
+	}
+
+	fn escrow_coins(&mut self, payer: &Signer, coins: &[PrefixedCoin]) -> Result<(), Ics29Error> {
+		let mut ibc_store = self.ibc_store.lock().unwrap();
+		ibc_store
+			.fee_escrowed
+			.extend(coins.iter().map(|coin| (payer.clone(), coin.clone())));
+		Ok(())
+	}
+
+	fn distribute_coins(
+		&mut self,
+		receiver: &Signer,
+		coins: &[PrefixedCoin],
+	) -> Result<(), Ics29Error> {
+		let mut ibc_store = self.ibc_store.lock().unwrap();
+		ibc_store
+			.fee_distributed
+			.extend(coins.iter().map(|coin| (receiver.clone(), coin.clone())));
+		Ok(())
+	}
+}
+
 impl<C: HostBlockType + Default> ClientReader for MockContext<C>
 where
 	C::AnyClientMessage: TryFrom<Any, Error = Ics02Error> + Into<Any> + From<C::HostBlock>,
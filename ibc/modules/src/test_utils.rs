@@ -37,7 +37,8 @@ use crate::{
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			context::{ChannelKeeper, ChannelReader},
 			error::Error,
-			packet::{Receipt, Sequence},
+			packet::{Packet, Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 			Version,
 		},
 		ics05_port::{context::PortReader, error::Error as PortError},
@@ -265,6 +266,35 @@ impl ics23::HostFunctionsProvider for Crypto {
 
 impl<C: HostBlockType> Ics20Keeper for DummyTransferModule<C> {
 	type AccountId = Signer;
+
+	fn store_forwarded_packet(
+		&mut self,
+		port_id: PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+		packet: Packet,
+	) -> Result<(), Ics20Error> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.forwarded_packets
+			.insert((port_id, channel_id, sequence), packet);
+		Ok(())
+	}
+
+	fn delete_forwarded_packet(
+		&mut self,
+		port_id: &PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics20Error> {
+		self.ibc_store.lock().unwrap().forwarded_packets.remove(&(
+			port_id.clone(),
+			channel_id,
+			sequence,
+		));
+		Ok(())
+	}
 }
 
 impl<C: HostBlockType> ChannelKeeper for DummyTransferModule<C> {
@@ -375,6 +405,44 @@ impl<C: HostBlockType> ChannelKeeper for DummyTransferModule<C> {
 	) -> Result<(), Error> {
 		Ok(())
 	}
+
+	fn store_channel_upgrade(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+		_upgrade: Upgrade,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn delete_channel_upgrade(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn store_counterparty_upgrade(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+		_upgrade: Upgrade,
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn delete_counterparty_upgrade(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+	) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn store_upgrade_error_receipt(
+		&mut self,
+		_port_channel_id: (PortId, ChannelId),
+		_receipt: ErrorReceipt,
+	) -> Result<(), Error> {
+		Ok(())
+	}
 }
 
 impl<C: HostBlockType> PortReader for DummyTransferModule<C> {
@@ -426,6 +494,30 @@ impl<C: HostBlockType> Ics20Reader for DummyTransferModule<C> {
 	fn is_receive_enabled(&self) -> bool {
 		true
 	}
+
+	fn get_forwarding_address(
+		&self,
+		port_id: &PortId,
+		channel_id: ChannelId,
+	) -> Result<Signer, Ics20Error> {
+		format!("forwarding-{}-{}", port_id, channel_id)
+			.parse()
+			.map_err(Ics20Error::signer)
+	}
+
+	fn get_forwarded_packet(
+		&self,
+		port_id: &PortId,
+		channel_id: ChannelId,
+		sequence: Sequence,
+	) -> Option<Packet> {
+		self.ibc_store
+			.lock()
+			.unwrap()
+			.forwarded_packets
+			.get(&(port_id.clone(), channel_id, sequence))
+			.cloned()
+	}
 }
 
 impl<C: HostBlockType> ConnectionReader for DummyTransferModule<C> {
@@ -512,7 +604,7 @@ impl<C: HostBlockType> ClientReader for DummyTransferModule<C> {
 	}
 
 	fn host_timestamp(&self) -> Timestamp {
-		todo!()
+		Timestamp::now()
 	}
 
 	fn client_counter(&self) -> Result<u64, Ics02Error> {
@@ -607,6 +699,41 @@ impl<C: HostBlockType> ChannelReader for DummyTransferModule<C> {
 	fn max_expected_time_per_block(&self) -> Duration {
 		unimplemented!()
 	}
+
+	fn channel_upgrade(&self, port_channel_id: &(PortId, ChannelId)) -> Result<Upgrade, Error> {
+		Err(Error::upgrade_not_found(port_channel_id.0.clone(), port_channel_id.1))
+	}
+
+	fn channel_counterparty_upgrade(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, Error> {
+		Err(Error::upgrade_not_found(port_channel_id.0.clone(), port_channel_id.1))
+	}
+
+	fn channel_upgrade_error(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, Error> {
+		Err(Error::missing_error_receipt())
+	}
+
+	fn inflight_packets(
+		&self,
+		port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<Sequence>, Error> {
+		Ok(self
+			.ibc_store
+			.lock()
+			.unwrap()
+			.packet_commitment
+			.keys()
+			.filter(|(port_id, channel_id, _)| {
+				port_id == &port_channel_id.0 && channel_id == &port_channel_id.1
+			})
+			.map(|(_, _, seq)| *seq)
+			.collect())
+	}
 }
 
 impl<C: HostBlockType> ClientTypes for DummyTransferModule<C> {
@@ -157,6 +157,11 @@ pub mod ibc {
 				include_proto!("ibc.applications.transfer.v2.rs");
 			}
 		}
+		pub mod nft_transfer {
+			pub mod v1 {
+				include_proto!("ibc.applications.nft_transfer.v1.rs");
+			}
+		}
 		pub mod interchain_accounts {
 			pub mod v1 {
 				include_proto!("ibc.applications.interchain_accounts.v1.rs");
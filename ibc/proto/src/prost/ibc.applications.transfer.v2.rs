@@ -20,3 +20,38 @@ pub struct FungibleTokenPacketData {
     #[prost(string, tag = "5")]
     pub memo: ::prost::alloc::string::String,
 }
+/// FungibleTokenPacketDataV2 defines a struct for the packet payload
+/// See FungibleTokenPacketDataV2 spec:
+/// <https://github.com/cosmos/ibc/tree/master/spec/app/ics-020-fungible-token-transfer#data-structures>
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FungibleTokenPacketDataV2 {
+    /// the tokens to be transferred
+    #[prost(message, repeated, tag = "1")]
+    pub tokens: ::prost::alloc::vec::Vec<super::v1::Token>,
+    /// the sender address
+    #[prost(string, tag = "2")]
+    pub sender: ::prost::alloc::string::String,
+    /// the recipient address on the destination chain
+    #[prost(string, tag = "3")]
+    pub receiver: ::prost::alloc::string::String,
+    /// optional memo
+    #[prost(string, tag = "4")]
+    pub memo: ::prost::alloc::string::String,
+    /// optional forwarding information
+    #[prost(message, optional, tag = "5")]
+    pub forwarding: ::core::option::Option<ForwardingPacketData>,
+}
+/// ForwardingPacketData defines a list of port ID, channel ID pairs determining the path
+/// through which a packet must be forwarded, and the destination memo string to be used in the
+/// final destination of the tokens.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ForwardingPacketData {
+    /// optional memo consumed by final destination chain
+    #[prost(string, tag = "1")]
+    pub destination_memo: ::prost::alloc::string::String,
+    /// optional intermediate path through which packet will be forwarded.
+    #[prost(message, repeated, tag = "2")]
+    pub hops: ::prost::alloc::vec::Vec<super::v1::Hop>,
+}
@@ -0,0 +1,358 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_message::ClientMessage, client_state::ClientState, consensus_state::ConsensusState,
+	error::Error, proof::merkle_path, HostFunctions,
+};
+use alloc::{format, string::ToString, vec::Vec};
+use core::marker::PhantomData;
+use ibc::{
+	core::{
+		ics02_client::{
+			client_consensus::ConsensusState as _,
+			client_def::{ClientDef, ConsensusUpdateResult},
+			client_state::ClientState as _,
+			error::Error as Ics02Error,
+		},
+		ics03_connection::connection::ConnectionEnd,
+		ics04_channel::{
+			channel::ChannelEnd,
+			commitment::{AcknowledgementCommitment, PacketCommitment},
+			packet::Sequence,
+		},
+		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath,
+				CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+			},
+		},
+		ics26_routing::context::ReaderContext,
+	},
+	Height,
+};
+use tendermint_proto::Protobuf;
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct SoloMachineClient<T>(PhantomData<T>);
+
+impl<H> ClientDef for SoloMachineClient<H>
+where
+	H: HostFunctions,
+{
+	type ClientMessage = ClientMessage;
+	type ClientState = ClientState<H>;
+	type ConsensusState = ConsensusState;
+
+	fn verify_client_message<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
+	) -> Result<(), Ics02Error> {
+		match client_message {
+			ClientMessage::Header(header) => client_state.verify_header(&header)?,
+			ClientMessage::Misbehaviour(misbehaviour) =>
+				client_state.verify_misbehaviour(&misbehaviour)?,
+		}
+
+		Ok(())
+	}
+
+	fn update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		let header = match client_message {
+			ClientMessage::Header(header) => header,
+			_ => unreachable!(
+				"02-client will check for misbehaviour before calling update_state; qed"
+			),
+		};
+		let client_state = client_state.with_header(header);
+		let consensus_state = Ctx::AnyConsensusState::wrap(&client_state.consensus_state)
+			.expect("AnyConsenusState is type checked; qed");
+
+		Ok((client_state, ConsensusUpdateResult::Single(consensus_state)))
+	}
+
+	fn update_state_on_misbehaviour(
+		&self,
+		client_state: Self::ClientState,
+		_client_message: Self::ClientMessage,
+	) -> Result<Self::ClientState, Ics02Error> {
+		Ok(client_state.with_frozen())
+	}
+
+	fn check_for_misbehaviour<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
+	) -> Result<bool, Ics02Error> {
+		// headers are signed over the current sequence, so they can't conflict with a previous
+		// update.
+		Ok(matches!(client_message, ClientMessage::Misbehaviour(_)))
+	}
+
+	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: ClientId,
+		_old_client_state: &Self::ClientState,
+		_upgrade_client_state: &Self::ClientState,
+		_upgrade_consensus_state: &Self::ConsensusState,
+		_proof_upgrade_client: Vec<u8>,
+		_proof_upgrade_consensus_state: Vec<u8>,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		Err(Error::Custom("Solo machine clients can't be upgraded".to_string()).into())
+	}
+
+	/// Will try to update the client with the state of the substitute.
+	///
+	/// The substitute must be at a greater or equal sequence and use a different public key than
+	/// the subject, the subject is unfrozen with the sequence and consensus state of the
+	/// substitute.
+	fn check_substitute_and_update_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_subject_client_id: ClientId,
+		_substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
+	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Ics02Error> {
+		if substitute_client_state.sequence < old_client_state.sequence {
+			Err(Error::Custom(format!(
+				"Substitute sequence {} is less than the subject sequence {}",
+				substitute_client_state.sequence, old_client_state.sequence
+			)))?
+		}
+		if substitute_client_state.consensus_state.public_key ==
+			old_client_state.consensus_state.public_key
+		{
+			Err(Error::Custom("Subject and substitute have the same public key".to_string()))?
+		}
+
+		let client_state = ClientState {
+			sequence: substitute_client_state.sequence,
+			is_frozen: false,
+			consensus_state: substitute_client_state.consensus_state,
+			..old_client_state
+		};
+		let consensus_state = Ctx::AnyConsensusState::wrap(&client_state.consensus_state)
+			.expect("AnyConsenusState is type checked; qed");
+
+		Ok((client_state, ConsensusUpdateResult::Single(consensus_state)))
+	}
+
+	fn verify_client_consensus_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		client_id: &ClientId,
+		consensus_height: Height,
+		expected_consensus_state: &Ctx::AnyConsensusState,
+	) -> Result<(), Ics02Error> {
+		let path = ClientConsensusStatePath {
+			client_id: client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		};
+		let value = expected_consensus_state.encode_to_vec().map_err(Ics02Error::encode)?;
+		client_state.verify_signature_proof(
+			height,
+			proof,
+			merkle_path(prefix.as_bytes(), path),
+			value,
+		)?;
+		Ok(())
+	}
+
+	fn verify_connection_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		connection_id: &ConnectionId,
+		expected_connection_end: &ConnectionEnd,
+	) -> Result<(), Ics02Error> {
+		let path = ConnectionsPath(connection_id.clone());
+		let value = expected_connection_end.encode_vec().map_err(Ics02Error::encode)?;
+		client_state.verify_signature_proof(
+			height,
+			proof,
+			merkle_path(prefix.as_bytes(), path),
+			value,
+		)?;
+		Ok(())
+	}
+
+	fn verify_channel_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		expected_channel_end: &ChannelEnd,
+	) -> Result<(), Ics02Error> {
+		let path = ChannelEndsPath(port_id.clone(), *channel_id);
+		let value = expected_channel_end.encode_vec().map_err(Ics02Error::encode)?;
+		client_state.verify_signature_proof(
+			height,
+			proof,
+			merkle_path(prefix.as_bytes(), path),
+			value,
+		)?;
+		Ok(())
+	}
+
+	fn verify_client_full_state<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		client_id: &ClientId,
+		expected_client_state: &Ctx::AnyClientState,
+	) -> Result<(), Ics02Error> {
+		let path = ClientStatePath(client_id.clone());
+		let value = expected_client_state.encode_to_vec().map_err(Ics02Error::encode)?;
+		client_state.verify_signature_proof(
+			height,
+			proof,
+			merkle_path(prefix.as_bytes(), path),
+			value,
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_data<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		commitment: PacketCommitment,
+	) -> Result<(), Ics02Error> {
+		let commitment_path =
+			CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		client_state.verify_signature_proof(
+			height,
+			proof,
+			merkle_path(connection_end.counterparty().prefix().as_bytes(), commitment_path),
+			commitment.into_vec(),
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack: AcknowledgementCommitment,
+	) -> Result<(), Ics02Error> {
+		let ack_path = AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		client_state.verify_signature_proof(
+			height,
+			proof,
+			merkle_path(connection_end.counterparty().prefix().as_bytes(), ack_path),
+			ack.into_vec(),
+		)?;
+		Ok(())
+	}
+
+	fn verify_next_sequence_recv<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		let seq_path = SeqRecvsPath(port_id.clone(), *channel_id);
+		client_state.verify_signature_proof(
+			height,
+			proof,
+			merkle_path(connection_end.counterparty().prefix().as_bytes(), seq_path),
+			u64::from(sequence).to_be_bytes().to_vec(),
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		_root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+	) -> Result<(), Ics02Error> {
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		client_state.verify_signature_proof(
+			height,
+			proof,
+			merkle_path(connection_end.counterparty().prefix().as_bytes(), receipt_path),
+			Vec::new(),
+		)?;
+		Ok(())
+	}
+}
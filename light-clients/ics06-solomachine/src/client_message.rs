@@ -0,0 +1,191 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	consensus_state::PublicKey,
+	error::Error,
+	proto::{Header as RawHeader, Misbehaviour as RawMisbehaviour, SignatureAndData},
+};
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+use ibc_proto::google::protobuf::Any;
+use tendermint_proto::Protobuf;
+
+pub const SOLOMACHINE_HEADER_TYPE_URL: &str = "/ibc.lightclients.solomachine.v3.Header";
+pub const SOLOMACHINE_MISBEHAVIOUR_TYPE_URL: &str = "/ibc.lightclients.solomachine.v3.Misbehaviour";
+pub const SOLOMACHINE_CLIENT_MESSAGE_TYPE_URL: &str =
+	"/ibc.lightclients.solomachine.v3.ClientMessage";
+
+/// Signed update of the public key and diversifier of a solo machine, at the current sequence of
+/// the client.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+	/// Timestamp of the update, it can't be older than the current consensus state
+	pub timestamp: u64,
+	/// Signature over the [`crate::proto::HeaderData`] sign bytes with the current public key
+	pub signature: Vec<u8>,
+	/// Public key of the solo machine after the update
+	pub new_public_key: PublicKey,
+	/// Diversifier of the solo machine after the update
+	pub new_diversifier: String,
+}
+
+/// Two different messages signed by the solo machine at the same sequence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Misbehaviour {
+	pub sequence: u64,
+	pub signature_one: SignatureAndData,
+	pub signature_two: SignatureAndData,
+}
+
+/// [`ClientMessage`] for Ics06-SoloMachine
+#[derive(Clone, Debug)]
+pub enum ClientMessage {
+	/// This is the variant for header updates
+	Header(Header),
+	/// This is for submitting misbehaviors.
+	Misbehaviour(Misbehaviour),
+}
+
+impl ibc::core::ics02_client::client_message::ClientMessage for ClientMessage {
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<Any> for ClientMessage {}
+
+impl TryFrom<Any> for ClientMessage {
+	type Error = Error;
+
+	fn try_from(any: Any) -> Result<Self, Self::Error> {
+		let msg = match &*any.type_url {
+			SOLOMACHINE_HEADER_TYPE_URL => Self::Header(
+				Header::decode(&*any.value).map_err(|e| Error::Custom(format!("{e:?}")))?,
+			),
+			SOLOMACHINE_MISBEHAVIOUR_TYPE_URL => Self::Misbehaviour(
+				Misbehaviour::decode(&*any.value).map_err(|e| Error::Custom(format!("{e:?}")))?,
+			),
+			_ => Err(Error::Custom(format!("Unknown type: {}", any.type_url)))?,
+		};
+
+		Ok(msg)
+	}
+}
+
+impl From<ClientMessage> for Any {
+	fn from(msg: ClientMessage) -> Self {
+		match msg {
+			ClientMessage::Header(header) => Any {
+				value: header.encode_vec().expect("failed to encode ClientMessage.header"),
+				type_url: SOLOMACHINE_HEADER_TYPE_URL.to_string(),
+			},
+			ClientMessage::Misbehaviour(misbehaviour) => Any {
+				value: misbehaviour
+					.encode_vec()
+					.expect("failed to encode ClientMessage.misbehaviour"),
+				type_url: SOLOMACHINE_MISBEHAVIOUR_TYPE_URL.to_string(),
+			},
+		}
+	}
+}
+
+impl Protobuf<RawHeader> for Header {}
+
+impl TryFrom<RawHeader> for Header {
+	type Error = Error;
+
+	fn try_from(raw: RawHeader) -> Result<Self, Self::Error> {
+		if raw.timestamp == 0 {
+			Err(Error::Custom("Invalid header: timestamp cannot be zero".into()))?
+		}
+		if raw.signature.is_empty() {
+			Err(Error::Custom("Invalid header: signature cannot be empty".into()))?
+		}
+		if !raw.new_diversifier.is_empty() && raw.new_diversifier.trim().is_empty() {
+			Err(Error::Custom("Invalid header: diversifier contains only spaces".into()))?
+		}
+		let new_public_key = raw
+			.new_public_key
+			.ok_or_else(|| Error::Custom("Invalid header: missing new public key".into()))?
+			.try_into()?;
+
+		Ok(Self {
+			timestamp: raw.timestamp,
+			signature: raw.signature,
+			new_public_key,
+			new_diversifier: raw.new_diversifier,
+		})
+	}
+}
+
+impl From<Header> for RawHeader {
+	fn from(header: Header) -> Self {
+		RawHeader {
+			timestamp: header.timestamp,
+			signature: header.signature,
+			new_public_key: Some(header.new_public_key.to_any()),
+			new_diversifier: header.new_diversifier,
+		}
+	}
+}
+
+impl Protobuf<RawMisbehaviour> for Misbehaviour {}
+
+impl TryFrom<RawMisbehaviour> for Misbehaviour {
+	type Error = Error;
+
+	fn try_from(raw: RawMisbehaviour) -> Result<Self, Self::Error> {
+		if raw.sequence == 0 {
+			Err(Error::Custom("Invalid misbehaviour: sequence cannot be zero".into()))?
+		}
+		let signature_one = raw
+			.signature_one
+			.ok_or_else(|| Error::Custom("Invalid misbehaviour: missing signature one".into()))?;
+		let signature_two = raw
+			.signature_two
+			.ok_or_else(|| Error::Custom("Invalid misbehaviour: missing signature two".into()))?;
+		for signature in [&signature_one, &signature_two] {
+			if signature.signature.is_empty() ||
+				signature.path.is_empty() ||
+				signature.data.is_empty() ||
+				signature.timestamp == 0
+			{
+				Err(Error::Custom("Invalid misbehaviour: incomplete signature and data".into()))?
+			}
+		}
+		if signature_one.signature == signature_two.signature {
+			Err(Error::Custom("Invalid misbehaviour: signatures cannot be equal".into()))?
+		}
+		if signature_one.path == signature_two.path && signature_one.data == signature_two.data {
+			Err(Error::Custom("Invalid misbehaviour: signed data cannot be equal".into()))?
+		}
+
+		Ok(Self { sequence: raw.sequence, signature_one, signature_two })
+	}
+}
+
+impl From<Misbehaviour> for RawMisbehaviour {
+	fn from(value: Misbehaviour) -> Self {
+		RawMisbehaviour {
+			sequence: value.sequence,
+			signature_one: Some(value.signature_one),
+			signature_two: Some(value.signature_two),
+		}
+	}
+}
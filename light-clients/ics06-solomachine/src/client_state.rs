@@ -0,0 +1,318 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_def::SoloMachineClient,
+	client_message::{Header, Misbehaviour},
+	consensus_state::ConsensusState,
+	error::Error,
+	proof::{decode_signature, sign_bytes, SENTINEL_HEADER_PATH},
+	proto::{
+		ClientState as RawClientState, HeaderData, SignatureAndData, TimestampedSignatureData,
+	},
+	HostFunctions,
+};
+use alloc::{format, string::ToString, vec::Vec};
+use core::{marker::PhantomData, time::Duration};
+use ibc::{
+	core::{
+		ics02_client::client_state::{ClientType, Status},
+		ics23_commitment::commitment::CommitmentProofBytes,
+		ics24_host::identifier::{ChainId, ClientId},
+		ics26_routing::context::ReaderContext,
+	},
+	Height,
+};
+use ibc_proto::google::protobuf::Any;
+use prost::Message;
+use tendermint_proto::Protobuf;
+
+/// Protobuf type url for solo machine ClientState
+pub const SOLOMACHINE_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.solomachine.v3.ClientState";
+
+#[derive(PartialEq, Clone, Debug, Eq)]
+pub struct ClientState<H> {
+	/// Latest sequence of the solo machine, every signature must be over this sequence. It's
+	/// advanced by headers and by every message whose proofs are verified against the client.
+	pub sequence: u64,
+	/// Whether the client was frozen due to a misbehaviour
+	pub is_frozen: bool,
+	/// Current consensus state of the solo machine
+	pub consensus_state: ConsensusState,
+	/// phantom type.
+	pub _phantom: PhantomData<H>,
+}
+
+impl<H: Clone> Protobuf<RawClientState> for ClientState<H> {}
+
+/// Solo machine clients can't be upgraded, a frozen client is recovered with a substitute.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UpgradeOptions;
+
+impl<H: Clone> ClientState<H> {
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: SOLOMACHINE_CLIENT_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().unwrap(),
+		}
+	}
+}
+
+impl<H> ClientState<H> {
+	pub fn new(sequence: u64, consensus_state: ConsensusState) -> Self {
+		Self { sequence, is_frozen: false, consensus_state, _phantom: PhantomData }
+	}
+
+	/// The sequence of the solo machine is the revision height of the client.
+	pub fn latest_height(&self) -> Height {
+		Height::new(0, self.sequence)
+	}
+
+	/// Solo machines aren't chains, the diversifier identifies them instead.
+	pub fn chain_id(&self) -> ChainId {
+		ChainId::from_string(&self.consensus_state.diversifier)
+	}
+
+	pub fn client_type() -> ClientType {
+		"06-solomachine".to_string()
+	}
+
+	pub fn frozen_height(&self) -> Option<Height> {
+		self.is_frozen.then(|| self.latest_height())
+	}
+
+	pub fn upgrade(
+		self,
+		_upgrade_height: Height,
+		_upgrade_options: UpgradeOptions,
+		_chain_id: ChainId,
+	) -> Self {
+		self
+	}
+
+	/// Solo machine consensus states never expire.
+	pub fn expired(&self, _elapsed: Duration) -> bool {
+		false
+	}
+
+	pub fn with_frozen(self) -> Self {
+		Self { is_frozen: true, ..self }
+	}
+
+	/// Returns the client state once the proofs of a message, signed over the current sequence,
+	/// have been verified: at the next sequence, so that they can't be verified again.
+	pub fn with_verified_proofs(self) -> Self {
+		Self { sequence: self.sequence + 1, ..self }
+	}
+
+	/// Returns the client state after applying a verified `header`, with the consensus state of
+	/// the header at the next sequence.
+	pub fn with_header(self, header: Header) -> Self {
+		Self {
+			sequence: self.sequence + 1,
+			consensus_state: ConsensusState::new(
+				header.new_public_key,
+				header.new_diversifier,
+				header.timestamp,
+			),
+			..self
+		}
+	}
+}
+
+impl<H: HostFunctions> ClientState<H> {
+	/// Verifies that the solo machine signed over `data` at `path` with the current sequence,
+	/// `proof` being the proto encoded [`TimestampedSignatureData`]. Proofs of absence are
+	/// signatures over empty data.
+	pub fn verify_signature_proof(
+		&self,
+		height: Height,
+		proof: &CommitmentProofBytes,
+		path: Vec<u8>,
+		data: Vec<u8>,
+	) -> Result<(), Error> {
+		if self.is_frozen {
+			Err(Error::Custom("Client is frozen".into()))?
+		}
+		if height != self.latest_height() {
+			Err(Error::Custom(format!(
+				"Proof height {height} doesn't match the sequence of the client {}",
+				self.sequence
+			)))?
+		}
+		let TimestampedSignatureData { signature_data, timestamp } =
+			TimestampedSignatureData::decode(proof.as_bytes())?;
+		if timestamp < self.consensus_state.timestamp {
+			Err(Error::Custom(format!(
+				"Proof timestamp {timestamp} is older than the consensus state timestamp {}",
+				self.consensus_state.timestamp
+			)))?
+		}
+		let signature = decode_signature(&signature_data)?;
+		let sign_bytes = sign_bytes(
+			self.sequence,
+			timestamp,
+			self.consensus_state.diversifier.clone(),
+			path,
+			data,
+		);
+		if !self.consensus_state.public_key.verify::<H>(&sign_bytes, &signature) {
+			Err(Error::Custom("Invalid signature proof".into()))?
+		}
+		Ok(())
+	}
+
+	/// Verifies that the current public key signed over the new public key and diversifier of
+	/// `header` at the current sequence.
+	pub fn verify_header(&self, header: &Header) -> Result<(), Error> {
+		if header.timestamp < self.consensus_state.timestamp {
+			Err(Error::Custom(format!(
+				"Header timestamp {} is older than the consensus state timestamp {}",
+				header.timestamp, self.consensus_state.timestamp
+			)))?
+		}
+		let header_data = HeaderData {
+			new_pub_key: Some(header.new_public_key.to_any()),
+			new_diversifier: header.new_diversifier.clone(),
+		};
+		let sign_bytes = sign_bytes(
+			self.sequence,
+			header.timestamp,
+			self.consensus_state.diversifier.clone(),
+			SENTINEL_HEADER_PATH.as_bytes().to_vec(),
+			header_data.encode_to_vec(),
+		);
+		let signature = decode_signature(&header.signature)?;
+		if !self.consensus_state.public_key.verify::<H>(&sign_bytes, &signature) {
+			Err(Error::Custom("Invalid header signature".into()))?
+		}
+		Ok(())
+	}
+
+	/// Verifies that both signatures of `misbehaviour` were made with the current public key, at a
+	/// sequence the client already went past.
+	pub fn verify_misbehaviour(&self, misbehaviour: &Misbehaviour) -> Result<(), Error> {
+		if misbehaviour.sequence >= self.sequence {
+			Err(Error::Custom(format!(
+				"Misbehaviour sequence {} hasn't been used by the client, at sequence {}",
+				misbehaviour.sequence, self.sequence
+			)))?
+		}
+		for SignatureAndData { signature, path, data, timestamp } in
+			[&misbehaviour.signature_one, &misbehaviour.signature_two]
+		{
+			let sign_bytes = sign_bytes(
+				misbehaviour.sequence,
+				*timestamp,
+				self.consensus_state.diversifier.clone(),
+				path.clone(),
+				data.clone(),
+			);
+			let signature = decode_signature(signature)?;
+			if !self.consensus_state.public_key.verify::<H>(&sign_bytes, &signature) {
+				Err(Error::Custom("Invalid misbehaviour signature".into()))?
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<H> ibc::core::ics02_client::client_state::ClientState for ClientState<H>
+where
+	H: HostFunctions,
+{
+	type UpgradeOptions = UpgradeOptions;
+	type ClientDef = SoloMachineClient<H>;
+
+	fn chain_id(&self) -> ChainId {
+		self.chain_id()
+	}
+
+	fn client_def(&self) -> Self::ClientDef {
+		SoloMachineClient::default()
+	}
+
+	fn client_type(&self) -> ClientType {
+		Self::client_type()
+	}
+
+	fn latest_height(&self) -> Height {
+		self.latest_height()
+	}
+
+	fn status<Ctx: ReaderContext>(&self, _ctx: &Ctx, _client_id: &ClientId) -> Status {
+		if self.is_frozen {
+			return Status::Frozen
+		}
+
+		Status::Active
+	}
+
+	fn frozen_height(&self) -> Option<Height> {
+		self.frozen_height()
+	}
+
+	fn upgrade(
+		self,
+		upgrade_height: Height,
+		upgrade_options: UpgradeOptions,
+		chain_id: ChainId,
+	) -> Self {
+		self.upgrade(upgrade_height, upgrade_options, chain_id)
+	}
+
+	fn expired(&self, elapsed: Duration) -> bool {
+		self.expired(elapsed)
+	}
+
+	fn on_proofs_verified(&self) -> Option<Self> {
+		Some(self.clone().with_verified_proofs())
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl<H> TryFrom<RawClientState> for ClientState<H> {
+	type Error = Error;
+
+	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+		if raw.sequence == 0 {
+			Err(Error::Custom("Invalid client state: sequence cannot be zero".into()))?
+		}
+		let consensus_state = raw
+			.consensus_state
+			.ok_or_else(|| Error::Custom("Invalid client state: missing consensus state".into()))?
+			.try_into()?;
+
+		Ok(Self {
+			sequence: raw.sequence,
+			is_frozen: raw.is_frozen,
+			consensus_state,
+			_phantom: PhantomData,
+		})
+	}
+}
+
+impl<H> From<ClientState<H>> for RawClientState {
+	fn from(client_state: ClientState<H>) -> Self {
+		RawClientState {
+			sequence: client_state.sequence,
+			is_frozen: client_state.is_frozen,
+			consensus_state: Some(client_state.consensus_state.into()),
+		}
+	}
+}
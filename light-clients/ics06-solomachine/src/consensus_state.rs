@@ -0,0 +1,165 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	error::Error,
+	proto::{
+		ConsensusState as RawConsensusState, PubKey, ED25519_PUBLIC_KEY_TYPE_URL,
+		SECP256K1_PUBLIC_KEY_TYPE_URL,
+	},
+	HostFunctions,
+};
+use alloc::{
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::convert::Infallible;
+use ibc::{core::ics23_commitment::commitment::CommitmentRoot, timestamp::Timestamp};
+use ibc_proto::google::protobuf::Any;
+use prost::Message;
+use tendermint_proto::Protobuf;
+
+/// Protobuf type url for solo machine ConsensusState
+pub const SOLOMACHINE_CONSENSUS_STATE_TYPE_URL: &str =
+	"/ibc.lightclients.solomachine.v3.ConsensusState";
+
+/// Half of the order of the secp256k1 curve, signatures with a greater `s` are malleable.
+const SECP256K1_HALF_ORDER: [u8; 32] = [
+	0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+	0x5d, 0x57, 0x6e, 0x73, 0x57, 0xa4, 0x50, 0x1d, 0xdf, 0xe9, 0x2f, 0x46, 0x68, 0x1b, 0x20, 0xa0,
+];
+
+/// Solo machines don't commit to their state, so their consensus states have no root.
+static EMPTY_ROOT: CommitmentRoot = CommitmentRoot { bytes: Vec::new() };
+
+/// Public key of a solo machine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PublicKey {
+	/// Compressed secp256k1 public key
+	Secp256k1([u8; 33]),
+	/// Ed25519 public key
+	Ed25519([u8; 32]),
+}
+
+impl PublicKey {
+	/// Verifies the `signature` of `message`. Secp256k1 signatures are over the sha256 digest of
+	/// the message and must be in the lower half of the curve order, as in the cosmos-sdk.
+	pub fn verify<H: HostFunctions>(&self, message: &[u8], signature: &[u8]) -> bool {
+		let Ok(signature) = <[u8; 64]>::try_from(signature) else { return false };
+		match self {
+			PublicKey::Secp256k1(public_key) =>
+				signature[32..] <= SECP256K1_HALF_ORDER[..] &&
+					H::secp256k1_verify(&signature, &H::sha256(message), public_key),
+			PublicKey::Ed25519(public_key) => H::ed25519_verify(&signature, message, public_key),
+		}
+	}
+
+	pub fn to_any(&self) -> Any {
+		let (type_url, key) = match self {
+			PublicKey::Secp256k1(key) => (SECP256K1_PUBLIC_KEY_TYPE_URL, key.to_vec()),
+			PublicKey::Ed25519(key) => (ED25519_PUBLIC_KEY_TYPE_URL, key.to_vec()),
+		};
+		Any { type_url: type_url.to_string(), value: PubKey { key }.encode_to_vec() }
+	}
+}
+
+impl TryFrom<Any> for PublicKey {
+	type Error = Error;
+
+	fn try_from(any: Any) -> Result<Self, Self::Error> {
+		let PubKey { key } = PubKey::decode(&*any.value)?;
+		let invalid_length =
+			|_| Error::Custom(format!("Invalid {} public key length: {}", any.type_url, key.len()));
+		match any.type_url.as_str() {
+			SECP256K1_PUBLIC_KEY_TYPE_URL =>
+				Ok(PublicKey::Secp256k1(key.as_slice().try_into().map_err(invalid_length)?)),
+			ED25519_PUBLIC_KEY_TYPE_URL =>
+				Ok(PublicKey::Ed25519(key.as_slice().try_into().map_err(invalid_length)?)),
+			type_url => Err(Error::Custom(format!("Unsupported public key type: {type_url}"))),
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusState {
+	/// Public key of the solo machine
+	pub public_key: PublicKey,
+	/// Allows the same public key to be re-used across different solo machine clients without
+	/// being considered misbehaviour
+	pub diversifier: String,
+	/// Unix timestamp of the solo machine in nanoseconds
+	pub timestamp: u64,
+}
+
+impl ConsensusState {
+	pub fn new(public_key: PublicKey, diversifier: String, timestamp: u64) -> Self {
+		Self { public_key, diversifier, timestamp }
+	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: SOLOMACHINE_CONSENSUS_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ConsensusState"),
+		}
+	}
+}
+
+impl ibc::core::ics02_client::client_consensus::ConsensusState for ConsensusState {
+	type Error = Infallible;
+
+	fn root(&self) -> &CommitmentRoot {
+		&EMPTY_ROOT
+	}
+
+	fn timestamp(&self) -> Timestamp {
+		Timestamp::from_nanoseconds(self.timestamp).unwrap_or_else(|_| Timestamp::none())
+	}
+
+	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+	type Error = Error;
+
+	fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+		let public_key = raw
+			.public_key
+			.ok_or_else(|| Error::Custom("Invalid consensus state: missing public key".into()))?
+			.try_into()?;
+		if !raw.diversifier.is_empty() && raw.diversifier.trim().is_empty() {
+			Err(Error::Custom("Invalid consensus state: diversifier contains only spaces".into()))?
+		}
+		if raw.timestamp == 0 {
+			Err(Error::Custom("Invalid consensus state: timestamp cannot be zero".into()))?
+		}
+
+		Ok(Self { public_key, diversifier: raw.diversifier, timestamp: raw.timestamp })
+	}
+}
+
+impl From<ConsensusState> for RawConsensusState {
+	fn from(value: ConsensusState) -> Self {
+		RawConsensusState {
+			public_key: Some(value.public_key.to_any()),
+			diversifier: value.diversifier,
+			timestamp: value.timestamp,
+		}
+	}
+}
@@ -0,0 +1,57 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::all)]
+
+//! ICS-06: Solo machine IBC light client protocol implementation
+//!
+//! A solo machine is a standalone process, e.g. an off-chain service or an HSM-backed custodian,
+//! that signs over its state with a single key. The client tracks the public key, the diversifier
+//! and the sequence of the solo machine, every signature must be over the current sequence. The
+//! sequence advances with every header and every message whose proofs are verified against the
+//! client, so that a signature can't be used twice.
+
+extern crate alloc;
+
+use core::fmt::Debug;
+
+pub mod client_def;
+pub mod client_message;
+pub mod client_state;
+pub mod consensus_state;
+pub mod error;
+pub mod proof;
+pub mod proto;
+
+#[cfg(test)]
+mod tests;
+
+/// Host functions used to verify the signatures of a solo machine.
+pub trait HostFunctions: Clone + Debug + Default + Eq + Send + Sync + 'static {
+	/// Returns the sha256 digest of `message`.
+	fn sha256(message: &[u8]) -> [u8; 32];
+
+	/// Verifies the ed25519 `signature` of `message` against `public_key`.
+	fn ed25519_verify(signature: &[u8; 64], message: &[u8], public_key: &[u8; 32]) -> bool;
+
+	/// Verifies the secp256k1 ECDSA `signature`, encoded as `r || s`, of the digest `message_hash`
+	/// against the compressed `public_key`.
+	fn secp256k1_verify(
+		signature: &[u8; 64],
+		message_hash: &[u8; 32],
+		public_key: &[u8; 33],
+	) -> bool;
+}
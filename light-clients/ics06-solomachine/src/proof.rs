@@ -0,0 +1,88 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Sign bytes of the messages signed by a solo machine, these match the ones of ibc-go so that
+//! the same signer can be used with any counterparty.
+
+use crate::{
+	error::Error,
+	proto::{data, SignBytes, SignMode, SignatureData, TimestampedSignatureData},
+};
+use alloc::{
+	string::{String, ToString},
+	vec::Vec,
+};
+use core::fmt::{Display, Write};
+use prost::Message;
+
+/// Path of the sign bytes of headers.
+pub const SENTINEL_HEADER_PATH: &str = "solomachine:header";
+
+/// Returns the path signed over by the solo machine for the value at `path` under the commitment
+/// `prefix`, that is the string of the equivalent merkle path: every key prefixed with `/` and
+/// percent-encoded as a path segment.
+pub fn merkle_path(prefix: &[u8], path: impl Display) -> Vec<u8> {
+	let mut merkle_path = String::new();
+	for key in [String::from_utf8_lossy(prefix).into_owned(), path.to_string()] {
+		merkle_path.push('/');
+		for byte in key.bytes() {
+			match byte {
+				b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' => merkle_path.push(byte as char),
+				b'-' | b'_' | b'.' | b'~' | b'$' | b'&' | b'+' | b',' | b':' | b';' | b'=' |
+				b'@' => merkle_path.push(byte as char),
+				_ => {
+					let _ = write!(merkle_path, "%{byte:02X}");
+				},
+			}
+		}
+	}
+	merkle_path.into_bytes()
+}
+
+/// Returns the bytes the solo machine signs over to prove `data` at `path`.
+pub fn sign_bytes(
+	sequence: u64,
+	timestamp: u64,
+	diversifier: String,
+	path: Vec<u8>,
+	data: Vec<u8>,
+) -> Vec<u8> {
+	SignBytes { sequence, timestamp, diversifier, path, data }.encode_to_vec()
+}
+
+/// Decodes the signature of the proto encoded `SignatureDescriptor.Data` of a proof, only single
+/// signatures are supported.
+pub fn decode_signature(signature_data: &[u8]) -> Result<Vec<u8>, Error> {
+	match SignatureData::decode(signature_data)?.sum {
+		Some(data::Sum::Single(data::Single { signature, .. })) => Ok(signature),
+		Some(data::Sum::Multi(_)) =>
+			Err(Error::Custom("Multisig solo machines are not supported".into())),
+		None => Err(Error::Custom("Missing signature data".into())),
+	}
+}
+
+/// Returns the proto encoded `SignatureDescriptor.Data` of a single `signature`, as expected in
+/// headers and misbehaviours.
+pub fn encode_signature(signature: Vec<u8>) -> Vec<u8> {
+	let single = data::Single { mode: SignMode::Direct as i32, signature };
+	SignatureData { sum: Some(data::Sum::Single(single)) }.encode_to_vec()
+}
+
+/// Returns the proof of a `signature` made at `timestamp`, to be submitted as the proof of the
+/// signed value.
+pub fn signature_proof(signature: Vec<u8>, timestamp: u64) -> Vec<u8> {
+	TimestampedSignatureData { signature_data: encode_signature(signature), timestamp }
+		.encode_to_vec()
+}
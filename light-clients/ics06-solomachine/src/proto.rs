@@ -0,0 +1,37 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub use ibc_proto::{
+	cosmos::tx::signing::v1beta1::{
+		signature_descriptor::{data, Data as SignatureData},
+		SignMode,
+	},
+	ibc::lightclients::solomachine::v1::{
+		ClientState, ConsensusState, Header, HeaderData, Misbehaviour, SignBytes, SignatureAndData,
+		TimestampedSignatureData,
+	},
+};
+
+/// Protobuf type url for cosmos-sdk secp256k1 public keys
+pub const SECP256K1_PUBLIC_KEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+/// Protobuf type url for cosmos-sdk ed25519 public keys
+pub const ED25519_PUBLIC_KEY_TYPE_URL: &str = "/cosmos.crypto.ed25519.PubKey";
+
+/// The cosmos-sdk `PubKey` message, shared by the secp256k1 and ed25519 public keys.
+#[derive(Clone, PartialEq, Eq, ::prost::Message)]
+pub struct PubKey {
+	#[prost(bytes = "vec", tag = "1")]
+	pub key: ::prost::alloc::vec::Vec<u8>,
+}
@@ -0,0 +1,250 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_message::{Header, Misbehaviour},
+	client_state::ClientState,
+	consensus_state::{ConsensusState, PublicKey},
+	proof::{encode_signature, merkle_path, signature_proof},
+	proto::SignatureAndData,
+	HostFunctions,
+};
+use ibc::{
+	core::{
+		ics23_commitment::commitment::CommitmentProofBytes,
+		ics24_host::{identifier::ConnectionId, path::ConnectionsPath},
+	},
+	Height,
+};
+use prost::Message;
+use sha2::Digest;
+use sp_core::{ecdsa, ed25519, Pair};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct HostFunctionsManager;
+
+impl HostFunctions for HostFunctionsManager {
+	fn sha256(message: &[u8]) -> [u8; 32] {
+		sha2::Sha256::digest(message).into()
+	}
+
+	fn ed25519_verify(signature: &[u8; 64], message: &[u8], public_key: &[u8; 32]) -> bool {
+		ed25519::Pair::verify(
+			&ed25519::Signature::from_raw(*signature),
+			message,
+			&ed25519::Public::from_raw(*public_key),
+		)
+	}
+
+	fn secp256k1_verify(
+		signature: &[u8; 64],
+		message_hash: &[u8; 32],
+		public_key: &[u8; 33],
+	) -> bool {
+		let public_key = ecdsa::Public::from_raw(*public_key);
+		(0..2).any(|recovery_id| {
+			let mut signature_with_id = [0u8; 65];
+			signature_with_id[..64].copy_from_slice(signature);
+			signature_with_id[64] = recovery_id;
+			ecdsa::Pair::verify_prehashed(
+				&ecdsa::Signature::from_raw(signature_with_id),
+				message_hash,
+				&public_key,
+			)
+		})
+	}
+}
+
+enum Signer {
+	Ed25519(ed25519::Pair),
+	Secp256k1(ecdsa::Pair),
+}
+
+impl Signer {
+	fn public_key(&self) -> PublicKey {
+		match self {
+			Signer::Ed25519(pair) => PublicKey::Ed25519(pair.public().0),
+			Signer::Secp256k1(pair) => PublicKey::Secp256k1(pair.public().0),
+		}
+	}
+
+	fn sign(&self, message: &[u8]) -> Vec<u8> {
+		match self {
+			Signer::Ed25519(pair) => pair.sign(message).0.to_vec(),
+			Signer::Secp256k1(pair) =>
+				pair.sign_prehashed(&HostFunctionsManager::sha256(message)).0[..64].to_vec(),
+		}
+	}
+}
+
+fn client_state(signer: &Signer) -> ClientState<HostFunctionsManager> {
+	ClientState::new(1, ConsensusState::new(signer.public_key(), "solo".to_string(), 10))
+}
+
+fn connection_path() -> Vec<u8> {
+	merkle_path(b"ibc", ConnectionsPath(ConnectionId::new(0)))
+}
+
+fn signature_proof(
+	signer: &Signer,
+	client_state: &ClientState<HostFunctionsManager>,
+	timestamp: u64,
+	path: Vec<u8>,
+	data: Vec<u8>,
+) -> CommitmentProofBytes {
+	let sign_bytes = crate::proof::sign_bytes(
+		client_state.sequence,
+		timestamp,
+		client_state.consensus_state.diversifier.clone(),
+		path,
+		data,
+	);
+	signature_proof(signer.sign(&sign_bytes), timestamp).try_into().unwrap()
+}
+
+#[test]
+fn merkle_path_should_escape_keys() {
+	assert_eq!(connection_path(), b"/ibc/connections%2Fconnection-0".to_vec());
+}
+
+#[test]
+fn should_verify_signature_proofs() {
+	for signer in [
+		Signer::Ed25519(ed25519::Pair::from_seed(&[1; 32])),
+		Signer::Secp256k1(ecdsa::Pair::from_seed(&[1; 32])),
+	] {
+		let client_state = client_state(&signer);
+		let height = client_state.latest_height();
+		let proof = signature_proof(&signer, &client_state, 11, connection_path(), vec![1, 2, 3]);
+
+		client_state
+			.verify_signature_proof(height, &proof, connection_path(), vec![1, 2, 3])
+			.unwrap();
+		// different data
+		assert!(client_state
+			.verify_signature_proof(height, &proof, connection_path(), vec![1, 2])
+			.is_err());
+		// different sequence
+		assert!(client_state
+			.verify_signature_proof(Height::new(0, 2), &proof, connection_path(), vec![1, 2, 3])
+			.is_err());
+		// frozen client
+		assert!(client_state
+			.clone()
+			.with_frozen()
+			.verify_signature_proof(height, &proof, connection_path(), vec![1, 2, 3])
+			.is_err());
+
+		// older than the consensus state
+		let proof = signature_proof(&signer, &client_state, 9, connection_path(), vec![1, 2, 3]);
+		assert!(client_state
+			.verify_signature_proof(height, &proof, connection_path(), vec![1, 2, 3])
+			.is_err());
+	}
+}
+
+#[test]
+fn should_update_public_key_with_header() {
+	let signer = Signer::Ed25519(ed25519::Pair::from_seed(&[1; 32]));
+	let new_signer = Signer::Secp256k1(ecdsa::Pair::from_seed(&[2; 32]));
+	let client_state = client_state(&signer);
+
+	let mut header = Header {
+		timestamp: 12,
+		signature: vec![],
+		new_public_key: new_signer.public_key(),
+		new_diversifier: "new solo".to_string(),
+	};
+	let header_data = crate::proto::HeaderData {
+		new_pub_key: Some(header.new_public_key.to_any()),
+		new_diversifier: header.new_diversifier.clone(),
+	};
+	let sign_bytes = crate::proof::sign_bytes(
+		1,
+		12,
+		"solo".to_string(),
+		crate::proof::SENTINEL_HEADER_PATH.as_bytes().to_vec(),
+		header_data.encode_to_vec(),
+	);
+
+	// signed by the new key
+	header.signature = encode_signature(new_signer.sign(&sign_bytes));
+	assert!(client_state.verify_header(&header).is_err());
+
+	header.signature = encode_signature(signer.sign(&sign_bytes));
+	client_state.verify_header(&header).unwrap();
+
+	let client_state = client_state.with_header(header);
+	assert_eq!(client_state.latest_height(), Height::new(0, 2));
+	assert_eq!(client_state.consensus_state.public_key, new_signer.public_key());
+	assert_eq!(client_state.consensus_state.diversifier, "new solo");
+	assert_eq!(client_state.consensus_state.timestamp, 12);
+
+	let proof = signature_proof(&new_signer, &client_state, 12, connection_path(), vec![1]);
+	client_state
+		.verify_signature_proof(Height::new(0, 2), &proof, connection_path(), vec![1])
+		.unwrap();
+}
+
+#[test]
+fn should_verify_misbehaviour() {
+	let signer = Signer::Ed25519(ed25519::Pair::from_seed(&[1; 32]));
+	let client_state = client_state(&signer).with_verified_proofs();
+	let signature_and_data = |data: Vec<u8>| {
+		let sign_bytes =
+			crate::proof::sign_bytes(1, 11, "solo".to_string(), connection_path(), data.clone());
+		SignatureAndData {
+			signature: encode_signature(signer.sign(&sign_bytes)),
+			path: connection_path(),
+			data,
+			timestamp: 11,
+		}
+	};
+
+	let misbehaviour = Misbehaviour {
+		sequence: 1,
+		signature_one: signature_and_data(vec![1]),
+		signature_two: signature_and_data(vec![2]),
+	};
+	client_state.verify_misbehaviour(&misbehaviour).unwrap();
+
+	let mut forged = misbehaviour.clone();
+	forged.signature_two.data = vec![3];
+	assert!(client_state.verify_misbehaviour(&forged).is_err());
+
+	// the client never used the sequence of the signatures
+	let client_state = ClientState { sequence: 1, ..client_state };
+	assert!(client_state.verify_misbehaviour(&misbehaviour).is_err());
+}
+
+#[test]
+fn should_not_verify_signature_proofs_twice() {
+	let signer = Signer::Ed25519(ed25519::Pair::from_seed(&[1; 32]));
+	let client_state = client_state(&signer);
+	let proof = signature_proof(&signer, &client_state, 11, connection_path(), vec![1, 2, 3]);
+	client_state
+		.verify_signature_proof(Height::new(0, 1), &proof, connection_path(), vec![1, 2, 3])
+		.unwrap();
+
+	let client_state = client_state.with_verified_proofs();
+	assert_eq!(client_state.latest_height(), Height::new(0, 2));
+	assert!(client_state
+		.verify_signature_proof(Height::new(0, 2), &proof, connection_path(), vec![1, 2, 3])
+		.is_err());
+	let proof = signature_proof(&signer, &client_state, 11, connection_path(), vec![1, 2, 3]);
+	client_state
+		.verify_signature_proof(Height::new(0, 2), &proof, connection_path(), vec![1, 2, 3])
+		.unwrap();
+}
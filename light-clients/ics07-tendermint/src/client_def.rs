@@ -26,6 +26,7 @@ use ibc::core::{
 		channel::ChannelEnd,
 		commitment::{AcknowledgementCommitment, PacketCommitment},
 		packet::Sequence,
+		upgrade::{ErrorReceipt, Upgrade},
 	},
 	ics23_commitment::{
 		commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
@@ -34,8 +35,8 @@ use ibc::core::{
 	ics24_host::{
 		identifier::{ChainId, ChannelId, ClientId, ConnectionId, PortId},
 		path::{
-			AcksPath, ChannelEndsPath, ClientConsensusStatePath, ClientStatePath, CommitmentsPath,
-			ConnectionsPath, ReceiptsPath, SeqRecvsPath,
+			AcksPath, ChannelEndsPath, ChannelUpgradePath, ClientConsensusStatePath,
+			ClientStatePath, CommitmentsPath, ConnectionsPath, ReceiptsPath, SeqRecvsPath,
 		},
 		Path,
 	},
@@ -409,6 +410,46 @@ where
 		verify_membership::<H, _>(client_state, prefix, proof, root, path, value)
 	}
 
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+
+		let path = ChannelUpgradePath::Upgrade(port_id.clone(), *channel_id);
+		let value = upgrade.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, root, path, value)
+	}
+
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		_ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
+	) -> Result<(), Ics02Error> {
+		client_state.verify_height(height)?;
+
+		let path = ChannelUpgradePath::UpgradeError(port_id.clone(), *channel_id);
+		let value = error_receipt.encode_vec().map_err(Ics02Error::encode)?;
+		verify_membership::<H, _>(client_state, prefix, proof, root, path, value)
+	}
+
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
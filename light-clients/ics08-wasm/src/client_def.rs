@@ -34,6 +34,7 @@ use ibc::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			packet::Sequence,
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
@@ -277,6 +278,60 @@ where
 		)
 	}
 
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
+	) -> Result<(), Error> {
+		self.inner.verify_channel_upgrade(
+			ctx,
+			client_id,
+			&client_state.inner,
+			height,
+			prefix,
+			proof,
+			root,
+			port_id,
+			channel_id,
+			upgrade,
+		)
+	}
+
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
+	) -> Result<(), Error> {
+		self.inner.verify_channel_upgrade_error(
+			ctx,
+			client_id,
+			&client_state.inner,
+			height,
+			prefix,
+			proof,
+			root,
+			port_id,
+			channel_id,
+			error_receipt,
+		)
+	}
+
 	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		ctx: &Ctx,
@@ -23,6 +23,7 @@ use ibc::{
 			context::ChannelReader,
 			error::Error,
 			packet::{Receipt, Sequence},
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
 	},
@@ -138,4 +139,37 @@ impl<'a, H: HostFunctions> ChannelReader for Context<'a, H> {
 	fn max_expected_time_per_block(&self) -> Duration {
 		unimplemented!("'max_expected_time_per_block' is unavailable from the client")
 	}
+
+	fn channel_upgrade(&self, _port_channel_id: &(PortId, ChannelId)) -> Result<Upgrade, Error> {
+		Err(Error::implementation_specific(
+			"'channel_upgrade' is unavailable from the client".to_string(),
+		))
+	}
+
+	fn channel_counterparty_upgrade(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<Upgrade, Error> {
+		Err(Error::implementation_specific(
+			"'channel_counterparty_upgrade' is unavailable from the client".to_string(),
+		))
+	}
+
+	fn channel_upgrade_error(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<ErrorReceipt, Error> {
+		Err(Error::implementation_specific(
+			"'channel_upgrade_error' is unavailable from the client".to_string(),
+		))
+	}
+
+	fn inflight_packets(
+		&self,
+		_port_channel_id: &(PortId, ChannelId),
+	) -> Result<Vec<Sequence>, Error> {
+		Err(Error::implementation_specific(
+			"'inflight_packets' is unavailable from the client".to_string(),
+		))
+	}
 }
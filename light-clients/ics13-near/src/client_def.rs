@@ -17,16 +17,21 @@ use super::{
 	client_state::NearClientState,
 	consensus_state::ConsensusState,
 	error::Error as NearError,
-	header::NearHeader,
+	header::{NearHeader, NearMisbehaviour},
 	types::{ApprovalInner, CryptoHash, LightClientBlockView},
 };
-use crate::header::NearClientMessage;
+use crate::{
+	header::NearClientMessage,
+	proof::{verify_membership, verify_non_membership},
+};
 use borsh::BorshSerialize;
-use core::fmt::Debug;
+use core::{fmt::Debug, marker::PhantomData};
 use ibc::{
 	core::{
 		ics02_client::{
+			client_consensus::ConsensusState as _,
 			client_def::{ClientDef, ConsensusUpdateResult},
+			client_state::ClientState as _,
 			error::Error,
 		},
 		ics03_connection::connection::ConnectionEnd,
@@ -34,79 +39,37 @@ use ibc::{
 			channel::ChannelEnd,
 			commitment::{AcknowledgementCommitment, PacketCommitment},
 			packet::Sequence,
+			upgrade::{ErrorReceipt, Upgrade},
 		},
 		ics23_commitment::commitment::{CommitmentPrefix, CommitmentProofBytes, CommitmentRoot},
-		ics24_host::identifier::{ChannelId, ClientId, ConnectionId, PortId},
+		ics24_host::{
+			identifier::{ChannelId, ClientId, ConnectionId, PortId},
+			path::{
+				AcksPath, ChannelEndsPath, ChannelUpgradePath, ClientConsensusStatePath,
+				ClientStatePath, ClientUpgradePath, CommitmentsPath, ConnectionsPath, ReceiptsPath,
+				SeqRecvsPath,
+			},
+		},
 		ics26_routing::context::ReaderContext,
 	},
 	prelude::*,
 	Height,
 };
 use ics23::HostFunctionsProvider;
-use std::marker::PhantomData;
+use light_client_common::verify_delay_passed;
+use tendermint_proto::Protobuf;
 
 pub trait HostFunctionsTrait:
 	HostFunctions + HostFunctionsProvider + Clone + Debug + PartialEq + Eq + Default + Send + Sync
 {
 }
 
-/// This trait captures all the functions that the host chain should provide for
-/// crypto operations.
 pub trait HostFunctions: Clone + Send + Sync + Default {
-	/// Keccak 256 hash function
-	fn keccak_256(input: &[u8]) -> [u8; 32];
-
-	/// Compressed Ecdsa public key recovery from a signature
-	fn secp256k1_ecdsa_recover_compressed(
-		signature: &[u8; 65],
-		value: &[u8; 32],
-	) -> Option<Vec<u8>>;
-
-	/// Recover the ED25519 pubkey that produced this signature, given a arbitrarily sized message
+	/// Verify an ED25519 signature over an arbitrarily sized message
 	fn ed25519_verify(signature: &[u8; 64], msg: &[u8], pubkey: &[u8]) -> bool;
 
-	/// This function should verify membership in a trie proof using sp_state_machine's
-	/// read_child_proof_check
-	fn verify_membership_trie_proof(
-		root: &[u8; 32],
-		proof: &[Vec<u8>],
-		key: &[u8],
-		value: &[u8],
-	) -> Result<(), Error>;
-
-	/// This function should verify non membership in a trie proof using sp_state_machine's
-	/// read_child_proof_check
-	fn verify_non_membership_trie_proof(
-		root: &[u8; 32],
-		proof: &[Vec<u8>],
-		key: &[u8],
-	) -> Result<(), Error>;
-
-	/// This function should verify membership in a trie proof using parity's sp-trie package
-	/// with a BlakeTwo256 Hasher
-	fn verify_timestamp_extrinsic(
-		root: &[u8; 32],
-		proof: &[Vec<u8>],
-		value: &[u8],
-	) -> Result<(), Error>;
-
 	/// Conduct a 256-bit Sha2 hash
 	fn sha256_digest(data: &[u8]) -> [u8; 32];
-
-	/// The SHA-256 hash algorithm
-	fn sha2_256(message: &[u8]) -> [u8; 32];
-
-	/// The SHA-512 hash algorithm
-	fn sha2_512(message: &[u8]) -> [u8; 64];
-
-	/// The SHA-512 hash algorithm with its output truncated to 256 bits.
-	fn sha2_512_truncated(message: &[u8]) -> [u8; 32];
-
-	/// SHA-3-512 hash function.
-	fn sha3_512(message: &[u8]) -> [u8; 64];
-
-	/// Ripemd160 hash function.
-	fn ripemd160(message: &[u8]) -> [u8; 20];
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
@@ -117,26 +80,14 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 	type ClientMessage = NearClientMessage;
 
 	/// The data that we need to know, to validate incoming headers and update the state
-	/// of our [`ClientState`]. Ususally this will store:
-	///    - The current epoch
-	///    - The current validator set
-	///
-	/// ```rust,ignore
-	/// pub struct NearLightClientState {
-	///     head: LightClientBlockView,
-	///     current_validators: Vec<ValidatorStakeView>,
-	///     next_validators:  Vec<ValidatorStakeView>,
-	/// }
-	/// ```
+	/// of our [`ClientState`]:
+	///    - The latest accepted block
+	///    - The block producers of the current and the next epoch
 	type ClientState = NearClientState<H>;
 
-	/// This is usually just two things, that should be derived from the header:
-	///    - The ibc commitment root hash as described by ics23 (possibly from tx outcome/ state
-	///      proof)
-	///    - The timestamp of the header.
+	/// The `prev_state_root` and the timestamp of a block.
 	type ConsensusState = ConsensusState;
 
-	// rehydrate client from its own storage, then call this function
 	fn verify_client_message<Ctx>(
 		&self,
 		_ctx: &Ctx,
@@ -148,11 +99,9 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		Ctx: ReaderContext,
 	{
 		match client_message {
-			NearClientMessage::Header(header) => {
-				// your light client, shouldn't do storage anymore, it should just do verification
-				// here.
-				validate_light_block::<H>(&header, client_state)
-			},
+			NearClientMessage::Header(header) => validate_light_block::<H>(&header, client_state),
+			NearClientMessage::Misbehaviour(misbehaviour) =>
+				validate_misbehaviour::<H>(&misbehaviour, &client_state),
 		}
 	}
 
@@ -160,200 +109,449 @@ impl<H: HostFunctionsTrait> ClientDef for NearClient<H> {
 		&self,
 		_ctx: &Ctx,
 		_client_id: ClientId,
-		_client_state: Self::ClientState,
-		_client_message: Self::ClientMessage,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		// 1. create new client state from this header, return that.
-		// 2. as well as all the neccessary consensus states.
-		//
-		//
-		// []--[]--[]--[]--[]--[]--[]--[]--[]--[]
-		// 11  12  13  14  15  16  17  18  19  20 <- block merkle root
-		// ^                                    ^
-		// |    <-------consensus states----->  |
-		// current state                       new state
-
-		todo!()
+		let header = match client_message {
+			NearClientMessage::Header(header) => header,
+			_ => unreachable!(
+				"02-client will check for misbehaviour before calling update_state; qed"
+			),
+		};
+
+		// the light client blocks are the last final blocks of their epochs (or later), there
+		// is a single consensus state per update.
+		let block_view = header.into_light_client_block_view();
+		let consensus_state = ConsensusState::from_header(&block_view)?;
+		let client_state = client_state.with_head(block_view);
+		let wrapped = Ctx::AnyConsensusState::wrap(&consensus_state)
+			.expect("AnyConsenusState is type checked; qed");
+
+		Ok((client_state, ConsensusUpdateResult::Single(wrapped)))
 	}
 
 	fn update_state_on_misbehaviour(
 		&self,
-		_client_state: Self::ClientState,
-		_client_message: Self::ClientMessage,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
 	) -> Result<Self::ClientState, Error> {
-		todo!()
+		// consensus states from the conflicting height onwards can no longer be trusted
+		let height = match client_message {
+			NearClientMessage::Header(header) => header.height(),
+			NearClientMessage::Misbehaviour(misbehaviour) => misbehaviour.block_one.get_height(),
+		};
+		let frozen_height = height.min(client_state.latest_height());
+		Ok(client_state.with_frozen_height(frozen_height))
 	}
 
 	fn check_for_misbehaviour<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
-		_client_id: ClientId,
-		_client_state: Self::ClientState,
-		_client_message: Self::ClientMessage,
+		ctx: &Ctx,
+		client_id: ClientId,
+		client_state: Self::ClientState,
+		client_message: Self::ClientMessage,
 	) -> Result<bool, Error> {
-		Ok(false)
+		let header = match client_message {
+			NearClientMessage::Header(header) => header,
+			NearClientMessage::Misbehaviour(_) => return Ok(true),
+		};
+
+		// a header that conflicts with a consensus state we already have
+		let consensus_state = ConsensusState::from_header(header.get_light_client_block_view())?;
+		match ctx.maybe_consensus_state(&client_id, header.height())? {
+			Some(cs) => {
+				let cs: ConsensusState = cs
+					.downcast()
+					.ok_or(Error::client_args_type_mismatch(client_state.client_type()))?;
+				Ok(cs != consensus_state)
+			},
+			None => Ok(false),
+		}
 	}
 
+	/// The upgraded client and consensus states must be committed by the IBC contract under the
+	/// upgrade paths of the latest height of the client.
 	fn verify_upgrade_and_update_state<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
-		_client_id: ClientId,
-		_old_client_state: &Self::ClientState,
-		_upgrade_client_state: &Self::ClientState,
-		_upgrade_consensus_state: &Self::ConsensusState,
-		_proof_upgrade_client: Vec<u8>,
-		_proof_upgrade_consensus_state: Vec<u8>,
+		ctx: &Ctx,
+		client_id: ClientId,
+		old_client_state: &Self::ClientState,
+		upgrade_client_state: &Self::ClientState,
+		upgrade_consensus_state: &Self::ConsensusState,
+		proof_upgrade_client: Vec<u8>,
+		proof_upgrade_consensus_state: Vec<u8>,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		todo!()
+		let height = old_client_state.latest_height();
+		let consensus_state = ctx
+			.consensus_state(&client_id, height)?
+			.downcast::<Self::ConsensusState>()
+			.ok_or(Error::client_args_type_mismatch(old_client_state.client_type()))?;
+		if old_client_state.upgrade_prefix.is_empty() {
+			return Err(NearError::invalid_proof(
+				"cannot upgrade client, no upgrade prefix set".to_string(),
+			)
+			.into())
+		}
+		let to_proof = |proof: Vec<u8>| {
+			CommitmentProofBytes::try_from(proof)
+				.map_err(|e| Error::from(NearError::invalid_proof(e.to_string())))
+		};
+
+		let client_value = Ctx::AnyClientState::wrap(upgrade_client_state)
+			.expect("AnyClientState is type-checked; qed")
+			.encode_to_vec()
+			.map_err(Error::encode)?;
+		verify_membership::<H, _>(
+			consensus_state.root(),
+			&old_client_state.ibc_account_id,
+			&old_client_state.upgrade_prefix,
+			ClientUpgradePath::UpgradedClientState(height.revision_height),
+			&to_proof(proof_upgrade_client)?,
+			&client_value,
+		)?;
+
+		let wrapped_consensus_state = Ctx::AnyConsensusState::wrap(upgrade_consensus_state)
+			.expect("AnyConsensusState is type-checked; qed");
+		let consensus_value = wrapped_consensus_state.encode_to_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(
+			consensus_state.root(),
+			&old_client_state.ibc_account_id,
+			&old_client_state.upgrade_prefix,
+			ClientUpgradePath::UpgradedClientConsensusState(height.revision_height),
+			&to_proof(proof_upgrade_consensus_state)?,
+			&consensus_value,
+		)?;
+
+		Ok((
+			NearClientState { frozen_height: None, ..upgrade_client_state.clone() },
+			ConsensusUpdateResult::Single(wrapped_consensus_state),
+		))
 	}
 
+	/// Will try to update the client with the state of the substitute.
+	///
+	/// The substitute must track the same chain and IBC contract as the subject and be ahead of
+	/// it, the subject is unfrozen with the head and the block producers of the substitute.
 	fn check_substitute_and_update_state<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_subject_client_id: ClientId,
-		_substitute_client_id: ClientId,
-		_old_client_state: Self::ClientState,
-		_substitute_client_state: Self::ClientState,
+		substitute_client_id: ClientId,
+		old_client_state: Self::ClientState,
+		substitute_client_state: Self::ClientState,
 	) -> Result<(Self::ClientState, ConsensusUpdateResult<Ctx>), Error> {
-		todo!()
+		if substitute_client_state.chain_id != old_client_state.chain_id ||
+			substitute_client_state.ibc_account_id != old_client_state.ibc_account_id
+		{
+			Err(NearError::invalid_substitute(
+				"Subject and substitute track different chains".to_string(),
+			))?
+		}
+		if substitute_client_state.latest_height() <= old_client_state.latest_height() {
+			Err(NearError::invalid_substitute(format!(
+				"Substitute height {} is not greater than the subject height {}",
+				substitute_client_state.latest_height(),
+				old_client_state.latest_height()
+			)))?
+		}
+
+		let consensus_state =
+			ctx.consensus_state(&substitute_client_id, substitute_client_state.latest_height())?;
+		let client_state = NearClientState { frozen_height: None, ..substitute_client_state };
+
+		Ok((client_state, ConsensusUpdateResult::Single(consensus_state)))
 	}
 
 	fn verify_client_consensus_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_client_id: &ClientId,
-		_consensus_height: Height,
-		_expected_consensus_state: &Ctx::AnyConsensusState,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		client_id: &ClientId,
+		consensus_height: Height,
+		expected_consensus_state: &Ctx::AnyConsensusState,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ClientConsensusStatePath {
+			client_id: client_id.clone(),
+			epoch: consensus_height.revision_number,
+			height: consensus_height.revision_height,
+		};
+		let value = expected_consensus_state.encode_to_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			prefix.as_bytes(),
+			path,
+			proof,
+			&value,
+		)?;
+		Ok(())
 	}
 
-	// Consensus state will be verified in the verification functions  before these are called
 	fn verify_connection_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_connection_id: &ConnectionId,
-		_expected_connection_end: &ConnectionEnd,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		connection_id: &ConnectionId,
+		expected_connection_end: &ConnectionEnd,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ConnectionsPath(connection_id.clone());
+		let value = expected_connection_end.encode_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			prefix.as_bytes(),
+			path,
+			proof,
+			&value,
+		)?;
+		Ok(())
 	}
 
 	fn verify_channel_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_expected_channel_end: &ChannelEnd,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		expected_channel_end: &ChannelEnd,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ChannelEndsPath(port_id.clone(), *channel_id);
+		let value = expected_channel_end.encode_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			prefix.as_bytes(),
+			path,
+			proof,
+			&value,
+		)?;
+		Ok(())
 	}
 
-	fn verify_client_full_state<Ctx: ReaderContext>(
+	fn verify_channel_upgrade<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_prefix: &CommitmentPrefix,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
 		_client_id: &ClientId,
-		_expected_client_state: &Ctx::AnyClientState,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		upgrade: &Upgrade,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ChannelUpgradePath::Upgrade(port_id.clone(), *channel_id);
+		let value = upgrade.encode_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			prefix.as_bytes(),
+			path,
+			proof,
+			&value,
+		)?;
+		Ok(())
 	}
 
-	fn verify_packet_data<Ctx: ReaderContext>(
+	fn verify_channel_upgrade_error<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
-		_commitment: PacketCommitment,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		error_receipt: &ErrorReceipt,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		let path = ChannelUpgradePath::UpgradeError(port_id.clone(), *channel_id);
+		let value = error_receipt.encode_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			prefix.as_bytes(),
+			path,
+			proof,
+			&value,
+		)?;
+		Ok(())
 	}
 
-	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+	fn verify_client_full_state<Ctx: ReaderContext>(
 		&self,
 		_ctx: &Ctx,
+		client_state: &Self::ClientState,
+		height: Height,
+		prefix: &CommitmentPrefix,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		client_id: &ClientId,
+		expected_client_state: &Ctx::AnyClientState,
+	) -> Result<(), Error> {
+		client_state.verify_height(height)?;
+		let path = ClientStatePath(client_id.clone());
+		let value = expected_client_state.encode_to_vec().map_err(Error::encode)?;
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			prefix.as_bytes(),
+			path,
+			proof,
+			&value,
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_data<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
-		_ack: AcknowledgementCommitment,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		commitment: PacketCommitment,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end)
+			.map_err(|e| Error::implementation_specific(e.to_string()))?;
+
+		let commitment_path =
+			CommitmentsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			connection_end.counterparty().prefix().as_bytes(),
+			commitment_path,
+			proof,
+			&commitment.into_vec(),
+		)?;
+		Ok(())
+	}
+
+	fn verify_packet_acknowledgement<Ctx: ReaderContext>(
+		&self,
+		ctx: &Ctx,
+		_client_id: &ClientId,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
+		ack: AcknowledgementCommitment,
+	) -> Result<(), Error> {
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end)
+			.map_err(|e| Error::implementation_specific(e.to_string()))?;
+
+		let ack_path = AcksPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			connection_end.counterparty().prefix().as_bytes(),
+			ack_path,
+			proof,
+			&ack.into_vec(),
+		)?;
+		Ok(())
 	}
 
 	fn verify_next_sequence_recv<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end)
+			.map_err(|e| Error::implementation_specific(e.to_string()))?;
+
+		// the contract stores the sequence as borsh, like the rest of its state
+		let seq_bytes = u64::from(sequence)
+			.try_to_vec()
+			.map_err(|_| Error::from(NearError::serialization_error()))?;
+		let seq_path = SeqRecvsPath(port_id.clone(), *channel_id);
+		verify_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			connection_end.counterparty().prefix().as_bytes(),
+			seq_path,
+			proof,
+			&seq_bytes,
+		)?;
+		Ok(())
 	}
 
 	fn verify_packet_receipt_absence<Ctx: ReaderContext>(
 		&self,
-		_ctx: &Ctx,
+		ctx: &Ctx,
 		_client_id: &ClientId,
-		_client_state: &Self::ClientState,
-		_height: Height,
-		_connection_end: &ConnectionEnd,
-		_proof: &CommitmentProofBytes,
-		_root: &CommitmentRoot,
-		_port_id: &PortId,
-		_channel_id: &ChannelId,
-		_sequence: Sequence,
+		client_state: &Self::ClientState,
+		height: Height,
+		connection_end: &ConnectionEnd,
+		proof: &CommitmentProofBytes,
+		root: &CommitmentRoot,
+		port_id: &PortId,
+		channel_id: &ChannelId,
+		sequence: Sequence,
 	) -> Result<(), Error> {
-		todo!()
+		client_state.verify_height(height)?;
+		verify_delay_passed::<H, _>(ctx, height, connection_end)
+			.map_err(|e| Error::implementation_specific(e.to_string()))?;
+
+		let receipt_path =
+			ReceiptsPath { port_id: port_id.clone(), channel_id: *channel_id, sequence };
+		verify_non_membership::<H, _>(
+			root,
+			&client_state.ibc_account_id,
+			connection_end.counterparty().prefix().as_bytes(),
+			receipt_path,
+			proof,
+		)?;
+		Ok(())
 	}
 }
 
-/// validates a light block that's contained on the `NearHeader` based on the current
-/// state of the light client.
 pub fn validate_light_block<H: HostFunctionsTrait>(
 	header: &NearHeader,
 	client_state: NearClientState<H>,
-) -> Result<(), Error>
-where
-{
+) -> Result<(), Error> {
 	//The light client updates its head with the information from LightClientBlockView iff:
 
 	// 1. The height of the block is higher than the height of the current head;
@@ -365,13 +563,8 @@ where
 	// stake (see next section). 6. If next_bps is not none, sha256(borsh(next_bps)) corresponds to
 	// the next_bp_hash in inner_lite.
 
-	// QUESTION: do we also want to pass the block hash received from the RPC?
-	// it's not on the spec, but it's an extra validation
-
 	let new_block_view = header.get_light_client_block_view();
 	let current_block_view = client_state.get_head();
-	let (_current_block_hash, _next_block_hash, approval_message) =
-		reconstruct_light_client_block_view_fields::<H>(new_block_view)?;
 
 	// (1)
 	if new_block_view.inner_lite.height <= current_block_view.inner_lite.height {
@@ -393,30 +586,89 @@ where
 	}
 
 	//  (4) and (5)
-	let mut total_stake = 0;
-	let mut approved_stake = 0;
+	verify_approvals::<H>(&client_state, new_block_view)?;
+
+	// # (6)
+	if let Some(next_bps) = new_block_view.next_bps.as_deref() {
+		let next_bps_serialized = next_bps
+			.try_to_vec()
+			.map_err(|_| Error::from(NearError::serialization_error()))?;
+		if H::sha256_digest(next_bps_serialized.as_ref()).as_slice() !=
+			new_block_view.inner_lite.next_bp_hash.as_ref()
+		{
+			return Err(NearError::invalid_next_block_producers_hash().into())
+		}
+	}
+	Ok(())
+}
+
+/// Two distinct blocks at the same height, each approved by more than 2/3 of the stake of a
+/// known epoch, prove that the block producers equivocated.
+pub fn validate_misbehaviour<H: HostFunctionsTrait>(
+	misbehaviour: &NearMisbehaviour,
+	client_state: &NearClientState<H>,
+) -> Result<(), Error> {
+	let NearMisbehaviour { block_one, block_two } = misbehaviour;
+	if block_one.inner_lite.height != block_two.inner_lite.height {
+		Err(NearError::invalid_misbehaviour("Blocks are at different heights".to_string()))?
+	}
+	if block_one.current_block_hash::<H>() == block_two.current_block_hash::<H>() {
+		Err(NearError::invalid_misbehaviour("Blocks are identical".to_string()))?
+	}
+
+	verify_approvals::<H>(client_state, block_one)?;
+	verify_approvals::<H>(client_state, block_two)?;
+	Ok(())
+}
+
+/// Checks that the approvals of the block are valid signatures of the block producers of its
+/// epoch, which hold more than 2/3 of the stake.
+pub fn verify_approvals<H: HostFunctionsTrait>(
+	client_state: &NearClientState<H>,
+	block_view: &LightClientBlockView,
+) -> Result<(), Error> {
+	let (_current_block_hash, _next_block_hash, approval_message) =
+		reconstruct_light_client_block_view_fields::<H>(block_view)?;
 
 	let epoch_block_producers = client_state
-		.get_validators_by_epoch(&new_block_view.inner_lite.epoch_id)
-		.ok_or_else(|| Error::from(NearError::invalid_epoch(new_block_view.inner_lite.epoch_id)))?;
+		.get_validators_by_epoch(&block_view.inner_lite.epoch_id)
+		.ok_or_else(|| Error::from(NearError::invalid_epoch(block_view.inner_lite.epoch_id)))?;
+
+	if block_view.approvals_after_next.len() != epoch_block_producers.len() {
+		return Err(NearError::invalid_approvals_length(
+			epoch_block_producers.len(),
+			block_view.approvals_after_next.len(),
+		)
+		.into())
+	}
+
+	// the threshold is relative to the stake of all the block producers of the epoch, not only
+	// of the ones that approved the block
+	let total_stake: u128 = epoch_block_producers
+		.iter()
+		.map(|block_producer| block_producer.clone().into_validator_stake().stake)
+		.sum();
+	let mut approved_stake = 0;
 
 	for (maybe_signature, block_producer) in
-		new_block_view.approvals_after_next.iter().zip(epoch_block_producers.iter())
+		block_view.approvals_after_next.iter().zip(epoch_block_producers.iter())
 	{
 		let bp_stake_view = block_producer.clone().into_validator_stake();
 		let bp_stake = bp_stake_view.stake;
-		total_stake += bp_stake;
 
-		if maybe_signature.is_none() {
-			continue
-		}
+		let signature = match maybe_signature {
+			Some(signature) => signature,
+			None => continue,
+		};
 
 		approved_stake += bp_stake;
 
 		let validator_public_key = &bp_stake_view.public_key;
-		let data = H::sha256_digest(&approval_message);
-		let signature = maybe_signature.as_ref().unwrap();
-		if H::ed25519_verify(signature.get_inner(), &data, validator_public_key.get_inner()) {
+		if !H::ed25519_verify(
+			signature.get_inner(),
+			&approval_message,
+			validator_public_key.get_inner(),
+		) {
 			return Err(NearError::invalid_signature().into())
 		}
 	}
@@ -425,21 +677,6 @@ where
 	if approved_stake <= threshold {
 		return Err(NearError::insufficient_staked_amount().into())
 	}
-
-	// # (6)
-	if new_block_view.next_bps.is_some() {
-		let new_block_view_next_bps_serialized = new_block_view
-			.next_bps
-			.as_deref()
-			.unwrap()
-			.try_to_vec()
-			.map_err(|_| Error::from(NearError::serialization_error()))?;
-		if H::sha256_digest(new_block_view_next_bps_serialized.as_ref()).as_slice() !=
-			new_block_view.inner_lite.next_bp_hash.as_ref()
-		{
-			return Err(NearError::serialization_error().into())
-		}
-	}
 	Ok(())
 }
 
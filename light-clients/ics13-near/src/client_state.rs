@@ -13,8 +13,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use super::types::{CryptoHash, LightClientBlockView, ValidatorStakeView};
-use crate::client_def::{HostFunctionsTrait, NearClient};
+use super::types::{AccountId, CryptoHash, LightClientBlockView, ValidatorStakeView};
+use crate::{
+	client_def::{HostFunctionsTrait, NearClient},
+	error::Error,
+	proto::ClientState as RawClientState,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use core::{marker::PhantomData, time::Duration};
 use ibc::{
 	core::{
 		ics02_client::client_state::{ClientState, ClientType},
@@ -23,19 +29,31 @@ use ibc::{
 	prelude::*,
 	Height,
 };
+use ibc_proto::google::protobuf::Any;
 use serde::{Deserialize, Serialize};
-use std::{marker::PhantomData, time::Duration};
 use tendermint_proto::Protobuf;
 
+/// Protobuf type url for NEAR ClientState
+pub const NEAR_CLIENT_STATE_TYPE_URL: &str = "/ibc.lightclients.near.v1.ClientState";
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct NearClientState<H> {
-	chain_id: ChainId,
-	head: LightClientBlockView,
-	current_epoch: CryptoHash,
-	next_epoch: CryptoHash,
-	current_validators: Vec<ValidatorStakeView>,
-	next_validators: Vec<ValidatorStakeView>,
-	_phantom: PhantomData<H>,
+	pub chain_id: ChainId,
+	/// The latest block accepted by the light client
+	pub head: LightClientBlockView,
+	pub current_epoch: CryptoHash,
+	pub next_epoch: CryptoHash,
+	/// Block producers of `current_epoch`, in the order of the approvals of its blocks
+	pub current_validators: Vec<ValidatorStakeView>,
+	/// Block producers of `next_epoch`, in the order of the approvals of its blocks
+	pub next_validators: Vec<ValidatorStakeView>,
+	/// The NEAR account of the IBC contract, whose storage the state proofs are checked against
+	pub ibc_account_id: AccountId,
+	/// Prefix of the keys under which the IBC contract stores the upgraded client and consensus
+	/// states, the client can't be upgraded if it's empty
+	pub upgrade_prefix: Vec<u8>,
+	pub frozen_height: Option<Height>,
+	pub _phantom: PhantomData<H>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -58,6 +76,47 @@ impl<H: HostFunctionsTrait> NearClientState<H> {
 	pub fn get_head(&self) -> &LightClientBlockView {
 		&self.head
 	}
+
+	/// Moves the head to `block_view`, rotating the block producers if the block is the first
+	/// one of the next epoch. The block must have been validated against this state.
+	pub fn with_head(mut self, block_view: LightClientBlockView) -> Self {
+		if block_view.inner_lite.epoch_id == self.next_epoch {
+			self.current_epoch = self.next_epoch;
+			self.current_validators = core::mem::take(&mut self.next_validators);
+		}
+		if let Some(next_bps) = block_view.next_bps.clone() {
+			self.next_epoch = block_view.inner_lite.next_epoch_id;
+			self.next_validators = next_bps;
+		}
+		self.head = block_view;
+		self
+	}
+
+	pub fn with_frozen_height(self, height: Height) -> Self {
+		Self { frozen_height: Some(height), ..self }
+	}
+
+	/// Verification functions can only be called for heights the client knows of, on a client
+	/// that hasn't been frozen at or below that height.
+	pub fn verify_height(&self, height: Height) -> Result<(), Error> {
+		let latest_height = self.head.get_height();
+		if latest_height < height {
+			return Err(Error::insufficient_height(latest_height, height))
+		}
+
+		match self.frozen_height {
+			Some(frozen_height) if frozen_height <= height =>
+				Err(Error::frozen_client(frozen_height)),
+			_ => Ok(()),
+		}
+	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: NEAR_CLIENT_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ClientState"),
+		}
+	}
 }
 
 impl<H: HostFunctionsTrait> ClientState for NearClientState<H> {
@@ -81,26 +140,28 @@ impl<H: HostFunctionsTrait> ClientState for NearClientState<H> {
 	}
 
 	fn frozen_height(&self) -> Option<Height> {
-		// TODO: validate this
-		Some(self.head.get_height())
+		self.frozen_height
 	}
 
 	fn upgrade(
-		self,
+		mut self,
 		_upgrade_height: Height,
 		_upgrade_options: Self::UpgradeOptions,
-		_chain_id: ChainId,
+		chain_id: ChainId,
 	) -> Self {
-		// TODO: validate this -- not sure how to process the given parameters in this case
+		self.chain_id = chain_id;
+		self.frozen_height = None;
 		self
 	}
 
 	fn expired(&self, _elapsed: Duration) -> bool {
-		todo!()
+		// the client stays valid for as long as it is updated at least once per epoch, there is no
+		// trusting period to expire.
+		false
 	}
 
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		todo!("implement encoding")
+		self.encode_vec()
 	}
 }
 
@@ -110,16 +171,51 @@ impl<H> NearClientState<H> {
 	}
 }
 
-impl<H: HostFunctionsTrait> Protobuf<()> for NearClientState<H> {}
-
-impl<H: HostFunctionsTrait> From<NearClientState<H>> for () {
-	fn from(_: NearClientState<H>) -> Self {
-		todo!()
+impl<H: HostFunctionsTrait> Protobuf<RawClientState> for NearClientState<H> {}
+
+impl<H: HostFunctionsTrait> TryFrom<RawClientState> for NearClientState<H> {
+	type Error = Error;
+
+	fn try_from(raw: RawClientState) -> Result<Self, Self::Error> {
+		let decode = |e: borsh::maybestd::io::Error| Error::decode(e.to_string());
+		let frozen_height = (raw.frozen_height != 0).then(|| Height::new(0, raw.frozen_height));
+
+		Ok(Self {
+			chain_id: ChainId::from_string(&raw.chain_id),
+			head: LightClientBlockView::try_from_slice(&raw.head).map_err(decode)?,
+			current_epoch: CryptoHash::try_from_slice(&raw.current_epoch).map_err(decode)?,
+			next_epoch: CryptoHash::try_from_slice(&raw.next_epoch).map_err(decode)?,
+			current_validators: Vec::<ValidatorStakeView>::try_from_slice(&raw.current_validators)
+				.map_err(decode)?,
+			next_validators: Vec::<ValidatorStakeView>::try_from_slice(&raw.next_validators)
+				.map_err(decode)?,
+			ibc_account_id: raw.ibc_account_id,
+			upgrade_prefix: raw.upgrade_prefix,
+			frozen_height,
+			_phantom: PhantomData,
+		})
 	}
 }
 
-impl<H: HostFunctionsTrait> From<()> for NearClientState<H> {
-	fn from(_: ()) -> Self {
-		todo!()
+impl<H: HostFunctionsTrait> From<NearClientState<H>> for RawClientState {
+	fn from(client_state: NearClientState<H>) -> Self {
+		let encode = |result: borsh::maybestd::io::Result<Vec<u8>>| {
+			result.expect("borsh encoding into a vec is infallible; qed")
+		};
+
+		RawClientState {
+			chain_id: client_state.chain_id.to_string(),
+			head: encode(client_state.head.try_to_vec()),
+			current_epoch: client_state.current_epoch.0.to_vec(),
+			next_epoch: client_state.next_epoch.0.to_vec(),
+			current_validators: encode(client_state.current_validators.try_to_vec()),
+			next_validators: encode(client_state.next_validators.try_to_vec()),
+			ibc_account_id: client_state.ibc_account_id,
+			upgrade_prefix: client_state.upgrade_prefix,
+			frozen_height: client_state
+				.frozen_height
+				.map(|height| height.revision_height)
+				.unwrap_or_default(),
+		}
 	}
 }
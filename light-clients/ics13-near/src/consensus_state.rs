@@ -14,6 +14,8 @@
 // limitations under the License.
 
 use super::error::Error;
+use crate::{proto::ConsensusState as RawConsensusState, types::LightClientBlockView};
+use alloc::{string::ToString, vec::Vec};
 use ibc::{
 	core::{
 		ics02_client::client_consensus::{self},
@@ -21,12 +23,37 @@ use ibc::{
 	},
 	timestamp::Timestamp,
 };
+use ibc_proto::google::protobuf::Any;
 use serde::Serialize;
 use tendermint_proto::Protobuf;
 
+/// Protobuf type url for NEAR Consensus State
+pub const NEAR_CONSENSUS_STATE_TYPE_URL: &str = "/ibc.lightclients.near.v1.ConsensusState";
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize)]
 pub struct ConsensusState {
-	commitment_root: CommitmentRoot,
+	/// The `prev_state_root` of the block, i.e. the merkle root of the shard state roots before
+	/// the chunks of this block were applied.
+	pub commitment_root: CommitmentRoot,
+	pub timestamp: Timestamp,
+}
+
+impl ConsensusState {
+	pub fn from_header(block_view: &LightClientBlockView) -> Result<Self, Error> {
+		let timestamp = Timestamp::from_nanoseconds(block_view.inner_lite.timestamp_nanosec)
+			.map_err(|e| Error::decode(e.to_string()))?;
+		Ok(Self {
+			commitment_root: block_view.inner_lite.prev_state_root.0.to_vec().into(),
+			timestamp,
+		})
+	}
+
+	pub fn to_any(&self) -> Any {
+		Any {
+			type_url: NEAR_CONSENSUS_STATE_TYPE_URL.to_string(),
+			value: self.encode_vec().expect("encode ConsensusState"),
+		}
+	}
 }
 
 impl client_consensus::ConsensusState for ConsensusState {
@@ -37,24 +64,34 @@ impl client_consensus::ConsensusState for ConsensusState {
 	}
 
 	fn timestamp(&self) -> Timestamp {
-		todo!()
+		self.timestamp
 	}
 
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		todo!("Encode to vec")
+		self.encode_vec()
 	}
 }
 
-impl Protobuf<()> for ConsensusState {}
+impl Protobuf<RawConsensusState> for ConsensusState {}
+
+impl TryFrom<RawConsensusState> for ConsensusState {
+	type Error = Error;
 
-impl From<ConsensusState> for () {
-	fn from(_: ConsensusState) -> Self {
-		todo!()
+	fn try_from(raw: RawConsensusState) -> Result<Self, Self::Error> {
+		if raw.root.len() != 32 {
+			return Err(Error::decode("invalid commitment root length".to_string()))
+		}
+		let timestamp =
+			Timestamp::from_nanoseconds(raw.timestamp).map_err(|e| Error::decode(e.to_string()))?;
+		Ok(Self { commitment_root: raw.root.into(), timestamp })
 	}
 }
 
-impl From<()> for ConsensusState {
-	fn from(_: ()) -> Self {
-		todo!()
+impl From<ConsensusState> for RawConsensusState {
+	fn from(value: ConsensusState) -> Self {
+		RawConsensusState {
+			root: value.commitment_root.into_vec(),
+			timestamp: value.timestamp.nanoseconds(),
+		}
 	}
 }
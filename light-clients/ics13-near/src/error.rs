@@ -15,8 +15,9 @@
 
 use super::types::CryptoHash;
 use crate::client_state::NearClientState;
+use alloc::string::{String, ToString};
 use flex_error::define_error;
-use ibc::core::ics02_client::error::Error as Ics02Error;
+use ibc::{core::ics02_client::error::Error as Ics02Error, Height};
 
 define_error! {
 	#[derive(Debug, PartialEq, Eq)]
@@ -36,6 +37,12 @@ define_error! {
 		| _ | { format_args!(
 			"insufficient staked amount")
 		},
+		InvalidApprovalsLength
+		{ expected: usize, given: usize }
+		| e | { format_args!(
+			"expected {} approvals, one per block producer, got {}",
+			e.expected, e.given)
+		},
 		SerializationError
 		| _ | { format_args!(
 			"serialization error")
@@ -44,11 +51,46 @@ define_error! {
 		| _ | { format_args!(
 			"unavailable block producers")
 		},
+		InvalidNextBlockProducersHash
+		| _ | { format_args!(
+			"next block producers don't match the next_bp_hash of the block")
+		},
+		InsufficientHeight
+		{ known_height: Height, given_height: Height }
+		| e | { format_args!(
+			"insufficient height, known height: {}, given height: {}",
+			e.known_height, e.given_height)
+		},
+		FrozenClient
+		{ frozen_height: Height }
+		| e | { format_args!(
+			"client has been frozen at height {}", e.frozen_height)
+		},
+		InvalidProof
+		{ reason: String }
+		| e | { format_args!(
+			"invalid state proof: {}", e.reason)
+		},
+		InvalidMisbehaviour
+		{ reason: String }
+		| e | { format_args!(
+			"invalid misbehaviour: {}", e.reason)
+		},
+		InvalidSubstitute
+		{ reason: String }
+		| e | { format_args!(
+			"invalid substitute client: {}", e.reason)
+		},
+		Decode
+		{ reason: String }
+		| e | { format_args!(
+			"failed to decode: {}", e.reason)
+		},
 	}
 }
 
 impl From<Error> for Ics02Error {
 	fn from(e: Error) -> Self {
-		Ics02Error::client_error(NearClientState::<()>::client_type().to_owned(), e.to_string())
+		Ics02Error::client_error(NearClientState::<()>::client_type(), e.to_string())
 	}
 }
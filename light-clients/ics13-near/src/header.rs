@@ -13,10 +13,27 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use alloc::{string::ToString, vec::Vec};
+use borsh::{BorshDeserialize, BorshSerialize};
 use ibc::{core::ics02_client::client_message::ClientMessage, Height};
+use ibc_proto::google::protobuf::Any;
 use tendermint_proto::Protobuf;
 
-use super::types::LightClientBlockView;
+use super::{
+	error::Error,
+	proto::{
+		client_message, ClientMessage as RawClientMessage, Header as RawHeader,
+		Misbehaviour as RawMisbehaviour,
+	},
+	types::LightClientBlockView,
+};
+
+/// Protobuf type url for NEAR ClientMessage
+pub const NEAR_CLIENT_MESSAGE_TYPE_URL: &str = "/ibc.lightclients.near.v1.ClientMessage";
+/// Protobuf type url for NEAR Header
+pub const NEAR_HEADER_TYPE_URL: &str = "/ibc.lightclients.near.v1.Header";
+/// Protobuf type url for NEAR Misbehaviour
+pub const NEAR_MISBEHAVIOUR_TYPE_URL: &str = "/ibc.lightclients.near.v1.Misbehaviour";
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub struct NearHeader {
@@ -24,40 +41,136 @@ pub struct NearHeader {
 }
 
 impl NearHeader {
+	pub fn new(inner: LightClientBlockView) -> Self {
+		Self { inner }
+	}
+
 	pub fn get_light_client_block_view(&self) -> &LightClientBlockView {
 		&self.inner
 	}
 
+	pub fn into_light_client_block_view(self) -> LightClientBlockView {
+		self.inner
+	}
+
 	pub fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		unimplemented!()
+		self.encode_vec()
 	}
 
 	pub fn height(&self) -> Height {
-		todo!()
+		self.inner.get_height()
 	}
 }
 
-impl Protobuf<()> for NearHeader {}
+impl Protobuf<RawHeader> for NearHeader {}
+
+impl TryFrom<RawHeader> for NearHeader {
+	type Error = Error;
 
-impl From<NearHeader> for () {
-	fn from(_: NearHeader) -> Self {
-		todo!()
+	fn try_from(raw: RawHeader) -> Result<Self, Self::Error> {
+		let inner = LightClientBlockView::try_from_slice(&raw.light_client_block)
+			.map_err(|e| Error::decode(e.to_string()))?;
+		Ok(Self { inner })
 	}
 }
 
-impl From<()> for NearHeader {
-	fn from(_: ()) -> Self {
-		todo!()
+impl From<NearHeader> for RawHeader {
+	fn from(header: NearHeader) -> Self {
+		RawHeader {
+			light_client_block: header
+				.inner
+				.try_to_vec()
+				.expect("borsh encoding into a vec is infallible; qed"),
+		}
+	}
+}
+
+/// Two blocks at the same height, both approved by enough stake of a known epoch.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NearMisbehaviour {
+	pub block_one: LightClientBlockView,
+	pub block_two: LightClientBlockView,
+}
+
+impl Protobuf<RawMisbehaviour> for NearMisbehaviour {}
+
+impl TryFrom<RawMisbehaviour> for NearMisbehaviour {
+	type Error = Error;
+
+	fn try_from(raw: RawMisbehaviour) -> Result<Self, Self::Error> {
+		let decode = |bytes: &[u8]| {
+			LightClientBlockView::try_from_slice(bytes).map_err(|e| Error::decode(e.to_string()))
+		};
+		Ok(Self { block_one: decode(&raw.block_one)?, block_two: decode(&raw.block_two)? })
+	}
+}
+
+impl From<NearMisbehaviour> for RawMisbehaviour {
+	fn from(misbehaviour: NearMisbehaviour) -> Self {
+		let encode = |block: LightClientBlockView| {
+			block.try_to_vec().expect("borsh encoding into a vec is infallible; qed")
+		};
+		RawMisbehaviour {
+			block_one: encode(misbehaviour.block_one),
+			block_two: encode(misbehaviour.block_two),
+		}
 	}
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum NearClientMessage {
 	Header(NearHeader),
+	Misbehaviour(NearMisbehaviour),
 }
 
 impl ClientMessage for NearClientMessage {
 	fn encode_to_vec(&self) -> Result<Vec<u8>, tendermint_proto::Error> {
-		todo!()
+		self.encode_vec()
+	}
+}
+
+impl Protobuf<RawClientMessage> for NearClientMessage {}
+
+impl TryFrom<RawClientMessage> for NearClientMessage {
+	type Error = Error;
+
+	fn try_from(raw: RawClientMessage) -> Result<Self, Self::Error> {
+		let message = match raw.message.ok_or_else(|| {
+			Error::decode("Must supply either Header or Misbehaviour type!".to_string())
+		})? {
+			client_message::Message::Header(raw_header) =>
+				NearClientMessage::Header(NearHeader::try_from(raw_header)?),
+			client_message::Message::Misbehaviour(raw_misbehaviour) =>
+				NearClientMessage::Misbehaviour(NearMisbehaviour::try_from(raw_misbehaviour)?),
+		};
+
+		Ok(message)
+	}
+}
+
+impl From<NearClientMessage> for RawClientMessage {
+	fn from(client_message: NearClientMessage) -> Self {
+		match client_message {
+			NearClientMessage::Header(header) =>
+				RawClientMessage { message: Some(client_message::Message::Header(header.into())) },
+			NearClientMessage::Misbehaviour(misbehaviour) => RawClientMessage {
+				message: Some(client_message::Message::Misbehaviour(misbehaviour.into())),
+			},
+		}
+	}
+}
+
+impl From<NearClientMessage> for Any {
+	fn from(client_message: NearClientMessage) -> Self {
+		match client_message {
+			NearClientMessage::Header(header) => Any {
+				type_url: NEAR_HEADER_TYPE_URL.to_string(),
+				value: header.encode_vec().expect("encode Header"),
+			},
+			NearClientMessage::Misbehaviour(misbehaviour) => Any {
+				type_url: NEAR_MISBEHAVIOUR_TYPE_URL.to_string(),
+				value: misbehaviour.encode_vec().expect("encode Misbehaviour"),
+			},
+		}
 	}
 }
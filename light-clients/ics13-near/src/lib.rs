@@ -13,8 +13,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+#![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::all)]
 
+//! ICS-13: NEAR IBC light client protocol implementation
+//!
+//! The client follows the NEAR light client spec: it tracks the last accepted
+//! `LightClientBlockView` together with the block producers of the current and the next epoch,
+//! and verifies IBC state against NEAR state trie proofs of the account hosting the IBC contract.
+
 extern crate alloc;
 
 pub mod client_def;
@@ -22,4 +29,9 @@ pub mod client_state;
 pub mod consensus_state;
 pub mod error;
 pub mod header;
+pub mod proof;
+pub mod proto;
 pub mod types;
+
+#[cfg(test)]
+mod tests;
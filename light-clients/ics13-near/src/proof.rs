@@ -0,0 +1,279 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Verification of NEAR state proofs.
+//!
+//! The IBC contract stores every commitment under the key `prefix ++ path`. A proof of such a key
+//! consists of the state root of the shard holding the contract account, the merkle path of that
+//! root to the `prev_state_root` of the block and the trie nodes on the way to the key, as
+//! returned by the `view_state` RPC with `include_proof`.
+
+use crate::{
+	client_def::HostFunctions,
+	error::Error,
+	types::{CryptoHash, Direction, MerklePathItem},
+};
+use alloc::{collections::BTreeMap, format, string::ToString, vec::Vec};
+use borsh::{maybestd::io, BorshDeserialize, BorshSerialize};
+use ibc::core::{
+	ics23_commitment::commitment::{CommitmentProofBytes, CommitmentRoot},
+	ics24_host::path::Path,
+};
+
+/// Trie column of contract storage.
+pub const CONTRACT_DATA_COLUMN: u8 = 9;
+/// Separates the account id from the storage key in contract storage trie keys.
+pub const ACCOUNT_DATA_SEPARATOR: u8 = b',';
+
+/// A proof of a key in the storage of the IBC contract.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct StateProof {
+	/// State root of the shard holding the IBC contract account.
+	pub shard_state_root: CryptoHash,
+	/// Merkle path from `shard_state_root` to the `prev_state_root` of the block.
+	pub shard_proof: Vec<MerklePathItem>,
+	/// Borsh encoded trie nodes visited while looking up the key.
+	pub nodes: Vec<Vec<u8>>,
+}
+
+/// Reference to a value stored in the trie.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct ValueRef {
+	pub length: u32,
+	pub hash: CryptoHash,
+}
+
+/// The children of a branch node, encoded as a bitmap of the present children followed by their
+/// hashes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Children(pub [Option<CryptoHash>; 16]);
+
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub enum RawTrieNode {
+	Leaf(Vec<u8>, ValueRef),
+	BranchNoValue(Children),
+	BranchWithValue(ValueRef, Children),
+	Extension(Vec<u8>, CryptoHash),
+}
+
+/// A trie node as it is stored and hashed by NEAR.
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct RawTrieNodeWithSize {
+	pub node: RawTrieNode,
+	pub memory_usage: u64,
+}
+
+impl BorshSerialize for Children {
+	fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+		let bitmap = self
+			.0
+			.iter()
+			.enumerate()
+			.filter(|(_, child)| child.is_some())
+			.fold(0u16, |bitmap, (index, _)| bitmap | (1 << index));
+		BorshSerialize::serialize(&bitmap, writer)?;
+		for child in self.0.iter().flatten() {
+			BorshSerialize::serialize(child, writer)?;
+		}
+		Ok(())
+	}
+}
+
+impl BorshDeserialize for Children {
+	fn deserialize_reader<R: io::Read>(rd: &mut R) -> io::Result<Self> {
+		let bitmap = u16::deserialize_reader(rd)?;
+		let mut children = Children::default();
+		for (index, child) in children.0.iter_mut().enumerate() {
+			if bitmap & (1 << index) != 0 {
+				*child = Some(CryptoHash::deserialize_reader(rd)?);
+			}
+		}
+		Ok(children)
+	}
+}
+
+/// The key of `storage_key` in the storage of `account_id` in the state trie.
+pub fn contract_data_key(account_id: &str, storage_key: &[u8]) -> Vec<u8> {
+	[&[CONTRACT_DATA_COLUMN], account_id.as_bytes(), &[ACCOUNT_DATA_SEPARATOR], storage_key]
+		.concat()
+}
+
+/// Splits `bytes` into nibbles, most significant first.
+pub fn to_nibbles(bytes: &[u8]) -> Vec<u8> {
+	bytes.iter().flat_map(|byte| [byte >> 4, byte & 0x0f]).collect()
+}
+
+/// Decodes the nibbles of a leaf or extension key. The high nibble of the first byte holds the
+/// flags, if the key has an odd number of nibbles the first one is stored in its low nibble.
+pub fn decode_key_nibbles(encoded: &[u8]) -> Vec<u8> {
+	match encoded.first() {
+		Some(first) => to_nibbles(encoded).split_off(if first & 0x10 != 0 { 1 } else { 2 }),
+		None => Vec::new(),
+	}
+}
+
+/// Encodes `nibbles` as the key of a leaf or extension node.
+pub fn encode_key_nibbles(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+	let flag = if is_leaf { 0x20 } else { 0 };
+	let (first, rest) = if nibbles.len() % 2 == 1 {
+		(flag | 0x10 | nibbles[0], &nibbles[1..])
+	} else {
+		(flag, nibbles)
+	};
+	let mut encoded = Vec::with_capacity(1 + rest.len() / 2);
+	encoded.push(first);
+	encoded.extend(rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]));
+	encoded
+}
+
+/// Merkle root of a single item in NEAR's merklization of borsh items.
+pub fn compute_root_from_path<H: HostFunctions>(
+	path: &[MerklePathItem],
+	item_hash: CryptoHash,
+) -> CryptoHash {
+	path.iter().fold(item_hash, |hash, item| {
+		let pair = match item.direction {
+			Direction::Left => [item.hash.0, hash.0],
+			Direction::Right => [hash.0, item.hash.0],
+		};
+		CryptoHash(H::sha256_digest(&pair.concat()))
+	})
+}
+
+/// Looks up `key` in the trie with the given `root`, returning the reference to its value if the
+/// key is present. Errors if the proof is missing a node on the way to the key.
+pub fn lookup<H: HostFunctions>(
+	root: CryptoHash,
+	nodes: &[Vec<u8>],
+	key: &[u8],
+) -> Result<Option<ValueRef>, Error> {
+	let nodes = nodes
+		.iter()
+		.map(|node| {
+			let decoded = RawTrieNodeWithSize::try_from_slice(node)
+				.map_err(|e| Error::invalid_proof(format!("Failed to decode trie node: {e}")))?;
+			Ok((CryptoHash(H::sha256_digest(node)), decoded.node))
+		})
+		.collect::<Result<BTreeMap<_, _>, Error>>()?;
+
+	let nibbles = to_nibbles(key);
+	let mut key = &nibbles[..];
+	let mut hash = root;
+	// the root of an empty trie
+	if hash == CryptoHash([0u8; 32]) {
+		return Ok(None)
+	}
+
+	loop {
+		let node = nodes
+			.get(&hash)
+			.ok_or_else(|| Error::invalid_proof(format!("Missing trie node {:?}", hash.0)))?;
+		match node {
+			RawTrieNode::Leaf(encoded, value) => {
+				let leaf_key = decode_key_nibbles(encoded);
+				return Ok((leaf_key == key).then(|| value.clone()))
+			},
+			RawTrieNode::Extension(encoded, child) => {
+				let extension = decode_key_nibbles(encoded);
+				if !key.starts_with(&extension) {
+					return Ok(None)
+				}
+				key = &key[extension.len()..];
+				hash = *child;
+			},
+			RawTrieNode::BranchNoValue(children) | RawTrieNode::BranchWithValue(_, children) =>
+				match key.split_first() {
+					Some((index, rest)) => match children.0[*index as usize] {
+						Some(child) => {
+							key = rest;
+							hash = child;
+						},
+						None => return Ok(None),
+					},
+					None =>
+						return Ok(match node {
+							RawTrieNode::BranchWithValue(value, _) => Some(value.clone()),
+							_ => None,
+						}),
+				},
+		}
+	}
+}
+
+/// Checks the shard proof against the block's state root and looks up the contract storage key
+/// of `path` in the shard trie.
+fn read_value<H: HostFunctions>(
+	root: &CommitmentRoot,
+	account_id: &str,
+	prefix: &[u8],
+	path: Path,
+	proof: &CommitmentProofBytes,
+) -> Result<Option<ValueRef>, Error> {
+	let proof = StateProof::try_from_slice(proof.as_bytes())
+		.map_err(|e| Error::invalid_proof(format!("Failed to decode proof for {path}: {e}")))?;
+	let state_root = CryptoHash::try_from(root.as_bytes()).map_err(|_| {
+		Error::invalid_proof(format!("invalid commitment root length: {}", root.as_bytes().len()))
+	})?;
+
+	let shard_state_root_hash = CryptoHash(H::sha256_digest(&proof.shard_state_root.0));
+	if compute_root_from_path::<H>(&proof.shard_proof, shard_state_root_hash) != state_root {
+		return Err(Error::invalid_proof("Invalid shard state root proof".to_string()))
+	}
+
+	let storage_key = [prefix, path.to_string().as_bytes()].concat();
+	lookup::<H>(proof.shard_state_root, &proof.nodes, &contract_data_key(account_id, &storage_key))
+}
+
+/// Verifies that `value` is stored under `prefix ++ path` in the storage of `account_id`.
+pub fn verify_membership<H, P>(
+	root: &CommitmentRoot,
+	account_id: &str,
+	prefix: &[u8],
+	path: P,
+	proof: &CommitmentProofBytes,
+	value: &[u8],
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let path = path.into();
+	let value_ref = read_value::<H>(root, account_id, prefix, path.clone(), proof)?
+		.ok_or_else(|| Error::invalid_proof(format!("No value found for {path}")))?;
+
+	if value_ref.length as usize != value.len() || value_ref.hash.0 != H::sha256_digest(value) {
+		return Err(Error::invalid_proof(format!("Value mismatch for {path}")))
+	}
+	Ok(())
+}
+
+/// Verifies that nothing is stored under `prefix ++ path` in the storage of `account_id`.
+pub fn verify_non_membership<H, P>(
+	root: &CommitmentRoot,
+	account_id: &str,
+	prefix: &[u8],
+	path: P,
+	proof: &CommitmentProofBytes,
+) -> Result<(), Error>
+where
+	H: HostFunctions,
+	P: Into<Path>,
+{
+	let path = path.into();
+	if read_value::<H>(root, account_id, prefix, path.clone(), proof)?.is_some() {
+		return Err(Error::invalid_proof(format!("Found a value for {path}")))
+	}
+	Ok(())
+}
@@ -0,0 +1,87 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Protobuf envelopes of the NEAR client types. NEAR's own data structures are carried as their
+//! borsh encoding, the same encoding NEAR uses to hash and sign them.
+
+use alloc::{string::String, vec::Vec};
+
+#[derive(Clone, PartialEq, Eq, ::prost::Message)]
+pub struct ClientState {
+	#[prost(string, tag = "1")]
+	pub chain_id: String,
+	/// borsh encoded `LightClientBlockView`
+	#[prost(bytes = "vec", tag = "2")]
+	pub head: Vec<u8>,
+	#[prost(bytes = "vec", tag = "3")]
+	pub current_epoch: Vec<u8>,
+	#[prost(bytes = "vec", tag = "4")]
+	pub next_epoch: Vec<u8>,
+	/// borsh encoded `Vec<ValidatorStakeView>`
+	#[prost(bytes = "vec", tag = "5")]
+	pub current_validators: Vec<u8>,
+	/// borsh encoded `Vec<ValidatorStakeView>`
+	#[prost(bytes = "vec", tag = "6")]
+	pub next_validators: Vec<u8>,
+	#[prost(string, tag = "7")]
+	pub ibc_account_id: String,
+	/// Zero if the client is not frozen
+	#[prost(uint64, tag = "8")]
+	pub frozen_height: u64,
+	#[prost(bytes = "vec", tag = "9")]
+	pub upgrade_prefix: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Eq, ::prost::Message)]
+pub struct ConsensusState {
+	#[prost(bytes = "vec", tag = "1")]
+	pub root: Vec<u8>,
+	/// Block timestamp in nanoseconds
+	#[prost(uint64, tag = "2")]
+	pub timestamp: u64,
+}
+
+#[derive(Clone, PartialEq, Eq, ::prost::Message)]
+pub struct Header {
+	/// borsh encoded `LightClientBlockView`
+	#[prost(bytes = "vec", tag = "1")]
+	pub light_client_block: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Eq, ::prost::Message)]
+pub struct Misbehaviour {
+	/// borsh encoded `LightClientBlockView`
+	#[prost(bytes = "vec", tag = "1")]
+	pub block_one: Vec<u8>,
+	/// borsh encoded `LightClientBlockView`
+	#[prost(bytes = "vec", tag = "2")]
+	pub block_two: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Eq, ::prost::Message)]
+pub struct ClientMessage {
+	#[prost(oneof = "client_message::Message", tags = "1, 2")]
+	pub message: Option<client_message::Message>,
+}
+
+pub mod client_message {
+	#[derive(Clone, PartialEq, Eq, ::prost::Oneof)]
+	pub enum Message {
+		#[prost(message, tag = "1")]
+		Header(super::Header),
+		#[prost(message, tag = "2")]
+		Misbehaviour(super::Misbehaviour),
+	}
+}
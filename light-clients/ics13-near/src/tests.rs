@@ -0,0 +1,485 @@
+// Copyright (C) 2022 ComposableFi.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	client_def::{
+		reconstruct_light_client_block_view_fields, validate_light_block, validate_misbehaviour,
+		HostFunctions, HostFunctionsTrait,
+	},
+	client_state::NearClientState,
+	header::{NearClientMessage, NearHeader, NearMisbehaviour},
+	proof::{
+		contract_data_key, encode_key_nibbles, to_nibbles, verify_membership,
+		verify_non_membership, Children, RawTrieNode, RawTrieNodeWithSize, StateProof, ValueRef,
+	},
+	types::{
+		BlockHeaderInnerLiteView, CryptoHash, Direction, LightClientBlockView, MerklePathItem,
+		PublicKey, Signature, ValidatorStakeView, ValidatorStakeViewV1,
+	},
+};
+use borsh::BorshSerialize;
+use core::marker::PhantomData;
+use ibc::{
+	core::{
+		ics23_commitment::commitment::{CommitmentProofBytes, CommitmentRoot},
+		ics24_host::{
+			identifier::{ChainId, ConnectionId},
+			path::ConnectionsPath,
+		},
+	},
+	Height,
+};
+use sha2::Digest;
+use sp_core::{ed25519, Pair};
+use tendermint_proto::Protobuf;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct HostFunctionsManager;
+
+impl HostFunctions for HostFunctionsManager {
+	fn ed25519_verify(signature: &[u8; 64], msg: &[u8], pubkey: &[u8]) -> bool {
+		match <[u8; 32]>::try_from(pubkey) {
+			Ok(pubkey) => ed25519::Pair::verify(
+				&ed25519::Signature::from_raw(*signature),
+				msg,
+				&ed25519::Public::from_raw(pubkey),
+			),
+			Err(_) => false,
+		}
+	}
+
+	fn sha256_digest(data: &[u8]) -> [u8; 32] {
+		sha2::Sha256::digest(data).into()
+	}
+}
+
+impl ics23::HostFunctionsProvider for HostFunctionsManager {
+	fn sha2_256(message: &[u8]) -> [u8; 32] {
+		sha2::Sha256::digest(message).into()
+	}
+
+	fn sha2_512(message: &[u8]) -> [u8; 64] {
+		let mut res = [0u8; 64];
+		res.copy_from_slice(&sha2::Sha512::digest(message));
+		res
+	}
+
+	fn sha2_512_truncated(message: &[u8]) -> [u8; 32] {
+		let mut res = [0u8; 32];
+		res.copy_from_slice(&sha2::Sha512::digest(message)[..32]);
+		res
+	}
+
+	fn sha3_512(message: &[u8]) -> [u8; 64] {
+		let mut res = [0u8; 64];
+		res.copy_from_slice(&sha3::Sha3_512::digest(message));
+		res
+	}
+
+	fn ripemd160(message: &[u8]) -> [u8; 20] {
+		ripemd::Ripemd160::digest(message).into()
+	}
+}
+
+impl HostFunctionsTrait for HostFunctionsManager {}
+
+type ClientState = NearClientState<HostFunctionsManager>;
+
+const IBC_ACCOUNT: &str = "ibc.near";
+const EPOCH_ONE: CryptoHash = CryptoHash([1u8; 32]);
+const EPOCH_TWO: CryptoHash = CryptoHash([2u8; 32]);
+const EPOCH_THREE: CryptoHash = CryptoHash([3u8; 32]);
+
+fn sha256(data: &[u8]) -> CryptoHash {
+	CryptoHash(HostFunctionsManager::sha256_digest(data))
+}
+
+fn block_producers(seed: &str) -> Vec<ed25519::Pair> {
+	(0..4)
+		.map(|i| ed25519::Pair::from_string(&format!("//{seed}{i}"), None).unwrap())
+		.collect()
+}
+
+fn stake_views(producers: &[ed25519::Pair]) -> Vec<ValidatorStakeView> {
+	producers
+		.iter()
+		.enumerate()
+		.map(|(i, pair)| {
+			ValidatorStakeView::V1(ValidatorStakeViewV1 {
+				account_id: format!("validator{i}.near"),
+				public_key: PublicKey(pair.public().0),
+				stake: 100,
+			})
+		})
+		.collect()
+}
+
+/// A light client block of `epoch_id` at `height`, approved by `signers`, `None` skips the
+/// approval of the block producer at that index.
+fn light_client_block(
+	height: u64,
+	epoch_id: CryptoHash,
+	next_epoch_id: CryptoHash,
+	next_bps: Option<Vec<ValidatorStakeView>>,
+	signers: &[Option<&ed25519::Pair>],
+) -> LightClientBlockView {
+	let next_bp_hash = next_bps
+		.as_ref()
+		.map(|bps| sha256(&bps.try_to_vec().unwrap()))
+		.unwrap_or(CryptoHash([0u8; 32]));
+	let mut block = LightClientBlockView {
+		prev_block_hash: CryptoHash([height as u8; 32]),
+		next_block_inner_hash: CryptoHash([0xaa; 32]),
+		inner_lite: BlockHeaderInnerLiteView {
+			height,
+			epoch_id,
+			next_epoch_id,
+			prev_state_root: CryptoHash([0xbb; 32]),
+			outcome_root: CryptoHash([0xcc; 32]),
+			timestamp: height * 1_000,
+			timestamp_nanosec: height * 1_000_000_000,
+			next_bp_hash,
+			block_merkle_root: CryptoHash([0xdd; 32]),
+		},
+		inner_rest_hash: CryptoHash([0xee; 32]),
+		next_bps,
+		approvals_after_next: vec![],
+	};
+	block.approvals_after_next = sign(&block, signers);
+	block
+}
+
+fn sign(
+	block: &LightClientBlockView,
+	signers: &[Option<&ed25519::Pair>],
+) -> Vec<Option<Signature>> {
+	let (_, _, approval_message) =
+		reconstruct_light_client_block_view_fields::<HostFunctionsManager>(block).unwrap();
+	signers
+		.iter()
+		.map(|signer| signer.map(|pair| Signature::Ed25519(pair.sign(&approval_message))))
+		.collect()
+}
+
+/// A client at height 10 of epoch one, which knows the block producers of epoch two.
+fn client_state(current: &[ed25519::Pair], next: &[ed25519::Pair]) -> ClientState {
+	let head = light_client_block(10, EPOCH_ONE, EPOCH_TWO, Some(stake_views(next)), &[]);
+	NearClientState {
+		chain_id: ChainId::new("near".to_string(), 0),
+		head,
+		current_epoch: EPOCH_ONE,
+		next_epoch: EPOCH_TWO,
+		current_validators: stake_views(current),
+		next_validators: stake_views(next),
+		ibc_account_id: IBC_ACCOUNT.to_string(),
+		upgrade_prefix: PREFIX.to_vec(),
+		frozen_height: None,
+		_phantom: PhantomData,
+	}
+}
+
+fn all(producers: &[ed25519::Pair]) -> Vec<Option<&ed25519::Pair>> {
+	producers.iter().map(Some).collect()
+}
+
+#[test]
+fn should_accept_block_of_current_epoch() {
+	let (current, next) = (block_producers("one"), block_producers("two"));
+	let client_state = client_state(&current, &next);
+
+	let block = light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &all(&current));
+	validate_light_block::<HostFunctionsManager>(
+		&NearHeader::new(block.clone()),
+		client_state.clone(),
+	)
+	.unwrap();
+
+	let client_state = client_state.with_head(block);
+	assert_eq!(client_state.head.get_height(), Height::new(0, 11));
+	assert_eq!(client_state.current_epoch, EPOCH_ONE);
+	assert_eq!(client_state.next_validators, stake_views(&next));
+}
+
+#[test]
+fn should_rotate_block_producers_on_epoch_change() {
+	let (current, next, after_next) =
+		(block_producers("one"), block_producers("two"), block_producers("three"));
+	let client_state = client_state(&current, &next);
+
+	let block =
+		light_client_block(20, EPOCH_TWO, EPOCH_THREE, Some(stake_views(&after_next)), &all(&next));
+	validate_light_block::<HostFunctionsManager>(
+		&NearHeader::new(block.clone()),
+		client_state.clone(),
+	)
+	.unwrap();
+
+	let client_state = client_state.with_head(block);
+	assert_eq!(client_state.current_epoch, EPOCH_TWO);
+	assert_eq!(client_state.current_validators, stake_views(&next));
+	assert_eq!(client_state.next_epoch, EPOCH_THREE);
+	assert_eq!(client_state.next_validators, stake_views(&after_next));
+
+	// blocks of the new epoch are now verified against its block producers
+	let block = light_client_block(21, EPOCH_TWO, EPOCH_THREE, None, &all(&next));
+	validate_light_block::<HostFunctionsManager>(&NearHeader::new(block), client_state).unwrap();
+}
+
+#[test]
+fn should_reject_invalid_blocks() {
+	let (current, next) = (block_producers("one"), block_producers("two"));
+	let client_state = client_state(&current, &next);
+	let validate = |block: LightClientBlockView| {
+		validate_light_block::<HostFunctionsManager>(&NearHeader::new(block), client_state.clone())
+	};
+
+	// not newer than the head
+	assert!(validate(light_client_block(10, EPOCH_ONE, EPOCH_TWO, None, &all(&current))).is_err());
+	// unknown epoch
+	assert!(validate(light_client_block(11, EPOCH_THREE, EPOCH_ONE, None, &all(&current))).is_err());
+	// first block of the next epoch without the block producers of the epoch after
+	assert!(validate(light_client_block(11, EPOCH_TWO, EPOCH_THREE, None, &all(&next))).is_err());
+	// signed by the block producers of another epoch
+	assert!(validate(light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &all(&next))).is_err());
+
+	// more than 2/3 of the stake must approve the block
+	let signers = [Some(&current[0]), Some(&current[1]), None, None];
+	assert!(validate(light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &signers)).is_err());
+	let signers = [Some(&current[0]), Some(&current[1]), Some(&current[2]), None];
+	assert!(validate(light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &signers)).is_ok());
+
+	// next block producers that don't match the next_bp_hash
+	let mut block =
+		light_client_block(11, EPOCH_ONE, EPOCH_TWO, Some(stake_views(&next)), &all(&current));
+	block.next_bps = Some(stake_views(&current));
+	assert!(validate(block).is_err());
+}
+
+#[test]
+fn should_reject_minority_approvals() {
+	let (current, next) = (block_producers("one"), block_producers("two"));
+	let client_state = client_state(&current, &next);
+	let validate = |block: LightClientBlockView| {
+		validate_light_block::<HostFunctionsManager>(&NearHeader::new(block), client_state.clone())
+	};
+
+	// a single approval is weighed against the stake of all the block producers of the epoch
+	let signers = [Some(&current[0]), None, None, None];
+	assert!(validate(light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &signers)).is_err());
+
+	// and the block must carry one approval slot per block producer
+	let block = light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &[Some(&current[0])]);
+	assert!(validate(block).is_err());
+	let mut signers = all(&current);
+	signers.push(None);
+	assert!(validate(light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &signers)).is_err());
+
+	// a misbehaviour approved by a single block producer can't freeze the client
+	let signers = [Some(&current[0])];
+	let block_one = light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &signers);
+	let mut block_two = block_one.clone();
+	block_two.inner_lite.outcome_root = CryptoHash([0xff; 32]);
+	block_two.approvals_after_next = sign(&block_two, &signers);
+	let misbehaviour = NearMisbehaviour { block_one, block_two };
+	assert!(validate_misbehaviour::<HostFunctionsManager>(&misbehaviour, &client_state).is_err());
+}
+
+#[test]
+fn should_verify_misbehaviour() {
+	let (current, next) = (block_producers("one"), block_producers("two"));
+	let client_state = client_state(&current, &next);
+
+	let block_one = light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &all(&current));
+	let mut block_two = block_one.clone();
+	block_two.inner_lite.outcome_root = CryptoHash([0xff; 32]);
+	block_two.approvals_after_next = sign(&block_two, &all(&current));
+
+	let misbehaviour = NearMisbehaviour { block_one: block_one.clone(), block_two };
+	validate_misbehaviour::<HostFunctionsManager>(&misbehaviour, &client_state).unwrap();
+
+	// the same block twice is no misbehaviour
+	let misbehaviour = NearMisbehaviour { block_one: block_one.clone(), block_two: block_one };
+	assert!(validate_misbehaviour::<HostFunctionsManager>(&misbehaviour, &client_state).is_err());
+
+	let frozen = client_state.with_frozen_height(Height::new(0, 10));
+	assert!(frozen.verify_height(Height::new(0, 9)).is_ok());
+	assert!(frozen.verify_height(Height::new(0, 10)).is_err());
+}
+
+#[test]
+fn should_encode_and_decode_client_types() {
+	let (current, next) = (block_producers("one"), block_producers("two"));
+	let client_state = client_state(&current, &next).with_frozen_height(Height::new(0, 10));
+	let encoded = client_state.encode_vec().unwrap();
+	assert_eq!(ClientState::decode_vec(&encoded).unwrap(), client_state);
+
+	let block = light_client_block(11, EPOCH_ONE, EPOCH_TWO, None, &all(&current));
+	let message = NearClientMessage::Header(NearHeader::new(block));
+	let encoded = message.encode_vec().unwrap();
+	assert_eq!(NearClientMessage::decode_vec(&encoded).unwrap(), message);
+}
+
+fn trie_node(node: RawTrieNode) -> Vec<u8> {
+	RawTrieNodeWithSize { node, memory_usage: 0 }.try_to_vec().unwrap()
+}
+
+fn value_ref(value: &[u8]) -> ValueRef {
+	ValueRef { length: value.len() as u32, hash: sha256(value) }
+}
+
+/// A shard trie holding `connections/connection-0` and `connections/connection-1` of the IBC
+/// contract, the second shard of a block with two shards.
+struct TrieFixture {
+	root: CommitmentRoot,
+	shard_state_root: CryptoHash,
+	shard_proof: Vec<MerklePathItem>,
+	extension: Vec<u8>,
+	branch: Vec<u8>,
+	leaf: Vec<u8>,
+}
+
+const PREFIX: &[u8] = b"ibc/";
+
+fn trie_fixture(value_zero: &[u8], value_one: &[u8]) -> TrieFixture {
+	let path = |id| ConnectionsPath(ConnectionId::new(id)).to_string();
+	let key = contract_data_key(IBC_ACCOUNT, &[PREFIX, path(0).as_bytes()].concat());
+	let nibbles = to_nibbles(&key);
+	// the keys only differ in their last nibble
+	let (common, last) = nibbles.split_at(nibbles.len() - 1);
+	assert_eq!(last, [0]);
+
+	let leaf = trie_node(RawTrieNode::Leaf(encode_key_nibbles(&[], true), value_ref(value_zero)));
+	let other_leaf =
+		trie_node(RawTrieNode::Leaf(encode_key_nibbles(&[], true), value_ref(value_one)));
+	let mut children = Children::default();
+	children.0[0] = Some(sha256(&leaf));
+	children.0[1] = Some(sha256(&other_leaf));
+	let branch = trie_node(RawTrieNode::BranchNoValue(children));
+	let extension =
+		trie_node(RawTrieNode::Extension(encode_key_nibbles(common, false), sha256(&branch)));
+
+	let shard_state_root = sha256(&extension);
+	let other_shard = sha256(&[0x11; 32]);
+	let root = sha256(&[other_shard.0, sha256(&shard_state_root.0).0].concat());
+
+	TrieFixture {
+		root: root.0.to_vec().into(),
+		shard_state_root,
+		shard_proof: vec![MerklePathItem { hash: other_shard, direction: Direction::Left }],
+		extension,
+		branch,
+		leaf,
+	}
+}
+
+impl TrieFixture {
+	fn proof(&self, nodes: Vec<Vec<u8>>) -> CommitmentProofBytes {
+		let proof = StateProof {
+			shard_state_root: self.shard_state_root,
+			shard_proof: self.shard_proof.clone(),
+			nodes,
+		};
+		proof.try_to_vec().unwrap().try_into().unwrap()
+	}
+}
+
+#[test]
+fn should_verify_trie_proofs() {
+	let fixture = trie_fixture(b"connection-0", b"connection-1");
+	let nodes = vec![fixture.extension.clone(), fixture.branch.clone(), fixture.leaf.clone()];
+	let proof = fixture.proof(nodes.clone());
+	let connection = |id| ConnectionsPath(ConnectionId::new(id));
+
+	verify_membership::<HostFunctionsManager, _>(
+		&fixture.root,
+		IBC_ACCOUNT,
+		PREFIX,
+		connection(0),
+		&proof,
+		b"connection-0",
+	)
+	.unwrap();
+
+	// wrong value, account or prefix
+	assert!(verify_membership::<HostFunctionsManager, _>(
+		&fixture.root,
+		IBC_ACCOUNT,
+		PREFIX,
+		connection(0),
+		&proof,
+		b"connection-1",
+	)
+	.is_err());
+	assert!(verify_membership::<HostFunctionsManager, _>(
+		&fixture.root,
+		"evil.near",
+		PREFIX,
+		connection(0),
+		&proof,
+		b"connection-0",
+	)
+	.is_err());
+	assert!(verify_membership::<HostFunctionsManager, _>(
+		&fixture.root,
+		IBC_ACCOUNT,
+		b"evil/",
+		connection(0),
+		&proof,
+		b"connection-0",
+	)
+	.is_err());
+
+	// a state root the shard root isn't part of
+	let other_root: CommitmentRoot = vec![0x22; 32].into();
+	assert!(verify_membership::<HostFunctionsManager, _>(
+		&other_root,
+		IBC_ACCOUNT,
+		PREFIX,
+		connection(0),
+		&proof,
+		b"connection-0",
+	)
+	.is_err());
+
+	// the branch has no child for connection-2
+	let proof = fixture.proof(vec![fixture.extension.clone(), fixture.branch.clone()]);
+	verify_non_membership::<HostFunctionsManager, _>(
+		&fixture.root,
+		IBC_ACCOUNT,
+		PREFIX,
+		connection(2),
+		&proof,
+	)
+	.unwrap();
+	// the leaf of connection-1 is missing from the proof
+	assert!(verify_non_membership::<HostFunctionsManager, _>(
+		&fixture.root,
+		IBC_ACCOUNT,
+		PREFIX,
+		connection(1),
+		&proof,
+	)
+	.is_err());
+	// connection-0 exists
+	let proof = fixture.proof(nodes);
+	assert!(verify_non_membership::<HostFunctionsManager, _>(
+		&fixture.root,
+		IBC_ACCOUNT,
+		PREFIX,
+		connection(0),
+		&proof,
+	)
+	.is_err());
+}
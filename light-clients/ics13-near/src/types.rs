@@ -13,7 +13,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use alloc::vec::Vec;
+use alloc::{format, vec::Vec};
 
 use borsh::{
 	maybestd::{io, string::String},
@@ -154,7 +154,8 @@ pub struct BlockHeaderInnerLiteView {
 
 /// For some reason, when calculating the hash of the current block
 /// `timestamp_nanosec` is ignored
-#[derive(Debug, Clone, BorshSerialize, BorshDeserialize)]
+/// The inner lite header as it is hashed by NEAR, the view carries the timestamp twice.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
 pub struct BlockHeaderInnerLiteViewFinal {
 	pub height: BlockHeight,
 	pub epoch_id: CryptoHash,
@@ -251,7 +252,7 @@ impl BorshSerialize for Signature {
 	fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
 		match self {
 			Signature::Ed25519(signature) => {
-				BorshSerialize::serialize(&0u8, writer)?;
+				BorshSerialize::serialize(&ED25519_KEY_TYPE, writer)?;
 				writer.write_all(&signature.0)?;
 			},
 		}
@@ -261,7 +262,7 @@ impl BorshSerialize for Signature {
 
 impl BorshSerialize for PublicKey {
 	fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
-		BorshSerialize::serialize(&0u8, writer)?;
+		BorshSerialize::serialize(&ED25519_KEY_TYPE, writer)?;
 		writer.write_all(&self.0)?;
 		Ok(())
 	}
@@ -274,9 +275,12 @@ impl BorshDeserialize for PublicKey {
 	}
 }
 
+/// Key type tag of ED25519 keys and signatures in NEAR's borsh encoding.
+const ED25519_KEY_TYPE: u8 = 0;
+
 fn read_key_type<R: io::Read>(rd: &mut R) -> io::Result<()> {
 	match u8::deserialize_reader(rd)? {
-		8 => Ok(()),
+		ED25519_KEY_TYPE => Ok(()),
 		key_type => Err(io::Error::new(
 			io::ErrorKind::InvalidData,
 			format!("Unrecognised key type: {key_type}"),
@@ -284,14 +288,30 @@ fn read_key_type<R: io::Read>(rd: &mut R) -> io::Result<()> {
 	}
 }
 
+impl From<&BlockHeaderInnerLiteView> for BlockHeaderInnerLiteViewFinal {
+	fn from(view: &BlockHeaderInnerLiteView) -> Self {
+		Self {
+			height: view.height,
+			epoch_id: view.epoch_id,
+			next_epoch_id: view.next_epoch_id,
+			prev_state_root: view.prev_state_root,
+			outcome_root: view.outcome_root,
+			timestamp: view.timestamp_nanosec,
+			next_bp_hash: view.next_bp_hash,
+			block_merkle_root: view.block_merkle_root,
+		}
+	}
+}
+
 impl LightClientBlockView {
 	pub fn get_height(&self) -> Height {
 		Height { revision_number: 0, revision_height: self.inner_lite.height }
 	}
 
 	pub fn current_block_hash<H: HostFunctions>(&self) -> CryptoHash {
+		let inner_lite = BlockHeaderInnerLiteViewFinal::from(&self.inner_lite);
 		current_block_hash::<H>(
-			H::sha256_digest(self.inner_lite.try_to_vec().unwrap().as_ref())
+			H::sha256_digest(inner_lite.try_to_vec().unwrap().as_ref())
 				.as_slice()
 				.try_into()
 				.unwrap(),
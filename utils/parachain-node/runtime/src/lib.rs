@@ -59,7 +59,10 @@ use frame_support::{
 	construct_runtime,
 	dispatch::DispatchClass,
 	parameter_types,
-	traits::{fungibles::metadata::Inspect as InspectMetadata, AsEnsureOriginWithArg, Everything},
+	traits::{
+		fungibles::metadata::Inspect as InspectMetadata, AsEnsureOriginWithArg, Contains,
+		Everything,
+	},
 	weights::{
 		constants::WEIGHT_REF_TIME_PER_SECOND, ConstantMultiplier, Weight, WeightToFeeCoefficient,
 		WeightToFeeCoefficients, WeightToFeePolynomial,
@@ -563,23 +566,34 @@ parameter_types! {
 #[derive(Clone, Debug, Eq, PartialEq, Default)]
 pub struct Router {
 	pallet_ibc_ping: pallet_ibc_ping::IbcModule<Runtime>,
+	/// ICS-20 transfers whose memos are executed by [`pallet_ibc::Config::HandleMemo`]
+	ics20: pallet_ibc::ics29::FeeMiddleware<
+		Runtime,
+		pallet_ibc::ics20::memo::Memo<Runtime, pallet_ibc::ics20::IbcModule<Runtime>>,
+	>,
 }
 
 impl ModuleRouter for Router {
 	fn get_route_mut(&mut self, module_id: &ModuleId) -> Option<&mut dyn Module> {
 		match module_id.as_ref() {
 			pallet_ibc_ping::MODULE_ID => Some(&mut self.pallet_ibc_ping),
+			ibc::applications::transfer::MODULE_ID_STR => Some(&mut self.ics20),
 			_ => None,
 		}
 	}
 
 	fn has_route(module_id: &ModuleId) -> bool {
-		matches!(module_id.as_ref(), pallet_ibc_ping::MODULE_ID)
+		matches!(
+			module_id.as_ref(),
+			pallet_ibc_ping::MODULE_ID | ibc::applications::transfer::MODULE_ID_STR
+		)
 	}
 
 	fn lookup_module_by_port(port_id: &PortId) -> Option<ModuleId> {
 		match port_id.as_str() {
 			pallet_ibc_ping::PORT_ID => ModuleId::from_str(pallet_ibc_ping::MODULE_ID).ok(),
+			ibc::applications::transfer::PORT_ID_STR =>
+				ModuleId::from_str(ibc::applications::transfer::MODULE_ID_STR).ok(),
 			_ => None,
 		}
 	}
@@ -805,7 +819,7 @@ impl pallet_ibc::Config for Runtime {
 	type MemoMessage = RawMemo;
 	type IsReceiveEnabled = sp_core::ConstBool<true>;
 	type IsSendEnabled = sp_core::ConstBool<true>;
-	type HandleMemo = ();
+	type HandleMemo = pallet_ibc::ics20::hooks::IbcHooksHandler<(), Runtime>;
 	type PalletPrefix = IbcTriePrefix;
 	type LightClientProtocol = GRANDPA;
 	type IbcAccountId = Self::AccountId;
@@ -816,6 +830,33 @@ impl pallet_ibc::Config for Runtime {
 	type FlatFeeAssetId = AssetIdUSDT;
 	type FlatFeeAmount = FlatFeeUSDTAmount;
 	type SubstrateMultihopXcmHandler = SubstrateMultihopXcmHandlerNone<Runtime>;
+	type RuntimeCall = RuntimeCall;
+	type IcaAllowedCalls = IcaAllowedCalls;
+	type MemoAllowedCalls = IcaAllowedCalls;
+	type ContractHandler = ();
+	type Ics20RateLimiter = ();
+	type IsIcaHostEnabled = sp_core::ConstBool<true>;
+	type IsIcaControllerEnabled = sp_core::ConstBool<true>;
+	type NftRegistry = ();
+	type AssetRegistry = ();
+	type AutoRegisterAssetMetadata = frame_support::traits::ConstBool<false>;
+	type PauseOnEscrowViolation = frame_support::traits::ConstBool<false>;
+}
+
+/// Calls that interchain accounts controlled by counterparty chains, and the memos of the transfers
+/// received, may dispatch.
+pub struct IcaAllowedCalls;
+
+impl Contains<RuntimeCall> for IcaAllowedCalls {
+	fn contains(call: &RuntimeCall) -> bool {
+		matches!(
+			call,
+			RuntimeCall::Balances(
+				pallet_balances::Call::transfer { .. } |
+					pallet_balances::Call::transfer_keep_alive { .. }
+			)
+		)
+	}
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -1029,16 +1070,16 @@ impl_runtime_apis! {
 			Ibc::consensus_state(client_id, revision_number, revision_height, latest_cs).ok()
 		}
 
-		fn clients() -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
-			Some(Ibc::clients())
+		fn clients(pagination: Option<ibc_primitives::PageRequest>) -> Option<ibc_primitives::QueryClientStatesResponse> {
+			Some(Ibc::clients(pagination))
 		}
 
 		fn connection(connection_id: Vec<u8>) -> Option<ibc_primitives::QueryConnectionResponse>{
 			Ibc::connection(connection_id).ok()
 		}
 
-		fn connections() -> Option<ibc_primitives::QueryConnectionsResponse> {
-			Ibc::connections().ok()
+		fn connections(pagination: Option<ibc_primitives::PageRequest>) -> Option<ibc_primitives::QueryConnectionsResponse> {
+			Ibc::connections(pagination).ok()
 		}
 
 		fn connection_using_client(client_id: Vec<u8>) -> Option<Vec<ibc_primitives::IdentifiedConnection>>{
@@ -1053,6 +1094,14 @@ impl_runtime_apis! {
 			Ibc::channel(channel_id, port_id).ok()
 		}
 
+		fn channel_upgrade(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<ibc_primitives::QueryChannelUpgradeResponse> {
+			Ibc::channel_upgrade(channel_id, port_id).ok()
+		}
+
+		fn channel_upgrade_error(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<ibc_primitives::QueryChannelUpgradeErrorResponse> {
+			Ibc::channel_upgrade_error(channel_id, port_id).ok()
+		}
+
 		fn channel_client(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<ibc_primitives::IdentifiedClientState> {
 			Ibc::channel_client(channel_id, port_id).ok()
 		}
@@ -1061,16 +1110,16 @@ impl_runtime_apis! {
 			Ibc::connection_channels(connection_id).ok()
 		}
 
-		fn channels() -> Option<ibc_primitives::QueryChannelsResponse> {
-			Ibc::channels().ok()
+		fn channels(pagination: Option<ibc_primitives::PageRequest>) -> Option<ibc_primitives::QueryChannelsResponse> {
+			Ibc::channels(pagination).ok()
 		}
 
-		fn packet_commitments(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<ibc_primitives::QueryPacketCommitmentsResponse> {
-			Ibc::packet_commitments(channel_id, port_id).ok()
+		fn packet_commitments(channel_id: Vec<u8>, port_id: Vec<u8>, pagination: Option<ibc_primitives::PageRequest>) -> Option<ibc_primitives::QueryPacketCommitmentsResponse> {
+			Ibc::packet_commitments(channel_id, port_id, pagination).ok()
 		}
 
-		fn packet_acknowledgements(channel_id: Vec<u8>, port_id: Vec<u8>) -> Option<ibc_primitives::QueryPacketAcknowledgementsResponse>{
-			Ibc::packet_acknowledgements(channel_id, port_id).ok()
+		fn packet_acknowledgements(channel_id: Vec<u8>, port_id: Vec<u8>, pagination: Option<ibc_primitives::PageRequest>) -> Option<ibc_primitives::QueryPacketAcknowledgementsResponse>{
+			Ibc::packet_acknowledgements(channel_id, port_id, pagination).ok()
 		}
 
 		fn unreceived_packets(channel_id: Vec<u8>, port_id: Vec<u8>, seqs: Vec<u64>) -> Option<Vec<u64>> {
@@ -1106,6 +1155,14 @@ impl_runtime_apis! {
 			Ibc::get_denom_traces(key, limit, count_total)
 		}
 
+		fn escrow_violations() -> Vec<pallet_ibc::ics20::escrow::EscrowViolation> {
+			Ibc::escrow_violations()
+		}
+
+		fn simulate_deliver(messages: Vec<pallet_ibc::Any>) -> Vec<pallet_ibc::SimulatedMessage> {
+			Ibc::simulate_deliver(messages)
+		}
+
 		fn block_events(extrinsic_index: Option<u32>) -> Vec<Result<pallet_ibc::events::IbcEvent, pallet_ibc::errors::IbcError>> {
 			let mut raw_events = frame_system::Pallet::<Self>::read_events_no_consensus();
 			if let Some(idx) = extrinsic_index {
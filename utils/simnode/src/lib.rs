@@ -64,6 +64,7 @@ impl ChainInfo for ParachainRuntimeChainInfo {
 			pool: deps.pool,
 			deny_unsafe: deps.deny_unsafe,
 			chain_props: Default::default(),
+			subscription_executor: deps.subscription_executor,
 		};
 		parachain_node::rpc::create_full(full_deps).expect("Rpc to be initialized")
 	}